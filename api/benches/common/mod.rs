@@ -0,0 +1,68 @@
+//! Batch fixtures shared by the benches.
+
+use std::io::Write;
+
+use flate2::{write::GzEncoder, Compression};
+use uuid::Uuid;
+
+pub const PLAYERS: u128 = 50;
+
+/// Entities spawned at the start of every batch, attacked in turn.
+const ENTITIES: usize = 7;
+
+/// A gzipped batch of `records` packets from [`PLAYERS`] players: mostly movement, with an attack
+/// on one of the spawned entities every tenth record, entity moves every twentieth, a hurt
+/// status every hundredth and a brand plugin message every five hundredth.
+pub fn batch(records: usize) -> Vec<u8> {
+    let mut out = GzEncoder::new(Vec::new(), Compression::fast());
+    writeln!(
+        out,
+        r#"{{"meta_version":1,"plugin_version":"1.0.0","mc_version":"1.21","tick_rate":20.0}}"#
+    )
+    .unwrap();
+    for id in 0..ENTITIES {
+        writeln!(
+            out,
+            r#"{{"ts":1700000000000,"dir":"clientbound","pkt":"SPAWN_ENTITY","fields":{{"entity_id":{},"entity_type":"minecraft:zombie","x":{},"y":64.0,"z":2.0}}}}"#,
+            id, id
+        )
+        .unwrap();
+    }
+    for i in 0..records {
+        let uuid = Uuid::from_u128(i as u128 % PLAYERS + 1);
+        let ts = 1_700_000_000_000u64 + i as u64 * 5;
+        let entity = i % ENTITIES;
+        let line = if i % 500 == 499 {
+            format!(
+                r#"{{"ts":{},"uuid":"{}","dir":"serverbound","pkt":"PLUGIN_MESSAGE","fields":{{"channel":"minecraft:brand","data":"\u0006fabric"}}}}"#,
+                ts, uuid
+            )
+        } else if i % 100 == 99 {
+            format!(
+                r#"{{"ts":{},"uuid":"{}","dir":"clientbound","pkt":"ENTITY_STATUS","fields":{{"entity_id":{},"status":2}}}}"#,
+                ts, uuid, entity
+            )
+        } else if i % 20 == 19 {
+            format!(
+                r#"{{"ts":{},"uuid":"{}","dir":"clientbound","pkt":"ENTITY_RELATIVE_MOVE","fields":{{"entity_id":{},"dx":0.05,"dy":0.0,"dz":-0.05,"on_ground":true}}}}"#,
+                ts, uuid, entity
+            )
+        } else if i % 10 == 9 {
+            format!(
+                r#"{{"ts":{},"uuid":"{}","dir":"serverbound","pkt":"INTERACT_ENTITY","fields":{{"entity_id":{},"action":"ATTACK","sneaking":false}}}}"#,
+                ts, uuid, entity
+            )
+        } else {
+            format!(
+                r#"{{"ts":{},"uuid":"{}","dir":"serverbound","pkt":"PLAYER_POSITION_AND_ROTATION","fields":{{"x":{},"y":64.0,"z":{},"yaw":{},"pitch":3.5,"on_ground":true}}}}"#,
+                ts,
+                uuid,
+                i as f64 * 0.1,
+                i as f64 * 0.05,
+                (i % 360) as f64
+            )
+        };
+        writeln!(out, "{}", line).unwrap();
+    }
+    out.finish().unwrap()
+}
@@ -0,0 +1,113 @@
+//! Criterion benchmarks for the batch pipeline: record parsing, every transform, and encoding
+//! transform output for dispatch, across small, medium and large batches.
+//!
+//! `cargo bench --bench pipeline` (filter with e.g. `-- ncp_fight_v1/large`). Criterion keeps the
+//! previous run under `target/criterion` and reports the change against it, so run it before and
+//! after a refactor; `--save-baseline <name>` / `--baseline <name>` compare named runs instead.
+
+use std::hint::black_box;
+use std::io::{BufRead, BufReader, Read};
+
+use async_anticheat_api::dispatch_encoding::{self, ContentEncoding};
+use async_anticheat_api::dispatch_parts;
+use async_anticheat_api::ndjson::RecordParser;
+use async_anticheat_api::transforms::{apply_transform, SUPPORTED};
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use flate2::read::GzDecoder;
+
+mod common;
+
+/// Records per batch: a quiet server's batch, a busy one's, and a flushed backlog.
+const SIZES: [(&str, usize); 3] = [("small", 500), ("medium", 10_000), ("large", 100_000)];
+
+/// Part size for the split dispatch case (large batches are split in several parts).
+const MAX_PART_BYTES: usize = 1024 * 1024;
+
+/// Decompress a batch and parse every record, as transforms and in-process checks do.
+fn parse_records(raw_gz: &[u8]) -> usize {
+    let mut parser = RecordParser::new();
+    BufReader::new(GzDecoder::new(raw_gz))
+        .lines()
+        .map_while(Result::ok)
+        .filter(|line| parser.parse(line).is_some())
+        .count()
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_records");
+    for (size, records) in SIZES {
+        let raw = common::batch(records);
+        group.throughput(Throughput::Elements(records as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &raw, |b, raw| {
+            b.iter(|| parse_records(black_box(raw)))
+        });
+    }
+    group.finish();
+}
+
+fn transforms(c: &mut Criterion) {
+    let batches: Vec<_> = SIZES
+        .iter()
+        .map(|(size, records)| (*size, *records, common::batch(*records)))
+        .collect();
+    for transform in SUPPORTED {
+        let mut group = c.benchmark_group(transform.trim_end_matches("_ndjson_gz"));
+        // Large batches take seconds a run; the default 100 samples would take minutes per case.
+        group.sample_size(20);
+        for (size, records, raw) in &batches {
+            group.throughput(Throughput::Elements(*records as u64));
+            group.bench_with_input(BenchmarkId::from_parameter(size), raw, |b, raw| {
+                b.iter(|| apply_transform(transform, black_box(raw)).unwrap())
+            });
+        }
+        group.finish();
+    }
+}
+
+fn dispatch(c: &mut Criterion) {
+    for (size, records) in SIZES {
+        let raw = common::batch(records);
+        let payload = Bytes::from(apply_transform("ncp_fight_v1_ndjson_gz", &raw).unwrap());
+        let mut plain = Vec::new();
+        GzDecoder::new(&payload[..])
+            .read_to_end(&mut plain)
+            .unwrap();
+
+        let mut group = c.benchmark_group(format!("dispatch/{}", size));
+        group.throughput(Throughput::Bytes(plain.len() as u64));
+        for encoding in [
+            ContentEncoding::Gzip,
+            ContentEncoding::Zstd,
+            ContentEncoding::Identity,
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new("encode", encoding.as_str()),
+                &payload,
+                |b, payload| b.iter(|| dispatch_encoding::encode(payload.clone(), encoding)),
+            );
+        }
+        group.bench_with_input(
+            BenchmarkId::new("encode_parts", "zstd"),
+            &payload,
+            |b, payload| {
+                b.iter(|| {
+                    dispatch_parts::encode_parts(
+                        payload.clone(),
+                        ContentEncoding::Zstd,
+                        MAX_PART_BYTES,
+                    )
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("split_ndjson", "plain"),
+            &plain,
+            |b, plain| b.iter(|| dispatch_parts::split_ndjson(black_box(plain), MAX_PART_BYTES)),
+        );
+        group.finish();
+    }
+}
+
+criterion_group!(benches, parse, transforms, dispatch);
+criterion_main!(benches);
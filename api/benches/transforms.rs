@@ -0,0 +1,165 @@
+//! Transform throughput, record parsing, and typed events against the `serde_json::Map` lines
+//! transforms used to build.
+//!
+//! `cargo bench --bench transforms` (no external harness; prints one line per case). Add
+//! `--features simd-json` to compare the SIMD record parser.
+
+use std::hint::black_box;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+use async_anticheat_api::ndjson::{RecordParser, BACKEND};
+use async_anticheat_api::transform_events::MovementEvent;
+use async_anticheat_api::transforms::apply_transform;
+use serde_json::Value;
+use uuid::Uuid;
+
+mod common;
+
+use common::PLAYERS;
+
+const RECORDS: usize = 50_000;
+const EVENTS: usize = 200_000;
+
+fn event(i: usize) -> MovementEvent {
+    MovementEvent {
+        ts: 1_700_000_000_000 + i as u64,
+        uuid: Uuid::from_u128(i as u128 % PLAYERS + 1),
+        x: i as f64 * 0.1,
+        y: 64.0,
+        z: i as f64 * 0.05,
+        on_ground: Some(true),
+        dt_ms: Some(50.0),
+        dx: Some(0.1),
+        dy: Some(0.0),
+        dz: Some(0.05),
+        speed_bps: Some(2.236),
+        world: Some("world".to_string()),
+        gamemode: Some("SURVIVAL".to_string()),
+        allow_flying: Some(false),
+        flight_allowed: false,
+        client_type: None,
+        client_protocol: None,
+    }
+}
+
+/// The same line built the way transforms did before typed events.
+fn map_line(e: &MovementEvent) -> Value {
+    let mut obj = serde_json::Map::new();
+    let f = |v: f64| Value::Number(serde_json::Number::from_f64(v).unwrap());
+    obj.insert("ts".to_string(), Value::Number(e.ts.into()));
+    obj.insert("uuid".to_string(), Value::String(e.uuid.to_string()));
+    obj.insert("x".to_string(), f(e.x));
+    obj.insert("y".to_string(), f(e.y));
+    obj.insert("z".to_string(), f(e.z));
+    if let Some(og) = e.on_ground {
+        obj.insert("on_ground".to_string(), Value::Bool(og));
+    }
+    if let Some(world) = &e.world {
+        obj.insert("world".to_string(), Value::String(world.clone()));
+    }
+    if let Some(gamemode) = &e.gamemode {
+        obj.insert("gamemode".to_string(), Value::String(gamemode.clone()));
+    }
+    if let Some(allow) = e.allow_flying {
+        obj.insert("allow_flying".to_string(), Value::Bool(allow));
+    }
+    obj.insert("flight_allowed".to_string(), Value::Bool(e.flight_allowed));
+    for (key, v) in [
+        ("dt_ms", e.dt_ms),
+        ("dx", e.dx),
+        ("dy", e.dy),
+        ("dz", e.dz),
+        ("speed_bps", e.speed_bps),
+    ] {
+        if let Some(v) = v {
+            obj.insert(key.to_string(), f(v));
+        }
+    }
+    Value::Object(obj)
+}
+
+/// Best of five runs of `f`.
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    (0..5)
+        .map(|_| {
+            let started = Instant::now();
+            f();
+            started.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, elapsed: Duration, items: usize) {
+    println!(
+        "{:<40} {:>9.2?}  {:>8.1} ns/item",
+        name,
+        elapsed,
+        elapsed.as_nanos() as f64 / items as f64
+    );
+}
+
+fn main() {
+    let events: Vec<MovementEvent> = (0..EVENTS).map(event).collect();
+    let mut buf = Vec::with_capacity(64 * 1024 * 1024);
+
+    let typed = time(|| {
+        buf.clear();
+        for e in &events {
+            serde_json::to_writer(&mut buf, e).unwrap();
+            buf.push(b'\n');
+        }
+        black_box(&buf);
+    });
+    report("movement line: typed struct", typed, EVENTS);
+
+    let map = time(|| {
+        buf.clear();
+        for e in &events {
+            writeln!(buf, "{}", map_line(e)).unwrap();
+        }
+        black_box(&buf);
+    });
+    report("movement line: serde_json::Map", map, EVENTS);
+    println!(
+        "{:<40} {:>9.2}x",
+        "typed speedup",
+        map.as_secs_f64() / typed.as_secs_f64()
+    );
+
+    let raw = common::batch(RECORDS);
+    let mut text = String::new();
+    flate2::read::GzDecoder::new(raw.as_slice())
+        .read_to_string(&mut text)
+        .unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    let serde = time(|| {
+        for line in &lines {
+            black_box(serde_json::from_str::<Value>(line).ok());
+        }
+    });
+    report("record parse: serde_json", serde, lines.len());
+    let mut parser = RecordParser::new();
+    let parsed = time(|| {
+        for line in &lines {
+            black_box(parser.parse(line));
+        }
+    });
+    report(
+        &format!("record parse: RecordParser ({})", BACKEND),
+        parsed,
+        lines.len(),
+    );
+
+    for transform in [
+        "movement_events_v1_ndjson_gz",
+        "combat_events_v1_ndjson_gz",
+        "ncp_fight_v1_ndjson_gz",
+    ] {
+        let elapsed = time(|| {
+            black_box(apply_transform(transform, &raw).unwrap());
+        });
+        report(transform, elapsed, RECORDS);
+    }
+}
@@ -0,0 +1,480 @@
+//! Typed HTTP client for the AsyncAnticheat API.
+//!
+//! Request and response types are the server's own route types (re-exported below), so a
+//! payload change on the server breaks the client build instead of failing at runtime.
+//!
+//! - [`PluginClient`]: handshake, heartbeat and batch ingest with a per-server token
+//! - [`ModuleClient`]: findings and player-state callbacks with `MODULE_CALLBACK_TOKEN`
+//! - [`DashboardClient`]: dashboard endpoints with `DASHBOARD_TOKEN`
+//!
+//! ```ignore
+//! let dashboard = DashboardClient::new("http://127.0.0.1:3002", Some(token));
+//! let findings = dashboard.findings("my-server", &FindingsQuery::default()).await?;
+//! ```
+
+use reqwest::{Method, RequestBuilder, StatusCode};
+use serde::{de::DeserializeOwned, Serialize};
+use uuid::Uuid;
+
+pub use async_anticheat_api::error::ErrorBody;
+pub use async_anticheat_api::pending_actions::PendingAction;
+pub use async_anticheat_api::plugin_version::PluginUpdate;
+pub use async_anticheat_api::routes::callbacks::{
+    BatchGetPlayerStatesRequest, BatchGetPlayerStatesResponse, BatchPlayerState,
+    BatchSetPlayerStatesRequest, BatchSetPlayerStatesResponse, FindingIn, GetPlayerStateRequest,
+    PlayerStateEntry, PlayerStateResponse, PostFindingsRequest, PostFindingsResponse,
+    SetPlayerStateRequest, SetPlayerStateResponse,
+};
+pub use async_anticheat_api::routes::dashboard::{
+    ActivePlayer, ConnectionStatus, CreateModuleRequest, CreateModuleResponse, DashboardStats,
+    DashboardStatsResponse, FindingItem, FindingsQuery, FindingsResponse, HeartbeatSample,
+    ModuleItem, ModulesResponse, PlayerClientSession, PlayerDetailResponse, PlayerItem,
+    PlayersQuery, PlayersResponse, RelatedPlayer, RelatedPlayersResponse, ServerInfo,
+    ServerPerformance, ServersResponse, StatusResponse, ToggleModuleRequest, ToggleModuleResponse,
+    UpdateFindingStatusRequest, UpdateFindingStatusResponse, WorldStats,
+};
+pub use async_anticheat_api::routes::exemptions::{
+    CreateExemptionRequest, CreateExemptionResponse, DeleteExemptionResponse, ExemptionItem,
+    ExemptionsResponse,
+};
+pub use async_anticheat_api::routes::handshake::HandshakeResponse;
+pub use async_anticheat_api::routes::heartbeat::{HeartbeatRequest, HeartbeatResponse};
+pub use async_anticheat_api::routes::ingest::{
+    IngestResponse, UpdateRequiredResponse, WaitingForRegistrationResponse,
+};
+pub use async_anticheat_api::routes::watchlist::{
+    AddToWatchlistRequest, WatchlistEntry, WatchlistResponse, WatchlistUpdateResponse,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// Non-success response; `message` and `code` come from the API's error body when present
+    /// (`code` is empty otherwise).
+    #[error("api returned {status}: {message}")]
+    Api {
+        status: StatusCode,
+        code: String,
+        message: String,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+/// Base URL + bearer token shared by the per-audience clients.
+#[derive(Debug, Clone)]
+struct Http {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl Http {
+    fn new(base_url: &str, token: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let req = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            Some(token) => req.bearer_auth(token),
+            None => req,
+        }
+    }
+
+    async fn send<T: DeserializeOwned>(req: RequestBuilder) -> Result<T> {
+        Self::send_accepting(req, &[]).await
+    }
+
+    /// Like `send`, but also decodes the body on the given non-2xx statuses (e.g. the 409
+    /// "waiting for registration" replies, which carry a normal response body).
+    async fn send_accepting<T: DeserializeOwned>(
+        req: RequestBuilder,
+        accept: &[StatusCode],
+    ) -> Result<T> {
+        let resp = req.send().await?;
+        let status = resp.status();
+        if status.is_success() || accept.contains(&status) {
+            return Ok(resp.json().await?);
+        }
+        Err(api_error(resp).await)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        Self::send(self.request(Method::GET, path)).await
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        Self::send(self.request(Method::POST, path).json(body)).await
+    }
+
+    async fn delete<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        Self::send(self.request(Method::DELETE, path)).await
+    }
+}
+
+async fn api_error(resp: reqwest::Response) -> ClientError {
+    let status = resp.status();
+    let text = resp.text().await.unwrap_or_default();
+    let (code, message) = match serde_json::from_str::<ErrorBody>(&text) {
+        Ok(body) => (body.code, body.error),
+        Err(_) => (String::new(), text),
+    };
+    ClientError::Api {
+        status,
+        code,
+        message,
+    }
+}
+
+/// Result of [`PluginClient::ingest`]; batches from servers not yet linked to an account are
+/// rejected with HTTP 409.
+#[derive(Debug)]
+pub enum IngestOutcome {
+    Stored(IngestResponse),
+    WaitingForRegistration(WaitingForRegistrationResponse),
+    /// HTTP 426: the plugin version is below the server's minimum; the batch was dropped.
+    UpdateRequired(UpdateRequiredResponse),
+}
+
+/// Plugin-side endpoints, authenticated with the server's own token.
+#[derive(Debug, Clone)]
+pub struct PluginClient {
+    http: Http,
+    server_id: String,
+}
+
+impl PluginClient {
+    pub fn new(base_url: &str, server_id: &str, token: &str) -> Self {
+        Self {
+            http: Http::new(base_url, Some(token.to_string())),
+            server_id: server_id.to_string(),
+        }
+    }
+
+    fn request(&self, path: &str) -> RequestBuilder {
+        self.http
+            .request(Method::POST, path)
+            .header("X-Server-Id", &self.server_id)
+    }
+
+    /// POST /handshake (`status` is "waiting_for_registration" until the server is linked, or
+    /// "update_required" when the plugin version is no longer supported).
+    pub async fn handshake(&self) -> Result<HandshakeResponse> {
+        Http::send_accepting(
+            self.request("/handshake"),
+            &[StatusCode::CONFLICT, StatusCode::UPGRADE_REQUIRED],
+        )
+        .await
+    }
+
+    /// POST /heartbeat, returning queued pending actions.
+    pub async fn heartbeat(&self) -> Result<HeartbeatResponse> {
+        Http::send(self.request("/heartbeat")).await
+    }
+
+    /// POST /heartbeat with server performance metrics.
+    pub async fn heartbeat_with_metrics(
+        &self,
+        metrics: &HeartbeatRequest,
+    ) -> Result<HeartbeatResponse> {
+        Http::send(self.request("/heartbeat").json(metrics)).await
+    }
+
+    /// POST /ingest with a gzipped NDJSON batch (metadata line first). Sends the local clock as
+    /// `X-Client-Time` so the API can track clock skew.
+    pub async fn ingest(&self, session_id: &str, batch_gz: Vec<u8>) -> Result<IngestOutcome> {
+        let client_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let resp = self
+            .request("/ingest")
+            .header("X-Session-Id", session_id)
+            .header("X-Client-Time", client_time.to_string())
+            .header("Content-Type", "application/x-ndjson")
+            .header("Content-Encoding", "gzip")
+            .body(batch_gz)
+            .send()
+            .await?;
+        let status = resp.status();
+        if status == StatusCode::CONFLICT {
+            return Ok(IngestOutcome::WaitingForRegistration(resp.json().await?));
+        }
+        if status == StatusCode::UPGRADE_REQUIRED {
+            return Ok(IngestOutcome::UpdateRequired(resp.json().await?));
+        }
+        if status.is_success() {
+            return Ok(IngestOutcome::Stored(resp.json().await?));
+        }
+        Err(api_error(resp).await)
+    }
+}
+
+/// Module callback endpoints, authenticated with `MODULE_CALLBACK_TOKEN`.
+#[derive(Debug, Clone)]
+pub struct ModuleClient {
+    http: Http,
+}
+
+impl ModuleClient {
+    pub fn new(base_url: &str, callback_token: &str) -> Self {
+        Self {
+            http: Http::new(base_url, Some(callback_token.to_string())),
+        }
+    }
+
+    /// POST /callbacks/findings
+    pub async fn post_findings(&self, req: &PostFindingsRequest) -> Result<PostFindingsResponse> {
+        self.http.post("/callbacks/findings", req).await
+    }
+
+    /// GET /callbacks/player-state (the lookup is sent as a JSON body).
+    pub async fn get_player_state(
+        &self,
+        req: &GetPlayerStateRequest,
+    ) -> Result<PlayerStateResponse> {
+        Http::send(
+            self.http
+                .request(Method::GET, "/callbacks/player-state")
+                .json(req),
+        )
+        .await
+    }
+
+    /// POST /callbacks/player-state
+    pub async fn set_player_state(
+        &self,
+        req: &SetPlayerStateRequest,
+    ) -> Result<SetPlayerStateResponse> {
+        self.http.post("/callbacks/player-state", req).await
+    }
+
+    /// POST /callbacks/player-states/batch-get
+    pub async fn batch_get_player_states(
+        &self,
+        req: &BatchGetPlayerStatesRequest,
+    ) -> Result<BatchGetPlayerStatesResponse> {
+        self.http
+            .post("/callbacks/player-states/batch-get", req)
+            .await
+    }
+
+    /// POST /callbacks/player-states/batch-set
+    pub async fn batch_set_player_states(
+        &self,
+        req: &BatchSetPlayerStatesRequest,
+    ) -> Result<BatchSetPlayerStatesResponse> {
+        self.http
+            .post("/callbacks/player-states/batch-set", req)
+            .await
+    }
+}
+
+/// Dashboard endpoints. The token may be omitted when the API runs without `DASHBOARD_TOKEN`.
+#[derive(Debug, Clone)]
+pub struct DashboardClient {
+    http: Http,
+}
+
+impl DashboardClient {
+    pub fn new(base_url: &str, token: Option<String>) -> Self {
+        Self {
+            http: Http::new(base_url, token),
+        }
+    }
+
+    /// GET /dashboard/servers
+    pub async fn servers(&self) -> Result<ServersResponse> {
+        self.http.get("/dashboard/servers").await
+    }
+
+    /// GET /dashboard/:server_id/stats
+    pub async fn stats(&self, server_id: &str) -> Result<DashboardStatsResponse> {
+        self.http
+            .get(&format!("/dashboard/{}/stats", server_id))
+            .await
+    }
+
+    /// GET /dashboard/:server_id/findings
+    pub async fn findings(
+        &self,
+        server_id: &str,
+        query: &FindingsQuery,
+    ) -> Result<FindingsResponse> {
+        Http::send(
+            self.http
+                .request(Method::GET, &format!("/dashboard/{}/findings", server_id))
+                .query(query),
+        )
+        .await
+    }
+
+    /// POST /dashboard/:server_id/findings/:finding_id/status
+    pub async fn update_finding_status(
+        &self,
+        server_id: &str,
+        finding_id: Uuid,
+        req: &UpdateFindingStatusRequest,
+    ) -> Result<UpdateFindingStatusResponse> {
+        self.http
+            .post(
+                &format!("/dashboard/{}/findings/{}/status", server_id, finding_id),
+                req,
+            )
+            .await
+    }
+
+    /// GET /dashboard/:server_id/players
+    pub async fn players(&self, server_id: &str) -> Result<PlayersResponse> {
+        self.http
+            .get(&format!("/dashboard/{}/players", server_id))
+            .await
+    }
+
+    /// GET /dashboard/:server_id/players with a page size or the previous page's `next_cursor`
+    pub async fn players_page(
+        &self,
+        server_id: &str,
+        query: &PlayersQuery,
+    ) -> Result<PlayersResponse> {
+        Http::send(
+            self.http
+                .request(Method::GET, &format!("/dashboard/{}/players", server_id))
+                .query(query),
+        )
+        .await
+    }
+
+    /// GET /dashboard/:server_id/players/:player_uuid
+    pub async fn player(&self, server_id: &str, player_uuid: Uuid) -> Result<PlayerDetailResponse> {
+        self.http
+            .get(&format!("/dashboard/{}/players/{}", server_id, player_uuid))
+            .await
+    }
+
+    /// GET /dashboard/:server_id/players/:player_uuid/related
+    pub async fn related_players(
+        &self,
+        server_id: &str,
+        player_uuid: Uuid,
+    ) -> Result<RelatedPlayersResponse> {
+        self.http
+            .get(&format!(
+                "/dashboard/{}/players/{}/related",
+                server_id, player_uuid
+            ))
+            .await
+    }
+
+    /// GET /dashboard/:server_id/modules
+    pub async fn modules(&self, server_id: &str) -> Result<ModulesResponse> {
+        self.http
+            .get(&format!("/dashboard/{}/modules", server_id))
+            .await
+    }
+
+    /// POST /dashboard/:server_id/modules
+    pub async fn create_module(
+        &self,
+        server_id: &str,
+        req: &CreateModuleRequest,
+    ) -> Result<CreateModuleResponse> {
+        self.http
+            .post(&format!("/dashboard/{}/modules", server_id), req)
+            .await
+    }
+
+    /// POST /dashboard/:server_id/modules/:module_id/toggle
+    pub async fn toggle_module(
+        &self,
+        server_id: &str,
+        module_id: Uuid,
+        enabled: bool,
+    ) -> Result<ToggleModuleResponse> {
+        self.http
+            .post(
+                &format!("/dashboard/{}/modules/{}/toggle", server_id, module_id),
+                &ToggleModuleRequest { enabled },
+            )
+            .await
+    }
+
+    /// GET /dashboard/:server_id/status
+    pub async fn status(&self, server_id: &str) -> Result<StatusResponse> {
+        self.http
+            .get(&format!("/dashboard/{}/status", server_id))
+            .await
+    }
+
+    /// GET /dashboard/:server_id/watchlist
+    pub async fn watchlist(&self, server_id: &str) -> Result<WatchlistResponse> {
+        self.http
+            .get(&format!("/dashboard/{}/watchlist", server_id))
+            .await
+    }
+
+    /// POST /dashboard/:server_id/watchlist
+    pub async fn add_to_watchlist(
+        &self,
+        server_id: &str,
+        req: &AddToWatchlistRequest,
+    ) -> Result<WatchlistUpdateResponse> {
+        self.http
+            .post(&format!("/dashboard/{}/watchlist", server_id), req)
+            .await
+    }
+
+    /// DELETE /dashboard/:server_id/watchlist/:player_uuid
+    pub async fn remove_from_watchlist(
+        &self,
+        server_id: &str,
+        player_uuid: Uuid,
+    ) -> Result<WatchlistUpdateResponse> {
+        self.http
+            .delete(&format!(
+                "/dashboard/{}/watchlist/{}",
+                server_id, player_uuid
+            ))
+            .await
+    }
+
+    /// GET /dashboard/:server_id/exemptions
+    pub async fn exemptions(&self, server_id: &str) -> Result<ExemptionsResponse> {
+        self.http
+            .get(&format!("/dashboard/{}/exemptions", server_id))
+            .await
+    }
+
+    /// POST /dashboard/:server_id/exemptions
+    pub async fn create_exemption(
+        &self,
+        server_id: &str,
+        req: &CreateExemptionRequest,
+    ) -> Result<CreateExemptionResponse> {
+        self.http
+            .post(&format!("/dashboard/{}/exemptions", server_id), req)
+            .await
+    }
+
+    /// DELETE /dashboard/:server_id/exemptions/:exemption_id
+    pub async fn delete_exemption(
+        &self,
+        server_id: &str,
+        exemption_id: Uuid,
+    ) -> Result<DeleteExemptionResponse> {
+        self.http
+            .delete(&format!(
+                "/dashboard/{}/exemptions/{}",
+                server_id, exemption_id
+            ))
+            .await
+    }
+}
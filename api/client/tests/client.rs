@@ -0,0 +1,152 @@
+use async_anticheat_api::error::ApiError;
+use async_anticheat_client::*;
+use axum::{
+    extract::Query,
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use uuid::Uuid;
+
+/// Serve `app` on an ephemeral port and return its base URL.
+fn serve(app: Router) -> String {
+    let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    format!("http://{}", addr)
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> &'a str {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+}
+
+#[tokio::test]
+async fn module_callbacks_round_trip_server_types() {
+    let app = Router::new()
+        .route(
+            "/callbacks/findings",
+            post(
+                |headers: HeaderMap, Json(req): Json<PostFindingsRequest>| async move {
+                    assert_eq!(header(&headers, "authorization"), "Bearer cb-token");
+                    Json(PostFindingsResponse {
+                        ok: true,
+                        inserted: req.findings.len(),
+                    })
+                },
+            ),
+        )
+        .route(
+            "/callbacks/player-state",
+            post(|Json(req): Json<SetPlayerStateRequest>| async move {
+                assert_eq!(req.module_name, "movement");
+                assert_eq!(req.state["vl"], 3);
+                Json(SetPlayerStateResponse { ok: true })
+            }),
+        );
+    let client = ModuleClient::new(&serve(app), "cb-token");
+
+    let resp = client
+        .post_findings(&PostFindingsRequest {
+            server_id: "s1".to_string(),
+            module_id: Some(Uuid::nil()),
+            session_id: Some("sess".to_string()),
+            batch_id: None,
+            findings: vec![FindingIn {
+                player_uuid: Some(Uuid::nil()),
+                detector_name: "speed".to_string(),
+                detector_version: None,
+                severity: Some("high".to_string()),
+                title: "Speed".to_string(),
+                description: None,
+                evidence_s3_key: None,
+                evidence_json: None,
+                confidence: None,
+            }],
+        })
+        .await
+        .unwrap();
+    assert_eq!(resp.inserted, 1);
+
+    let resp = client
+        .set_player_state(&SetPlayerStateRequest {
+            server_id: "s1".to_string(),
+            player_uuid: Uuid::nil(),
+            module_name: "movement".to_string(),
+            state: serde_json::json!({ "vl": 3 }),
+        })
+        .await
+        .unwrap();
+    assert!(resp.ok);
+}
+
+#[tokio::test]
+async fn ingest_reports_waiting_for_registration() {
+    let app = Router::new().route(
+        "/ingest",
+        post(|headers: HeaderMap| async move {
+            assert_eq!(header(&headers, "x-server-id"), "s1");
+            assert_eq!(header(&headers, "x-session-id"), "sess");
+            (
+                StatusCode::CONFLICT,
+                Json(WaitingForRegistrationResponse {
+                    ok: true,
+                    status: "waiting_for_registration".to_string(),
+                    server_id: "s1".to_string(),
+                }),
+            )
+        }),
+    );
+    let client = PluginClient::new(&serve(app), "s1", "server-token");
+
+    match client.ingest("sess", vec![0x1f, 0x8b]).await.unwrap() {
+        IngestOutcome::WaitingForRegistration(w) => assert_eq!(w.server_id, "s1"),
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn dashboard_sends_query_and_maps_api_errors() {
+    let app = Router::new()
+        .route(
+            "/dashboard/s1/findings",
+            get(|Query(q): Query<FindingsQuery>| async move {
+                assert_eq!(q.world.as_deref(), Some("world_nether"));
+                assert_eq!(q.limit, Some(5));
+                Json(FindingsResponse {
+                    ok: true,
+                    findings: Vec::new(),
+                    total: 0,
+                    next_cursor: None,
+                })
+            }),
+        )
+        .route(
+            "/dashboard/s1/stats",
+            get(|| async { Err::<Json<DashboardStatsResponse>, _>(ApiError::Unauthorized) }),
+        );
+    let client = DashboardClient::new(&serve(app), None);
+
+    let query = FindingsQuery {
+        world: Some("world_nether".to_string()),
+        limit: Some(5),
+        ..Default::default()
+    };
+    let resp = client.findings("s1", &query).await.unwrap();
+    assert_eq!(resp.total, 0);
+
+    match client.stats("s1").await {
+        Err(ClientError::Api {
+            status,
+            code,
+            message,
+        }) => {
+            assert_eq!(status.as_u16(), 401);
+            assert_eq!(code, "unauthorized");
+            assert_eq!(message, "unauthorized");
+        }
+        other => panic!("expected api error, got {:?}", other.map(|r| r.ok)),
+    }
+}
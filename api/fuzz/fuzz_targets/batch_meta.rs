@@ -0,0 +1,13 @@
+//! Batch metadata lines: ingest validation and the standardized line sent to modules.
+#![no_main]
+
+use async_anticheat_api::{batch_meta, transforms};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|line: &str| {
+    let _ = batch_meta::validate(line);
+    let meta = batch_meta::standardized(line);
+    let _ = transforms::client_types(&meta);
+    let _ = transforms::client_protocols(&meta);
+    let _ = transforms::uses_legacy_fields(&meta);
+});
@@ -0,0 +1,37 @@
+//! The synchronous stages `POST /ingest` runs on a plugin's body, in route order.
+#![no_main]
+
+use async_anticheat_api::ingest_filters::{IngestFilterRule, IngestFilters};
+use async_anticheat_api::{
+    batch_meta, ingest_anomalies, ingest_filters, player_sessions, privacy, sampling,
+};
+use async_anticheat_api_fuzz::batch;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let raw = batch(data);
+    if let Some(line) = batch_meta::first_line(&raw) {
+        let _ = batch_meta::validate(&line);
+    }
+
+    let filters = IngestFilters {
+        rules: vec![
+            IngestFilterRule {
+                packet: "KEEP_ALIVE".to_string(),
+                dir: None,
+            },
+            IngestFilterRule {
+                packet: "BLOCK_*".to_string(),
+                dir: Some("clientbound".to_string()),
+            },
+        ],
+    }
+    .validate()
+    .unwrap();
+    let _ = ingest_filters::apply(&raw, &filters);
+
+    let _ = privacy::pseudonymize_batch(&raw, "fuzz-key", "fuzz-server");
+    let _ = ingest_anomalies::inspect(&raw, 1_700_000_000_000);
+    let _ = sampling::batch_players(&raw);
+    let _ = player_sessions::session_records(&raw).map(player_sessions::segments);
+});
@@ -0,0 +1,22 @@
+//! Every transform and in-process check over an untrusted batch: errors are fine, panics are not.
+#![no_main]
+
+use async_anticheat_api::{checks, transforms};
+use async_anticheat_api_fuzz::batch;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let raw = batch(data);
+    for transform in transforms::SUPPORTED {
+        let _ = transforms::apply_transform(transform, &raw);
+    }
+    let _ = transforms::apply_transform_with(
+        "movement_events_v1_ndjson_gz+server_time",
+        &raw,
+        Some(-1500),
+    );
+    let _ = transforms::client_channel_records(&raw);
+    for engine in ["movement_core", "combat_core", "player_core"] {
+        let _ = checks::run(engine, &raw);
+    }
+});
@@ -0,0 +1,16 @@
+//! Shared input handling for the fuzz targets.
+
+use flate2::{write::GzEncoder, Compression};
+use std::io::Write;
+
+/// The batch body for a fuzz input: inputs starting with the gzip magic are used as they are
+/// (exercising decompression), anything else is gzipped first so the fuzzer reaches the NDJSON
+/// and JSON parsing behind it. Plain NDJSON fixtures thus work as seeds.
+pub fn batch(data: &[u8]) -> Vec<u8> {
+    if data.starts_with(&[0x1f, 0x8b]) {
+        return data.to_vec();
+    }
+    let mut enc = GzEncoder::new(Vec::new(), Compression::fast());
+    enc.write_all(data).unwrap();
+    enc.finish().unwrap()
+}
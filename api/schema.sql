@@ -45,6 +45,92 @@ alter table public.servers
 alter table public.servers
     add column if not exists webhook_severity_levels text[] not null default array['critical', 'high']::text[];
 
+-- Share findings with (and query) the cross-server reputation service.
+alter table public.servers
+    add column if not exists reputation_opt_in boolean not null default false;
+
+-- Webhook notifications of findings below min_confidence are downgraded one severity level or
+-- dropped (low_confidence_action: downgrade | drop).
+alter table public.servers
+    add column if not exists min_confidence double precision;
+alter table public.servers
+    add column if not exists low_confidence_action text not null default 'downgrade';
+
+-- Last plugin version reported in X-Plugin-Version (see src/plugin_version.rs).
+alter table public.servers
+    add column if not exists plugin_version text;
+alter table public.servers
+    add column if not exists plugin_version_seen_at timestamptz;
+
+--------------------------------------------------------------------------------
+-- ORGANIZATIONS: networks (proxy + backends) owning groups of servers
+--------------------------------------------------------------------------------
+-- A server attached to an organization counts as registered without an owner_user_id.
+-- The organization webhook is used by member servers that have no webhook of their own.
+create table if not exists public.organizations (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    name text not null,
+    webhook_url text,
+    webhook_enabled boolean not null default false,
+    webhook_severity_levels text[] not null default array['critical', 'high']::text[]
+);
+
+create table if not exists public.organization_members (
+    organization_id uuid not null references public.organizations(id) on delete cascade,
+    user_id uuid not null,                       -- Supabase auth.users.id
+    role text not null default 'member' check (role in ('owner', 'admin', 'member')),
+    created_at timestamptz not null default now(),
+    primary key (organization_id, user_id)
+);
+
+create index if not exists idx_organization_members_user
+    on public.organization_members (user_id);
+
+alter table public.servers
+    add column if not exists organization_id uuid references public.organizations(id) on delete set null;
+
+create index if not exists idx_servers_organization
+    on public.servers (organization_id);
+
+-- Exemptions applying to every server of an organization (same selectors as server_exemptions).
+create table if not exists public.organization_exemptions (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    organization_id uuid not null references public.organizations(id) on delete cascade,
+    player_uuid uuid,
+    permission text,
+    world text,
+    starts_at timestamptz not null default now(),
+    ends_at timestamptz,
+    reason text,
+    created_by text
+);
+
+create index if not exists idx_organization_exemptions_org
+    on public.organization_exemptions (organization_id, ends_at);
+
+--------------------------------------------------------------------------------
+-- PROXY_GROUPS: backends behind one BungeeCord/Velocity proxy
+--------------------------------------------------------------------------------
+-- Backends send `X-Proxy-Id` on ingest. Proxy ids are only unique per owner (user or
+-- organization), so another account can't join its servers to someone else's group.
+create table if not exists public.proxy_groups (
+    id uuid primary key default gen_random_uuid(),
+    owner_id uuid not null,                      -- coalesce(owner_user_id, organization_id)
+    proxy_id text not null,                      -- X-Proxy-Id
+    name text,
+    first_seen_at timestamptz not null default now(),
+    last_seen_at timestamptz not null default now(),
+    unique (owner_id, proxy_id)
+);
+
+alter table public.servers
+    add column if not exists proxy_group_id uuid references public.proxy_groups(id) on delete set null;
+
+create index if not exists idx_servers_proxy_group
+    on public.servers (proxy_group_id);
+
 --------------------------------------------------------------------------------
 -- PLAYERS: unique player identities (by UUID)
 --------------------------------------------------------------------------------
@@ -57,6 +143,23 @@ create table if not exists public.players (
 
 create index if not exists idx_players_username on public.players (username);
 
+-- A player's continuous stay on a proxy group: extended while they keep appearing on any
+-- backend within the session gap (see src/proxy_groups.rs), so backend hops don't split it.
+create table if not exists public.proxy_sessions (
+    id uuid primary key default gen_random_uuid(),
+    proxy_group_id uuid not null references public.proxy_groups(id) on delete cascade,
+    player_uuid uuid not null references public.players(uuid) on delete cascade,
+    started_at timestamptz not null default now(),
+    last_seen_at timestamptz not null default now(),
+    last_server_id text not null,
+    server_ids text[] not null default '{}'      -- backends visited, in first-visit order
+);
+
+create index if not exists idx_proxy_sessions_group_player
+    on public.proxy_sessions (proxy_group_id, player_uuid, last_seen_at desc);
+create index if not exists idx_proxy_sessions_group_last_seen
+    on public.proxy_sessions (proxy_group_id, last_seen_at desc);
+
 --------------------------------------------------------------------------------
 -- SERVER_PLAYERS: per-server last-seen tracking (for "active players" UI)
 --------------------------------------------------------------------------------
@@ -72,6 +175,25 @@ create table if not exists public.server_players (
 create index if not exists idx_server_players_server_last_seen
     on public.server_players (server_id, last_seen_at desc);
 
+-- Name lookups from the in-game command (GET /plugin/findings?player=<name>).
+create index if not exists idx_server_players_server_name
+    on public.server_players (server_id, lower(player_name), last_seen_at desc);
+
+-- Last known world / gamemode (from PLAYER_STATE records or record-level context).
+alter table public.server_players
+    add column if not exists world text;
+alter table public.server_players
+    add column if not exists gamemode text;
+-- Exemption permission nodes the player holds (see src/exemptions.rs).
+alter table public.server_players
+    add column if not exists permissions text[] not null default '{}';
+-- java | bedrock (Geyser), from the batch metadata's `client_types`.
+alter table public.server_players
+    add column if not exists client_type text;
+-- Protocol version the player joined with (ViaVersion), from `client_protocols`; 47 = 1.8.
+alter table public.server_players
+    add column if not exists client_protocol int;
+
 --------------------------------------------------------------------------------
 -- SESSIONS: a player's connection session on a server
 --------------------------------------------------------------------------------
@@ -125,6 +247,23 @@ create table if not exists public.server_modules (
 create index if not exists idx_server_modules_server
     on public.server_modules (server_id, enabled);
 
+--------------------------------------------------------------------------------
+-- MODULE_CATALOG: curated community modules listed next to the built-in ones
+--------------------------------------------------------------------------------
+create table if not exists public.module_catalog (
+    name text primary key,                      -- becomes server_modules.name when enabled
+    tier text not null default 'community',     -- core | advanced | community
+    short_description text not null default '',
+    checks text[] not null default '{}',        -- detector names the module reports
+    transform text not null default 'raw_ndjson_gz',
+    default_base_url text,                      -- suggested base_url (null = must be provided)
+    install_instructions text,
+    homepage_url text,
+    listed boolean not null default true,
+    created_at timestamptz not null default now(),
+    updated_at timestamptz not null default now()
+);
+
 --------------------------------------------------------------------------------
 -- MODULE_DISPATCHES: audit/debugging for fanout attempts
 --------------------------------------------------------------------------------
@@ -134,19 +273,106 @@ create table if not exists public.module_dispatches (
     batch_id uuid not null references public.batch_index(id) on delete cascade,
     server_id text not null references public.servers(id) on delete cascade,
     module_id uuid not null references public.server_modules(id) on delete cascade,
-    status text not null,                       -- sent | failed
+    status text not null,                       -- sent | failed | processing | completed | timed_out
     http_status int,
     error text
 );
 
 create index if not exists idx_module_dispatches_batch
     on public.module_dispatches (batch_id, created_at desc);
+-- Dispatch history of a module (cursor pagination).
+create index if not exists idx_module_dispatches_module_time
+    on public.module_dispatches (module_id, created_at desc, id desc);
+
+-- Set when the batch contains a watchlisted player; cleanup skips the batch until then.
+alter table public.batch_index
+    add column if not exists retain_until timestamptz;
 
 create index if not exists idx_batch_index_server_time
     on public.batch_index (server_id, received_at desc);
 create index if not exists idx_batch_index_session
     on public.batch_index (session_id, received_at desc);
 
+-- Per-session batch number from X-Batch-Seq and how it followed the previous ones
+-- (ok | gap | reordered | duplicate); see src/batch_sequence.rs.
+alter table public.batch_index
+    add column if not exists batch_seq bigint;
+alter table public.batch_index
+    add column if not exists seq_status text;
+alter table public.batch_index
+    add column if not exists seq_missing int;
+
+create index if not exists idx_batch_index_server_session_seq
+    on public.batch_index (server_id, session_id, batch_seq)
+    where batch_seq is not null;
+
+-- Suspicious-ingest detection (src/ingest_anomalies.rs): quarantined batches are stored but never
+-- dispatched, reprocessed or replayed; payload_sha256 (of the decompressed payload) finds repeats.
+alter table public.batch_index
+    add column if not exists quarantined boolean not null default false;
+alter table public.batch_index
+    add column if not exists payload_sha256 text;
+
+create index if not exists idx_batch_index_server_sha256
+    on public.batch_index (server_id, payload_sha256)
+    where payload_sha256 is not null;
+
+-- Plugin clock vs API clock (src/clock_skew.rs): received_at is the API receive time,
+-- clock_offset_ms the plugin's X-Client-Time minus it, clock_skew_ms the session's estimate.
+alter table public.batch_index
+    add column if not exists clock_offset_ms bigint;
+alter table public.batch_index
+    add column if not exists clock_skew_ms bigint;
+
+--------------------------------------------------------------------------------
+-- INGEST_ANOMALIES: why a batch was quarantined (one row per reason)
+--------------------------------------------------------------------------------
+create table if not exists public.ingest_anomalies (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    batch_id uuid not null references public.batch_index(id) on delete cascade,
+    server_id text not null references public.servers(id) on delete cascade,
+    session_id text not null,
+    kind text not null,                         -- undecodable | low_entropy | future_timestamp | ...
+    details jsonb not null default '{}'
+);
+
+create index if not exists idx_ingest_anomalies_server_time
+    on public.ingest_anomalies (server_id, created_at desc);
+
+--------------------------------------------------------------------------------
+-- MODULE_CONFORMANCE_RUNS: contract test reports (POST /admin/modules/:id/conformance)
+--------------------------------------------------------------------------------
+create table if not exists public.module_conformance_runs (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    module_id uuid not null references public.server_modules(id) on delete cascade,
+    server_id text not null references public.servers(id) on delete cascade,
+    marker text not null unique,                -- session id carried by the canned batch
+    passed boolean,                             -- null while the run is in progress
+    report_json jsonb,
+    callback_received_at timestamptz,
+    callback_findings int
+);
+
+create index if not exists idx_module_conformance_runs_module
+    on public.module_conformance_runs (module_id, created_at desc);
+
+--------------------------------------------------------------------------------
+-- WASM_MODULES: uploaded sandboxed modules (server_modules.base_url = wasm://<id>)
+--------------------------------------------------------------------------------
+create table if not exists public.wasm_modules (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    server_id text not null references public.servers(id) on delete cascade,
+    name text not null,                         -- server_modules.name it backs
+    sha256 text not null,
+    size_bytes int not null,
+    wasm bytea not null,
+    fuel_limit bigint not null,
+    memory_limit_bytes bigint not null
+);
+
 --------------------------------------------------------------------------------
 -- FINDINGS: detections/alerts produced by processors
 --------------------------------------------------------------------------------
@@ -170,18 +396,194 @@ create table if not exists public.findings (
     last_seen_at timestamptz not null default now(),
     reviewed_at timestamptz,
     reviewed_by text,
-    status text not null default 'open'        -- open, confirmed, dismissed
+    status text not null default 'open'        -- open, confirmed, dismissed, false_positive
 );
 
+-- World the player was in (evidence `world`, else their last known world).
+alter table public.findings
+    add column if not exists world text;
+
+-- Detector confidence 0-1 (null: not reported, treated as certain); highest of the bucket.
+alter table public.findings
+    add column if not exists confidence double precision;
+
 create index if not exists idx_findings_server on public.findings (server_id, created_at desc);
 create index if not exists idx_findings_player on public.findings (player_uuid, created_at desc);
 create index if not exists idx_findings_status on public.findings (status, created_at desc);
 create index if not exists idx_findings_server_last_seen on public.findings (server_id, last_seen_at desc);
 create index if not exists idx_findings_player_last_seen on public.findings (player_uuid, last_seen_at desc);
+create index if not exists idx_findings_server_world on public.findings (server_id, world, last_seen_at desc);
 create unique index if not exists uq_findings_agg_minute
     on public.findings (server_id, player_uuid, detector_name, window_start_at)
     where player_uuid is not null;
 
+--------------------------------------------------------------------------------
+-- PLAYER_IDENTIFIERS: shared-identity signals for alt correlation (see src/identity.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.player_identifiers (
+    server_id text not null references public.servers(id) on delete cascade,
+    player_uuid uuid not null references public.players(uuid) on delete cascade,
+    kind text not null,                         -- ip_hash | fingerprint | client_brand
+    value text not null,                        -- salted hash / opaque value from the plugin
+    first_seen_at timestamptz not null default now(),
+    last_seen_at timestamptz not null default now(),
+    primary key (server_id, player_uuid, kind, value)
+);
+create index if not exists idx_player_identifiers_value
+    on public.player_identifiers (server_id, kind, value);
+
+--------------------------------------------------------------------------------
+-- PLAYER_SESSION_CLIENTS: client brand + registered plugin channels per session (see src/client_channels.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.player_session_clients (
+    server_id text not null references public.servers(id) on delete cascade,
+    session_id text not null,
+    player_uuid uuid not null,
+    brand text,                                 -- minecraft:brand payload
+    channels text[] not null default '{}',      -- minecraft:register channels (sorted, distinct)
+    first_seen_at timestamptz not null default now(),
+    last_seen_at timestamptz not null default now(),
+    primary key (server_id, session_id, player_uuid)
+);
+create index if not exists idx_player_session_clients_player
+    on public.player_session_clients (server_id, player_uuid, last_seen_at desc);
+
+--------------------------------------------------------------------------------
+-- PLAYER_SESSIONS: a player's stay on a server, stitched across batches (see src/player_sessions.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.player_sessions (
+    id uuid primary key default gen_random_uuid(),
+    server_id text not null references public.servers(id) on delete cascade,
+    player_uuid uuid not null references public.players(uuid) on delete cascade,
+    plugin_session_id text not null,            -- X-Session-Id the session was seen under
+    started_at timestamptz not null,            -- from record `ts`
+    last_seen_at timestamptz not null,
+    ended_at timestamptz,                       -- null while open (or until the next sighting closes it)
+    end_reason text                             -- quit, timeout, rejoin, restart
+);
+
+create index if not exists idx_player_sessions_player
+    on public.player_sessions (server_id, player_uuid, started_at desc);
+create index if not exists idx_player_sessions_server
+    on public.player_sessions (server_id, last_seen_at desc);
+
+--------------------------------------------------------------------------------
+-- SERVER_HEARTBEATS: performance samples from heartbeat bodies (rolling 24h window)
+--------------------------------------------------------------------------------
+create table if not exists public.server_heartbeats (
+    id bigint generated always as identity primary key,
+    server_id text not null references public.servers(id) on delete cascade,
+    received_at timestamptz not null default now(),
+    tps double precision,
+    mspt double precision,
+    online_players integer,
+    plugin_version text,
+    mc_version text
+);
+
+create index if not exists idx_server_heartbeats_server
+    on public.server_heartbeats (server_id, received_at desc);
+
+--------------------------------------------------------------------------------
+-- SERVER_PROBES: latest Server List Ping per server, written by the background prober
+--------------------------------------------------------------------------------
+create table if not exists public.server_probes (
+    server_id text primary key references public.servers(id) on delete cascade,
+    address text not null,                      -- host:port that was pinged
+    reachable boolean not null,
+    latency_ms bigint,
+    motd text,
+    players_online bigint,
+    players_max bigint,
+    version text,
+    protocol bigint,
+    probed_at timestamptz not null default now()
+);
+
+--------------------------------------------------------------------------------
+-- SERVER_CAPTURE_CONFIG: dashboard-managed capture settings served at GET /config
+--------------------------------------------------------------------------------
+create table if not exists public.server_capture_config (
+    server_id text primary key references public.servers(id) on delete cascade,
+    config jsonb not null,                      -- see CaptureConfig in src/capture_config.rs
+    updated_at timestamptz not null default now(),
+    updated_by text
+);
+
+--------------------------------------------------------------------------------
+-- SERVER_EXEMPTIONS: players/permissions/worlds excluded from detection (see src/exemptions.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.server_exemptions (
+    id uuid primary key default gen_random_uuid(),
+    server_id text not null references public.servers(id) on delete cascade,
+    player_uuid uuid,                           -- selectors: every non-null one must match
+    permission text,
+    world text,
+    starts_at timestamptz not null default now(),
+    ends_at timestamptz,                        -- null = until removed
+    reason text,
+    created_by text,
+    created_at timestamptz not null default now(),
+    check (player_uuid is not null or permission is not null or world is not null)
+);
+create index if not exists idx_server_exemptions_server
+    on public.server_exemptions (server_id, ends_at);
+
+--------------------------------------------------------------------------------
+-- SERVER_WATCHLIST: players under enhanced capture (see src/watchlist.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.server_watchlist (
+    server_id text not null references public.servers(id) on delete cascade,
+    player_uuid uuid not null,
+    reason text,
+    added_by text,
+    expires_at timestamptz,                     -- null = until removed
+    created_at timestamptz not null default now(),
+    primary key (server_id, player_uuid)
+);
+
+--------------------------------------------------------------------------------
+-- SERVER_PENDING_ACTIONS: server -> plugin actions, delivered in the heartbeat response
+--------------------------------------------------------------------------------
+create table if not exists public.server_pending_actions (
+    id uuid primary key default gen_random_uuid(),
+    server_id text not null references public.servers(id) on delete cascade,
+    action text not null,                       -- e.g. watchlist_sync
+    payload jsonb not null,
+    created_at timestamptz not null default now(),
+    delivered_at timestamptz                    -- null until handed to the plugin
+);
+create index if not exists idx_server_pending_actions_server
+    on public.server_pending_actions (server_id, delivered_at);
+
+--------------------------------------------------------------------------------
+-- PLAYER_REPUTATION: cross-server scores from opted-in servers (see src/reputation.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.player_reputation (
+    player_uuid uuid primary key,
+    score double precision not null,            -- 0-100
+    weight double precision not null,           -- raw severity-weighted sum
+    servers_flagged int not null,
+    findings int not null,
+    confirmed_findings int not null,
+    last_flagged_at timestamptz,
+    updated_at timestamptz not null default now()
+);
+
+--------------------------------------------------------------------------------
+-- DETECTOR_METRICS: review counters per detector (precision = confirmed / (confirmed + false_positives))
+--------------------------------------------------------------------------------
+create table if not exists public.detector_metrics (
+    server_id text not null references public.servers(id) on delete cascade,
+    detector_name text not null,
+    confirmed bigint not null default 0,
+    false_positives bigint not null default 0,
+    dismissed bigint not null default 0,
+    last_false_positive_at timestamptz,
+    updated_at timestamptz not null default now(),
+    primary key (server_id, detector_name)
+);
+
 --------------------------------------------------------------------------------
 -- DETECTOR_CONFIGS: per-server processor/detector settings
 --------------------------------------------------------------------------------
@@ -195,6 +597,18 @@ create table if not exists public.detector_configs (
     unique (server_id, detector_name)
 );
 
+--------------------------------------------------------------------------------
+-- SEVERITY_OVERRIDES: per-server severity for a detector's findings (src/severity_overrides.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.severity_overrides (
+    server_id text not null references public.servers(id) on delete cascade,
+    detector_name text not null,
+    severity text not null,                     -- critical | high | medium | low | info
+    created_at timestamptz not null default now(),
+    updated_at timestamptz not null default now(),
+    primary key (server_id, detector_name)
+);
+
 --------------------------------------------------------------------------------
 -- AGGREGATES: pre-computed metrics for dashboards
 --------------------------------------------------------------------------------
@@ -246,6 +660,32 @@ create index if not exists idx_module_player_state_lookup
 --   ]
 -- }
 
+--------------------------------------------------------------------------------
+-- MODULE_PLAYER_STATE_ARCHIVE: summaries of expired per-player module state
+--------------------------------------------------------------------------------
+-- State of players not seen on a server for PLAYER_STATE_DECAY_DAYS is removed
+-- from module_player_state (see player_state_decay.rs); its VL and top-level
+-- scalar fields are kept here.
+--------------------------------------------------------------------------------
+create table if not exists public.module_player_state_archive (
+    id uuid primary key default gen_random_uuid(),
+    server_id text not null references public.servers(id) on delete cascade,
+    player_uuid uuid not null,
+    module_name text not null,
+    vl double precision,                       -- state_json.vl, when numeric
+    summary_json jsonb not null default '{}',  -- top-level numbers and booleans
+    state_bytes int not null default 0,        -- size of the removed state_json
+    state_updated_at timestamptz not null,     -- last write by the module
+    player_last_seen_at timestamptz,           -- server_players.last_seen_at
+    archived_at timestamptz not null default now()
+);
+
+create index if not exists idx_module_player_state_archive_player
+    on public.module_player_state_archive (server_id, player_uuid, archived_at desc);
+
+create index if not exists idx_module_player_state_updated
+    on public.module_player_state (updated_at);
+
 --------------------------------------------------------------------------------
 -- MODULE_GLOBAL_STATE: persistent global state for modules (non-player-specific)
 --------------------------------------------------------------------------------
@@ -327,6 +767,103 @@ create index if not exists idx_cheat_observations_type
 create index if not exists idx_cheat_observations_finding
     on public.cheat_observations (finding_id);
 
+-- Findings whose time window overlaps an observation (linked automatically).
+create table if not exists public.observation_findings (
+    observation_id uuid not null references public.cheat_observations(id) on delete cascade,
+    finding_id uuid not null references public.findings(id) on delete cascade,
+    linked_at timestamptz not null default now(),
+    primary key (observation_id, finding_id)
+);
+
+create index if not exists idx_observation_findings_finding
+    on public.observation_findings (finding_id);
+
+-- Replays of the batches covering an observation (POST /admin/observations/:id/replay).
+-- Replay findings are kept apart from the live findings table.
+create table if not exists public.observation_replays (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    completed_at timestamptz,
+    observation_id uuid not null references public.cheat_observations(id) on delete cascade,
+    server_id text not null references public.servers(id) on delete cascade,
+    module_id uuid references public.server_modules(id) on delete set null,  -- null = all enabled modules
+    status text not null default 'running',     -- running, completed, failed
+    batches_total int not null default 0,
+    batches_replayed int not null default 0,
+    batches_missing int not null default 0,
+    errors jsonb
+);
+
+create table if not exists public.observation_replay_findings (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    replay_id uuid not null references public.observation_replays(id) on delete cascade,
+    observation_id uuid not null references public.cheat_observations(id) on delete cascade,
+    module_id uuid not null,
+    player_uuid uuid,
+    detector_name text not null,
+    detector_version text,
+    severity text not null,
+    title text not null,
+    evidence_json jsonb
+);
+
+create index if not exists idx_observation_replay_findings_replay
+    on public.observation_replay_findings (replay_id);
+
+-- Reprocessing jobs (POST /admin/reprocess): re-run stored batches received in
+-- [range_start, range_end) through one module, e.g. after a transform fix. The worker
+-- pages through batch_index by (received_at, id) and saves the cursor after every page
+-- so jobs resume after a restart. Findings go to the live findings table.
+create table if not exists public.reprocess_jobs (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    server_id text not null references public.servers(id) on delete cascade,
+    module_id uuid not null references public.server_modules(id) on delete cascade,
+    transform text,                              -- null = module's configured transform
+    range_start timestamptz not null,
+    range_end timestamptz not null,
+    status text not null default 'queued',      -- queued, running, completed, failed, cancelled
+    batches_total int not null default 0,
+    batches_processed int not null default 0,
+    batches_missing int not null default 0,
+    findings int not null default 0,
+    errors jsonb,
+    cursor_received_at timestamptz,
+    cursor_batch_id uuid,
+    started_at timestamptz,
+    updated_at timestamptz not null default now(),
+    completed_at timestamptz
+);
+
+create index if not exists idx_reprocess_jobs_status
+    on public.reprocess_jobs (status, created_at);
+
+-- Simulation runs (POST /admin/simulate): curated cheat scenarios sent through a server's
+-- enabled modules. Findings are attributed to scenarios by fixture player and kept apart
+-- from the live findings table.
+create table if not exists public.simulation_runs (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    completed_at timestamptz,
+    server_id text not null references public.servers(id) on delete cascade,
+    scenarios text[] not null,
+    report_json jsonb                            -- SimulationReport, set on completion
+);
+
+create table if not exists public.simulation_findings (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    run_id uuid not null references public.simulation_runs(id) on delete cascade,
+    module_id uuid not null,
+    player_uuid uuid not null,                   -- scenario fixture player
+    detector_name text not null,
+    severity text not null
+);
+
+create index if not exists idx_simulation_findings_run
+    on public.simulation_findings (run_id);
+
 --------------------------------------------------------------------------------
 -- MODULE_ENABLED_AUDIT: track all changes to server_modules.enabled
 --------------------------------------------------------------------------------
@@ -391,3 +928,555 @@ drop trigger if exists trg_audit_module_enabled on public.server_modules;
 create trigger trg_audit_module_enabled
     after insert or update or delete on public.server_modules
     for each row execute function audit_module_enabled_change();
+
+--------------------------------------------------------------------------------
+-- PUNISHMENT_EXPORTS: automated ban rule + export destination per server
+--------------------------------------------------------------------------------
+-- Findings with a trigger severity (and at least min_confidence) produce one ban
+-- action per player, exported to a LiteBans-style webhook or left queued in
+-- punishment_actions for a bridge to consume (see punishments.rs).
+--------------------------------------------------------------------------------
+create table if not exists public.punishment_exports (
+    server_id text primary key references public.servers(id) on delete cascade,
+    enabled boolean not null default false,
+    mode text not null default 'queue',          -- queue | webhook
+    webhook_url text,
+    webhook_token text,                          -- sent as a bearer token
+    trigger_severities text[] not null default '{critical}',
+    min_confidence double precision,
+    ban_duration_seconds bigint,                 -- null = permanent
+    reason text not null default 'Unfair advantage ({detectors})',
+    evidence_base_url text,
+    created_at timestamptz not null default now(),
+    updated_at timestamptz not null default now()
+);
+
+--------------------------------------------------------------------------------
+-- PUNISHMENT_ACTIONS: exported bans (columns follow LiteBans' bans table)
+--------------------------------------------------------------------------------
+create table if not exists public.punishment_actions (
+    id bigserial primary key,
+    created_at timestamptz not null default now(),
+    server_id text not null references public.servers(id) on delete cascade,
+    uuid varchar(36) not null,                   -- player UUID
+    player_name text,
+    reason text not null,
+    banned_by_name text not null,
+    time bigint not null,                        -- epoch ms
+    until bigint not null,                       -- epoch ms, -1 = permanent
+    silent boolean not null default false,
+    ipban boolean not null default false,
+    active boolean not null default true,
+    evidence_url text not null,
+    evidence jsonb not null default '[]',        -- triggering findings
+    mode text not null,                          -- queue | webhook
+    delivery text not null,                      -- queued | consumed | pending | sent | failed
+    delivered_at timestamptz,
+    last_error text
+);
+
+create index if not exists idx_punishment_actions_server
+    on public.punishment_actions (server_id, created_at desc);
+
+create index if not exists idx_punishment_actions_player
+    on public.punishment_actions (server_id, uuid, created_at desc);
+
+create index if not exists idx_punishment_actions_queued
+    on public.punishment_actions (server_id, id) where delivery = 'queued';
+
+--------------------------------------------------------------------------------
+-- EVIDENCE_BUNDLES: appeal review bundles stored in the object store
+--------------------------------------------------------------------------------
+-- Finding, related findings, event excerpts and module state of the player,
+-- as gzipped JSON (see evidence_bundle.rs).
+--------------------------------------------------------------------------------
+create table if not exists public.evidence_bundles (
+    id uuid primary key,
+    created_at timestamptz not null default now(),
+    server_id text not null references public.servers(id) on delete cascade,
+    finding_id uuid not null references public.findings(id) on delete cascade,
+    s3_key text not null,                        -- bundles/{server_id}/{finding_id}/{id}.json.gz
+    size_bytes bigint not null,
+    events int not null default 0                -- event excerpts included
+);
+
+create index if not exists idx_evidence_bundles_finding
+    on public.evidence_bundles (finding_id, created_at desc);
+
+-- Encoding batches are dispatched to the module with (gzip | zstd | identity), negotiated from
+-- its registration or /health Accept-Encoding; see src/dispatch_encoding.rs.
+alter table public.server_modules
+    add column if not exists content_encoding text not null default 'gzip';
+
+-- Async processing (src/async_dispatch.rs): a module answering 202 with a processing id
+-- reports completion later via /callbacks/dispatch-complete. Dispatch status then goes
+-- processing -> completed | failed, or timed_out once deadline_at passes.
+alter table public.server_modules
+    add column if not exists processing_deadline_seconds int not null default 300;
+alter table public.module_dispatches
+    add column if not exists processing_id text;
+alter table public.module_dispatches
+    add column if not exists deadline_at timestamptz;
+alter table public.module_dispatches
+    add column if not exists completed_at timestamptz;
+
+create index if not exists idx_module_dispatches_processing
+    on public.module_dispatches (server_id, processing_id) where processing_id is not null;
+create index if not exists idx_module_dispatches_deadline
+    on public.module_dispatches (deadline_at) where status = 'processing';
+
+-- Module that reported the finding (the X-Module-Id it was dispatched with); null for plugin
+-- findings, correlation findings and rows stored before attribution.
+alter table public.findings
+    add column if not exists module_id uuid references public.server_modules(id) on delete set null;
+
+create index if not exists idx_findings_module
+    on public.findings (module_id) where module_id is not null;
+
+--------------------------------------------------------------------------------
+-- MODULE_DISPATCH_STATS: hourly rollup of module_dispatches (src/dispatch_stats.rs)
+--------------------------------------------------------------------------------
+-- Raw dispatch rows are kept for MODULE_DISPATCH_RETENTION_DAYS; dashboard views read these.
+--------------------------------------------------------------------------------
+alter table public.module_dispatches
+    add column if not exists latency_ms int;     -- time to the module's response (all parts)
+
+create index if not exists idx_module_dispatches_created
+    on public.module_dispatches (created_at);
+
+create table if not exists public.module_dispatch_stats (
+    module_id uuid not null references public.server_modules(id) on delete cascade,
+    hour timestamptz not null,
+    server_id text not null references public.servers(id) on delete cascade,
+    dispatches int not null,
+    succeeded int not null,                      -- sent | completed
+    failed int not null,                         -- failed
+    timed_out int not null,
+    avg_latency_ms double precision,
+    p95_latency_ms double precision,
+    updated_at timestamptz not null default now(),
+    primary key (module_id, hour)
+);
+
+create index if not exists idx_module_dispatch_stats_server
+    on public.module_dispatch_stats (server_id, hour desc);
+
+--------------------------------------------------------------------------------
+-- SERVER_PRESENCE_EVENTS: plugin offline / online transitions (src/server_presence.rs)
+--------------------------------------------------------------------------------
+-- A server is offline once last_seen_at is older than SERVER_OFFLINE_AFTER_SECONDS;
+-- offline_since is its last contact before the outage, null while online.
+--------------------------------------------------------------------------------
+alter table public.servers
+    add column if not exists offline_since timestamptz;
+
+create table if not exists public.server_presence_events (
+    id bigserial primary key,
+    server_id text not null references public.servers(id) on delete cascade,
+    online boolean not null,
+    at timestamptz not null                     -- last contact (down) / first contact (up)
+);
+
+create index if not exists idx_server_presence_events_server
+    on public.server_presence_events (server_id, at desc);
+
+--------------------------------------------------------------------------------
+-- SERVER_PURGE_JOBS: decommissioned servers (src/server_deletion.rs)
+--------------------------------------------------------------------------------
+-- DELETE /dashboard/:server_id sets deleted_at (plugin data is refused from then on); an admin
+-- purge job removes the server's objects and rows once SERVER_PURGE_GRACE_DAYS have passed.
+--------------------------------------------------------------------------------
+alter table public.servers
+    add column if not exists deleted_at timestamptz;
+
+create table if not exists public.server_purge_jobs (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    server_id text not null,                     -- no FK: the job is the record of the purge
+    status text not null default 'queued',      -- queued, running, completed, failed
+    objects_deleted bigint not null default 0,
+    findings_deleted bigint not null default 0,
+    batches_deleted bigint not null default 0,
+    modules_deleted bigint not null default 0,
+    error text,
+    started_at timestamptz,
+    updated_at timestamptz not null default now(),
+    completed_at timestamptz
+);
+
+create index if not exists idx_server_purge_jobs_status
+    on public.server_purge_jobs (status, created_at);
+create index if not exists idx_server_purge_jobs_server
+    on public.server_purge_jobs (server_id, created_at desc);
+
+--------------------------------------------------------------------------------
+-- PLAYER_DELETION_JOBS: erasure of a player's data (src/privacy.rs)
+--------------------------------------------------------------------------------
+-- Queued via POST /admin/privacy/delete-player; the worker scrubs the player's records from
+-- stored raw batches, then deletes their rows. player_uuid is cleared once the job completes.
+--------------------------------------------------------------------------------
+create table if not exists public.player_deletion_jobs (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    player_uuid uuid,                            -- no FK: the player row is deleted
+    status text not null default 'queued',      -- queued, running, completed, failed
+    batches_rewritten bigint not null default 0,
+    records_removed bigint not null default 0,
+    findings_deleted bigint not null default 0,
+    objects_deleted bigint not null default 0,   -- evidence bundles
+    rows_deleted bigint not null default 0,
+    error text,
+    started_at timestamptz,
+    updated_at timestamptz not null default now(),
+    completed_at timestamptz
+);
+
+create index if not exists idx_player_deletion_jobs_status
+    on public.player_deletion_jobs (status, created_at);
+
+-- Store a keyed per-server hash instead of player UUIDs (src/privacy.rs; needs
+-- PLAYER_UUID_HASH_KEY). Data stored before it was enabled is not rewritten.
+alter table public.servers
+    add column if not exists hash_player_uuids boolean not null default false;
+
+--------------------------------------------------------------------------------
+-- SERVER_EXPORTS: takeout archives of a server's data (src/server_export.rs)
+--------------------------------------------------------------------------------
+-- POST /dashboard/:server_id/export builds a tar.gz of findings, players, module config,
+-- observations and optionally raw batches under exports/ in the object store.
+--------------------------------------------------------------------------------
+create table if not exists public.server_exports (
+    id uuid primary key,
+    created_at timestamptz not null default now(),
+    server_id text not null references public.servers(id) on delete cascade,
+    status text not null default 'running',     -- running, completed, failed
+    include_raw_batches boolean not null default false,
+    s3_key text not null,                        -- exports/{server_id}/{id}.tar.gz
+    findings bigint not null default 0,
+    players bigint not null default 0,
+    observations bigint not null default 0,
+    raw_batches bigint not null default 0,
+    raw_batches_truncated boolean not null default false,
+    size_bytes bigint,
+    error text,
+    completed_at timestamptz
+);
+
+create index if not exists idx_server_exports_server
+    on public.server_exports (server_id, created_at desc);
+
+--------------------------------------------------------------------------------
+-- FINDINGS.SOURCE: violation history imported from other anticheats (src/findings_import.rs)
+--------------------------------------------------------------------------------
+-- POST /dashboard/:server_id/import stores NCP/Grim/Vulcan log violations as findings;
+-- source is the log format (ncp, grim, vulcan), null for findings of our own modules.
+--------------------------------------------------------------------------------
+alter table public.findings
+    add column if not exists source text;
+
+--------------------------------------------------------------------------------
+-- MODULE_ENDPOINTS: per-endpoint health of modules with backups (src/module_failover.rs)
+--------------------------------------------------------------------------------
+-- Backup endpoints tried in order when base_url (the primary) fails; http(s) modules only.
+alter table public.server_modules
+    add column if not exists backup_base_urls text[] not null default '{}';
+
+create table if not exists public.module_endpoints (
+    module_id uuid not null references public.server_modules(id) on delete cascade,
+    base_url text not null,                     -- server_modules.base_url or one of the backups
+    healthy boolean not null,
+    consecutive_failures int not null default 0,
+    last_error text,
+    last_checked_at timestamptz not null default now(),
+    primary key (module_id, base_url)
+);
+
+--------------------------------------------------------------------------------
+-- MODULE_VARIANT_STATS: canary vs stable comparison per module and day (src/module_canary.rs)
+--------------------------------------------------------------------------------
+-- canary_percent of a module's batches go to canary_base_url instead of its stable endpoints.
+alter table public.server_modules
+    add column if not exists canary_base_url text;
+alter table public.server_modules
+    add column if not exists canary_percent int not null default 0;
+-- stable | canary; null for modules without a canary.
+alter table public.module_dispatches
+    add column if not exists variant text;
+
+create table if not exists public.module_variant_stats (
+    module_id uuid not null references public.server_modules(id) on delete cascade,
+    day date not null,
+    variant text not null,                      -- stable | canary
+    batches bigint not null default 0,          -- batches the variant accepted
+    findings bigint not null default 0,         -- findings it reported for them
+    primary key (module_id, day, variant)
+);
+
+-- Variant processing each batch of a module with a canary, recorded before the batch is sent.
+-- Pruned with module_dispatches.
+create table if not exists public.module_batch_variants (
+    module_id uuid not null references public.server_modules(id) on delete cascade,
+    batch_id uuid not null,
+    variant text not null,                      -- stable | canary
+    assigned_at timestamptz not null default now(),
+    primary key (module_id, batch_id)
+);
+
+-- stable | canary for findings of a batch processed by a module with a canary.
+alter table public.findings
+    add column if not exists module_variant text;
+
+--------------------------------------------------------------------------------
+-- SHADOW_FINDINGS: findings of modules running in shadow mode (src/shadow.rs)
+--------------------------------------------------------------------------------
+-- active | shadow; shadow modules get batches but their findings stay out of `findings`.
+alter table public.server_modules
+    add column if not exists mode text not null default 'active';
+
+create table if not exists public.shadow_findings (
+    id uuid primary key default gen_random_uuid(),
+    created_at timestamptz not null default now(),
+    server_id text not null references public.servers(id) on delete cascade,
+    module_id uuid not null references public.server_modules(id) on delete cascade,
+    session_id text,
+    batch_id uuid,
+    player_uuid uuid,
+    detector_name text not null,
+    detector_version text,
+    severity text not null,
+    title text not null,
+    description text,
+    confidence double precision,
+    evidence_s3_key text,
+    evidence_json jsonb
+);
+
+-- Purged after 30 days.
+create index if not exists idx_shadow_findings_module
+    on public.shadow_findings (module_id, created_at desc);
+
+--------------------------------------------------------------------------------
+-- MODULE_USAGE_DAILY: payload bytes and transform time per module and day (src/module_usage.rs)
+--------------------------------------------------------------------------------
+-- Encoded payload sent to the module (0 for in-process and WASM modules).
+alter table public.module_dispatches
+    add column if not exists payload_bytes bigint;
+-- Time spent transforming and encoding the batch (running the module for local ones).
+alter table public.module_dispatches
+    add column if not exists transform_us bigint;
+
+create table if not exists public.module_usage_daily (
+    module_id uuid not null references public.server_modules(id) on delete cascade,
+    day date not null,
+    server_id text not null references public.servers(id) on delete cascade,
+    dispatches bigint not null default 0,
+    payload_bytes bigint not null default 0,
+    transform_us bigint not null default 0,
+    primary key (module_id, day)
+);
+
+create index if not exists idx_module_usage_daily_server
+    on public.module_usage_daily (server_id, day);
+
+--------------------------------------------------------------------------------
+-- BATCH_INDEX.DISPATCH_STATUS: batches stored without module dispatch (src/dispatch_shedding.rs)
+--------------------------------------------------------------------------------
+-- null (dispatched at ingest) | stored_not_dispatched (shed under overload) | recovered | lost
+alter table public.batch_index
+    add column if not exists dispatch_status text;
+
+create index if not exists idx_batch_index_not_dispatched
+    on public.batch_index (received_at) where dispatch_status = 'stored_not_dispatched';
+
+--------------------------------------------------------------------------------
+-- TASK_LEASES: which API replica runs each background task (src/task_lock.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.task_leases (
+    task text primary key,                      -- e.g. module_healthcheck
+    holder text not null,                       -- replica: host name + per-process id
+    acquired_at timestamptz not null default now(),
+    expires_at timestamptz not null,            -- renewed by the holder; free once past
+    last_finished_at timestamptz
+);
+
+--------------------------------------------------------------------------------
+-- API_REPLICAS: live API replicas sharing per-server work (src/replica_shards.rs)
+--------------------------------------------------------------------------------
+-- Heartbeat every 15s; rows silent for 60s are dropped.
+create table if not exists public.api_replicas (
+    instance_id text primary key,               -- same holder name as task_leases
+    started_at timestamptz not null default now(),
+    heartbeat_at timestamptz not null default now()
+);
+
+--------------------------------------------------------------------------------
+-- BATCH_INDEX metadata columns: typed fields of the batch metadata line (src/batch_meta.rs)
+--------------------------------------------------------------------------------
+-- meta_version 0: unversioned line from an older plugin
+alter table public.batch_index
+    add column if not exists meta_version int not null default 0;
+alter table public.batch_index
+    add column if not exists plugin_version text;
+alter table public.batch_index
+    add column if not exists protocol_version int;
+alter table public.batch_index
+    add column if not exists mc_version text;
+alter table public.batch_index
+    add column if not exists world text;
+alter table public.batch_index
+    add column if not exists tick_rate double precision;
+alter table public.batch_index
+    add column if not exists player_count int;
+
+--------------------------------------------------------------------------------
+-- SERVER_INGEST_FILTERS: packet types dropped at ingest, before storage (src/ingest_filters.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.server_ingest_filters (
+    server_id text primary key references public.servers(id) on delete cascade,
+    filters jsonb not null,                     -- see IngestFilters in src/ingest_filters.rs
+    updated_at timestamptz not null default now(),
+    updated_by text
+);
+
+--------------------------------------------------------------------------------
+-- Sampling of low-risk players' batches (src/sampling.rs)
+--------------------------------------------------------------------------------
+-- Share of low-risk batches analyzed; null: every batch is analyzed.
+alter table public.servers
+    add column if not exists sample_rate double precision;
+-- Rate the batch was sampled at (null: analyzed in full); sampled-out batches have
+-- dispatch_status = 'sampled_out'.
+alter table public.batch_index
+    add column if not exists sample_rate double precision;
+
+--------------------------------------------------------------------------------
+-- PLAYER_CASES: moderation cases with attached evidence and actions (src/cases.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.player_cases (
+    id uuid primary key default gen_random_uuid(),
+    server_id text not null references public.servers(id) on delete cascade,
+    player_uuid uuid not null,
+    title text not null,
+    status text not null default 'open',         -- open | closed
+    outcome text,                                -- confirmed_cheater | cleared | insufficient_evidence
+    summary text,
+    opened_by text,
+    closed_by text,
+    created_at timestamptz not null default now(),
+    updated_at timestamptz not null default now(),
+    closed_at timestamptz
+);
+
+create index if not exists idx_player_cases_server
+    on public.player_cases (server_id, updated_at desc);
+create index if not exists idx_player_cases_player
+    on public.player_cases (server_id, player_uuid, created_at desc);
+create unique index if not exists uq_player_cases_open
+    on public.player_cases (server_id, player_uuid) where status = 'open';
+
+-- Exactly one of finding_id / observation_id / evidence_bundle_id is set.
+create table if not exists public.case_items (
+    id uuid primary key default gen_random_uuid(),
+    case_id uuid not null references public.player_cases(id) on delete cascade,
+    finding_id uuid references public.findings(id) on delete cascade,
+    observation_id uuid references public.cheat_observations(id) on delete cascade,
+    evidence_bundle_id uuid references public.evidence_bundles(id) on delete cascade,
+    note text,
+    added_by text,
+    added_at timestamptz not null default now(),
+    check (num_nonnulls(finding_id, observation_id, evidence_bundle_id) = 1)
+);
+
+create index if not exists idx_case_items_case
+    on public.case_items (case_id, added_at);
+
+create table if not exists public.case_actions (
+    id uuid primary key default gen_random_uuid(),
+    case_id uuid not null references public.player_cases(id) on delete cascade,
+    action text not null,                        -- warning | kick | mute | temp_ban | ban | unban | other
+    details text,
+    taken_by text,
+    created_at timestamptz not null default now()
+);
+
+create index if not exists idx_case_actions_case
+    on public.case_actions (case_id, created_at);
+
+--------------------------------------------------------------------------------
+-- MODERATOR_NOTES: staff notes on players and comments on findings (src/notes.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.moderator_notes (
+    id uuid primary key default gen_random_uuid(),
+    server_id text not null references public.servers(id) on delete cascade,
+    player_uuid uuid,                            -- the player, also on finding comments
+    finding_id uuid references public.findings(id) on delete cascade,  -- null: note on the player
+    author text not null,
+    body text not null,
+    created_at timestamptz not null default now()
+);
+
+create index if not exists idx_moderator_notes_player
+    on public.moderator_notes (server_id, player_uuid, created_at desc);
+create index if not exists idx_moderator_notes_finding
+    on public.moderator_notes (finding_id, created_at desc) where finding_id is not null;
+
+--------------------------------------------------------------------------------
+-- REPORT_SCHEDULES: weekly digest reports per server (src/reports.rs)
+--------------------------------------------------------------------------------
+create table if not exists public.report_schedules (
+    server_id text primary key references public.servers(id) on delete cascade,
+    enabled boolean not null default false,
+    weekday int not null default 1,              -- ISO weekday it goes out: 1 = Monday .. 7 = Sunday
+    hour_utc int not null default 9,
+    format text not null default 'html',         -- html | json (email body)
+    webhook_url text,
+    email_recipients text[] not null default '{}',
+    last_period_end timestamptz,                 -- end of the last week reported
+    last_sent_at timestamptz,
+    last_error text,                             -- failed channels of the last delivery
+    updated_at timestamptz not null default now()
+);
+
+--------------------------------------------------------------------------------
+-- FINDING_LATENCY: ingest -> finding latency per detector and day (src/finding_latency.rs)
+--------------------------------------------------------------------------------
+alter table public.findings
+    add column if not exists batch_received_at timestamptz,   -- arrival of the batch it was found in
+    add column if not exists ingest_latency_ms int;           -- stored this long after (first occurrence)
+
+create index if not exists idx_findings_latency
+    on public.findings (server_id, first_seen_at) where ingest_latency_ms is not null;
+
+create table if not exists public.finding_latency_daily (
+    server_id text not null references public.servers(id) on delete cascade,
+    detector_name text not null,
+    day date not null,                           -- UTC day the findings were stored
+    findings int not null,
+    p50_ms double precision,
+    p90_ms double precision,
+    p99_ms double precision,
+    max_ms int,
+    within_slo int not null,                     -- findings within slo_ms
+    slo_ms int not null,                         -- FINDING_LATENCY_SLO_SECONDS at rollup time
+    updated_at timestamptz not null default now(),
+    primary key (server_id, detector_name, day)
+);
+
+--------------------------------------------------------------------------------
+-- MODULE_MAINTENANCE_WINDOWS: planned module downtime (src/module_maintenance.rs)
+--------------------------------------------------------------------------------
+-- No dispatch, healthchecks or failure counting until then; null = not paused.
+alter table public.server_modules
+    add column if not exists paused_until timestamptz;
+
+create table if not exists public.module_maintenance_windows (
+    id uuid primary key default gen_random_uuid(),
+    module_id uuid not null references public.server_modules(id) on delete cascade,
+    weekday smallint,                            -- 0 = Monday .. 6 = Sunday (UTC); null = daily
+    start_minute int not null,                   -- minute of the day (UTC)
+    duration_minutes int not null,
+    created_at timestamptz not null default now()
+);
+
+create index if not exists idx_module_maintenance_windows_module
+    on public.module_maintenance_windows (module_id);
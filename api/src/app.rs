@@ -0,0 +1,666 @@
+//! The HTTP API: shared state built from the configuration, and the router with every route and
+//! its auth layers. `serve` runs it; route tests drive it in-process.
+
+use axum::{
+    extract::DefaultBodyLimit,
+    http::{
+        header::{AUTHORIZATION, CONTENT_TYPE},
+        HeaderName, HeaderValue, Method,
+    },
+    middleware,
+    routing::get,
+    Router,
+};
+use sqlx::PgPool;
+use tower_http::{cors::CorsLayer, trace::TraceLayer};
+
+use crate::{
+    chaos::Faults,
+    config::Config,
+    ingest_hints::DispatchBacklog,
+    plugin_version::{Version, VersionPolicy},
+    replica_shards::ShardMap,
+    reports::Mailer,
+    request_id, routes,
+    s3::ObjectStore,
+    service_status::{self, IngestOutcomes},
+    shared_state::SharedState,
+    supervisor::Supervisor,
+    traffic_stats::{self, TrafficStats},
+    transform_pool::TransformPool,
+    AppState,
+};
+
+/// Shared state over already-connected backends. Does not run migrations or start background
+/// work.
+pub fn state(
+    cfg: &Config,
+    db: PgPool,
+    object_store: ObjectStore,
+    shared: SharedState,
+    report_mailer: Mailer,
+) -> anyhow::Result<AppState> {
+    let http = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()?;
+
+    Ok(AppState {
+        db,
+        object_store,
+        ingest_token: cfg.ingest_token.clone(),
+        module_callback_token: cfg.module_callback_token.clone(),
+        dashboard_token: cfg.dashboard_token.clone(),
+        admin_token: cfg.admin_token.clone(),
+        http,
+        max_body_bytes: cfg.max_body_bytes,
+        dispatch_part_max_bytes: cfg.dispatch_part_max_bytes,
+        transform_pool: TransformPool::new(
+            cfg.transform_workers,
+            cfg.transform_queue_capacity,
+            std::time::Duration::from_millis(cfg.transform_budget_ms),
+        ),
+        object_store_cleanup_enabled: cfg.object_store_cleanup_enabled,
+        object_store_cleanup_dry_run: cfg.object_store_cleanup_dry_run,
+        object_store_cleanup_interval_seconds: cfg.object_store_cleanup_interval_seconds,
+        object_store_ttl_days: cfg.object_store_ttl_days,
+        object_store_ttl_seconds_override: cfg.object_store_ttl_seconds_override,
+        batch_index_ttl_days: cfg.batch_index_ttl_days,
+        batch_index_ttl_seconds_override: cfg.batch_index_ttl_seconds_override,
+        supervisor: Supervisor::default(),
+        reputation_enabled: cfg.reputation_enabled,
+        reputation_lookback_days: cfg.reputation_lookback_days,
+        player_state_decay_enabled: cfg.player_state_decay_enabled,
+        player_state_decay_days: cfg.player_state_decay_days,
+        watchlist_retention_days: cfg.watchlist_retention_days,
+        module_dispatch_retention_days: cfg.module_dispatch_retention_days,
+        server_offline_after_seconds: cfg.server_offline_after_seconds,
+        server_purge_grace_days: cfg.server_purge_grace_days,
+        player_uuid_hash_key: cfg.player_uuid_hash_key.clone(),
+        dispatch_backlog: DispatchBacklog::default(),
+        shards: ShardMap::default(),
+        shared,
+        ingest_rate_limit_per_minute: cfg.ingest_rate_limit_per_minute,
+        webhook_dedup_seconds: cfg.webhook_dedup_seconds,
+        plugin_versions: VersionPolicy {
+            min_supported: cfg
+                .min_supported_plugin_version
+                .as_deref()
+                .and_then(Version::parse),
+            recommended: cfg
+                .recommended_plugin_version
+                .as_deref()
+                .and_then(Version::parse),
+        },
+        traffic: TrafficStats::default(),
+        ingest_outcomes: IngestOutcomes::default(),
+        finding_latency_slo_seconds: cfg.finding_latency_slo_seconds,
+        faults: Faults::default(),
+        report_mailer,
+    })
+}
+
+/// Every route with its auth layers, plus CORS, tracing and request id propagation.
+pub fn router(state: AppState, cfg: &Config) -> Router {
+    // Dashboard routes (protected by DASHBOARD_TOKEN when set)
+    let dashboard_routes = Router::new()
+        .route("/openapi.json", get(routes::docs::openapi_json))
+        .route("/docs", get(routes::docs::swagger_ui))
+        .route("/dashboard/servers", get(routes::dashboard::get_servers))
+        .route(
+            "/dashboard/organizations",
+            get(routes::organizations::list_organizations)
+                .post(routes::organizations::create_organization),
+        )
+        .route(
+            "/dashboard/organizations/:org_id",
+            get(routes::organizations::get_organization),
+        )
+        .route(
+            "/dashboard/organizations/:org_id/servers",
+            axum::routing::post(routes::organizations::attach_server),
+        )
+        .route(
+            "/dashboard/organizations/:org_id/servers/:server_id",
+            axum::routing::delete(routes::organizations::detach_server),
+        )
+        .route(
+            "/dashboard/organizations/:org_id/members",
+            axum::routing::post(routes::organizations::set_member),
+        )
+        .route(
+            "/dashboard/organizations/:org_id/members/:user_id",
+            axum::routing::delete(routes::organizations::remove_member),
+        )
+        .route(
+            "/dashboard/organizations/:org_id/webhook",
+            axum::routing::post(routes::organizations::set_webhook),
+        )
+        .route(
+            "/dashboard/organizations/:org_id/exemptions",
+            get(routes::organizations::list_exemptions)
+                .post(routes::organizations::create_exemption),
+        )
+        .route(
+            "/dashboard/organizations/:org_id/exemptions/:exemption_id",
+            axum::routing::delete(routes::organizations::delete_exemption),
+        )
+        .route(
+            "/dashboard/organizations/:org_id/findings",
+            get(routes::organizations::get_findings),
+        )
+        .route(
+            "/dashboard/proxy-groups",
+            get(routes::proxy_groups::list_proxy_groups),
+        )
+        .route(
+            "/dashboard/proxy-groups/:group_id",
+            get(routes::proxy_groups::get_proxy_group),
+        )
+        .route(
+            "/dashboard/proxy-groups/:group_id/findings",
+            get(routes::proxy_groups::get_findings),
+        )
+        .route(
+            "/dashboard/proxy-groups/:group_id/players",
+            get(routes::proxy_groups::get_players),
+        )
+        .route(
+            "/dashboard/proxy-groups/:group_id/players/:player_uuid/sessions",
+            get(routes::proxy_groups::get_player_sessions),
+        )
+        .route(
+            "/dashboard/:server_id",
+            axum::routing::delete(routes::server_deletion::delete_server),
+        )
+        .route(
+            "/dashboard/:server_id/restore",
+            axum::routing::post(routes::server_deletion::restore_server),
+        )
+        .route(
+            "/dashboard/:server_id/stats",
+            get(routes::dashboard::get_stats),
+        )
+        .route(
+            "/dashboard/:server_id/findings",
+            get(routes::dashboard::get_findings),
+        )
+        .route(
+            "/dashboard/:server_id/findings/:finding_id",
+            get(routes::dashboard::get_finding),
+        )
+        .route(
+            "/dashboard/:server_id/findings/:finding_id/comments",
+            axum::routing::post(routes::notes::add_finding_comment),
+        )
+        .route(
+            "/dashboard/:server_id/notes/:note_id",
+            axum::routing::delete(routes::notes::delete_note),
+        )
+        .route(
+            "/dashboard/:server_id/findings/:finding_id/status",
+            axum::routing::post(routes::dashboard::update_finding_status),
+        )
+        .route(
+            "/dashboard/:server_id/findings/:finding_id/bundle",
+            axum::routing::post(routes::evidence_bundles::create_evidence_bundle),
+        )
+        .route(
+            "/dashboard/:server_id/bundles/:bundle_id",
+            get(routes::evidence_bundles::download_evidence_bundle),
+        )
+        .route(
+            "/dashboard/:server_id/batches",
+            get(routes::batches::list_batches),
+        )
+        .route(
+            "/dashboard/:server_id/batches/:batch_id/packets",
+            get(routes::batches::get_batch_packets),
+        )
+        .route(
+            "/dashboard/:server_id/detectors/quality",
+            get(routes::dashboard::get_detector_quality),
+        )
+        .route(
+            "/dashboard/:server_id/players",
+            get(routes::dashboard::get_players),
+        )
+        .route(
+            "/dashboard/:server_id/players/:player_uuid",
+            get(routes::dashboard::get_player),
+        )
+        .route(
+            "/dashboard/:server_id/players/:player_uuid/notes",
+            get(routes::notes::list_player_notes).post(routes::notes::add_player_note),
+        )
+        .route(
+            "/dashboard/:server_id/players/:player_uuid/related",
+            get(routes::dashboard::get_related_players),
+        )
+        .route(
+            "/dashboard/:server_id/players/:player_uuid/path",
+            get(routes::player_path::get_player_path),
+        )
+        .route(
+            "/dashboard/:server_id/sessions",
+            get(routes::sessions::list_sessions),
+        )
+        .route(
+            "/dashboard/:server_id/capture-config",
+            get(routes::capture_config::get_capture_config)
+                .post(routes::capture_config::update_capture_config),
+        )
+        .route(
+            "/dashboard/:server_id/ingest-filters",
+            get(routes::ingest_filters::get_ingest_filters)
+                .post(routes::ingest_filters::update_ingest_filters),
+        )
+        .route(
+            "/dashboard/:server_id/check-thresholds",
+            get(routes::check_thresholds::list_check_thresholds),
+        )
+        .route(
+            "/dashboard/:server_id/check-thresholds/:detector_name",
+            axum::routing::post(routes::check_thresholds::update_check_threshold),
+        )
+        .route(
+            "/dashboard/:server_id/ingest-anomalies",
+            get(routes::ingest_anomalies::list_ingest_anomalies),
+        )
+        .route(
+            "/dashboard/:server_id/severity-overrides",
+            get(routes::severity_overrides::list_severity_overrides),
+        )
+        .route(
+            "/dashboard/:server_id/severity-overrides/:detector_name",
+            axum::routing::post(routes::severity_overrides::set_severity_override)
+                .delete(routes::severity_overrides::delete_severity_override),
+        )
+        .route(
+            "/dashboard/:server_id/confidence-filter",
+            get(routes::confidence_filter::get_confidence_filter)
+                .post(routes::confidence_filter::update_confidence_filter),
+        )
+        .route(
+            "/dashboard/:server_id/punishment-export",
+            get(routes::punishments::get_punishment_export)
+                .post(routes::punishments::update_punishment_export),
+        )
+        .route(
+            "/dashboard/:server_id/punishments",
+            get(routes::punishments::list_punishments),
+        )
+        .route(
+            "/dashboard/:server_id/reports/schedule",
+            get(routes::reports::get_report_schedule).post(routes::reports::update_report_schedule),
+        )
+        .route(
+            "/dashboard/:server_id/reports/weekly",
+            get(routes::reports::get_weekly_report),
+        )
+        .route(
+            "/dashboard/:server_id/reports/send",
+            axum::routing::post(routes::reports::send_report),
+        )
+        .route(
+            "/dashboard/:server_id/modules",
+            get(routes::dashboard::get_modules).post(routes::dashboard::create_module),
+        )
+        .route(
+            "/dashboard/:server_id/modules/:module_id/toggle",
+            axum::routing::post(routes::dashboard::toggle_module),
+        )
+        .route(
+            "/dashboard/:server_id/status",
+            get(routes::dashboard::get_status),
+        )
+        .route(
+            "/dashboard/:server_id/modules/catalog/enable",
+            axum::routing::post(routes::catalog::enable_catalog_module),
+        )
+        .route(
+            "/dashboard/:server_id/modules/wasm",
+            axum::routing::post(routes::wasm_modules::upload_wasm_module),
+        )
+        .route(
+            "/dashboard/:server_id/modules/:module_id/conformance",
+            get(routes::dashboard::get_module_conformance),
+        )
+        .route(
+            "/dashboard/:server_id/modules/:module_id/dispatch-stats",
+            get(routes::dispatch_stats::get_dispatch_stats),
+        )
+        .route(
+            "/dashboard/:server_id/modules/:module_id/dispatches",
+            get(routes::dispatch_stats::list_dispatches),
+        )
+        .route(
+            "/dashboard/:server_id/finding-latency",
+            get(routes::finding_latency::get_finding_latency),
+        )
+        .route(
+            "/dashboard/:server_id/modules/:module_id/canary",
+            get(routes::module_canary::get_canary_report),
+        )
+        .route(
+            "/dashboard/:server_id/modules/:module_id/mode",
+            axum::routing::post(routes::shadow::set_module_mode),
+        )
+        .route(
+            "/dashboard/:server_id/modules/:module_id/shadow-findings",
+            get(routes::shadow::list_shadow_findings),
+        )
+        .route(
+            "/dashboard/:server_id/modules/:module_id/maintenance",
+            get(routes::module_maintenance::get_module_maintenance)
+                .put(routes::module_maintenance::put_module_maintenance),
+        )
+        .route(
+            "/dashboard/:server_id/modules/:module_id/pause",
+            axum::routing::post(routes::module_maintenance::pause_module),
+        )
+        .route(
+            "/dashboard/:server_id/modules/:module_id/resume",
+            axum::routing::post(routes::module_maintenance::resume_module),
+        )
+        .route(
+            "/dashboard/:server_id/modules/usage",
+            get(routes::module_usage::get_module_usage),
+        )
+        .route(
+            "/dashboard/:server_id/modules/audit",
+            get(routes::dashboard::get_module_audit),
+        )
+        .route(
+            "/dashboard/:server_id/reputation",
+            axum::routing::post(routes::reputation::set_reputation_opt_in),
+        )
+        .route(
+            "/dashboard/:server_id/export",
+            axum::routing::post(routes::server_export::create_server_export),
+        )
+        .route(
+            "/dashboard/:server_id/exports/:export_id",
+            get(routes::server_export::get_server_export),
+        )
+        .route(
+            "/dashboard/:server_id/exports/:export_id/download",
+            get(routes::server_export::download_server_export),
+        )
+        .route(
+            "/dashboard/:server_id/import",
+            axum::routing::post(routes::findings_import::import_findings),
+        )
+        .route(
+            "/dashboard/:server_id/privacy",
+            axum::routing::post(routes::privacy::set_privacy_settings),
+        )
+        .route(
+            "/dashboard/:server_id/sampling",
+            get(routes::sampling::get_sampling_settings)
+                .post(routes::sampling::set_sampling_settings),
+        )
+        .route(
+            "/dashboard/:server_id/exemptions",
+            get(routes::exemptions::list_exemptions).post(routes::exemptions::create_exemption),
+        )
+        .route(
+            "/dashboard/:server_id/exemptions/:exemption_id",
+            axum::routing::delete(routes::exemptions::delete_exemption),
+        )
+        .route(
+            "/dashboard/:server_id/watchlist",
+            get(routes::watchlist::list_watchlist).post(routes::watchlist::add_to_watchlist),
+        )
+        .route(
+            "/dashboard/:server_id/watchlist/:player_uuid",
+            axum::routing::delete(routes::watchlist::remove_from_watchlist),
+        )
+        .route(
+            "/dashboard/:server_id/observations",
+            get(routes::observations::list_observations),
+        )
+        .route(
+            "/dashboard/:server_id/observations/:observation_id",
+            get(routes::observations::get_observation)
+                .patch(routes::observations::review_observation),
+        )
+        .route(
+            "/dashboard/:server_id/cases",
+            get(routes::cases::list_cases).post(routes::cases::create_case),
+        )
+        .route(
+            "/dashboard/:server_id/cases/:case_id",
+            get(routes::cases::get_case)
+                .patch(routes::cases::update_case)
+                .delete(routes::cases::delete_case),
+        )
+        .route(
+            "/dashboard/:server_id/cases/:case_id/items",
+            axum::routing::post(routes::cases::add_case_item),
+        )
+        .route(
+            "/dashboard/:server_id/cases/:case_id/items/:item_id",
+            axum::routing::delete(routes::cases::remove_case_item),
+        )
+        .route(
+            "/dashboard/:server_id/cases/:case_id/actions",
+            axum::routing::post(routes::cases::add_case_action),
+        )
+        .route(
+            "/dashboard/:server_id/cases/:case_id/close",
+            axum::routing::post(routes::cases::close_case),
+        )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            routes::auth::require_dashboard,
+        ));
+
+    // Admin routes (protected by ADMIN_TOKEN; disabled when unset)
+    let admin_routes = Router::new()
+        .route(
+            "/admin/modules/:module_id/conformance",
+            axum::routing::post(routes::admin::run_module_conformance),
+        )
+        .route(
+            "/admin/observations/:observation_id/replay",
+            axum::routing::post(routes::admin::replay_observation),
+        )
+        .route(
+            "/admin/privacy/delete-player",
+            axum::routing::post(routes::admin::delete_player),
+        )
+        .route(
+            "/admin/privacy/deletions/:job_id",
+            get(routes::admin::get_player_deletion),
+        )
+        .route("/admin/replays/:replay_id", get(routes::admin::get_replay))
+        .route("/admin/replicas", get(routes::admin::list_replicas))
+        .route(
+            "/admin/reprocess",
+            axum::routing::post(routes::admin::create_reprocess_job),
+        )
+        .route(
+            "/admin/reprocess/:job_id",
+            get(routes::admin::get_reprocess_job),
+        )
+        .route(
+            "/admin/reprocess/:job_id/cancel",
+            axum::routing::post(routes::admin::cancel_reprocess_job),
+        )
+        .route(
+            "/admin/servers/:server_id/purge",
+            axum::routing::post(routes::admin::purge_server),
+        )
+        .route(
+            "/admin/server-purges/:job_id",
+            get(routes::admin::get_server_purge),
+        )
+        .route(
+            "/admin/simulate",
+            axum::routing::post(routes::admin::simulate),
+        )
+        .route(
+            "/admin/simulations/:run_id",
+            get(routes::admin::get_simulation),
+        )
+        .route("/admin/tasks", get(routes::admin::list_task_leases))
+        .route("/admin/top-talkers", get(routes::admin::get_top_talkers))
+        .route(
+            "/admin/transform-pool",
+            get(routes::admin::get_transform_pool),
+        );
+    // Fault injection exists only in builds with the `chaos` feature (see src/chaos.rs).
+    #[cfg(feature = "chaos")]
+    let admin_routes = {
+        tracing::warn!(
+            "built with the chaos feature: fault injection is available at /admin/chaos"
+        );
+        admin_routes.route(
+            "/admin/chaos",
+            get(routes::chaos::get_faults)
+                .put(routes::chaos::set_faults)
+                .delete(routes::chaos::clear_faults),
+        )
+    };
+    let admin_routes = admin_routes.layer(middleware::from_fn_with_state(
+        state.clone(),
+        routes::auth::require_admin,
+    ));
+
+    Router::new()
+        .route("/health", get(routes::health::health))
+        .route("/status/public", get(routes::status::public_status))
+        .route(
+            "/handshake",
+            axum::routing::post(routes::handshake::handshake),
+        )
+        .route(
+            "/heartbeat",
+            axum::routing::post(routes::heartbeat::heartbeat),
+        )
+        .route("/config", get(routes::capture_config::get_plugin_config))
+        .route(
+            "/ingest",
+            axum::routing::post(routes::ingest::ingest)
+                .layer(DefaultBodyLimit::max(cfg.max_body_bytes))
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    service_status::track_ingest,
+                )),
+        )
+        .route(
+            "/servers/:server_id/modules",
+            axum::routing::post(routes::modules::upsert_module).get(routes::modules::list_modules),
+        )
+        .route("/modules/catalog", get(routes::catalog::get_catalog))
+        .route(
+            "/checks/:check_name",
+            get(routes::check_docs::get_check_doc),
+        )
+        .route(
+            "/callbacks/findings",
+            axum::routing::post(routes::callbacks::post_findings),
+        )
+        .route(
+            "/callbacks/dispatch-complete",
+            axum::routing::post(routes::callbacks::dispatch_complete),
+        )
+        // Module state persistence endpoints
+        .route(
+            "/callbacks/player-state",
+            axum::routing::get(routes::callbacks::get_player_state)
+                .post(routes::callbacks::set_player_state),
+        )
+        .route(
+            "/callbacks/player-states/batch-get",
+            axum::routing::post(routes::callbacks::batch_get_player_states),
+        )
+        .route(
+            "/callbacks/player-states/batch-set",
+            axum::routing::post(routes::callbacks::batch_set_player_states),
+        )
+        // Observation submission endpoint (from plugin)
+        .route(
+            "/observations",
+            axum::routing::post(routes::observations::create_observation),
+        )
+        .route(
+            "/observations/:observation_id",
+            axum::routing::patch(routes::observations::end_observation),
+        )
+        .route(
+            "/reputation/:player_uuid",
+            get(routes::reputation::get_reputation),
+        )
+        // In-game lookup command (per-server token)
+        .route(
+            "/plugin/findings",
+            get(routes::plugin_findings::get_plugin_findings),
+        )
+        // GraphQL (dashboard or admin token; checked per field in the handler)
+        .route("/graphql", axum::routing::post(routes::graphql::graphql))
+        .merge(dashboard_routes)
+        .merge(admin_routes)
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            traffic_stats::track,
+        ))
+        .with_state(state)
+        .layer(cors_layer(cfg))
+        .layer(TraceLayer::new_for_http())
+        .layer(middleware::from_fn(request_id::propagate))
+}
+
+const X_REQUEST_ID: HeaderName = HeaderName::from_static(request_id::REQUEST_ID_HEADER);
+
+/// Build a CORS layer.
+/// SECURITY: Permissive CORS is only allowed when CORS_PERMISSIVE_DEV=true is explicitly set.
+/// This prevents accidental permissive CORS in production.
+pub fn cors_layer(cfg: &Config) -> CorsLayer {
+    if cfg.cors_allow_origins.is_empty() {
+        if cfg.cors_permissive_dev {
+            tracing::warn!(
+                "CORS_PERMISSIVE_DEV=true: using permissive CORS. DO NOT USE IN PRODUCTION!"
+            );
+            return CorsLayer::permissive();
+        } else {
+            // In production with no origins configured, use restrictive defaults
+            // This allows same-origin requests only
+            tracing::info!(
+                "CORS_ALLOW_ORIGINS is empty and CORS_PERMISSIVE_DEV is not set. \
+                 Using restrictive CORS (same-origin only). Set CORS_ALLOW_ORIGINS \
+                 or CORS_PERMISSIVE_DEV=true for cross-origin requests."
+            );
+            return CorsLayer::new()
+                .allow_methods([
+                    Method::GET,
+                    Method::POST,
+                    Method::PUT,
+                    Method::PATCH,
+                    Method::DELETE,
+                    Method::OPTIONS,
+                ])
+                .allow_headers([CONTENT_TYPE, AUTHORIZATION, X_REQUEST_ID])
+                .expose_headers([X_REQUEST_ID]);
+        }
+    }
+
+    let origins: Vec<HeaderValue> = cfg
+        .cors_allow_origins
+        .iter()
+        .filter_map(|o| o.parse().ok())
+        .collect();
+
+    CorsLayer::new()
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers([CONTENT_TYPE, AUTHORIZATION, X_REQUEST_ID])
+        .expose_headers([X_REQUEST_ID])
+        .allow_origin(origins)
+}
@@ -0,0 +1,167 @@
+//! Async module processing.
+//!
+//! A module that needs longer than an HTTP request (statistical checks over many batches, a
+//! queue in front of workers) can answer a dispatch with `202 Accepted` and a processing id,
+//! either as an `X-Processing-Id` header or as `{"processing_id": "..."}` in the body. The
+//! dispatch is then recorded as `processing` with a deadline of the module's
+//! `processing_deadline_seconds`, and the module reports the outcome later on
+//! `POST /callbacks/dispatch-complete`. The watchdog marks dispatches still `processing` past
+//! their deadline as `timed_out` and counts that as a module failure.
+//!
+//! A 202 without a processing id is an ordinary `sent` dispatch.
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{module_pipeline, AppState};
+
+pub const PROCESSING_ID_HEADER: &str = "x-processing-id";
+pub const DEFAULT_DEADLINE_SECONDS: i32 = 300;
+pub const MAX_DEADLINE_SECONDS: i32 = 24 * 3600;
+pub const MAX_PROCESSING_ID_LEN: usize = 200;
+pub const WATCHDOG_INTERVAL_SECONDS: u64 = 30;
+
+/// The processing id of a 202 response, from its header or JSON body.
+pub fn processing_id(header: Option<&str>, body: &[u8]) -> Option<String> {
+    let id = match header.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(id) => id.to_string(),
+        None => serde_json::from_slice::<Value>(body)
+            .ok()?
+            .get("processing_id")
+            .and_then(Value::as_str)?
+            .trim()
+            .to_string(),
+    };
+    (!id.is_empty() && id.len() <= MAX_PROCESSING_ID_LEN).then_some(id)
+}
+
+/// Record a dispatch the module is still working on.
+pub async fn record_processing(
+    state: &AppState,
+    batch_id: Uuid,
+    module_id: &Uuid,
+    server_id: &str,
+    processing_id: &str,
+    deadline_seconds: i32,
+    latency_ms: i32,
+) {
+    let res = sqlx::query(
+        r#"
+        insert into public.module_dispatches
+            (batch_id, server_id, module_id, status, http_status, processing_id, deadline_at,
+             latency_ms)
+        values
+            ($1, $2, $3, 'processing', 202, $4, now() + make_interval(secs => $5), $6)
+        "#,
+    )
+    .bind(batch_id)
+    .bind(server_id)
+    .bind(module_id)
+    .bind(processing_id)
+    .bind(deadline_seconds.clamp(1, MAX_DEADLINE_SECONDS) as f64)
+    .bind(latency_ms)
+    .execute(&state.db)
+    .await;
+    if let Err(e) = res {
+        tracing::error!(batch_id = %batch_id, "record async dispatch failed: {:?}", e);
+    }
+}
+
+/// Outcome of a completion callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Completion {
+    /// The dispatch moved to this status.
+    Recorded(&'static str),
+    NotFound,
+    /// The dispatch already finished with this status (e.g. `timed_out`).
+    AlreadyFinished(String),
+}
+
+/// Finish the most recent `processing` dispatch with this id.
+pub async fn complete(
+    state: &AppState,
+    server_id: &str,
+    processing_id: &str,
+    ok: bool,
+    error: Option<&str>,
+) -> Result<Completion, sqlx::Error> {
+    let status = if ok { "completed" } else { "failed" };
+    let updated: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        update public.module_dispatches
+        set status = $3, error = $4, completed_at = now()
+        where id = (
+            select id from public.module_dispatches
+            where server_id = $1 and processing_id = $2 and status = 'processing'
+            order by created_at desc
+            limit 1
+        )
+        returning module_id
+        "#,
+    )
+    .bind(server_id)
+    .bind(processing_id)
+    .bind(status)
+    .bind(error)
+    .fetch_optional(&state.db)
+    .await?;
+    if let Some(module_id) = updated {
+        if ok {
+            module_pipeline::mark_module_ok(state, &module_id).await;
+        } else {
+            let err = error.unwrap_or("processing failed");
+            module_pipeline::mark_module_failure(state, &module_id, err).await;
+        }
+        return Ok(Completion::Recorded(status));
+    }
+
+    let finished: Option<String> = sqlx::query_scalar(
+        r#"
+        select status from public.module_dispatches
+        where server_id = $1 and processing_id = $2
+        order by created_at desc
+        limit 1
+        "#,
+    )
+    .bind(server_id)
+    .bind(processing_id)
+    .fetch_optional(&state.db)
+    .await?;
+    Ok(match finished {
+        Some(status) => Completion::AlreadyFinished(status),
+        None => Completion::NotFound,
+    })
+}
+
+/// Time out dispatches past their deadline.
+pub async fn watchdog_tick(state: AppState) {
+    let expired: Result<Vec<(Uuid, String)>, _> = sqlx::query_as(
+        r#"
+        update public.module_dispatches
+        set status = 'timed_out',
+            error = 'no completion before the processing deadline',
+            completed_at = now()
+        where status = 'processing' and deadline_at < now()
+        returning module_id, coalesce(processing_id, '')
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let expired = match expired {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::warn!("async dispatch watchdog failed: {:?}", e);
+            return;
+        }
+    };
+    for (module_id, processing_id) in expired {
+        tracing::warn!(
+            module_id = %module_id,
+            processing_id = %processing_id,
+            "async dispatch timed out"
+        );
+        let err = format!("processing {} timed out", processing_id);
+        module_pipeline::mark_module_failure(&state, &module_id, &err).await;
+    }
+}
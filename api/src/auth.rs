@@ -4,8 +4,12 @@
 //! consistent security practices like constant-time comparison.
 
 use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
 use sha2::Digest;
 use subtle::ConstantTimeEq;
+use uuid::Uuid;
+
+use crate::{error::ApiError, request_id, AppState};
 
 /// Extracts and parses a Bearer token from the Authorization header.
 ///
@@ -82,10 +86,7 @@ pub fn extract_server_address(headers: &HeaderMap) -> Option<String> {
     }
 
     // 2. X-Forwarded-For (first IP in the chain, closest to client)
-    if let Some(forwarded) = headers
-        .get("x-forwarded-for")
-        .and_then(|v| v.to_str().ok())
-    {
+    if let Some(forwarded) = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok()) {
         // X-Forwarded-For can be comma-separated: "client, proxy1, proxy2"
         if let Some(first_ip) = forwarded.split(',').next().map(|s| s.trim()) {
             if !first_ip.is_empty() && !is_local_ip(first_ip) {
@@ -125,3 +126,244 @@ fn is_local_ip(ip: &str) -> bool {
         || ip.starts_with("172.30.")
         || ip.starts_with("172.31.")
 }
+
+/// The trimmed `X-Server-Id` header.
+pub fn server_id_from_headers(headers: &HeaderMap) -> Result<String, ApiError> {
+    let server_id = headers
+        .get("x-server-id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .trim()
+        .to_string();
+    if server_id.is_empty() {
+        return Err(ApiError::BadRequest(
+            "missing X-Server-Id header".to_string(),
+        ));
+    }
+    Ok(server_id)
+}
+
+/// A server row's auth state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerAuth {
+    pub auth_token_hash: Option<String>,
+    /// Owning account or organization.
+    pub owner_id: Option<Uuid>,
+    pub registered_at: Option<DateTime<Utc>>,
+    /// Soft-deleted from the dashboard (see `server_deletion`).
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl ServerAuth {
+    /// Constant-time check of a token hash; a server without a stored hash matches nothing.
+    pub fn token_matches(&self, token_hash: &str) -> bool {
+        match &self.auth_token_hash {
+            Some(stored) => validate_token_hash(token_hash, stored),
+            None => false,
+        }
+    }
+
+    /// Linked to a dashboard account or organization; only then are payloads accepted.
+    pub fn is_registered(&self) -> bool {
+        self.owner_id.is_some() && self.registered_at.is_some()
+    }
+}
+
+pub async fn load_server_auth(
+    db: &sqlx::PgPool,
+    server_id: &str,
+) -> Result<Option<ServerAuth>, sqlx::Error> {
+    let row: Option<(
+        Option<String>,
+        Option<Uuid>,
+        Option<DateTime<Utc>>,
+        Option<DateTime<Utc>>,
+    )> = sqlx::query_as(
+        r#"
+        select auth_token_hash, coalesce(owner_user_id, organization_id), registered_at,
+               deleted_at
+        from public.servers
+        where id = $1
+        "#,
+    )
+    .bind(server_id)
+    .fetch_optional(db)
+    .await?;
+    Ok(row.map(
+        |(auth_token_hash, owner_id, registered_at, deleted_at)| ServerAuth {
+            auth_token_hash,
+            owner_id,
+            registered_at,
+            deleted_at,
+        },
+    ))
+}
+
+/// Where a plugin's server stands after [`admit_plugin`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    /// Known (or just recorded) but not linked to an account yet.
+    Pending,
+    Registered {
+        owner_id: Uuid,
+    },
+    /// Deleted from the dashboard; its data is no longer accepted.
+    Deleted,
+}
+
+/// Trust-on-first-use gate shared by handshake and ingest.
+///
+/// An unknown server is recorded as pending with the presented token; a known server must
+/// present its stored token (adopting this one if none is stored yet). Only after the token
+/// checks out are `last_seen_at` and the callback address updated.
+pub async fn admit_plugin(
+    db: &sqlx::PgPool,
+    server_id: &str,
+    token_hash: &str,
+    platform: Option<&str>,
+    server_address: Option<&str>,
+) -> Result<Admission, ApiError> {
+    let server = load_server_auth(db, server_id).await.map_err(|e| {
+        tracing::error!("server registration lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let Some(server) = server else {
+        sqlx::query(
+            r#"
+            insert into public.servers
+                (id, platform, first_seen_at, last_seen_at, auth_token_hash, auth_token_first_seen_at, callback_url)
+            values
+                ($1, $2, now(), now(), $3, now(), $4)
+            on conflict (id) do update set
+                platform = coalesce(excluded.platform, servers.platform),
+                last_seen_at = now(),
+                callback_url = coalesce(excluded.callback_url, servers.callback_url)
+            "#,
+        )
+        .bind(server_id)
+        .bind(platform)
+        .bind(token_hash)
+        .bind(server_address)
+        .execute(db)
+        .await
+        .map_err(|e| {
+            tracing::error!("insert pending server failed: {:?}", e);
+            ApiError::Internal
+        })?;
+        return Ok(Admission::Pending);
+    };
+
+    // Validate the token FIRST so invalid tokens can't spoof last_seen_at.
+    if server.auth_token_hash.is_some() && !server.token_matches(token_hash) {
+        return Err(ApiError::Unauthorized);
+    }
+    request_id::record_server_id(server_id);
+    if server.deleted_at.is_some() {
+        return Ok(Admission::Deleted);
+    }
+
+    let _ = sqlx::query(
+        r#"
+        update public.servers
+        set last_seen_at = now(),
+            callback_url = coalesce($2, callback_url)
+        where id = $1
+        "#,
+    )
+    .bind(server_id)
+    .bind(server_address)
+    .execute(db)
+    .await;
+
+    // First time we see a token for an existing row: store it.
+    if server.auth_token_hash.is_none() {
+        let _ = sqlx::query(
+            r#"
+            update public.servers
+            set auth_token_hash = $2,
+                auth_token_first_seen_at = coalesce(auth_token_first_seen_at, now())
+            where id = $1
+            "#,
+        )
+        .bind(server_id)
+        .bind(token_hash)
+        .execute(db)
+        .await;
+    }
+
+    Ok(match server.owner_id {
+        Some(owner_id) if server.is_registered() => Admission::Registered { owner_id },
+        _ => Admission::Pending,
+    })
+}
+
+/// Check a plugin's X-Server-Id + per-server bearer token against the stored token hash,
+/// without requiring registration. A missing Authorization header is a bad request, unknown
+/// servers are unauthorized.
+pub async fn verify_server_token(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Result<String, ApiError> {
+    let server_id = server_id_from_headers(headers)?;
+    let token = parse_bearer_token(headers)
+        .ok_or_else(|| ApiError::BadRequest("Authorization header is required".to_string()))?;
+    if token.is_empty() {
+        return Err(ApiError::Unauthorized);
+    }
+
+    let server = load_server_auth(&state.db, &server_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("server auth lookup failed: {:?}", e);
+            ApiError::Internal
+        })?
+        .ok_or(ApiError::Unauthorized)?;
+    if !server.token_matches(&sha256_hex(&token)) {
+        return Err(ApiError::Unauthorized);
+    }
+    request_id::record_server_id(&server_id);
+    if server.deleted_at.is_some() {
+        return Err(deleted(&server_id));
+    }
+    Ok(server_id)
+}
+
+fn deleted(server_id: &str) -> ApiError {
+    ApiError::Gone(format!(
+        "server {} was deleted from the dashboard",
+        server_id
+    ))
+}
+
+/// Authenticate a plugin request (X-Server-Id + per-server bearer token) against a registered
+/// server and return the server id.
+pub async fn authenticate_server(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Result<String, ApiError> {
+    let server_id = server_id_from_headers(headers)?;
+    let token = parse_bearer_token(headers).ok_or(ApiError::Unauthorized)?;
+
+    let server = load_server_auth(&state.db, &server_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("server auth lookup failed: {:?}", e);
+            ApiError::Internal
+        })?
+        .ok_or(ApiError::Unauthorized)?;
+
+    if !server.token_matches(&sha256_hex(&token)) {
+        return Err(ApiError::Unauthorized);
+    }
+    if server.deleted_at.is_some() {
+        return Err(deleted(&server_id));
+    }
+    if !server.is_registered() {
+        return Err(ApiError::BadRequest(
+            "server not registered - please link it in the dashboard first".to_string(),
+        ));
+    }
+
+    Ok(server_id)
+}
@@ -0,0 +1,198 @@
+//! The batch metadata line.
+//!
+//! The first NDJSON line of a batch describes the batch rather than a packet. Plugins that
+//! declare `meta_version` (currently [`CURRENT_VERSION`]) send these typed fields:
+//!
+//! ```json
+//! {"meta_version":1, "plugin_version":"1.4.0", "protocol_version":767, "mc_version":"1.21",
+//!  "world":"world", "tick_rate":19.8, "player_count":42, "client_types":{...}}
+//! ```
+//!
+//! All fields are optional and other keys are kept. Ingest rejects a versioned batch whose
+//! metadata line is not a JSON object or has a field of the wrong type or out of range (HTTP 400);
+//! lines without `meta_version` come from older plugins and are read leniently (bad fields are
+//! ignored, anything but an object counts as `{}`). The fields are stored on the `batch_index`
+//! row, and the event transforms pass modules the standardized line: typed fields as validated,
+//! invalid ones removed (`raw_ndjson_gz` modules get the line as sent).
+
+use std::io::{BufRead, BufReader, Read};
+
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+
+use crate::plugin_version::Version;
+
+pub const CURRENT_VERSION: u32 = 1;
+/// Longest metadata line read; `client_types` grows with the player count.
+pub const MAX_LINE_BYTES: u64 = 1024 * 1024;
+pub const MAX_WORLD_LEN: usize = 128;
+pub const MAX_TICK_RATE: f64 = 1000.0;
+pub const MAX_PLAYER_COUNT: i64 = 100_000;
+
+/// Typed fields of a batch metadata line.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BatchMeta {
+    /// 0 for lines without `meta_version` (older plugins).
+    pub meta_version: u32,
+    pub plugin_version: Option<String>,
+    /// Minecraft protocol number of the server.
+    pub protocol_version: Option<i32>,
+    pub mc_version: Option<String>,
+    pub world: Option<String>,
+    /// Server ticks per second over the batch.
+    pub tick_rate: Option<f64>,
+    pub player_count: Option<i32>,
+}
+
+impl BatchMeta {
+    /// Read the typed fields of a metadata line. Fields that fail validation are left unset and
+    /// reported.
+    pub fn extract(meta: &Value) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+        let mut out = BatchMeta::default();
+        let Some(obj) = meta.as_object() else {
+            return (out, vec!["metadata line is not a JSON object".to_string()]);
+        };
+
+        let mut invalid = |key: &str, expected: &str| {
+            problems.push(format!("{} must be {}", key, expected));
+        };
+
+        if let Some(v) = obj.get("meta_version") {
+            match v.as_u64().and_then(|n| u32::try_from(n).ok()) {
+                Some(n) => out.meta_version = n,
+                None => invalid("meta_version", "a non-negative integer"),
+            }
+        }
+        if let Some(v) = obj.get("plugin_version") {
+            match v.as_str().filter(|s| Version::parse(s).is_some()) {
+                Some(s) => out.plugin_version = Some(s.trim().to_string()),
+                None => invalid("plugin_version", "a version string"),
+            }
+        }
+        if let Some(v) = obj.get("protocol_version") {
+            match v
+                .as_i64()
+                .filter(|n| *n > 0)
+                .and_then(|n| i32::try_from(n).ok())
+            {
+                Some(n) => out.protocol_version = Some(n),
+                None => invalid("protocol_version", "a positive integer"),
+            }
+        }
+        if let Some(v) = obj.get("mc_version") {
+            match v.as_str().filter(|s| Version::parse(s).is_some()) {
+                Some(s) => out.mc_version = Some(s.trim().to_string()),
+                None => invalid("mc_version", "a version string"),
+            }
+        }
+        if let Some(v) = obj.get("world") {
+            match v
+                .as_str()
+                .map(str::trim)
+                .filter(|s| !s.is_empty() && s.chars().count() <= MAX_WORLD_LEN)
+            {
+                Some(s) => out.world = Some(s.to_string()),
+                None => invalid("world", "a non-empty string of at most 128 characters"),
+            }
+        }
+        if let Some(v) = obj.get("tick_rate") {
+            match v
+                .as_f64()
+                .filter(|t| t.is_finite() && *t > 0.0 && *t <= MAX_TICK_RATE)
+            {
+                Some(t) => out.tick_rate = Some(t),
+                None => invalid("tick_rate", "a number above 0 and at most 1000"),
+            }
+        }
+        if let Some(v) = obj.get("player_count") {
+            match v.as_i64().filter(|n| (0..=MAX_PLAYER_COUNT).contains(n)) {
+                Some(n) => out.player_count = Some(n as i32),
+                None => invalid("player_count", "an integer from 0 to 100000"),
+            }
+        }
+
+        (out, problems)
+    }
+
+    /// Whether the line declared a `meta_version` (and is validated strictly).
+    pub fn is_versioned(&self) -> bool {
+        self.meta_version > 0
+    }
+
+    /// Write the typed fields over a metadata line's own, removing invalid ones.
+    pub fn standardize(&self, obj: &mut Map<String, Value>) {
+        fn set(obj: &mut Map<String, Value>, key: &str, value: Option<Value>) {
+            match value {
+                Some(v) => {
+                    obj.insert(key.to_string(), v);
+                }
+                None => {
+                    obj.remove(key);
+                }
+            }
+        }
+        set(
+            obj,
+            "meta_version",
+            self.is_versioned().then(|| self.meta_version.into()),
+        );
+        set(
+            obj,
+            "plugin_version",
+            self.plugin_version.clone().map(Value::String),
+        );
+        set(
+            obj,
+            "protocol_version",
+            self.protocol_version.map(Value::from),
+        );
+        set(
+            obj,
+            "mc_version",
+            self.mc_version.clone().map(Value::String),
+        );
+        set(obj, "world", self.world.clone().map(Value::String));
+        set(obj, "tick_rate", self.tick_rate.map(Value::from));
+        set(obj, "player_count", self.player_count.map(Value::from));
+    }
+}
+
+/// Validate a batch's metadata line for ingest. Versioned lines must be valid; unversioned
+/// ones keep whatever fields are valid.
+pub fn validate(line: &str) -> Result<BatchMeta, String> {
+    let value: Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(_) => return Ok(BatchMeta::default()),
+    };
+    let (meta, problems) = BatchMeta::extract(&value);
+    if meta.is_versioned() && !problems.is_empty() {
+        return Err(problems.join("; "));
+    }
+    Ok(meta)
+}
+
+/// A metadata line standardized for modules (`{}` when the line isn't an object).
+pub fn standardized(line: &str) -> Value {
+    let mut value: Value =
+        serde_json::from_str(line.trim_end()).unwrap_or(Value::Object(Default::default()));
+    let (meta, _) = BatchMeta::extract(&value);
+    match value.as_object_mut() {
+        Some(obj) => meta.standardize(obj),
+        None => value = Value::Object(Default::default()),
+    }
+    value
+}
+
+/// The first line of a gzipped NDJSON batch (at most [`MAX_LINE_BYTES`]); None when the batch
+/// is empty or can't be decoded.
+pub fn first_line(raw_gz: &[u8]) -> Option<String> {
+    let mut line = String::new();
+    BufReader::new(GzDecoder::new(raw_gz).take(MAX_LINE_BYTES))
+        .read_line(&mut line)
+        .ok()?;
+    let line = line.trim_end_matches(['\n', '\r']);
+    (!line.trim().is_empty()).then(|| line.to_string())
+}
@@ -0,0 +1,153 @@
+//! Ingest sequence tracking (anti-tamper).
+//!
+//! The plugin numbers its batches per session (1, 2, 3, ...) when it spools them and sends the
+//! number in `X-Batch-Seq`. Each batch is stored with its number and how it relates to the
+//! batches already received for the session: in order, after a gap (batches never arrived),
+//! reordered (older than one already received) or a duplicate. Gaps and duplicates mean data
+//! was lost, replayed or tampered with on the way; the dashboard status endpoint reports them
+//! as a warning. Batches without the header (older plugins, batches spooled by a previous
+//! plugin run) are not tracked.
+
+use axum::http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+pub const BATCH_SEQ_HEADER: &str = "x-batch-seq";
+
+/// How far back the dashboard summary looks.
+pub const SUMMARY_WINDOW_HOURS: i64 = 24;
+
+/// The batch number from `X-Batch-Seq` (absent for untracked batches).
+pub fn from_headers(headers: &HeaderMap) -> Result<Option<i64>, String> {
+    let Some(raw) = headers.get(BATCH_SEQ_HEADER) else {
+        return Ok(None);
+    };
+    raw.to_str()
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok())
+        .filter(|seq| *seq >= 1)
+        .map(Some)
+        .ok_or_else(|| "X-Batch-Seq must be a positive integer".to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeqStatus {
+    InOrder,
+    /// `missing` batches between the last one received and this one never arrived (yet).
+    Gap {
+        missing: i64,
+    },
+    /// Older than a batch already received; it may fill an earlier gap.
+    Reordered,
+    Duplicate,
+}
+
+impl SeqStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SeqStatus::InOrder => "ok",
+            SeqStatus::Gap { .. } => "gap",
+            SeqStatus::Reordered => "reordered",
+            SeqStatus::Duplicate => "duplicate",
+        }
+    }
+
+    pub fn missing(self) -> Option<i64> {
+        match self {
+            SeqStatus::Gap { missing } => Some(missing),
+            _ => None,
+        }
+    }
+}
+
+/// Classify a batch against the session's highest number so far and whether its own number
+/// was already received. Sessions start at 1, so a first batch numbered higher is a gap too.
+pub fn classify(seq: i64, prev_max: Option<i64>, already_received: bool) -> SeqStatus {
+    if already_received {
+        return SeqStatus::Duplicate;
+    }
+    let expected = prev_max.unwrap_or(0) + 1;
+    match seq.cmp(&expected) {
+        std::cmp::Ordering::Equal => SeqStatus::InOrder,
+        std::cmp::Ordering::Greater => SeqStatus::Gap {
+            missing: seq - expected,
+        },
+        std::cmp::Ordering::Less => SeqStatus::Reordered,
+    }
+}
+
+/// Classify a batch of a session against the ones already in `batch_index`.
+pub async fn check(
+    db: &PgPool,
+    server_id: &str,
+    session_id: &str,
+    seq: i64,
+) -> Result<SeqStatus, sqlx::Error> {
+    let (prev_max, already_received): (Option<i64>, bool) = sqlx::query_as(
+        r#"
+        select max(batch_seq), coalesce(bool_or(batch_seq = $3), false)
+        from public.batch_index
+        where server_id = $1 and session_id = $2 and batch_seq is not null
+        "#,
+    )
+    .bind(server_id)
+    .bind(session_id)
+    .bind(seq)
+    .fetch_one(db)
+    .await?;
+    Ok(classify(seq, prev_max, already_received))
+}
+
+/// Sequence anomalies in the server's recent batches.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct IngestIntegrity {
+    pub window_hours: i64,
+    /// Batches received with a sequence number.
+    pub tracked_batches: i64,
+    pub gaps: i64,
+    /// Batches skipped over by the gaps (late arrivals are not subtracted).
+    pub missing_batches: i64,
+    pub reordered: i64,
+    pub duplicates: i64,
+    /// Set when any anomaly was seen.
+    pub warning: Option<String>,
+}
+
+pub async fn summary(db: &PgPool, server_id: &str) -> Result<IngestIntegrity, sqlx::Error> {
+    let row: (i64, i64, i64, i64, i64) = sqlx::query_as(
+        r#"
+        select
+            count(*),
+            count(*) filter (where seq_status = 'gap'),
+            coalesce(sum(seq_missing), 0)::bigint,
+            count(*) filter (where seq_status = 'reordered'),
+            count(*) filter (where seq_status = 'duplicate')
+        from public.batch_index
+        where server_id = $1
+          and batch_seq is not null
+          and received_at > now() - make_interval(hours => $2::int)
+        "#,
+    )
+    .bind(server_id)
+    .bind(SUMMARY_WINDOW_HOURS as i32)
+    .fetch_one(db)
+    .await?;
+    let (tracked_batches, gaps, missing_batches, reordered, duplicates) = row;
+
+    let warning = (gaps + reordered + duplicates > 0).then(|| {
+        format!(
+            "possible data tampering or packet loss: {} gap(s) ({} batch(es) missing), {} reordered, {} duplicate batch(es) in the last {}h",
+            gaps, missing_batches, reordered, duplicates, SUMMARY_WINDOW_HOURS
+        )
+    });
+    Ok(IngestIntegrity {
+        window_hours: SUMMARY_WINDOW_HOURS,
+        tracked_batches,
+        gaps,
+        missing_batches,
+        reordered,
+        duplicates,
+        warning,
+    })
+}
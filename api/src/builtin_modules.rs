@@ -1,18 +1,29 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum BuiltinTier {
     Core,
     Advanced,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl BuiltinTier {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BuiltinTier::Core => "core",
+            BuiltinTier::Advanced => "advanced",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BuiltinModuleInfo {
     pub name: String,
     pub tier: BuiltinTier,
     pub default_port: u16,
     pub default_base_url: String,
+    pub transform: String,
     pub short_description: String,
     pub full_description: String,
     pub checks: Vec<String>,
@@ -23,9 +34,37 @@ pub struct BuiltinModuleDef {
     pub name: &'static str,
     pub tier: BuiltinTier,
     pub default_port: u16,
+    /// Transform applied by the API before dispatching to this module.
+    pub transform: &'static str,
     pub short_description: &'static str,
     pub full_description: &'static str,
-    pub checks: &'static [&'static str],
+    pub checks: &'static [BuiltinCheckDef],
+}
+
+/// Reference documentation for one check, shown next to its findings in the dashboard.
+#[derive(Debug, Clone, Copy)]
+pub struct BuiltinCheckDef {
+    /// Detector name the check reports findings under.
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Typical causes of false positives.
+    pub false_positives: &'static [&'static str],
+    /// Settings servers can override (`/dashboard/:server_id/check-thresholds/:detector_name`).
+    pub parameters: &'static [CheckParamDef],
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CheckParamDef {
+    pub name: &'static str,
+    pub default: f64,
+    pub unit: &'static str,
+    pub description: &'static str,
+}
+
+impl BuiltinModuleDef {
+    pub fn check_names(&self) -> Vec<String> {
+        self.checks.iter().map(|c| c.name.to_string()).collect()
+    }
 }
 
 pub const BUILTIN_MODULES: &[BuiltinModuleDef] = &[
@@ -33,102 +72,443 @@ pub const BUILTIN_MODULES: &[BuiltinModuleDef] = &[
         name: "Movement Core",
         tier: BuiltinTier::Core,
         default_port: 4030,
+        transform: "raw_ndjson_gz",
         short_description: "Blatant movement cheats",
         full_description:
             "Pareto tier: Catches obvious flight, blatant speed, nofall exploits, and ground spoofing with minimal false positives.",
         checks: &[
-            "movement_core_flight_ascend",
-            "movement_core_speed_blatant",
-            "movement_core_nofall_ground",
-            "movement_core_groundspoof_fall",
-            "movement_core_groundspoof_ascend",
+            BuiltinCheckDef {
+                name: "movement_core_flight_ascend",
+                description: "Flags a player who keeps gaining height without touching the ground, beyond what a vanilla jump allows (~1.25 blocks).",
+                false_positives: &[
+                    "Jump boost potions",
+                    "Slime blocks, bubble columns and elytra boosts",
+                    "Plugin knockback or launch pads",
+                    "Creative or spectator mode not reported by the plugin",
+                ],
+                parameters: &[CheckParamDef {
+                    name: "threshold",
+                    default: 1.5,
+                    unit: "blocks",
+                    description: "Height gained without ground contact before a finding",
+                }],
+            },
+            BuiltinCheckDef {
+                name: "movement_core_speed_blatant",
+                description: "Flags horizontal movement far beyond sprint-jumping speed (sprint-jumping on ice peaks around 10 blocks/s).",
+                false_positives: &[
+                    "Speed effects from plugins or high-level potions",
+                    "Riptide tridents and elytra flight",
+                    "Server-side velocity (launch pads, knockback plugins)",
+                    "Teleports not reported as such",
+                ],
+                parameters: &[CheckParamDef {
+                    name: "threshold",
+                    default: 20.0,
+                    unit: "blocks/s",
+                    description: "Horizontal speed above which movement is blatant",
+                }],
+            },
+            BuiltinCheckDef {
+                name: "movement_core_nofall_ground",
+                description: "Flags a player who claims to be on ground while falling, which cancels fall damage.",
+                false_positives: &[
+                    "Landing on blocks the server treats differently (scaffolding, powder snow)",
+                    "Lag spikes around block edges",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "movement_core_groundspoof_fall",
+                description: "Flags on_ground claims during a fall where no block can be under the player.",
+                false_positives: &[
+                    "Boats, shulkers and other entities players can stand on",
+                    "Blocks placed by other players during the fall",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "movement_core_groundspoof_ascend",
+                description: "Flags on_ground claims while the player is moving upwards.",
+                false_positives: &[
+                    "Stairs, slabs and step-up on uneven terrain",
+                    "Pistons and moving blocks",
+                ],
+                parameters: &[],
+            },
         ],
     },
     BuiltinModuleDef {
         name: "Movement Advanced",
         tier: BuiltinTier::Advanced,
         default_port: 4031,
+        transform: "raw_ndjson_gz",
         short_description: "Subtle movement analysis",
         full_description:
             "Y-prediction physics, hovering detection, sprint/sneak speed limits, timer manipulation, step height, and noslow bypass.",
         checks: &[
-            "movement_advanced_flight_ypred",
-            "movement_advanced_flight_hover",
-            "movement_advanced_speed_sprint",
-            "movement_advanced_speed_sneak",
-            "movement_advanced_timer_fast",
-            "movement_advanced_timer_slow",
-            "movement_advanced_step_height",
-            "movement_advanced_noslow_item",
+            BuiltinCheckDef {
+                name: "movement_advanced_flight_ypred",
+                description: "Compares vertical motion against vanilla gravity and drag prediction; a sustained mismatch means the client ignores physics.",
+                false_positives: &[
+                    "Levitation and slow falling effects",
+                    "Water, lava, cobwebs and climbable blocks",
+                    "Version differences handled by protocol translators (ViaVersion)",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "movement_advanced_flight_hover",
+                description: "Flags a player holding the same height in the air over several movement packets.",
+                false_positives: &[
+                    "Standing on entities or barrier-like plugin blocks",
+                    "Chunks not yet loaded on the client",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "movement_advanced_speed_sprint",
+                description: "Flags sprinting faster than the sprint speed limit for the player's current surface and effects.",
+                false_positives: &[
+                    "Speed effects and attribute modifiers from plugins",
+                    "Ice, soul speed boots and depth strider",
+                    "Knockback received right before the movement",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "movement_advanced_speed_sneak",
+                description: "Flags moving faster than allowed while sneaking.",
+                false_positives: &[
+                    "Swift sneak enchantment",
+                    "Falling or being pushed while sneaking",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "movement_advanced_timer_fast",
+                description: "Flags clients sending movement packets faster than 20 ticks per second over time (timer speed-up).",
+                false_positives: &[
+                    "Network jitter delivering buffered packets in bursts",
+                    "Client lag recovery after a freeze",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "movement_advanced_timer_slow",
+                description: "Flags clients sending movement packets persistently slower than the tick rate (timer slow-down, blink).",
+                false_positives: &[
+                    "Players with poor connections or very low FPS",
+                    "Server TPS drops reflected in packet timing",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "movement_advanced_step_height",
+                description: "Flags single-packet climbs higher than the vanilla step height without jumping.",
+                false_positives: &[
+                    "Horses and other mounts with higher step height",
+                    "Plugins that change the step height attribute",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "movement_advanced_noslow_item",
+                description: "Flags full-speed movement while using an item that slows the player (eating, blocking, drawing a bow).",
+                false_positives: &[
+                    "Item use cancelled server-side without the client knowing",
+                    "Version differences in item use handling",
+                ],
+                parameters: &[],
+            },
         ],
     },
     BuiltinModuleDef {
         name: "Combat Core",
         tier: BuiltinTier::Core,
         default_port: 4032,
+        transform: "raw_ndjson_gz",
         short_description: "High-signal combat cheats",
         full_description:
             "Pareto tier: Simple checks catching 80% of combat cheaters. High CPS, critical reach, multi-target switching, and missing arm animations.",
         checks: &[
-            "combat_core_autoclicker_cps",
-            "combat_core_reach_critical",
-            "combat_core_killaura_multi",
-            "combat_core_noswing",
+            BuiltinCheckDef {
+                name: "combat_core_autoclicker_cps",
+                description: "Flags more attacks per second than a human can click.",
+                false_positives: &[
+                    "Macros or mice with hardware double-click issues",
+                    "Multiple attack packets sent by some clients per click on old versions",
+                ],
+                parameters: &[CheckParamDef {
+                    name: "threshold",
+                    default: 20.0,
+                    unit: "clicks/s",
+                    description: "Attacks within one second before a finding",
+                }],
+            },
+            BuiltinCheckDef {
+                name: "combat_core_reach_critical",
+                description: "Flags attacks on entities far beyond survival reach (3 blocks, plus hitbox and latency allowance).",
+                false_positives: &[
+                    "High ping with fast-moving targets",
+                    "Creative mode players (longer reach)",
+                    "Entity positions lagging behind on the server",
+                ],
+                parameters: &[CheckParamDef {
+                    name: "threshold",
+                    default: 6.0,
+                    unit: "blocks",
+                    description: "Attack distance above which reach is critical",
+                }],
+            },
+            BuiltinCheckDef {
+                name: "combat_core_killaura_multi",
+                description: "Flags hitting several different entities within a time no player could turn between them.",
+                false_positives: &[
+                    "Sweeping edge hits registered as separate attacks",
+                    "Dense mob farms with entities stacked together",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_core_noswing",
+                description: "Flags attacks not preceded by an arm swing animation packet.",
+                false_positives: &[
+                    "Clients on versions with a different swing/attack order",
+                    "Packet loss dropping the swing",
+                ],
+                parameters: &[],
+            },
         ],
     },
     BuiltinModuleDef {
         name: "Combat Advanced",
         tier: BuiltinTier::Advanced,
         default_port: 4033,
+        transform: "raw_ndjson_gz",
         short_description: "Statistical combat analysis",
         full_description:
             "Statistical analysis of aim patterns, autoclicker timing distributions, GCD sensitivity checks, and subtle reach accumulation.",
         checks: &[
-            "combat_advanced_aim_headsnap",
-            "combat_advanced_aim_pitchspread",
-            "combat_advanced_aim_sensitivity",
-            "combat_advanced_aim_modulo",
-            "combat_advanced_aim_dirswitch",
-            "combat_advanced_aim_repeated_yaw",
-            "combat_advanced_autoclicker_timing",
-            "combat_advanced_autoclicker_variance",
-            "combat_advanced_autoclicker_kurtosis",
-            "combat_advanced_autoclicker_tickalign",
-            "combat_advanced_killaura_post",
-            "combat_advanced_reach_distance",
+            BuiltinCheckDef {
+                name: "combat_advanced_aim_headsnap",
+                description: "Flags instant large rotations that land exactly on a target right before an attack.",
+                false_positives: &[
+                    "Very high mouse sensitivity with flick aiming",
+                    "Rotation resets after a teleport",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_advanced_aim_pitchspread",
+                description: "Flags pitch changes with implausibly little spread while tracking targets.",
+                false_positives: &[
+                    "Fighting targets on flat ground at a fixed height",
+                    "Controller input (Bedrock players)",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_advanced_aim_sensitivity",
+                description: "Checks that rotation steps share the GCD implied by a real mouse sensitivity; aim assists break it.",
+                false_positives: &[
+                    "Cinematic camera and zoom mods",
+                    "Bedrock (Geyser) and controller players",
+                    "Mouse tweaks or raw input changes mid-fight",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_advanced_aim_modulo",
+                description: "Flags rotations whose values repeat modulo a fixed step, typical of rotation smoothing in cheats.",
+                false_positives: &["Client-side rotation snapping from mods", "Controller input"],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_advanced_aim_dirswitch",
+                description: "Flags abrupt direction switches in aim that track a target too precisely.",
+                false_positives: &["Erratic but legitimate PvP styles (jitter aiming)"],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_advanced_aim_repeated_yaw",
+                description: "Flags identical yaw deltas repeated across many rotation packets.",
+                false_positives: &[
+                    "Players turning with keyboard-bound rotation mods",
+                    "Very low mouse DPI",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_advanced_autoclicker_timing",
+                description: "Looks for click intervals that are too regular for human input.",
+                false_positives: &[
+                    "Hardware macros and programmable mice",
+                    "Drag or butterfly clicking",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_advanced_autoclicker_variance",
+                description: "Flags click interval variance too low to be human over a window of clicks.",
+                false_positives: &[
+                    "Jitter clicking sessions",
+                    "Low packet resolution on old clients",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_advanced_autoclicker_kurtosis",
+                description: "Compares the shape of the click interval distribution with human clicking; randomized autoclickers are too flat or too peaked.",
+                false_positives: &["Short fights with few clicks", "Drag clicking"],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_advanced_autoclicker_tickalign",
+                description: "Flags clicks that line up with client ticks more often than chance, typical of tick-based autoclickers.",
+                false_positives: &[
+                    "Clients that only send attacks on tick boundaries (some older versions)",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_advanced_killaura_post",
+                description: "Flags attack packets sent after the tick's movement packet, a known killaura ordering pattern.",
+                false_positives: &[
+                    "Protocol translators reordering packets",
+                    "Unusual client builds",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "combat_advanced_reach_distance",
+                description: "Accumulates attacks slightly beyond survival reach over time, catching subtle reach modifiers.",
+                false_positives: &[
+                    "Sustained high latency",
+                    "Targets moving fast toward the attacker",
+                ],
+                parameters: &[],
+            },
         ],
     },
     BuiltinModuleDef {
         name: "Player Core",
         tier: BuiltinTier::Core,
         default_port: 4034,
+        transform: "raw_ndjson_gz",
         short_description: "Obvious packet abuse",
         full_description:
             "Pareto tier: Invalid packets (pitch, NaN, slots), impossible abilities, critical fast place/break, and airborne scaffolding.",
         checks: &[
-            "player_core_badpackets_pitch",
-            "player_core_badpackets_nan",
-            "player_core_badpackets_abilities",
-            "player_core_badpackets_slot",
-            "player_core_fastplace_critical",
-            "player_core_fastbreak_critical",
-            "player_core_scaffold_airborne",
+            BuiltinCheckDef {
+                name: "player_core_badpackets_pitch",
+                description: "Flags a pitch outside the -90..90 range the vanilla client can send.",
+                false_positives: &["Broken client mods sending raw angles"],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "player_core_badpackets_nan",
+                description: "Flags NaN, infinite or out-of-world coordinates or rotations.",
+                false_positives: &[],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "player_core_badpackets_abilities",
+                description: "Flags clients claiming abilities (flying, instant build) the server never granted.",
+                false_positives: &[
+                    "Plugins granting flight without updating the player's abilities",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "player_core_badpackets_slot",
+                description: "Flags held-item changes to an invalid hotbar slot or to the slot already held.",
+                false_positives: &["Inventory plugins changing the slot server-side"],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "player_core_fastplace_critical",
+                description: "Flags block placements far faster than the vanilla place delay allows.",
+                false_positives: &[
+                    "Placing on multiple faces in one tick with some versions",
+                    "Plugins placing blocks for the player",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "player_core_fastbreak_critical",
+                description: "Flags blocks broken far faster than their hardness allows with the held tool.",
+                false_positives: &[
+                    "Efficiency and haste from plugins",
+                    "Instant-break blocks and creative mode",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "player_core_scaffold_airborne",
+                description: "Flags blocks placed below the player while they are airborne and moving in a way only scaffold cheats allow.",
+                false_positives: &["Skilled speed-bridging techniques (god bridging)"],
+                parameters: &[],
+            },
         ],
     },
     BuiltinModuleDef {
         name: "Player Advanced",
         tier: BuiltinTier::Advanced,
         default_port: 4035,
+        transform: "raw_ndjson_gz",
         short_description: "Complex interaction analysis",
         full_description:
             "Interaction angles, rapid inventory clicks, fast place/break accumulation, and sprint-while-bridging detection.",
         checks: &[
-            "player_advanced_interact_angle",
-            "player_advanced_interact_impossible",
-            "player_advanced_inventory_fast",
-            "player_advanced_fastplace",
-            "player_advanced_fastbreak",
-            "player_advanced_scaffold_sprint",
+            BuiltinCheckDef {
+                name: "player_advanced_interact_angle",
+                description: "Flags interactions with blocks or entities the player is not looking at.",
+                false_positives: &[
+                    "High latency while turning",
+                    "Interactions triggered by plugins",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "player_advanced_interact_impossible",
+                description: "Flags interactions through walls or with targets out of reach.",
+                false_positives: &[
+                    "Blocks changed server-side but not yet on the client",
+                    "Doors and trapdoors mid-animation",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "player_advanced_inventory_fast",
+                description: "Flags inventory clicks faster than human input, typical of chest stealers and auto-armor.",
+                false_positives: &["Inventory sorting mods", "Shift-click batching by the client"],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "player_advanced_fastplace",
+                description: "Accumulates slightly-too-fast block placements over time.",
+                false_positives: &["Lag bursts delivering placement packets together"],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "player_advanced_fastbreak",
+                description: "Accumulates slightly-too-fast block breaking over time.",
+                false_positives: &[
+                    "Haste and efficiency variations not reported by the plugin",
+                    "Lag bursts",
+                ],
+                parameters: &[],
+            },
+            BuiltinCheckDef {
+                name: "player_advanced_scaffold_sprint",
+                description: "Flags sprinting while bridging backwards, which vanilla movement doesn't allow.",
+                false_positives: &[
+                    "Plugins forcing sprint state",
+                    "Brief sprint state desyncs after knockback",
+                ],
+                parameters: &[],
+            },
         ],
     },
 ];
@@ -145,9 +525,10 @@ pub fn builtin_modules_info() -> Vec<BuiltinModuleInfo> {
             tier: m.tier,
             default_port: m.default_port,
             default_base_url: default_base_url(m.default_port),
+            transform: m.transform.to_string(),
             short_description: m.short_description.to_string(),
             full_description: m.full_description.to_string(),
-            checks: m.checks.iter().map(|c| (*c).to_string()).collect(),
+            checks: m.check_names(),
         })
         .collect()
 }
@@ -155,3 +536,15 @@ pub fn builtin_modules_info() -> Vec<BuiltinModuleInfo> {
 pub fn builtin_by_name(name: &str) -> Option<&'static BuiltinModuleDef> {
     BUILTIN_MODULES.iter().find(|m| m.name == name)
 }
+
+/// The built-in check named `check_name` and the module that runs it.
+pub fn check_by_name(
+    check_name: &str,
+) -> Option<(&'static BuiltinModuleDef, &'static BuiltinCheckDef)> {
+    BUILTIN_MODULES.iter().find_map(|m| {
+        m.checks
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(check_name.trim()))
+            .map(|c| (m, c))
+    })
+}
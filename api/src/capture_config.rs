@@ -0,0 +1,190 @@
+//! Capture settings managed from the dashboard and fetched by the plugin (`GET /config`).
+//!
+//! Field names mirror the plugin's `config.yml` (`capture.enabled_packets`,
+//! `spool.flush_interval_ms`, ...) so a fetched config can override the local one key for key.
+//! Saving a config also queues a `config_sync` pending action carrying it, so plugins pick up
+//! changes on their next heartbeat without polling. Watchlisted players are still captured in
+//! full regardless of sampling (see `watchlist`).
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+use crate::pending_actions;
+
+pub const SYNC_ACTION: &str = "config_sync";
+
+const MAX_PACKET_TYPES: usize = 200;
+const MIN_FLUSH_INTERVAL_MS: u32 = 100;
+const MAX_FLUSH_INTERVAL_MS: u32 = 60_000;
+const MIN_BATCH_BYTES: u64 = 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct CaptureConfig {
+    /// Packet types to capture; empty captures everything not disabled.
+    pub enabled_packets: Vec<String>,
+    pub disabled_packets: Vec<String>,
+    /// Fraction of packets kept (0.0-1.0).
+    pub sample_rate: f64,
+    /// Per packet type overrides of `sample_rate`.
+    pub sample_rates: BTreeMap<String, f64>,
+    /// How often the plugin flushes and uploads a batch.
+    pub flush_interval_ms: u32,
+    /// Largest compressed batch the plugin should upload (at most the API's `MAX_BODY_BYTES`).
+    pub max_batch_bytes: u64,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled_packets: Vec::new(),
+            disabled_packets: Vec::new(),
+            sample_rate: 1.0,
+            sample_rates: BTreeMap::new(),
+            flush_interval_ms: 1_000,
+            max_batch_bytes: 0,
+        }
+    }
+}
+
+fn packet_type(raw: &str) -> Result<String, String> {
+    let name = raw.trim().to_ascii_uppercase();
+    if name.is_empty()
+        || name.len() > 64
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(format!("invalid packet type: {:?}", raw));
+    }
+    Ok(name)
+}
+
+fn packet_types(raw: &[String]) -> Result<Vec<String>, String> {
+    if raw.len() > MAX_PACKET_TYPES {
+        return Err(format!("at most {} packet types", MAX_PACKET_TYPES));
+    }
+    let mut out = raw
+        .iter()
+        .map(|p| packet_type(p))
+        .collect::<Result<Vec<_>, _>>()?;
+    out.sort();
+    out.dedup();
+    Ok(out)
+}
+
+fn rate(name: &str, value: f64) -> Result<f64, String> {
+    if !(0.0..=1.0).contains(&value) {
+        return Err(format!("{} must be between 0 and 1", name));
+    }
+    Ok(value)
+}
+
+impl CaptureConfig {
+    /// Defaults for a server without a saved config.
+    pub fn default_for(max_body_bytes: usize) -> Self {
+        Self {
+            max_batch_bytes: max_body_bytes as u64,
+            ..Self::default()
+        }
+    }
+
+    /// Normalize packet names (upper case, sorted, distinct) and check every value's range.
+    /// A missing `max_batch_bytes` (0) means the API maximum.
+    pub fn validate(self, max_body_bytes: usize) -> Result<Self, String> {
+        let max_body_bytes = max_body_bytes as u64;
+        let sample_rates = self
+            .sample_rates
+            .iter()
+            .map(|(name, value)| Ok((packet_type(name)?, rate("sample_rates", *value)?)))
+            .collect::<Result<BTreeMap<_, _>, String>>()?;
+        if sample_rates.len() > MAX_PACKET_TYPES {
+            return Err(format!(
+                "at most {} sample rate overrides",
+                MAX_PACKET_TYPES
+            ));
+        }
+        if !(MIN_FLUSH_INTERVAL_MS..=MAX_FLUSH_INTERVAL_MS).contains(&self.flush_interval_ms) {
+            return Err(format!(
+                "flush_interval_ms must be between {} and {}",
+                MIN_FLUSH_INTERVAL_MS, MAX_FLUSH_INTERVAL_MS
+            ));
+        }
+        let max_batch_bytes = match self.max_batch_bytes {
+            0 => max_body_bytes,
+            n if (MIN_BATCH_BYTES..=max_body_bytes).contains(&n) => n,
+            _ => {
+                return Err(format!(
+                    "max_batch_bytes must be between {} and {}",
+                    MIN_BATCH_BYTES, max_body_bytes
+                ))
+            }
+        };
+
+        Ok(Self {
+            enabled_packets: packet_types(&self.enabled_packets)?,
+            disabled_packets: packet_types(&self.disabled_packets)?,
+            sample_rate: rate("sample_rate", self.sample_rate)?,
+            sample_rates,
+            flush_interval_ms: self.flush_interval_ms,
+            max_batch_bytes,
+        })
+    }
+}
+
+/// The server's saved config (or the defaults) and when it was last changed.
+pub async fn load(
+    db: &PgPool,
+    server_id: &str,
+    max_body_bytes: usize,
+) -> Result<(CaptureConfig, Option<DateTime<Utc>>), sqlx::Error> {
+    let row: Option<(sqlx::types::Json<CaptureConfig>, DateTime<Utc>)> = sqlx::query_as(
+        "select config, updated_at from public.server_capture_config where server_id = $1",
+    )
+    .bind(server_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(match row {
+        Some((config, updated_at)) => {
+            let mut config = config.0;
+            // MAX_BODY_BYTES may have been lowered since the config was saved.
+            if config.max_batch_bytes == 0 || config.max_batch_bytes > max_body_bytes as u64 {
+                config.max_batch_bytes = max_body_bytes as u64;
+            }
+            (config, Some(updated_at))
+        }
+        None => (CaptureConfig::default_for(max_body_bytes), None),
+    })
+}
+
+/// Save a validated config and queue it for the plugin.
+pub async fn save(
+    db: &PgPool,
+    server_id: &str,
+    config: &CaptureConfig,
+    updated_by: Option<&str>,
+) -> Result<DateTime<Utc>, sqlx::Error> {
+    let updated_at: DateTime<Utc> = sqlx::query_scalar(
+        r#"
+        insert into public.server_capture_config (server_id, config, updated_at, updated_by)
+        values ($1, $2, now(), $3)
+        on conflict (server_id) do update set
+            config = excluded.config,
+            updated_at = excluded.updated_at,
+            updated_by = excluded.updated_by
+        returning updated_at
+        "#,
+    )
+    .bind(server_id)
+    .bind(sqlx::types::Json(config))
+    .bind(updated_by)
+    .fetch_one(db)
+    .await?;
+
+    let payload = serde_json::json!({ "config": config, "updated_at": updated_at });
+    pending_actions::enqueue(db, server_id, SYNC_ACTION, &payload, true).await?;
+    Ok(updated_at)
+}
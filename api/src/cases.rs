@@ -0,0 +1,166 @@
+//! Moderation cases (`player_cases`).
+//!
+//! A moderator opens a case for a player on a server (at most one open case per player), attaches
+//! the findings, observations and evidence bundles it rests on (`case_items`), records the actions
+//! taken (`case_actions`: warnings, kicks, bans, ...) and closes it with an [`OUTCOMES`] value.
+//! Closing a case as `confirmed_cheater` confirms its still-open attached findings, which feeds
+//! `detector_metrics` like a confirmed observation does.
+//!
+//! Attached rows belong to the case's server (and, for findings and observations, to its
+//! player); deleting one removes it from the case. Player detail (`GET
+//! /dashboard/:server_id/players/:player_uuid`) lists the player's cases.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::detector_metrics;
+
+pub const OPEN: &str = "open";
+pub const CLOSED: &str = "closed";
+pub const CONFIRMED_CHEATER: &str = "confirmed_cheater";
+pub const OUTCOMES: [&str; 3] = [CONFIRMED_CHEATER, "cleared", "insufficient_evidence"];
+pub const ACTIONS: [&str; 7] = [
+    "warning", "kick", "mute", "temp_ban", "ban", "unban", "other",
+];
+pub const MAX_TITLE_CHARS: usize = 200;
+
+/// What a case item points at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Finding,
+    Observation,
+    EvidenceBundle,
+}
+
+impl ItemKind {
+    /// `case_items` column holding the reference.
+    pub fn column(self) -> &'static str {
+        match self {
+            ItemKind::Finding => "finding_id",
+            ItemKind::Observation => "observation_id",
+            ItemKind::EvidenceBundle => "evidence_bundle_id",
+        }
+    }
+}
+
+/// Lower-cased `value` if it is one of `allowed`.
+pub fn validate_choice(field: &str, value: &str, allowed: &[&str]) -> Result<String, String> {
+    let value = value.trim().to_ascii_lowercase();
+    if allowed.contains(&value.as_str()) {
+        Ok(value)
+    } else {
+        Err(format!(
+            "invalid {}: {:?} (expected one of {})",
+            field,
+            value,
+            allowed.join(", ")
+        ))
+    }
+}
+
+/// Trimmed, non-empty title of at most [`MAX_TITLE_CHARS`].
+pub fn validate_title(title: &str) -> Result<String, String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("title must not be empty".to_string());
+    }
+    if title.chars().count() > MAX_TITLE_CHARS {
+        return Err(format!("title longer than {} characters", MAX_TITLE_CHARS));
+    }
+    Ok(title.to_string())
+}
+
+/// A case as listed on player detail.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CaseSummary {
+    pub id: Uuid,
+    pub title: String,
+    /// open | closed
+    pub status: String,
+    pub outcome: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+/// The player's cases on a server, open ones first, then newest first.
+pub async fn player_cases(
+    db: &PgPool,
+    server_id: &str,
+    player_uuid: Uuid,
+) -> Result<Vec<CaseSummary>, sqlx::Error> {
+    let rows: Vec<(
+        Uuid,
+        String,
+        String,
+        Option<String>,
+        DateTime<Utc>,
+        Option<DateTime<Utc>>,
+    )> = sqlx::query_as(
+        r#"
+        select id, title, status, outcome, created_at, closed_at
+        from public.player_cases
+        where server_id = $1 and player_uuid = $2
+        order by status = 'open' desc, created_at desc
+        limit 20
+        "#,
+    )
+    .bind(server_id)
+    .bind(player_uuid)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(id, title, status, outcome, created_at, closed_at)| CaseSummary {
+                id,
+                title,
+                status,
+                outcome,
+                created_at,
+                closed_at,
+            },
+        )
+        .collect())
+}
+
+/// Confirm the still-open findings attached to a case (best-effort).
+pub async fn confirm_case_findings(db: &PgPool, server_id: &str, case_id: Uuid) {
+    let ids: Vec<Uuid> = match sqlx::query_scalar(
+        r#"
+        select f.id
+        from public.case_items i
+        join public.findings f on f.id = i.finding_id
+        where i.case_id = $1 and f.server_id = $2 and f.status = 'open'
+        "#,
+    )
+    .bind(case_id)
+    .bind(server_id)
+    .fetch_all(db)
+    .await
+    {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::warn!(case_id = %case_id, "case findings lookup failed: {:?}", e);
+            return;
+        }
+    };
+
+    let reviewer = format!("case:{}", case_id);
+    for finding_id in ids {
+        if let Err(e) = detector_metrics::set_finding_status(
+            db,
+            server_id,
+            finding_id,
+            "confirmed",
+            Some(&reviewer),
+        )
+        .await
+        {
+            tracing::warn!(finding_id = %finding_id, "confirming case finding failed: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Fault injection for exercising the retry and recovery paths (`chaos` build feature).
+//!
+//! With the feature, `PUT /admin/chaos` installs fault rules: each makes a pipeline stage fail
+//! (after an optional delay) with some probability, optionally only for one server or module and
+//! for a limited number of times. Without the feature the admin routes don't exist and
+//! [`Faults::inject`] is a no-op, so production builds carry no way to turn faults on.
+//!
+//! Injected failures take the same path as real ones (logged, answered 500, recorded as a failed
+//! dispatch), which is what integration tests and staging want to see handled.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Rules installed at once, at most.
+pub const MAX_RULES: usize = 32;
+pub const MAX_DELAY_MS: u64 = 120_000;
+
+/// Where a fault is injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultPoint {
+    /// Object store upload of an ingested batch (target: server id).
+    S3Put,
+    /// Module dispatch, failing like a timeout after `delay_ms` (target: module name).
+    ModuleDispatch,
+    /// `batch_index` insert of an ingested batch (target: server id).
+    DbBatchIndex,
+    /// Storing a module's findings callback (target: server id).
+    DbFindings,
+}
+
+impl FaultPoint {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FaultPoint::S3Put => "s3_put",
+            FaultPoint::ModuleDispatch => "module_dispatch",
+            FaultPoint::DbBatchIndex => "db_batch_index",
+            FaultPoint::DbFindings => "db_findings",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FaultRule {
+    pub point: FaultPoint,
+    /// Only operations for this server id (or module name, for `module_dispatch`).
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Chance each matching operation fails, 0-1 (default 1).
+    #[serde(default = "always")]
+    pub probability: f64,
+    /// Fail this many more times, then the rule is dropped (default: until cleared).
+    #[serde(default)]
+    pub remaining: Option<u32>,
+    /// Wait this long before failing (max 120000).
+    #[serde(default)]
+    pub delay_ms: u64,
+    /// Times the rule fired.
+    #[serde(default)]
+    pub fired: u64,
+}
+
+fn always() -> f64 {
+    1.0
+}
+
+impl FaultRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.probability) {
+            return Err(format!(
+                "probability of the {} rule must be between 0 and 1",
+                self.point.as_str()
+            ));
+        }
+        if self.delay_ms > MAX_DELAY_MS {
+            return Err(format!("delay_ms must be at most {}", MAX_DELAY_MS));
+        }
+        Ok(())
+    }
+
+    fn matches(&self, point: FaultPoint, target: &str) -> bool {
+        self.point == point
+            && self.remaining != Some(0)
+            && self.target.as_deref().is_none_or(|t| t == target)
+    }
+}
+
+/// A fault that fired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InjectedFault {
+    pub point: FaultPoint,
+    pub delay_ms: u64,
+}
+
+impl fmt::Display for InjectedFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.point {
+            FaultPoint::ModuleDispatch => write!(f, "operation timed out (injected fault)"),
+            point => write!(f, "injected {} fault", point.as_str()),
+        }
+    }
+}
+
+impl std::error::Error for InjectedFault {}
+
+impl From<InjectedFault> for sqlx::Error {
+    fn from(fault: InjectedFault) -> Self {
+        sqlx::Error::Protocol(fault.to_string())
+    }
+}
+
+/// The installed fault rules.
+#[derive(Debug, Clone, Default)]
+pub struct Faults(Arc<Mutex<Vec<FaultRule>>>);
+
+impl Faults {
+    pub fn list(&self) -> Vec<FaultRule> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the rules (fire counts start over).
+    pub fn set(&self, mut rules: Vec<FaultRule>) {
+        for rule in &mut rules {
+            rule.fired = 0;
+        }
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = rules;
+    }
+
+    /// The fault to inject for an operation at `point` on `target`, given a uniform `roll` in
+    /// [0, 1). The first matching rule decides; a firing counts against its `remaining`.
+    pub fn check_at(&self, point: FaultPoint, target: &str, roll: f64) -> Option<InjectedFault> {
+        let mut rules = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let index = rules.iter().position(|r| r.matches(point, target))?;
+        let rule = &mut rules[index];
+        if roll >= rule.probability {
+            return None;
+        }
+        rule.fired += 1;
+        let fault = InjectedFault {
+            point,
+            delay_ms: rule.delay_ms,
+        };
+        if let Some(remaining) = rule.remaining.as_mut() {
+            *remaining -= 1;
+            if *remaining == 0 {
+                rules.remove(index);
+            }
+        }
+        Some(fault)
+    }
+
+    /// Fail the operation at `point` on `target` if a rule says so (after the rule's delay).
+    /// Always `Ok` without the `chaos` feature.
+    pub async fn inject(&self, point: FaultPoint, target: &str) -> Result<(), InjectedFault> {
+        if !cfg!(feature = "chaos") {
+            return Ok(());
+        }
+        let roll = (Uuid::new_v4().as_u64_pair().0 >> 11) as f64 / (1u64 << 53) as f64;
+        let Some(fault) = self.check_at(point, target, roll) else {
+            return Ok(());
+        };
+        if fault.delay_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(fault.delay_ms)).await;
+        }
+        tracing::warn!(point = point.as_str(), target = target, "injecting fault");
+        Err(fault)
+    }
+}
@@ -0,0 +1,492 @@
+//! In-process detection engine for the Core tier.
+//!
+//! The Core-tier checks are simple threshold checks over transformed events. Running them
+//! inside the API removes the HTTP hop to the localhost modules for the common case.
+//!
+//! A `server_modules` row opts in by using an `inproc://` base URL:
+//!
+//! - `inproc://movement_core`: blatant speed and flight (on `movement_events_v1`)
+//! - `inproc://combat_core`: critical CPS and reach (on `ncp_fight_v1`)
+//! - `inproc://player_core`: bad packets (on raw packets)
+//!
+//! Detector names match the checks advertised in `builtin_modules`, so findings look the
+//! same as the ones reported by the HTTP modules.
+//!
+//! Thresholds can be overridden per server and check in `detector_configs.config_json`
+//! (`{"threshold": 24.0, "bedrock_threshold": 30.0}`). The Bedrock value applies to events
+//! whose `client_type` is `bedrock`: Geyser translates Bedrock movement, which trips Java
+//! thresholds, so servers with Geyser usually need them looser.
+//!
+//! Behind ViaVersion, players join with other protocol versions than the server's, and 1.8
+//! clients (no attack cooldown) click far faster than 1.9+ ones. Events carry the player's
+//! `client_protocol`, and `protocol_thresholds` (`[{"max_protocol": 47, "threshold": 28.0}]`)
+//! sets thresholds per version range: the entry with the lowest `max_protocol` at or above the
+//! player's protocol applies. Without one, clients up to [`LEGACY_COMBAT_MAX_PROTOCOL`] get the
+//! built-in legacy value of the check, if it has one ([`LEGACY_DEFAULTS`]).
+
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::ndjson::RecordParser;
+use crate::routes::callbacks::FindingIn;
+use crate::transforms::{self, ClientType};
+
+pub const INPROC_SCHEME: &str = "inproc://";
+
+const DETECTOR_VERSION: &str = "inproc-1";
+
+// Movement Core thresholds.
+/// Sprint-jumping on ice peaks around 10 b/s; anything this fast is blatant.
+const SPEED_BLATANT_BPS: f64 = 20.0;
+/// Ignore tiny time deltas (packet bursts make speeds meaningless).
+const SPEED_MIN_DT_MS: f64 = 40.0;
+/// Larger jumps are almost always teleports.
+const SPEED_MAX_STEP_BLOCKS: f64 = 8.0;
+/// A vanilla jump gains ~1.25 blocks; more without touching ground is flight.
+const FLIGHT_MAX_ASCEND_BLOCKS: f64 = 1.5;
+
+// Combat Core thresholds.
+const CPS_CRITICAL: f64 = 20.0;
+/// 1.8 clients have no attack cooldown; jitter and butterfly clicking reach the low 20s.
+const CPS_CRITICAL_LEGACY: f64 = 26.0;
+const REACH_CRITICAL_BLOCKS: f64 = 6.0;
+
+// Player Core thresholds.
+const MAX_ABS_PITCH: f64 = 90.0;
+/// Minecraft world border limit.
+const MAX_ABS_COORD: f64 = 30_000_000.0;
+
+/// Return the engine name if the base URL targets the in-process engine.
+pub fn inproc_engine(base_url: &str) -> Option<&str> {
+    base_url
+        .trim()
+        .strip_prefix(INPROC_SCHEME)
+        .map(|s| s.trim_end_matches('/'))
+}
+
+/// Whether the engine name is one this build implements.
+pub fn is_known_engine(engine: &str) -> bool {
+    matches!(engine, "movement_core" | "combat_core" | "player_core")
+}
+
+/// Detectors whose threshold can be overridden, with their built-in value.
+pub const TUNABLE_CHECKS: [(&str, f64); 4] = [
+    ("movement_core_speed_blatant", SPEED_BLATANT_BPS),
+    ("movement_core_flight_ascend", FLIGHT_MAX_ASCEND_BLOCKS),
+    ("combat_core_autoclicker_cps", CPS_CRITICAL),
+    ("combat_core_reach_critical", REACH_CRITICAL_BLOCKS),
+];
+
+/// Highest protocol version with 1.8 combat (1.8.x is 47, 1.9 is 107).
+pub const LEGACY_COMBAT_MAX_PROTOCOL: i32 = 47;
+
+/// Built-in thresholds for clients up to [`LEGACY_COMBAT_MAX_PROTOCOL`], where they differ.
+pub const LEGACY_DEFAULTS: [(&str, f64); 1] =
+    [("combat_core_autoclicker_cps", CPS_CRITICAL_LEGACY)];
+
+/// Version ranges per check, at most.
+pub const MAX_PROTOCOL_THRESHOLDS: usize = 8;
+
+/// A threshold for clients up to a protocol version.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ProtocolThreshold {
+    /// Applies to clients with this protocol version or lower (47 = 1.8).
+    pub max_protocol: i32,
+    pub threshold: f64,
+}
+
+/// A check's threshold overrides, as stored in `detector_configs.config_json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ThresholdOverride {
+    /// Replaces the built-in threshold.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    /// Threshold for Bedrock (Geyser) players; falls back to `threshold`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bedrock_threshold: Option<f64>,
+    /// Thresholds per client protocol version (ViaVersion); take precedence over `threshold`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub protocol_thresholds: Vec<ProtocolThreshold>,
+}
+
+impl ThresholdOverride {
+    pub fn validate(mut self) -> Result<Self, String> {
+        for (name, value) in [
+            ("threshold", self.threshold),
+            ("bedrock_threshold", self.bedrock_threshold),
+        ] {
+            if value.is_some_and(|v| !v.is_finite() || v <= 0.0) {
+                return Err(format!("{} must be a positive number", name));
+            }
+        }
+        if self.protocol_thresholds.len() > MAX_PROTOCOL_THRESHOLDS {
+            return Err(format!(
+                "at most {} protocol_thresholds",
+                MAX_PROTOCOL_THRESHOLDS
+            ));
+        }
+        for p in &self.protocol_thresholds {
+            if p.max_protocol <= 0 {
+                return Err("max_protocol must be a positive protocol version".to_string());
+            }
+            if !p.threshold.is_finite() || p.threshold <= 0.0 {
+                return Err(format!(
+                    "threshold for max_protocol {} must be a positive number",
+                    p.max_protocol
+                ));
+            }
+        }
+        self.protocol_thresholds.sort_by_key(|p| p.max_protocol);
+        if self
+            .protocol_thresholds
+            .windows(2)
+            .any(|w| w[0].max_protocol == w[1].max_protocol)
+        {
+            return Err("max_protocol values must be distinct".to_string());
+        }
+        Ok(self)
+    }
+
+    /// The threshold for a client of this protocol version, if a range covers it.
+    fn for_protocol(&self, protocol: i32) -> Option<f64> {
+        self.protocol_thresholds
+            .iter()
+            .filter(|p| protocol <= p.max_protocol)
+            .min_by_key(|p| p.max_protocol)
+            .map(|p| p.threshold)
+    }
+}
+
+/// The built-in threshold of a check for a client of this protocol version.
+pub fn builtin_threshold(detector_name: &str, default: f64, protocol: Option<i32>) -> f64 {
+    if protocol.is_some_and(|p| p <= LEGACY_COMBAT_MAX_PROTOCOL) {
+        if let Some((_, legacy)) = LEGACY_DEFAULTS.iter().find(|(n, _)| *n == detector_name) {
+            return *legacy;
+        }
+    }
+    default
+}
+
+/// A server's threshold overrides, keyed by detector name.
+#[derive(Debug, Clone, Default)]
+pub struct CheckConfig {
+    overrides: HashMap<String, ThresholdOverride>,
+}
+
+impl CheckConfig {
+    pub fn with_override(mut self, detector_name: &str, value: ThresholdOverride) -> Self {
+        self.overrides.insert(detector_name.to_string(), value);
+        self
+    }
+
+    /// The threshold a check applies to a player of the given client type and protocol
+    /// version: the Bedrock override, the override for the protocol, the plain override, then
+    /// the built-in value for the protocol.
+    pub fn threshold(
+        &self,
+        detector_name: &str,
+        default: f64,
+        client: Option<ClientType>,
+        protocol: Option<i32>,
+    ) -> f64 {
+        let builtin = builtin_threshold(detector_name, default, protocol);
+        let Some(o) = self.overrides.get(detector_name) else {
+            return builtin;
+        };
+        let bedrock = (client == Some(ClientType::Bedrock))
+            .then_some(o.bedrock_threshold)
+            .flatten();
+        bedrock
+            .or_else(|| protocol.and_then(|p| o.for_protocol(p)))
+            .or(o.threshold)
+            .unwrap_or(builtin)
+    }
+
+    /// Overrides saved for the server's enabled detectors.
+    pub async fn load(db: &PgPool, server_id: &str) -> Result<Self, sqlx::Error> {
+        let rows: Vec<(String, sqlx::types::Json<ThresholdOverride>)> = sqlx::query_as(
+            r#"
+            select detector_name, config_json
+            from public.detector_configs
+            where server_id = $1 and enabled = true
+            "#,
+        )
+        .bind(server_id)
+        .fetch_all(db)
+        .await?;
+        Ok(Self {
+            overrides: rows.into_iter().map(|(name, o)| (name, o.0)).collect(),
+        })
+    }
+}
+
+/// Run an in-process engine against a raw gzipped NDJSON batch with the built-in thresholds.
+pub fn run(engine: &str, raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<FindingIn>> {
+    run_with(engine, raw_gz_ndjson, &CheckConfig::default())
+}
+
+/// Run an in-process engine with a server's threshold overrides.
+pub fn run_with(
+    engine: &str,
+    raw_gz_ndjson: &[u8],
+    config: &CheckConfig,
+) -> anyhow::Result<Vec<FindingIn>> {
+    match engine {
+        "movement_core" => {
+            let events =
+                transforms::apply_transform("movement_events_v1_ndjson_gz", raw_gz_ndjson)?;
+            movement_core(&events, config)
+        }
+        "combat_core" => {
+            let events = transforms::apply_transform("ncp_fight_v1_ndjson_gz", raw_gz_ndjson)?;
+            combat_core(&events, config)
+        }
+        "player_core" => player_core(raw_gz_ndjson),
+        other => anyhow::bail!("unknown in-process engine: {}", other),
+    }
+}
+
+/// Iterate over the event lines of a gzipped NDJSON stream, skipping the metadata line.
+fn for_each_event(gz: &[u8], mut f: impl FnMut(&Value)) -> anyhow::Result<()> {
+    let reader = BufReader::new(GzDecoder::new(gz));
+    let mut parser = RecordParser::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if i == 0 || line.trim().is_empty() {
+            continue;
+        }
+        if let Some(v) = parser.parse(&line) {
+            f(&v);
+        }
+    }
+    Ok(())
+}
+
+fn event_uuid(v: &Value) -> Option<Uuid> {
+    v.get("uuid")
+        .and_then(|x| x.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok())
+}
+
+fn event_client_type(v: &Value) -> Option<ClientType> {
+    v.get("client_type")
+        .and_then(|x| x.as_str())
+        .and_then(ClientType::parse)
+}
+
+fn event_client_protocol(v: &Value) -> Option<i32> {
+    v.get("client_protocol")
+        .and_then(|x| x.as_i64())
+        .and_then(|p| i32::try_from(p).ok())
+}
+
+fn finding(
+    player_uuid: Uuid,
+    detector_name: &str,
+    severity: &str,
+    title: &str,
+    evidence: Value,
+) -> FindingIn {
+    FindingIn {
+        player_uuid: Some(player_uuid),
+        detector_name: detector_name.to_string(),
+        detector_version: Some(DETECTOR_VERSION.to_string()),
+        severity: Some(severity.to_string()),
+        title: title.to_string(),
+        description: None,
+        evidence_s3_key: None,
+        evidence_json: Some(evidence),
+        confidence: None,
+    }
+}
+
+fn movement_core(events_gz: &[u8], config: &CheckConfig) -> anyhow::Result<Vec<FindingIn>> {
+    let mut findings = Vec::new();
+    // Height gained since the player last touched the ground.
+    let mut ascend: HashMap<Uuid, f64> = HashMap::new();
+
+    for_each_event(events_gz, |v| {
+        let Some(uuid) = event_uuid(v) else { return };
+        let on_ground = v.get("on_ground").and_then(|x| x.as_bool());
+        let dt_ms = v.get("dt_ms").and_then(|x| x.as_f64());
+        let dx = v.get("dx").and_then(|x| x.as_f64()).unwrap_or(0.0);
+        let dy = v.get("dy").and_then(|x| x.as_f64()).unwrap_or(0.0);
+        let dz = v.get("dz").and_then(|x| x.as_f64()).unwrap_or(0.0);
+        let speed = v.get("speed_bps").and_then(|x| x.as_f64());
+        let flight_allowed = v.get("flight_allowed").and_then(|x| x.as_bool()) == Some(true);
+        let world = v.get("world");
+        let client = event_client_type(v);
+        let protocol = event_client_protocol(v);
+
+        if let (Some(dt_ms), Some(speed)) = (dt_ms, speed) {
+            let step = (dx * dx + dz * dz).sqrt();
+            let max_speed = config.threshold(
+                "movement_core_speed_blatant",
+                SPEED_BLATANT_BPS,
+                client,
+                protocol,
+            );
+            if dt_ms >= SPEED_MIN_DT_MS && step <= SPEED_MAX_STEP_BLOCKS && speed > max_speed {
+                findings.push(finding(
+                    uuid,
+                    "movement_core_speed_blatant",
+                    "high",
+                    "Blatant speed",
+                    json!({ "speed_bps": speed, "dt_ms": dt_ms, "ts": v.get("ts"), "world": world, "client_type": client.map(ClientType::as_str) }),
+                ));
+            }
+        }
+
+        // Creative/spectator and `/fly` players may fly legitimately.
+        if on_ground == Some(true) || flight_allowed {
+            ascend.remove(&uuid);
+        } else if dt_ms.is_some() && dy > 0.0 {
+            let total = ascend.entry(uuid).or_insert(0.0);
+            *total += dy;
+            let max_ascend = config.threshold(
+                "movement_core_flight_ascend",
+                FLIGHT_MAX_ASCEND_BLOCKS,
+                client,
+                protocol,
+            );
+            if *total > max_ascend {
+                findings.push(finding(
+                    uuid,
+                    "movement_core_flight_ascend",
+                    "high",
+                    "Ascending without ground contact",
+                    json!({ "ascended_blocks": *total, "ts": v.get("ts"), "world": world, "client_type": client.map(ClientType::as_str) }),
+                ));
+                // Re-arm so a long flight produces one finding per extra jump height.
+                *total = 0.0;
+            }
+        }
+    })?;
+
+    Ok(findings)
+}
+
+fn combat_core(events_gz: &[u8], config: &CheckConfig) -> anyhow::Result<Vec<FindingIn>> {
+    let mut findings = Vec::new();
+    // Attack timestamps within the last second, per player.
+    let mut window: HashMap<Uuid, VecDeque<u64>> = HashMap::new();
+
+    for_each_event(events_gz, |v| {
+        let Some(uuid) = event_uuid(v) else { return };
+        let Some(ts) = v.get("ts").and_then(|x| x.as_u64()) else {
+            return;
+        };
+        let client = event_client_type(v);
+        let protocol = event_client_protocol(v);
+
+        let attacks = window.entry(uuid).or_default();
+        attacks.push_back(ts);
+        while attacks
+            .front()
+            .is_some_and(|t| ts.saturating_sub(*t) >= 1000)
+        {
+            attacks.pop_front();
+        }
+        if attacks.len() as f64
+            > config.threshold(
+                "combat_core_autoclicker_cps",
+                CPS_CRITICAL,
+                client,
+                protocol,
+            )
+        {
+            findings.push(finding(
+                uuid,
+                "combat_core_autoclicker_cps",
+                "high",
+                "Critical attack rate",
+                json!({ "cps": attacks.len(), "ts": ts, "client_protocol": protocol }),
+            ));
+            attacks.clear();
+        }
+
+        // To the hitbox when the transform measured it (older events only have the feet).
+        let reach = v
+            .get("reach_hitbox")
+            .or_else(|| v.get("reach_distance"))
+            .and_then(|x| x.as_f64());
+        // Only flag what the target's interpolated position can't explain.
+        let uncertainty = v
+            .get("reach_uncertainty")
+            .and_then(|x| x.as_f64())
+            .unwrap_or(0.0);
+        if let Some(reach) = reach {
+            if reach - uncertainty
+                > config.threshold(
+                    "combat_core_reach_critical",
+                    REACH_CRITICAL_BLOCKS,
+                    client,
+                    protocol,
+                )
+            {
+                findings.push(finding(
+                    uuid,
+                    "combat_core_reach_critical",
+                    "critical",
+                    "Critical reach",
+                    json!({ "reach_distance": reach, "reach_uncertainty": uncertainty, "target_type": v.get("target_type"), "entity_id": v.get("entity_id"), "ts": ts, "client_type": client.map(ClientType::as_str), "client_protocol": protocol }),
+                ));
+            }
+        }
+    })?;
+
+    Ok(findings)
+}
+
+fn player_core(raw_gz: &[u8]) -> anyhow::Result<Vec<FindingIn>> {
+    let mut findings = Vec::new();
+
+    for_each_event(raw_gz, |v| {
+        if v.get("dir").and_then(|x| x.as_str()) != Some("serverbound") {
+            return;
+        }
+        let Some(uuid) = event_uuid(v) else { return };
+        let Some(fields) = v.get("fields").and_then(|x| x.as_object()) else {
+            return;
+        };
+
+        if let Some(pitch) = fields.get("pitch").and_then(|x| x.as_f64()) {
+            if pitch.abs() > MAX_ABS_PITCH {
+                findings.push(finding(
+                    uuid,
+                    "player_core_badpackets_pitch",
+                    "critical",
+                    "Impossible pitch",
+                    json!({ "pitch": pitch, "pkt": v.get("pkt"), "ts": v.get("ts") }),
+                ));
+            }
+        }
+
+        // JSON cannot carry NaN, so plugins send them as strings; huge values are equally invalid.
+        let invalid = ["x", "y", "z", "yaw", "pitch"].iter().find(|k| {
+            fields.get(**k).is_some_and(|f| match f {
+                Value::Number(n) => n.as_f64().is_some_and(|n| n.abs() > MAX_ABS_COORD),
+                Value::String(s) => s.parse::<f64>().is_ok_and(|n| !n.is_finite()),
+                _ => false,
+            })
+        });
+        if let Some(field) = invalid {
+            findings.push(finding(
+                uuid,
+                "player_core_badpackets_nan",
+                "critical",
+                "Invalid coordinates",
+                json!({ "field": field, "value": fields.get(*field), "pkt": v.get("pkt"), "ts": v.get("ts") }),
+            ));
+        }
+    })?;
+
+    Ok(findings)
+}
@@ -0,0 +1,71 @@
+//! Per player-session client brand and plugin-channel registrations.
+//!
+//! Many cheat clients leak themselves through their brand ("meteor", "wurst") or the plugin
+//! channels they register. Ingest extracts both (see `transforms::client_channel_records`) into
+//! `player_session_clients`; the player detail endpoint shows them per session.
+
+use std::collections::{BTreeSet, HashMap};
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::transforms::{self, ClientChannel};
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SessionClient {
+    pub brand: Option<String>,
+    pub channels: BTreeSet<String>,
+}
+
+/// Fold a batch's brand / registration packets into one entry per player (latest brand wins).
+pub fn collect(records: Vec<(u64, Uuid, ClientChannel)>) -> HashMap<Uuid, SessionClient> {
+    let mut by_player: HashMap<Uuid, SessionClient> = HashMap::new();
+    for (_, uuid, info) in records {
+        let entry = by_player.entry(uuid).or_default();
+        match info {
+            ClientChannel::Brand(brand) => entry.brand = Some(brand),
+            ClientChannel::Register(channels) => entry.channels.extend(channels),
+        }
+    }
+    by_player
+}
+
+/// Extract and store client info from a raw batch. Most batches carry none.
+pub async fn record_batch(
+    db: &PgPool,
+    server_id: &str,
+    session_id: &str,
+    raw_gz_ndjson: &[u8],
+) -> anyhow::Result<()> {
+    let records = transforms::client_channel_records(raw_gz_ndjson)?;
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    for (player_uuid, client) in collect(records) {
+        let channels: Vec<String> = client.channels.into_iter().collect();
+        sqlx::query(
+            r#"
+            insert into public.player_session_clients
+                (server_id, session_id, player_uuid, brand, channels, first_seen_at, last_seen_at)
+            values ($1, $2, $3, $4, $5, now(), now())
+            on conflict (server_id, session_id, player_uuid) do update set
+                brand = coalesce(excluded.brand, player_session_clients.brand),
+                channels = array(
+                    select distinct c
+                    from unnest(player_session_clients.channels || excluded.channels) c
+                    order by c
+                ),
+                last_seen_at = now()
+            "#,
+        )
+        .bind(server_id)
+        .bind(session_id)
+        .bind(player_uuid)
+        .bind(client.brand.as_deref())
+        .bind(&channels)
+        .execute(db)
+        .await?;
+    }
+    Ok(())
+}
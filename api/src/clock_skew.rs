@@ -0,0 +1,86 @@
+//! Plugin clock skew.
+//!
+//! Record timestamps (`ts`) come from the game server's clock, which can drift by minutes from
+//! the API's; that breaks window aggregation and deltas across batches (and across servers of a
+//! proxy group). The plugin sends its clock in `X-Client-Time` when it uploads a batch, and
+//! ingest stores, per batch, the server receive time and the raw offset (client time minus
+//! receive time).
+//!
+//! A single offset also contains the upload latency, which only ever makes it smaller, so the
+//! session's skew is estimated as the largest offset among its recent batches. Batches without
+//! the header (older plugins) inherit the session's estimate. Transforms requested with the
+//! `+server_time` option (see `transforms`) rebase `ts` onto server time by subtracting it.
+
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub const CLIENT_TIME_HEADER: &str = "x-client-time";
+
+/// Recent batches of a session whose offsets make up its skew estimate.
+pub const SESSION_SAMPLES: i64 = 20;
+
+/// The plugin's clock (epoch ms) from `X-Client-Time`, absent for older plugins.
+pub fn from_headers(headers: &HeaderMap) -> Result<Option<i64>, String> {
+    let Some(raw) = headers.get(CLIENT_TIME_HEADER) else {
+        return Ok(None);
+    };
+    raw.to_str()
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok())
+        .filter(|ms| *ms > 0)
+        .map(Some)
+        .ok_or_else(|| "X-Client-Time must be epoch milliseconds".to_string())
+}
+
+/// When a batch arrived and how the plugin's clock compared.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchClock {
+    pub received_at: DateTime<Utc>,
+    pub offset_ms: Option<i64>,
+    pub skew_ms: Option<i64>,
+}
+
+/// Client time minus receive time (skew minus upload latency).
+pub fn offset(client_time_ms: i64, received_at: DateTime<Utc>) -> i64 {
+    client_time_ms.saturating_sub(received_at.timestamp_millis())
+}
+
+/// Skew estimate from a session's offsets: the one least delayed by upload latency.
+pub fn estimate(offsets: impl IntoIterator<Item = i64>) -> Option<i64> {
+    offsets.into_iter().max()
+}
+
+/// The session's skew including this batch's offset (if it has one).
+pub async fn session_skew(
+    db: &PgPool,
+    server_id: &str,
+    session_id: &str,
+    offset: Option<i64>,
+) -> Result<Option<i64>, sqlx::Error> {
+    let recent: Vec<i64> = sqlx::query_scalar(
+        r#"
+        select clock_offset_ms from public.batch_index
+        where session_id = $2 and server_id = $1 and clock_offset_ms is not null
+        order by received_at desc
+        limit $3
+        "#,
+    )
+    .bind(server_id)
+    .bind(session_id)
+    .bind(SESSION_SAMPLES - 1)
+    .fetch_all(db)
+    .await?;
+    Ok(estimate(recent.into_iter().chain(offset)))
+}
+
+/// Skew stored with a batch.
+pub async fn for_batch(db: &PgPool, batch_id: Uuid) -> Result<Option<i64>, sqlx::Error> {
+    let skew: Option<Option<i64>> =
+        sqlx::query_scalar("select clock_skew_ms from public.batch_index where id = $1")
+            .bind(batch_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(skew.flatten())
+}
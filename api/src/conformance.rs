@@ -0,0 +1,381 @@
+//! Module contract/conformance testing.
+//!
+//! Sends a module a canned batch (through the module's configured transform), checks the HTTP
+//! response shape, and optionally waits for the findings callback carrying the run's marker.
+//! The marker travels as the batch's session id; callbacks whose session is the marker of a
+//! stored run are recorded against the run instead of being stored as real findings. Other
+//! sessions with the marker prefix are ordinary plugin sessions.
+//!
+//! Reports are stored in `module_conformance_runs` and shown in the dashboard.
+
+use chrono::Utc;
+use flate2::{write::GzEncoder, Compression};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{checks, error::ApiError, module_pipeline, transforms, wasm_runtime, AppState};
+
+/// Conformance markers start with this prefix (followed by the run id).
+pub const MARKER_PREFIX: &str = "conformance-";
+
+/// Player used in the canned batch (never a real Mojang UUID: version nibble 4, fixed tail).
+pub const FIXTURE_PLAYER: &str = "00000000-0000-4000-8000-00000000c0de";
+
+const MAX_CALLBACK_WAIT: Duration = Duration::from_secs(60);
+
+/// Whether a session id looks like a marker; only a stored run makes it one
+/// ([`record_callback`]).
+pub fn is_conformance_session(session_id: Option<&str>) -> bool {
+    session_id.is_some_and(|s| s.starts_with(MARKER_PREFIX))
+}
+
+/// Canned raw batch covering movement, rotation, and combat packets (gzipped NDJSON).
+pub fn fixture_batch(server_id: &str, marker: &str) -> anyhow::Result<Vec<u8>> {
+    let mut lines: Vec<Value> = vec![json!({
+        "server_id": server_id,
+        "session_id": marker,
+        "conformance": true,
+    })];
+
+    let base_ts = Utc::now().timestamp_millis().max(0) as u64;
+    lines.push(json!({
+        "ts": base_ts, "dir": "clientbound", "pkt": "SPAWN_ENTITY",
+        "fields": { "entity_id": 4242, "x": 3.0, "y": 64.0, "z": 0.0 }
+    }));
+    // Walk, then move far too fast, then attack from range.
+    for i in 0..10u64 {
+        let x = if i < 5 {
+            i as f64 * 0.2
+        } else {
+            i as f64 * 2.0
+        };
+        lines.push(json!({
+            "ts": base_ts + 50 * (i + 1), "dir": "serverbound", "pkt": "PLAYER_POSITION_AND_ROTATION",
+            "uuid": FIXTURE_PLAYER, "name": "conformance",
+            "fields": { "x": x, "y": 64.0, "z": 0.0, "yaw": 270.0, "pitch": 0.0, "on_ground": true }
+        }));
+    }
+    for i in 0..3u64 {
+        lines.push(json!({
+            "ts": base_ts + 600 + 50 * i, "dir": "serverbound", "pkt": "INTERACT_ENTITY",
+            "uuid": FIXTURE_PLAYER, "name": "conformance",
+            "fields": { "entity_id": 4242, "action": "ATTACK", "sneaking": false }
+        }));
+    }
+
+    let mut out = Vec::new();
+    let mut encoder = GzEncoder::new(&mut out, Compression::default());
+    for line in lines {
+        writeln!(encoder, "{}", line)?;
+    }
+    encoder.finish()?;
+    Ok(out)
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConformanceCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConformanceReport {
+    pub run_id: Uuid,
+    pub module_id: Uuid,
+    pub marker: String,
+    pub passed: bool,
+    pub checks: Vec<ConformanceCheck>,
+}
+
+fn check(checks: &mut Vec<ConformanceCheck>, name: &str, passed: bool, detail: Option<String>) {
+    checks.push(ConformanceCheck {
+        name: name.to_string(),
+        passed,
+        detail,
+    });
+}
+
+/// Run a conformance test against a module and persist the report.
+pub async fn run(
+    state: &AppState,
+    module_id: Uuid,
+    wait_for_callback: Option<Duration>,
+) -> Result<ConformanceReport, ApiError> {
+    let module: Option<(String, String, String, String)> = sqlx::query_as(
+        "select server_id, name, base_url, transform from public.server_modules where id = $1",
+    )
+    .bind(module_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("conformance module lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let Some((server_id, name, base_url, transform)) = module else {
+        return Err(ApiError::NotFound(format!(
+            "module {} not found",
+            module_id
+        )));
+    };
+
+    let run_id = Uuid::new_v4();
+    let marker = format!("{}{}", MARKER_PREFIX, run_id.simple());
+    sqlx::query(
+        r#"
+        insert into public.module_conformance_runs (id, module_id, server_id, marker)
+        values ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(run_id)
+    .bind(module_id)
+    .bind(&server_id)
+    .bind(&marker)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("conformance run insert failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let mut checks_out = Vec::new();
+    let raw = fixture_batch(&server_id, &marker).map_err(|e| {
+        tracing::error!("conformance fixture failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    if let Some(engine) = checks::inproc_engine(&base_url) {
+        // In-process engines have no HTTP contract; just make sure they run.
+        match checks::run(engine, &raw) {
+            Ok(findings) => check(
+                &mut checks_out,
+                "inproc_engine_runs",
+                true,
+                Some(format!("{} findings", findings.len())),
+            ),
+            Err(e) => check(
+                &mut checks_out,
+                "inproc_engine_runs",
+                false,
+                Some(e.to_string()),
+            ),
+        }
+    } else if let Some(wasm_id) = wasm_runtime::wasm_module_id(&base_url) {
+        match wasm_runtime::run(state, wasm_id, &transform, &raw, None).await {
+            Ok(findings) => check(
+                &mut checks_out,
+                "wasm_module_runs",
+                true,
+                Some(format!("{} findings", findings.len())),
+            ),
+            Err(e) => check(
+                &mut checks_out,
+                "wasm_module_runs",
+                false,
+                Some(e.to_string()),
+            ),
+        }
+    } else {
+        exercise_http_module(
+            state,
+            &server_id,
+            module_id,
+            &base_url,
+            &transform,
+            &marker,
+            run_id,
+            &raw,
+            wait_for_callback,
+            &mut checks_out,
+        )
+        .await;
+    }
+
+    let passed = checks_out.iter().all(|c| c.passed);
+    let report = ConformanceReport {
+        run_id,
+        module_id,
+        marker,
+        passed,
+        checks: checks_out,
+    };
+
+    sqlx::query(
+        "update public.module_conformance_runs set passed = $2, report_json = $3 where id = $1",
+    )
+    .bind(run_id)
+    .bind(passed)
+    .bind(sqlx::types::Json(&report))
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("conformance run update failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    tracing::info!(module = %name, module_id = %module_id, passed = passed, "module conformance run finished");
+    Ok(report)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn exercise_http_module(
+    state: &AppState,
+    server_id: &str,
+    module_id: Uuid,
+    base_url: &str,
+    transform: &str,
+    marker: &str,
+    run_id: Uuid,
+    raw: &[u8],
+    wait_for_callback: Option<Duration>,
+    checks_out: &mut Vec<ConformanceCheck>,
+) {
+    let payload = match transforms::apply_transform(transform, raw) {
+        Ok(p) => {
+            check(
+                checks_out,
+                "transform_applies",
+                true,
+                Some(transform.to_string()),
+            );
+            p
+        }
+        Err(e) => {
+            check(checks_out, "transform_applies", false, Some(e.to_string()));
+            return;
+        }
+    };
+
+    let resp = module_pipeline::post_ingest(
+        &state.http,
+        base_url,
+        server_id,
+        marker,
+        run_id,
+        Some(module_id),
+        None,
+        payload,
+    )
+    .await;
+
+    let resp = match resp {
+        Ok(r) => r,
+        Err(e) => {
+            check(checks_out, "ingest_reachable", false, Some(e.to_string()));
+            return;
+        }
+    };
+    check(checks_out, "ingest_reachable", true, None);
+
+    let status = resp.status();
+    check(
+        checks_out,
+        "ingest_status_2xx",
+        status.is_success(),
+        Some(format!("HTTP {}", status.as_u16())),
+    );
+
+    // Modules may answer with an empty body; if they send JSON it must be an object and
+    // must not report `ok: false`.
+    let body = resp.bytes().await.unwrap_or_default();
+    if body.iter().all(|b| b.is_ascii_whitespace()) {
+        check(
+            checks_out,
+            "ingest_response_shape",
+            true,
+            Some("empty body".to_string()),
+        );
+    } else {
+        match serde_json::from_slice::<Value>(&body) {
+            Ok(Value::Object(obj)) => {
+                let ok = obj.get("ok").and_then(|v| v.as_bool()).unwrap_or(true);
+                check(
+                    checks_out,
+                    "ingest_response_shape",
+                    ok,
+                    (!ok).then(|| "response has ok=false".to_string()),
+                );
+            }
+            Ok(_) => check(
+                checks_out,
+                "ingest_response_shape",
+                false,
+                Some("response JSON is not an object".to_string()),
+            ),
+            Err(e) => check(
+                checks_out,
+                "ingest_response_shape",
+                false,
+                Some(format!("invalid JSON: {}", e)),
+            ),
+        }
+    }
+
+    let Some(wait) = wait_for_callback else {
+        return;
+    };
+    let deadline = Instant::now() + wait.min(MAX_CALLBACK_WAIT);
+    loop {
+        let received: Option<(Option<chrono::DateTime<Utc>>, Option<i32>)> = sqlx::query_as(
+            "select callback_received_at, callback_findings from public.module_conformance_runs where id = $1",
+        )
+        .bind(run_id)
+        .fetch_optional(&state.db)
+        .await
+        .ok()
+        .flatten();
+
+        if let Some((Some(_), findings)) = received {
+            check(
+                checks_out,
+                "findings_callback",
+                true,
+                Some(format!("{} findings", findings.unwrap_or(0))),
+            );
+            return;
+        }
+        if Instant::now() >= deadline {
+            check(
+                checks_out,
+                "findings_callback",
+                false,
+                Some(format!(
+                    "no callback with marker within {}s",
+                    wait.as_secs()
+                )),
+            );
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// Record a findings callback for a conformance marker session.
+///
+/// Returns whether the marker belongs to a run of `server_id`; plugins pick their own session
+/// ids, so a session that merely looks like a marker carries real findings.
+pub async fn record_callback(
+    state: &AppState,
+    server_id: &str,
+    marker: &str,
+    findings: usize,
+) -> Result<bool, sqlx::Error> {
+    let updated = sqlx::query(
+        r#"
+        update public.module_conformance_runs
+        set callback_received_at = coalesce(callback_received_at, now()),
+            callback_findings = coalesce(callback_findings, 0) + $3
+        where marker = $1 and server_id = $2
+        "#,
+    )
+    .bind(marker)
+    .bind(server_id)
+    .bind(findings as i32)
+    .execute(&state.db)
+    .await?;
+    Ok(updated.rows_affected() > 0)
+}
@@ -0,0 +1,201 @@
+//! Multi-detector correlation.
+//!
+//! A single check firing is a weak signal; several independent checks firing on the same player
+//! within a few minutes (reach + autoclicker + aim, speed + killaura) is a strong one. When
+//! findings are stored, `correlate` looks at what else flagged each player within
+//! [`WINDOW_SECONDS`] and, if at least [`MIN_FAMILIES`] independent check families are involved,
+//! adds a synthetic [`COMBINED_DETECTOR`] finding with an elevated severity. It is stored,
+//! aggregated and sent to webhooks like any other finding (and can be remapped with a severity
+//! override). While a combined finding of the player covers the same families within the window,
+//! no new one is created; one is only added (and alerted on) when another family joins.
+//!
+//! Detectors are independent when they belong to different check families: built-in names are
+//! `<category>_<tier>_<check>_<variant>` and the family is `<category>_<check>`, so the core
+//! and advanced reach checks count once. Other names are their own family. `info` findings and
+//! findings reviewed as dismissed or false positive don't count.
+
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use uuid::Uuid;
+
+use crate::routes::callbacks::FindingIn;
+use crate::severity_overrides::SeverityOverrides;
+
+pub const COMBINED_DETECTOR: &str = "correlation_combined";
+pub const COMBINED_DETECTOR_VERSION: &str = "1";
+
+/// How far back other detectors' findings are considered.
+pub const WINDOW_SECONDS: i64 = 300;
+/// Independent check families needed for a combined finding.
+pub const MIN_FAMILIES: usize = 2;
+
+const TIERS: [&str; 2] = ["core", "advanced"];
+
+/// The check family of a detector (`combat_advanced_reach_distance` -> `combat_reach`).
+pub fn family(detector_name: &str) -> String {
+    let name = detector_name.trim().to_ascii_lowercase();
+    let parts: Vec<&str> = name.split('_').collect();
+    match parts.as_slice() {
+        [category, tier, check, ..] if TIERS.contains(tier) => format!("{}_{}", category, check),
+        _ => name,
+    }
+}
+
+/// Severity of a combined finding over `families` independent families.
+pub fn combined_severity(families: usize) -> Option<&'static str> {
+    match families {
+        n if n >= 3 => Some("critical"),
+        n if n >= MIN_FAMILIES => Some("high"),
+        _ => None,
+    }
+}
+
+/// The combined finding for a player flagged by `detectors` within the window, if they span
+/// enough independent families.
+pub fn combine<'a>(
+    player_uuid: Uuid,
+    detectors: impl IntoIterator<Item = &'a str>,
+) -> Option<FindingIn> {
+    let mut families: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for detector in detectors {
+        if detector == COMBINED_DETECTOR {
+            continue;
+        }
+        families
+            .entry(family(detector))
+            .or_default()
+            .insert(detector.to_string());
+    }
+    let severity = combined_severity(families.len())?;
+
+    let checks: Vec<&str> = families
+        .keys()
+        .map(|f| f.split_once('_').map_or(f.as_str(), |(_, check)| check))
+        .collect();
+    let detectors: Vec<&String> = families.values().flatten().collect();
+    Some(FindingIn {
+        player_uuid: Some(player_uuid),
+        detector_name: COMBINED_DETECTOR.to_string(),
+        detector_version: Some(COMBINED_DETECTOR_VERSION.to_string()),
+        severity: Some(severity.to_string()),
+        title: format!("Combined detection: {}", checks.join(" + ")),
+        description: Some(format!(
+            "{} independent checks flagged this player within {} minutes",
+            families.len(),
+            WINDOW_SECONDS / 60
+        )),
+        evidence_s3_key: None,
+        evidence_json: Some(json!({
+            "families": families.keys().collect::<Vec<_>>(),
+            "detectors": detectors,
+            "window_seconds": WINDOW_SECONDS,
+        })),
+        confidence: None,
+    })
+}
+
+/// Whether `combined` involves a family that isn't in `covered` (the families of the player's
+/// recent combined findings).
+pub fn adds_family(combined: &FindingIn, covered: &BTreeSet<String>) -> bool {
+    combined
+        .evidence_json
+        .as_ref()
+        .and_then(|e| e["families"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|f| f.as_str())
+        .any(|f| !covered.contains(f))
+}
+
+/// Combined findings for the players of `findings` (incoming, not yet stored), counting their
+/// other findings from the last [`WINDOW_SECONDS`]. Players whose combined findings from the
+/// window already cover every family get none.
+pub async fn correlate(
+    db: &PgPool,
+    server_id: &str,
+    findings: &[&FindingIn],
+    overrides: &SeverityOverrides,
+) -> Result<Vec<FindingIn>, sqlx::Error> {
+    let mut detectors: HashMap<Uuid, BTreeSet<String>> = HashMap::new();
+    for f in findings {
+        let Some(player) = f.player_uuid else {
+            continue;
+        };
+        let name = f.detector_name.trim();
+        if name.is_empty()
+            || name == COMBINED_DETECTOR
+            || overrides.severity(name, f.severity.as_deref()) == "info"
+        {
+            continue;
+        }
+        detectors
+            .entry(player)
+            .or_default()
+            .insert(name.to_string());
+    }
+    if detectors.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let players: Vec<Uuid> = detectors.keys().copied().collect();
+    let recent: Vec<(Uuid, String)> = sqlx::query_as(
+        r#"
+        select distinct player_uuid, detector_name
+        from public.findings
+        where server_id = $1
+          and player_uuid = any($2)
+          and last_seen_at > now() - make_interval(secs => $3)
+          and detector_name <> $4
+          and severity <> 'info'
+          and status not in ('dismissed', 'false_positive')
+        "#,
+    )
+    .bind(server_id)
+    .bind(&players)
+    .bind(WINDOW_SECONDS as f64)
+    .bind(COMBINED_DETECTOR)
+    .fetch_all(db)
+    .await?;
+    for (player, detector) in recent {
+        if let Some(set) = detectors.get_mut(&player) {
+            set.insert(detector);
+        }
+    }
+
+    let existing: Vec<(Uuid, Option<sqlx::types::Json<Value>>)> = sqlx::query_as(
+        r#"
+        select player_uuid, evidence_json
+        from public.findings
+        where server_id = $1
+          and player_uuid = any($2)
+          and last_seen_at > now() - make_interval(secs => $3)
+          and detector_name = $4
+        "#,
+    )
+    .bind(server_id)
+    .bind(&players)
+    .bind(WINDOW_SECONDS as f64)
+    .bind(COMBINED_DETECTOR)
+    .fetch_all(db)
+    .await?;
+    let mut covered: HashMap<Uuid, BTreeSet<String>> = HashMap::new();
+    for (player, evidence) in existing {
+        let families = evidence
+            .as_ref()
+            .and_then(|e| e.0["families"].as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|f| f.as_str().map(str::to_string));
+        covered.entry(player).or_default().extend(families);
+    }
+
+    Ok(detectors
+        .into_iter()
+        .filter_map(|(player, names)| combine(player, names.iter().map(String::as_str)))
+        .filter(|f| {
+            let player = f.player_uuid.unwrap_or_default();
+            adds_family(f, covered.get(&player).unwrap_or(&BTreeSet::new()))
+        })
+        .collect())
+}
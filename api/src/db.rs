@@ -51,5 +51,1810 @@ pub async fn migrate(db: &PgPool) -> Result<(), sqlx::Error> {
     .execute(db)
     .await?;
 
+    sqlx::query(
+        r#"
+        alter table public.servers
+            add column if not exists reputation_opt_in boolean not null default false;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Curated community module catalog.
+    sqlx::query(
+        r#"
+        create table if not exists public.module_catalog (
+            name text primary key,
+            tier text not null default 'community',
+            short_description text not null default '',
+            checks text[] not null default '{}',
+            transform text not null default 'raw_ndjson_gz',
+            default_base_url text,
+            install_instructions text,
+            homepage_url text,
+            listed boolean not null default true,
+            created_at timestamptz not null default now(),
+            updated_at timestamptz not null default now()
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Module conformance reports.
+    sqlx::query(
+        r#"
+        create table if not exists public.module_conformance_runs (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            module_id uuid not null references public.server_modules(id) on delete cascade,
+            server_id text not null references public.servers(id) on delete cascade,
+            marker text not null unique,
+            passed boolean,
+            report_json jsonb,
+            callback_received_at timestamptz,
+            callback_findings int
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Uploaded WASM modules.
+    sqlx::query(
+        r#"
+        create table if not exists public.wasm_modules (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            server_id text not null references public.servers(id) on delete cascade,
+            name text not null,
+            sha256 text not null,
+            size_bytes int not null,
+            wasm bytea not null,
+            fuel_limit bigint not null,
+            memory_limit_bytes bigint not null
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Observation <-> finding links.
+    sqlx::query(
+        r#"
+        create table if not exists public.observation_findings (
+            observation_id uuid not null references public.cheat_observations(id) on delete cascade,
+            finding_id uuid not null references public.findings(id) on delete cascade,
+            linked_at timestamptz not null default now(),
+            primary key (observation_id, finding_id)
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Detector review counters.
+    sqlx::query(
+        r#"
+        create table if not exists public.detector_metrics (
+            server_id text not null references public.servers(id) on delete cascade,
+            detector_name text not null,
+            confirmed bigint not null default 0,
+            false_positives bigint not null default 0,
+            dismissed bigint not null default 0,
+            last_false_positive_at timestamptz,
+            updated_at timestamptz not null default now(),
+            primary key (server_id, detector_name)
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Observation replays.
+    sqlx::query(
+        r#"
+        create table if not exists public.observation_replays (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            completed_at timestamptz,
+            observation_id uuid not null references public.cheat_observations(id) on delete cascade,
+            server_id text not null references public.servers(id) on delete cascade,
+            module_id uuid references public.server_modules(id) on delete set null,
+            status text not null default 'running',
+            batches_total int not null default 0,
+            batches_replayed int not null default 0,
+            batches_missing int not null default 0,
+            errors jsonb
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.observation_replay_findings (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            replay_id uuid not null references public.observation_replays(id) on delete cascade,
+            observation_id uuid not null references public.cheat_observations(id) on delete cascade,
+            module_id uuid not null,
+            player_uuid uuid,
+            detector_name text not null,
+            detector_version text,
+            severity text not null,
+            title text not null,
+            evidence_json jsonb
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_observation_replay_findings_replay
+            on public.observation_replay_findings (replay_id);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Cross-server reputation.
+    sqlx::query(
+        r#"
+        create table if not exists public.player_reputation (
+            player_uuid uuid primary key,
+            score double precision not null,
+            weight double precision not null,
+            servers_flagged int not null,
+            findings int not null,
+            confirmed_findings int not null,
+            last_flagged_at timestamptz,
+            updated_at timestamptz not null default now()
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Player watchlist + server -> plugin pending actions.
+    sqlx::query(
+        r#"
+        create table if not exists public.server_watchlist (
+            server_id text not null references public.servers(id) on delete cascade,
+            player_uuid uuid not null,
+            reason text,
+            added_by text,
+            expires_at timestamptz,
+            created_at timestamptz not null default now(),
+            primary key (server_id, player_uuid)
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.server_pending_actions (
+            id uuid primary key default gen_random_uuid(),
+            server_id text not null references public.servers(id) on delete cascade,
+            action text not null,
+            payload jsonb not null,
+            created_at timestamptz not null default now(),
+            delivered_at timestamptz
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_server_pending_actions_server
+            on public.server_pending_actions (server_id, delivered_at);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists retain_until timestamptz;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Alt-account correlation signals.
+    sqlx::query(
+        r#"
+        create table if not exists public.player_identifiers (
+            server_id text not null references public.servers(id) on delete cascade,
+            player_uuid uuid not null references public.players(uuid) on delete cascade,
+            kind text not null,
+            value text not null,
+            first_seen_at timestamptz not null default now(),
+            last_seen_at timestamptz not null default now(),
+            primary key (server_id, player_uuid, kind, value)
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_player_identifiers_value
+            on public.player_identifiers (server_id, kind, value);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Client brand / plugin channels per player-session.
+    sqlx::query(
+        r#"
+        create table if not exists public.player_session_clients (
+            server_id text not null references public.servers(id) on delete cascade,
+            session_id text not null,
+            player_uuid uuid not null,
+            brand text,
+            channels text[] not null default '{}',
+            first_seen_at timestamptz not null default now(),
+            last_seen_at timestamptz not null default now(),
+            primary key (server_id, session_id, player_uuid)
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_player_session_clients_player
+            on public.player_session_clients (server_id, player_uuid, last_seen_at desc);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Per-world / per-gamemode context.
+    sqlx::query(
+        r#"
+        alter table public.server_players
+            add column if not exists world text,
+            add column if not exists gamemode text;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.findings
+            add column if not exists world text;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_findings_server_world
+            on public.findings (server_id, world, last_seen_at desc);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Detection exemptions.
+    sqlx::query(
+        r#"
+        alter table public.server_players
+            add column if not exists permissions text[] not null default '{}';
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.server_exemptions (
+            id uuid primary key default gen_random_uuid(),
+            server_id text not null references public.servers(id) on delete cascade,
+            player_uuid uuid,
+            permission text,
+            world text,
+            starts_at timestamptz not null default now(),
+            ends_at timestamptz,
+            reason text,
+            created_by text,
+            created_at timestamptz not null default now(),
+            check (player_uuid is not null or permission is not null or world is not null)
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_server_exemptions_server
+            on public.server_exemptions (server_id, ends_at);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.reprocess_jobs (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            server_id text not null references public.servers(id) on delete cascade,
+            module_id uuid not null references public.server_modules(id) on delete cascade,
+            transform text,
+            range_start timestamptz not null,
+            range_end timestamptz not null,
+            status text not null default 'queued',
+            batches_total int not null default 0,
+            batches_processed int not null default 0,
+            batches_missing int not null default 0,
+            findings int not null default 0,
+            errors jsonb,
+            cursor_received_at timestamptz,
+            cursor_batch_id uuid,
+            started_at timestamptz,
+            updated_at timestamptz not null default now(),
+            completed_at timestamptz
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_reprocess_jobs_status
+            on public.reprocess_jobs (status, created_at);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.simulation_runs (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            completed_at timestamptz,
+            server_id text not null references public.servers(id) on delete cascade,
+            scenarios text[] not null,
+            report_json jsonb
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.simulation_findings (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            run_id uuid not null references public.simulation_runs(id) on delete cascade,
+            module_id uuid not null,
+            player_uuid uuid not null,
+            detector_name text not null,
+            severity text not null
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_simulation_findings_run
+            on public.simulation_findings (run_id);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.organizations (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            name text not null,
+            webhook_url text,
+            webhook_enabled boolean not null default false,
+            webhook_severity_levels text[] not null default array['critical', 'high']::text[]
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.organization_members (
+            organization_id uuid not null references public.organizations(id) on delete cascade,
+            user_id uuid not null,
+            role text not null default 'member' check (role in ('owner', 'admin', 'member')),
+            created_at timestamptz not null default now(),
+            primary key (organization_id, user_id)
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_organization_members_user
+            on public.organization_members (user_id);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.servers
+            add column if not exists organization_id uuid references public.organizations(id) on delete set null;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_servers_organization
+            on public.servers (organization_id);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.organization_exemptions (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            organization_id uuid not null references public.organizations(id) on delete cascade,
+            player_uuid uuid,
+            permission text,
+            world text,
+            starts_at timestamptz not null default now(),
+            ends_at timestamptz,
+            reason text,
+            created_by text
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_organization_exemptions_org
+            on public.organization_exemptions (organization_id, ends_at);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.proxy_groups (
+            id uuid primary key default gen_random_uuid(),
+            owner_id uuid not null,
+            proxy_id text not null,
+            name text,
+            first_seen_at timestamptz not null default now(),
+            last_seen_at timestamptz not null default now(),
+            unique (owner_id, proxy_id)
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.servers
+            add column if not exists proxy_group_id uuid references public.proxy_groups(id) on delete set null;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_servers_proxy_group
+            on public.servers (proxy_group_id);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.proxy_sessions (
+            id uuid primary key default gen_random_uuid(),
+            proxy_group_id uuid not null references public.proxy_groups(id) on delete cascade,
+            player_uuid uuid not null references public.players(uuid) on delete cascade,
+            started_at timestamptz not null default now(),
+            last_seen_at timestamptz not null default now(),
+            last_server_id text not null,
+            server_ids text[] not null default '{}'
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_proxy_sessions_group_player
+            on public.proxy_sessions (proxy_group_id, player_uuid, last_seen_at desc);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_proxy_sessions_group_last_seen
+            on public.proxy_sessions (proxy_group_id, last_seen_at desc);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.player_sessions (
+            id uuid primary key default gen_random_uuid(),
+            server_id text not null references public.servers(id) on delete cascade,
+            player_uuid uuid not null references public.players(uuid) on delete cascade,
+            plugin_session_id text not null,
+            started_at timestamptz not null,
+            last_seen_at timestamptz not null,
+            ended_at timestamptz,
+            end_reason text
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_player_sessions_player
+            on public.player_sessions (server_id, player_uuid, started_at desc);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_player_sessions_server
+            on public.player_sessions (server_id, last_seen_at desc);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.server_heartbeats (
+            id bigint generated always as identity primary key,
+            server_id text not null references public.servers(id) on delete cascade,
+            received_at timestamptz not null default now(),
+            tps double precision,
+            mspt double precision,
+            online_players integer,
+            plugin_version text,
+            mc_version text
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_server_heartbeats_server
+            on public.server_heartbeats (server_id, received_at desc);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.servers
+            add column if not exists plugin_version text;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.servers
+            add column if not exists plugin_version_seen_at timestamptz;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.server_capture_config (
+            server_id text primary key references public.servers(id) on delete cascade,
+            config jsonb not null,
+            updated_at timestamptz not null default now(),
+            updated_by text
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.server_probes (
+            server_id text primary key references public.servers(id) on delete cascade,
+            address text not null,
+            reachable boolean not null,
+            latency_ms bigint,
+            motd text,
+            players_online bigint,
+            players_max bigint,
+            version text,
+            protocol bigint,
+            probed_at timestamptz not null default now()
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Bedrock (Geyser) players.
+    sqlx::query(
+        r#"
+        alter table public.server_players
+            add column if not exists client_type text;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Ingest sequence tracking.
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists batch_seq bigint,
+            add column if not exists seq_status text,
+            add column if not exists seq_missing int;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_batch_index_server_session_seq
+            on public.batch_index (server_id, session_id, batch_seq)
+            where batch_seq is not null;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists quarantined boolean not null default false,
+            add column if not exists payload_sha256 text;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_batch_index_server_sha256
+            on public.batch_index (server_id, payload_sha256)
+            where payload_sha256 is not null;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.ingest_anomalies (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            batch_id uuid not null references public.batch_index(id) on delete cascade,
+            server_id text not null references public.servers(id) on delete cascade,
+            session_id text not null,
+            kind text not null,
+            details jsonb not null default '{}'
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_ingest_anomalies_server_time
+            on public.ingest_anomalies (server_id, created_at desc);
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists clock_offset_ms bigint,
+            add column if not exists clock_skew_ms bigint;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.severity_overrides (
+            server_id text not null references public.servers(id) on delete cascade,
+            detector_name text not null,
+            severity text not null,
+            created_at timestamptz not null default now(),
+            updated_at timestamptz not null default now(),
+            primary key (server_id, detector_name)
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.findings
+            add column if not exists confidence double precision;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.servers
+            add column if not exists min_confidence double precision,
+            add column if not exists low_confidence_action text not null default 'downgrade';
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Archive of expired per-player module state (player_state_decay.rs).
+    sqlx::query(
+        r#"
+        create table if not exists public.module_player_state_archive (
+            id uuid primary key default gen_random_uuid(),
+            server_id text not null references public.servers(id) on delete cascade,
+            player_uuid uuid not null,
+            module_name text not null,
+            vl double precision,
+            summary_json jsonb not null default '{}',
+            state_bytes int not null default 0,
+            state_updated_at timestamptz not null,
+            player_last_seen_at timestamptz,
+            archived_at timestamptz not null default now()
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create index if not exists idx_module_player_state_archive_player
+            on public.module_player_state_archive (server_id, player_uuid, archived_at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create index if not exists idx_module_player_state_updated
+            on public.module_player_state (updated_at)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Automated ban rule and exported bans (punishments.rs).
+    sqlx::query(
+        r#"
+        create table if not exists public.punishment_exports (
+            server_id text primary key references public.servers(id) on delete cascade,
+            enabled boolean not null default false,
+            mode text not null default 'queue',
+            webhook_url text,
+            webhook_token text,
+            trigger_severities text[] not null default '{critical}',
+            min_confidence double precision,
+            ban_duration_seconds bigint,
+            reason text not null default 'Unfair advantage ({detectors})',
+            evidence_base_url text,
+            created_at timestamptz not null default now(),
+            updated_at timestamptz not null default now()
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create table if not exists public.punishment_actions (
+            id bigserial primary key,
+            created_at timestamptz not null default now(),
+            server_id text not null references public.servers(id) on delete cascade,
+            uuid varchar(36) not null,
+            player_name text,
+            reason text not null,
+            banned_by_name text not null,
+            time bigint not null,
+            until bigint not null,
+            silent boolean not null default false,
+            ipban boolean not null default false,
+            active boolean not null default true,
+            evidence_url text not null,
+            evidence jsonb not null default '[]',
+            mode text not null,
+            delivery text not null,
+            delivered_at timestamptz,
+            last_error text
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create index if not exists idx_punishment_actions_server
+            on public.punishment_actions (server_id, created_at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create index if not exists idx_punishment_actions_player
+            on public.punishment_actions (server_id, uuid, created_at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create index if not exists idx_punishment_actions_queued
+            on public.punishment_actions (server_id, id) where delivery = 'queued'
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Player name lookups for GET /plugin/findings.
+    sqlx::query(
+        r#"
+        create index if not exists idx_server_players_server_name
+            on public.server_players (server_id, lower(player_name), last_seen_at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Evidence bundles for appeals (evidence_bundle.rs).
+    sqlx::query(
+        r#"
+        create table if not exists public.evidence_bundles (
+            id uuid primary key,
+            created_at timestamptz not null default now(),
+            server_id text not null references public.servers(id) on delete cascade,
+            finding_id uuid not null references public.findings(id) on delete cascade,
+            s3_key text not null,
+            size_bytes bigint not null,
+            events int not null default 0
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create index if not exists idx_evidence_bundles_finding
+            on public.evidence_bundles (finding_id, created_at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Negotiated module dispatch encoding (dispatch_encoding.rs).
+    sqlx::query(
+        r#"
+        alter table public.server_modules
+            add column if not exists content_encoding text not null default 'gzip'
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Async module processing (async_dispatch.rs).
+    sqlx::query(
+        r#"
+        alter table public.server_modules
+            add column if not exists processing_deadline_seconds int not null default 300
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        alter table public.module_dispatches
+            add column if not exists processing_id text
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        alter table public.module_dispatches
+            add column if not exists deadline_at timestamptz
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        alter table public.module_dispatches
+            add column if not exists completed_at timestamptz
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create index if not exists idx_module_dispatches_processing
+            on public.module_dispatches (server_id, processing_id) where processing_id is not null
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create index if not exists idx_module_dispatches_deadline
+            on public.module_dispatches (deadline_at) where status = 'processing'
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Finding attribution by module (callbacks::store_findings).
+    sqlx::query(
+        r#"
+        alter table public.findings
+            add column if not exists module_id uuid references public.server_modules(id) on delete set null
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create index if not exists idx_findings_module
+            on public.findings (module_id) where module_id is not null
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Dispatch latency and hourly dispatch stats (dispatch_stats.rs).
+    sqlx::query(
+        r#"
+        alter table public.module_dispatches
+            add column if not exists latency_ms int
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create index if not exists idx_module_dispatches_created
+            on public.module_dispatches (created_at)
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create table if not exists public.module_dispatch_stats (
+            module_id uuid not null references public.server_modules(id) on delete cascade,
+            hour timestamptz not null,
+            server_id text not null references public.servers(id) on delete cascade,
+            dispatches int not null,
+            succeeded int not null,
+            failed int not null,
+            timed_out int not null,
+            avg_latency_ms double precision,
+            p95_latency_ms double precision,
+            updated_at timestamptz not null default now(),
+            primary key (module_id, hour)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+    sqlx::query(
+        r#"
+        create index if not exists idx_module_dispatch_stats_server
+            on public.module_dispatch_stats (server_id, hour desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Server presence: offline flag and up/down transitions (see server_presence).
+    sqlx::query(
+        r#"
+        alter table public.servers
+            add column if not exists offline_since timestamptz
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.server_presence_events (
+            id bigserial primary key,
+            server_id text not null references public.servers(id) on delete cascade,
+            online boolean not null,
+            at timestamptz not null
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_server_presence_events_server
+            on public.server_presence_events (server_id, at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Server deletion: soft-delete flag and purge jobs (see server_deletion).
+    sqlx::query(
+        r#"
+        alter table public.servers
+            add column if not exists deleted_at timestamptz
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.server_purge_jobs (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            server_id text not null,
+            status text not null default 'queued',
+            objects_deleted bigint not null default 0,
+            findings_deleted bigint not null default 0,
+            batches_deleted bigint not null default 0,
+            modules_deleted bigint not null default 0,
+            error text,
+            started_at timestamptz,
+            updated_at timestamptz not null default now(),
+            completed_at timestamptz
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_server_purge_jobs_status
+            on public.server_purge_jobs (status, created_at)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_server_purge_jobs_server
+            on public.server_purge_jobs (server_id, created_at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Player erasure jobs (src/privacy.rs)
+    sqlx::query(
+        r#"
+        create table if not exists public.player_deletion_jobs (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            player_uuid uuid,
+            status text not null default 'queued',
+            batches_rewritten bigint not null default 0,
+            records_removed bigint not null default 0,
+            findings_deleted bigint not null default 0,
+            objects_deleted bigint not null default 0,
+            rows_deleted bigint not null default 0,
+            error text,
+            started_at timestamptz,
+            updated_at timestamptz not null default now(),
+            completed_at timestamptz
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_player_deletion_jobs_status
+            on public.player_deletion_jobs (status, created_at)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Player UUID pseudonymization opt-in (src/privacy.rs)
+    sqlx::query(
+        r#"
+        alter table public.servers
+            add column if not exists hash_player_uuids boolean not null default false
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Server takeout archives (src/server_export.rs)
+    sqlx::query(
+        r#"
+        create table if not exists public.server_exports (
+            id uuid primary key,
+            created_at timestamptz not null default now(),
+            server_id text not null references public.servers(id) on delete cascade,
+            status text not null default 'running',
+            include_raw_batches boolean not null default false,
+            s3_key text not null,
+            findings bigint not null default 0,
+            players bigint not null default 0,
+            observations bigint not null default 0,
+            raw_batches bigint not null default 0,
+            raw_batches_truncated boolean not null default false,
+            size_bytes bigint,
+            error text,
+            completed_at timestamptz
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_server_exports_server
+            on public.server_exports (server_id, created_at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Imported findings: the anticheat log they came from (null for our own modules)
+    sqlx::query(
+        r#"
+        alter table public.findings
+            add column if not exists source text
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Module failover: backup endpoints and their health
+    sqlx::query(
+        r#"
+        alter table public.server_modules
+            add column if not exists backup_base_urls text[] not null default '{}'
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.module_endpoints (
+            module_id uuid not null references public.server_modules(id) on delete cascade,
+            base_url text not null,
+            healthy boolean not null,
+            consecutive_failures int not null default 0,
+            last_error text,
+            last_checked_at timestamptz not null default now(),
+            primary key (module_id, base_url)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Module canaries: traffic split and per-variant stats
+    sqlx::query(
+        r#"
+        alter table public.server_modules
+            add column if not exists canary_base_url text
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.server_modules
+            add column if not exists canary_percent int not null default 0
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.module_dispatches
+            add column if not exists variant text
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.module_variant_stats (
+            module_id uuid not null references public.server_modules(id) on delete cascade,
+            day date not null,
+            variant text not null,
+            batches bigint not null default 0,
+            findings bigint not null default 0,
+            primary key (module_id, day, variant)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.module_batch_variants (
+            module_id uuid not null references public.server_modules(id) on delete cascade,
+            batch_id uuid not null,
+            variant text not null,
+            assigned_at timestamptz not null default now(),
+            primary key (module_id, batch_id)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.findings
+            add column if not exists module_variant text
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Shadow-mode modules
+    sqlx::query(
+        r#"
+        alter table public.server_modules
+            add column if not exists mode text not null default 'active'
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.shadow_findings (
+            id uuid primary key default gen_random_uuid(),
+            created_at timestamptz not null default now(),
+            server_id text not null references public.servers(id) on delete cascade,
+            module_id uuid not null references public.server_modules(id) on delete cascade,
+            session_id text,
+            batch_id uuid,
+            player_uuid uuid,
+            detector_name text not null,
+            detector_version text,
+            severity text not null,
+            title text not null,
+            description text,
+            confidence double precision,
+            evidence_s3_key text,
+            evidence_json jsonb
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_shadow_findings_module
+            on public.shadow_findings (module_id, created_at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Module usage accounting
+    sqlx::query(
+        r#"
+        alter table public.module_dispatches
+            add column if not exists payload_bytes bigint
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.module_dispatches
+            add column if not exists transform_us bigint
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.module_usage_daily (
+            module_id uuid not null references public.server_modules(id) on delete cascade,
+            day date not null,
+            server_id text not null references public.servers(id) on delete cascade,
+            dispatches bigint not null default 0,
+            payload_bytes bigint not null default 0,
+            transform_us bigint not null default 0,
+            primary key (module_id, day)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_module_usage_daily_server
+            on public.module_usage_daily (server_id, day)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Dispatch shedding under overload
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists dispatch_status text
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_batch_index_not_dispatched
+            on public.batch_index (received_at) where dispatch_status = 'stored_not_dispatched'
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Background task leases
+    sqlx::query(
+        r#"
+        create table if not exists public.task_leases (
+            task text primary key,
+            holder text not null,
+            acquired_at timestamptz not null default now(),
+            expires_at timestamptz not null,
+            last_finished_at timestamptz
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // API replica membership
+    sqlx::query(
+        r#"
+        create table if not exists public.api_replicas (
+            instance_id text primary key,
+            started_at timestamptz not null default now(),
+            heartbeat_at timestamptz not null default now()
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Batch metadata line fields
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists meta_version int not null default 0
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists plugin_version text
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists protocol_version int
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists mc_version text
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists world text
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists tick_rate double precision
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists player_count int
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Per-server packet filters applied at ingest (see ingest_filters).
+    sqlx::query(
+        r#"
+        create table if not exists public.server_ingest_filters (
+            server_id text primary key references public.servers(id) on delete cascade,
+            filters jsonb not null,
+            updated_at timestamptz not null default now(),
+            updated_by text
+        );
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Sampling of low-risk players' batches (src/sampling.rs)
+    sqlx::query(
+        r#"
+        alter table public.servers
+            add column if not exists sample_rate double precision
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Moderation cases (src/cases.rs)
+    sqlx::query(
+        r#"
+        create table if not exists public.player_cases (
+            id uuid primary key default gen_random_uuid(),
+            server_id text not null references public.servers(id) on delete cascade,
+            player_uuid uuid not null,
+            title text not null,
+            status text not null default 'open',
+            outcome text,
+            summary text,
+            opened_by text,
+            closed_by text,
+            created_at timestamptz not null default now(),
+            updated_at timestamptz not null default now(),
+            closed_at timestamptz
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_player_cases_server
+            on public.player_cases (server_id, updated_at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_player_cases_player
+            on public.player_cases (server_id, player_uuid, created_at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create unique index if not exists uq_player_cases_open
+            on public.player_cases (server_id, player_uuid) where status = 'open'
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.case_items (
+            id uuid primary key default gen_random_uuid(),
+            case_id uuid not null references public.player_cases(id) on delete cascade,
+            finding_id uuid references public.findings(id) on delete cascade,
+            observation_id uuid references public.cheat_observations(id) on delete cascade,
+            evidence_bundle_id uuid references public.evidence_bundles(id) on delete cascade,
+            note text,
+            added_by text,
+            added_at timestamptz not null default now(),
+            check (num_nonnulls(finding_id, observation_id, evidence_bundle_id) = 1)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_case_items_case
+            on public.case_items (case_id, added_at)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.case_actions (
+            id uuid primary key default gen_random_uuid(),
+            case_id uuid not null references public.player_cases(id) on delete cascade,
+            action text not null,
+            details text,
+            taken_by text,
+            created_at timestamptz not null default now()
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_case_actions_case
+            on public.case_actions (case_id, created_at)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        alter table public.batch_index
+            add column if not exists sample_rate double precision
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Moderator notes and finding comments (src/notes.rs)
+    sqlx::query(
+        r#"
+        create table if not exists public.moderator_notes (
+            id uuid primary key default gen_random_uuid(),
+            server_id text not null references public.servers(id) on delete cascade,
+            player_uuid uuid,
+            finding_id uuid references public.findings(id) on delete cascade,
+            author text not null,
+            body text not null,
+            created_at timestamptz not null default now()
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_moderator_notes_player
+            on public.moderator_notes (server_id, player_uuid, created_at desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_moderator_notes_finding
+            on public.moderator_notes (finding_id, created_at desc) where finding_id is not null
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Weekly digest report schedules (src/reports.rs)
+    sqlx::query(
+        r#"
+        create table if not exists public.report_schedules (
+            server_id text primary key references public.servers(id) on delete cascade,
+            enabled boolean not null default false,
+            weekday int not null default 1,
+            hour_utc int not null default 9,
+            format text not null default 'html',
+            webhook_url text,
+            email_recipients text[] not null default '{}',
+            last_period_end timestamptz,
+            last_sent_at timestamptz,
+            last_error text,
+            updated_at timestamptz not null default now()
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Ingest -> finding latency (src/finding_latency.rs)
+    sqlx::query(
+        r#"
+        alter table public.findings
+            add column if not exists batch_received_at timestamptz,
+            add column if not exists ingest_latency_ms int
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_findings_latency
+            on public.findings (server_id, first_seen_at) where ingest_latency_ms is not null
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.finding_latency_daily (
+            server_id text not null references public.servers(id) on delete cascade,
+            detector_name text not null,
+            day date not null,
+            findings int not null,
+            p50_ms double precision,
+            p90_ms double precision,
+            p99_ms double precision,
+            max_ms int,
+            within_slo int not null,
+            slo_ms int not null,
+            updated_at timestamptz not null default now(),
+            primary key (server_id, detector_name, day)
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Player protocol versions (ViaVersion).
+    sqlx::query(
+        r#"
+        alter table public.server_players
+            add column if not exists client_protocol int;
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Module pauses and maintenance windows
+    sqlx::query(
+        r#"
+        alter table public.server_modules
+            add column if not exists paused_until timestamptz
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create table if not exists public.module_maintenance_windows (
+            id uuid primary key default gen_random_uuid(),
+            module_id uuid not null references public.server_modules(id) on delete cascade,
+            weekday smallint,
+            start_minute int not null,
+            duration_minutes int not null,
+            created_at timestamptz not null default now()
+        )
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        create index if not exists idx_module_maintenance_windows_module
+            on public.module_maintenance_windows (module_id)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    // Cursor pagination of a module's dispatch history
+    sqlx::query(
+        r#"
+        create index if not exists idx_module_dispatches_module_time
+            on public.module_dispatches (module_id, created_at desc, id desc)
+        "#,
+    )
+    .execute(db)
+    .await?;
+
     Ok(())
 }
@@ -0,0 +1,155 @@
+//! Per-detector precision stats from finding reviews.
+//!
+//! Every finding status change (dashboard review, or a reviewed observation labeling its linked
+//! findings) moves one count between the `confirmed` / `false_positives` / `dismissed` counters
+//! of `detector_metrics`, so noisy checks can be identified objectively.
+
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+pub const FINDING_STATUSES: &[&str] = &["open", "confirmed", "dismissed", "false_positive"];
+
+/// Counter deltas (confirmed, false_positives, dismissed) for a status.
+fn counters(status: &str) -> (i64, i64, i64) {
+    match status {
+        "confirmed" => (1, 0, 0),
+        "false_positive" => (0, 1, 0),
+        "dismissed" => (0, 0, 1),
+        _ => (0, 0, 0),
+    }
+}
+
+/// Counter deltas (confirmed, false_positives, dismissed) for a finding moving from `old_status`
+/// to `new_status`: one count moves between counters, none for open or an unchanged status.
+pub fn transition(old_status: &str, new_status: &str) -> (i64, i64, i64) {
+    let (oc, of, od) = counters(old_status);
+    let (nc, nf, nd) = counters(new_status);
+    (nc - oc, nf - of, nd - od)
+}
+
+/// Precision over reviewed findings (`None` until at least one is confirmed or rejected).
+pub fn precision(confirmed: i64, false_positives: i64) -> Option<f64> {
+    let reviewed = confirmed + false_positives;
+    (reviewed > 0).then(|| confirmed as f64 / reviewed as f64)
+}
+
+async fn apply_transition(
+    tx: &mut Transaction<'_, Postgres>,
+    server_id: &str,
+    detector_name: &str,
+    old_status: &str,
+    new_status: &str,
+) -> Result<(), sqlx::Error> {
+    let (dc, df, dd) = transition(old_status, new_status);
+    if (dc, df, dd) == (0, 0, 0) {
+        return Ok(());
+    }
+
+    sqlx::query(
+        r#"
+        insert into public.detector_metrics
+            (server_id, detector_name, confirmed, false_positives, dismissed, updated_at)
+        values ($1, $2, greatest($3, 0), greatest($4, 0), greatest($5, 0), now())
+        on conflict (server_id, detector_name) do update set
+            confirmed = greatest(public.detector_metrics.confirmed + $3, 0),
+            false_positives = greatest(public.detector_metrics.false_positives + $4, 0),
+            dismissed = greatest(public.detector_metrics.dismissed + $5, 0),
+            last_false_positive_at = case when $4 > 0 then now()
+                else public.detector_metrics.last_false_positive_at end,
+            updated_at = now()
+        "#,
+    )
+    .bind(server_id)
+    .bind(detector_name)
+    .bind(dc)
+    .bind(df)
+    .bind(dd)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Set a finding's review status and update the detector counters.
+///
+/// Returns the previous status, or `None` if the finding doesn't belong to the server.
+pub async fn set_finding_status(
+    db: &PgPool,
+    server_id: &str,
+    finding_id: Uuid,
+    status: &str,
+    reviewed_by: Option<&str>,
+) -> Result<Option<String>, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let row: Option<(String, String)> = sqlx::query_as(
+        r#"
+        select status, detector_name
+        from public.findings
+        where id = $1 and server_id = $2
+        for update
+        "#,
+    )
+    .bind(finding_id)
+    .bind(server_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some((old_status, detector_name)) = row else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        r#"
+        update public.findings
+        set status = $2, reviewed_at = now(), reviewed_by = coalesce($3, reviewed_by)
+        where id = $1
+        "#,
+    )
+    .bind(finding_id)
+    .bind(status)
+    .bind(reviewed_by)
+    .execute(&mut *tx)
+    .await?;
+
+    apply_transition(&mut tx, server_id, &detector_name, &old_status, status).await?;
+    tx.commit().await?;
+    Ok(Some(old_status))
+}
+
+/// Label the still-open findings linked to an observation (best-effort).
+pub async fn label_observation_findings(
+    db: &PgPool,
+    server_id: &str,
+    observation_id: Uuid,
+    status: &str,
+) {
+    let ids: Result<Vec<(Uuid,)>, _> = sqlx::query_as(
+        r#"
+        select f.id
+        from public.findings f
+        where f.server_id = $2
+          and f.status = 'open'
+          and (f.id in (select finding_id from public.observation_findings where observation_id = $1)
+               or f.id = (select finding_id from public.cheat_observations where id = $1))
+        "#,
+    )
+    .bind(observation_id)
+    .bind(server_id)
+    .fetch_all(db)
+    .await;
+
+    let ids = match ids {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::warn!(observation_id = %observation_id, "observation findings lookup failed: {:?}", e);
+            return;
+        }
+    };
+
+    let reviewer = format!("observation:{}", observation_id);
+    for (finding_id,) in ids {
+        if let Err(e) = set_finding_status(db, server_id, finding_id, status, Some(&reviewer)).await
+        {
+            tracing::warn!(finding_id = %finding_id, "labeling observation finding failed: {:?}", e);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Content-encoding negotiation for batches dispatched to HTTP modules.
+//!
+//! Transforms produce gzipped NDJSON, and modules that say nothing keep receiving exactly that.
+//! A module can list the encodings it accepts, either when it is registered
+//! (`accept_encoding` on `POST /servers/:server_id/modules`) or as an `Accept-Encoding` header
+//! on its `/health` response (RFC 7694). The chosen encoding is stored on the module
+//! (`server_modules.content_encoding`):
+//!
+//! - modules on a loopback address prefer `identity`: the bytes never leave the host, so
+//!   skipping compression saves the module the decode
+//! - remote modules prefer `zstd`, which is smaller than gzip for NDJSON at similar CPU cost
+//!
+//! Modules built on `module_sdk::router` advertise all three and decode with [`decode`].
+//! Only the live dispatch negotiates; reprocessing, replays and conformance runs send gzip,
+//! which every module accepts.
+
+use std::io::{Read, Write};
+
+use bytes::Bytes;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentEncoding {
+    #[default]
+    Gzip,
+    Zstd,
+    Identity,
+}
+
+const LOOPBACK_PREFERENCE: [ContentEncoding; 3] = [
+    ContentEncoding::Identity,
+    ContentEncoding::Gzip,
+    ContentEncoding::Zstd,
+];
+const REMOTE_PREFERENCE: [ContentEncoding; 3] = [
+    ContentEncoding::Zstd,
+    ContentEncoding::Gzip,
+    ContentEncoding::Identity,
+];
+
+/// zstd level for dispatch payloads (the library default; higher levels cost far more CPU).
+const ZSTD_LEVEL: i32 = 3;
+
+impl ContentEncoding {
+    pub const ALL: [&'static str; 3] = ["gzip", "zstd", "identity"];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Self::Gzip),
+            "zstd" => Some(Self::Zstd),
+            "identity" => Some(Self::Identity),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+            Self::Identity => "identity",
+        }
+    }
+
+    /// `Content-Encoding` header value; none for `identity`.
+    pub fn header_value(self) -> Option<&'static str> {
+        match self {
+            Self::Identity => None,
+            other => Some(other.as_str()),
+        }
+    }
+}
+
+/// Encodings listed in an `Accept-Encoding` value, e.g. `zstd, gzip;q=0.5`. Unknown codings,
+/// `*` and `q=0` entries are left out.
+pub fn parse_accepted(value: &str) -> Vec<ContentEncoding> {
+    let mut accepted = Vec::new();
+    for item in value.split(',') {
+        let mut parts = item.split(';');
+        let coding = parts.next().unwrap_or("");
+        let refused = parts.any(|p| {
+            p.trim()
+                .strip_prefix("q=")
+                .and_then(|q| q.trim().parse::<f64>().ok())
+                .is_some_and(|q| q <= 0.0)
+        });
+        if refused {
+            continue;
+        }
+        if let Some(enc) = ContentEncoding::parse(coding) {
+            if !accepted.contains(&enc) {
+                accepted.push(enc);
+            }
+        }
+    }
+    accepted
+}
+
+/// Whether the module's base URL points at this host.
+pub fn is_loopback(base_url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(base_url.trim()) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    match host.parse::<std::net::IpAddr>() {
+        Ok(ip) => ip.is_loopback(),
+        Err(_) => host.eq_ignore_ascii_case("localhost"),
+    }
+}
+
+/// The encoding to dispatch with, given what the module accepts. Modules that list nothing
+/// (or nothing we produce) get gzip.
+pub fn negotiate(base_url: &str, accepted: &[ContentEncoding]) -> ContentEncoding {
+    let preference = if is_loopback(base_url) {
+        LOOPBACK_PREFERENCE
+    } else {
+        REMOTE_PREFERENCE
+    };
+    preference
+        .into_iter()
+        .find(|e| accepted.contains(e))
+        .unwrap_or_default()
+}
+
+/// Re-encode a gzipped payload for the module. Gzip shares the payload's buffer.
+pub fn encode(payload_gz: Bytes, encoding: ContentEncoding) -> anyhow::Result<Bytes> {
+    if encoding == ContentEncoding::Gzip {
+        return Ok(payload_gz);
+    }
+    let mut plain = Vec::with_capacity(payload_gz.len() * 4);
+    GzDecoder::new(&payload_gz[..]).read_to_end(&mut plain)?;
+    match encoding {
+        ContentEncoding::Identity => Ok(plain.into()),
+        _ => encode_plain(&plain, encoding).map(Bytes::from),
+    }
+}
+
+/// Encode plain NDJSON.
+pub fn encode_plain(plain: &[u8], encoding: ContentEncoding) -> anyhow::Result<Vec<u8>> {
+    match encoding {
+        ContentEncoding::Gzip => {
+            let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+            enc.write_all(plain)?;
+            Ok(enc.finish()?)
+        }
+        ContentEncoding::Zstd => Ok(zstd::encode_all(plain, ZSTD_LEVEL)?),
+        ContentEncoding::Identity => Ok(plain.to_vec()),
+    }
+}
+
+/// Decode a dispatched body to plain NDJSON from its `Content-Encoding`. Bodies without one
+/// that start with the gzip magic bytes are gzip (what modules received before negotiation).
+pub fn decode(body: &[u8], content_encoding: Option<&str>) -> anyhow::Result<Vec<u8>> {
+    let encoding = match content_encoding.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(value) => ContentEncoding::parse(value)
+            .ok_or_else(|| anyhow::anyhow!("unsupported content-encoding: {}", value))?,
+        None if body.starts_with(&[0x1f, 0x8b]) => ContentEncoding::Gzip,
+        None => ContentEncoding::Identity,
+    };
+    let mut plain = Vec::new();
+    match encoding {
+        ContentEncoding::Gzip => {
+            GzDecoder::new(body).read_to_end(&mut plain)?;
+        }
+        ContentEncoding::Zstd => plain = zstd::decode_all(body)?,
+        ContentEncoding::Identity => plain = body.to_vec(),
+    }
+    Ok(plain)
+}
@@ -0,0 +1,92 @@
+//! Splitting large dispatches into sequenced parts.
+//!
+//! Modules often sit behind a proxy or framework with a small request-size limit and answer
+//! 413 to a big batch. When an encoded payload is larger than `DISPATCH_PART_MAX_BYTES`, it is
+//! cut on line boundaries into parts of at most that many uncompressed bytes (so every encoded
+//! part fits too) and posted one after another. Each part starts with the batch metadata line,
+//! so a module can process it on its own, and carries:
+//!
+//! - `X-Batch-Part`: 0-based part index
+//! - `X-Batch-Parts`: number of parts
+//! - `X-Batch-Final: true` on the last part
+//!
+//! Single-part dispatches carry none of these. The parts of a batch are sent in order and
+//! sending stops at the first part the module rejects.
+
+use std::io::Read;
+
+use bytes::Bytes;
+use flate2::read::GzDecoder;
+
+use crate::dispatch_encoding::{self, ContentEncoding};
+
+pub const PART_HEADER: &str = "x-batch-part";
+pub const PARTS_HEADER: &str = "x-batch-parts";
+pub const FINAL_HEADER: &str = "x-batch-final";
+
+/// Where a part sits in its batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DispatchPart {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl DispatchPart {
+    pub fn is_final(&self) -> bool {
+        self.index + 1 == self.count
+    }
+}
+
+/// Split plain NDJSON into parts of at most `max_part_bytes`, each starting with the metadata
+/// (first) line. Records are never split, so a record longer than the limit gets a part of its
+/// own.
+pub fn split_ndjson(plain: &[u8], max_part_bytes: usize) -> Vec<Vec<u8>> {
+    let mut lines = plain
+        .split(|b| *b == b'\n')
+        .filter(|l| !l.iter().all(u8::is_ascii_whitespace));
+    let Some(meta) = lines.next() else {
+        return vec![plain.to_vec()];
+    };
+
+    let mut parts = Vec::new();
+    let mut current: Vec<u8> = Vec::new();
+    for line in lines {
+        if !current.is_empty() && current.len() + line.len() + 1 > max_part_bytes {
+            parts.push(std::mem::take(&mut current));
+        }
+        if current.is_empty() {
+            current.extend_from_slice(meta);
+            current.push(b'\n');
+        }
+        current.extend_from_slice(line);
+        current.push(b'\n');
+    }
+    if !current.is_empty() || parts.is_empty() {
+        if current.is_empty() {
+            current.extend_from_slice(meta);
+            current.push(b'\n');
+        }
+        parts.push(current);
+    }
+    parts
+}
+
+/// Encode a gzipped transform output for a module, in parts when it is larger than
+/// `max_part_bytes` (0 disables splitting). An unsplit gzip payload is passed on without a copy.
+pub fn encode_parts(
+    payload_gz: Bytes,
+    encoding: ContentEncoding,
+    max_part_bytes: usize,
+) -> anyhow::Result<Vec<Bytes>> {
+    let whole = dispatch_encoding::encode(payload_gz.clone(), encoding)?;
+    if max_part_bytes == 0 || whole.len() <= max_part_bytes {
+        return Ok(vec![whole]);
+    }
+    drop(whole);
+    let mut plain = Vec::with_capacity(payload_gz.len() * 4);
+    GzDecoder::new(&payload_gz[..]).read_to_end(&mut plain)?;
+    split_ndjson(&plain, max_part_bytes)
+        .iter()
+        .map(|part| dispatch_encoding::encode_plain(part, encoding).map(Bytes::from))
+        .collect()
+}
@@ -0,0 +1,133 @@
+//! Shedding module dispatch under overload.
+//!
+//! When the API is overloaded (load pressure from `ingest_hints` at [`SHED_PRESSURE`]: the
+//! dispatch backlog or the database pool is full), ingest still stores the raw batch and
+//! acknowledges it, but skips module dispatch and marks the batch
+//! `dispatch_status = 'stored_not_dispatched'`. Storing is what plugins can't redo; dispatch can
+//! happen later.
+//!
+//! [`recovery_tick`] dispatches those batches oldest first once pressure is back below
+//! [`RESUME_PRESSURE`], up to [`RECOVERY_BATCHES_PER_TICK`] per run and stopping as soon as load
+//! climbs again. Every replica runs it, each for the servers it owns (see `replica_shards`). A dispatched batch becomes `recovered`; one whose object is gone becomes `lost`.
+//! Quarantined batches are never dispatched, so they are never shed either.
+
+use crate::ingest_hints::Load;
+use crate::{module_pipeline, AppState};
+
+pub const STORED_NOT_DISPATCHED: &str = "stored_not_dispatched";
+pub const RECOVERED: &str = "recovered";
+pub const LOST: &str = "lost";
+
+/// Pressure from which new batches are stored without dispatch.
+pub const SHED_PRESSURE: f64 = 1.0;
+/// Pressure below which shed batches are dispatched again.
+pub const RESUME_PRESSURE: f64 = 0.5;
+pub const RECOVERY_INTERVAL_SECONDS: u64 = 30;
+pub const RECOVERY_BATCHES_PER_TICK: usize = 64;
+
+/// Whether a new batch should skip module dispatch.
+pub fn sheds_dispatch(load: &Load) -> bool {
+    load.pressure() >= SHED_PRESSURE
+}
+
+/// Whether there is room to dispatch shed batches.
+pub fn can_recover(load: &Load) -> bool {
+    load.pressure() < RESUME_PRESSURE
+}
+
+/// Dispatch shed batches while load allows it.
+pub async fn recovery_tick(state: AppState) {
+    if !can_recover(&Load::current(&state.dispatch_backlog, &state.db)) {
+        return;
+    }
+    let waiting: Vec<String> = match sqlx::query_scalar(
+        "select distinct server_id from public.batch_index where dispatch_status = $1",
+    )
+    .bind(STORED_NOT_DISPATCHED)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(servers) => servers,
+        Err(e) => {
+            tracing::warn!("shed batch lookup failed: {:?}", e);
+            return;
+        }
+    };
+    let owned: Vec<String> = waiting
+        .into_iter()
+        .filter(|server_id| state.shards.owns(server_id))
+        .collect();
+    if owned.is_empty() {
+        return;
+    }
+
+    let mut recovered = 0;
+    for _ in 0..RECOVERY_BATCHES_PER_TICK {
+        if !can_recover(&Load::current(&state.dispatch_backlog, &state.db)) {
+            break;
+        }
+
+        // Claiming flips the status, so concurrent workers never dispatch a batch twice.
+        let claimed: Result<Option<(uuid::Uuid, String, String, String)>, sqlx::Error> =
+            sqlx::query_as(
+                r#"
+                update public.batch_index set dispatch_status = $2
+                where id = (
+                    select id from public.batch_index
+                    where dispatch_status = $1 and server_id = any($3)
+                    order by received_at asc
+                    limit 1
+                    for update skip locked
+                )
+                returning id, server_id, session_id, s3_key
+                "#,
+            )
+            .bind(STORED_NOT_DISPATCHED)
+            .bind(RECOVERED)
+            .bind(&owned)
+            .fetch_optional(&state.db)
+            .await;
+        let (batch_id, server_id, session_id, s3_key) = match claimed {
+            Ok(Some(row)) => row,
+            Ok(None) => break,
+            Err(e) => {
+                tracing::warn!("shed batch claim failed: {:?}", e);
+                break;
+            }
+        };
+
+        let raw = match state.object_store.get_batch(&s3_key).await {
+            Ok(raw) => raw,
+            Err(e) => {
+                tracing::warn!(batch_id = %batch_id, s3_key = %s3_key, "shed batch object unavailable: {:?}", e);
+                let _ =
+                    sqlx::query("update public.batch_index set dispatch_status = $2 where id = $1")
+                        .bind(batch_id)
+                        .bind(LOST)
+                        .execute(&state.db)
+                        .await;
+                continue;
+            }
+        };
+
+        let backlog = state.dispatch_backlog.track();
+        if let Err(e) = module_pipeline::dispatch_batch(
+            state.clone(),
+            server_id,
+            session_id,
+            batch_id,
+            s3_key,
+            raw.into(),
+        )
+        .await
+        {
+            tracing::warn!(batch_id = %batch_id, "shed batch dispatch failed: {:?}", e);
+        }
+        drop(backlog);
+        recovered += 1;
+    }
+
+    if recovered > 0 {
+        tracing::info!(batches = recovered, "dispatched shed batches");
+    }
+}
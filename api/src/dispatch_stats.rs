@@ -0,0 +1,155 @@
+//! Module dispatch history: hourly rollups and retention.
+//!
+//! `module_dispatches` gets a row per module per batch, so it is only kept for
+//! `MODULE_DISPATCH_RETENTION_DAYS`. Once an hour, complete hours are rolled up into
+//! `module_dispatch_stats` (counts by outcome, average and p95 latency) and raw rows past the
+//! retention are deleted. The last [`RECOMPUTE_HOURS`] are recomputed on every run because async
+//! dispatches can still finish (or time out) up to a day later; older hours are rolled up once,
+//! which also covers rows left over after downtime. Dashboard views read the rollups, plus the
+//! current hour from the raw rows.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{module_usage, AppState};
+
+pub const COMPACTION_INTERVAL_SECONDS: u64 = 3600;
+/// Covers the longest async processing deadline.
+pub const RECOMPUTE_HOURS: i32 = 25;
+pub const STATS_RETENTION_DAYS: i32 = 90;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DispatchStatsHour {
+    pub hour: DateTime<Utc>,
+    pub dispatches: i64,
+    /// `sent`, or `completed` for async dispatches.
+    pub succeeded: i64,
+    pub failed: i64,
+    pub timed_out: i64,
+    pub avg_latency_ms: Option<f64>,
+    pub p95_latency_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DispatchStatsTotals {
+    pub dispatches: i64,
+    pub succeeded: i64,
+    pub failed: i64,
+    pub timed_out: i64,
+    /// Share of finished dispatches that succeeded (none before any finished).
+    pub success_rate: Option<f64>,
+    /// Highest hourly p95 latency in the range.
+    pub max_p95_latency_ms: Option<f64>,
+}
+
+/// Sum hourly rows. Dispatches still processing count in `dispatches` only.
+pub fn totals(hours: &[DispatchStatsHour]) -> DispatchStatsTotals {
+    let (mut dispatches, mut succeeded, mut failed, mut timed_out) = (0, 0, 0, 0);
+    let mut max_p95: Option<f64> = None;
+    for h in hours {
+        dispatches += h.dispatches;
+        succeeded += h.succeeded;
+        failed += h.failed;
+        timed_out += h.timed_out;
+        if let Some(p95) = h.p95_latency_ms {
+            max_p95 = Some(max_p95.map_or(p95, |m| m.max(p95)));
+        }
+    }
+    let finished = succeeded + failed + timed_out;
+    DispatchStatsTotals {
+        dispatches,
+        succeeded,
+        failed,
+        timed_out,
+        success_rate: (finished > 0).then(|| succeeded as f64 / finished as f64),
+        max_p95_latency_ms: max_p95,
+    }
+}
+
+/// Roll up complete hours, then purge raw rows and rollups past their retention.
+pub async fn compaction_tick(state: AppState) {
+    let rolled_up = sqlx::query(
+        r#"
+        insert into public.module_dispatch_stats
+            (module_id, hour, server_id, dispatches, succeeded, failed, timed_out,
+             avg_latency_ms, p95_latency_ms, updated_at)
+        select
+            d.module_id,
+            date_trunc('hour', d.created_at) as hour,
+            min(d.server_id),
+            count(*),
+            count(*) filter (where d.status in ('sent', 'completed')),
+            count(*) filter (where d.status = 'failed'),
+            count(*) filter (where d.status = 'timed_out'),
+            avg(d.latency_ms),
+            percentile_cont(0.95) within group (order by d.latency_ms),
+            now()
+        from public.module_dispatches d
+        where d.created_at < date_trunc('hour', now())
+          and (
+              d.created_at >= date_trunc('hour', now()) - make_interval(hours => $1)
+              or not exists (
+                  select 1 from public.module_dispatch_stats s
+                  where s.module_id = d.module_id
+                    and s.hour = date_trunc('hour', d.created_at)
+              )
+          )
+        group by d.module_id, date_trunc('hour', d.created_at)
+        on conflict (module_id, hour) do update set
+            dispatches = excluded.dispatches,
+            succeeded = excluded.succeeded,
+            failed = excluded.failed,
+            timed_out = excluded.timed_out,
+            avg_latency_ms = excluded.avg_latency_ms,
+            p95_latency_ms = excluded.p95_latency_ms,
+            updated_at = now()
+        "#,
+    )
+    .bind(RECOMPUTE_HOURS)
+    .execute(&state.db)
+    .await;
+    let hours = match rolled_up {
+        Ok(r) => r.rows_affected(),
+        Err(e) => {
+            // Don't purge rows that were never rolled up.
+            tracing::warn!("module dispatch rollup failed: {:?}", e);
+            return;
+        }
+    };
+
+    let purged = sqlx::query(
+        "delete from public.module_dispatches where created_at < now() - make_interval(days => $1)",
+    )
+    .bind(state.module_dispatch_retention_days as i32)
+    .execute(&state.db)
+    .await;
+    let purged = match purged {
+        Ok(r) => r.rows_affected(),
+        Err(e) => {
+            tracing::warn!("module dispatch purge failed: {:?}", e);
+            0
+        }
+    };
+
+    let _ = sqlx::query(
+        "delete from public.module_dispatch_stats where hour < now() - make_interval(days => $1)",
+    )
+    .bind(STATS_RETENTION_DAYS)
+    .execute(&state.db)
+    .await;
+    let _ = sqlx::query(
+        "delete from public.module_batch_variants \
+         where assigned_at < now() - make_interval(days => $1)",
+    )
+    .bind(state.module_dispatch_retention_days as i32)
+    .execute(&state.db)
+    .await;
+    module_usage::purge(&state.db).await;
+
+    tracing::info!(
+        hours_rolled_up = hours,
+        dispatches_purged = purged,
+        "module dispatch compaction finished"
+    );
+}
@@ -1,9 +1,13 @@
 use axum::{
-    http::StatusCode,
+    http::{header::RETRY_AFTER, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use utoipa::ToSchema;
+
+use crate::request_id;
 
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
@@ -11,22 +15,105 @@ pub enum ApiError {
     Unauthorized,
     #[error("bad request: {0}")]
     BadRequest(String),
+    #[error("{0}")]
+    NotFound(String),
+    /// The request is valid but clashes with the resource's current state.
+    #[error("{0}")]
+    Conflict(String),
+    /// The resource existed but was deleted (e.g. a decommissioned server).
+    #[error("{0}")]
+    Gone(String),
+    #[error("payload too large: {size} bytes (max {max})")]
+    PayloadTooLarge { size: usize, max: usize },
+    #[error("rate limited")]
+    RateLimited { retry_after_seconds: Option<u64> },
+    /// A module the API called on the caller's behalf failed.
+    #[error("module {module} failed: {message}")]
+    UpstreamModuleError { module: String, message: String },
     #[error("internal error")]
     Internal,
 }
 
-#[derive(Serialize)]
-struct ErrorBody {
-    error: String,
+impl ApiError {
+    /// Stable machine-readable code, sent as `code` in the error body.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::Gone(_) => "gone",
+            ApiError::PayloadTooLarge { .. } => "payload_too_large",
+            ApiError::RateLimited { .. } => "rate_limited",
+            ApiError::UpstreamModuleError { .. } => "upstream_module_error",
+            ApiError::Internal => "internal",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::Gone(_) => StatusCode::GONE,
+            ApiError::PayloadTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::UpstreamModuleError { .. } => StatusCode::BAD_GATEWAY,
+            ApiError::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Structured fields for clients that branch on more than the code.
+    pub fn details(&self) -> Option<Value> {
+        match self {
+            ApiError::PayloadTooLarge { size, max } => {
+                Some(json!({ "size_bytes": size, "max_bytes": max }))
+            }
+            ApiError::RateLimited {
+                retry_after_seconds: Some(secs),
+            } => Some(json!({ "retry_after_seconds": secs })),
+            ApiError::UpstreamModuleError { module, .. } => Some(json!({ "module": module })),
+            _ => None,
+        }
+    }
+
+    pub fn body(&self) -> ErrorBody {
+        ErrorBody {
+            error: self.to_string(),
+            code: self.code().to_string(),
+            details: self.details(),
+            request_id: request_id::current(),
+        }
+    }
+}
+
+/// Body of every error response.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ErrorBody {
+    /// Human-readable message.
+    pub error: String,
+    /// unauthorized | bad_request | not_found | conflict | gone | payload_too_large |
+    /// rate_limited | upstream_module_error | internal
+    #[serde(default)]
+    pub code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+    /// Echo of the `X-Request-Id` response header; quote it when reporting a failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status, msg) = match &self {
-            ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, self.to_string()),
-            ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, self.to_string()),
-            ApiError::Internal => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
-        };
-        (status, Json(ErrorBody { error: msg })).into_response()
+        let mut resp = (self.status(), Json(self.body())).into_response();
+        if let ApiError::RateLimited {
+            retry_after_seconds: Some(secs),
+        } = self
+        {
+            resp.headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(secs));
+        }
+        resp
     }
 }
@@ -0,0 +1,435 @@
+//! Evidence bundles for ban appeals.
+//!
+//! A reviewer handling an appeal needs more than the finding row: what else the player was
+//! flagged for around the same time, what the module actually saw, and the state it had built
+//! up. `build` assembles all of it into one JSON document:
+//!
+//! - the finding and the player's other findings within [`RELATED_WINDOW_MINUTES`]
+//! - event excerpts: the player's records from the batches received around the finding
+//!   ([`EXCERPT_BEFORE_SECONDS`] before its first occurrence to [`EXCERPT_AFTER_SECONDS`] after
+//!   its last), run through the transform of the module that reported it (by the finding's
+//!   `module_id`, or detector name prefix for older findings) and capped at
+//!   [`MAX_EXCERPT_EVENTS`]
+//! - the player's module state snapshots (`module_player_state`)
+//!
+//! The bundle is gzipped and stored in the object store under `bundles/` (outside the batch
+//! TTL), and recorded in `evidence_bundles` so it can be downloaded later.
+
+use chrono::{DateTime, Duration, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{error::ApiError, transforms, AppState};
+
+pub const BUNDLE_VERSION: u32 = 1;
+pub const RELATED_WINDOW_MINUTES: i64 = 30;
+pub const MAX_RELATED_FINDINGS: i64 = 50;
+pub const EXCERPT_BEFORE_SECONDS: i64 = 60;
+pub const EXCERPT_AFTER_SECONDS: i64 = 10;
+pub const MAX_EXCERPT_BATCHES: i64 = 20;
+pub const MAX_EXCERPT_EVENTS: usize = 5000;
+
+/// The server module whose detectors are named `<module name>_...`, for findings stored
+/// without a `module_id`.
+pub fn module_for_detector<'a>(
+    modules: &'a [(String, String)],
+    detector_name: &str,
+) -> Option<&'a (String, String)> {
+    let detector = detector_name.trim().to_ascii_lowercase();
+    modules.iter().find(|(name, _)| {
+        let prefix = format!("{}_", name.trim().to_ascii_lowercase().replace(' ', "_"));
+        detector.starts_with(&prefix)
+    })
+}
+
+/// The player's records of a (transformed) gzipped NDJSON batch with `ts` in `[from_ms, to_ms]`,
+/// at most `max`. Returns the records and whether more matched.
+pub fn excerpt(
+    gz_ndjson: &[u8],
+    player: Uuid,
+    from_ms: i64,
+    to_ms: i64,
+    max: usize,
+) -> anyhow::Result<(Vec<Value>, bool)> {
+    let player = player.to_string();
+    let mut events = Vec::new();
+    for line in BufReader::new(GzDecoder::new(gz_ndjson)).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(record) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if record.get("uuid").and_then(Value::as_str) != Some(player.as_str()) {
+            continue;
+        }
+        let Some(ts) = record.get("ts").and_then(Value::as_i64) else {
+            continue;
+        };
+        if ts < from_ms || ts > to_ms {
+            continue;
+        }
+        if events.len() == max {
+            return Ok((events, true));
+        }
+        events.push(record);
+    }
+    Ok((events, false))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EvidenceBundle {
+    pub bundle_id: Uuid,
+    pub finding_id: Uuid,
+    pub s3_key: String,
+    pub size_bytes: i64,
+    pub related_findings: usize,
+    pub events: usize,
+    pub batches_scanned: usize,
+    pub created_at: DateTime<Utc>,
+}
+
+type FindingRow = (
+    Uuid,
+    Option<Uuid>,
+    Option<String>,
+    String,
+    Option<String>,
+    String,
+    String,
+    Option<String>,
+    Option<Value>,
+    Option<String>,
+    i32,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    String,
+    Option<f64>,
+    Option<String>,
+);
+
+fn finding_json(row: &FindingRow) -> Value {
+    let (
+        id,
+        player_uuid,
+        session_id,
+        detector_name,
+        detector_version,
+        severity,
+        title,
+        description,
+        evidence_json,
+        evidence_s3_key,
+        occurrences,
+        first_seen_at,
+        last_seen_at,
+        status,
+        confidence,
+        world,
+    ) = row;
+    json!({
+        "id": id,
+        "player_uuid": player_uuid,
+        "session_id": session_id,
+        "detector_name": detector_name,
+        "detector_version": detector_version,
+        "severity": severity,
+        "title": title,
+        "description": description,
+        "evidence_json": evidence_json,
+        "evidence_s3_key": evidence_s3_key,
+        "occurrences": occurrences,
+        "first_seen_at": first_seen_at,
+        "last_seen_at": last_seen_at,
+        "status": status,
+        "confidence": confidence,
+        "world": world,
+    })
+}
+
+const FINDING_COLUMNS: &str =
+    "id, player_uuid, session_id, detector_name, detector_version, severity, \
+     title, description, evidence_json, evidence_s3_key, occurrences, first_seen_at, \
+     last_seen_at, status, confidence, world";
+
+fn db_error(what: &'static str) -> impl Fn(sqlx::Error) -> ApiError {
+    move |e| {
+        tracing::error!("evidence bundle {} failed: {:?}", what, e);
+        ApiError::Internal
+    }
+}
+
+/// Assemble, store and record the evidence bundle of a finding.
+pub async fn build(
+    state: &AppState,
+    server_id: &str,
+    finding_id: Uuid,
+) -> Result<EvidenceBundle, ApiError> {
+    let row: Option<FindingRow> = sqlx::query_as(&format!(
+        "select {} from public.findings where id = $1 and server_id = $2",
+        FINDING_COLUMNS
+    ))
+    .bind(finding_id)
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error("finding lookup"))?;
+    let Some(row) = row else {
+        return Err(ApiError::NotFound(format!(
+            "finding {} not found",
+            finding_id
+        )));
+    };
+    let Some(player_uuid) = row.1 else {
+        return Err(ApiError::BadRequest(
+            "finding has no player; nothing to bundle".to_string(),
+        ));
+    };
+    let (session_id, detector_name, first_seen_at, last_seen_at) =
+        (row.2.clone(), row.3.clone(), row.11, row.12);
+
+    // Other findings of the player around this one.
+    let related: Vec<FindingRow> = sqlx::query_as(&format!(
+        r#"
+        select {} from public.findings
+        where server_id = $1 and player_uuid = $2 and id <> $3
+          and last_seen_at >= $4 - make_interval(mins => $6)
+          and first_seen_at <= $5 + make_interval(mins => $6)
+        order by first_seen_at asc
+        limit $7
+        "#,
+        FINDING_COLUMNS
+    ))
+    .bind(server_id)
+    .bind(player_uuid)
+    .bind(finding_id)
+    .bind(first_seen_at)
+    .bind(last_seen_at)
+    .bind(RELATED_WINDOW_MINUTES as i32)
+    .bind(MAX_RELATED_FINDINGS)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error("related findings lookup"))?;
+
+    // The reporting module decides which transform the excerpts go through. Findings stored
+    // before attribution have no module_id; match those by detector name prefix.
+    let attributed: Option<(String, String)> = sqlx::query_as(
+        r#"
+        select m.name, m.transform
+        from public.findings f
+        join public.server_modules m on m.id = f.module_id
+        where f.id = $1
+        "#,
+    )
+    .bind(finding_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error("module lookup"))?;
+    let module = match attributed {
+        Some(module) => Some(module),
+        None => {
+            let modules: Vec<(String, String)> = sqlx::query_as(
+                "select name, transform from public.server_modules where server_id = $1 order by name",
+            )
+            .bind(server_id)
+            .fetch_all(&state.db)
+            .await
+            .map_err(db_error("module lookup"))?;
+            module_for_detector(&modules, &detector_name).cloned()
+        }
+    };
+    let transform = module
+        .as_ref()
+        .map(|(_, t)| t.clone())
+        .filter(|t| transforms::is_supported(t))
+        .unwrap_or_else(|| "raw_ndjson_gz".to_string());
+
+    let from = first_seen_at - Duration::seconds(EXCERPT_BEFORE_SECONDS);
+    let to = last_seen_at + Duration::seconds(EXCERPT_AFTER_SECONDS);
+    let batches: Vec<(Uuid, String, Option<i64>)> = sqlx::query_as(
+        r#"
+        select id, s3_key, clock_skew_ms
+        from public.batch_index
+        where server_id = $1
+          and received_at >= $2 and received_at <= $3 + make_interval(secs => $5)
+          and ($4::text is null or session_id = $4)
+          and not quarantined
+        order by received_at asc
+        limit $6
+        "#,
+    )
+    .bind(server_id)
+    .bind(from)
+    .bind(to)
+    .bind(session_id.as_deref())
+    .bind(EXCERPT_BEFORE_SECONDS as f64)
+    .bind(MAX_EXCERPT_BATCHES)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error("batch lookup"))?;
+
+    let server_time = transforms::wants_server_time(&transform);
+    let mut events: Vec<Value> = Vec::new();
+    let mut truncated = false;
+    let mut batches_scanned = 0usize;
+    for (batch_id, s3_key, skew) in &batches {
+        if events.len() >= MAX_EXCERPT_EVENTS {
+            truncated = true;
+            break;
+        }
+        let raw = match state.object_store.get_batch(s3_key).await {
+            Ok(raw) => raw,
+            Err(e) => {
+                tracing::warn!(batch_id = %batch_id, "evidence bundle batch unavailable: {:?}", e);
+                continue;
+            }
+        };
+        // Record `ts` is on the plugin clock unless the transform rebased it.
+        let skew = *skew;
+        let shift = if server_time { 0 } else { skew.unwrap_or(0) };
+        let from_ms = from.timestamp_millis() + shift;
+        let to_ms = to.timestamp_millis() + shift;
+        let transform = transform.clone();
+        let budget = MAX_EXCERPT_EVENTS - events.len();
+        let res = tokio::task::spawn_blocking(move || {
+            let transformed = transforms::apply_transform_with(&transform, &raw, skew)?;
+            excerpt(&transformed, player_uuid, from_ms, to_ms, budget)
+        })
+        .await;
+        match res {
+            Ok(Ok((batch_events, more))) => {
+                batches_scanned += 1;
+                truncated |= more;
+                events.extend(batch_events.into_iter().map(|mut e| {
+                    if let Some(obj) = e.as_object_mut() {
+                        obj.insert("batch_id".to_string(), json!(batch_id));
+                    }
+                    e
+                }));
+            }
+            Ok(Err(e)) => {
+                tracing::warn!(batch_id = %batch_id, "evidence bundle transform failed: {:?}", e)
+            }
+            Err(e) => tracing::error!("evidence bundle task failed: {:?}", e),
+        }
+    }
+
+    let states: Vec<(String, Value, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        select module_name, state_json, updated_at from public.module_player_state
+        where server_id = $1 and player_uuid = $2
+        order by module_name
+        "#,
+    )
+    .bind(server_id)
+    .bind(player_uuid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error("module state lookup"))?;
+
+    let bundle_id = Uuid::new_v4();
+    let created_at = Utc::now();
+    let document = json!({
+        "version": BUNDLE_VERSION,
+        "bundle_id": bundle_id,
+        "generated_at": created_at,
+        "server_id": server_id,
+        "finding": finding_json(&row),
+        "related_findings": related.iter().map(finding_json).collect::<Vec<_>>(),
+        "module": module.as_ref().map(|(name, _)| name),
+        "excerpts": {
+            "transform": transform,
+            "from": from,
+            "to": to,
+            "clock": if server_time { "server" } else { "client" },
+            "batches": batches.iter().map(|(id, _, _)| id).collect::<Vec<_>>(),
+            "batches_scanned": batches_scanned,
+            "truncated": truncated,
+            "events": events,
+        },
+        "module_state": states.iter().map(|(module_name, state_json, updated_at)| json!({
+            "module_name": module_name,
+            "state": state_json,
+            "updated_at": updated_at,
+        })).collect::<Vec<_>>(),
+    });
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    let gz = encoder
+        .write_all(document.to_string().as_bytes())
+        .and_then(|_| encoder.finish())
+        .map_err(|e| {
+            tracing::error!("evidence bundle encode failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    let size_bytes = gz.len() as i64;
+    let s3_key = state
+        .object_store
+        .put_bundle(server_id, &finding_id, &bundle_id, gz)
+        .await
+        .map_err(|e| {
+            tracing::error!("evidence bundle upload failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    sqlx::query(
+        r#"
+        insert into public.evidence_bundles
+            (id, server_id, finding_id, s3_key, size_bytes, events, created_at)
+        values ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(bundle_id)
+    .bind(server_id)
+    .bind(finding_id)
+    .bind(&s3_key)
+    .bind(size_bytes)
+    .bind(events.len() as i32)
+    .bind(created_at)
+    .execute(&state.db)
+    .await
+    .map_err(db_error("record"))?;
+
+    Ok(EvidenceBundle {
+        bundle_id,
+        finding_id,
+        s3_key,
+        size_bytes,
+        related_findings: related.len(),
+        events: events.len(),
+        batches_scanned,
+        created_at,
+    })
+}
+
+/// The stored bundle (gzipped JSON) and its finding, for download.
+pub async fn load(
+    state: &AppState,
+    server_id: &str,
+    bundle_id: Uuid,
+) -> Result<(Uuid, Vec<u8>), ApiError> {
+    let row: Option<(Uuid, String)> = sqlx::query_as(
+        "select finding_id, s3_key from public.evidence_bundles where id = $1 and server_id = $2",
+    )
+    .bind(bundle_id)
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error("lookup"))?;
+    let Some((finding_id, s3_key)) = row else {
+        return Err(ApiError::NotFound(format!(
+            "bundle {} not found",
+            bundle_id
+        )));
+    };
+    let bytes = state.object_store.get_batch(&s3_key).await.map_err(|e| {
+        tracing::error!("evidence bundle download failed: {:?}", e);
+        ApiError::NotFound(format!("bundle {} is no longer stored", bundle_id))
+    })?;
+    Ok((finding_id, bytes))
+}
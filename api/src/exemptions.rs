@@ -0,0 +1,343 @@
+//! Detection exemptions (`server_exemptions`).
+//!
+//! An exemption selects players by UUID, permission node and/or world (every set selector must
+//! match) and applies while `starts_at <= now() < ends_at` (open-ended when `ends_at` is null).
+//! Exempted players are stripped from batches before transforms run, and findings reported for
+//! them are dropped in `post_findings`, so staff flying in creative/spectator stop generating
+//! noise.
+//!
+//! Organization-wide exemptions (`organization_exemptions`) apply to every server of the
+//! organization in addition to the server's own.
+//!
+//! Permission nodes are reported by the plugin in `PLAYER_STATE` `fields.permissions` (it only
+//! checks the nodes pushed with the `exemptions_sync` pending action); the last reported set
+//! and world are kept on `server_players`.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::pending_actions;
+use crate::routes::callbacks::FindingIn;
+
+pub const SYNC_ACTION: &str = "exemptions_sync";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exemption {
+    pub player_uuid: Option<Uuid>,
+    pub permission: Option<String>,
+    pub world: Option<String>,
+}
+
+impl Exemption {
+    fn matches(&self, player: Uuid, facts: &PlayerFacts) -> bool {
+        self.player_uuid.is_none_or(|u| u == player)
+            && self
+                .permission
+                .as_ref()
+                .is_none_or(|p| facts.permissions.iter().any(|have| have == p))
+            && self
+                .world
+                .as_ref()
+                .is_none_or(|w| facts.world.as_deref() == Some(w.as_str()))
+    }
+}
+
+/// What the API knows about a player when evaluating exemptions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerFacts {
+    pub world: Option<String>,
+    pub permissions: Vec<String>,
+}
+
+/// Exemptions active right now for one server.
+#[derive(Debug, Clone, Default)]
+pub struct ActiveExemptions {
+    pub rules: Vec<Exemption>,
+}
+
+impl ActiveExemptions {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn is_exempt(&self, player: Uuid, facts: &PlayerFacts) -> bool {
+        self.rules.iter().any(|r| r.matches(player, facts))
+    }
+
+    /// Whether any rule depends on per-player state (world / permission).
+    pub fn needs_facts(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|r| r.permission.is_some() || r.world.is_some())
+    }
+}
+
+pub async fn load_active(db: &PgPool, server_id: &str) -> Result<ActiveExemptions, sqlx::Error> {
+    let rows: Vec<(Option<Uuid>, Option<String>, Option<String>)> = sqlx::query_as(
+        r#"
+        select player_uuid, permission, world
+        from public.server_exemptions
+        where server_id = $1
+          and starts_at <= now()
+          and (ends_at is null or ends_at > now())
+        union all
+        select e.player_uuid, e.permission, e.world
+        from public.organization_exemptions e
+        join public.servers s on s.organization_id = e.organization_id
+        where s.id = $1
+          and e.starts_at <= now()
+          and (e.ends_at is null or e.ends_at > now())
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(ActiveExemptions {
+        rules: rows
+            .into_iter()
+            .map(|(player_uuid, permission, world)| Exemption {
+                player_uuid,
+                permission,
+                world,
+            })
+            .collect(),
+    })
+}
+
+/// Last known world / permissions for the given players.
+pub async fn load_facts(
+    db: &PgPool,
+    server_id: &str,
+    players: &[Uuid],
+) -> Result<HashMap<Uuid, PlayerFacts>, sqlx::Error> {
+    let rows: Vec<(Uuid, Option<String>, Vec<String>)> = sqlx::query_as(
+        r#"
+        select player_uuid, world, permissions
+        from public.server_players
+        where server_id = $1 and player_uuid = any($2)
+        "#,
+    )
+    .bind(server_id)
+    .bind(players)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(uuid, world, permissions)| (uuid, PlayerFacts { world, permissions }))
+        .collect())
+}
+
+/// Distinct player UUIDs in a raw batch.
+pub fn batch_players(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<Uuid>> {
+    let reader = BufReader::new(GzDecoder::new(raw_gz_ndjson));
+    let mut players = std::collections::HashSet::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if i == 0 || line.trim().is_empty() {
+            continue;
+        }
+        let Ok(v) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if let Some(uuid) = v
+            .get("uuid")
+            .and_then(|x| x.as_str())
+            .and_then(|s| Uuid::parse_str(s).ok())
+        {
+            players.insert(uuid);
+        }
+    }
+    Ok(players.into_iter().collect())
+}
+
+/// Drop records of exempted players from a raw batch.
+///
+/// Facts start from `known` (the database) and follow `world` / `PLAYER_STATE` updates within
+/// the batch, so a player changing worlds mid-batch is handled per record. Returns the filtered
+/// batch and the number of records removed; the input is returned as-is when nothing matched.
+pub fn strip_exempt(
+    raw_gz_ndjson: &[u8],
+    exemptions: &ActiveExemptions,
+    known: &HashMap<Uuid, PlayerFacts>,
+) -> anyhow::Result<(Vec<u8>, usize)> {
+    if exemptions.is_empty() {
+        return Ok((raw_gz_ndjson.to_vec(), 0));
+    }
+
+    let mut reader = BufReader::new(GzDecoder::new(raw_gz_ndjson));
+    let mut out = Vec::new();
+    let mut encoder = GzEncoder::new(&mut out, Compression::default());
+
+    let mut facts = known.clone();
+    let no_facts = PlayerFacts::default();
+    let mut buf = String::new();
+    let mut line_no = 0usize;
+    let mut removed = 0usize;
+
+    while {
+        buf.clear();
+        reader.read_line(&mut buf)?
+    } != 0
+    {
+        line_no += 1;
+        let line = buf.trim_end_matches(&['\n', '\r'][..]);
+        if line.is_empty() {
+            continue;
+        }
+        if line_no == 1 {
+            writeln!(encoder, "{}", line)?;
+            continue;
+        }
+
+        let player = serde_json::from_str::<Value>(line).ok().and_then(|v| {
+            let uuid = v
+                .get("uuid")
+                .and_then(|x| x.as_str())
+                .and_then(|s| Uuid::parse_str(s).ok())?;
+            if exemptions.needs_facts() {
+                update_facts(facts.entry(uuid).or_default(), &v);
+            }
+            Some(uuid)
+        });
+
+        if let Some(uuid) = player {
+            if exemptions.is_exempt(uuid, facts.get(&uuid).unwrap_or(&no_facts)) {
+                removed += 1;
+                continue;
+            }
+        }
+        writeln!(encoder, "{}", line)?;
+    }
+
+    encoder.finish()?;
+    if removed == 0 {
+        return Ok((raw_gz_ndjson.to_vec(), 0));
+    }
+    Ok((out, removed))
+}
+
+fn update_facts(facts: &mut PlayerFacts, record: &Value) {
+    if let Some(world) = record.get("world").and_then(|x| x.as_str()) {
+        facts.world = Some(world.to_string());
+    }
+    let is_state = record
+        .get("pkt")
+        .and_then(|x| x.as_str())
+        .is_some_and(|p| p.eq_ignore_ascii_case("PLAYER_STATE"));
+    if !is_state {
+        return;
+    }
+    let Some(fields) = record.get("fields") else {
+        return;
+    };
+    if let Some(world) = fields.get("world").and_then(|x| x.as_str()) {
+        facts.world = Some(world.to_string());
+    }
+    if let Some(perms) = fields.get("permissions").and_then(|x| x.as_array()) {
+        facts.permissions = perms
+            .iter()
+            .filter_map(|p| p.as_str().map(str::to_string))
+            .collect();
+    }
+}
+
+/// Findings not attributed to an exempted player (a finding's evidence `world` overrides the
+/// player's last known world). Best-effort: on lookup errors every finding is kept.
+pub async fn filter_findings<'a>(
+    db: &PgPool,
+    server_id: &str,
+    findings: &'a [FindingIn],
+) -> Vec<&'a FindingIn> {
+    let lookup = async {
+        let active = load_active(db, server_id).await?;
+        let facts = if active.needs_facts() {
+            let players: Vec<Uuid> = findings.iter().filter_map(|f| f.player_uuid).collect();
+            load_facts(db, server_id, &players).await?
+        } else {
+            HashMap::new()
+        };
+        Ok::<_, sqlx::Error>((active, facts))
+    };
+    let (active, facts) = match lookup.await {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::warn!("exemption lookup failed, keeping all findings: {:?}", e);
+            return findings.iter().collect();
+        }
+    };
+    if active.is_empty() {
+        return findings.iter().collect();
+    }
+
+    findings
+        .iter()
+        .filter(|f| {
+            let Some(player) = f.player_uuid else {
+                return true;
+            };
+            let mut player_facts = facts.get(&player).cloned().unwrap_or_default();
+            if let Some(world) = f
+                .evidence_json
+                .as_ref()
+                .and_then(|e| e.get("world"))
+                .and_then(|w| w.as_str())
+            {
+                player_facts.world = Some(world.to_string());
+            }
+            !active.is_exempt(player, &player_facts)
+        })
+        .collect()
+}
+
+/// Push the permission nodes the plugin should report in `PLAYER_STATE` records.
+pub async fn sync_to_plugin(db: &PgPool, server_id: &str) -> Result<(), sqlx::Error> {
+    let permissions: Vec<String> = sqlx::query_scalar(
+        r#"
+        select permission
+        from public.server_exemptions
+        where server_id = $1
+          and permission is not null
+          and (ends_at is null or ends_at > now())
+        union
+        select e.permission
+        from public.organization_exemptions e
+        join public.servers s on s.organization_id = e.organization_id
+        where s.id = $1
+          and e.permission is not null
+          and (e.ends_at is null or e.ends_at > now())
+        order by permission
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db)
+    .await?;
+
+    pending_actions::enqueue(
+        db,
+        server_id,
+        SYNC_ACTION,
+        &json!({ "permissions": permissions }),
+        true,
+    )
+    .await
+}
+
+/// Re-sync every server of an organization after its shared exemptions changed.
+pub async fn sync_organization(db: &PgPool, organization_id: Uuid) -> Result<(), sqlx::Error> {
+    let servers: Vec<String> =
+        sqlx::query_scalar("select id from public.servers where organization_id = $1")
+            .bind(organization_id)
+            .fetch_all(db)
+            .await?;
+    for server_id in servers {
+        sync_to_plugin(db, &server_id).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,160 @@
+//! Ingest → finding latency, and its daily percentiles per detector.
+//!
+//! A finding reported for a batch (module callbacks and in-process checks carry the batch id) is
+//! stamped with the batch's `batch_index.received_at` and the milliseconds between then and the
+//! finding being stored. A minute-bucket row keeps the latency of its first occurrence; findings
+//! without a batch (plugin-reported, imported) have none.
+//!
+//! Once an hour the latencies are rolled up into `finding_latency_daily` per server, detector and
+//! UTC day: p50 / p90 / p99 / max and how many findings arrived within the SLO
+//! (`FINDING_LATENCY_SLO_SECONDS`, recorded with the row). The last [`RECOMPUTE_DAYS`] are
+//! recomputed on every run; older days are rolled up once. The dashboard reads the rollups, plus
+//! the current day from the findings themselves.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppState;
+
+pub const ROLLUP_INTERVAL_SECONDS: u64 = 3600;
+pub const RECOMPUTE_DAYS: i32 = 2;
+pub const RETENTION_DAYS: i32 = 90;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LatencyDay {
+    pub day: NaiveDate,
+    pub detector_name: String,
+    /// Findings with a latency stored that day.
+    pub findings: i64,
+    pub p50_ms: Option<f64>,
+    pub p90_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+    pub max_ms: Option<i32>,
+    /// Findings stored within `slo_ms` of their batch's arrival.
+    pub within_slo: i64,
+    pub slo_ms: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LatencySummary {
+    pub findings: i64,
+    pub within_slo: i64,
+    /// Share of findings within the SLO (none without findings).
+    pub attainment: Option<f64>,
+    /// Highest daily p99 in the range.
+    pub worst_p99_ms: Option<f64>,
+    pub max_ms: Option<i32>,
+}
+
+/// Sum daily rows.
+pub fn summarize<'a>(days: impl IntoIterator<Item = &'a LatencyDay>) -> LatencySummary {
+    let (mut findings, mut within_slo) = (0, 0);
+    let mut worst_p99: Option<f64> = None;
+    let mut max_ms: Option<i32> = None;
+    for d in days {
+        findings += d.findings;
+        within_slo += d.within_slo;
+        if let Some(p99) = d.p99_ms {
+            worst_p99 = Some(worst_p99.map_or(p99, |w| w.max(p99)));
+        }
+        if let Some(m) = d.max_ms {
+            max_ms = Some(max_ms.map_or(m, |w| w.max(m)));
+        }
+    }
+    LatencySummary {
+        findings,
+        within_slo,
+        attainment: (findings > 0).then(|| within_slo as f64 / findings as f64),
+        worst_p99_ms: worst_p99,
+        max_ms,
+    }
+}
+
+/// When the server's batch `batch_id` was received, to stamp its findings with.
+pub async fn batch_received_at(
+    db: &PgPool,
+    server_id: &str,
+    batch_id: Option<Uuid>,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let Some(batch_id) = batch_id else {
+        return Ok(None);
+    };
+    sqlx::query_scalar(
+        "select received_at from public.batch_index where id = $1 and server_id = $2",
+    )
+    .bind(batch_id)
+    .bind(server_id)
+    .fetch_optional(db)
+    .await
+}
+
+/// Roll up recent days (and days never rolled up), then purge rollups past the retention.
+pub async fn rollup_tick(state: AppState) {
+    let slo_ms = (state.finding_latency_slo_seconds * 1000).min(i32::MAX as u64) as i32;
+    let rolled_up = sqlx::query(
+        r#"
+        insert into public.finding_latency_daily
+            (server_id, detector_name, day, findings, p50_ms, p90_ms, p99_ms, max_ms,
+             within_slo, slo_ms, updated_at)
+        select
+            f.server_id,
+            f.detector_name,
+            (f.first_seen_at at time zone 'utc')::date as day,
+            count(*),
+            percentile_cont(0.5) within group (order by f.ingest_latency_ms),
+            percentile_cont(0.9) within group (order by f.ingest_latency_ms),
+            percentile_cont(0.99) within group (order by f.ingest_latency_ms),
+            max(f.ingest_latency_ms),
+            count(*) filter (where f.ingest_latency_ms <= $1),
+            $1,
+            now()
+        from public.findings f
+        where f.ingest_latency_ms is not null
+          and f.first_seen_at >= now() - make_interval(days => $3)
+          and (
+              f.first_seen_at >= date_trunc('day', now() at time zone 'utc') at time zone 'utc'
+                  - make_interval(days => $2 - 1)
+              or not exists (
+                  select 1 from public.finding_latency_daily d
+                  where d.server_id = f.server_id
+                    and d.detector_name = f.detector_name
+                    and d.day = (f.first_seen_at at time zone 'utc')::date
+              )
+          )
+        group by f.server_id, f.detector_name, (f.first_seen_at at time zone 'utc')::date
+        on conflict (server_id, detector_name, day) do update set
+            findings = excluded.findings,
+            p50_ms = excluded.p50_ms,
+            p90_ms = excluded.p90_ms,
+            p99_ms = excluded.p99_ms,
+            max_ms = excluded.max_ms,
+            within_slo = excluded.within_slo,
+            slo_ms = excluded.slo_ms,
+            updated_at = now()
+        "#,
+    )
+    .bind(slo_ms)
+    .bind(RECOMPUTE_DAYS)
+    .bind(RETENTION_DAYS)
+    .execute(&state.db)
+    .await;
+    let rows = match rolled_up {
+        Ok(r) => r.rows_affected(),
+        Err(e) => {
+            tracing::warn!("finding latency rollup failed: {:?}", e);
+            return;
+        }
+    };
+
+    let _ = sqlx::query(
+        "delete from public.finding_latency_daily where day < (now() at time zone 'utc')::date - $1",
+    )
+    .bind(RETENTION_DAYS)
+    .execute(&state.db)
+    .await;
+
+    tracing::info!(rows = rows, "finding latency rollup finished");
+}
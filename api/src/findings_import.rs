@@ -0,0 +1,595 @@
+//! Violation history import from other anticheats
+//!
+//! `POST /dashboard/:server_id/import` takes a log export from NoCheatPlus, Grim or Vulcan and
+//! stores its violations as findings tagged with the log's `source` (`ncp`, `grim`, `vulcan`), so
+//! a server switching over keeps its history on the dashboard. Each format is a `LogParser`;
+//! supporting another anticheat means implementing the trait and listing it in `PARSERS`.
+//!
+//! Violations are bucketed per player, check and minute like native findings, so re-importing a
+//! log leaves the counts unchanged. Players are matched by name against the server's
+//! `server_players`; lines for players the server has never seen are skipped and reported.
+//! Imported findings do not send webhooks or feed punishment rules.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, DurationRound, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::Serialize;
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{error::ApiError, AppState};
+
+/// At most this many unresolved player names are listed in an import summary.
+pub const MAX_REPORTED_PLAYERS: usize = 50;
+
+/// A violation as reported by a log line, before the timestamp is attached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogViolation {
+    pub player_name: String,
+    /// Check name as the anticheat prints it, e.g. `SurvivalFly` or `Speed A`.
+    pub check: String,
+    pub vl: Option<f64>,
+    pub details: Option<String>,
+}
+
+/// Parser for one anticheat's log lines.
+pub trait LogParser: Sync {
+    /// Request `format` and findings `source` tag.
+    fn source(&self) -> &'static str;
+    /// Anticheat name shown in finding titles.
+    fn display_name(&self) -> &'static str;
+    /// The violation on a line whose timestamp and colour codes were already removed.
+    fn parse_violation(&self, message: &str) -> Option<LogViolation>;
+}
+
+/// NoCheatPlus: `Steve failed SurvivalFly: moved too quickly. VL 12.3.`
+pub struct NcpParser;
+
+impl LogParser for NcpParser {
+    fn source(&self) -> &'static str {
+        "ncp"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "NoCheatPlus"
+    }
+
+    fn parse_violation(&self, message: &str) -> Option<LogViolation> {
+        let (player_name, rest) = split_failed(message)?;
+        let check_end = rest
+            .find(|c: char| c == ':' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let check = rest[..check_end].trim_end_matches('.');
+        let rest = rest[check_end..].trim_start_matches(':').trim();
+
+        // Everything before the trailing "VL <n>." is the detail text.
+        let (details, vl) = match rest.rfind("VL ") {
+            Some(i) => (&rest[..i], leading_number(&rest[i + 3..])),
+            None => (rest, None),
+        };
+        Some(LogViolation {
+            player_name,
+            check: check.to_string(),
+            vl,
+            details: non_empty(details.trim().trim_end_matches('.')),
+        })
+    }
+}
+
+/// Grim: `Steve failed Reach (x5) 3.12 blocks`
+pub struct GrimParser;
+
+impl LogParser for GrimParser {
+    fn source(&self) -> &'static str {
+        "grim"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Grim"
+    }
+
+    fn parse_violation(&self, message: &str) -> Option<LogViolation> {
+        let (player_name, rest) = split_failed(message)?;
+        let (check, rest) = split_token(rest);
+        let (vl, details) = match rest.strip_prefix("(x") {
+            Some(counted) => {
+                let end = counted.find(')')?;
+                (leading_number(&counted[..end]), &counted[end + 1..])
+            }
+            None => (None, rest),
+        };
+        Some(LogViolation {
+            player_name,
+            check: check.to_string(),
+            vl,
+            details: non_empty(details.trim()),
+        })
+    }
+}
+
+/// Vulcan: `Steve failed Speed (Type A) [VL: 5] offset=0.21` (also `(x5)` / `[x5]` counters).
+pub struct VulcanParser;
+
+impl LogParser for VulcanParser {
+    fn source(&self) -> &'static str {
+        "vulcan"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Vulcan"
+    }
+
+    fn parse_violation(&self, message: &str) -> Option<LogViolation> {
+        let (player_name, rest) = split_failed(message)?;
+        let (name, mut rest) = split_token(rest);
+        let mut check = name.to_string();
+        if let Some(typed) = rest.strip_prefix("(Type ") {
+            let end = typed.find(')')?;
+            check = format!("{} {}", check, typed[..end].trim());
+            rest = typed[end + 1..].trim_start();
+        }
+
+        let mut vl = None;
+        let mut details = rest;
+        for open in ['[', '('] {
+            let Some(start) = details.find(open) else {
+                continue;
+            };
+            let inner = &details[start + 1..];
+            let counter = inner
+                .strip_prefix("VL:")
+                .or_else(|| inner.strip_prefix("VL "))
+                .or_else(|| inner.strip_prefix('x'));
+            if let Some(n) = counter.and_then(|c| leading_number(c.trim_start())) {
+                vl = Some(n);
+                let close = inner.find([']', ')']).map_or(inner.len(), |i| i + 1);
+                details = &inner[close..];
+                break;
+            }
+        }
+        Some(LogViolation {
+            player_name,
+            check,
+            vl,
+            details: non_empty(details.trim()),
+        })
+    }
+}
+
+/// Supported formats, looked up by `LogParser::source`.
+pub static PARSERS: &[&dyn LogParser] = &[&NcpParser, &GrimParser, &VulcanParser];
+
+pub fn parser_for(format: &str) -> Option<&'static dyn LogParser> {
+    let format = format.trim().to_ascii_lowercase();
+    let format = match format.as_str() {
+        "nocheatplus" => "ncp",
+        other => other,
+    };
+    PARSERS.iter().copied().find(|p| p.source() == format)
+}
+
+/// The player name before ` failed ` and the text after it.
+fn split_failed(message: &str) -> Option<(String, &str)> {
+    let i = message.find(" failed ")?;
+    let name = message[..i]
+        .split_whitespace()
+        .last()?
+        .trim_matches(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '*')));
+    if name.is_empty() || name.len() > 32 {
+        return None;
+    }
+    let rest = message[i + " failed ".len()..].trim();
+    (!rest.is_empty()).then(|| (name.to_string(), rest))
+}
+
+fn split_token(s: &str) -> (&str, &str) {
+    let s = s.trim();
+    match s.find(char::is_whitespace) {
+        Some(i) => (&s[..i], s[i..].trim_start()),
+        None => (s, ""),
+    }
+}
+
+/// The number at the start of `s` (`12.3.` -> 12.3).
+fn leading_number(s: &str) -> Option<f64> {
+    let end = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    s[..end].trim_end_matches('.').parse().ok()
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+/// Remove `§x` / `&x` colour and format codes.
+pub fn strip_formatting(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if matches!(c, '§' | '&')
+            && chars
+                .peek()
+                .is_some_and(|n| n.is_ascii_hexdigit() || "klmnorxKLMNORX".contains(*n))
+        {
+            chars.next();
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// How timestamps without a date or zone are interpreted.
+#[derive(Debug, Clone, Copy)]
+pub struct TimeContext {
+    /// Day for time-only timestamps (`[12:03:44 INFO]`).
+    pub date: Option<NaiveDate>,
+    /// Zone the log was written in.
+    pub offset: FixedOffset,
+}
+
+impl Default for TimeContext {
+    fn default() -> Self {
+        Self {
+            date: None,
+            offset: FixedOffset::east_opt(0).expect("zero offset"),
+        }
+    }
+}
+
+fn parse_stamp(s: &str, ctx: &TimeContext) -> Option<DateTime<Utc>> {
+    let s = s.split(['.', ',']).next().unwrap_or(s);
+    let naive = [
+        "%Y-%m-%d %H:%M:%S",
+        "%d/%m/%y %H:%M:%S",
+        "%d/%m/%Y %H:%M:%S",
+    ]
+    .iter()
+    .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+    .or_else(|| {
+        let time = chrono::NaiveTime::parse_from_str(s, "%H:%M:%S").ok()?;
+        Some(ctx.date?.and_time(time))
+    })?;
+    ctx.offset
+        .from_local_datetime(&naive)
+        .single()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// A timestamp made of the first one or two whitespace-separated tokens of `s`, and the byte
+/// length it takes up.
+fn stamp_prefix(s: &str, ctx: &TimeContext) -> Option<(DateTime<Utc>, usize)> {
+    let mut ends = Vec::with_capacity(2);
+    let mut in_token = false;
+    for (i, c) in s.char_indices() {
+        if c.is_whitespace() {
+            if in_token {
+                ends.push(i);
+                if ends.len() == 2 {
+                    break;
+                }
+            }
+            in_token = false;
+        } else {
+            in_token = true;
+        }
+    }
+    if in_token && ends.len() < 2 {
+        ends.push(s.len());
+    }
+    ends.iter()
+        .rev()
+        .find_map(|&end| parse_stamp(s[..end].trim_end_matches(':'), ctx).map(|at| (at, end)))
+}
+
+/// Split the leading timestamp (`2024-03-01 12:03:44`, `[01/03/24 12:03:44]`,
+/// `[12:03:44 INFO]:`) off a line.
+pub fn split_timestamp<'a>(line: &'a str, ctx: &TimeContext) -> (Option<DateTime<Utc>>, &'a str) {
+    let line = line.trim_start();
+    if let Some(inner) = line.strip_prefix('[') {
+        if let Some(end) = inner.find(']') {
+            if let Some((at, _)) = stamp_prefix(&inner[..end], ctx) {
+                let rest = inner[end + 1..].trim_start_matches(':');
+                return (Some(at), rest.trim_start());
+            }
+        }
+        return (None, line);
+    }
+    match stamp_prefix(line, ctx) {
+        Some((at, len)) => (Some(at), line[len..].trim_start()),
+        None => (None, line),
+    }
+}
+
+/// A dated violation read from a log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedViolation {
+    pub at: DateTime<Utc>,
+    pub violation: LogViolation,
+    pub line: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedLog {
+    pub violations: Vec<ImportedViolation>,
+    /// Non-empty lines read.
+    pub lines: usize,
+    /// Violation lines without a usable timestamp (time-only stamps need `date`).
+    pub undated_lines: usize,
+}
+
+pub fn parse_log(parser: &dyn LogParser, log: &str, ctx: &TimeContext) -> ParsedLog {
+    let mut parsed = ParsedLog::default();
+    for raw in log.lines() {
+        let line = strip_formatting(raw.trim());
+        if line.is_empty() {
+            continue;
+        }
+        parsed.lines += 1;
+        let (at, message) = split_timestamp(&line, ctx);
+        let Some(violation) = parser.parse_violation(message) else {
+            continue;
+        };
+        match at {
+            Some(at) => parsed.violations.push(ImportedViolation {
+                at,
+                violation,
+                line: line.clone(),
+            }),
+            None => parsed.undated_lines += 1,
+        }
+    }
+    parsed
+}
+
+/// `SurvivalFly` -> `survival_fly`, `Speed A` -> `speed_a`.
+pub fn detector_slug(check: &str) -> String {
+    let mut out = String::with_capacity(check.len() + 4);
+    let mut prev: Option<char> = None;
+    for c in check.chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase()
+                && prev.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+            {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else if !out.is_empty() && !out.ends_with('_') {
+            out.push('_');
+        }
+        prev = Some(c);
+    }
+    out.trim_end_matches('_').to_string()
+}
+
+/// Severity for a violation level: 20+ high, 5+ medium, else low.
+pub fn severity_for_vl(vl: Option<f64>) -> &'static str {
+    match vl {
+        Some(vl) if vl >= 20.0 => "high",
+        Some(vl) if vl >= 5.0 => "medium",
+        _ => "low",
+    }
+}
+
+/// One minute bucket of a player's violations of one check.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedFinding {
+    pub player_name: String,
+    pub detector_name: String,
+    pub title: String,
+    pub severity: &'static str,
+    pub occurrences: i32,
+    pub window_start_at: DateTime<Utc>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub max_vl: Option<f64>,
+    /// Highest-VL line of the bucket.
+    pub line: String,
+    pub details: Option<String>,
+}
+
+pub fn aggregate(parser: &dyn LogParser, violations: &[ImportedViolation]) -> Vec<ImportedFinding> {
+    let mut buckets: BTreeMap<(String, String, DateTime<Utc>), ImportedFinding> = BTreeMap::new();
+    for v in violations {
+        let slug = detector_slug(&v.violation.check);
+        if slug.is_empty() {
+            continue;
+        }
+        let detector_name = format!("{}_{}", parser.source(), slug);
+        let window_start_at =
+            v.at.duration_trunc(chrono::Duration::minutes(1))
+                .unwrap_or(v.at);
+        let key = (
+            v.violation.player_name.to_lowercase(),
+            detector_name.clone(),
+            window_start_at,
+        );
+        let entry = buckets.entry(key).or_insert_with(|| ImportedFinding {
+            player_name: v.violation.player_name.clone(),
+            detector_name,
+            title: format!(
+                "{} (imported from {})",
+                v.violation.check,
+                parser.display_name()
+            ),
+            severity: "low",
+            occurrences: 0,
+            window_start_at,
+            first_seen_at: v.at,
+            last_seen_at: v.at,
+            max_vl: None,
+            line: v.line.clone(),
+            details: v.violation.details.clone(),
+        });
+        entry.occurrences += 1;
+        entry.first_seen_at = entry.first_seen_at.min(v.at);
+        entry.last_seen_at = entry.last_seen_at.max(v.at);
+        if v.violation.vl > entry.max_vl {
+            entry.max_vl = v.violation.vl;
+            entry.line = v.line.clone();
+            entry.details = v.violation.details.clone();
+        }
+        entry.severity = severity_for_vl(entry.max_vl);
+    }
+    buckets.into_values().collect()
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportSummary {
+    /// `ncp`, `grim` or `vulcan`; stored as the findings' `source`.
+    pub source: String,
+    /// Non-empty lines read.
+    pub lines: usize,
+    /// Violations with a timestamp.
+    pub violations: usize,
+    /// Violation lines skipped for lack of a timestamp (pass `date` for time-only logs).
+    pub undated_lines: usize,
+    /// Minute buckets stored (new or updated).
+    pub findings: usize,
+    /// Violations of players this server has never seen (skipped).
+    pub unresolved_violations: usize,
+    /// Names of those players (first 50).
+    pub unresolved_players: Vec<String>,
+}
+
+fn db_error(what: &'static str) -> impl FnOnce(sqlx::Error) -> ApiError {
+    move |e| {
+        tracing::error!("{} failed: {:?}", what, e);
+        ApiError::Internal
+    }
+}
+
+/// Parse `log` and store its violations as findings of `server_id`.
+pub async fn import(
+    state: &AppState,
+    server_id: &str,
+    parser: &dyn LogParser,
+    log: &str,
+    ctx: &TimeContext,
+) -> Result<ImportSummary, ApiError> {
+    let exists: bool =
+        sqlx::query_scalar("select exists(select 1 from public.servers where id = $1)")
+            .bind(server_id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(db_error("import server lookup"))?;
+    if !exists {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    let parsed = parse_log(parser, log, ctx);
+    let findings = aggregate(parser, &parsed.violations);
+
+    let mut names: Vec<String> = findings
+        .iter()
+        .map(|f| f.player_name.to_lowercase())
+        .collect();
+    names.sort();
+    names.dedup();
+    let players: HashMap<String, Uuid> = sqlx::query_as::<_, (String, Uuid)>(
+        r#"
+        select distinct on (lower(player_name)) lower(player_name), player_uuid
+        from public.server_players
+        where server_id = $1 and lower(player_name) = any($2)
+        order by lower(player_name), last_seen_at desc
+        "#,
+    )
+    .bind(server_id)
+    .bind(&names)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error("import player lookup"))?
+    .into_iter()
+    .collect();
+
+    let mut stored = 0usize;
+    let mut unresolved_violations = 0usize;
+    let mut unresolved_players: Vec<String> = Vec::new();
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(db_error("import transaction"))?;
+    for f in &findings {
+        let Some(player_uuid) = players.get(&f.player_name.to_lowercase()) else {
+            unresolved_violations += f.occurrences as usize;
+            if unresolved_players.len() < MAX_REPORTED_PLAYERS
+                && !unresolved_players
+                    .iter()
+                    .any(|n| n.eq_ignore_ascii_case(&f.player_name))
+            {
+                unresolved_players.push(f.player_name.clone());
+            }
+            continue;
+        };
+        let evidence = json!({
+            "imported": {
+                "source": parser.source(),
+                "player_name": f.player_name,
+                "vl": f.max_vl,
+                "line": f.line,
+            }
+        });
+
+        // Re-imports of the same log keep the larger count instead of adding up.
+        sqlx::query(
+            r#"
+            insert into public.findings
+                (server_id, player_uuid, detector_name, severity, title, description, evidence_json,
+                 occurrences, window_start_at, first_seen_at, last_seen_at, created_at, source)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $10, $12)
+            on conflict (server_id, player_uuid, detector_name, window_start_at)
+                where player_uuid is not null
+            do update set
+                occurrences = greatest(public.findings.occurrences, excluded.occurrences),
+                first_seen_at = least(public.findings.first_seen_at, excluded.first_seen_at),
+                last_seen_at = greatest(public.findings.last_seen_at, excluded.last_seen_at),
+                severity = excluded.severity,
+                description = excluded.description,
+                evidence_json = excluded.evidence_json
+            where public.findings.source = excluded.source
+            "#,
+        )
+        .bind(server_id)
+        .bind(player_uuid)
+        .bind(&f.detector_name)
+        .bind(f.severity)
+        .bind(&f.title)
+        .bind(f.details.as_deref())
+        .bind(sqlx::types::Json(&evidence))
+        .bind(f.occurrences)
+        .bind(f.window_start_at)
+        .bind(f.first_seen_at)
+        .bind(f.last_seen_at)
+        .bind(parser.source())
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error("import finding upsert"))?;
+        stored += 1;
+    }
+    tx.commit().await.map_err(db_error("import commit"))?;
+
+    tracing::info!(
+        server_id = %server_id,
+        source = parser.source(),
+        violations = parsed.violations.len(),
+        findings = stored,
+        unresolved = unresolved_violations,
+        "imported violation history"
+    );
+
+    Ok(ImportSummary {
+        source: parser.source().to_string(),
+        lines: parsed.lines,
+        violations: parsed.violations.len(),
+        undated_lines: parsed.undated_lines,
+        findings: stored,
+        unresolved_violations,
+        unresolved_players,
+    })
+}
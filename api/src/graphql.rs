@@ -0,0 +1,641 @@
+//! GraphQL schema over dashboard data (`POST /graphql`).
+//!
+//! Lets a dashboard page fetch everything its panels need (server, stats, players, findings,
+//! modules, observations) in one request. Lists that can grow use Relay-style connections with
+//! keyset cursors (`first` / `after`, see `pagination`), newest first.
+//!
+//! Auth happens in the handler (`routes::graphql`): `DASHBOARD_TOKEN` grants the `Dashboard`
+//! role, `ADMIN_TOKEN` the `Admin` role. Fields exposing infrastructure details or raw evidence
+//! (module URLs and errors, finding evidence, reviewer notes) are guarded with [`AdminOnly`].
+
+use std::sync::OnceLock;
+
+use async_graphql::connection::{Connection, CursorType, Edge};
+use async_graphql::{
+    ComplexObject, Context, EmptyMutation, EmptySubscription, Guard, Json, Object, Schema,
+    SimpleObject,
+};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::AppState;
+
+pub type DashboardSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 100;
+
+/// Built once; per-request state (`AppState`, `Role`) is attached as request data.
+pub fn schema() -> &'static DashboardSchema {
+    static SCHEMA: OnceLock<DashboardSchema> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+            .limit_depth(10)
+            .limit_complexity(2_000)
+            .finish()
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Dashboard,
+    Admin,
+}
+
+/// Field guard: only resolvable with `ADMIN_TOKEN`.
+pub struct AdminOnly;
+
+impl Guard for AdminOnly {
+    async fn check(&self, ctx: &Context<'_>) -> async_graphql::Result<()> {
+        match ctx.data_opt::<Role>() {
+            Some(Role::Admin) => Ok(()),
+            _ => Err("this field requires the admin token".into()),
+        }
+    }
+}
+
+/// The REST lists' keyset cursor (`pagination::Cursor`).
+pub use crate::pagination::Cursor as KeysetCursor;
+
+impl CursorType for KeysetCursor {
+    type Error = String;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        KeysetCursor::decode(s)
+    }
+
+    fn encode_cursor(&self) -> String {
+        self.encode()
+    }
+}
+
+fn page_size(first: Option<i32>) -> i64 {
+    first
+        .map(i64::from)
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE)
+}
+
+fn decode_after(after: Option<String>) -> async_graphql::Result<Option<KeysetCursor>> {
+    after
+        .map(|a| KeysetCursor::decode_cursor(&a).map_err(async_graphql::Error::new))
+        .transpose()
+}
+
+/// Turn `limit + 1` fetched rows into a connection page.
+fn connection<T: async_graphql::OutputType>(
+    mut rows: Vec<T>,
+    limit: i64,
+    has_previous: bool,
+    cursor: impl Fn(&T) -> KeysetCursor,
+) -> Connection<KeysetCursor, T> {
+    let has_next = rows.len() as i64 > limit;
+    rows.truncate(limit as usize);
+    let mut conn = Connection::new(has_previous, has_next);
+    conn.edges
+        .extend(rows.into_iter().map(|row| Edge::new(cursor(&row), row)));
+    conn
+}
+
+fn internal(what: &str, e: sqlx::Error) -> async_graphql::Error {
+    tracing::error!("graphql {} query failed: {:?}", what, e);
+    async_graphql::Error::new("internal error")
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Known servers (not deleted), most recently seen first.
+    async fn servers(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Server>> {
+        let state = ctx.data::<AppState>()?;
+        let rows: Vec<(String, Option<String>, Option<String>, DateTime<Utc>)> = sqlx::query_as(
+            "select id, name, platform, last_seen_at from public.servers where deleted_at is null \
+             order by last_seen_at desc",
+        )
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| internal("servers", e))?;
+        Ok(rows.into_iter().map(Server::from_row).collect())
+    }
+
+    async fn server(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<Option<Server>> {
+        let state = ctx.data::<AppState>()?;
+        let row: Option<(String, Option<String>, Option<String>, DateTime<Utc>)> = sqlx::query_as(
+            "select id, name, platform, last_seen_at from public.servers where id = $1",
+        )
+        .bind(id.trim())
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| internal("server", e))?;
+        Ok(row.map(Server::from_row))
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Server {
+    pub id: String,
+    pub name: Option<String>,
+    pub platform: Option<String>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl Server {
+    fn from_row(
+        (id, name, platform, last_seen_at): (String, Option<String>, Option<String>, DateTime<Utc>),
+    ) -> Self {
+        Server {
+            id,
+            name,
+            platform,
+            last_seen_at,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ServerStats {
+    pub total_findings: i64,
+    pub findings_today: i64,
+    pub open_findings: i64,
+    pub players_monitored: i64,
+    pub active_modules: i64,
+}
+
+#[ComplexObject]
+impl Server {
+    async fn stats(&self, ctx: &Context<'_>) -> async_graphql::Result<ServerStats> {
+        let state = ctx.data::<AppState>()?;
+        let (total_findings, findings_today, open_findings, players_monitored, active_modules): (
+            i64,
+            i64,
+            i64,
+            i64,
+            i64,
+        ) = sqlx::query_as(
+            r#"
+            select
+                coalesce(sum(occurrences), 0)::bigint,
+                coalesce(sum(occurrences) filter (where last_seen_at > now() - interval '24 hours'), 0)::bigint,
+                count(*) filter (where status = 'open'),
+                count(distinct player_uuid),
+                (select count(*) from public.server_modules where server_id = $1 and enabled = true)
+            from public.findings
+            where server_id = $1
+            "#,
+        )
+        .bind(&self.id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| internal("stats", e))?;
+        Ok(ServerStats {
+            total_findings,
+            findings_today,
+            open_findings,
+            players_monitored,
+            active_modules,
+        })
+    }
+
+    /// Players seen on this server, most recently seen first.
+    async fn players(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+    ) -> async_graphql::Result<Connection<KeysetCursor, Player>> {
+        let state = ctx.data::<AppState>()?;
+        let limit = page_size(first);
+        let after = decode_after(after)?;
+        let rows: Vec<PlayerRow> = sqlx::query_as(
+            r#"
+            select player_uuid, player_name, first_seen_at, last_seen_at, world, gamemode
+            from public.server_players
+            where server_id = $1
+              and ($2::timestamptz is null or (last_seen_at, player_uuid) < ($2, $3))
+            order by last_seen_at desc, player_uuid desc
+            limit $4
+            "#,
+        )
+        .bind(&self.id)
+        .bind(after.map(|c| c.at))
+        .bind(after.map(|c| c.id))
+        .bind(limit + 1)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| internal("players", e))?;
+
+        let players = rows
+            .into_iter()
+            .map(|row| Player::from_row(&self.id, row))
+            .collect();
+        Ok(connection(players, limit, after.is_some(), |p| {
+            KeysetCursor {
+                at: p.last_seen_at,
+                id: p.uuid,
+            }
+        }))
+    }
+
+    async fn player(&self, ctx: &Context<'_>, uuid: Uuid) -> async_graphql::Result<Option<Player>> {
+        let state = ctx.data::<AppState>()?;
+        let row: Option<PlayerRow> = sqlx::query_as(
+            r#"
+            select player_uuid, player_name, first_seen_at, last_seen_at, world, gamemode
+            from public.server_players
+            where server_id = $1 and player_uuid = $2
+            "#,
+        )
+        .bind(&self.id)
+        .bind(uuid)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| internal("player", e))?;
+        Ok(row.map(|row| Player::from_row(&self.id, row)))
+    }
+
+    /// Findings, newest first. Filters combine with AND.
+    #[allow(clippy::too_many_arguments)]
+    async fn findings(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+        severity: Option<String>,
+        status: Option<String>,
+        world: Option<String>,
+        detector: Option<String>,
+    ) -> async_graphql::Result<Connection<KeysetCursor, Finding>> {
+        let filter = FindingFilter {
+            player_uuid: None,
+            severity,
+            status,
+            world,
+            detector,
+        };
+        load_findings(ctx, &self.id, filter, first, after).await
+    }
+
+    async fn modules(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Module>> {
+        let state = ctx.data::<AppState>()?;
+        let rows: Vec<(
+            Uuid,
+            String,
+            String,
+            bool,
+            String,
+            Option<bool>,
+            Option<String>,
+        )> = sqlx::query_as(
+            r#"
+            select id, name, base_url, enabled, transform, last_healthcheck_ok, last_error
+            from public.server_modules
+            where server_id = $1
+            order by name asc
+            "#,
+        )
+        .bind(&self.id)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| internal("modules", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, name, base_url, enabled, transform, healthy, last_error)| Module {
+                    id,
+                    name,
+                    enabled,
+                    transform,
+                    healthy: healthy.unwrap_or(false),
+                    base_url,
+                    last_error,
+                },
+            )
+            .collect())
+    }
+
+    /// Cheat observations, newest first.
+    async fn observations(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+        status: Option<String>,
+    ) -> async_graphql::Result<Connection<KeysetCursor, Observation>> {
+        let state = ctx.data::<AppState>()?;
+        let limit = page_size(first);
+        let after = decode_after(after)?;
+        let rows: Vec<ObservationRow> = sqlx::query_as(
+            r#"
+            select id, observation_type, source, player_uuid, player_name, cheat_type, label,
+                   started_at, ended_at, status, recorded_by_name, admin_notes, created_at
+            from public.cheat_observations
+            where server_id = $1
+              and ($2::text is null or status = $2)
+              and ($3::timestamptz is null or (created_at, id) < ($3, $4))
+            order by created_at desc, id desc
+            limit $5
+            "#,
+        )
+        .bind(&self.id)
+        .bind(status.as_deref())
+        .bind(after.map(|c| c.at))
+        .bind(after.map(|c| c.id))
+        .bind(limit + 1)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| internal("observations", e))?;
+
+        let observations = rows.into_iter().map(Observation::from_row).collect();
+        Ok(connection(observations, limit, after.is_some(), |o| {
+            KeysetCursor {
+                at: o.created_at,
+                id: o.id,
+            }
+        }))
+    }
+}
+
+type PlayerRow = (
+    Uuid,
+    String,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    Option<String>,
+    Option<String>,
+);
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Player {
+    #[graphql(skip)]
+    pub server_id: String,
+    pub uuid: Uuid,
+    pub username: String,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    /// Last known world / gamemode on this server.
+    pub world: Option<String>,
+    pub gamemode: Option<String>,
+}
+
+impl Player {
+    fn from_row(
+        server_id: &str,
+        (uuid, username, first_seen_at, last_seen_at, world, gamemode): PlayerRow,
+    ) -> Self {
+        Player {
+            server_id: server_id.to_string(),
+            uuid,
+            username,
+            first_seen_at,
+            last_seen_at,
+            world,
+            gamemode,
+        }
+    }
+}
+
+#[ComplexObject]
+impl Player {
+    /// This player's findings on the server, newest first.
+    async fn findings(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<i32>,
+        after: Option<String>,
+        status: Option<String>,
+    ) -> async_graphql::Result<Connection<KeysetCursor, Finding>> {
+        let filter = FindingFilter {
+            player_uuid: Some(self.uuid),
+            status,
+            ..Default::default()
+        };
+        load_findings(ctx, &self.server_id, filter, first, after).await
+    }
+}
+
+#[derive(Default)]
+struct FindingFilter {
+    player_uuid: Option<Uuid>,
+    severity: Option<String>,
+    status: Option<String>,
+    world: Option<String>,
+    detector: Option<String>,
+}
+
+type FindingRow = (
+    Uuid,
+    Option<Uuid>,
+    String,
+    Option<String>,
+    String,
+    String,
+    Option<String>,
+    i32,
+    String,
+    Option<String>,
+    Option<String>,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    Option<String>,
+    Option<serde_json::Value>,
+);
+
+async fn load_findings(
+    ctx: &Context<'_>,
+    server_id: &str,
+    filter: FindingFilter,
+    first: Option<i32>,
+    after: Option<String>,
+) -> async_graphql::Result<Connection<KeysetCursor, Finding>> {
+    let state = ctx.data::<AppState>()?;
+    let limit = page_size(first);
+    let after = decode_after(after)?;
+    let rows: Vec<FindingRow> = sqlx::query_as(
+        r#"
+        select id, player_uuid, detector_name, detector_version, severity, title, description,
+               occurrences, status, world, source, created_at, last_seen_at, evidence_s3_key,
+               evidence_json
+        from public.findings
+        where server_id = $1
+          and ($2::uuid is null or player_uuid = $2)
+          and ($3::text is null or severity = $3)
+          and ($4::text is null or status = $4)
+          and ($5::text is null or world = $5)
+          and ($6::text is null or detector_name = $6)
+          and ($7::timestamptz is null or (created_at, id) < ($7, $8))
+        order by created_at desc, id desc
+        limit $9
+        "#,
+    )
+    .bind(server_id)
+    .bind(filter.player_uuid)
+    .bind(filter.severity.as_deref())
+    .bind(filter.status.as_deref())
+    .bind(filter.world.as_deref())
+    .bind(filter.detector.as_deref())
+    .bind(after.map(|c| c.at))
+    .bind(after.map(|c| c.id))
+    .bind(limit + 1)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| internal("findings", e))?;
+
+    let findings = rows.into_iter().map(Finding::from_row).collect();
+    Ok(connection(findings, limit, after.is_some(), |f| {
+        KeysetCursor {
+            at: f.created_at,
+            id: f.id,
+        }
+    }))
+}
+
+#[derive(SimpleObject)]
+pub struct Finding {
+    pub id: Uuid,
+    pub player_uuid: Option<Uuid>,
+    pub detector_name: String,
+    pub detector_version: Option<String>,
+    pub severity: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub occurrences: i32,
+    /// open | confirmed | dismissed | false_positive
+    pub status: String,
+    pub world: Option<String>,
+    /// Anticheat log the finding was imported from (ncp | grim | vulcan); null for our own.
+    pub source: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    #[graphql(guard = "AdminOnly")]
+    pub evidence_s3_key: Option<String>,
+    #[graphql(guard = "AdminOnly")]
+    pub evidence: Option<Json<serde_json::Value>>,
+}
+
+impl Finding {
+    fn from_row(
+        (
+            id,
+            player_uuid,
+            detector_name,
+            detector_version,
+            severity,
+            title,
+            description,
+            occurrences,
+            status,
+            world,
+            source,
+            created_at,
+            last_seen_at,
+            evidence_s3_key,
+            evidence_json,
+        ): FindingRow,
+    ) -> Self {
+        Finding {
+            id,
+            player_uuid,
+            detector_name,
+            detector_version,
+            severity,
+            title,
+            description,
+            occurrences,
+            status,
+            world,
+            source,
+            created_at,
+            last_seen_at,
+            evidence_s3_key,
+            evidence: evidence_json.map(Json),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Module {
+    pub id: Uuid,
+    pub name: String,
+    pub enabled: bool,
+    pub transform: String,
+    pub healthy: bool,
+    #[graphql(guard = "AdminOnly")]
+    pub base_url: String,
+    #[graphql(guard = "AdminOnly")]
+    pub last_error: Option<String>,
+}
+
+type ObservationRow = (
+    Uuid,
+    String,
+    String,
+    Uuid,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+    String,
+    Option<String>,
+    Option<String>,
+    DateTime<Utc>,
+);
+
+#[derive(SimpleObject)]
+pub struct Observation {
+    pub id: Uuid,
+    /// false_positive | undetected | recording
+    pub observation_type: String,
+    pub source: String,
+    pub player_uuid: Uuid,
+    pub player_name: Option<String>,
+    pub cheat_type: Option<String>,
+    pub label: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub status: String,
+    pub recorded_by_name: Option<String>,
+    #[graphql(guard = "AdminOnly")]
+    pub admin_notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Observation {
+    fn from_row(
+        (
+            id,
+            observation_type,
+            source,
+            player_uuid,
+            player_name,
+            cheat_type,
+            label,
+            started_at,
+            ended_at,
+            status,
+            recorded_by_name,
+            admin_notes,
+            created_at,
+        ): ObservationRow,
+    ) -> Self {
+        Observation {
+            id,
+            observation_type,
+            source,
+            player_uuid,
+            player_name,
+            cheat_type,
+            label,
+            started_at,
+            ended_at,
+            status,
+            recorded_by_name,
+            admin_notes,
+            created_at,
+        }
+    }
+}
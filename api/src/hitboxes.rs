@@ -0,0 +1,136 @@
+//! Entity hitbox dimensions, for reach measured the way the game (and NCP) does: from the
+//! attacker's eyes to the nearest point of the target's bounding box rather than to its feet.
+//!
+//! Entity types come from spawn packets (`entity_type`), named after the registry
+//! (`minecraft:zombie`), Bukkit (`PIG_ZOMBIE`) or PacketEvents; [`normalize_type`] maps them to
+//! the bare registry name. Types missing from [`HITBOXES`] (and targets spawned before the
+//! batch) get the player's box, the common case for reach checks.
+
+/// Bounding box size in blocks; the entity's position is the bottom center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hitbox {
+    pub width: f64,
+    pub height: f64,
+}
+
+const fn hitbox(width: f64, height: f64) -> Hitbox {
+    Hitbox { width, height }
+}
+
+pub const PLAYER: Hitbox = hitbox(0.6, 1.8);
+
+/// 1.8 clients pick targets with boxes grown by this much on every side (removed in 1.9).
+pub const LEGACY_EXPANSION: f64 = 0.1;
+
+/// Adult sizes by registry name. Size-dependent entities (slimes, magma cubes) are left out.
+pub const HITBOXES: [(&str, Hitbox); 54] = [
+    ("player", PLAYER),
+    ("armor_stand", hitbox(0.5, 1.975)),
+    ("bat", hitbox(0.5, 0.9)),
+    ("bee", hitbox(0.7, 0.6)),
+    ("blaze", hitbox(0.6, 1.8)),
+    ("breeze", hitbox(0.6, 1.77)),
+    ("cat", hitbox(0.6, 0.7)),
+    ("cave_spider", hitbox(0.7, 0.5)),
+    ("chicken", hitbox(0.4, 0.7)),
+    ("cow", hitbox(0.9, 1.4)),
+    ("creeper", hitbox(0.6, 1.7)),
+    ("donkey", hitbox(1.3964844, 1.5)),
+    ("drowned", hitbox(0.6, 1.95)),
+    ("elder_guardian", hitbox(1.9975, 1.9975)),
+    ("enderman", hitbox(0.6, 2.9)),
+    ("endermite", hitbox(0.4, 0.3)),
+    ("evoker", hitbox(0.6, 1.95)),
+    ("fox", hitbox(0.6, 0.7)),
+    ("ghast", hitbox(4.0, 4.0)),
+    ("goat", hitbox(0.9, 1.3)),
+    ("guardian", hitbox(0.85, 0.85)),
+    ("hoglin", hitbox(1.3964844, 1.4)),
+    ("horse", hitbox(1.3964844, 1.6)),
+    ("husk", hitbox(0.6, 1.95)),
+    ("illusioner", hitbox(0.6, 1.95)),
+    ("iron_golem", hitbox(1.4, 2.7)),
+    ("llama", hitbox(0.9, 1.87)),
+    ("mooshroom", hitbox(0.9, 1.4)),
+    ("mule", hitbox(1.3964844, 1.6)),
+    ("phantom", hitbox(0.9, 0.5)),
+    ("pig", hitbox(0.9, 0.9)),
+    ("piglin", hitbox(0.6, 1.95)),
+    ("piglin_brute", hitbox(0.6, 1.95)),
+    ("pillager", hitbox(0.6, 1.95)),
+    ("polar_bear", hitbox(1.4, 1.4)),
+    ("rabbit", hitbox(0.4, 0.5)),
+    ("ravager", hitbox(1.95, 2.2)),
+    ("sheep", hitbox(0.9, 1.3)),
+    ("shulker", hitbox(1.0, 1.0)),
+    ("silverfish", hitbox(0.4, 0.3)),
+    ("skeleton", hitbox(0.6, 1.99)),
+    ("snow_golem", hitbox(0.7, 1.9)),
+    ("spider", hitbox(1.4, 0.9)),
+    ("stray", hitbox(0.6, 1.99)),
+    ("vex", hitbox(0.4, 0.8)),
+    ("villager", hitbox(0.6, 1.95)),
+    ("vindicator", hitbox(0.6, 1.95)),
+    ("warden", hitbox(0.9, 2.9)),
+    ("witch", hitbox(0.6, 1.95)),
+    ("wither", hitbox(0.9, 3.5)),
+    ("wither_skeleton", hitbox(0.7, 2.4)),
+    ("wolf", hitbox(0.6, 0.85)),
+    ("zombie", hitbox(0.6, 1.95)),
+    ("zombified_piglin", hitbox(0.6, 1.95)),
+];
+
+/// `(legacy, registry)` entity type names (Bukkit before 1.16 / 1.20.5, pre-flattening IDs).
+const TYPE_ALIASES: [(&str, &str); 6] = [
+    ("pig_zombie", "zombified_piglin"),
+    ("zombie_pigman", "zombified_piglin"),
+    ("snowman", "snow_golem"),
+    ("villager_golem", "iron_golem"),
+    ("mushroom_cow", "mooshroom"),
+    ("entity_human", "player"),
+];
+
+/// Bare lowercase registry name of an entity type (`minecraft:Zombie` -> `zombie`).
+pub fn normalize_type(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let name = raw
+        .rsplit_once(':')
+        .map_or(raw, |(_, name)| name)
+        .to_ascii_lowercase()
+        .replace([' ', '-'], "_");
+    if name.is_empty() {
+        return None;
+    }
+    Some(
+        TYPE_ALIASES
+            .iter()
+            .find(|(legacy, _)| *legacy == name)
+            .map_or(name, |(_, registry)| registry.to_string()),
+    )
+}
+
+/// The hitbox of a (normalized) entity type, the player's for unknown ones.
+pub fn for_type(entity_type: Option<&str>) -> Hitbox {
+    entity_type
+        .and_then(|t| HITBOXES.iter().find(|(name, _)| *name == t))
+        .map_or(PLAYER, |(_, hitbox)| *hitbox)
+}
+
+/// Distances from `eye` to the center of the box of an entity at `feet`, and to the box's
+/// nearest point (0 from inside), with the box grown by `expand` on every side.
+pub fn reach(eye: [f64; 3], feet: [f64; 3], hitbox: Hitbox, expand: f64) -> (f64, f64) {
+    let half = hitbox.width / 2.0 + expand;
+    let min = [feet[0] - half, feet[1] - expand, feet[2] - half];
+    let max = [
+        feet[0] + half,
+        feet[1] + hitbox.height + expand,
+        feet[2] + half,
+    ];
+    let center = [feet[0], feet[1] + hitbox.height / 2.0, feet[2]];
+
+    let dist = |p: [f64; 3]| {
+        ((p[0] - eye[0]).powi(2) + (p[1] - eye[1]).powi(2) + (p[2] - eye[2]).powi(2)).sqrt()
+    };
+    let nearest = [0, 1, 2].map(|i| eye[i].clamp(min[i], max[i]));
+    (dist(center), dist(nearest))
+}
@@ -0,0 +1,62 @@
+//! Shared-identity signals for alt-account correlation.
+//!
+//! Packet records may carry `ip_hash` (a salted hash computed by the plugin; raw IPs are never
+//! sent), an opaque client `fingerprint`, and the `client_brand`. Each distinct value is stored
+//! per server in `player_identifiers`. Accounts sharing an IP hash or fingerprint on the same
+//! server are "related"; the brand alone is far too common to link accounts and is only reported
+//! alongside a match.
+//!
+//! Identifier values never leave the API; the dashboard only sees which kinds matched.
+
+use std::collections::HashSet;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub const IP_HASH: &str = "ip_hash";
+pub const FINGERPRINT: &str = "fingerprint";
+pub const CLIENT_BRAND: &str = "client_brand";
+
+/// Kinds that link two accounts on their own.
+pub const LINKING_KINDS: [&str; 2] = [IP_HASH, FINGERPRINT];
+
+const MAX_VALUE_LEN: usize = 128;
+
+/// Clean up a plugin-supplied identifier; `None` if it is unusable.
+pub fn normalize(kind: &str, value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value.len() > MAX_VALUE_LEN {
+        return None;
+    }
+    Some(match kind {
+        // Case-insensitive: hex case varies between encoders, brand casing between clients.
+        IP_HASH | FINGERPRINT | CLIENT_BRAND => value.to_lowercase(),
+        _ => return None,
+    })
+}
+
+/// Upsert identifiers seen in a batch (`(player, kind, normalized value)`).
+pub async fn record(
+    db: &PgPool,
+    server_id: &str,
+    seen: &HashSet<(Uuid, &'static str, String)>,
+) -> Result<(), sqlx::Error> {
+    for (player_uuid, kind, value) in seen {
+        sqlx::query(
+            r#"
+            insert into public.player_identifiers
+                (server_id, player_uuid, kind, value, first_seen_at, last_seen_at)
+            values ($1, $2, $3, $4, now(), now())
+            on conflict (server_id, player_uuid, kind, value) do update set
+                last_seen_at = now()
+            "#,
+        )
+        .bind(server_id)
+        .bind(player_uuid)
+        .bind(kind)
+        .bind(value)
+        .execute(db)
+        .await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,317 @@
+//! Suspicious-ingest detection.
+//!
+//! Every batch is inspected before it is stored: a payload that does not decode, whose bytes
+//! are far too uniform or too random for NDJSON, whose record timestamps lie in the future or
+//! could not have been produced by a running server, or that repeats a batch the server sent
+//! recently is quarantined. Quarantined batches are kept (S3 + `batch_index`, flagged
+//! `quarantined`) so they can be examined, but are never dispatched to modules, reprocessed or
+//! used for player tracking; each reason is recorded as an `ingest_anomalies` row.
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Digest;
+use sqlx::PgPool;
+use std::io::Read;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Decompressed size above which a batch is treated as a decompression bomb.
+pub const MAX_INSPECT_BYTES: usize = 64 * 1024 * 1024;
+
+/// Entropy is only meaningful on payloads of some size.
+pub const ENTROPY_MIN_BYTES: usize = 4096;
+/// Bits per byte. NDJSON packet records sit around 4-5; padding or a single repeated byte is
+/// far below, compressed or encrypted data (not text) far above.
+pub const ENTROPY_MIN_BITS: f64 = 2.0;
+pub const ENTROPY_MAX_BITS: f64 = 6.8;
+
+/// Record timestamps may run ahead of the API clock by this much (clock drift) before they
+/// count as future.
+pub const FUTURE_TOLERANCE_MS: i64 = 10 * 60 * 1000;
+/// Timestamps before this (2020-01-01) cannot come from a running plugin.
+pub const MIN_PLAUSIBLE_TS_MS: i64 = 1_577_836_800_000;
+/// A single batch covers seconds of capture; anything spanning more than this is fabricated.
+pub const MAX_BATCH_SPAN_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// How far back a repeated payload is looked for.
+pub const REPEAT_WINDOW_HOURS: i32 = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Anomaly {
+    /// Not gzip, or truncated.
+    Undecodable {
+        error: String,
+    },
+    DecompressedTooLarge {
+        limit: usize,
+    },
+    LowEntropy {
+        bits_per_byte: f64,
+    },
+    HighEntropy {
+        bits_per_byte: f64,
+    },
+    FutureTimestamp {
+        count: usize,
+        max_ts: i64,
+        now_ms: i64,
+    },
+    /// Before `MIN_PLAUSIBLE_TS_MS`, or the batch spans more than `MAX_BATCH_SPAN_MS`.
+    ImpossibleTimestamp {
+        count: usize,
+        min_ts: i64,
+        max_ts: i64,
+    },
+    /// Same content as a batch the server sent within `REPEAT_WINDOW_HOURS`.
+    RepeatedPayload {
+        previous_batch_id: Uuid,
+    },
+}
+
+impl Anomaly {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Anomaly::Undecodable { .. } => "undecodable",
+            Anomaly::DecompressedTooLarge { .. } => "decompressed_too_large",
+            Anomaly::LowEntropy { .. } => "low_entropy",
+            Anomaly::HighEntropy { .. } => "high_entropy",
+            Anomaly::FutureTimestamp { .. } => "future_timestamp",
+            Anomaly::ImpossibleTimestamp { .. } => "impossible_timestamp",
+            Anomaly::RepeatedPayload { .. } => "repeated_payload",
+        }
+    }
+
+    pub fn details(&self) -> Value {
+        match self {
+            Anomaly::Undecodable { error } => json!({ "error": error }),
+            Anomaly::DecompressedTooLarge { limit } => json!({ "limit_bytes": limit }),
+            Anomaly::LowEntropy { bits_per_byte } | Anomaly::HighEntropy { bits_per_byte } => {
+                json!({ "bits_per_byte": bits_per_byte })
+            }
+            Anomaly::FutureTimestamp {
+                count,
+                max_ts,
+                now_ms,
+            } => json!({ "records": count, "max_ts": max_ts, "received_ms": now_ms }),
+            Anomaly::ImpossibleTimestamp {
+                count,
+                min_ts,
+                max_ts,
+            } => json!({ "records": count, "min_ts": min_ts, "max_ts": max_ts }),
+            Anomaly::RepeatedPayload { previous_batch_id } => {
+                json!({ "previous_batch_id": previous_batch_id })
+            }
+        }
+    }
+}
+
+/// Result of inspecting one batch body.
+#[derive(Debug, Clone, Default)]
+pub struct Inspection {
+    /// Hex SHA-256 of the decompressed payload (gzip headers differ between identical batches);
+    /// `None` when it did not decode.
+    pub sha256: Option<String>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+/// Shannon entropy of `bytes`, in bits per byte.
+pub fn entropy(bytes: &[u8]) -> f64 {
+    if bytes.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for b in bytes {
+        counts[*b as usize] += 1;
+    }
+    let len = bytes.len() as f64;
+    counts
+        .iter()
+        .filter(|c| **c > 0)
+        .map(|c| {
+            let p = *c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Inspect a gzipped NDJSON batch received at `now_ms` (CPU-bound; run it off the runtime).
+/// The repeated-payload check needs the database and is done by [`find_repeat`].
+pub fn inspect(raw_gz: &[u8], now_ms: i64) -> Inspection {
+    let mut payload = Vec::new();
+    let read = GzDecoder::new(raw_gz)
+        .take(MAX_INSPECT_BYTES as u64 + 1)
+        .read_to_end(&mut payload);
+    if let Err(e) = read {
+        return Inspection {
+            sha256: None,
+            anomalies: vec![Anomaly::Undecodable {
+                error: e.to_string(),
+            }],
+        };
+    }
+    if payload.len() > MAX_INSPECT_BYTES {
+        return Inspection {
+            sha256: None,
+            anomalies: vec![Anomaly::DecompressedTooLarge {
+                limit: MAX_INSPECT_BYTES,
+            }],
+        };
+    }
+
+    let mut anomalies = Vec::new();
+    if payload.len() >= ENTROPY_MIN_BYTES {
+        let bits_per_byte = entropy(&payload);
+        if bits_per_byte < ENTROPY_MIN_BITS {
+            anomalies.push(Anomaly::LowEntropy { bits_per_byte });
+        } else if bits_per_byte > ENTROPY_MAX_BITS {
+            anomalies.push(Anomaly::HighEntropy { bits_per_byte });
+        }
+    }
+    anomalies.extend(timestamp_anomalies(&payload, now_ms));
+
+    Inspection {
+        sha256: Some(hex::encode(sha2::Sha256::digest(&payload))),
+        anomalies,
+    }
+}
+
+/// Future / impossible record timestamps (line 0 is the batch metadata and is skipped).
+fn timestamp_anomalies(payload: &[u8], now_ms: i64) -> Vec<Anomaly> {
+    let mut future = 0usize;
+    let mut too_old = 0usize;
+    let mut min_ts = i64::MAX;
+    let mut max_ts = i64::MIN;
+    for line in payload.split(|b| *b == b'\n').skip(1) {
+        if line.is_empty() {
+            continue;
+        }
+        let Ok(v) = serde_json::from_slice::<Value>(line) else {
+            continue;
+        };
+        let Some(ts) = v.get("ts").and_then(|t| t.as_i64()) else {
+            continue;
+        };
+        if ts > now_ms + FUTURE_TOLERANCE_MS {
+            future += 1;
+        }
+        if ts < MIN_PLAUSIBLE_TS_MS {
+            too_old += 1;
+        }
+        min_ts = min_ts.min(ts);
+        max_ts = max_ts.max(ts);
+    }
+
+    let mut anomalies = Vec::new();
+    if future > 0 {
+        anomalies.push(Anomaly::FutureTimestamp {
+            count: future,
+            max_ts,
+            now_ms,
+        });
+    }
+    if too_old > 0 || (min_ts <= max_ts && max_ts.saturating_sub(min_ts) > MAX_BATCH_SPAN_MS) {
+        anomalies.push(Anomaly::ImpossibleTimestamp {
+            count: too_old,
+            min_ts,
+            max_ts,
+        });
+    }
+    anomalies
+}
+
+/// A recent batch of the server with the same decompressed content.
+pub async fn find_repeat(
+    db: &PgPool,
+    server_id: &str,
+    sha256: &str,
+) -> Result<Option<Uuid>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        select id from public.batch_index
+        where server_id = $1
+          and payload_sha256 = $2
+          and received_at > now() - make_interval(hours => $3)
+        order by received_at desc
+        limit 1
+        "#,
+    )
+    .bind(server_id)
+    .bind(sha256)
+    .bind(REPEAT_WINDOW_HOURS)
+    .fetch_optional(db)
+    .await
+}
+
+/// Record why a batch was quarantined (after its `batch_index` row exists).
+pub async fn record(
+    db: &PgPool,
+    batch_id: Uuid,
+    server_id: &str,
+    session_id: &str,
+    anomalies: &[Anomaly],
+) -> Result<(), sqlx::Error> {
+    for anomaly in anomalies {
+        sqlx::query(
+            r#"
+            insert into public.ingest_anomalies (batch_id, server_id, session_id, kind, details)
+            values ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(batch_id)
+        .bind(server_id)
+        .bind(session_id)
+        .bind(anomaly.kind())
+        .bind(anomaly.details())
+        .execute(db)
+        .await?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IngestAnomaly {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub batch_id: Uuid,
+    pub session_id: String,
+    /// undecodable | decompressed_too_large | low_entropy | high_entropy | future_timestamp |
+    /// impossible_timestamp | repeated_payload
+    pub kind: String,
+    pub details: Value,
+}
+
+/// The server's most recent anomalies, newest first.
+pub async fn list(
+    db: &PgPool,
+    server_id: &str,
+    limit: i64,
+) -> Result<Vec<IngestAnomaly>, sqlx::Error> {
+    let rows: Vec<(Uuid, DateTime<Utc>, Uuid, String, String, Value)> = sqlx::query_as(
+        r#"
+        select id, created_at, batch_id, session_id, kind, details
+        from public.ingest_anomalies
+        where server_id = $1
+        order by created_at desc
+        limit $2
+        "#,
+    )
+    .bind(server_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(id, created_at, batch_id, session_id, kind, details)| IngestAnomaly {
+                id,
+                created_at,
+                batch_id,
+                session_id,
+                kind,
+                details,
+            },
+        )
+        .collect())
+}
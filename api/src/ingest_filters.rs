@@ -0,0 +1,238 @@
+//! Per-server packet filters applied at ingest.
+//!
+//! Plugins capture packets no check reads (keep-alives, chat, sounds, ...). A server's filters,
+//! edited from the dashboard, name packet types to drop before the batch is stored, so they
+//! cost neither storage nor transform time. A rule names a packet type (canonical or any alias,
+//! see `packet_names`) or a prefix ending in `*` (`CHAT_*`), optionally for one direction.
+//!
+//! Packets the built-in transforms, checks and session tracking read can't be filtered: a rule
+//! matching one is refused. The metadata line, records without a `pkt` and lines that aren't
+//! JSON are always kept. Batches from which nothing is dropped are stored byte for byte.
+
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Write};
+
+use chrono::{DateTime, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+use crate::packet_names;
+use crate::plugin_version::Version;
+
+pub const MAX_RULES: usize = 100;
+pub const SERVERBOUND: &str = "serverbound";
+pub const CLIENTBOUND: &str = "clientbound";
+
+/// Packets read by the built-in transforms, checks and player/session tracking.
+pub const PROTECTED: [&str; 21] = [
+    packet_names::PLAYER_POSITION,
+    packet_names::PLAYER_POSITION_AND_ROTATION,
+    packet_names::PLAYER_ROTATION,
+    packet_names::PLAYER_FLYING,
+    packet_names::INTERACT_ENTITY,
+    packet_names::PLUGIN_MESSAGE,
+    packet_names::SPAWN_ENTITY,
+    packet_names::SPAWN_LIVING_ENTITY,
+    packet_names::SPAWN_PLAYER,
+    packet_names::ENTITY_TELEPORT,
+    packet_names::ENTITY_RELATIVE_MOVE,
+    packet_names::ENTITY_RELATIVE_MOVE_AND_ROTATION,
+    packet_names::ENTITY_ROTATION,
+    packet_names::DESTROY_ENTITIES,
+    packet_names::PLAYER_POSITION_AND_LOOK,
+    "PLAYER_STATE",
+    "PLAYER_JOIN",
+    "PLAYER_QUIT",
+    "LOGIN",
+    "LOGOUT",
+    "DISCONNECT",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct IngestFilterRule {
+    /// Packet type, or a prefix ending in `*`.
+    pub packet: String,
+    /// "serverbound" | "clientbound"; unset drops the packet in both directions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+}
+
+impl IngestFilterRule {
+    fn validate(&self) -> Result<Self, String> {
+        let raw = self.packet.trim();
+        let (name, wildcard) = match raw.strip_suffix('*') {
+            Some(prefix) => (prefix, true),
+            None => (raw, false),
+        };
+        let valid = |s: &str| s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if (name.is_empty() && !wildcard) || name.len() > 64 || !valid(name) {
+            return Err(format!("invalid packet type: {:?}", self.packet));
+        }
+        let packet = if wildcard {
+            format!("{}*", name.to_ascii_uppercase())
+        } else {
+            packet_names::lookup_name(name).to_ascii_uppercase()
+        };
+        let dir = match self.dir.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(d) if d.eq_ignore_ascii_case(SERVERBOUND) => Some(SERVERBOUND.to_string()),
+            Some(d) if d.eq_ignore_ascii_case(CLIENTBOUND) => Some(CLIENTBOUND.to_string()),
+            Some(d) => return Err(format!("invalid direction: {:?}", d)),
+        };
+        let rule = Self { packet, dir };
+        if let Some(p) = PROTECTED.iter().find(|p| rule.matches_name(p)) {
+            return Err(format!(
+                "{} would drop {}, which the built-in checks need",
+                rule.packet, p
+            ));
+        }
+        Ok(rule)
+    }
+
+    fn matches_name(&self, name: &str) -> bool {
+        match self.packet.strip_suffix('*') {
+            Some(prefix) => name
+                .get(..prefix.len())
+                .is_some_and(|head| head.eq_ignore_ascii_case(prefix)),
+            None => name.eq_ignore_ascii_case(&self.packet),
+        }
+    }
+
+    /// Whether the rule drops a record of canonical packet type `name` sent in `dir` (empty
+    /// when unknown; only direction-less rules match those).
+    pub fn matches(&self, name: &str, dir: &str) -> bool {
+        self.dir.as_deref().is_none_or(|d| d == dir) && self.matches_name(name)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct IngestFilters {
+    pub rules: Vec<IngestFilterRule>,
+}
+
+impl IngestFilters {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Normalize packet names (upper case, canonical, distinct) and refuse rules matching
+    /// [`PROTECTED`] packets.
+    pub fn validate(self) -> Result<Self, String> {
+        if self.rules.len() > MAX_RULES {
+            return Err(format!("at most {} rules", MAX_RULES));
+        }
+        let mut rules = self
+            .rules
+            .iter()
+            .map(IngestFilterRule::validate)
+            .collect::<Result<Vec<_>, _>>()?;
+        rules.sort_by(|a, b| (&a.packet, &a.dir).cmp(&(&b.packet, &b.dir)));
+        rules.dedup();
+        Ok(Self { rules })
+    }
+
+    pub fn drops(&self, name: &str, dir: &str) -> bool {
+        self.rules.iter().any(|r| r.matches(name, dir))
+    }
+}
+
+#[derive(Deserialize)]
+struct RecordHead<'a> {
+    #[serde(borrow, default)]
+    pkt: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    dir: Option<Cow<'a, str>>,
+}
+
+/// Drop the records `filters` match from a gzipped batch, one line at a time. Returns the
+/// filtered batch and the number of records dropped, or None when nothing matched.
+pub fn apply(
+    raw_gz_ndjson: &[u8],
+    filters: &IngestFilters,
+) -> anyhow::Result<Option<(Vec<u8>, usize)>> {
+    if filters.is_empty() {
+        return Ok(None);
+    }
+
+    let mut reader = BufReader::new(GzDecoder::new(raw_gz_ndjson));
+    let mut out = Vec::with_capacity(raw_gz_ndjson.len());
+    let mut encoder = GzEncoder::new(&mut out, Compression::default());
+    let mut version: Option<Version> = None;
+    let mut buf = String::new();
+    let mut first = true;
+    let mut dropped = 0usize;
+
+    while {
+        buf.clear();
+        reader.read_line(&mut buf)?
+    } != 0
+    {
+        let line = buf.trim_end_matches(['\n', '\r']);
+        if first {
+            first = false;
+            version = serde_json::from_str::<serde_json::Value>(line)
+                .ok()
+                .and_then(|meta| packet_names::batch_version(&meta));
+        } else if let Ok(RecordHead {
+            pkt: Some(pkt),
+            dir,
+        }) = serde_json::from_str(line)
+        {
+            let dir = dir.as_deref().unwrap_or("");
+            if filters.drops(packet_names::canonical(&pkt, dir, version), dir) {
+                dropped += 1;
+                continue;
+            }
+        }
+        encoder.write_all(line.as_bytes())?;
+        encoder.write_all(b"\n")?;
+    }
+
+    encoder.finish()?;
+    Ok((dropped > 0).then_some((out, dropped)))
+}
+
+/// The server's filters (none until saved) and when they were last changed.
+pub async fn load(
+    db: &PgPool,
+    server_id: &str,
+) -> Result<(IngestFilters, Option<DateTime<Utc>>), sqlx::Error> {
+    let row: Option<(sqlx::types::Json<IngestFilters>, DateTime<Utc>)> = sqlx::query_as(
+        "select filters, updated_at from public.server_ingest_filters where server_id = $1",
+    )
+    .bind(server_id)
+    .fetch_optional(db)
+    .await?;
+    Ok(match row {
+        Some((filters, updated_at)) => (filters.0, Some(updated_at)),
+        None => (IngestFilters::default(), None),
+    })
+}
+
+/// Save validated filters; they apply from the server's next batch.
+pub async fn save(
+    db: &PgPool,
+    server_id: &str,
+    filters: &IngestFilters,
+    updated_by: Option<&str>,
+) -> Result<DateTime<Utc>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        insert into public.server_ingest_filters (server_id, filters, updated_at, updated_by)
+        values ($1, $2, now(), $3)
+        on conflict (server_id) do update set
+            filters = excluded.filters,
+            updated_at = excluded.updated_at,
+            updated_by = excluded.updated_by
+        returning updated_at
+        "#,
+    )
+    .bind(server_id)
+    .bind(sqlx::types::Json(filters))
+    .bind(updated_by)
+    .fetch_one(db)
+    .await
+}
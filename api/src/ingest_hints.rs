@@ -0,0 +1,129 @@
+//! Adaptive batching hints in the ingest response.
+//!
+//! Every accepted batch is answered with `hints`: how backed up the API is, how long the plugin
+//! should wait before its next upload and the largest payload it should send. Load is measured
+//! from batches still being dispatched to modules (queued or running) and database pool
+//! saturation, whichever is higher relative to its limit:
+//!
+//! - `normal` (below half): no delay, payloads up to `MAX_BODY_BYTES`
+//! - `elevated`: a delay rising from [`MIN_BACKOFF_MS`] towards [`MAX_BACKOFF_MS`] and half the
+//!   payload size
+//! - `saturated` (from [`SATURATED_PRESSURE`]): close to the longest delay and a quarter of the
+//!   payload size
+//!
+//! Plugins keep spooling to disk while they wait, so backing off delays data instead of
+//! dropping it.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+/// Pending module dispatches at which the API counts as fully loaded.
+pub const DISPATCH_BACKLOG_HIGH: usize = 256;
+pub const MIN_BACKOFF_MS: u64 = 2_000;
+pub const MAX_BACKOFF_MS: u64 = 30_000;
+const ELEVATED_PRESSURE: f64 = 0.5;
+const SATURATED_PRESSURE: f64 = 0.9;
+
+/// Batches accepted but not yet through the module pipeline.
+#[derive(Debug, Clone, Default)]
+pub struct DispatchBacklog(Arc<AtomicUsize>);
+
+impl DispatchBacklog {
+    /// Count a batch until the returned guard is dropped.
+    pub fn track(&self) -> BacklogGuard {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        BacklogGuard(self.0.clone())
+    }
+
+    pub fn pending(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug)]
+pub struct BacklogGuard(Arc<AtomicUsize>);
+
+impl Drop for BacklogGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Backlog {
+    Normal,
+    Elevated,
+    Saturated,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct IngestHints {
+    pub backlog: Backlog,
+    /// Batches waiting for or in module dispatch, across all servers.
+    pub pending_dispatches: usize,
+    /// Wait at least this long before the next upload; null keeps the configured interval.
+    pub next_batch_interval_ms: Option<u64>,
+    /// Largest compressed batch to send next.
+    pub max_payload_bytes: usize,
+}
+
+/// A snapshot of the load hints are computed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Load {
+    pub pending_dispatches: usize,
+    pub db_connections_busy: u32,
+    pub db_connections_max: u32,
+}
+
+impl Load {
+    pub fn current(backlog: &DispatchBacklog, db: &PgPool) -> Self {
+        let open = db.size();
+        Self {
+            pending_dispatches: backlog.pending(),
+            db_connections_busy: open.saturating_sub(db.num_idle() as u32),
+            db_connections_max: db.options().get_max_connections(),
+        }
+    }
+
+    /// The higher of dispatch backlog and database pool use, relative to their limits.
+    pub fn pressure(&self) -> f64 {
+        let dispatch = self.pending_dispatches as f64 / DISPATCH_BACKLOG_HIGH as f64;
+        let db = if self.db_connections_max == 0 {
+            0.0
+        } else {
+            self.db_connections_busy as f64 / self.db_connections_max as f64
+        };
+        dispatch.max(db)
+    }
+}
+
+pub fn hints(load: &Load, max_body_bytes: usize) -> IngestHints {
+    let pressure = load.pressure();
+    let backlog = if pressure >= SATURATED_PRESSURE {
+        Backlog::Saturated
+    } else if pressure >= ELEVATED_PRESSURE {
+        Backlog::Elevated
+    } else {
+        Backlog::Normal
+    };
+    let next_batch_interval_ms = (backlog != Backlog::Normal).then(|| {
+        let over = ((pressure - ELEVATED_PRESSURE) / (1.0 - ELEVATED_PRESSURE)).min(1.0);
+        MIN_BACKOFF_MS + ((MAX_BACKOFF_MS - MIN_BACKOFF_MS) as f64 * over).round() as u64
+    });
+    let max_payload_bytes = match backlog {
+        Backlog::Normal => max_body_bytes,
+        Backlog::Elevated => max_body_bytes / 2,
+        Backlog::Saturated => max_body_bytes / 4,
+    };
+    IngestHints {
+        backlog,
+        pending_dispatches: load.pending_dispatches,
+        next_batch_interval_ms,
+        max_payload_bytes,
+    }
+}
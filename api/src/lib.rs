@@ -1,18 +1,97 @@
+// sqlx row tuples are used pervasively for ad-hoc query results.
+#![allow(clippy::type_complexity)]
+
+pub mod app;
+pub mod async_dispatch;
 pub mod auth;
+pub mod batch_meta;
+pub mod batch_sequence;
 pub mod builtin_modules;
+pub mod capture_config;
+pub mod cases;
+pub mod chaos;
+pub mod checks;
+pub mod client_channels;
+pub mod clock_skew;
 pub mod config;
+pub mod conformance;
+pub mod correlation;
 pub mod db;
+pub mod detector_metrics;
+pub mod dispatch_encoding;
+pub mod dispatch_parts;
+pub mod dispatch_shedding;
+pub mod dispatch_stats;
 pub mod error;
+pub mod evidence_bundle;
+pub mod exemptions;
+pub mod finding_latency;
+pub mod findings_import;
+pub mod graphql;
+pub mod hitboxes;
+pub mod identity;
+pub mod ingest_anomalies;
+pub mod ingest_filters;
+pub mod ingest_hints;
+pub mod logging;
+pub mod maintenance;
+pub mod module_canary;
+pub mod module_failover;
+pub mod module_maintenance;
+pub mod module_outage;
 pub mod module_pipeline;
+#[cfg(feature = "module-sdk")]
+pub mod module_sdk;
+pub mod module_usage;
+pub mod movement_path;
+pub mod ndjson;
+pub mod notes;
 pub mod object_store_cleanup;
+pub mod openapi;
+pub mod packet_names;
+pub mod packet_viewer;
+pub mod pagination;
+pub mod pending_actions;
+pub mod player_sessions;
+pub mod player_state_decay;
+pub mod plugin_version;
+pub mod privacy;
+pub mod proxy_groups;
+pub mod punishments;
+pub mod replay;
+pub mod replica_shards;
+pub mod reports;
+pub mod reprocess;
+pub mod reputation;
+pub mod request_id;
 pub mod routes;
 pub mod s3;
+pub mod sampling;
+pub mod server_deletion;
+pub mod server_export;
+pub mod server_ping;
+pub mod server_presence;
+pub mod service_status;
+pub mod severity_overrides;
+pub mod shadow;
+pub mod shared_state;
+pub mod simulation;
+pub mod supervisor;
+pub mod task_lock;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod traffic_stats;
+pub mod transform_events;
+pub mod transform_pool;
 pub mod transforms;
+pub mod wasm_runtime;
+pub mod watchlist;
 pub mod webhooks;
 
 use sqlx::PgPool;
 
 use crate::s3::ObjectStore;
+use crate::supervisor::Supervisor;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -21,8 +100,13 @@ pub struct AppState {
     pub ingest_token: String,
     pub module_callback_token: String,
     pub dashboard_token: Option<String>,
+    pub admin_token: Option<String>,
     pub http: reqwest::Client,
     pub max_body_bytes: usize,
+    /// Module dispatches larger than this are sent in parts (0: never).
+    pub dispatch_part_max_bytes: usize,
+    /// Runs module transforms and in-process checks off the async runtime.
+    pub transform_pool: transform_pool::TransformPool,
     // Cleanup config
     pub object_store_cleanup_enabled: bool,
     pub object_store_cleanup_dry_run: bool,
@@ -31,4 +115,39 @@ pub struct AppState {
     pub object_store_ttl_seconds_override: Option<i64>,
     pub batch_index_ttl_days: i64,
     pub batch_index_ttl_seconds_override: Option<i64>,
+    // Module process supervisor (empty when disabled)
+    pub supervisor: Supervisor,
+    // Cross-server reputation
+    pub reputation_enabled: bool,
+    pub reputation_lookback_days: i64,
+    // Stale module player state expiry
+    pub player_state_decay_enabled: bool,
+    pub player_state_decay_days: i64,
+    pub watchlist_retention_days: i64,
+    pub module_dispatch_retention_days: i64,
+    pub server_offline_after_seconds: u64,
+    pub server_purge_grace_days: i64,
+    /// Key for player UUID pseudonyms on servers with `hash_player_uuids` (see `privacy`).
+    pub player_uuid_hash_key: Option<String>,
+    /// Batches not yet through module dispatch (load for ingest hints).
+    pub dispatch_backlog: ingest_hints::DispatchBacklog,
+    /// Live API replicas, for per-server ownership of queued work.
+    pub shards: replica_shards::ShardMap,
+    /// Caches and counters shared across replicas (Redis or in-process).
+    pub shared: shared_state::SharedState,
+    /// Batches accepted per server per minute (0: unlimited).
+    pub ingest_rate_limit_per_minute: u64,
+    /// Identical webhook notifications within this window are sent once (0: no dedup).
+    pub webhook_dedup_seconds: u64,
+    pub plugin_versions: plugin_version::VersionPolicy,
+    /// Request / response bytes per server and endpoint (`GET /admin/top-talkers`).
+    pub traffic: traffic_stats::TrafficStats,
+    /// `/ingest` outcomes for the public status page.
+    pub ingest_outcomes: service_status::IngestOutcomes,
+    /// Sends weekly report emails (disabled without `SMTP_URL`, see `reports`).
+    pub report_mailer: reports::Mailer,
+    /// Findings stored within this long of their batch's arrival meet the latency SLO.
+    pub finding_latency_slo_seconds: u64,
+    /// Fault rules of `chaos` builds (no-op otherwise).
+    pub faults: chaos::Faults,
 }
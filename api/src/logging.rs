@@ -0,0 +1,251 @@
+//! Log output: human-readable text (default) or one JSON object per line, and sampling of
+//! debug/trace events.
+//!
+//! `LOG_FORMAT=json` is meant for hosted deployments shipping logs to Loki/ELK. Each line holds
+//! `timestamp`, `level`, `target`, the event's fields (`message` included) and, at the top level,
+//! the [`CONTEXT_FIELDS`] of the spans the event is in: `request_id` and `server_id` from the
+//! `request` span (see [`crate::request_id`]), `batch_id` and `module` when a span carries them.
+//! A field of the event itself wins over the same field of a span.
+//!
+//! `LOG_DEBUG_SAMPLE_EVERY=N` keeps 1 in N debug and trace events of each call site (the first
+//! one always), so per-packet and per-batch debug logs can stay on without drowning the log
+//! pipeline. Info and above are never sampled. `RUST_LOG` still decides what is logged at all.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use chrono::{SecondsFormat, Utc};
+use serde_json::{Map, Value};
+use tracing::callsite::Identifier;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::fmt::format::{JsonFields, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormattedFields, MakeWriter};
+use tracing_subscriber::layer::{Context, Filter, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Span fields copied to the top level of JSON log lines.
+pub const CONTEXT_FIELDS: [&str; 4] = ["request_id", "server_id", "batch_id", "module"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "text" | "" => Some(LogFormat::Text),
+            "json" => Some(LogFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogSettings {
+    pub format: LogFormat,
+    /// Keep 1 in this many debug/trace events per call site (1 = all).
+    pub debug_sample_every: u64,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::Text,
+            debug_sample_every: 1,
+        }
+    }
+}
+
+impl LogSettings {
+    /// Read from `LOG_FORMAT` and `LOG_DEBUG_SAMPLE_EVERY`. Logging starts before [`crate::config`]
+    /// is loaded, so these are read here; invalid values fall back to the defaults.
+    pub fn from_env() -> Self {
+        let format = std::env::var("LOG_FORMAT")
+            .ok()
+            .and_then(|v| LogFormat::parse(&v))
+            .unwrap_or_default();
+        let debug_sample_every = std::env::var("LOG_DEBUG_SAMPLE_EVERY")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(1)
+            .max(1);
+        Self {
+            format,
+            debug_sample_every,
+        }
+    }
+}
+
+/// The subscriber for `settings`, writing to `writer` and filtered by `filter`.
+pub fn subscriber<W>(
+    settings: LogSettings,
+    filter: EnvFilter,
+    writer: W,
+) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let sampler = DebugSampler::new(settings.debug_sample_every);
+    let registry = tracing_subscriber::registry().with(filter);
+    match settings.format {
+        LogFormat::Text => Box::new(
+            registry.with(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(writer)
+                    .with_filter(sampler),
+            ),
+        ),
+        LogFormat::Json => Box::new(
+            registry.with(
+                tracing_subscriber::fmt::layer()
+                    .fmt_fields(JsonFields::new())
+                    .event_format(JsonFormat)
+                    .with_writer(writer)
+                    .with_filter(sampler),
+            ),
+        ),
+    }
+}
+
+/// Install the global subscriber from `LOG_FORMAT`, `LOG_DEBUG_SAMPLE_EVERY` and `RUST_LOG`.
+pub fn init() {
+    let settings = LogSettings::from_env();
+    tracing::subscriber::set_global_default(subscriber(
+        settings,
+        EnvFilter::from_default_env(),
+        std::io::stdout,
+    ))
+    .expect("a global tracing subscriber was already set");
+    if settings.debug_sample_every > 1 {
+        tracing::info!(
+            every = settings.debug_sample_every,
+            "debug and trace events are sampled"
+        );
+    }
+}
+
+/// Keeps every event at info and above, and 1 in N debug/trace events of each call site.
+pub struct DebugSampler {
+    every: u64,
+    seen: Mutex<HashMap<Identifier, u64>>,
+}
+
+impl DebugSampler {
+    pub fn new(every: u64) -> Self {
+        Self {
+            every: every.max(1),
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<S> Filter<S> for DebugSampler {
+    fn enabled(&self, meta: &Metadata<'_>, _cx: &Context<'_, S>) -> bool {
+        if self.every == 1 || !meta.is_event() || *meta.level() <= Level::INFO {
+            return true;
+        }
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        let count = seen.entry(meta.callsite()).or_insert(0);
+        let keep = count.is_multiple_of(self.every);
+        *count += 1;
+        keep
+    }
+
+    fn callsite_enabled(&self, meta: &'static Metadata<'static>) -> tracing::subscriber::Interest {
+        if self.every == 1 || !meta.is_event() || *meta.level() <= Level::INFO {
+            tracing::subscriber::Interest::always()
+        } else {
+            // Ask per event so the count advances.
+            tracing::subscriber::Interest::sometimes()
+        }
+    }
+}
+
+/// One JSON object per event, with the span context fields at the top level.
+pub struct JsonFormat;
+
+impl<S> FormatEvent<S, JsonFields> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, JsonFields>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let meta = event.metadata();
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".to_string(),
+            Value::String(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+        );
+        line.insert(
+            "level".to_string(),
+            Value::String(meta.level().as_str().to_string()),
+        );
+        line.insert(
+            "target".to_string(),
+            Value::String(meta.target().to_string()),
+        );
+
+        // Outer spans first, so an inner span's value wins.
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                let extensions = span.extensions();
+                let Some(fields) = extensions.get::<FormattedFields<JsonFields>>() else {
+                    continue;
+                };
+                let Ok(Value::Object(fields)) = serde_json::from_str::<Value>(fields) else {
+                    continue;
+                };
+                for name in CONTEXT_FIELDS {
+                    if let Some(value) = fields.get(name) {
+                        line.insert(name.to_string(), value.clone());
+                    }
+                }
+            }
+        }
+
+        event.record(&mut JsonVisitor(&mut line));
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0
+            .insert(field.name().to_string(), Value::String(value.to_string()));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(
+            field.name().to_string(),
+            Value::String(format!("{:?}", value)),
+        );
+    }
+}
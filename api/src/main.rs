@@ -1,26 +1,182 @@
-use axum::{
-    http::{
-        header::{AUTHORIZATION, CONTENT_TYPE},
-        HeaderValue, Method,
-    },
-    middleware,
-    routing::get,
-    Router,
-};
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing_subscriber::EnvFilter;
+use clap::{Parser, Subcommand};
+use uuid::Uuid;
 
 use async_anticheat_api::{
-    config::Config, db, module_pipeline, object_store_cleanup, routes, s3::ObjectStore, AppState,
+    app, async_dispatch,
+    config::Config,
+    db, dispatch_shedding, dispatch_stats, finding_latency, logging, maintenance, module_pipeline,
+    object_store_cleanup, player_state_decay,
+    plugin_version::Version,
+    privacy, replica_shards,
+    reports::{self, Mailer},
+    reprocess, reputation,
+    s3::ObjectStore,
+    server_deletion, server_ping, server_presence, shadow,
+    shared_state::SharedState,
+    supervisor::Supervisor,
+    task_lock, AppState,
 };
 
+#[derive(Parser)]
+#[command(about = "AsyncAnticheat ingest API and maintenance commands")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the HTTP API (default when no command is given).
+    Serve,
+    /// Apply schema migrations and exit.
+    Migrate,
+    /// Run one object store / batch_index cleanup pass using the configured TTLs.
+    Cleanup {
+        /// Report what would be deleted without deleting anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Re-dispatch a stored batch to its server's enabled modules.
+    Replay {
+        #[arg(long)]
+        batch: Uuid,
+    },
+    /// Issue a new plugin token for a server and print it once.
+    RotateToken {
+        #[arg(long)]
+        server: String,
+    },
+    /// Check that a stored batch decodes and matches its batch_index row.
+    VerifyObject {
+        #[arg(long)]
+        key: String,
+    },
+    /// Print a stored batch as a sanitized transform test fixture (tests/fixtures/transforms).
+    ExportFixture {
+        #[arg(long)]
+        key: String,
+    },
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
+    logging::init();
 
+    let cli = Cli::parse();
     let cfg = Config::from_env();
+
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(cfg).await,
+        Command::Migrate => {
+            let db = db::connect(&cfg.database_url).await?;
+            db::migrate(&db).await?;
+            println!("migrations applied");
+            Ok(())
+        }
+        Command::Cleanup { dry_run } => {
+            let mut state = bootstrap(&cfg).await?;
+            state.object_store_cleanup_enabled = true;
+            state.object_store_cleanup_dry_run = dry_run;
+            // Results are reported through the "cleanup tick completed" log line.
+            object_store_cleanup::cleanup_tick(state).await;
+            Ok(())
+        }
+        Command::Replay { batch } => {
+            let state = bootstrap(&cfg).await?;
+            maintenance::replay_batch(&state, batch).await?;
+            println!("batch {} dispatched", batch);
+            Ok(())
+        }
+        Command::RotateToken { server } => {
+            let state = bootstrap(&cfg).await?;
+            let token = maintenance::rotate_server_token(&state, &server).await?;
+            println!("{}", token);
+            eprintln!(
+                "server {}: token rotated; update the plugin config, the old token no longer works",
+                server.trim()
+            );
+            Ok(())
+        }
+        Command::VerifyObject { key } => {
+            let state = bootstrap(&cfg).await?;
+            let report = maintenance::verify_object(&state, &key).await?;
+            println!(
+                "{}: {} bytes, {} records, {} invalid lines",
+                report.key, report.bytes, report.contents.records, report.contents.invalid_lines
+            );
+            match report.indexed {
+                Some((payload_bytes, event_count)) => println!(
+                    "batch_index: payload_bytes={} event_count={}",
+                    payload_bytes,
+                    event_count.map_or("-".to_string(), |n| n.to_string())
+                ),
+                None => println!("batch_index: no row for this key"),
+            }
+            if !report.is_consistent() {
+                anyhow::bail!("object {} failed verification", key);
+            }
+            Ok(())
+        }
+        Command::ExportFixture { key } => {
+            let state = bootstrap(&cfg).await?;
+            print!("{}", maintenance::export_fixture(&state, &key).await?);
+            Ok(())
+        }
+    }
+}
+
+/// Connect to the database and object store and build the shared state. Used by `serve` and
+/// the maintenance commands; does not run migrations or start background work.
+async fn bootstrap(cfg: &Config) -> anyhow::Result<AppState> {
+    let db = db::connect(&cfg.database_url).await?;
+    let object_store = ObjectStore::from_config(cfg)?;
+    let shared = connect_shared_state(cfg).await?;
+    app::state(cfg, db, object_store, shared, connect_mailer(cfg)?)
+}
+
+/// SMTP mailer for weekly reports when `SMTP_URL` is set, otherwise disabled.
+fn connect_mailer(cfg: &Config) -> anyhow::Result<Mailer> {
+    let Some(url) = cfg.smtp_url.as_deref() else {
+        return Ok(Mailer::default());
+    };
+    #[cfg(feature = "email-reports")]
+    {
+        let mailer = Mailer::smtp(url, &cfg.report_email_from)?;
+        tracing::info!("weekly report emails enabled");
+        Ok(mailer)
+    }
+    #[cfg(not(feature = "email-reports"))]
+    {
+        let _ = url;
+        tracing::warn!(
+            "SMTP_URL is set but this build lacks the `email-reports` feature; weekly reports go to webhooks only."
+        );
+        Ok(Mailer::default())
+    }
+}
+
+/// Redis-backed shared state when `REDIS_URL` is set, otherwise in-process.
+async fn connect_shared_state(cfg: &Config) -> anyhow::Result<SharedState> {
+    let Some(url) = cfg.redis_url.as_deref() else {
+        return Ok(SharedState::local());
+    };
+    #[cfg(feature = "redis")]
+    {
+        let shared = SharedState::connect_redis(url).await?;
+        tracing::info!("shared caches and rate limits in Redis");
+        Ok(shared)
+    }
+    #[cfg(not(feature = "redis"))]
+    {
+        let _ = url;
+        tracing::warn!(
+            "REDIS_URL is set but this build lacks the `redis` feature; caches and rate limits stay per replica."
+        );
+        Ok(SharedState::local())
+    }
+}
+
+async fn serve(cfg: Config) -> anyhow::Result<()> {
     if cfg.database_url.is_empty() {
         tracing::warn!("DATABASE_URL is empty; the service will fail when ingesting.");
     }
@@ -36,139 +192,183 @@ async fn main() -> anyhow::Result<()> {
     if cfg.module_callback_token.is_empty() {
         tracing::warn!("MODULE_CALLBACK_TOKEN is empty; module callbacks will be rejected.");
     }
+    if cfg.admin_token.is_none() {
+        tracing::info!("ADMIN_TOKEN is empty; /admin routes are disabled.");
+    }
+    for (key, value) in [
+        (
+            "MIN_SUPPORTED_PLUGIN_VERSION",
+            &cfg.min_supported_plugin_version,
+        ),
+        (
+            "RECOMMENDED_PLUGIN_VERSION",
+            &cfg.recommended_plugin_version,
+        ),
+    ] {
+        if let Some(v) = value.as_deref().filter(|v| Version::parse(v).is_none()) {
+            tracing::warn!("{}={} is not a version (x.y.z); ignoring it.", key, v);
+        }
+    }
 
-    let db = db::connect(&cfg.database_url).await?;
+    let state = bootstrap(&cfg).await?;
     // Minimal migrations to keep deployments forward-compatible.
-    db::migrate(&db).await?;
-    let object_store = ObjectStore::from_config(&cfg).expect("Failed to initialize object store");
-    let http = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .expect("Failed to build HTTP client");
-
-    let state = AppState {
-        db,
-        object_store,
-        ingest_token: cfg.ingest_token.clone(),
-        module_callback_token: cfg.module_callback_token.clone(),
-        dashboard_token: cfg.dashboard_token.clone(),
-        http,
-        max_body_bytes: cfg.max_body_bytes,
-        object_store_cleanup_enabled: cfg.object_store_cleanup_enabled,
-        object_store_cleanup_dry_run: cfg.object_store_cleanup_dry_run,
-        object_store_cleanup_interval_seconds: cfg.object_store_cleanup_interval_seconds,
-        object_store_ttl_days: cfg.object_store_ttl_days,
-        object_store_ttl_seconds_override: cfg.object_store_ttl_seconds_override,
-        batch_index_ttl_days: cfg.batch_index_ttl_days,
-        batch_index_ttl_seconds_override: cfg.batch_index_ttl_seconds_override,
-    };
+    db::migrate(&state.db).await?;
+
+    // Optional: spawn and monitor module binaries (single-host deployments).
+    if let Some(ref path) = cfg.module_supervisor_config {
+        let modules = Supervisor::load_config(path)?;
+        tracing::info!(path = %path, modules = modules.len(), "module supervisor enabled");
+        state.supervisor.spawn_all(modules);
+    }
 
     // Background: module health checks ("check modules" system)
+    task_lock::spawn_exclusive(
+        state.clone(),
+        "module_healthcheck",
+        std::time::Duration::from_secs(cfg.module_healthcheck_interval_seconds.max(1)),
+        module_pipeline::healthcheck_tick,
+    );
+
+    // Background: object store cleanup (delete old batches and batch_index rows)
+    task_lock::spawn_exclusive(
+        state.clone(),
+        "object_store_cleanup",
+        std::time::Duration::from_secs(cfg.object_store_cleanup_interval_seconds.max(60)),
+        object_store_cleanup::cleanup_tick,
+    );
+
+    // Background: cross-server reputation aggregation
+    if cfg.reputation_enabled {
+        task_lock::spawn_exclusive(
+            state.clone(),
+            "reputation_aggregation",
+            std::time::Duration::from_secs(cfg.reputation_interval_seconds.max(60)),
+            reputation::aggregate_tick,
+        );
+    }
+
+    // Background: archive and drop module state of players unseen for PLAYER_STATE_DECAY_DAYS
+    if cfg.player_state_decay_enabled {
+        task_lock::spawn_exclusive(
+            state.clone(),
+            "player_state_decay",
+            std::time::Duration::from_secs(cfg.player_state_decay_interval_seconds.max(60)),
+            player_state_decay::decay_tick,
+        );
+    }
+
+    // Background: Server List Ping of active servers (read by /dashboard/:server_id/status)
+    if cfg.server_probe_enabled {
+        task_lock::spawn_exclusive(
+            state.clone(),
+            "server_ping",
+            std::time::Duration::from_secs(server_ping::PROBE_INTERVAL_SECONDS),
+            server_ping::probe_tick,
+        );
+    }
+
+    // Background: batch reprocessing jobs (queued via /admin/reprocess)
+    task_lock::spawn_exclusive(
+        state.clone(),
+        "reprocess_jobs",
+        std::time::Duration::from_secs(reprocess::WORKER_INTERVAL_SECONDS),
+        reprocess::worker_tick,
+    );
+
+    // Background: time out async module dispatches past their deadline
+    task_lock::spawn_exclusive(
+        state.clone(),
+        "async_dispatch_watchdog",
+        std::time::Duration::from_secs(async_dispatch::WATCHDOG_INTERVAL_SECONDS),
+        async_dispatch::watchdog_tick,
+    );
+
+    // Background: heartbeat this replica and refresh the replicas sharing per-server work
     {
-        let health_state = state.clone();
-        let interval_seconds = cfg.module_healthcheck_interval_seconds.max(1);
+        let heartbeat_state = state.clone();
         tokio::spawn(async move {
-            let mut ticker =
-                tokio::time::interval(std::time::Duration::from_secs(interval_seconds));
+            let mut ticker = tokio::time::interval(std::time::Duration::from_secs(
+                replica_shards::HEARTBEAT_INTERVAL_SECONDS,
+            ));
             loop {
                 ticker.tick().await;
-                module_pipeline::healthcheck_tick(health_state.clone()).await;
+                replica_shards::heartbeat_tick(heartbeat_state.clone()).await;
             }
         });
     }
 
-    // Background: object store cleanup (delete old batches and batch_index rows)
+    // Background: dispatch batches stored without dispatch during overload once load drops
+    // (every replica, for the servers it owns)
     {
-        let cleanup_state = state.clone();
-        let interval_seconds = cfg.object_store_cleanup_interval_seconds.max(60);
+        let recovery_state = state.clone();
         tokio::spawn(async move {
-            let mut ticker =
-                tokio::time::interval(std::time::Duration::from_secs(interval_seconds));
+            let mut ticker = tokio::time::interval(std::time::Duration::from_secs(
+                dispatch_shedding::RECOVERY_INTERVAL_SECONDS,
+            ));
             loop {
                 ticker.tick().await;
-                object_store_cleanup::cleanup_tick(cleanup_state.clone()).await;
+                dispatch_shedding::recovery_tick(recovery_state.clone()).await;
             }
         });
     }
 
-    // Dashboard routes (protected by DASHBOARD_TOKEN when set)
-    let dashboard_routes = Router::new()
-        .route("/dashboard/servers", get(routes::dashboard::get_servers))
-        .route(
-            "/dashboard/:server_id/stats",
-            get(routes::dashboard::get_stats),
-        )
-        .route(
-            "/dashboard/:server_id/findings",
-            get(routes::dashboard::get_findings),
-        )
-        .route(
-            "/dashboard/:server_id/players",
-            get(routes::dashboard::get_players),
-        )
-        .route(
-            "/dashboard/:server_id/modules",
-            get(routes::dashboard::get_modules).post(routes::dashboard::create_module),
-        )
-        .route(
-            "/dashboard/:server_id/modules/:module_id/toggle",
-            axum::routing::post(routes::dashboard::toggle_module),
-        )
-        .route(
-            "/dashboard/:server_id/status",
-            get(routes::dashboard::get_status),
-        )
-        .route(
-            "/dashboard/:server_id/modules/audit",
-            get(routes::dashboard::get_module_audit),
-        )
-        .layer(middleware::from_fn_with_state(
-            state.clone(),
-            routes::auth::require_dashboard,
-        ));
-
-    let app = Router::new()
-        .route("/health", get(routes::health::health))
-        .route(
-            "/handshake",
-            axum::routing::post(routes::handshake::handshake),
-        )
-        .route(
-            "/heartbeat",
-            axum::routing::post(routes::heartbeat::heartbeat),
-        )
-        .route("/ingest", axum::routing::post(routes::ingest::ingest))
-        .route(
-            "/servers/:server_id/modules",
-            axum::routing::post(routes::modules::upsert_module).get(routes::modules::list_modules),
-        )
-        .route(
-            "/callbacks/findings",
-            axum::routing::post(routes::callbacks::post_findings),
-        )
-        // Module state persistence endpoints
-        .route(
-            "/callbacks/player-state",
-            axum::routing::get(routes::callbacks::get_player_state)
-                .post(routes::callbacks::set_player_state),
-        )
-        .route(
-            "/callbacks/player-states/batch-get",
-            axum::routing::post(routes::callbacks::batch_get_player_states),
-        )
-        .route(
-            "/callbacks/player-states/batch-set",
-            axum::routing::post(routes::callbacks::batch_set_player_states),
-        )
-        // Observation submission endpoint (from plugin)
-        .route(
-            "/observations",
-            axum::routing::post(routes::observations::create_observation),
-        )
-        .merge(dashboard_routes)
-        .with_state(state)
-        .layer(cors_layer(&cfg))
-        .layer(TraceLayer::new_for_http());
+    // Background: roll up module dispatches hourly and purge old raw rows
+    task_lock::spawn_exclusive(
+        state.clone(),
+        "dispatch_stats_compaction",
+        std::time::Duration::from_secs(dispatch_stats::COMPACTION_INTERVAL_SECONDS),
+        dispatch_stats::compaction_tick,
+    );
+
+    // Background: roll up ingest → finding latency per detector and day
+    task_lock::spawn_exclusive(
+        state.clone(),
+        "finding_latency_rollup",
+        std::time::Duration::from_secs(finding_latency::ROLLUP_INTERVAL_SECONDS),
+        finding_latency::rollup_tick,
+    );
+
+    // Background: purge shadow findings older than 30 days
+    task_lock::spawn_exclusive(
+        state.clone(),
+        "shadow_findings_purge",
+        std::time::Duration::from_secs(shadow::PURGE_INTERVAL_SECONDS),
+        shadow::purge_tick,
+    );
+
+    // Background: flag servers whose plugin went offline (or came back) and notify webhooks
+    task_lock::spawn_exclusive(
+        state.clone(),
+        "server_presence",
+        std::time::Duration::from_secs(server_presence::CHECK_INTERVAL_SECONDS),
+        server_presence::presence_tick,
+    );
+
+    // Background: server purge jobs (queued via /admin/servers/:server_id/purge)
+    task_lock::spawn_exclusive(
+        state.clone(),
+        "server_purge_jobs",
+        std::time::Duration::from_secs(server_deletion::WORKER_INTERVAL_SECONDS),
+        server_deletion::worker_tick,
+    );
+
+    // Background: player erasure jobs (queued via /admin/privacy/delete-player)
+    task_lock::spawn_exclusive(
+        state.clone(),
+        "player_erasure_jobs",
+        std::time::Duration::from_secs(privacy::WORKER_INTERVAL_SECONDS),
+        privacy::worker_tick,
+    );
+
+    // Background: weekly digest reports to webhooks / email
+    task_lock::spawn_exclusive(
+        state.clone(),
+        "weekly_reports",
+        std::time::Duration::from_secs(reports::CHECK_INTERVAL_SECONDS),
+        reports::report_tick,
+    );
+
+    let app = app::router(state, &cfg);
 
     let addr = format!("{}:{}", cfg.host, cfg.port).parse()?;
     tracing::info!("async_anticheat_api listening on {}", addr);
@@ -178,39 +378,3 @@ async fn main() -> anyhow::Result<()> {
         .await?;
     Ok(())
 }
-
-/// Build a CORS layer.
-/// SECURITY: Permissive CORS is only allowed when CORS_PERMISSIVE_DEV=true is explicitly set.
-/// This prevents accidental permissive CORS in production.
-fn cors_layer(cfg: &Config) -> CorsLayer {
-    if cfg.cors_allow_origins.is_empty() {
-        if cfg.cors_permissive_dev {
-            tracing::warn!(
-                "CORS_PERMISSIVE_DEV=true: using permissive CORS. DO NOT USE IN PRODUCTION!"
-            );
-            return CorsLayer::permissive();
-        } else {
-            // In production with no origins configured, use restrictive defaults
-            // This allows same-origin requests only
-            tracing::info!(
-                "CORS_ALLOW_ORIGINS is empty and CORS_PERMISSIVE_DEV is not set. \
-                 Using restrictive CORS (same-origin only). Set CORS_ALLOW_ORIGINS \
-                 or CORS_PERMISSIVE_DEV=true for cross-origin requests."
-            );
-            return CorsLayer::new()
-                .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-                .allow_headers([CONTENT_TYPE, AUTHORIZATION]);
-        }
-    }
-
-    let origins: Vec<HeaderValue> = cfg
-        .cors_allow_origins
-        .iter()
-        .filter_map(|o| o.parse().ok())
-        .collect();
-
-    CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-        .allow_headers([CONTENT_TYPE, AUTHORIZATION])
-        .allow_origin(origins)
-}
@@ -0,0 +1,223 @@
+//! One-shot operator tasks behind the CLI subcommands (`async_anticheat_api <command>`).
+//!
+//! Each function takes the same `AppState` the server builds, so maintenance runs against the
+//! configured database and object store without going through HTTP.
+
+use std::io::{BufRead, BufReader};
+
+use anyhow::Context;
+use flate2::read::GzDecoder;
+use uuid::Uuid;
+
+use crate::{auth, module_pipeline, AppState};
+
+/// Replace a server's plugin token. Returns the new plaintext token; only its hash is stored,
+/// so it has to be copied into the plugin config now.
+pub async fn rotate_server_token(state: &AppState, server_id: &str) -> anyhow::Result<String> {
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    let updated = sqlx::query(
+        r#"
+        update public.servers
+        set auth_token_hash = $2,
+            auth_token_first_seen_at = now()
+        where id = $1
+        "#,
+    )
+    .bind(server_id.trim())
+    .bind(auth::sha256_hex(&token))
+    .execute(&state.db)
+    .await
+    .context("token update failed")?;
+    if updated.rows_affected() == 0 {
+        anyhow::bail!("server {} not found", server_id);
+    }
+    Ok(token)
+}
+
+/// Re-dispatch a stored batch to its server's enabled modules.
+pub async fn replay_batch(state: &AppState, batch_id: Uuid) -> anyhow::Result<()> {
+    let row: Option<(String, String, String)> = sqlx::query_as(
+        "select server_id, session_id, s3_key from public.batch_index where id = $1",
+    )
+    .bind(batch_id)
+    .fetch_optional(&state.db)
+    .await
+    .context("batch lookup failed")?;
+    let Some((server_id, session_id, s3_key)) = row else {
+        anyhow::bail!("batch {} not found", batch_id);
+    };
+
+    let raw = state
+        .object_store
+        .get_batch(&s3_key)
+        .await
+        .with_context(|| format!("batch object {} unavailable", s3_key))?;
+    module_pipeline::dispatch_batch(
+        state.clone(),
+        server_id,
+        session_id,
+        batch_id,
+        s3_key,
+        raw.into(),
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!("dispatch failed: {}", e))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchContents {
+    pub records: u64,
+    pub invalid_lines: u64,
+}
+
+/// Count the NDJSON records in a gzipped batch, flagging lines that aren't JSON objects.
+pub fn inspect_batch(raw_gz_ndjson: &[u8]) -> anyhow::Result<BatchContents> {
+    let mut contents = BatchContents {
+        records: 0,
+        invalid_lines: 0,
+    };
+    for line in BufReader::new(GzDecoder::new(raw_gz_ndjson)).lines() {
+        let line = line.context("gzip stream is corrupt")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<serde_json::Value>(&line) {
+            Ok(v) if v.is_object() => contents.records += 1,
+            _ => contents.invalid_lines += 1,
+        }
+    }
+    Ok(contents)
+}
+
+#[derive(Debug)]
+pub struct ObjectReport {
+    pub key: String,
+    pub bytes: usize,
+    pub contents: BatchContents,
+    /// `(payload_bytes, event_count)` from `batch_index`, if the key is indexed.
+    pub indexed: Option<(i32, Option<i32>)>,
+}
+
+impl ObjectReport {
+    /// Whether the object decodes cleanly and agrees with its `batch_index` row.
+    pub fn is_consistent(&self) -> bool {
+        self.contents.invalid_lines == 0
+            && self.indexed.is_none_or(|(payload_bytes, event_count)| {
+                payload_bytes as usize == self.bytes
+                    && event_count.is_none_or(|n| n as u64 == self.contents.records)
+            })
+    }
+}
+
+/// Fetch an object and check it against its `batch_index` row.
+pub async fn verify_object(state: &AppState, key: &str) -> anyhow::Result<ObjectReport> {
+    let raw = state
+        .object_store
+        .get_batch(key)
+        .await
+        .with_context(|| format!("object {} unavailable", key))?;
+    let indexed: Option<(i32, Option<i32>)> = sqlx::query_as(
+        "select payload_bytes, event_count from public.batch_index where s3_key = $1 limit 1",
+    )
+    .bind(key)
+    .fetch_optional(&state.db)
+    .await
+    .context("batch_index lookup failed")?;
+
+    Ok(ObjectReport {
+        key: key.to_string(),
+        bytes: raw.len(),
+        contents: inspect_batch(&raw)?,
+        indexed,
+    })
+}
+
+/// Record fields that identify the player or their connection; left out of fixtures.
+const FIXTURE_DROPPED_FIELDS: [&str; 2] = ["ip_hash", "fingerprint"];
+
+/// A gzipped batch as a transform test fixture (`tests/fixtures/transforms`): plain NDJSON with
+/// player and entity UUIDs replaced by sequential ones, names by `player<N>`, the server and
+/// session ids by `fixture`, IP hashes and fingerprints removed and `ts` rebased so the first
+/// record is at 1000. Lines that aren't JSON are dropped.
+pub fn sanitize_fixture(raw_gz_ndjson: &[u8]) -> anyhow::Result<String> {
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    // Sanitized UUIDs are numbered in order of appearance.
+    let mut uuids: HashMap<String, u128> = HashMap::new();
+    let mut number = |raw: &str| {
+        let next = uuids.len() as u128 + 1;
+        *uuids.entry(raw.to_string()).or_insert(next)
+    };
+
+    let mut first_ts: Option<i64> = None;
+    let mut out = String::new();
+
+    for (i, line) in BufReader::new(GzDecoder::new(raw_gz_ndjson))
+        .lines()
+        .enumerate()
+    {
+        let line = line.context("gzip stream is corrupt")?;
+        let Ok(Value::Object(mut v)) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if i == 0 {
+            for key in ["server_id", "session_id"] {
+                if v.contains_key(key) {
+                    v.insert(key.to_string(), Value::String("fixture".to_string()));
+                }
+            }
+            for key in ["client_types", "client_protocols"] {
+                if let Some(Value::Object(map)) = v.get_mut(key) {
+                    *map = std::mem::take(map)
+                        .into_iter()
+                        .map(|(uuid, value)| (Uuid::from_u128(number(&uuid)).to_string(), value))
+                        .collect();
+                }
+            }
+        } else {
+            if let Some(uuid) = v.get("uuid").and_then(|x| x.as_str()).map(str::to_string) {
+                let n = number(&uuid);
+                if v.contains_key("name") {
+                    v.insert("name".to_string(), Value::String(format!("player{}", n)));
+                }
+                v.insert(
+                    "uuid".to_string(),
+                    Value::String(Uuid::from_u128(n).to_string()),
+                );
+            }
+            for key in FIXTURE_DROPPED_FIELDS {
+                v.remove(key);
+            }
+            if let Some(Value::Object(fields)) = v.get_mut("fields") {
+                if let Some(uuid) = fields
+                    .get("entity_uuid")
+                    .and_then(|x| x.as_str())
+                    .map(str::to_string)
+                {
+                    fields.insert(
+                        "entity_uuid".to_string(),
+                        Value::String(Uuid::from_u128(number(&uuid)).to_string()),
+                    );
+                }
+            }
+            if let Some(ts) = v.get("ts").and_then(|x| x.as_i64()) {
+                let first = *first_ts.get_or_insert(ts);
+                v.insert("ts".to_string(), Value::from(ts - first + 1000));
+            }
+        }
+        out.push_str(&serde_json::to_string(&v)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Fetch a stored batch and sanitize it into a transform fixture (see [`sanitize_fixture`]).
+pub async fn export_fixture(state: &AppState, key: &str) -> anyhow::Result<String> {
+    let raw = state
+        .object_store
+        .get_batch(key)
+        .await
+        .with_context(|| format!("object {} unavailable", key))?;
+    sanitize_fixture(&raw)
+}
@@ -0,0 +1,177 @@
+//! Canary deployments for HTTP modules.
+//!
+//! A module can register a `canary_base_url` with a `canary_percent`: that share of batches
+//! goes to the canary instead of the module's regular (stable) endpoints. The split is decided
+//! per batch from its id, so a batch always lands on the same side. A canary that fails falls
+//! back to the stable endpoints for that batch.
+//!
+//! The variant (`stable` / `canary`) is recorded in `module_batch_variants` before the batch is
+//! sent to it (and again if a failed canary hands the batch to the stable endpoints), so findings
+//! a module posts before it even replies are attributed correctly. Findings are stored with their
+//! variant (`findings.module_variant`), each accepted dispatch row is tagged with it, and
+//! `module_variant_stats` counts batches and reported findings per module, day and variant, so
+//! module authors can compare finding rates before a full rollout
+//! (`GET /dashboard/:server_id/modules/:module_id/canary`). Canary findings are live like the
+//! stable ones; the healthcheck does not probe the canary, and a failing canary counts against
+//! neither the module's failure counter nor its health.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const STABLE: &str = "stable";
+pub const CANARY: &str = "canary";
+
+/// Whether `batch_id` falls in the canary's share (0-100 percent).
+pub fn routes_to_canary(batch_id: Uuid, percent: i32) -> bool {
+    (batch_id.as_u128() % 100) < percent.clamp(0, 100) as u128
+}
+
+/// A trimmed canary URL; `Ok(None)` for a blank one (removes the canary).
+pub fn normalize_url(url: &str) -> Result<Option<String>, String> {
+    let url = url.trim().trim_end_matches('/');
+    if url.is_empty() {
+        return Ok(None);
+    }
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(format!("canary_base_url {} must be an http(s) URL", url));
+    }
+    Ok(Some(url.to_string()))
+}
+
+/// Record that `variant` is about to process the batch for the module.
+pub async fn assign(db: &PgPool, module_id: Uuid, batch_id: Uuid, variant: &str) {
+    let res = sqlx::query(
+        r#"
+        insert into public.module_batch_variants (module_id, batch_id, variant)
+        values ($1, $2, $3)
+        on conflict (module_id, batch_id) do update set
+            variant = excluded.variant,
+            assigned_at = now()
+        "#,
+    )
+    .bind(module_id)
+    .bind(batch_id)
+    .bind(variant)
+    .execute(db)
+    .await;
+    if let Err(e) = res {
+        tracing::warn!(module_id = %module_id, batch_id = %batch_id, "canary assignment failed: {:?}", e);
+    }
+}
+
+/// Tag the module's latest dispatch of the batch and count the batch for its variant.
+pub async fn record_dispatch(db: &PgPool, module_id: Uuid, batch_id: Uuid, variant: &str) {
+    let res = async {
+        sqlx::query(
+            r#"
+            update public.module_dispatches set variant = $3
+            where id = (
+                select id from public.module_dispatches
+                where batch_id = $1 and module_id = $2
+                order by created_at desc
+                limit 1
+            )
+            "#,
+        )
+        .bind(batch_id)
+        .bind(module_id)
+        .bind(variant)
+        .execute(db)
+        .await?;
+        bump(db, module_id, variant, 1, 0).await
+    }
+    .await;
+    if let Err(e) = res {
+        tracing::warn!(module_id = %module_id, batch_id = %batch_id, "canary tagging failed: {:?}", e);
+    }
+}
+
+/// Count findings a module reported for a batch under the variant that processed it; returns
+/// that variant. Batches dispatched without a canary configured have none and are not counted.
+pub async fn record_findings(
+    db: &PgPool,
+    module_id: Uuid,
+    batch_id: Uuid,
+    findings: usize,
+) -> Option<String> {
+    let res = async {
+        let variant: Option<String> = sqlx::query_scalar(
+            r#"
+            select variant from public.module_batch_variants
+            where module_id = $1 and batch_id = $2
+            "#,
+        )
+        .bind(module_id)
+        .bind(batch_id)
+        .fetch_optional(db)
+        .await?;
+        if let Some(variant) = &variant {
+            bump(db, module_id, variant, 0, findings as i64).await?;
+        }
+        Ok::<_, sqlx::Error>(variant)
+    }
+    .await;
+    res.unwrap_or_else(|e| {
+        tracing::warn!(module_id = %module_id, batch_id = %batch_id, "canary finding count failed: {:?}", e);
+        None
+    })
+}
+
+async fn bump(
+    db: &PgPool,
+    module_id: Uuid,
+    variant: &str,
+    batches: i64,
+    findings: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        insert into public.module_variant_stats (module_id, day, variant, batches, findings)
+        values ($1, current_date, $2, $3, $4)
+        on conflict (module_id, day, variant) do update set
+            batches = public.module_variant_stats.batches + excluded.batches,
+            findings = public.module_variant_stats.findings + excluded.findings
+        "#,
+    )
+    .bind(module_id)
+    .bind(variant)
+    .bind(batches)
+    .bind(findings)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Batches and findings of one variant on one day.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VariantDay {
+    pub day: NaiveDate,
+    /// stable | canary
+    pub variant: String,
+    pub batches: i64,
+    pub findings: i64,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VariantTotals {
+    pub batches: i64,
+    pub findings: i64,
+    /// Findings per 1000 batches; None without batches.
+    pub findings_per_1k_batches: Option<f64>,
+}
+
+/// Totals of `variant` over `days`.
+pub fn totals(days: &[VariantDay], variant: &str) -> VariantTotals {
+    let (batches, findings) = days
+        .iter()
+        .filter(|d| d.variant == variant)
+        .fold((0, 0), |(b, f), d| (b + d.batches, f + d.findings));
+    VariantTotals {
+        batches,
+        findings,
+        findings_per_1k_batches: (batches > 0).then(|| findings as f64 * 1000.0 / batches as f64),
+    }
+}
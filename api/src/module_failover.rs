@@ -0,0 +1,191 @@
+//! Primary/backup endpoints for HTTP modules.
+//!
+//! A module may register `backup_base_urls` next to its `base_url` (the primary). The live
+//! dispatch tries the endpoints in order and moves on to the next one when a request fails or the
+//! endpoint answers 5xx/408/429, so restarting one module host doesn't leave a detection gap.
+//! Endpoints are health-weighted: ones that failed `DOWN_AFTER_FAILURES` times in a row are
+//! tried last, after the healthy ones in their configured order.
+//!
+//! The healthcheck probes every endpoint and keeps its state in `module_endpoints`; the module
+//! itself counts as healthy while any endpoint is. Reprocessing, replays and conformance runs only
+//! use the primary.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Backup endpoints a module may register.
+pub const MAX_BACKUP_URLS: usize = 4;
+
+/// Consecutive failures after which an endpoint is tried last.
+pub const DOWN_AFTER_FAILURES: i32 = 3;
+
+/// Health of one module endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EndpointHealth {
+    pub base_url: String,
+    /// Null until the endpoint was checked or dispatched to.
+    pub healthy: Option<bool>,
+    pub consecutive_failures: i32,
+    pub last_error: Option<String>,
+    pub last_checked_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl EndpointHealth {
+    pub fn is_down(&self) -> bool {
+        self.healthy == Some(false) && self.consecutive_failures >= DOWN_AFTER_FAILURES
+    }
+}
+
+fn is_http(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// Trimmed, de-duplicated backups (the primary and blanks left out). Only http(s) URLs can have
+/// backups.
+pub fn normalize_backups(primary: &str, backups: &[String]) -> Result<Vec<String>, String> {
+    let primary = primary.trim().trim_end_matches('/');
+    let mut out: Vec<String> = Vec::new();
+    for url in backups {
+        let url = url.trim().trim_end_matches('/');
+        if url.is_empty() || url == primary || out.iter().any(|u| u == url) {
+            continue;
+        }
+        if !is_http(url) {
+            return Err(format!("backup base_url {} must be an http(s) URL", url));
+        }
+        out.push(url.to_string());
+    }
+    if !out.is_empty() && !is_http(primary) {
+        return Err("backup_base_urls need an http(s) base_url".to_string());
+    }
+    if out.len() > MAX_BACKUP_URLS {
+        return Err(format!(
+            "at most {} backup_base_urls are allowed",
+            MAX_BACKUP_URLS
+        ));
+    }
+    Ok(out)
+}
+
+/// The order to dispatch in: the primary and backups as configured, with down endpoints last.
+pub fn dispatch_order(primary: &str, backups: &[String], health: &[EndpointHealth]) -> Vec<String> {
+    let mut urls: Vec<String> = std::iter::once(primary.to_string())
+        .chain(backups.iter().cloned())
+        .collect();
+    // Stable sort keeps the configured order within each group.
+    urls.sort_by_key(|u| health.iter().any(|h| h.base_url == *u && h.is_down()));
+    urls
+}
+
+/// Whether a failed dispatch may succeed on another endpoint (transport errors and host-side
+/// statuses; other 4xx would fail the same way everywhere).
+pub fn should_fail_over(status: Option<reqwest::StatusCode>) -> bool {
+    match status {
+        None => true,
+        Some(s) => {
+            s.is_server_error()
+                || s == reqwest::StatusCode::REQUEST_TIMEOUT
+                || s == reqwest::StatusCode::TOO_MANY_REQUESTS
+        }
+    }
+}
+
+type EndpointRow = (
+    Uuid,
+    String,
+    Option<bool>,
+    i32,
+    Option<String>,
+    Option<chrono::DateTime<chrono::Utc>>,
+);
+
+fn endpoint_from_row(
+    (module_id, base_url, healthy, consecutive_failures, last_error, last_checked_at): EndpointRow,
+) -> (Uuid, EndpointHealth) {
+    (
+        module_id,
+        EndpointHealth {
+            base_url,
+            healthy,
+            consecutive_failures,
+            last_error,
+            last_checked_at,
+        },
+    )
+}
+
+/// Endpoint health of modules, in configured order (primary first) per module.
+pub async fn load(
+    db: &PgPool,
+    module_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<EndpointHealth>>, sqlx::Error> {
+    let rows: Vec<EndpointRow> = sqlx::query_as(
+        r#"
+        select m.id, u.base_url, e.healthy, coalesce(e.consecutive_failures, 0), e.last_error,
+               e.last_checked_at
+        from public.server_modules m
+        cross join lateral unnest(array[m.base_url] || m.backup_base_urls)
+            with ordinality as u(base_url, position)
+        left join public.module_endpoints e
+            on e.module_id = m.id and e.base_url = u.base_url
+        where m.id = any($1)
+        order by m.id, u.position
+        "#,
+    )
+    .bind(module_ids)
+    .fetch_all(db)
+    .await?;
+    let mut out: HashMap<Uuid, Vec<EndpointHealth>> = HashMap::new();
+    for (module_id, endpoint) in rows.into_iter().map(endpoint_from_row) {
+        out.entry(module_id).or_default().push(endpoint);
+    }
+    Ok(out)
+}
+
+/// Record a dispatch or healthcheck outcome for one endpoint.
+pub async fn record(db: &PgPool, module_id: Uuid, base_url: &str, err: Option<&str>) {
+    let res = sqlx::query(
+        r#"
+        insert into public.module_endpoints
+            (module_id, base_url, healthy, consecutive_failures, last_error, last_checked_at)
+        values ($1, $2, $3, case when $3 then 0 else 1 end, $4, now())
+        on conflict (module_id, base_url) do update set
+            healthy = excluded.healthy,
+            consecutive_failures = case
+                when excluded.healthy then 0
+                else public.module_endpoints.consecutive_failures + 1
+            end,
+            last_error = excluded.last_error,
+            last_checked_at = now()
+        "#,
+    )
+    .bind(module_id)
+    .bind(base_url)
+    .bind(err.is_none())
+    .bind(err)
+    .execute(db)
+    .await;
+    if let Err(e) = res {
+        tracing::warn!(module_id = %module_id, "endpoint health update failed: {:?}", e);
+    }
+}
+
+/// Forget the health of endpoints a module no longer lists.
+pub async fn prune(db: &PgPool, module_id: Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        delete from public.module_endpoints e
+        using public.server_modules m
+        where e.module_id = m.id and m.id = $1
+          and e.base_url <> all(array[m.base_url] || m.backup_base_urls)
+        "#,
+    )
+    .bind(module_id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,196 @@
+//! Pausing modules and scheduled maintenance windows.
+//!
+//! A paused module (`server_modules.paused_until`) or one inside one of its recurring
+//! maintenance windows is suspended: batches are not dispatched to it, the healthcheck skips it
+//! and failures reported for it (late async completions, timed-out dispatches) are not counted.
+//! It is not marked unhealthy, so once the restart is over it picks up batches again without
+//! waiting for the failure count to recover. Batches ingested meanwhile are not replayed.
+//!
+//! Windows are in UTC: `weekday` (0 = Monday .. 6 = Sunday, none = every day), a start minute
+//! of the day and a duration; a window may run past midnight.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Windows per module.
+pub const MAX_WINDOWS: usize = 16;
+/// Longest window, in minutes.
+pub const MAX_WINDOW_MINUTES: i32 = 12 * 60;
+/// Longest pause, in seconds.
+pub const MAX_PAUSE_SECONDS: i64 = 7 * 24 * 3600;
+
+pub const REASON_PAUSED: &str = "paused";
+pub const REASON_WINDOW: &str = "maintenance_window";
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+const MINUTES_PER_WEEK: i64 = 7 * MINUTES_PER_DAY;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceWindow {
+    /// 0 = Monday .. 6 = Sunday (UTC); omitted for a daily window.
+    #[serde(default)]
+    pub weekday: Option<i16>,
+    /// Minute of the day the window starts (UTC, 0-1439).
+    pub start_minute: i32,
+    /// 1-720.
+    pub duration_minutes: i32,
+}
+
+/// Why and until when a module is suspended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct Suspension {
+    /// paused | maintenance_window
+    pub reason: String,
+    pub until: DateTime<Utc>,
+}
+
+/// Reject windows outside the limits above.
+pub fn validate(windows: &[MaintenanceWindow]) -> Result<(), String> {
+    if windows.len() > MAX_WINDOWS {
+        return Err(format!("at most {} maintenance windows", MAX_WINDOWS));
+    }
+    for w in windows {
+        if let Some(day) = w.weekday {
+            if !(0..=6).contains(&day) {
+                return Err(format!("weekday {} must be 0 (Monday) to 6 (Sunday)", day));
+            }
+        }
+        if !(0..MINUTES_PER_DAY as i32).contains(&w.start_minute) {
+            return Err(format!("start_minute {} must be 0-1439", w.start_minute));
+        }
+        if !(1..=MAX_WINDOW_MINUTES).contains(&w.duration_minutes) {
+            return Err(format!(
+                "duration_minutes {} must be 1-{}",
+                w.duration_minutes, MAX_WINDOW_MINUTES
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Minutes `now` is into `w`'s current occurrence, if it is inside one.
+fn minutes_into(w: &MaintenanceWindow, now: DateTime<Utc>) -> Option<i64> {
+    let minute_of_day = i64::from(now.hour()) * 60 + i64::from(now.minute());
+    let since_start = match w.weekday {
+        Some(day) => {
+            let minute_of_week =
+                i64::from(now.weekday().num_days_from_monday()) * MINUTES_PER_DAY + minute_of_day;
+            let start = i64::from(day) * MINUTES_PER_DAY + i64::from(w.start_minute);
+            (minute_of_week - start).rem_euclid(MINUTES_PER_WEEK)
+        }
+        None => (minute_of_day - i64::from(w.start_minute)).rem_euclid(MINUTES_PER_DAY),
+    };
+    (since_start < i64::from(w.duration_minutes)).then_some(since_start)
+}
+
+/// Whether `now` falls inside an occurrence of `w`.
+pub fn window_active(w: &MaintenanceWindow, now: DateTime<Utc>) -> bool {
+    minutes_into(w, now).is_some()
+}
+
+/// The suspension in effect at `now`, if any; with several, the one lasting longest.
+pub fn suspension(
+    paused_until: Option<DateTime<Utc>>,
+    windows: &[MaintenanceWindow],
+    now: DateTime<Utc>,
+) -> Option<Suspension> {
+    let minute_start = now.with_second(0)?.with_nanosecond(0)?;
+    let window_end = windows
+        .iter()
+        .filter_map(|w| {
+            let into = minutes_into(w, now)?;
+            Some(minute_start + Duration::minutes(i64::from(w.duration_minutes) - into))
+        })
+        .max();
+    let pause_end = paused_until.filter(|until| *until > now);
+
+    match (pause_end, window_end) {
+        (Some(p), Some(w)) if w > p => Some((REASON_WINDOW, w)),
+        (Some(p), _) => Some((REASON_PAUSED, p)),
+        (None, Some(w)) => Some((REASON_WINDOW, w)),
+        (None, None) => None,
+    }
+    .map(|(reason, until)| Suspension {
+        reason: reason.to_string(),
+        until,
+    })
+}
+
+/// Maintenance windows of the given modules, in start order.
+pub async fn load_windows(
+    db: &PgPool,
+    module_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<MaintenanceWindow>>, sqlx::Error> {
+    let rows: Vec<(Uuid, Option<i16>, i32, i32)> = sqlx::query_as(
+        r#"
+        select module_id, weekday, start_minute, duration_minutes
+        from public.module_maintenance_windows
+        where module_id = any($1)
+        order by module_id, weekday nulls first, start_minute
+        "#,
+    )
+    .bind(module_ids)
+    .fetch_all(db)
+    .await?;
+
+    let mut windows: HashMap<Uuid, Vec<MaintenanceWindow>> = HashMap::new();
+    for (module_id, weekday, start_minute, duration_minutes) in rows {
+        windows
+            .entry(module_id)
+            .or_default()
+            .push(MaintenanceWindow {
+                weekday,
+                start_minute,
+                duration_minutes,
+            });
+    }
+    Ok(windows)
+}
+
+/// Replace a module's windows.
+pub async fn replace_windows(
+    db: &PgPool,
+    module_id: Uuid,
+    windows: &[MaintenanceWindow],
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+    sqlx::query("delete from public.module_maintenance_windows where module_id = $1")
+        .bind(module_id)
+        .execute(&mut *tx)
+        .await?;
+    for w in windows {
+        sqlx::query(
+            r#"
+            insert into public.module_maintenance_windows
+                (module_id, weekday, start_minute, duration_minutes)
+            values ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(module_id)
+        .bind(w.weekday)
+        .bind(w.start_minute)
+        .bind(w.duration_minutes)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await
+}
+
+/// The module's suspension right now, if any.
+pub async fn current(db: &PgPool, module_id: Uuid) -> Result<Option<Suspension>, sqlx::Error> {
+    let paused_until: Option<Option<DateTime<Utc>>> =
+        sqlx::query_scalar("select paused_until from public.server_modules where id = $1")
+            .bind(module_id)
+            .fetch_optional(db)
+            .await?;
+    let windows = load_windows(db, &[module_id])
+        .await?
+        .remove(&module_id)
+        .unwrap_or_default();
+    Ok(suspension(paused_until.flatten(), &windows, Utc::now()))
+}
@@ -0,0 +1,165 @@
+//! Telling server owners a module went down.
+//!
+//! Dispatch skips a module once [`DOWN_AFTER_FAILURES`] healthchecks or dispatches in a row
+//! failed, so its checks silently stop running. When a module crosses that threshold the server
+//! gets a `system.module_down` finding (no player, severity high, visible with the other
+//! findings on the dashboard) and a webhook notification, whatever its severity levels.
+//!
+//! A flapping module would repeat this on every outage: while its finding is still open and was
+//! seen in the last [`REPEAT_AFTER_HOURS`], further outages only bump the finding's occurrences.
+//! Resolving or dismissing the finding re-arms the notification.
+
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::webhooks::{self, ModuleDownNotification};
+use crate::AppState;
+
+/// Failures in a row after which a module counts as down.
+pub const DOWN_AFTER_FAILURES: i32 = 3;
+/// Detector name of the findings recorded for module outages.
+pub const DETECTOR_NAME: &str = "system.module_down";
+/// An open outage finding older than this gets a new finding and notification.
+pub const REPEAT_AFTER_HOURS: i32 = 24;
+
+/// Title of the outage finding.
+pub fn title(module_name: &str) -> String {
+    format!("Module {} is offline", module_name)
+}
+
+/// Description of the outage finding.
+pub fn description(n: &ModuleDownNotification) -> String {
+    format!(
+        "{} failed {} times in a row and gets no batches until it recovers ({} of {} enabled modules offline). Last error: {}",
+        n.module_name, n.consecutive_failures, n.modules_down, n.modules_enabled, n.last_error
+    )
+}
+
+/// Record the outage of a module that just reached [`DOWN_AFTER_FAILURES`] and notify the
+/// server's webhook (best-effort: errors are logged).
+pub async fn module_went_down(
+    state: &AppState,
+    module_id: Uuid,
+    server_id: &str,
+    module_name: &str,
+    consecutive_failures: i32,
+    last_error: &str,
+) {
+    let res = async {
+        let (modules_down, modules_enabled): (i64, i64) = sqlx::query_as(
+            r#"
+            select
+                count(*) filter (
+                    where last_healthcheck_ok = false and consecutive_failures >= $2
+                ),
+                count(*)
+            from public.server_modules
+            where server_id = $1 and enabled = true
+            "#,
+        )
+        .bind(server_id)
+        .bind(DOWN_AFTER_FAILURES)
+        .fetch_one(&state.db)
+        .await?;
+        let notification = ModuleDownNotification {
+            server_id: server_id.to_string(),
+            module_id,
+            module_name: module_name.to_string(),
+            consecutive_failures,
+            last_error: last_error.to_string(),
+            modules_down,
+            modules_enabled,
+        };
+
+        let repeated: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            update public.findings
+            set occurrences = occurrences + 1, last_seen_at = now(), description = $4
+            where server_id = $1
+              and detector_name = $2
+              and status = 'open'
+              and evidence_json->>'module_id' = $3
+              and last_seen_at > now() - make_interval(hours => $5)
+            returning id
+            "#,
+        )
+        .bind(server_id)
+        .bind(DETECTOR_NAME)
+        .bind(module_id.to_string())
+        .bind(description(&notification))
+        .bind(REPEAT_AFTER_HOURS)
+        .fetch_optional(&state.db)
+        .await?;
+        if repeated.is_some() {
+            return Ok::<_, sqlx::Error>(None);
+        }
+
+        sqlx::query(
+            r#"
+            insert into public.findings
+                (server_id, detector_name, severity, title, description, evidence_json)
+            values ($1, $2, 'high', $3, $4, $5)
+            "#,
+        )
+        .bind(server_id)
+        .bind(DETECTOR_NAME)
+        .bind(title(module_name))
+        .bind(description(&notification))
+        .bind(json!({
+            "module_id": module_id,
+            "module_name": module_name,
+            "consecutive_failures": consecutive_failures,
+            "last_error": last_error,
+            "modules_down": modules_down,
+            "modules_enabled": modules_enabled,
+        }))
+        .execute(&state.db)
+        .await?;
+        Ok(Some(notification))
+    }
+    .await;
+
+    let notification = match res {
+        Ok(Some(notification)) => notification,
+        Ok(None) => {
+            tracing::info!(module_id = %module_id, module = %module_name, "module down again, outage finding updated");
+            return;
+        }
+        Err(e) => {
+            tracing::warn!(module_id = %module_id, "module outage recording failed: {:?}", e);
+            return;
+        }
+    };
+    tracing::warn!(
+        server_id = %server_id,
+        module = %module_name,
+        modules_down = notification.modules_down,
+        modules_enabled = notification.modules_enabled,
+        "module down, owner notified"
+    );
+
+    let Some(settings) = webhooks::get_webhook_settings(&state.db, server_id).await else {
+        return;
+    };
+    let Some(url) = settings.webhook_url.filter(|_| settings.webhook_enabled) else {
+        return;
+    };
+    let server_name: Option<String> =
+        sqlx::query_scalar::<_, Option<String>>("select name from public.servers where id = $1")
+            .bind(server_id)
+            .fetch_optional(&state.db)
+            .await
+            .ok()
+            .flatten()
+            .flatten();
+    let client = state.http.clone();
+    tokio::spawn(async move {
+        webhooks::send_module_down_notification(
+            &client,
+            &url,
+            &notification,
+            server_name.as_deref(),
+        )
+        .await;
+    });
+}
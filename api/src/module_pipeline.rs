@@ -1,17 +1,109 @@
-use crate::{error::ApiError, transforms, AppState};
+use crate::async_dispatch;
+use crate::chaos::FaultPoint;
+use crate::dispatch_encoding::{self, ContentEncoding};
+use crate::dispatch_parts::{self, DispatchPart};
+use crate::routes::callbacks::{self, FindingIn, PostFindingsRequest};
+use crate::transform_pool::PoolError;
+use crate::{
+    checks, clock_skew, error::ApiError, exemptions, module_canary, module_failover,
+    module_maintenance, module_outage, module_usage, transforms, wasm_runtime, AppState,
+};
+use bytes::Bytes;
 use sqlx::FromRow;
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Remove exempted players' records before any module sees the batch (best-effort: on error the
+/// batch is dispatched unfiltered; `post_findings` still drops their findings).
+async fn strip_exempt_players(
+    state: &AppState,
+    server_id: &str,
+    batch_id: Uuid,
+    raw_gz_ndjson: Bytes,
+) -> Bytes {
+    let res = async {
+        let active = exemptions::load_active(&state.db, server_id).await?;
+        if active.is_empty() {
+            return Ok::<_, anyhow::Error>(None);
+        }
+        let known = if active.needs_facts() {
+            let players = exemptions::batch_players(&raw_gz_ndjson)?;
+            exemptions::load_facts(&state.db, server_id, &players).await?
+        } else {
+            Default::default()
+        };
+        let (filtered, removed) = exemptions::strip_exempt(&raw_gz_ndjson, &active, &known)?;
+        Ok((removed > 0).then_some((filtered, removed)))
+    }
+    .await;
+
+    match res {
+        Ok(Some((filtered, removed))) => {
+            tracing::debug!(batch_id = %batch_id, removed = removed, "stripped exempted players");
+            filtered.into()
+        }
+        Ok(None) => raw_gz_ndjson,
+        Err(e) => {
+            tracing::warn!(batch_id = %batch_id, "exemption filtering failed: {:?}", e);
+            raw_gz_ndjson
+        }
+    }
+}
+
 #[derive(Debug, FromRow)]
 struct ServerModuleRow {
     id: Uuid,
     server_id: String,
     name: String,
     base_url: String,
-    enabled: bool,
+    backup_base_urls: Vec<String>,
+    canary_base_url: Option<String>,
+    canary_percent: i32,
     transform: String,
     last_healthcheck_ok: Option<bool>,
     consecutive_failures: i32,
+    content_encoding: String,
+    processing_deadline_seconds: i32,
+    paused_until: Option<chrono::DateTime<chrono::Utc>>,
+    has_maintenance_windows: bool,
+}
+
+/// Drop paused modules and modules inside a maintenance window. If the windows cannot be
+/// loaded they are ignored (pauses still apply).
+async fn without_suspended(
+    state: &AppState,
+    modules: Vec<ServerModuleRow>,
+) -> Vec<ServerModuleRow> {
+    let with_windows: Vec<Uuid> = modules
+        .iter()
+        .filter(|m| m.has_maintenance_windows)
+        .map(|m| m.id)
+        .collect();
+    let mut windows = if with_windows.is_empty() {
+        Default::default()
+    } else {
+        module_maintenance::load_windows(&state.db, &with_windows)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("maintenance window lookup failed: {:?}", e);
+                Default::default()
+            })
+    };
+
+    let now = chrono::Utc::now();
+    modules
+        .into_iter()
+        .filter(|m| {
+            let w = windows.remove(&m.id).unwrap_or_default();
+            match module_maintenance::suspension(m.paused_until, &w, now) {
+                Some(s) => {
+                    tracing::debug!(module = %m.name, reason = %s.reason, until = %s.until, "module suspended");
+                    false
+                }
+                None => true,
+            }
+        })
+        .collect()
 }
 
 pub async fn dispatch_batch(
@@ -20,7 +112,7 @@ pub async fn dispatch_batch(
     session_id: String,
     batch_id: Uuid,
     s3_key: String,
-    raw_gz_ndjson: Vec<u8>,
+    raw_gz_ndjson: Bytes,
 ) -> Result<(), ApiError> {
     let server_id = server_id.trim().to_string();
     let session_id = session_id.trim().to_string();
@@ -32,10 +124,19 @@ pub async fn dispatch_batch(
             server_id,
             name,
             base_url,
-            enabled,
+            backup_base_urls,
+            canary_base_url,
+            canary_percent,
             transform,
             last_healthcheck_ok,
-            consecutive_failures
+            consecutive_failures,
+            content_encoding,
+            processing_deadline_seconds,
+            paused_until,
+            exists (
+                select 1 from public.module_maintenance_windows w
+                where w.module_id = server_modules.id
+            ) as has_maintenance_windows
         from public.server_modules
         where server_id = $1 and enabled = true
         order by name asc
@@ -48,21 +149,102 @@ pub async fn dispatch_batch(
         tracing::error!("dispatch query failed: {:?}", e);
         ApiError::Internal
     })?;
+    let modules = without_suspended(&state, modules).await;
+
+    // Every module below shares this one buffer; only transforms that rewrite the batch allocate.
+    let raw_gz_ndjson = strip_exempt_players(&state, &server_id, batch_id, raw_gz_ndjson).await;
+
+    let check_config = if modules
+        .iter()
+        .any(|m| checks::inproc_engine(&m.base_url).is_some())
+    {
+        checks::CheckConfig::load(&state.db, &server_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(server_id = %server_id, "check config lookup failed: {:?}", e);
+                checks::CheckConfig::default()
+            })
+    } else {
+        checks::CheckConfig::default()
+    };
+    let check_config = Arc::new(check_config);
+
+    let clock_skew_ms = if modules
+        .iter()
+        .any(|m| transforms::wants_server_time(&m.transform))
+    {
+        clock_skew::for_batch(&state.db, batch_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(batch_id = %batch_id, "clock skew lookup failed: {:?}", e);
+                None
+            })
+    } else {
+        None
+    };
 
     for m in modules {
         // Skip modules that are known-down.
-        if m.last_healthcheck_ok == Some(false) && m.consecutive_failures >= 3 {
+        if m.last_healthcheck_ok == Some(false)
+            && m.consecutive_failures >= module_outage::DOWN_AFTER_FAILURES
+        {
             continue;
         }
 
-        // Category modules accept gzipped NDJSON batches via POST /ingest.
-        let ingest_url = format!("{}/ingest", m.base_url.trim_end_matches('/'));
+        // In-process engines and WASM modules run here instead of posting to a module.
+        if let Some(engine) = checks::inproc_engine(&m.base_url) {
+            let engine = engine.to_string();
+            let (raw, config) = (raw_gz_ndjson.clone(), check_config.clone());
+            let job_engine = engine.clone();
+            let result = state
+                .transform_pool
+                .run(move || {
+                    let started = std::time::Instant::now();
+                    let findings = checks::run_with(&job_engine, &raw, &config)?;
+                    Ok((findings, module_usage::elapsed_us(started)))
+                })
+                .await;
+            let run_us = result.as_ref().map_or(0, |(_, us)| *us);
+            let result = result
+                .map(|(findings, _)| findings)
+                .map_err(|e| format!("in-process engine '{}' failed: {}", engine, e));
+            dispatch_local(&state, &m, result, &session_id, batch_id, &s3_key).await;
+            module_usage::record(&state.db, m.id, &m.server_id, batch_id, 0, run_us).await;
+            continue;
+        }
+        if let Some(wasm_id) = wasm_runtime::wasm_module_id(&m.base_url) {
+            let started = std::time::Instant::now();
+            let result =
+                wasm_runtime::run(&state, wasm_id, &m.transform, &raw_gz_ndjson, clock_skew_ms)
+                    .await
+                    .map_err(|e| format!("wasm module failed: {}", e));
+            let run_us = module_usage::elapsed_us(started);
+            dispatch_local(&state, &m, result, &session_id, batch_id, &s3_key).await;
+            module_usage::record(&state.db, m.id, &m.server_id, batch_id, 0, run_us).await;
+            continue;
+        }
 
-        let payload_gz = match transforms::apply_transform(&m.transform, &raw_gz_ndjson) {
+        // Transform and encode on the transform pool, off the async runtime.
+        let encoding = ContentEncoding::parse(&m.content_encoding).unwrap_or_default();
+        let max_part_bytes = state.dispatch_part_max_bytes;
+        let (raw, transform) = (raw_gz_ndjson.clone(), m.transform.clone());
+        let prepared = state
+            .transform_pool
+            .run(move || {
+                let started = std::time::Instant::now();
+                let payload_gz =
+                    transforms::apply_transform_shared(&transform, &raw, clock_skew_ms)
+                        .map_err(|e| anyhow::anyhow!("transform '{}' failed: {}", transform, e))?;
+                let parts = dispatch_parts::encode_parts(payload_gz, encoding, max_part_bytes)
+                    .map_err(|e| anyhow::anyhow!("{} encoding failed: {}", encoding.as_str(), e))?;
+                Ok((parts, module_usage::elapsed_us(started)))
+            })
+            .await;
+        let (parts, transform_us) = match prepared {
             Ok(v) => v,
             Err(e) => {
-                let err = format!("transform '{}' failed: {}", m.transform, e);
-                tracing::error!("module {} transform failed: {}", m.name, err);
+                let err = e.to_string();
+                tracing::error!("module {} payload preparation failed: {}", m.name, err);
                 record_dispatch(
                     &state,
                     batch_id,
@@ -71,28 +253,172 @@ pub async fn dispatch_batch(
                     "failed",
                     None,
                     Some(&err),
+                    None,
                 )
                 .await;
-                mark_module_failure(&state, &m.id, &err).await;
+                // A saturated or slow pool is the API's problem, not the module's.
+                if matches!(e, PoolError::Failed(_)) {
+                    mark_module_failure(&state, &m.id, &err).await;
+                }
                 continue;
             }
         };
+        let payload_bytes: i64 = parts.iter().map(|p| p.len() as i64).sum();
 
-        let resp = state
-            .http
-            .post(ingest_url)
-            // Keep these headers consistent with plugin → API ingest.
-            .header("content-type", "application/x-ndjson")
-            .header("content-encoding", "gzip")
-            .header("x-server-id", &server_id)
-            .header("x-session-id", &session_id)
-            .header("x-batch-id", batch_id.to_string())
-            .header("x-s3-key", &s3_key)
-            .body(payload_gz)
-            .send()
-            .await;
+        // With backups, endpoints are tried in order until one takes the batch.
+        let mut endpoints = if m.backup_base_urls.is_empty() {
+            vec![m.base_url.clone()]
+        } else {
+            let health = module_failover::load(&state.db, &[m.id])
+                .await
+                .map(|mut h| h.remove(&m.id).unwrap_or_default())
+                .unwrap_or_else(|e| {
+                    tracing::warn!(module = %m.name, "endpoint health lookup failed: {:?}", e);
+                    Vec::new()
+                });
+            module_failover::dispatch_order(&m.base_url, &m.backup_base_urls, &health)
+        };
+        // The canary's share of batches tries the canary first.
+        let canary_configured = m.canary_base_url.is_some() && m.canary_percent > 0;
+        let canary = m
+            .canary_base_url
+            .clone()
+            .filter(|_| module_canary::routes_to_canary(batch_id, m.canary_percent));
+        if let Some(canary) = &canary {
+            endpoints.insert(0, canary.clone());
+        }
+
+        let started = std::time::Instant::now();
+        let count = parts.len();
+        let mut resp = None;
+        let mut part_label = String::new();
+        let mut served_by_canary = false;
+        let mut assigned = None;
+        for (attempt, base_url) in endpoints.iter().enumerate() {
+            let last_endpoint = attempt + 1 == endpoints.len();
+            resp = None;
+            part_label.clear();
+
+            // Findings may arrive before the response: record the variant before sending.
+            served_by_canary = canary.as_ref() == Some(base_url);
+            let variant = if served_by_canary {
+                module_canary::CANARY
+            } else {
+                module_canary::STABLE
+            };
+            if canary_configured && assigned != Some(variant) {
+                module_canary::assign(&state.db, m.id, batch_id, variant).await;
+                assigned = Some(variant);
+            }
+
+            // Parts go out in order; stop at the first one the module doesn't accept.
+            for (index, payload) in parts.iter().enumerate() {
+                let part = (count > 1).then_some(DispatchPart { index, count });
+                let r = match state
+                    .faults
+                    .inject(FaultPoint::ModuleDispatch, &m.name)
+                    .await
+                {
+                    Ok(()) => post_ingest_encoded(
+                        &state.http,
+                        base_url,
+                        &server_id,
+                        &session_id,
+                        batch_id,
+                        Some(m.id),
+                        Some(&s3_key),
+                        payload.clone(),
+                        encoding,
+                        part,
+                    )
+                    .await
+                    .map_err(|e| e.to_string()),
+                    Err(fault) => Err(fault.to_string()),
+                };
+                let accepted = matches!(&r, Ok(r) if r.status().is_success());
+                resp = Some(r);
+                if !accepted {
+                    if count > 1 {
+                        part_label = format!(" (part {}/{})", index + 1, count);
+                    }
+                    break;
+                }
+            }
+
+            if endpoints.len() == 1 {
+                break;
+            }
+            let (status, err) = match &resp {
+                Some(Ok(r)) if r.status().is_success() => (Some(r.status()), None),
+                Some(Ok(r)) => (
+                    Some(r.status()),
+                    Some(format!("module returned http {}{}", r.status(), part_label)),
+                ),
+                Some(Err(e)) => (None, Some(format!("dispatch error{}: {}", part_label, e))),
+                None => break,
+            };
+            if !served_by_canary {
+                module_failover::record(&state.db, m.id, base_url, err.as_deref()).await;
+            }
+            // A failed canary always hands the batch to the stable endpoints.
+            if err.is_none()
+                || last_endpoint
+                || (!served_by_canary && !module_failover::should_fail_over(status))
+            {
+                break;
+            }
+            tracing::warn!(
+                module = %m.name,
+                endpoint = %base_url,
+                error = err.as_deref().unwrap_or_default(),
+                "module endpoint failed, trying the next one"
+            );
+        }
+        let Some(resp) = resp else {
+            continue;
+        };
+        let latency_ms = started.elapsed().as_millis().min(i32::MAX as u128) as i32;
+        let accepted = matches!(&resp, Ok(r) if r.status().is_success());
 
         match resp {
+            Ok(r) if r.status() == reqwest::StatusCode::ACCEPTED => {
+                let header = r
+                    .headers()
+                    .get(async_dispatch::PROCESSING_ID_HEADER)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                let body = r.bytes().await.unwrap_or_default();
+                match async_dispatch::processing_id(header.as_deref(), &body) {
+                    Some(processing_id) => {
+                        async_dispatch::record_processing(
+                            &state,
+                            batch_id,
+                            &m.id,
+                            &m.server_id,
+                            &processing_id,
+                            m.processing_deadline_seconds,
+                            latency_ms,
+                        )
+                        .await
+                    }
+                    None => {
+                        record_dispatch(
+                            &state,
+                            batch_id,
+                            &m.id,
+                            &m.server_id,
+                            "sent",
+                            Some(202),
+                            None,
+                            Some(latency_ms),
+                        )
+                        .await
+                    }
+                }
+                if !served_by_canary {
+                    mark_module_ok(&state, &m.id).await;
+                }
+            }
             Ok(r) if r.status().is_success() => {
                 record_dispatch(
                     &state,
@@ -102,12 +428,15 @@ pub async fn dispatch_batch(
                     "sent",
                     Some(r.status().as_u16() as i32),
                     None,
+                    Some(latency_ms),
                 )
                 .await;
-                mark_module_ok(&state, &m.id).await;
+                if !served_by_canary {
+                    mark_module_ok(&state, &m.id).await;
+                }
             }
             Ok(r) => {
-                let err = format!("module returned http {}", r.status());
+                let err = format!("module returned http {}{}", r.status(), part_label);
                 record_dispatch(
                     &state,
                     batch_id,
@@ -116,12 +445,16 @@ pub async fn dispatch_batch(
                     "failed",
                     Some(r.status().as_u16() as i32),
                     Some(&err),
+                    Some(latency_ms),
                 )
                 .await;
-                mark_module_failure(&state, &m.id, &err).await;
+                // A failing canary must not take the stable module down.
+                if !served_by_canary {
+                    mark_module_failure(&state, &m.id, &err).await;
+                }
             }
             Err(e) => {
-                let err = format!("dispatch error: {}", e);
+                let err = format!("dispatch error{}: {}", part_label, e);
                 record_dispatch(
                     &state,
                     batch_id,
@@ -130,16 +463,177 @@ pub async fn dispatch_batch(
                     "failed",
                     None,
                     Some(&err),
+                    Some(latency_ms),
                 )
                 .await;
-                mark_module_failure(&state, &m.id, &err).await;
+                if !served_by_canary {
+                    mark_module_failure(&state, &m.id, &err).await;
+                }
             }
         }
+
+        if let Some(variant) = assigned.filter(|_| accepted) {
+            module_canary::record_dispatch(&state.db, m.id, batch_id, variant).await;
+        }
+        module_usage::record(
+            &state.db,
+            m.id,
+            &m.server_id,
+            batch_id,
+            payload_bytes,
+            transform_us,
+        )
+        .await;
     }
 
     Ok(())
 }
 
+/// POST a transformed batch to a module's `/ingest`.
+///
+/// Category modules accept gzipped NDJSON batches; keep these headers consistent with
+/// plugin → API ingest.
+#[allow(clippy::too_many_arguments)]
+pub async fn post_ingest(
+    http: &reqwest::Client,
+    base_url: &str,
+    server_id: &str,
+    session_id: &str,
+    batch_id: Uuid,
+    module_id: Option<Uuid>,
+    s3_key: Option<&str>,
+    payload_gz: Vec<u8>,
+) -> reqwest::Result<reqwest::Response> {
+    post_ingest_encoded(
+        http,
+        base_url,
+        server_id,
+        session_id,
+        batch_id,
+        module_id,
+        s3_key,
+        payload_gz.into(),
+        ContentEncoding::Gzip,
+        None,
+    )
+    .await
+}
+
+/// POST a batch (or one part of it, see `dispatch_parts`) already encoded as `encoding` (see
+/// `dispatch_encoding`).
+#[allow(clippy::too_many_arguments)]
+pub async fn post_ingest_encoded(
+    http: &reqwest::Client,
+    base_url: &str,
+    server_id: &str,
+    session_id: &str,
+    batch_id: Uuid,
+    module_id: Option<Uuid>,
+    s3_key: Option<&str>,
+    payload: Bytes,
+    encoding: ContentEncoding,
+    part: Option<DispatchPart>,
+) -> reqwest::Result<reqwest::Response> {
+    let ingest_url = format!("{}/ingest", base_url.trim_end_matches('/'));
+    let mut req = http
+        .post(ingest_url)
+        .header("content-type", "application/x-ndjson")
+        .header("x-server-id", server_id)
+        .header("x-session-id", session_id)
+        .header("x-batch-id", batch_id.to_string());
+    if let Some(value) = encoding.header_value() {
+        req = req.header("content-encoding", value);
+    }
+    if let Some(module_id) = module_id {
+        req = req.header("x-module-id", module_id.to_string());
+    }
+    if let Some(s3_key) = s3_key {
+        req = req.header("x-s3-key", s3_key);
+    }
+    if let Some(part) = part {
+        req = req
+            .header(dispatch_parts::PART_HEADER, part.index.to_string())
+            .header(dispatch_parts::PARTS_HEADER, part.count.to_string());
+        if part.is_final() {
+            req = req.header(dispatch_parts::FINAL_HEADER, "true");
+        }
+    }
+    req.body(payload).send().await
+}
+
+/// Store findings produced inside the API and record the dispatch like an HTTP one.
+async fn dispatch_local(
+    state: &AppState,
+    m: &ServerModuleRow,
+    result: Result<Vec<FindingIn>, String>,
+    session_id: &str,
+    batch_id: Uuid,
+    s3_key: &str,
+) {
+    let mut findings = match result {
+        Ok(f) => f,
+        Err(err) => {
+            tracing::error!("module {} failed: {}", m.name, err);
+            record_dispatch(
+                state,
+                batch_id,
+                &m.id,
+                &m.server_id,
+                "failed",
+                None,
+                Some(&err),
+                None,
+            )
+            .await;
+            mark_module_failure(state, &m.id, &err).await;
+            return;
+        }
+    };
+
+    for f in &mut findings {
+        f.evidence_s3_key = Some(s3_key.to_string());
+    }
+
+    if !findings.is_empty() {
+        let req = PostFindingsRequest {
+            server_id: m.server_id.clone(),
+            module_id: Some(m.id),
+            session_id: Some(session_id.to_string()),
+            batch_id: Some(batch_id),
+            findings,
+        };
+        if let Err(e) = callbacks::store_findings(state, &req).await {
+            let err = format!("storing module findings failed: {:?}", e);
+            record_dispatch(
+                state,
+                batch_id,
+                &m.id,
+                &m.server_id,
+                "failed",
+                None,
+                Some(&err),
+                None,
+            )
+            .await;
+            mark_module_failure(state, &m.id, &err).await;
+            return;
+        }
+    }
+
+    record_dispatch(
+        state,
+        batch_id,
+        &m.id,
+        &m.server_id,
+        "sent",
+        None,
+        None,
+        None,
+    )
+    .await;
+    mark_module_ok(state, &m.id).await;
+}
+
 pub async fn healthcheck_tick(state: AppState) {
     let modules = sqlx::query_as::<_, ServerModuleRow>(
         r#"
@@ -148,10 +642,19 @@ pub async fn healthcheck_tick(state: AppState) {
             server_id,
             name,
             base_url,
-            enabled,
+            backup_base_urls,
+            canary_base_url,
+            canary_percent,
             transform,
             last_healthcheck_ok,
-            consecutive_failures
+            consecutive_failures,
+            content_encoding,
+            processing_deadline_seconds,
+            paused_until,
+            exists (
+                select 1 from public.module_maintenance_windows w
+                where w.module_id = server_modules.id
+            ) as has_maintenance_windows
         from public.server_modules
         where enabled = true
         order by server_id asc, name asc
@@ -163,28 +666,91 @@ pub async fn healthcheck_tick(state: AppState) {
     let Ok(modules) = modules else {
         return;
     };
+    // Suspended modules keep their last health state.
+    let modules = without_suspended(&state, modules).await;
 
     for m in modules {
-        let health_url = format!("{}/health", m.base_url.trim_end_matches('/'));
-        let result = state.http.get(&health_url).send().await;
-        match &result {
-            Ok(r) if r.status().is_success() => {
-                tracing::debug!(module = %m.name, url = %health_url, "healthcheck passed");
+        // In-process engines have no endpoint; they are healthy if this build implements them.
+        if let Some(engine) = checks::inproc_engine(&m.base_url) {
+            if checks::is_known_engine(engine) {
                 mark_health(&state, &m.id, true, None).await;
+            } else {
+                let err = format!("unknown in-process engine: {}", engine);
+                mark_health(&state, &m.id, false, Some(&err)).await;
             }
-            Ok(r) => {
-                let status = r.status();
-                tracing::warn!(module = %m.name, url = %health_url, status = %status, "healthcheck failed with non-success status");
-                mark_health(&state, &m.id, false, Some(&format!("HTTP {}", status))).await;
+            continue;
+        }
+        if let Some(wasm_id) = wasm_runtime::wasm_module_id(&m.base_url) {
+            match wasm_runtime::is_available(&state, wasm_id).await {
+                Ok(()) => mark_health(&state, &m.id, true, None).await,
+                Err(err) => mark_health(&state, &m.id, false, Some(&err)).await,
             }
-            Err(e) => {
-                tracing::warn!(module = %m.name, url = %health_url, error = %e, "healthcheck request failed");
-                mark_health(&state, &m.id, false, Some(&format!("request error: {}", e))).await;
+            continue;
+        }
+
+        // The module is healthy while any of its endpoints is.
+        let has_backups = !m.backup_base_urls.is_empty();
+        let mut healthy = false;
+        let mut errors: Vec<String> = Vec::new();
+        let mut accepted: Option<Vec<ContentEncoding>> = None;
+        for base_url in std::iter::once(&m.base_url).chain(&m.backup_base_urls) {
+            let health_url = format!("{}/health", base_url.trim_end_matches('/'));
+            let result = state.http.get(&health_url).send().await;
+            let err = match &result {
+                Ok(r) if r.status().is_success() => {
+                    tracing::debug!(module = %m.name, url = %health_url, "healthcheck passed");
+                    healthy = true;
+                    // Dispatch only uses encodings every endpoint accepts.
+                    if let Some(accept) = r
+                        .headers()
+                        .get(reqwest::header::ACCEPT_ENCODING)
+                        .and_then(|v| v.to_str().ok())
+                    {
+                        let listed = dispatch_encoding::parse_accepted(accept);
+                        accepted = Some(match accepted {
+                            Some(prev) => prev.into_iter().filter(|e| listed.contains(e)).collect(),
+                            None => listed,
+                        });
+                    }
+                    None
+                }
+                Ok(r) => {
+                    let status = r.status();
+                    tracing::warn!(module = %m.name, url = %health_url, status = %status, "healthcheck failed with non-success status");
+                    Some(format!("HTTP {}", status))
+                }
+                Err(e) => {
+                    tracing::warn!(module = %m.name, url = %health_url, error = %e, "healthcheck request failed");
+                    Some(format!("request error: {}", e))
+                }
+            };
+            if has_backups {
+                module_failover::record(&state.db, m.id, base_url, err.as_deref()).await;
+            }
+            if let Some(err) = err {
+                errors.push(if has_backups {
+                    format!("{}: {}", base_url, err)
+                } else {
+                    err
+                });
             }
         }
+
+        if healthy {
+            mark_health(&state, &m.id, true, None).await;
+            if let Some(accepted) = accepted {
+                let encoding = dispatch_encoding::negotiate(&m.base_url, &accepted);
+                if encoding.as_str() != m.content_encoding {
+                    set_content_encoding(&state, &m, encoding).await;
+                }
+            }
+        } else {
+            mark_health(&state, &m.id, false, Some(&errors.join("; "))).await;
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn record_dispatch(
     state: &AppState,
     batch_id: Uuid,
@@ -193,13 +759,14 @@ async fn record_dispatch(
     status: &str,
     http_status: Option<i32>,
     error: Option<&str>,
+    latency_ms: Option<i32>,
 ) {
     let _ = sqlx::query(
         r#"
         insert into public.module_dispatches
-            (batch_id, server_id, module_id, status, http_status, error)
+            (batch_id, server_id, module_id, status, http_status, error, latency_ms)
         values
-            ($1, $2, $3, $4, $5, $6)
+            ($1, $2, $3, $4, $5, $6, $7)
         "#,
     )
     .bind(batch_id)
@@ -208,11 +775,12 @@ async fn record_dispatch(
     .bind(status)
     .bind(http_status)
     .bind(error)
+    .bind(latency_ms)
     .execute(&state.db)
     .await;
 }
 
-async fn mark_module_ok(state: &AppState, module_id: &Uuid) {
+pub async fn mark_module_ok(state: &AppState, module_id: &Uuid) {
     let _ = sqlx::query(
         r#"
         update public.server_modules
@@ -229,8 +797,17 @@ async fn mark_module_ok(state: &AppState, module_id: &Uuid) {
     .await;
 }
 
-async fn mark_module_failure(state: &AppState, module_id: &Uuid, err: &str) {
-    let _ = sqlx::query(
+/// Count a failure against the module, unless it is paused or in a maintenance window.
+pub async fn mark_module_failure(state: &AppState, module_id: &Uuid, err: &str) {
+    match module_maintenance::current(&state.db, *module_id).await {
+        Ok(Some(s)) => {
+            tracing::debug!(module_id = %module_id, reason = %s.reason, error = err, "failure during module suspension not counted");
+            return;
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!(module_id = %module_id, "suspension lookup failed: {:?}", e),
+    }
+    let updated: Result<Option<(String, String, i32)>, _> = sqlx::query_as(
         r#"
         update public.server_modules
         set
@@ -239,12 +816,38 @@ async fn mark_module_failure(state: &AppState, module_id: &Uuid, err: &str) {
             last_healthcheck_ok = false,
             last_healthcheck_at = now()
         where id = $1
+        returning server_id, name, consecutive_failures
         "#,
     )
     .bind(module_id)
     .bind(err)
-    .execute(&state.db)
+    .fetch_optional(&state.db)
     .await;
+
+    // Exactly one failure reaches the threshold per outage.
+    if let Ok(Some((server_id, name, failures))) = updated {
+        if failures == module_outage::DOWN_AFTER_FAILURES {
+            module_outage::module_went_down(state, *module_id, &server_id, &name, failures, err)
+                .await;
+        }
+    }
+}
+
+async fn set_content_encoding(state: &AppState, m: &ServerModuleRow, encoding: ContentEncoding) {
+    let res = sqlx::query("update public.server_modules set content_encoding = $2 where id = $1")
+        .bind(m.id)
+        .bind(encoding.as_str())
+        .execute(&state.db)
+        .await;
+    match res {
+        Ok(_) => tracing::info!(
+            module = %m.name,
+            from = %m.content_encoding,
+            to = encoding.as_str(),
+            "module dispatch encoding negotiated"
+        ),
+        Err(e) => tracing::warn!(module = %m.name, "content encoding update failed: {:?}", e),
+    }
 }
 
 async fn mark_health(state: &AppState, module_id: &Uuid, ok: bool, err: Option<&str>) {
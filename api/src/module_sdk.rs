@@ -0,0 +1,247 @@
+//! Types and scaffolding for third-party modules written in Rust.
+//!
+//! Enabled with the `module-sdk` feature. Modules share the exact payload types the pipeline
+//! uses instead of guessing the JSON shape:
+//!
+//! - [`ProcessBatchRequest`]: a dispatched batch (headers + decoded NDJSON lines)
+//! - [`MovementEvent`], [`CombatEvent`], [`FightEvent`], [`ClientChannelEvent`]: lines produced by
+//!   the standard transforms
+//! - [`PostFindingsRequest`] / [`FindingIn`]: the findings callback payload
+//! - [`router`]: an axum router serving `GET /health` and `POST /ingest`
+//!
+//! ```ignore
+//! let app = module_sdk::router(|batch: ProcessBatchRequest| async move {
+//!     for ev in batch.events::<MovementEvent>() { /* ... */ }
+//!     Ok(())
+//! });
+//! ```
+
+use axum::{
+    body::Bytes,
+    http::{header, HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use std::io::BufRead;
+use uuid::Uuid;
+
+pub use crate::routes::callbacks::{
+    DispatchCompleteRequest, DispatchCompleteResponse, FindingIn, PostFindingsRequest,
+    PostFindingsResponse,
+};
+pub use crate::transform_events::{ClientChannelEvent, CombatEvent, FightEvent, MovementEvent};
+
+use crate::{dispatch_encoding, dispatch_parts};
+
+/// Request encodings `router` modules accept, advertised on `/health` (see `dispatch_encoding`).
+pub const ACCEPT_ENCODING: &str = "zstd, gzip, identity";
+
+/// A batch as dispatched by the API to `POST {base_url}/ingest`.
+#[derive(Debug, Clone)]
+pub struct ProcessBatchRequest {
+    pub server_id: String,
+    pub session_id: String,
+    pub batch_id: Uuid,
+    /// The module's id on the API (`X-Module-Id`); echoed in findings callbacks.
+    pub module_id: Option<Uuid>,
+    pub s3_key: Option<String>,
+    /// First NDJSON line (batch metadata, annotated with `transform`).
+    pub meta: Value,
+    /// Remaining NDJSON lines.
+    pub lines: Vec<Value>,
+    /// Large batches arrive in parts (see `dispatch_parts`); 0 and 1 for a whole batch.
+    pub part_index: usize,
+    pub part_count: usize,
+}
+
+impl ProcessBatchRequest {
+    /// Decode a dispatched batch from its headers and NDJSON body (gzip, zstd or plain, per
+    /// `Content-Encoding`).
+    pub fn from_parts(headers: &HeaderMap, body: &[u8]) -> anyhow::Result<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        let server_id =
+            header("x-server-id").ok_or_else(|| anyhow::anyhow!("missing X-Server-Id"))?;
+        let session_id =
+            header("x-session-id").ok_or_else(|| anyhow::anyhow!("missing X-Session-Id"))?;
+        let batch_id = header("x-batch-id")
+            .and_then(|s| Uuid::parse_str(&s).ok())
+            .ok_or_else(|| anyhow::anyhow!("missing or invalid X-Batch-Id"))?;
+
+        let mut meta = Value::Object(Default::default());
+        let mut lines = Vec::new();
+        let ndjson = dispatch_encoding::decode(body, header("content-encoding").as_deref())?;
+        for (i, line) in ndjson.as_slice().lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Ok(v) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            if i == 0 {
+                meta = v;
+            } else {
+                lines.push(v);
+            }
+        }
+
+        Ok(Self {
+            server_id,
+            session_id,
+            batch_id,
+            module_id: header("x-module-id").and_then(|s| Uuid::parse_str(&s).ok()),
+            s3_key: header("x-s3-key"),
+            meta,
+            lines,
+            part_index: header(dispatch_parts::PART_HEADER)
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            part_count: header(dispatch_parts::PARTS_HEADER)
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1),
+        })
+    }
+
+    /// Whether this is the last (or only) part of the batch, e.g. to flush per-batch state.
+    pub fn is_final_part(&self) -> bool {
+        self.part_index + 1 >= self.part_count
+    }
+
+    /// Name of the transform that produced the lines (from the metadata line).
+    pub fn transform(&self) -> Option<&str> {
+        self.meta.get("transform").and_then(|v| v.as_str())
+    }
+
+    /// Lines decoded as typed events; lines that don't match `T` are skipped.
+    pub fn events<T: DeserializeOwned>(&self) -> impl Iterator<Item = T> + '_ {
+        self.lines.iter().filter_map(|v| T::deserialize(v).ok())
+    }
+
+    /// Start a findings callback payload for this batch.
+    pub fn findings_request(&self, findings: Vec<FindingIn>) -> PostFindingsRequest {
+        PostFindingsRequest {
+            server_id: self.server_id.clone(),
+            module_id: self.module_id,
+            session_id: Some(self.session_id.clone()),
+            batch_id: Some(self.batch_id),
+            findings,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct OkBody {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Router scaffold for a module: `GET /health` and `POST /ingest`. `/health` advertises
+/// [`ACCEPT_ENCODING`], so the API negotiates the cheapest dispatch encoding for it.
+///
+/// The handler runs once per dispatched batch; returning `Err` answers HTTP 500 so the API
+/// records the dispatch as failed.
+pub fn router<F, Fut>(handler: F) -> Router
+where
+    F: Fn(ProcessBatchRequest) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = Result<(), String>> + Send + 'static,
+{
+    Router::new()
+        .route(
+            "/health",
+            get(|| async {
+                (
+                    [(header::ACCEPT_ENCODING, ACCEPT_ENCODING)],
+                    Json(OkBody {
+                        ok: true,
+                        error: None,
+                    }),
+                )
+            }),
+        )
+        .route(
+            "/ingest",
+            post(move |headers: HeaderMap, body: Bytes| {
+                let handler = handler.clone();
+                async move {
+                    let batch = match ProcessBatchRequest::from_parts(&headers, &body) {
+                        Ok(b) => b,
+                        Err(e) => {
+                            return (
+                                StatusCode::BAD_REQUEST,
+                                Json(OkBody {
+                                    ok: false,
+                                    error: Some(e.to_string()),
+                                }),
+                            )
+                        }
+                    };
+                    match handler(batch).await {
+                        Ok(()) => (
+                            StatusCode::OK,
+                            Json(OkBody {
+                                ok: true,
+                                error: None,
+                            }),
+                        ),
+                        Err(e) => (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Json(OkBody {
+                                ok: false,
+                                error: Some(e),
+                            }),
+                        ),
+                    }
+                }
+            }),
+        )
+}
+
+/// Post findings back to the API (`POST {api_base_url}/callbacks/findings`).
+pub async fn post_findings(
+    http: &reqwest::Client,
+    api_base_url: &str,
+    callback_token: &str,
+    req: &PostFindingsRequest,
+) -> anyhow::Result<PostFindingsResponse> {
+    let url = format!("{}/callbacks/findings", api_base_url.trim_end_matches('/'));
+    let resp = http
+        .post(url)
+        .bearer_auth(callback_token)
+        .json(req)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(resp.json().await?)
+}
+
+/// Report an async dispatch as done (`POST {api_base_url}/callbacks/dispatch-complete`), for
+/// modules that answered `/ingest` with 202 and a processing id.
+pub async fn post_dispatch_complete(
+    http: &reqwest::Client,
+    api_base_url: &str,
+    callback_token: &str,
+    req: &DispatchCompleteRequest,
+) -> anyhow::Result<DispatchCompleteResponse> {
+    let url = format!(
+        "{}/callbacks/dispatch-complete",
+        api_base_url.trim_end_matches('/')
+    );
+    let resp = http
+        .post(url)
+        .bearer_auth(callback_token)
+        .json(req)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(resp.json().await?)
+}
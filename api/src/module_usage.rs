@@ -0,0 +1,150 @@
+//! Per-module dispatch cost accounting.
+//!
+//! Every dispatch records what it cost the API: `payload_bytes` is the transformed, encoded
+//! payload sent to an HTTP module (all parts, counted once even when the batch fails over to a
+//! backup), and `transform_us` the time spent producing it, measured on the transform pool worker
+//! (so without queueing, see `transform_pool`). In-process and WASM modules send nothing; their
+//! `transform_us` is the time spent running the module itself.
+//!
+//! Both are stored on the dispatch row and added to `module_usage_daily`, which
+//! `GET /dashboard/:server_id/modules/usage` reads to show which modules drive bandwidth and CPU.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Daily rows are kept as long as the hourly dispatch rollups.
+pub const RETENTION_DAYS: i32 = crate::dispatch_stats::STATS_RETENTION_DAYS;
+
+/// Microseconds elapsed since `started`.
+pub fn elapsed_us(started: std::time::Instant) -> i64 {
+    started.elapsed().as_micros().min(i64::MAX as u128) as i64
+}
+
+/// Tag the module's latest dispatch of the batch with its cost and add it to the day's usage.
+pub async fn record(
+    db: &PgPool,
+    module_id: Uuid,
+    server_id: &str,
+    batch_id: Uuid,
+    payload_bytes: i64,
+    transform_us: i64,
+) {
+    let res = async {
+        sqlx::query(
+            r#"
+            update public.module_dispatches set payload_bytes = $3, transform_us = $4
+            where id = (
+                select id from public.module_dispatches
+                where batch_id = $1 and module_id = $2
+                order by created_at desc
+                limit 1
+            )
+            "#,
+        )
+        .bind(batch_id)
+        .bind(module_id)
+        .bind(payload_bytes)
+        .bind(transform_us)
+        .execute(db)
+        .await?;
+        sqlx::query(
+            r#"
+            insert into public.module_usage_daily
+                (module_id, day, server_id, dispatches, payload_bytes, transform_us)
+            values ($1, current_date, $2, 1, $3, $4)
+            on conflict (module_id, day) do update set
+                dispatches = public.module_usage_daily.dispatches + 1,
+                payload_bytes = public.module_usage_daily.payload_bytes + excluded.payload_bytes,
+                transform_us = public.module_usage_daily.transform_us + excluded.transform_us
+            "#,
+        )
+        .bind(module_id)
+        .bind(server_id)
+        .bind(payload_bytes)
+        .bind(transform_us)
+        .execute(db)
+        .await?;
+        Ok::<_, sqlx::Error>(())
+    }
+    .await;
+    if let Err(e) = res {
+        tracing::warn!(module_id = %module_id, batch_id = %batch_id, "module usage recording failed: {:?}", e);
+    }
+}
+
+/// Drop daily usage rows past `RETENTION_DAYS`.
+pub async fn purge(db: &PgPool) {
+    let res = sqlx::query("delete from public.module_usage_daily where day < current_date - $1")
+        .bind(RETENTION_DAYS)
+        .execute(db)
+        .await;
+    if let Err(e) = res {
+        tracing::warn!("module usage purge failed: {:?}", e);
+    }
+}
+
+/// One module's usage on one day.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UsageDay {
+    pub module_id: Uuid,
+    pub day: NaiveDate,
+    pub dispatches: i64,
+    pub payload_bytes: i64,
+    pub transform_us: i64,
+}
+
+/// A module's usage over the requested days.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ModuleUsage {
+    pub module_id: Uuid,
+    pub name: String,
+    pub dispatches: i64,
+    pub payload_bytes: i64,
+    pub transform_ms: f64,
+    /// Average payload per dispatch; None without dispatches.
+    pub avg_payload_bytes: Option<f64>,
+    /// Share (0-1) of all modules' payload bytes and transform time; None when the total is 0.
+    pub bandwidth_share: Option<f64>,
+    pub cpu_share: Option<f64>,
+}
+
+/// Per-module totals of `days`, most bandwidth first. Modules without usage are listed with
+/// zeros.
+pub fn summarize(modules: &[(Uuid, String)], days: &[UsageDay]) -> Vec<ModuleUsage> {
+    let total_bytes: i64 = days.iter().map(|d| d.payload_bytes).sum();
+    let total_us: i64 = days.iter().map(|d| d.transform_us).sum();
+
+    let mut out: Vec<ModuleUsage> = modules
+        .iter()
+        .map(|(module_id, name)| {
+            let (dispatches, payload_bytes, transform_us) = days
+                .iter()
+                .filter(|d| d.module_id == *module_id)
+                .fold((0, 0, 0), |(n, b, us), d| {
+                    (n + d.dispatches, b + d.payload_bytes, us + d.transform_us)
+                });
+            ModuleUsage {
+                module_id: *module_id,
+                name: name.clone(),
+                dispatches,
+                payload_bytes,
+                transform_ms: transform_us as f64 / 1000.0,
+                avg_payload_bytes: (dispatches > 0)
+                    .then(|| payload_bytes as f64 / dispatches as f64),
+                bandwidth_share: (total_bytes > 0)
+                    .then(|| payload_bytes as f64 / total_bytes as f64),
+                cpu_share: (total_us > 0).then(|| transform_us as f64 / total_us as f64),
+            }
+        })
+        .collect();
+    out.sort_by(|a, b| {
+        b.payload_bytes
+            .cmp(&a.payload_bytes)
+            .then(b.transform_ms.total_cmp(&a.transform_ms))
+            .then(a.name.cmp(&b.name))
+    });
+    out
+}
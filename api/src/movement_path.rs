@@ -0,0 +1,233 @@
+//! Movement path reconstruction for the dashboard's trajectory view.
+//!
+//! A player's positions are read back from the stored batches of the plugin sessions they were
+//! on (`player_sessions`), through the movement transform with `+server_time` so record `ts` is
+//! on the API clock and lines up with finding times. Consecutive positions further apart than
+//! [`TELEPORT_BLOCKS`], in another world or [`GAP_MS`] apart start a new segment, so the
+//! dashboard doesn't draw a line across a teleport. The polyline is then downsampled to the
+//! requested number of points, always keeping segment boundaries where it can.
+
+use std::io::{BufRead, BufReader};
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{error::ApiError, transforms, AppState};
+
+pub const TRANSFORM: &str = "movement_events_v1_ndjson_gz+server_time";
+pub const DEFAULT_WINDOW_MINUTES: i64 = 5;
+pub const MAX_WINDOW_MINUTES: i64 = 30;
+pub const DEFAULT_MAX_POINTS: usize = 1000;
+pub const MAX_POINTS: usize = 5000;
+pub const MAX_BATCHES: i64 = 600;
+pub const TELEPORT_BLOCKS: f64 = 8.0;
+pub const GAP_MS: i64 = 5000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PathPoint {
+    /// Milliseconds since the epoch, API clock (plugin clock when the session has no skew estimate).
+    pub ts: i64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_ground: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub world: Option<String>,
+    /// Don't connect this point to the previous one (teleport, world change or gap).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub segment_start: bool,
+}
+
+/// The player's positions in a movement-transformed batch with `ts` in `[from_ms, to_ms]`.
+pub fn points_from(
+    gz_movement: &[u8],
+    player: Uuid,
+    from_ms: i64,
+    to_ms: i64,
+) -> anyhow::Result<Vec<PathPoint>> {
+    let player = player.to_string();
+    let mut points = Vec::new();
+    for line in BufReader::new(GzDecoder::new(gz_movement)).lines() {
+        let line = line?;
+        let Ok(v) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if v.get("uuid").and_then(Value::as_str) != Some(player.as_str()) {
+            continue;
+        }
+        let Some(ts) = v.get("ts").and_then(Value::as_i64) else {
+            continue;
+        };
+        if ts < from_ms || ts > to_ms {
+            continue;
+        }
+        let coord = |k: &str| v.get(k).and_then(Value::as_f64);
+        let (Some(x), Some(y), Some(z)) = (coord("x"), coord("y"), coord("z")) else {
+            continue;
+        };
+        points.push(PathPoint {
+            ts,
+            x,
+            y,
+            z,
+            on_ground: v.get("on_ground").and_then(Value::as_bool),
+            world: v.get("world").and_then(Value::as_str).map(str::to_string),
+            segment_start: false,
+        });
+    }
+    Ok(points)
+}
+
+/// Sort by time and mark where the path is discontinuous.
+pub fn mark_segments(points: &mut [PathPoint]) {
+    points.sort_by_key(|p| p.ts);
+    for i in 1..points.len() {
+        let (prev, cur) = (&points[i - 1], &points[i]);
+        let dist =
+            ((cur.x - prev.x).powi(2) + (cur.y - prev.y).powi(2) + (cur.z - prev.z).powi(2)).sqrt();
+        let world_changed = matches!((&prev.world, &cur.world), (Some(a), Some(b)) if a != b);
+        points[i].segment_start =
+            dist > TELEPORT_BLOCKS || world_changed || cur.ts - prev.ts > GAP_MS;
+    }
+}
+
+/// The points at the kept indices; a dropped segment start moves to the next kept point.
+fn select(points: &[PathPoint], keep: impl Fn(usize) -> bool) -> Vec<PathPoint> {
+    let mut out: Vec<PathPoint> = Vec::new();
+    let mut pending_break = false;
+    for (i, p) in points.iter().enumerate() {
+        pending_break |= p.segment_start;
+        if !keep(i) {
+            continue;
+        }
+        let mut p = p.clone();
+        p.segment_start = pending_break && !out.is_empty();
+        pending_break = false;
+        out.push(p);
+    }
+    out
+}
+
+/// At most `max` points: the first and last, both ends of every segment boundary, and evenly
+/// spaced points in between. With more boundaries than fit, every point is evenly spaced.
+pub fn downsample(points: &[PathPoint], max: usize) -> Vec<PathPoint> {
+    let n = points.len();
+    if n <= max {
+        return points.to_vec();
+    }
+    let max = max.max(2);
+    let boundary = |i: usize| {
+        i == 0
+            || i == n - 1
+            || points[i].segment_start
+            || points.get(i + 1).is_some_and(|p| p.segment_start)
+    };
+    let boundaries = (0..n).filter(|&i| boundary(i)).count();
+    if boundaries < max {
+        let stride = (n - boundaries).div_ceil(max - boundaries).max(1);
+        let picked = select(points, |i| boundary(i) || i % stride == 0);
+        if picked.len() <= max {
+            return picked;
+        }
+    }
+    // Evenly spaced indices, first and last included.
+    let step = (n - 1) as f64 / (max - 1) as f64;
+    let indices: std::collections::HashSet<usize> = (0..max)
+        .map(|k| (k as f64 * step).round() as usize)
+        .collect();
+    select(points, |i| indices.contains(&i))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MovementPath {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Positions found before downsampling.
+    pub points_total: usize,
+    /// Batches in the window were left unread (more than the per-request cap).
+    pub truncated: bool,
+    pub points: Vec<PathPoint>,
+}
+
+fn db_error(what: &'static str) -> impl Fn(sqlx::Error) -> ApiError {
+    move |e| {
+        tracing::error!("movement path {} failed: {:?}", what, e);
+        ApiError::Internal
+    }
+}
+
+/// Reconstruct the player's path between `from` and `to` (at most [`MAX_WINDOW_MINUTES`]).
+pub async fn reconstruct(
+    state: &AppState,
+    server_id: &str,
+    player_uuid: Uuid,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    max_points: usize,
+) -> Result<MovementPath, ApiError> {
+    // Batches of the plugin sessions the player was on, received in the window. Records can
+    // arrive up to a flush interval late, hence the slack after `to`.
+    let batches: Vec<(Uuid, String, Option<i64>)> = sqlx::query_as(
+        r#"
+        select b.id, b.s3_key, b.clock_skew_ms
+        from public.batch_index b
+        where b.server_id = $1
+          and b.received_at >= $3 and b.received_at <= $4 + interval '30 seconds'
+          and not b.quarantined
+          and b.session_id in (
+              select ps.plugin_session_id from public.player_sessions ps
+              where ps.server_id = $1 and ps.player_uuid = $2
+                and ps.started_at <= $4 and ps.last_seen_at >= $3
+          )
+        order by b.received_at asc
+        limit $5
+        "#,
+    )
+    .bind(server_id)
+    .bind(player_uuid)
+    .bind(from)
+    .bind(to)
+    .bind(MAX_BATCHES + 1)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error("batch lookup"))?;
+    let truncated = batches.len() as i64 > MAX_BATCHES;
+
+    let (from_ms, to_ms) = (from.timestamp_millis(), to.timestamp_millis());
+    let mut points = Vec::new();
+    for (batch_id, s3_key, skew) in batches.into_iter().take(MAX_BATCHES as usize) {
+        let raw = match state.object_store.get_batch(&s3_key).await {
+            Ok(raw) => raw,
+            Err(e) => {
+                tracing::warn!(batch_id = %batch_id, "movement path batch unavailable: {:?}", e);
+                continue;
+            }
+        };
+        let res = tokio::task::spawn_blocking(move || {
+            let movement = transforms::apply_transform_with(TRANSFORM, &raw, skew)?;
+            points_from(&movement, player_uuid, from_ms, to_ms)
+        })
+        .await;
+        match res {
+            Ok(Ok(batch_points)) => points.extend(batch_points),
+            Ok(Err(e)) => {
+                tracing::warn!(batch_id = %batch_id, "movement path transform failed: {:?}", e)
+            }
+            Err(e) => tracing::error!("movement path task failed: {:?}", e),
+        }
+    }
+
+    mark_segments(&mut points);
+    Ok(MovementPath {
+        from,
+        to,
+        points_total: points.len(),
+        truncated,
+        points: downsample(&points, max_points),
+    })
+}
@@ -0,0 +1,51 @@
+//! Parsing batch records, one NDJSON line at a time.
+//!
+//! For large batches parsing each line dominates transform time. Built with the `simd-json`
+//! feature, [`RecordParser`] parses lines with simd-json (reusing its scratch buffers across
+//! lines) and falls back to `serde_json` for any line simd-json rejects, so both builds accept
+//! the same lines and produce the same values. Without the feature it is plain `serde_json`.
+
+use serde_json::Value;
+
+/// Which parser [`RecordParser`] uses first: "simd-json" or "serde_json".
+pub const BACKEND: &str = if cfg!(feature = "simd-json") {
+    "simd-json"
+} else {
+    "serde_json"
+};
+
+/// Parses the record lines of one batch.
+#[derive(Default)]
+pub struct RecordParser {
+    #[cfg(feature = "simd-json")]
+    scratch: Vec<u8>,
+    #[cfg(feature = "simd-json")]
+    buffers: simd_json::Buffers,
+}
+
+impl RecordParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The line as JSON, or None when it isn't valid JSON.
+    pub fn parse(&mut self, line: &str) -> Option<Value> {
+        #[cfg(feature = "simd-json")]
+        {
+            // simd-json parses in place, so it gets a copy of the line.
+            self.scratch.clear();
+            self.scratch.extend_from_slice(line.as_bytes());
+            if let Ok(v) =
+                simd_json::serde::from_slice_with_buffers(&mut self.scratch, &mut self.buffers)
+            {
+                return Some(v);
+            }
+        }
+        serde_json::from_str(line).ok()
+    }
+}
+
+/// Parse a single line (use a [`RecordParser`] for a whole batch).
+pub fn parse_record(line: &str) -> Option<Value> {
+    RecordParser::new().parse(line)
+}
@@ -0,0 +1,185 @@
+//! Moderator notes on players and comments on findings (`moderator_notes`).
+//!
+//! Staff leave free-text notes on a player (`finding_id` null) or comment on one finding; both
+//! carry their author and time and are listed oldest first, like a thread. A finding comment
+//! also records the finding's player, so erasing a player (`privacy`) removes every note about
+//! them, and deleting the finding removes its comments. Player detail lists the player's notes,
+//! finding detail the finding's comments.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const MAX_BODY_CHARS: usize = 4000;
+pub const MAX_AUTHOR_CHARS: usize = 64;
+/// Notes returned with a player or finding.
+pub const MAX_LISTED: i64 = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ModeratorNote {
+    pub id: Uuid,
+    pub player_uuid: Option<Uuid>,
+    /// Set for comments on a finding.
+    pub finding_id: Option<Uuid>,
+    pub author: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Trimmed author and body, or why they are refused.
+pub fn validate(author: &str, body: &str) -> Result<(String, String), String> {
+    let author = author.trim();
+    let body = body.trim();
+    if author.is_empty() {
+        return Err("author must not be empty".to_string());
+    }
+    if author.chars().count() > MAX_AUTHOR_CHARS {
+        return Err(format!(
+            "author longer than {} characters",
+            MAX_AUTHOR_CHARS
+        ));
+    }
+    if body.is_empty() {
+        return Err("note must not be empty".to_string());
+    }
+    if body.chars().count() > MAX_BODY_CHARS {
+        return Err(format!("note longer than {} characters", MAX_BODY_CHARS));
+    }
+    Ok((author.to_string(), body.to_string()))
+}
+
+type NoteRow = (
+    Uuid,
+    Option<Uuid>,
+    Option<Uuid>,
+    String,
+    String,
+    DateTime<Utc>,
+);
+
+fn note(row: NoteRow) -> ModeratorNote {
+    let (id, player_uuid, finding_id, author, body, created_at) = row;
+    ModeratorNote {
+        id,
+        player_uuid,
+        finding_id,
+        author,
+        body,
+        created_at,
+    }
+}
+
+/// Notes left on the player (not comments on their findings), oldest first.
+pub async fn player_notes(
+    db: &PgPool,
+    server_id: &str,
+    player_uuid: Uuid,
+) -> Result<Vec<ModeratorNote>, sqlx::Error> {
+    let rows: Vec<NoteRow> = sqlx::query_as(
+        r#"
+        select id, player_uuid, finding_id, author, body, created_at
+        from (
+            select * from public.moderator_notes
+            where server_id = $1 and player_uuid = $2 and finding_id is null
+            order by created_at desc
+            limit $3
+        ) n
+        order by created_at asc
+        "#,
+    )
+    .bind(server_id)
+    .bind(player_uuid)
+    .bind(MAX_LISTED)
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().map(note).collect())
+}
+
+/// Comments on a finding, oldest first.
+pub async fn finding_comments(
+    db: &PgPool,
+    server_id: &str,
+    finding_id: Uuid,
+) -> Result<Vec<ModeratorNote>, sqlx::Error> {
+    let rows: Vec<NoteRow> = sqlx::query_as(
+        r#"
+        select id, player_uuid, finding_id, author, body, created_at
+        from (
+            select * from public.moderator_notes
+            where server_id = $1 and finding_id = $2
+            order by created_at desc
+            limit $3
+        ) n
+        order by created_at asc
+        "#,
+    )
+    .bind(server_id)
+    .bind(finding_id)
+    .bind(MAX_LISTED)
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().map(note).collect())
+}
+
+/// Store a note on a player.
+pub async fn add_player_note(
+    db: &PgPool,
+    server_id: &str,
+    player_uuid: Uuid,
+    author: &str,
+    body: &str,
+) -> Result<ModeratorNote, sqlx::Error> {
+    let row: NoteRow = sqlx::query_as(
+        r#"
+        insert into public.moderator_notes (server_id, player_uuid, author, body)
+        values ($1, $2, $3, $4)
+        returning id, player_uuid, finding_id, author, body, created_at
+        "#,
+    )
+    .bind(server_id)
+    .bind(player_uuid)
+    .bind(author)
+    .bind(body)
+    .fetch_one(db)
+    .await?;
+    Ok(note(row))
+}
+
+/// Store a comment on a finding of the server; None when there is no such finding.
+pub async fn add_finding_comment(
+    db: &PgPool,
+    server_id: &str,
+    finding_id: Uuid,
+    author: &str,
+    body: &str,
+) -> Result<Option<ModeratorNote>, sqlx::Error> {
+    let row: Option<NoteRow> = sqlx::query_as(
+        r#"
+        insert into public.moderator_notes (server_id, player_uuid, finding_id, author, body)
+        select server_id, player_uuid, id, $3, $4
+        from public.findings
+        where id = $2 and server_id = $1
+        returning id, player_uuid, finding_id, author, body, created_at
+        "#,
+    )
+    .bind(server_id)
+    .bind(finding_id)
+    .bind(author)
+    .bind(body)
+    .fetch_optional(db)
+    .await?;
+    Ok(row.map(note))
+}
+
+/// Delete a note or comment; false when the server has no such note.
+pub async fn delete(db: &PgPool, server_id: &str, note_id: Uuid) -> Result<bool, sqlx::Error> {
+    let deleted =
+        sqlx::query("delete from public.moderator_notes where id = $1 and server_id = $2")
+            .bind(note_id)
+            .bind(server_id)
+            .execute(db)
+            .await?;
+    Ok(deleted.rows_affected() > 0)
+}
@@ -1,4 +1,5 @@
 use chrono::{Duration, Utc};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 use crate::{s3::ObjectStore, AppState};
@@ -33,15 +34,29 @@ pub async fn cleanup_tick(state: AppState) {
     // 1) Object store cleanup (raw batches)
     let mut stats = match &state.object_store {
         ObjectStore::Local { root } => {
-            cleanup_local_store(
-                root.clone(),
-                object_cutoff,
-                state.object_store_cleanup_dry_run,
-            )
-            .await
+            // Batches with watchlisted players are retained past the TTL.
+            let retained: Result<Vec<(String,)>, _> =
+                sqlx::query_as("select s3_key from public.batch_index where retain_until > now()")
+                    .fetch_all(&state.db)
+                    .await;
+            match retained {
+                Ok(keys) => {
+                    let retained: HashSet<PathBuf> =
+                        keys.into_iter().map(|(k,)| root.join(k)).collect();
+                    cleanup_local_store(
+                        root.clone(),
+                        object_cutoff,
+                        state.object_store_cleanup_dry_run,
+                        retained,
+                    )
+                    .await
+                }
+                Err(e) => Err(anyhow::anyhow!("retained batch lookup failed: {}", e)),
+            }
         }
         ObjectStore::S3 { .. } => {
-            // For S3/R2/etc. the preferred approach is bucket lifecycle rules.
+            // For S3/R2/etc. the preferred approach is bucket lifecycle rules. Those should
+            // exclude objects tagged `aac-retain=watchlist` (see watchlist.rs).
             tracing::info!(
                 dry_run = state.object_store_cleanup_dry_run,
                 ttl_days = state.object_store_ttl_days,
@@ -97,7 +112,7 @@ async fn cleanup_batch_index(
 ) -> anyhow::Result<u64> {
     if dry_run {
         let (count,): (i64,) =
-            sqlx::query_as("select count(*) from public.batch_index where received_at < $1")
+            sqlx::query_as("select count(*) from public.batch_index where received_at < $1 and (retain_until is null or retain_until <= now())")
                 .bind(cutoff)
                 .fetch_one(&state.db)
                 .await
@@ -105,7 +120,7 @@ async fn cleanup_batch_index(
         return Ok(count.max(0) as u64);
     }
 
-    let res = sqlx::query("delete from public.batch_index where received_at < $1")
+    let res = sqlx::query("delete from public.batch_index where received_at < $1 and (retain_until is null or retain_until <= now())")
         .bind(cutoff)
         .execute(&state.db)
         .await?;
@@ -116,15 +131,19 @@ async fn cleanup_local_store(
     root: PathBuf,
     cutoff: chrono::DateTime<chrono::Utc>,
     dry_run: bool,
+    retained: HashSet<PathBuf>,
 ) -> anyhow::Result<CleanupStats> {
-    tokio::task::spawn_blocking(move || cleanup_local_store_blocking(&root, cutoff, dry_run))
-        .await?
+    tokio::task::spawn_blocking(move || {
+        cleanup_local_store_blocking(&root, cutoff, dry_run, &retained)
+    })
+    .await?
 }
 
 fn cleanup_local_store_blocking(
     root: &Path,
     cutoff: chrono::DateTime<chrono::Utc>,
     dry_run: bool,
+    retained: &HashSet<PathBuf>,
 ) -> anyhow::Result<CleanupStats> {
     let mut stats = CleanupStats::default();
     let events_root = root.join("events");
@@ -136,6 +155,7 @@ fn cleanup_local_store_blocking(
         dir: &Path,
         cutoff: chrono::DateTime<chrono::Utc>,
         dry_run: bool,
+        retained: &HashSet<PathBuf>,
         stats: &mut CleanupStats,
     ) -> anyhow::Result<bool> {
         let mut is_empty = true;
@@ -145,7 +165,7 @@ fn cleanup_local_store_blocking(
             let meta = entry.metadata()?;
 
             if meta.is_dir() {
-                let child_empty = recurse_dir(&path, cutoff, dry_run, stats)?;
+                let child_empty = recurse_dir(&path, cutoff, dry_run, retained, stats)?;
                 if child_empty && !dry_run {
                     // Best-effort remove empty dir
                     if std::fs::remove_dir(&path).is_ok() {
@@ -164,10 +184,10 @@ fn cleanup_local_store_blocking(
                 // If we can't read mtime, use current time so the file is kept (not deleted).
                 let modified_dt: chrono::DateTime<chrono::Utc> = meta
                     .modified()
-                    .map(|m| chrono::DateTime::<chrono::Utc>::from(m))
+                    .map(chrono::DateTime::<chrono::Utc>::from)
                     .unwrap_or_else(|_| chrono::Utc::now());
 
-                if modified_dt < cutoff {
+                if modified_dt < cutoff && !retained.contains(&path) {
                     let len = meta.len();
                     if !dry_run {
                         if std::fs::remove_file(&path).is_ok() {
@@ -189,6 +209,6 @@ fn cleanup_local_store_blocking(
     }
 
     // Recurse and attempt to prune empty directories.
-    let _ = recurse_dir(&events_root, cutoff, dry_run, &mut stats)?;
+    let _ = recurse_dir(&events_root, cutoff, dry_run, retained, &mut stats)?;
     Ok(stats)
 }
@@ -0,0 +1,462 @@
+//! OpenAPI description of the HTTP API, generated from the `#[utoipa::path]` annotations on
+//! the route handlers. Served at `GET /openapi.json` (see `routes::docs`).
+//!
+//! Adding a route: annotate the handler, derive `ToSchema` on its request/response types and
+//! list both below.
+
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::routes::{
+    admin, batches, callbacks, capture_config, cases, catalog, check_docs, check_thresholds,
+    confidence_filter, dashboard, dispatch_stats, evidence_bundles, exemptions, finding_latency,
+    findings_import, handshake, health, heartbeat, ingest, ingest_anomalies, ingest_filters,
+    module_canary, module_maintenance, module_usage, modules, notes, observations, organizations,
+    player_path, plugin_findings, privacy, proxy_groups, punishments, reports, reputation,
+    sampling, server_deletion, server_export, sessions, severity_overrides, shadow, status,
+    wasm_modules, watchlist,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "AsyncAnticheat API",
+        description = "Ingestion, module callback, dashboard and admin endpoints."
+    ),
+    paths(
+        health::health,
+        status::public_status,
+        handshake::handshake,
+        heartbeat::heartbeat,
+        ingest::ingest,
+        observations::create_observation,
+        observations::end_observation,
+        reputation::get_reputation,
+        plugin_findings::get_plugin_findings,
+        capture_config::get_plugin_config,
+        capture_config::get_capture_config,
+        capture_config::update_capture_config,
+        ingest_filters::get_ingest_filters,
+        ingest_filters::update_ingest_filters,
+        check_thresholds::list_check_thresholds,
+        check_thresholds::update_check_threshold,
+        ingest_anomalies::list_ingest_anomalies,
+        severity_overrides::list_severity_overrides,
+        severity_overrides::set_severity_override,
+        severity_overrides::delete_severity_override,
+        confidence_filter::get_confidence_filter,
+        confidence_filter::update_confidence_filter,
+        punishments::get_punishment_export,
+        punishments::update_punishment_export,
+        punishments::list_punishments,
+        reports::get_report_schedule,
+        reports::update_report_schedule,
+        reports::get_weekly_report,
+        reports::send_report,
+        evidence_bundles::create_evidence_bundle,
+        evidence_bundles::download_evidence_bundle,
+        batches::get_batch_packets,
+        batches::list_batches,
+        player_path::get_player_path,
+        catalog::get_catalog,
+        check_docs::get_check_doc,
+        modules::upsert_module,
+        modules::list_modules,
+        callbacks::post_findings,
+        callbacks::dispatch_complete,
+        callbacks::get_player_state,
+        callbacks::set_player_state,
+        callbacks::batch_get_player_states,
+        callbacks::batch_set_player_states,
+        dashboard::get_servers,
+        dashboard::get_stats,
+        dashboard::get_findings,
+        dashboard::update_finding_status,
+        dashboard::get_detector_quality,
+        dashboard::get_players,
+        dashboard::get_player,
+        dashboard::get_finding,
+        notes::list_player_notes,
+        notes::add_player_note,
+        notes::add_finding_comment,
+        notes::delete_note,
+        cases::list_cases,
+        cases::create_case,
+        cases::get_case,
+        cases::update_case,
+        cases::delete_case,
+        cases::add_case_item,
+        cases::remove_case_item,
+        cases::add_case_action,
+        cases::close_case,
+        dashboard::get_related_players,
+        dashboard::get_modules,
+        dashboard::create_module,
+        dashboard::toggle_module,
+        dashboard::get_status,
+        dashboard::get_module_conformance,
+        dashboard::get_module_audit,
+        dispatch_stats::get_dispatch_stats,
+        dispatch_stats::list_dispatches,
+        finding_latency::get_finding_latency,
+        module_canary::get_canary_report,
+        module_usage::get_module_usage,
+        shadow::set_module_mode,
+        shadow::list_shadow_findings,
+        module_maintenance::get_module_maintenance,
+        module_maintenance::put_module_maintenance,
+        module_maintenance::pause_module,
+        module_maintenance::resume_module,
+        server_deletion::delete_server,
+        server_deletion::restore_server,
+        catalog::enable_catalog_module,
+        wasm_modules::upload_wasm_module,
+        reputation::set_reputation_opt_in,
+        privacy::set_privacy_settings,
+        sampling::get_sampling_settings,
+        sampling::set_sampling_settings,
+        findings_import::import_findings,
+        server_export::create_server_export,
+        server_export::get_server_export,
+        server_export::download_server_export,
+        exemptions::list_exemptions,
+        exemptions::create_exemption,
+        exemptions::delete_exemption,
+        organizations::list_organizations,
+        organizations::create_organization,
+        organizations::get_organization,
+        organizations::attach_server,
+        organizations::detach_server,
+        organizations::set_member,
+        organizations::remove_member,
+        organizations::set_webhook,
+        organizations::list_exemptions,
+        organizations::create_exemption,
+        organizations::delete_exemption,
+        organizations::get_findings,
+        proxy_groups::list_proxy_groups,
+        proxy_groups::get_proxy_group,
+        proxy_groups::get_findings,
+        proxy_groups::get_players,
+        proxy_groups::get_player_sessions,
+        sessions::list_sessions,
+        watchlist::list_watchlist,
+        watchlist::add_to_watchlist,
+        watchlist::remove_from_watchlist,
+        admin::run_module_conformance,
+        admin::replay_observation,
+        admin::get_replay,
+        admin::list_replicas,
+        admin::create_reprocess_job,
+        admin::get_reprocess_job,
+        admin::cancel_reprocess_job,
+        admin::simulate,
+        admin::get_simulation,
+        admin::purge_server,
+        admin::get_server_purge,
+        admin::delete_player,
+        admin::get_player_deletion,
+        admin::list_task_leases,
+        admin::get_top_talkers,
+        admin::get_transform_pool,
+    ),
+    components(schemas(
+        crate::error::ErrorBody,
+        crate::pending_actions::PendingAction,
+        crate::reputation::PlayerReputation,
+        crate::builtin_modules::BuiltinTier,
+        crate::builtin_modules::BuiltinModuleInfo,
+        crate::supervisor::ProcessState,
+        crate::supervisor::SupervisedStatus,
+        crate::conformance::ConformanceCheck,
+        crate::conformance::ConformanceReport,
+        crate::replay::ReplayStarted,
+        crate::replay::ReplayDetectorSummary,
+        crate::replay::ReplayReport,
+        crate::reprocess::ReprocessJob,
+        crate::simulation::FiredCheck,
+        crate::simulation::ScenarioResult,
+        crate::simulation::SimulationReport,
+        health::HealthResponse,
+        crate::service_status::PublicStatusResponse,
+        crate::service_status::ComponentStatus,
+        crate::service_status::IngestStatus,
+        crate::service_status::DispatchStatus,
+        crate::service_status::TaskStatus,
+        handshake::HandshakeResponse,
+        heartbeat::HeartbeatRequest,
+        crate::plugin_version::PluginUpdate,
+        ingest::UpdateRequiredResponse,
+        heartbeat::HeartbeatResponse,
+        ingest::IngestResponse,
+        crate::ingest_hints::IngestHints,
+        crate::ingest_hints::Backlog,
+        ingest::WaitingForRegistrationResponse,
+        ingest::ServerDeletedResponse,
+        observations::CreateObservation,
+        observations::CreateObservationResponse,
+        observations::PluginUpdateObservation,
+        observations::UpdateObservationResponse,
+        reputation::ReputationResponse,
+        plugin_findings::PluginFinding,
+        plugin_findings::PluginFindingsResponse,
+        crate::capture_config::CaptureConfig,
+        capture_config::CaptureConfigResponse,
+        capture_config::UpdateCaptureConfigRequest,
+        crate::ingest_filters::IngestFilterRule,
+        crate::ingest_filters::IngestFilters,
+        ingest_filters::IngestFiltersResponse,
+        ingest_filters::UpdateIngestFiltersRequest,
+        crate::checks::ThresholdOverride,
+        crate::checks::ProtocolThreshold,
+        check_thresholds::CheckThreshold,
+        check_thresholds::CheckThresholdsResponse,
+        crate::ingest_anomalies::IngestAnomaly,
+        ingest_anomalies::IngestAnomaliesResponse,
+        crate::severity_overrides::SeverityOverride,
+        severity_overrides::SeverityOverridesResponse,
+        severity_overrides::SetSeverityOverrideRequest,
+        severity_overrides::DeleteSeverityOverrideResponse,
+        confidence_filter::ConfidenceFilter,
+        punishments::PunishmentExportSettings,
+        punishments::PunishmentsResponse,
+        crate::punishments::PunishmentAction,
+        reports::ReportScheduleSettings,
+        reports::SendReportResponse,
+        crate::reports::WeeklyReport,
+        crate::reports::DetectorSummary,
+        crate::reports::PlayerSummary,
+        crate::reports::ModuleUptime,
+        crate::reports::IngestVolume,
+        crate::reports::Delivery,
+        crate::evidence_bundle::EvidenceBundle,
+        evidence_bundles::EvidenceBundleResponse,
+        player_path::PlayerPathResponse,
+        crate::movement_path::MovementPath,
+        crate::movement_path::PathPoint,
+        reputation::ReputationOptInRequest,
+        reputation::ReputationOptInResponse,
+        privacy::PrivacySettingsRequest,
+        privacy::PrivacySettingsResponse,
+        sampling::SamplingBatchCounts,
+        sampling::SamplingSettingsRequest,
+        sampling::SamplingSettingsResponse,
+        findings_import::FindingsImportRequest,
+        findings_import::FindingsImportResponse,
+        crate::findings_import::ImportSummary,
+        server_export::ServerExportRequest,
+        server_export::ServerExportResponse,
+        crate::server_export::ServerExport,
+        catalog::CatalogEntry,
+        check_docs::CheckDoc,
+        check_docs::CheckParameter,
+        check_docs::CheckDocResponse,
+        catalog::CatalogResponse,
+        catalog::EnableCatalogModuleRequest,
+        catalog::EnableCatalogModuleResponse,
+        modules::UpsertModuleRequest,
+        modules::ServerModule,
+        crate::module_failover::EndpointHealth,
+        callbacks::FindingIn,
+        callbacks::PostFindingsRequest,
+        callbacks::PostFindingsResponse,
+        callbacks::DispatchCompleteRequest,
+        callbacks::DispatchCompleteResponse,
+        callbacks::GetPlayerStateRequest,
+        callbacks::PlayerStateResponse,
+        callbacks::SetPlayerStateRequest,
+        callbacks::SetPlayerStateResponse,
+        callbacks::BatchGetPlayerStatesRequest,
+        callbacks::BatchPlayerState,
+        callbacks::BatchGetPlayerStatesResponse,
+        callbacks::BatchSetPlayerStatesRequest,
+        callbacks::PlayerStateEntry,
+        callbacks::BatchSetPlayerStatesResponse,
+        dashboard::DashboardStats,
+        dashboard::WorldStats,
+        dashboard::DashboardStatsResponse,
+        dashboard::FindingItem,
+        dashboard::FindingsResponse,
+        dashboard::PlayerItem,
+        dashboard::ActivePlayer,
+        dashboard::PlayersResponse,
+        dashboard::PlayerClientSession,
+        dashboard::PlayerDetailResponse,
+        dashboard::FindingDetailResponse,
+        crate::notes::ModeratorNote,
+        notes::AddNoteRequest,
+        notes::NoteResponse,
+        notes::NotesResponse,
+        notes::DeleteNoteResponse,
+        crate::cases::CaseSummary,
+        crate::cases::ItemKind,
+        cases::Case,
+        cases::CasesResponse,
+        cases::CreateCaseRequest,
+        cases::CaseResponse,
+        cases::CaseAttachment,
+        cases::CaseAction,
+        cases::CaseDetailResponse,
+        cases::UpdateCaseRequest,
+        cases::DeleteCaseResponse,
+        cases::AddCaseItemRequest,
+        cases::AddCaseActionRequest,
+        cases::CloseCaseRequest,
+        dashboard::RelatedPlayer,
+        dashboard::RelatedPlayersResponse,
+        dashboard::ModuleItem,
+        dashboard::ModulesResponse,
+        dashboard::ToggleModuleRequest,
+        dashboard::ToggleModuleResponse,
+        dashboard::CreateModuleRequest,
+        dashboard::CreateModuleResponse,
+        dashboard::ServerInfo,
+        dashboard::ServersResponse,
+        dashboard::ConnectionStatus,
+        dashboard::StatusResponse,
+        dashboard::ServerPerformance,
+        crate::batch_sequence::IngestIntegrity,
+        dashboard::HeartbeatSample,
+        dashboard::ModuleAuditEntry,
+        dashboard::ModuleAuditResponse,
+        dashboard::ConformanceRunEntry,
+        dashboard::ConformanceRunsResponse,
+        dispatch_stats::DispatchStatsResponse,
+        dispatch_stats::DispatchHistoryResponse,
+        dispatch_stats::DispatchItem,
+        batches::BatchesResponse,
+        batches::BatchItem,
+        crate::dispatch_stats::DispatchStatsHour,
+        crate::dispatch_stats::DispatchStatsTotals,
+        finding_latency::FindingLatencyResponse,
+        finding_latency::DetectorLatency,
+        crate::finding_latency::LatencyDay,
+        crate::finding_latency::LatencySummary,
+        module_canary::CanaryReportResponse,
+        crate::module_canary::VariantDay,
+        crate::module_canary::VariantTotals,
+        module_usage::ModuleUsageResponse,
+        crate::module_usage::ModuleUsage,
+        crate::module_usage::UsageDay,
+        shadow::ModuleModeRequest,
+        shadow::ModuleModeResponse,
+        shadow::ShadowFindingsResponse,
+        crate::shadow::ShadowFinding,
+        crate::shadow::ShadowDetectorSummary,
+        module_maintenance::ModuleMaintenanceResponse,
+        module_maintenance::PauseModuleRequest,
+        module_maintenance::MaintenanceWindowsRequest,
+        crate::module_maintenance::MaintenanceWindow,
+        crate::module_maintenance::Suspension,
+        server_deletion::ServerDeletionResponse,
+        dashboard::UpdateFindingStatusRequest,
+        dashboard::UpdateFindingStatusResponse,
+        dashboard::DetectorQuality,
+        dashboard::DetectorQualityResponse,
+        wasm_modules::WasmModuleInfo,
+        wasm_modules::UploadWasmModuleResponse,
+        exemptions::ExemptionItem,
+        exemptions::ExemptionsResponse,
+        exemptions::CreateExemptionRequest,
+        exemptions::CreateExemptionResponse,
+        exemptions::DeleteExemptionResponse,
+        organizations::OrganizationSummary,
+        organizations::OrganizationsResponse,
+        organizations::CreateOrganizationRequest,
+        organizations::CreateOrganizationResponse,
+        organizations::OrganizationServer,
+        organizations::OrganizationMember,
+        organizations::OrganizationDetail,
+        organizations::OrganizationResponse,
+        organizations::OrganizationOkResponse,
+        organizations::AttachServerRequest,
+        organizations::SetMemberRequest,
+        organizations::OrganizationWebhookRequest,
+        organizations::OrganizationExemptionsResponse,
+        organizations::OrganizationFinding,
+        organizations::OrganizationFindingsResponse,
+        proxy_groups::ProxyGroupSummary,
+        proxy_groups::ProxyGroupsResponse,
+        proxy_groups::ProxyGroupServer,
+        proxy_groups::ProxyGroupResponse,
+        proxy_groups::ProxyGroupFinding,
+        proxy_groups::ProxyGroupFindingsResponse,
+        proxy_groups::ProxySession,
+        proxy_groups::ProxyGroupPlayer,
+        proxy_groups::ProxyGroupPlayersResponse,
+        proxy_groups::ProxySessionsResponse,
+        sessions::PlayerSession,
+        sessions::SessionsResponse,
+        watchlist::WatchlistEntry,
+        watchlist::WatchlistResponse,
+        watchlist::AddToWatchlistRequest,
+        watchlist::WatchlistUpdateResponse,
+        admin::ConformanceRequest,
+        admin::ConformanceResponse,
+        admin::ReplayRequest,
+        admin::ReplayStartedResponse,
+        admin::ReplayReportResponse,
+        admin::ReplicasResponse,
+        crate::replica_shards::Replica,
+        admin::ReprocessRequest,
+        admin::ReprocessJobResponse,
+        admin::SimulateRequest,
+        admin::SimulationResponse,
+        admin::PurgeRequest,
+        admin::PurgeJobResponse,
+        crate::server_deletion::PurgeJob,
+        admin::DeletePlayerRequest,
+        admin::PlayerDeletionResponse,
+        admin::TransformPoolResponse,
+        crate::transform_pool::PoolSnapshot,
+        admin::TaskLeasesResponse,
+        admin::TopTalkersResponse,
+        admin::TopTalker,
+        crate::traffic_stats::ServerTraffic,
+        crate::traffic_stats::EndpointTraffic,
+        crate::traffic_stats::Traffic,
+        crate::traffic_stats::RankBy,
+        crate::task_lock::TaskLease,
+        crate::privacy::PlayerDeletionJob,
+    )),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "plugin", description = "Called by the server plugin (per-server token)"),
+        (name = "modules", description = "Module registration and catalog"),
+        (name = "callbacks", description = "Called by detection modules (MODULE_CALLBACK_TOKEN)"),
+        (name = "dashboard", description = "Dashboard API (DASHBOARD_TOKEN)"),
+        (name = "admin", description = "Operator endpoints (ADMIN_TOKEN)"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Bearer schemes referenced by the `security(...)` entries on the handlers.
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        for (name, description) in [
+            ("server_token", "Per-server plugin token (with X-Server-Id)"),
+            ("ingest_token", "INGEST_TOKEN"),
+            ("module_token", "MODULE_CALLBACK_TOKEN"),
+            ("dashboard_token", "DASHBOARD_TOKEN"),
+            ("admin_token", "ADMIN_TOKEN"),
+        ] {
+            components.add_security_scheme(
+                name,
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .description(Some(description))
+                        .build(),
+                ),
+            );
+        }
+    }
+}
+
+/// The generated spec.
+pub fn spec() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
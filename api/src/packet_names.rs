@@ -0,0 +1,318 @@
+//! Packet name normalization.
+//!
+//! The plugin names packets after PacketEvents' `PacketType` constants (`PLAYER_POSITION`,
+//! `INTERACT_ENTITY`, ...), which are the canonical names used by the transforms and checks.
+//! Captures from other sources name the same packets after protocol-wiki titles (1.8:
+//! `PLAYER_POSITION_AND_LOOK`), ProtocolLib constants (1.12: `POSITION_LOOK`, `USE_ENTITY`),
+//! Spigot classes (1.16: `PacketPlayInUseEntity`) or Mojang mappings (1.21: `MOVE_PLAYER_POS`).
+//!
+//! [`ALIASES`] maps those names to canonical ones. Some names only mean the packet for a range
+//! of game versions (before 1.13 `PLAYER` was the ground-status-only movement packet) or in one
+//! direction (a clientbound `POSITION` is a server teleport, not player movement), so entries
+//! carry both. The version comes from the batch metadata's `mc_version`; without one, every
+//! entry applies. Names are compared ignoring case and separators, so `Player Position` and
+//! `player_position` both match `PLAYER_POSITION`.
+
+use crate::plugin_version::Version;
+
+// Serverbound.
+pub const PLAYER_POSITION: &str = "PLAYER_POSITION";
+pub const PLAYER_POSITION_AND_ROTATION: &str = "PLAYER_POSITION_AND_ROTATION";
+pub const PLAYER_ROTATION: &str = "PLAYER_ROTATION";
+/// Ground status only (no position or rotation).
+pub const PLAYER_FLYING: &str = "PLAYER_FLYING";
+pub const INTERACT_ENTITY: &str = "INTERACT_ENTITY";
+pub const PLUGIN_MESSAGE: &str = "PLUGIN_MESSAGE";
+
+// Clientbound.
+pub const SPAWN_ENTITY: &str = "SPAWN_ENTITY";
+pub const SPAWN_LIVING_ENTITY: &str = "SPAWN_LIVING_ENTITY";
+pub const SPAWN_PLAYER: &str = "SPAWN_PLAYER";
+pub const ENTITY_TELEPORT: &str = "ENTITY_TELEPORT";
+pub const ENTITY_RELATIVE_MOVE: &str = "ENTITY_RELATIVE_MOVE";
+pub const ENTITY_RELATIVE_MOVE_AND_ROTATION: &str = "ENTITY_RELATIVE_MOVE_AND_ROTATION";
+pub const ENTITY_ROTATION: &str = "ENTITY_ROTATION";
+pub const DESTROY_ENTITIES: &str = "DESTROY_ENTITIES";
+/// Server-side teleport of the receiving player.
+pub const PLAYER_POSITION_AND_LOOK: &str = "PLAYER_POSITION_AND_LOOK";
+/// Entity event by status byte (before 1.19.4, status 2 and a few others mean "hurt").
+pub const ENTITY_STATUS: &str = "ENTITY_STATUS";
+/// Hurt animation of an entity (1.19.4+).
+pub const HURT_ANIMATION: &str = "HURT_ANIMATION";
+/// An entity took damage (1.19.4+).
+pub const DAMAGE_EVENT: &str = "DAMAGE_EVENT";
+
+/// `ENTITY_STATUS` statuses that play the hurt animation: generic, thorns, drowning, burning,
+/// sweet berry bush and freezing damage.
+pub const HURT_STATUSES: [i64; 6] = [2, 33, 36, 37, 44, 57];
+
+const SERVERBOUND: Option<&str> = Some("serverbound");
+const CLIENTBOUND: Option<&str> = Some("clientbound");
+
+/// A non-canonical packet name, the direction it is used in and the game versions it means
+/// `canonical` in (inclusive; `None` is unbounded).
+#[derive(Debug, Clone, Copy)]
+pub struct PacketAlias {
+    pub alias: &'static str,
+    pub canonical: &'static str,
+    pub dir: Option<&'static str>,
+    pub since: Option<Version>,
+    pub until: Option<Version>,
+}
+
+const fn alias(alias: &'static str, canonical: &'static str) -> PacketAlias {
+    PacketAlias {
+        alias,
+        canonical,
+        dir: None,
+        since: None,
+        until: None,
+    }
+}
+
+impl PacketAlias {
+    const fn dir(mut self, dir: Option<&'static str>) -> Self {
+        self.dir = dir;
+        self
+    }
+
+    const fn since(mut self, major: u32, minor: u32, patch: u32) -> Self {
+        self.since = Some(Version(major, minor, patch));
+        self
+    }
+
+    const fn until(mut self, major: u32, minor: u32, patch: u32) -> Self {
+        self.until = Some(Version(major, minor, patch));
+        self
+    }
+
+    /// Records without a direction are taken as serverbound.
+    fn applies(&self, name: &str, dir: &str, version: Option<Version>) -> bool {
+        let dir_ok = self.dir.is_none_or(|d| {
+            d.eq_ignore_ascii_case(dir) || (dir.is_empty() && self.dir == SERVERBOUND)
+        });
+        let version_ok = version.is_none_or(|v| {
+            self.since.is_none_or(|since| v >= since) && self.until.is_none_or(|until| v <= until)
+        });
+        dir_ok && version_ok && same_name(self.alias, name)
+    }
+}
+
+pub const ALIASES: [PacketAlias; 56] = [
+    // Player movement.
+    alias("POSITION", PLAYER_POSITION).dir(SERVERBOUND),
+    alias("PacketPlayInPosition", PLAYER_POSITION),
+    alias("MOVE_PLAYER_POS", PLAYER_POSITION),
+    alias("SET_PLAYER_POSITION", PLAYER_POSITION).since(1, 19, 3),
+    alias("PLAYER_POSITION_AND_LOOK", PLAYER_POSITION_AND_ROTATION).dir(SERVERBOUND),
+    alias("POSITION_LOOK", PLAYER_POSITION_AND_ROTATION).dir(SERVERBOUND),
+    alias("PacketPlayInPositionLook", PLAYER_POSITION_AND_ROTATION),
+    alias("MOVE_PLAYER_POS_ROT", PLAYER_POSITION_AND_ROTATION),
+    alias(
+        "SET_PLAYER_POSITION_AND_ROTATION",
+        PLAYER_POSITION_AND_ROTATION,
+    )
+    .since(1, 19, 3),
+    alias("PLAYER_LOOK", PLAYER_ROTATION).dir(SERVERBOUND),
+    alias("LOOK", PLAYER_ROTATION).dir(SERVERBOUND),
+    alias("PacketPlayInLook", PLAYER_ROTATION),
+    alias("MOVE_PLAYER_ROT", PLAYER_ROTATION),
+    alias("SET_PLAYER_ROTATION", PLAYER_ROTATION).since(1, 19, 3),
+    alias("PLAYER", PLAYER_FLYING)
+        .dir(SERVERBOUND)
+        .until(1, 12, 2),
+    alias("FLYING", PLAYER_FLYING)
+        .dir(SERVERBOUND)
+        .until(1, 16, 5),
+    alias("PacketPlayInFlying", PLAYER_FLYING).until(1, 16, 5),
+    alias("PLAYER_MOVEMENT", PLAYER_FLYING)
+        .dir(SERVERBOUND)
+        .since(1, 13, 0)
+        .until(1, 16, 5),
+    alias("GROUND", PLAYER_FLYING)
+        .dir(SERVERBOUND)
+        .since(1, 17, 0),
+    alias("MOVE_PLAYER_STATUS_ONLY", PLAYER_FLYING).since(1, 17, 0),
+    alias("SET_PLAYER_ON_GROUND", PLAYER_FLYING)
+        .since(1, 19, 3)
+        .until(1, 21, 1),
+    alias("SET_PLAYER_MOVEMENT_FLAGS", PLAYER_FLYING).since(1, 21, 2),
+    // Combat.
+    alias("USE_ENTITY", INTERACT_ENTITY),
+    alias("PacketPlayInUseEntity", INTERACT_ENTITY),
+    alias("INTERACT", INTERACT_ENTITY).dir(SERVERBOUND),
+    // Plugin channels.
+    alias("CUSTOM_PAYLOAD", PLUGIN_MESSAGE),
+    alias("PacketPlayInCustomPayload", PLUGIN_MESSAGE),
+    // Entity tracking.
+    alias("SPAWN_OBJECT", SPAWN_ENTITY).until(1, 18, 2),
+    alias("ADD_ENTITY", SPAWN_ENTITY),
+    alias("SPAWN_MOB", SPAWN_LIVING_ENTITY).until(1, 15, 2),
+    alias("SPAWN_ENTITY_LIVING", SPAWN_LIVING_ENTITY).until(1, 18, 2),
+    alias("PacketPlayOutSpawnEntityLiving", SPAWN_LIVING_ENTITY).until(1, 18, 2),
+    alias("ADD_MOB", SPAWN_LIVING_ENTITY).until(1, 18, 2),
+    alias("NAMED_ENTITY_SPAWN", SPAWN_PLAYER).until(1, 20, 1),
+    alias("PacketPlayOutNamedEntitySpawn", SPAWN_PLAYER).until(1, 20, 1),
+    alias("ADD_PLAYER", SPAWN_PLAYER).until(1, 20, 1),
+    alias("PacketPlayOutEntityTeleport", ENTITY_TELEPORT),
+    alias("TELEPORT_ENTITY", ENTITY_TELEPORT),
+    alias("ENTITY_POSITION_SYNC", ENTITY_TELEPORT).since(1, 21, 2),
+    alias("REL_ENTITY_MOVE", ENTITY_RELATIVE_MOVE),
+    alias("PacketPlayOutRelEntityMove", ENTITY_RELATIVE_MOVE),
+    alias("ENTITY_POSITION", ENTITY_RELATIVE_MOVE).dir(CLIENTBOUND),
+    alias("MOVE_ENTITY_POS", ENTITY_RELATIVE_MOVE),
+    alias("UPDATE_ENTITY_POSITION", ENTITY_RELATIVE_MOVE).since(1, 19, 3),
+    alias("REL_ENTITY_MOVE_LOOK", ENTITY_RELATIVE_MOVE_AND_ROTATION),
+    alias(
+        "ENTITY_LOOK_AND_RELATIVE_MOVE",
+        ENTITY_RELATIVE_MOVE_AND_ROTATION,
+    )
+    .until(1, 12, 2),
+    alias("MOVE_ENTITY_POS_ROT", ENTITY_RELATIVE_MOVE_AND_ROTATION),
+    alias("ENTITY_DESTROY", DESTROY_ENTITIES),
+    alias("REMOVE_ENTITIES", DESTROY_ENTITIES),
+    // Damage.
+    alias("ENTITY_EVENT", ENTITY_STATUS),
+    alias("PacketPlayOutEntityStatus", ENTITY_STATUS),
+    alias("PacketPlayOutHurtAnimation", HURT_ANIMATION),
+    // Clientbound names that would otherwise read as player movement.
+    alias("POSITION", PLAYER_POSITION_AND_LOOK).dir(CLIENTBOUND),
+    alias("PLAYER_POSITION", PLAYER_POSITION_AND_LOOK)
+        .dir(CLIENTBOUND)
+        .since(1, 21, 2),
+    alias("ENTITY_LOOK", ENTITY_ROTATION).dir(CLIENTBOUND),
+    alias("MOVE_ENTITY_ROT", ENTITY_ROTATION),
+];
+
+const CANONICAL: [&str; 19] = [
+    PLAYER_POSITION,
+    PLAYER_POSITION_AND_ROTATION,
+    PLAYER_ROTATION,
+    PLAYER_FLYING,
+    INTERACT_ENTITY,
+    PLUGIN_MESSAGE,
+    SPAWN_ENTITY,
+    SPAWN_LIVING_ENTITY,
+    SPAWN_PLAYER,
+    ENTITY_TELEPORT,
+    ENTITY_RELATIVE_MOVE,
+    ENTITY_RELATIVE_MOVE_AND_ROTATION,
+    ENTITY_ROTATION,
+    DESTROY_ENTITIES,
+    PLAYER_POSITION_AND_LOOK,
+    ENTITY_STATUS,
+    HURT_ANIMATION,
+    DAMAGE_EVENT,
+    "PLAYER_STATE",
+];
+
+/// Whether two names match ignoring case and separators (`_`, spaces, `$`, ...).
+fn same_name(a: &str, b: &str) -> bool {
+    let squash = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_uppercase())
+            .collect::<String>()
+    };
+    squash(a) == squash(b)
+}
+
+/// Canonical name of a packet seen in `dir` ("serverbound" / "clientbound"; empty when
+/// unknown). Direction-specific aliases win over canonical names (a serverbound
+/// `PLAYER_POSITION_AND_LOOK` is the 1.8 name of `PLAYER_POSITION_AND_ROTATION`). Names without
+/// an alias are returned unchanged.
+pub fn canonical<'a>(name: &'a str, dir: &str, version: Option<Version>) -> &'a str {
+    let name = name.trim();
+    // Spigot nests movement packets (`PacketPlayInFlying$PacketPlayInPosition`).
+    let name = name.rsplit(['$', '.']).next().unwrap_or(name);
+    let directed = ALIASES
+        .iter()
+        .filter(|a| a.dir.is_some())
+        .find(|a| a.applies(name, dir, version));
+    if let Some(a) = directed {
+        return a.canonical;
+    }
+    if let Some(c) = CANONICAL.iter().find(|c| same_name(c, name)) {
+        return c;
+    }
+    ALIASES
+        .iter()
+        .filter(|a| a.dir.is_none())
+        .find(|a| a.applies(name, dir, version))
+        .map_or(name, |a| a.canonical)
+}
+
+/// Canonical name a packet name typed by a user (e.g. a filter) refers to. Unlike [`canonical`]
+/// without a direction, canonical names stand for themselves (`PLAYER_POSITION_AND_LOOK` is the
+/// clientbound teleport); aliases resolve as for an unknown version.
+pub fn lookup_name(name: &str) -> &str {
+    let name = name.trim();
+    match CANONICAL.iter().find(|c| same_name(c, name)) {
+        Some(c) => c,
+        None => canonical(name, "", None),
+    }
+}
+
+/// Game version from a batch metadata line's `mc_version` (e.g. "1.8.8", "1.21").
+pub fn batch_version(meta: &serde_json::Value) -> Option<Version> {
+    meta.get("mc_version")
+        .and_then(|v| v.as_str())
+        .and_then(Version::parse)
+}
+
+/// Rewrite a record's `pkt` to its canonical name.
+pub fn normalize_record(record: &mut serde_json::Value, version: Option<Version>) {
+    let dir = record
+        .get("dir")
+        .and_then(|x| x.as_str())
+        .unwrap_or("")
+        .to_string();
+    let Some(pkt) = record.get_mut("pkt") else {
+        return;
+    };
+    let Some(name) = pkt.as_str() else {
+        return;
+    };
+    let name = canonical(name, &dir, version);
+    if pkt.as_str() != Some(name) {
+        *pkt = serde_json::Value::String(name.to_string());
+    }
+}
+
+/// Serverbound packets that carry the player's position and/or rotation.
+pub fn is_player_movement(pkt: &str) -> bool {
+    matches!(
+        pkt,
+        PLAYER_POSITION | PLAYER_POSITION_AND_ROTATION | PLAYER_ROTATION | PLAYER_FLYING
+    )
+}
+
+/// Clientbound packets that place an entity at an absolute position.
+pub fn is_entity_spawn_or_teleport(pkt: &str) -> bool {
+    matches!(
+        pkt,
+        SPAWN_ENTITY | SPAWN_LIVING_ENTITY | SPAWN_PLAYER | ENTITY_TELEPORT
+    )
+}
+
+/// Clientbound packets that move an entity relative to its last position.
+pub fn is_entity_relative_move(pkt: &str) -> bool {
+    matches!(
+        pkt,
+        ENTITY_RELATIVE_MOVE | ENTITY_RELATIVE_MOVE_AND_ROTATION
+    )
+}
+
+/// The entity a clientbound packet shows getting hurt, if it does.
+pub fn hurt_entity(pkt: &str, fields: &serde_json::Map<String, serde_json::Value>) -> Option<i64> {
+    let entity_id = fields.get("entity_id").and_then(|x| x.as_i64())?;
+    match pkt {
+        HURT_ANIMATION | DAMAGE_EVENT => Some(entity_id),
+        ENTITY_STATUS => fields
+            .get("status")
+            .and_then(|x| x.as_i64())
+            .filter(|status| HURT_STATUSES.contains(status))
+            .map(|_| entity_id),
+        _ => None,
+    }
+}
@@ -0,0 +1,114 @@
+//! Raw packet inspection of stored batches (`GET /dashboard/:server_id/batches/:batch_id/packets`).
+//!
+//! The stored object is decoded server-side and its records are filtered by player and packet
+//! name, so the dashboard never has to download and gunzip a whole batch to show a few lines.
+//! Records are emitted as the plugin sent them (line for line, without re-serializing); the
+//! metadata line is skipped. Packet names are compared after normalization with the batch's
+//! `mc_version` (see `packet_names`), so `?pkt=POSITION_LOOK` finds 1.12 captures as well.
+//!
+//! Pagination is by offset into the matching lines: a page of `limit` lines starting at
+//! `offset`, the next one at `offset + limit`. A page shorter than `limit` is the last one.
+
+use std::io::{BufRead, BufReader};
+
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::packet_names;
+use crate::plugin_version::Version;
+
+pub const DEFAULT_LIMIT: usize = 500;
+pub const MAX_LIMIT: usize = 5000;
+
+/// Which records of a batch to show.
+#[derive(Debug, Clone, Default)]
+pub struct PacketFilter {
+    pub player: Option<Uuid>,
+    /// Canonical packet names (any of); empty for all packets.
+    pub pkt: Vec<String>,
+}
+
+impl PacketFilter {
+    /// `pkt` is a comma-separated list of packet names (canonical or aliases).
+    pub fn new(player: Option<Uuid>, pkt: Option<&str>) -> Self {
+        let pkt = pkt
+            .unwrap_or("")
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| packet_names::lookup_name(p).to_string())
+            .collect();
+        Self { player, pkt }
+    }
+
+    pub fn matches(&self, record: &Value, version: Option<Version>) -> bool {
+        if let Some(player) = self.player {
+            let uuid = record
+                .get("uuid")
+                .and_then(Value::as_str)
+                .and_then(|s| Uuid::parse_str(s).ok());
+            if uuid != Some(player) {
+                return false;
+            }
+        }
+        if self.pkt.is_empty() {
+            return true;
+        }
+        let Some(name) = record.get("pkt").and_then(Value::as_str) else {
+            return false;
+        };
+        let dir = record.get("dir").and_then(Value::as_str).unwrap_or("");
+        let name = packet_names::canonical(name, dir, version);
+        self.pkt.iter().any(|p| p.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Decode a gzipped NDJSON batch and pass the `limit` matching lines after the first `offset`
+/// to `emit`, which returns `false` to stop early (e.g. the client went away). Returns the number
+/// of lines emitted.
+pub fn scan(
+    gz_ndjson: &[u8],
+    filter: &PacketFilter,
+    offset: usize,
+    limit: usize,
+    mut emit: impl FnMut(&str) -> bool,
+) -> anyhow::Result<usize> {
+    let mut reader = BufReader::new(GzDecoder::new(gz_ndjson));
+    let mut buf = String::new();
+    let mut line_no = 0usize;
+    let mut version = None;
+    let mut matched = 0usize;
+    let mut emitted = 0usize;
+
+    while emitted < limit {
+        buf.clear();
+        if reader.read_line(&mut buf)? == 0 {
+            break;
+        }
+        line_no += 1;
+        let line = buf.trim_end_matches(&['\n', '\r'][..]);
+        if line.is_empty() {
+            continue;
+        }
+        let Ok(record) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        if line_no == 1 {
+            version = packet_names::batch_version(&record);
+            continue;
+        }
+        if !filter.matches(&record, version) {
+            continue;
+        }
+        matched += 1;
+        if matched <= offset {
+            continue;
+        }
+        if !emit(line) {
+            break;
+        }
+        emitted += 1;
+    }
+    Ok(emitted)
+}
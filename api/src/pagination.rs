@@ -0,0 +1,121 @@
+//! Keyset (cursor) pagination for list endpoints.
+//!
+//! Lists ordered newest first by a timestamp hand out the position of their last row as an
+//! opaque `next_cursor`; passing it back as `cursor` continues strictly after that row
+//! (`(at, id) < (cursor.at, cursor.id)`, with the id breaking ties). Unlike `offset`, a deep page
+//! costs the same as the first and rows inserted meanwhile do not shift later pages. The GraphQL
+//! connections use the same cursors.
+//!
+//! Lists ranked by a count (players by occurrences) use a [`RankCursor`] the same way. The count
+//! is an aggregate, so every page recomputes the ranking and the cursor only skips the rows
+//! ranked above it; a count that grows between requests can move a row across a page boundary.
+
+use chrono::{DateTime, TimeZone, Utc};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+
+/// Keyset position: the row's sort timestamp plus its id as a tie-breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+/// Keyset position in a list ranked by a count, most first, with the id breaking ties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RankCursor {
+    pub rank: i64,
+    pub id: Uuid,
+}
+
+/// A position that can be handed out as `next_cursor`.
+pub trait PageCursor: Sized {
+    fn encode(&self) -> String;
+    fn decode(s: &str) -> Result<Self, String>;
+}
+
+/// Hex of the key (big-endian) followed by the id bytes.
+fn encode_key(key: i64, id: &Uuid) -> String {
+    let mut bytes = [0u8; 24];
+    bytes[..8].copy_from_slice(&key.to_be_bytes());
+    bytes[8..].copy_from_slice(id.as_bytes());
+    hex::encode(bytes)
+}
+
+fn decode_key(s: &str) -> Result<(i64, Uuid), String> {
+    let bytes = hex::decode(s.trim()).map_err(|_| "malformed cursor")?;
+    let bytes: [u8; 24] = bytes.try_into().map_err(|_| "malformed cursor")?;
+    let key = i64::from_be_bytes(bytes[..8].try_into().unwrap());
+    let id = Uuid::from_slice(&bytes[8..]).map_err(|_| "malformed cursor")?;
+    Ok((key, id))
+}
+
+impl Cursor {
+    /// Hex of the timestamp (microseconds, big-endian) followed by the id bytes.
+    pub fn encode(&self) -> String {
+        encode_key(self.at.timestamp_micros(), &self.id)
+    }
+
+    pub fn decode(s: &str) -> Result<Self, String> {
+        let (micros, id) = decode_key(s)?;
+        let at = Utc
+            .timestamp_micros(micros)
+            .single()
+            .ok_or("malformed cursor")?;
+        Ok(Cursor { at, id })
+    }
+}
+
+impl PageCursor for Cursor {
+    fn encode(&self) -> String {
+        Cursor::encode(self)
+    }
+
+    fn decode(s: &str) -> Result<Self, String> {
+        Cursor::decode(s)
+    }
+}
+
+impl PageCursor for RankCursor {
+    fn encode(&self) -> String {
+        encode_key(self.rank, &self.id)
+    }
+
+    fn decode(s: &str) -> Result<Self, String> {
+        let (rank, id) = decode_key(s)?;
+        Ok(RankCursor { rank, id })
+    }
+}
+
+/// A `cursor` query parameter; a malformed one is a 400.
+pub fn parse(raw: Option<&str>) -> Result<Option<Cursor>, ApiError> {
+    parse_as(raw)
+}
+
+/// [`parse`] for lists paged by another kind of cursor.
+pub fn parse_as<C: PageCursor>(raw: Option<&str>) -> Result<Option<C>, ApiError> {
+    raw.filter(|s| !s.trim().is_empty())
+        .map(|s| C::decode(s).map_err(ApiError::BadRequest))
+        .transpose()
+}
+
+/// Page size from a `limit` parameter: `default` when missing, at most `max`.
+pub fn limit(requested: Option<i64>, default: i64, max: i64) -> i64 {
+    requested.unwrap_or(default).clamp(1, max)
+}
+
+/// Cut `limit + 1` fetched rows down to the page; the cursor after its last row when more
+/// rows follow.
+pub fn page<T, C: PageCursor>(
+    mut rows: Vec<T>,
+    limit: i64,
+    cursor: impl Fn(&T) -> C,
+) -> (Vec<T>, Option<String>) {
+    let has_next = rows.len() as i64 > limit;
+    rows.truncate(limit.max(0) as usize);
+    let next = has_next
+        .then(|| rows.last().map(|row| cursor(row).encode()))
+        .flatten();
+    (rows, next)
+}
@@ -0,0 +1,134 @@
+//! Server → plugin action channel.
+//!
+//! Actions are queued per server in `server_pending_actions` and sent with every `POST /ingest`
+//! (and `POST /heartbeat`) response until the plugin acknowledges them: it lists the ids of the
+//! actions it applied in the [`ACK_HEADER`] of a later request, and only then are they marked
+//! delivered. An action the plugin doesn't know stays queued; every kind queued so far is a
+//! full-state sync that replaces its undelivered predecessor, so the queue stays small.
+
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Comma-separated ids of the actions the plugin applied.
+pub const ACK_HEADER: &str = "x-ack-actions";
+
+/// Most ids read from one [`ACK_HEADER`].
+const MAX_ACKS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PendingAction {
+    pub id: Uuid,
+    pub action: String,
+    pub payload: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Queue an action. With `replace`, undelivered actions of the same kind are dropped first
+/// (for full-state syncs where only the latest matters).
+pub async fn enqueue(
+    db: &PgPool,
+    server_id: &str,
+    action: &str,
+    payload: &Value,
+    replace: bool,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+    if replace {
+        sqlx::query(
+            r#"
+            delete from public.server_pending_actions
+            where server_id = $1 and action = $2 and delivered_at is null
+            "#,
+        )
+        .bind(server_id)
+        .bind(action)
+        .execute(&mut *tx)
+        .await?;
+    }
+    sqlx::query(
+        r#"
+        insert into public.server_pending_actions (server_id, action, payload)
+        values ($1, $2, $3)
+        "#,
+    )
+    .bind(server_id)
+    .bind(action)
+    .bind(sqlx::types::Json(payload))
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await
+}
+
+/// The server's undelivered actions, oldest first.
+pub async fn pending(db: &PgPool, server_id: &str) -> Result<Vec<PendingAction>, sqlx::Error> {
+    let rows: Vec<(Uuid, String, Value, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        select id, action, payload, created_at
+        from public.server_pending_actions
+        where server_id = $1 and delivered_at is null
+        order by created_at asc, id asc
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, action, payload, created_at)| PendingAction {
+            id,
+            action,
+            payload,
+            created_at,
+        })
+        .collect())
+}
+
+/// Action ids acknowledged in a request's [`ACK_HEADER`]; malformed ids are skipped.
+pub fn acked_ids(headers: &HeaderMap) -> Vec<Uuid> {
+    headers
+        .get(ACK_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .split(',')
+        .filter_map(|id| Uuid::parse_str(id.trim()).ok())
+        .take(MAX_ACKS)
+        .collect()
+}
+
+/// Mark the server's acknowledged actions as delivered; returns how many were still pending.
+pub async fn acknowledge(db: &PgPool, server_id: &str, ids: &[Uuid]) -> Result<u64, sqlx::Error> {
+    if ids.is_empty() {
+        return Ok(0);
+    }
+    let done = sqlx::query(
+        r#"
+        update public.server_pending_actions
+        set delivered_at = now()
+        where server_id = $1 and id = any($2) and delivered_at is null
+        "#,
+    )
+    .bind(server_id)
+    .bind(ids)
+    .execute(db)
+    .await?;
+    Ok(done.rows_affected())
+}
+
+/// Apply the request's acknowledgements, then return what is still pending for the response.
+///
+/// Best-effort: a failure only delays delivery to a later response.
+pub async fn exchange(db: &PgPool, server_id: &str, headers: &HeaderMap) -> Vec<PendingAction> {
+    if let Err(e) = acknowledge(db, server_id, &acked_ids(headers)).await {
+        tracing::warn!("pending action acknowledgement failed: {:?}", e);
+    }
+    pending(db, server_id).await.unwrap_or_else(|e| {
+        tracing::warn!("pending action lookup failed: {:?}", e);
+        Vec::new()
+    })
+}
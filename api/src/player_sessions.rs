@@ -0,0 +1,254 @@
+//! Per-player sessions stitched across batches.
+//!
+//! The plugin's `X-Session-Id` identifies a plugin run, not a player's stay. Ingest folds each
+//! batch into `player_sessions` rows with a real lifecycle:
+//!
+//! - a join marker (`PLAYER_JOIN` / `LOGIN` packet, or `PLAYER_STATE` with `fields.event = "join"`)
+//!   always starts a new session;
+//! - a quit marker (`PLAYER_QUIT` / `DISCONNECT` / `LOGOUT`, or `fields.event = "quit"`) ends it;
+//! - without markers, a player unseen for [`SESSION_GAP_MS`] (or seen under a new plugin session
+//!   id, i.e. after a restart) starts a new session and the old one ends at its last sighting.
+//!
+//! Times come from the records' `ts` (epoch millis), so out-of-order batches still land in the
+//! right session.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// A player unseen for longer than this is considered gone.
+pub const SESSION_GAP_MS: u64 = 120_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Marker {
+    Join,
+    Quit,
+}
+
+/// The session marker a record carries, if any.
+pub fn marker(record: &Value) -> Option<Marker> {
+    let pkt = record.get("pkt").and_then(|x| x.as_str()).unwrap_or("");
+    let named = |names: &[&str]| names.iter().any(|n| pkt.eq_ignore_ascii_case(n));
+    if named(&["PLAYER_JOIN", "LOGIN"]) {
+        return Some(Marker::Join);
+    }
+    if named(&["PLAYER_QUIT", "DISCONNECT", "LOGOUT"]) {
+        return Some(Marker::Quit);
+    }
+    if named(&["PLAYER_STATE"]) {
+        let event = record
+            .get("fields")
+            .and_then(|f| f.get("event"))
+            .and_then(|x| x.as_str())
+            .unwrap_or("");
+        if event.eq_ignore_ascii_case("join") {
+            return Some(Marker::Join);
+        }
+        if event.eq_ignore_ascii_case("quit") {
+            return Some(Marker::Quit);
+        }
+    }
+    None
+}
+
+/// `(ts, player, marker)` for every player record in a raw batch.
+pub fn session_records(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<(u64, Uuid, Option<Marker>)>> {
+    let reader = BufReader::new(GzDecoder::new(raw_gz_ndjson));
+    let mut records = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        // First line is batch metadata.
+        if line.is_empty() || i == 0 {
+            continue;
+        }
+        let Ok(v) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let uuid = v
+            .get("uuid")
+            .and_then(|x| x.as_str())
+            .and_then(|s| Uuid::parse_str(s).ok());
+        let ts = v.get("ts").and_then(|x| x.as_u64());
+        if let (Some(uuid), Some(ts)) = (uuid, ts) {
+            records.push((ts, uuid, marker(&v)));
+        }
+    }
+    Ok(records)
+}
+
+/// A contiguous stretch of one player's activity within a batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub start_ts: u64,
+    pub end_ts: u64,
+    /// Started by a join marker (never continues an earlier session).
+    pub joined: bool,
+    /// Ended by a quit marker.
+    pub quit: bool,
+}
+
+/// Split each player's records into segments at markers and gaps longer than [`SESSION_GAP_MS`].
+pub fn segments(mut records: Vec<(u64, Uuid, Option<Marker>)>) -> HashMap<Uuid, Vec<Segment>> {
+    records.sort_by_key(|(ts, uuid, _)| (*uuid, *ts));
+
+    let mut out: HashMap<Uuid, Vec<Segment>> = HashMap::new();
+    let mut current: Option<(Uuid, Segment)> = None;
+    for (ts, uuid, mark) in records {
+        if let Some((player, seg)) = current.take() {
+            let continues = player == uuid
+                && mark != Some(Marker::Join)
+                && ts.saturating_sub(seg.end_ts) <= SESSION_GAP_MS;
+            if continues {
+                let quit = mark == Some(Marker::Quit);
+                let seg = Segment {
+                    end_ts: ts,
+                    quit,
+                    ..seg
+                };
+                if quit {
+                    out.entry(player).or_default().push(seg);
+                } else {
+                    current = Some((player, seg));
+                }
+                continue;
+            }
+            out.entry(player).or_default().push(seg);
+        }
+
+        let seg = Segment {
+            start_ts: ts,
+            end_ts: ts,
+            joined: mark == Some(Marker::Join),
+            quit: mark == Some(Marker::Quit),
+        };
+        if seg.quit {
+            out.entry(uuid).or_default().push(seg);
+        } else {
+            current = Some((uuid, seg));
+        }
+    }
+    if let Some((player, seg)) = current {
+        out.entry(player).or_default().push(seg);
+    }
+    out
+}
+
+fn timestamp(ts: u64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(i64::try_from(ts).ok()?)
+}
+
+/// Fold a raw batch into `player_sessions`. Players must already exist in `players`.
+pub async fn record_batch(
+    db: &PgPool,
+    server_id: &str,
+    plugin_session_id: &str,
+    raw_gz_ndjson: &[u8],
+) -> anyhow::Result<()> {
+    let by_player = segments(session_records(raw_gz_ndjson)?);
+    for (player_uuid, segs) in by_player {
+        for seg in segs {
+            let (Some(start), Some(end)) = (timestamp(seg.start_ts), timestamp(seg.end_ts)) else {
+                continue;
+            };
+            apply_segment(
+                db,
+                server_id,
+                plugin_session_id,
+                player_uuid,
+                &seg,
+                start,
+                end,
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn apply_segment(
+    db: &PgPool,
+    server_id: &str,
+    plugin_session_id: &str,
+    player_uuid: Uuid,
+    seg: &Segment,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let open: Option<(Uuid, String, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        select id, plugin_session_id, last_seen_at
+        from public.player_sessions
+        where server_id = $1 and player_uuid = $2 and ended_at is null
+        order by started_at desc
+        limit 1
+        "#,
+    )
+    .bind(server_id)
+    .bind(player_uuid)
+    .fetch_optional(db)
+    .await?;
+
+    let gap = chrono::Duration::milliseconds(SESSION_GAP_MS as i64);
+    if let Some((id, open_plugin_session, last_seen_at)) = &open {
+        let continues =
+            !seg.joined && open_plugin_session == plugin_session_id && start <= *last_seen_at + gap;
+        if continues {
+            sqlx::query(
+                r#"
+                update public.player_sessions
+                set last_seen_at = greatest(last_seen_at, $2),
+                    ended_at = case when $3 then greatest(last_seen_at, $2) end,
+                    end_reason = case when $3 then 'quit' end
+                where id = $1
+                "#,
+            )
+            .bind(id)
+            .bind(end)
+            .bind(seg.quit)
+            .execute(db)
+            .await?;
+            return Ok(());
+        }
+
+        let reason = if open_plugin_session != plugin_session_id {
+            "restart"
+        } else if seg.joined {
+            "rejoin"
+        } else {
+            "timeout"
+        };
+        sqlx::query(
+            r#"
+            update public.player_sessions
+            set ended_at = last_seen_at, end_reason = $2
+            where id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(reason)
+        .execute(db)
+        .await?;
+    }
+
+    sqlx::query(
+        r#"
+        insert into public.player_sessions
+            (server_id, player_uuid, plugin_session_id, started_at, last_seen_at, ended_at, end_reason)
+        values ($1, $2, $3, $4, $5, case when $6 then $5 end, case when $6 then 'quit' end)
+        "#,
+    )
+    .bind(server_id)
+    .bind(player_uuid)
+    .bind(plugin_session_id)
+    .bind(start)
+    .bind(end)
+    .bind(seg.quit)
+    .execute(db)
+    .await?;
+    Ok(())
+}
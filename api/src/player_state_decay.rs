@@ -0,0 +1,142 @@
+//! Expiry of stale per-player module state.
+//!
+//! Modules keep violation levels and histories in `module_player_state` across batches. For a
+//! player who comes back after months, that state is stale: a VL accumulated long ago would get
+//! them punished on their first flag. A background tick (`PLAYER_STATE_DECAY_ENABLED`) removes
+//! the state of players not seen on the server for `PLAYER_STATE_DECAY_DAYS`, so modules start
+//! from scratch when they return.
+//!
+//! Before a row is removed, a summary goes to `module_player_state_archive`: the `vl` field, the
+//! top-level numbers and booleans of the state (counters, flags; histories and nested objects
+//! are dropped) and when the state was last written and the player last seen.
+
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::AppState;
+
+/// Rows archived per transaction.
+const BATCH_SIZE: i64 = 500;
+
+/// The archived summary of a module's state: its `vl` and top-level scalar fields.
+pub fn summarize(state: &Value) -> (Option<f64>, Value) {
+    let vl = state.get("vl").and_then(Value::as_f64);
+    let summary: Map<String, Value> = state
+        .as_object()
+        .map(|fields| {
+            fields
+                .iter()
+                .filter(|(_, v)| v.is_number() || v.is_boolean())
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    (vl, Value::Object(summary))
+}
+
+/// Cutoff for `days` of inactivity (at least one day).
+pub fn cutoff(now: DateTime<Utc>, days: i64) -> DateTime<Utc> {
+    now - Duration::days(days.max(1))
+}
+
+type StaleRow = (
+    Uuid,
+    String,
+    Uuid,
+    String,
+    Value,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+);
+
+/// Archive and remove one batch of stale state; returns the number of rows.
+async fn decay_batch(state: &AppState, cutoff: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let mut tx = state.db.begin().await?;
+
+    let rows: Vec<StaleRow> = sqlx::query_as(
+        r#"
+        select s.id, s.server_id, s.player_uuid, s.module_name, s.state_json, s.updated_at,
+               sp.last_seen_at
+        from public.module_player_state s
+        left join public.server_players sp
+            on sp.server_id = s.server_id and sp.player_uuid = s.player_uuid
+        where s.updated_at < $1
+          and coalesce(sp.last_seen_at, s.updated_at) < $1
+        order by s.updated_at asc
+        limit $2
+        for update of s skip locked
+        "#,
+    )
+    .bind(cutoff)
+    .bind(BATCH_SIZE)
+    .fetch_all(&mut *tx)
+    .await?;
+    if rows.is_empty() {
+        return Ok(0);
+    }
+
+    let mut ids = Vec::with_capacity(rows.len());
+    for (id, server_id, player_uuid, module_name, state_json, updated_at, last_seen_at) in rows {
+        let (vl, summary) = summarize(&state_json);
+        sqlx::query(
+            r#"
+            insert into public.module_player_state_archive
+                (server_id, player_uuid, module_name, vl, summary_json, state_bytes,
+                 state_updated_at, player_last_seen_at, archived_at)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, now())
+            "#,
+        )
+        .bind(&server_id)
+        .bind(player_uuid)
+        .bind(&module_name)
+        .bind(vl)
+        .bind(summary)
+        .bind(state_json.to_string().len() as i32)
+        .bind(updated_at)
+        .bind(last_seen_at)
+        .execute(&mut *tx)
+        .await?;
+        ids.push(id);
+    }
+
+    let removed = sqlx::query("delete from public.module_player_state where id = any($1)")
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    tx.commit().await?;
+    Ok(removed)
+}
+
+pub async fn decay_tick(state: AppState) {
+    if !state.player_state_decay_enabled {
+        return;
+    }
+
+    let cutoff = cutoff(Utc::now(), state.player_state_decay_days);
+    let mut archived = 0u64;
+    loop {
+        match decay_batch(&state, cutoff).await {
+            Ok(n) => {
+                archived += n;
+                if n < BATCH_SIZE as u64 {
+                    break;
+                }
+            }
+            Err(e) => {
+                tracing::error!("player state decay failed: {:?}", e);
+                break;
+            }
+        }
+    }
+
+    if archived > 0 {
+        tracing::info!(
+            archived = archived,
+            cutoff = %cutoff,
+            "stale player state archived"
+        );
+    }
+}
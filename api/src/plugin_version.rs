@@ -0,0 +1,138 @@
+//! Plugin version compatibility gate.
+//!
+//! Plugins report their version in `X-Plugin-Version` (handshake, heartbeat, ingest); the latest
+//! one is stored on the server row. With `MIN_SUPPORTED_PLUGIN_VERSION` set, older plugins get
+//! HTTP 426 from handshake and ingest (their batches are not stored); with
+//! `RECOMMENDED_PLUGIN_VERSION` set, older plugins are told to update but keep working.
+//! Plugins that send no (or an unparseable) version can't be judged and are only nudged.
+
+use std::fmt;
+
+use axum::http::HeaderMap;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+pub const PLUGIN_VERSION_HEADER: &str = "x-plugin-version";
+
+const MAX_VERSION_LEN: usize = 64;
+
+/// `major.minor.patch`; missing parts are 0 and pre-release / build suffixes are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version(pub u32, pub u32, pub u32);
+
+impl Version {
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        let raw = raw.strip_prefix(['v', 'V']).unwrap_or(raw);
+        let core = raw.split(['-', '+', ' ']).next().unwrap_or("");
+        let mut parts = core.split('.');
+        let mut next = |required: bool| match parts.next() {
+            Some(p) => p.parse::<u32>().ok(),
+            None if required => None,
+            None => Some(0),
+        };
+        let version = Version(next(true)?, next(false)?, next(false)?);
+        match parts.next() {
+            Some(_) => None,
+            None => Some(version),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+/// The reported plugin version header, trimmed (not necessarily parseable).
+pub fn from_headers(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(PLUGIN_VERSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty() && s.len() <= MAX_VERSION_LEN)
+}
+
+/// Update advice returned to the plugin; omitted from responses when the plugin is current.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PluginUpdate {
+    /// The plugin is below the minimum supported version and its data is rejected.
+    pub update_required: bool,
+    /// A newer version is recommended (always true when `update_required`).
+    pub update_recommended: bool,
+    pub current_version: Option<String>,
+    pub min_supported_version: Option<String>,
+    pub recommended_version: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VersionPolicy {
+    pub min_supported: Option<Version>,
+    pub recommended: Option<Version>,
+}
+
+impl VersionPolicy {
+    /// Advice for a plugin reporting `current`; `None` if it needs no update.
+    pub fn check(&self, current: Option<&str>) -> Option<PluginUpdate> {
+        if self.min_supported.is_none() && self.recommended.is_none() {
+            return None;
+        }
+        let parsed = current.and_then(Version::parse);
+        let below = |target: Option<Version>| match (target, parsed) {
+            (Some(target), Some(v)) => v < target,
+            _ => false,
+        };
+
+        let update_required = below(self.min_supported);
+        let update_recommended = update_required || below(self.recommended) || parsed.is_none();
+        if !update_recommended {
+            return None;
+        }
+
+        let target = self.recommended.max(self.min_supported);
+        let message = match (update_required, parsed) {
+            (true, _) => format!(
+                "plugin version {} is no longer supported; update to {} or newer",
+                current.unwrap_or("unknown"),
+                target.map(|v| v.to_string()).unwrap_or_default()
+            ),
+            (false, None) => {
+                "plugin version unknown (send X-Plugin-Version); please update the plugin"
+                    .to_string()
+            }
+            (false, Some(v)) => format!(
+                "plugin version {} is outdated; version {} is recommended",
+                v,
+                target.map(|v| v.to_string()).unwrap_or_default()
+            ),
+        };
+
+        Some(PluginUpdate {
+            update_required,
+            update_recommended,
+            current_version: current.map(str::to_string),
+            min_supported_version: self.min_supported.map(|v| v.to_string()),
+            recommended_version: self.recommended.map(|v| v.to_string()),
+            message,
+        })
+    }
+}
+
+/// Store the version a server's plugin last reported.
+pub async fn record(db: &PgPool, server_id: &str, version: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        update public.servers
+        set plugin_version = $2, plugin_version_seen_at = now()
+        where id = $1
+        "#,
+    )
+    .bind(server_id)
+    .bind(version)
+    .execute(db)
+    .await?;
+    Ok(())
+}
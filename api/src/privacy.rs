@@ -0,0 +1,592 @@
+//! Player privacy: UUID pseudonymization and erasure of a player's data.
+//!
+//! Servers with `hash_player_uuids` (set via `POST /dashboard/:server_id/privacy`, needs
+//! `PLAYER_UUID_HASH_KEY`) never store player UUIDs: ingest replaces them with a keyed hash
+//! per server ([`pseudonymize_player`]) before the batch is stored, tracked or dispatched, so
+//! batches, findings, sessions and module state only hold pseudonyms. Without the key a leaked
+//! database can't be joined back to players, nor one server's players to another's. The owning
+//! server's plugin can still look a player up by UUID (`GET /plugin/findings`), as the API
+//! derives the pseudonym for it. Usernames in the batch are replaced too
+//! ([`pseudonymous_name`]), so `players` and `server_players` never hold the real name. Turning
+//! hashing on doesn't rewrite data stored before.
+//!
+//! Erasure (GDPR right to erasure):
+//!
+//! `POST /admin/privacy/delete-player` queues a `player_deletion_jobs` row; the worker then:
+//!
+//! 1. rewrites every stored raw batch from the player's sessions without their records (and
+//!    without their entries in the metadata line's `client_types` and `client_protocols`);
+//! 2. deletes the evidence bundles of their findings from the object store;
+//! 3. in one transaction, deletes their findings, module state, sessions, identifiers,
+//!    exemptions, watchlist entries, observations, cases, moderator notes, bans and reputation,
+//!    detaches them from plugin sessions and staff observations they recorded, and finally
+//!    removes the player.
+//!
+//! Objects go first so a failed run can simply be retried: the rows that locate the batches are
+//! still there, and rewriting an already scrubbed batch changes nothing. Once the job completes
+//! its `player_uuid` is cleared, so the job row keeps only the counts. On servers that hash
+//! player UUIDs the player's pseudonym is erased as well.
+
+use std::io::{BufRead, BufReader, Write};
+
+use chrono::{DateTime, Utc};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::s3::ObjectStore;
+use crate::{error::ApiError, AppState};
+
+/// How often the worker looks for queued deletions.
+pub const WORKER_INTERVAL_SECONDS: u64 = 30;
+
+/// A running job whose row hasn't moved for this long is considered abandoned.
+const STALE_AFTER_SECONDS: f64 = 900.0;
+
+/// A raw batch with one player's records removed.
+#[derive(Debug)]
+pub struct ScrubbedBatch {
+    /// Gzipped NDJSON, metadata line first.
+    pub data: Vec<u8>,
+    pub records_removed: usize,
+    /// SHA-256 of the decompressed payload (`batch_index.payload_sha256`).
+    pub sha256: String,
+}
+
+/// Metadata line entries keyed by player UUID.
+const PER_PLAYER_META_KEYS: [&str; 2] = ["client_types", "client_protocols"];
+
+/// Remove `player`'s records from a raw batch. Returns `None` when the batch holds nothing of
+/// theirs. Lines that aren't JSON are kept as they are.
+pub fn scrub_batch(raw_gz_ndjson: &[u8], player: Uuid) -> anyhow::Result<Option<ScrubbedBatch>> {
+    let reader = BufReader::new(GzDecoder::new(raw_gz_ndjson));
+    let player_str = player.to_string();
+    let mut payload = Vec::new();
+    let mut records_removed = 0;
+    let mut meta_changed = false;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if i == 0 {
+            // Batch metadata: drop the player's client type and protocol.
+            let line = match serde_json::from_str::<Value>(&line) {
+                Ok(mut meta) => {
+                    let mut removed = false;
+                    for key in PER_PLAYER_META_KEYS {
+                        removed |= meta
+                            .get_mut(key)
+                            .and_then(|t| t.as_object_mut())
+                            .and_then(|t| t.remove(&player_str))
+                            .is_some();
+                    }
+                    if removed {
+                        meta_changed = true;
+                        serde_json::to_string(&meta)?
+                    } else {
+                        line
+                    }
+                }
+                Err(_) => line,
+            };
+            payload.extend_from_slice(line.as_bytes());
+            payload.push(b'\n');
+            continue;
+        }
+        let is_player = serde_json::from_str::<Value>(&line)
+            .ok()
+            .and_then(|v| {
+                v.get("uuid")
+                    .and_then(|x| x.as_str())
+                    .and_then(|s| Uuid::parse_str(s).ok())
+            })
+            .is_some_and(|uuid| uuid == player);
+        if is_player {
+            records_removed += 1;
+            continue;
+        }
+        payload.extend_from_slice(line.as_bytes());
+        payload.push(b'\n');
+    }
+
+    if records_removed == 0 && !meta_changed {
+        return Ok(None);
+    }
+    let sha256 = hex::encode(Sha256::digest(&payload));
+    let mut data = Vec::new();
+    let mut encoder = GzEncoder::new(&mut data, Compression::default());
+    encoder.write_all(&payload)?;
+    encoder.finish()?;
+    Ok(Some(ScrubbedBatch {
+        data,
+        records_removed,
+        sha256,
+    }))
+}
+
+/// The stand-in stored for `player` on a server that hashes player UUIDs: HMAC-SHA256 of the
+/// UUID, keyed with `PLAYER_UUID_HASH_KEY` and the server id, shaped as a version 8 UUID so it
+/// fits every `player_uuid` column. The same player gets a different pseudonym on every server.
+pub fn pseudonymize_player(key: &str, server_id: &str, player: Uuid) -> Uuid {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(server_id.as_bytes());
+    mac.update(&[0]);
+    mac.update(player.as_bytes());
+    let digest = mac.finalize().into_bytes();
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    uuid::Builder::from_custom_bytes(bytes).into_uuid()
+}
+
+/// The username stored for a pseudonymized player: derived from the pseudonym, so it is stable
+/// per server and says nothing about the real name.
+pub fn pseudonymous_name(pseudonym: Uuid) -> String {
+    format!("player_{}", &pseudonym.simple().to_string()[..8])
+}
+
+/// Replace every player UUID in a raw batch (record `uuid` fields and the metadata line's
+/// `client_types` and `client_protocols` keys) with its pseudonym, and every record `name` with
+/// [`pseudonymous_name`] (dropped when the record has no UUID to derive it from). Lines that
+/// aren't JSON are kept as they are.
+pub fn pseudonymize_batch(
+    raw_gz_ndjson: &[u8],
+    key: &str,
+    server_id: &str,
+) -> anyhow::Result<Vec<u8>> {
+    let reader = BufReader::new(GzDecoder::new(raw_gz_ndjson));
+    let pseudonym = |raw: &str| {
+        Uuid::parse_str(raw)
+            .ok()
+            .map(|uuid| pseudonymize_player(key, server_id, uuid))
+    };
+    let mut payload = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = match serde_json::from_str::<Value>(&line) {
+            Ok(mut v) if i == 0 => {
+                for key in PER_PLAYER_META_KEYS {
+                    if let Some(map) = v.get_mut(key).and_then(|t| t.as_object_mut()) {
+                        *map = std::mem::take(map)
+                            .into_iter()
+                            .map(|(uuid, value)| {
+                                (pseudonym(&uuid).map_or(uuid, |p| p.to_string()), value)
+                            })
+                            .collect();
+                    }
+                }
+                serde_json::to_string(&v)?
+            }
+            Ok(mut v) => {
+                let replaced = v.get("uuid").and_then(|x| x.as_str()).and_then(pseudonym);
+                let has_name = v.get("name").is_some();
+                match (replaced, v.as_object_mut()) {
+                    (Some(replaced), Some(record)) => {
+                        if has_name {
+                            record
+                                .insert("name".into(), Value::String(pseudonymous_name(replaced)));
+                        }
+                        record.insert("uuid".into(), Value::String(replaced.to_string()));
+                        serde_json::to_string(&v)?
+                    }
+                    (None, Some(record)) if has_name => {
+                        record.remove("name");
+                        serde_json::to_string(&v)?
+                    }
+                    _ => line,
+                }
+            }
+            Err(_) => line,
+        };
+        payload.extend_from_slice(line.as_bytes());
+        payload.push(b'\n');
+    }
+    let mut data = Vec::new();
+    let mut encoder = GzEncoder::new(&mut data, Compression::default());
+    encoder.write_all(&payload)?;
+    encoder.finish()?;
+    Ok(data)
+}
+
+/// Whether the server stores pseudonyms instead of player UUIDs.
+pub async fn hashes_player_uuids(db: &PgPool, server_id: &str) -> Result<bool, sqlx::Error> {
+    let enabled: Option<bool> =
+        sqlx::query_scalar("select hash_player_uuids from public.servers where id = $1")
+            .bind(server_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(enabled.unwrap_or(false))
+}
+
+fn db_error(what: &'static str) -> impl FnOnce(sqlx::Error) -> ApiError {
+    move |e| {
+        tracing::error!("{} failed: {:?}", what, e);
+        ApiError::Internal
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlayerDeletionJob {
+    pub id: Uuid,
+    /// Cleared once the job completes.
+    pub player_uuid: Option<Uuid>,
+    /// queued | running | completed | failed
+    pub status: String,
+    pub batches_rewritten: i64,
+    pub records_removed: i64,
+    pub findings_deleted: i64,
+    /// Evidence bundles removed from the object store.
+    pub objects_deleted: i64,
+    /// Other rows deleted or detached from the player.
+    pub rows_deleted: i64,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+type JobRow = (
+    Uuid,
+    Option<Uuid>,
+    String,
+    i64,
+    i64,
+    i64,
+    i64,
+    i64,
+    Option<String>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+);
+
+const JOB_COLUMNS: &str = "id, player_uuid, status, batches_rewritten, records_removed, \
+     findings_deleted, objects_deleted, rows_deleted, error, created_at, started_at, completed_at";
+
+fn job_from_row(row: JobRow) -> PlayerDeletionJob {
+    let (
+        id,
+        player_uuid,
+        status,
+        batches_rewritten,
+        records_removed,
+        findings_deleted,
+        objects_deleted,
+        rows_deleted,
+        error,
+        created_at,
+        started_at,
+        completed_at,
+    ) = row;
+    PlayerDeletionJob {
+        id,
+        player_uuid,
+        status,
+        batches_rewritten,
+        records_removed,
+        findings_deleted,
+        objects_deleted,
+        rows_deleted,
+        error,
+        created_at,
+        started_at,
+        completed_at,
+    }
+}
+
+/// Queue erasure of a player; an existing queued or running job for them is returned instead.
+pub async fn create_deletion(
+    state: &AppState,
+    player_uuid: Uuid,
+) -> Result<PlayerDeletionJob, ApiError> {
+    let existing: Option<JobRow> = sqlx::query_as(&format!(
+        r#"
+        select {} from public.player_deletion_jobs
+        where player_uuid = $1 and status in ('queued', 'running')
+        order by created_at desc
+        limit 1
+        "#,
+        JOB_COLUMNS
+    ))
+    .bind(player_uuid)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error("player deletion lookup"))?;
+    if let Some(row) = existing {
+        return Ok(job_from_row(row));
+    }
+
+    let row: JobRow = sqlx::query_as(&format!(
+        "insert into public.player_deletion_jobs (player_uuid) values ($1) returning {}",
+        JOB_COLUMNS
+    ))
+    .bind(player_uuid)
+    .fetch_one(&state.db)
+    .await
+    .map_err(db_error("player deletion insert"))?;
+    let job = job_from_row(row);
+    tracing::info!(job_id = %job.id, "player deletion queued");
+    Ok(job)
+}
+
+pub async fn get_deletion(state: &AppState, job_id: Uuid) -> Result<PlayerDeletionJob, ApiError> {
+    let row: Option<JobRow> = sqlx::query_as(&format!(
+        "select {} from public.player_deletion_jobs where id = $1",
+        JOB_COLUMNS
+    ))
+    .bind(job_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error("player deletion job lookup"))?;
+    row.map(job_from_row)
+        .ok_or_else(|| ApiError::NotFound(format!("player deletion job {} not found", job_id)))
+}
+
+#[derive(Debug, Default)]
+struct DeletionCounts {
+    batches_rewritten: i64,
+    records_removed: i64,
+    findings: i64,
+    objects: i64,
+    rows: i64,
+}
+
+/// Claim and run at most one deletion (queued, or running but abandoned).
+pub async fn worker_tick(state: AppState) {
+    let claimed: Result<Option<(Uuid, Uuid)>, _> = sqlx::query_as(
+        r#"
+        update public.player_deletion_jobs
+        set status = 'running', started_at = coalesce(started_at, now()), updated_at = now()
+        where id = (
+            select id from public.player_deletion_jobs
+            where player_uuid is not null
+              and (status = 'queued'
+                   or (status = 'running' and updated_at < now() - make_interval(secs => $1)))
+            order by created_at asc
+            limit 1
+            for update skip locked
+        )
+        returning id, player_uuid
+        "#,
+    )
+    .bind(STALE_AFTER_SECONDS)
+    .fetch_optional(&state.db)
+    .await;
+
+    let (job_id, player_uuid) = match claimed {
+        Ok(Some(job)) => job,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!("player deletion claim failed: {:?}", e);
+            return;
+        }
+    };
+
+    let (status, counts, error) = match erase(&state, player_uuid).await {
+        Ok(counts) => ("completed", counts, None),
+        Err(e) => {
+            tracing::error!(job_id = %job_id, "player deletion failed: {:#}", e);
+            (
+                "failed",
+                DeletionCounts::default(),
+                Some(format!("{:#}", e)),
+            )
+        }
+    };
+    let res = sqlx::query(
+        r#"
+        update public.player_deletion_jobs
+        set status = $2, batches_rewritten = $3, records_removed = $4, findings_deleted = $5,
+            objects_deleted = $6, rows_deleted = $7, error = $8,
+            player_uuid = case when $2 = 'completed' then null else player_uuid end,
+            completed_at = now(), updated_at = now()
+        where id = $1
+        "#,
+    )
+    .bind(job_id)
+    .bind(status)
+    .bind(counts.batches_rewritten)
+    .bind(counts.records_removed)
+    .bind(counts.findings)
+    .bind(counts.objects)
+    .bind(counts.rows)
+    .bind(error)
+    .execute(&state.db)
+    .await;
+    if let Err(e) = res {
+        tracing::error!(job_id = %job_id, "player deletion status update failed: {:?}", e);
+    }
+    tracing::info!(
+        job_id = %job_id,
+        status,
+        batches_rewritten = counts.batches_rewritten,
+        records_removed = counts.records_removed,
+        findings = counts.findings,
+        "player deletion finished"
+    );
+}
+
+/// Rows deleted outright, keyed by `player_uuid` (in addition to findings), in deletion order.
+pub const PLAYER_TABLES: [&str; 16] = [
+    "shadow_findings",
+    "player_cases",
+    "moderator_notes",
+    "observation_replay_findings",
+    "cheat_observations",
+    "module_player_state",
+    "module_player_state_archive",
+    "player_identifiers",
+    "player_session_clients",
+    "player_sessions",
+    "proxy_sessions",
+    "server_players",
+    "server_exemptions",
+    "organization_exemptions",
+    "server_watchlist",
+    "player_reputation",
+];
+
+async fn erase(state: &AppState, player: Uuid) -> anyhow::Result<DeletionCounts> {
+    // Servers hashing player UUIDs stored the player under their own pseudonym.
+    let mut identities = vec![player];
+    if let Some(key) = state.player_uuid_hash_key.as_deref() {
+        let servers: Vec<String> =
+            sqlx::query_scalar("select id from public.servers where hash_player_uuids = true")
+                .fetch_all(&state.db)
+                .await?;
+        identities.extend(
+            servers
+                .iter()
+                .map(|server_id| pseudonymize_player(key, server_id, player)),
+        );
+    }
+
+    let mut counts = DeletionCounts::default();
+    for identity in identities {
+        erase_identity(state, identity, &mut counts).await?;
+    }
+    Ok(counts)
+}
+
+async fn erase_identity(
+    state: &AppState,
+    player: Uuid,
+    counts: &mut DeletionCounts,
+) -> anyhow::Result<()> {
+    // Raw batches of every plugin session the player was seen in.
+    let batches: Vec<(Uuid, String, Option<DateTime<Utc>>)> = sqlx::query_as(
+        r#"
+        select b.id, b.s3_key, b.retain_until
+        from public.batch_index b
+        where (b.server_id, b.session_id) in (
+            select server_id, plugin_session_id from public.player_sessions where player_uuid = $1
+            union
+            select server_id, session_id from public.player_session_clients where player_uuid = $1
+            union
+            select server_id, session_id from public.sessions where player_uuid = $1
+        )
+        order by b.received_at asc
+        "#,
+    )
+    .bind(player)
+    .fetch_all(&state.db)
+    .await?;
+
+    for (batch_id, s3_key, retain_until) in batches {
+        let raw = match state.object_store.get_batch(&s3_key).await {
+            Ok(raw) => raw,
+            Err(e) => {
+                // Already removed by TTL cleanup.
+                tracing::debug!(batch_id = %batch_id, "batch not readable, skipping: {:?}", e);
+                continue;
+            }
+        };
+        let Some(scrubbed) = scrub_batch(&raw, player)? else {
+            continue;
+        };
+        let payload_bytes = scrubbed.data.len() as i32;
+        state
+            .object_store
+            .rewrite_batch(&s3_key, scrubbed.data)
+            .await?;
+        // Overwriting drops the object's tags.
+        if retain_until.is_some_and(|t| t > Utc::now()) {
+            state.object_store.mark_retained(&s3_key).await?;
+        }
+        sqlx::query(
+            r#"
+            update public.batch_index
+            set payload_bytes = $2,
+                event_count = greatest(event_count - $3, 0),
+                payload_sha256 = case when payload_sha256 is null then null else $4 end
+            where id = $1
+            "#,
+        )
+        .bind(batch_id)
+        .bind(payload_bytes)
+        .bind(scrubbed.records_removed as i32)
+        .bind(&scrubbed.sha256)
+        .execute(&state.db)
+        .await?;
+        counts.batches_rewritten += 1;
+        counts.records_removed += scrubbed.records_removed as i64;
+    }
+
+    // Evidence bundles of the player's findings.
+    let findings: Vec<(Uuid, String)> =
+        sqlx::query_as("select id, server_id from public.findings where player_uuid = $1")
+            .bind(player)
+            .fetch_all(&state.db)
+            .await?;
+    for (finding_id, server_id) in &findings {
+        if let Some(prefix) = ObjectStore::finding_bundles_prefix(server_id, finding_id) {
+            counts.objects += state.object_store.delete_prefix(&prefix).await? as i64;
+        }
+    }
+
+    let mut tx = state.db.begin().await?;
+    counts.findings += sqlx::query("delete from public.findings where player_uuid = $1")
+        .bind(player)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+    for table in PLAYER_TABLES {
+        counts.rows += sqlx::query(&format!(
+            "delete from public.{} where player_uuid = $1",
+            table
+        ))
+        .bind(player)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+    }
+    counts.rows += sqlx::query("delete from public.punishment_actions where lower(uuid) = $1")
+        .bind(player.to_string())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+    // Plugin sessions and observations by staff belong to the server; only unlink the player.
+    counts.rows +=
+        sqlx::query("update public.sessions set player_uuid = null where player_uuid = $1")
+            .bind(player)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected() as i64;
+    counts.rows += sqlx::query(
+        "update public.cheat_observations set recorded_by_uuid = null where recorded_by_uuid = $1",
+    )
+    .bind(player)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected() as i64;
+    counts.rows += sqlx::query("delete from public.players where uuid = $1")
+        .bind(player)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+    tx.commit().await?;
+    Ok(())
+}
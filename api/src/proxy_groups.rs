@@ -0,0 +1,131 @@
+//! Proxy groups: backends behind one BungeeCord/Velocity proxy.
+//!
+//! Plugins on proxied backends send `X-Proxy-Id` with each batch. The first batch links the
+//! server to the owner's group for that id (created on demand); batches without the header
+//! leave an existing link alone. Groups are scoped to the server's owner, so a proxy id only
+//! ever joins servers of the same account or organization.
+//!
+//! Presence is tracked per group in `proxy_sessions`: a player seen on any backend within
+//! [`SESSION_GAP_SECONDS`] of their last sighting continues the same session, so hopping from
+//! lobby to a game server is one session rather than two.
+
+use axum::http::HeaderMap;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub const PROXY_ID_HEADER: &str = "x-proxy-id";
+
+/// A player unseen on the group for longer than this starts a new session.
+pub const SESSION_GAP_SECONDS: i64 = 300;
+
+const MAX_PROXY_ID_LEN: usize = 64;
+
+/// Clean up a plugin-supplied proxy id; `None` if it is unusable.
+pub fn normalize_proxy_id(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value.len() > MAX_PROXY_ID_LEN {
+        return None;
+    }
+    if !value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+    {
+        return None;
+    }
+    Some(value.to_string())
+}
+
+/// The normalized `X-Proxy-Id` header, if present and valid.
+pub fn proxy_id_from_headers(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(PROXY_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(normalize_proxy_id)
+}
+
+/// Link `server_id` to the owner's group for `proxy_id`, creating it if needed; returns the group id.
+pub async fn link_server(
+    db: &PgPool,
+    owner_id: Uuid,
+    proxy_id: &str,
+    server_id: &str,
+) -> Result<Uuid, sqlx::Error> {
+    let group_id: Uuid = sqlx::query_scalar(
+        r#"
+        insert into public.proxy_groups (owner_id, proxy_id)
+        values ($1, $2)
+        on conflict (owner_id, proxy_id) do update set last_seen_at = now()
+        returning id
+        "#,
+    )
+    .bind(owner_id)
+    .bind(proxy_id)
+    .fetch_one(db)
+    .await?;
+
+    sqlx::query(
+        r#"
+        update public.servers
+        set proxy_group_id = $2
+        where id = $1 and proxy_group_id is distinct from $2
+        "#,
+    )
+    .bind(server_id)
+    .bind(group_id)
+    .execute(db)
+    .await?;
+
+    Ok(group_id)
+}
+
+/// Extend (or start) the group session of each player seen on `server_id`.
+pub async fn record_presence(
+    db: &PgPool,
+    group_id: Uuid,
+    server_id: &str,
+    players: &[Uuid],
+) -> Result<(), sqlx::Error> {
+    for player_uuid in players {
+        let extended = sqlx::query(
+            r#"
+            update public.proxy_sessions
+            set last_seen_at = now(),
+                last_server_id = $3,
+                server_ids = case
+                    when $3 = any(server_ids) then server_ids
+                    else array_append(server_ids, $3)
+                end
+            where id = (
+                select id from public.proxy_sessions
+                where proxy_group_id = $1
+                  and player_uuid = $2
+                  and last_seen_at > now() - make_interval(secs => $4)
+                order by last_seen_at desc
+                limit 1
+            )
+            "#,
+        )
+        .bind(group_id)
+        .bind(player_uuid)
+        .bind(server_id)
+        .bind(SESSION_GAP_SECONDS as f64)
+        .execute(db)
+        .await?;
+
+        if extended.rows_affected() == 0 {
+            sqlx::query(
+                r#"
+                insert into public.proxy_sessions
+                    (proxy_group_id, player_uuid, last_server_id, server_ids)
+                values ($1, $2, $3, array[$3])
+                "#,
+            )
+            .bind(group_id)
+            .bind(player_uuid)
+            .bind(server_id)
+            .execute(db)
+            .await?;
+        }
+    }
+    Ok(())
+}
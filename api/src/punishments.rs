@@ -0,0 +1,516 @@
+//! Ban export to an existing punishment stack.
+//!
+//! Networks usually already run LiteBans, AdvancedBan or a similar plugin and want bans to go
+//! through it rather than a second system. A server's ban rule (`punishment_exports`) fires
+//! when a stored finding has one of its trigger severities (after severity overrides) and, if
+//! set, at least its minimum confidence; findings without a confidence count as certain. One
+//! ban action is produced per player with all the findings that triggered it as evidence, and
+//! a player is not exported again within [`DEDUPE_HOURS`].
+//!
+//! Every action is recorded in `punishment_actions`, whose columns follow LiteBans' bans table
+//! (`uuid`, `reason`, `banned_by_name`, `time` / `until` in epoch ms with `-1` for permanent,
+//! `silent`, `ipban`, `active`). In `queue` mode the row is the export: a bridge on the network
+//! reads rows with `delivery = 'queued'` and marks them `consumed`. In `webhook` mode the same
+//! fields are POSTed as JSON to the configured endpoint (with `Authorization: Bearer <token>`
+//! when a token is set) and `delivery` records whether that succeeded.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppState;
+
+/// Name bans are issued under.
+pub const EXECUTOR_NAME: &str = "AsyncAnticheat";
+/// Dashboard linked from evidence when the server sets no `evidence_base_url`.
+pub const DEFAULT_EVIDENCE_BASE_URL: &str = "https://asyncanticheat.com";
+pub const DEFAULT_REASON: &str = "Unfair advantage ({detectors})";
+/// A player is exported at most once per this many hours.
+pub const DEDUPE_HOURS: i64 = 24;
+
+/// Where ban actions go.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportMode {
+    #[default]
+    Queue,
+    Webhook,
+}
+
+impl ExportMode {
+    pub const ALL: [&'static str; 2] = ["queue", "webhook"];
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "queue" => Some(Self::Queue),
+            "webhook" => Some(Self::Webhook),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queue => "queue",
+            Self::Webhook => "webhook",
+        }
+    }
+}
+
+/// A server's automated ban rule and export destination.
+#[derive(Debug, Clone)]
+pub struct BanRule {
+    pub enabled: bool,
+    pub mode: ExportMode,
+    pub webhook_url: Option<String>,
+    pub webhook_token: Option<String>,
+    pub trigger_severities: Vec<String>,
+    pub min_confidence: Option<f64>,
+    /// None for permanent bans.
+    pub ban_duration_seconds: Option<i64>,
+    pub reason: String,
+    pub evidence_base_url: Option<String>,
+}
+
+impl Default for BanRule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: ExportMode::Queue,
+            webhook_url: None,
+            webhook_token: None,
+            trigger_severities: vec!["critical".to_string()],
+            min_confidence: None,
+            ban_duration_seconds: None,
+            reason: DEFAULT_REASON.to_string(),
+            evidence_base_url: None,
+        }
+    }
+}
+
+impl BanRule {
+    /// Whether a stored finding with this severity and confidence fires the rule.
+    pub fn fires(&self, severity: &str, confidence: Option<f64>) -> bool {
+        self.enabled
+            && self.trigger_severities.iter().any(|s| s == severity)
+            && match (self.min_confidence, confidence) {
+                (Some(min), Some(c)) => c >= min,
+                _ => true,
+            }
+    }
+
+    pub async fn load(db: &PgPool, server_id: &str) -> Result<Option<Self>, sqlx::Error> {
+        let row: Option<(
+            bool,
+            String,
+            Option<String>,
+            Option<String>,
+            Vec<String>,
+            Option<f64>,
+            Option<i64>,
+            String,
+            Option<String>,
+        )> = sqlx::query_as(
+            r#"
+            select enabled, mode, webhook_url, webhook_token, trigger_severities, min_confidence,
+                   ban_duration_seconds, reason, evidence_base_url
+            from public.punishment_exports
+            where server_id = $1
+            "#,
+        )
+        .bind(server_id)
+        .fetch_optional(db)
+        .await?;
+        Ok(row.map(
+            |(
+                enabled,
+                mode,
+                webhook_url,
+                webhook_token,
+                trigger_severities,
+                min_confidence,
+                ban_duration_seconds,
+                reason,
+                evidence_base_url,
+            )| Self {
+                enabled,
+                mode: ExportMode::parse(&mode).unwrap_or_default(),
+                webhook_url,
+                webhook_token,
+                trigger_severities,
+                min_confidence,
+                ban_duration_seconds,
+                reason,
+                evidence_base_url,
+            },
+        ))
+    }
+
+    pub async fn save(&self, db: &PgPool, server_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            insert into public.punishment_exports
+                (server_id, enabled, mode, webhook_url, webhook_token, trigger_severities,
+                 min_confidence, ban_duration_seconds, reason, evidence_base_url, updated_at)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, now())
+            on conflict (server_id) do update set
+                enabled = excluded.enabled,
+                mode = excluded.mode,
+                webhook_url = excluded.webhook_url,
+                webhook_token = excluded.webhook_token,
+                trigger_severities = excluded.trigger_severities,
+                min_confidence = excluded.min_confidence,
+                ban_duration_seconds = excluded.ban_duration_seconds,
+                reason = excluded.reason,
+                evidence_base_url = excluded.evidence_base_url,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(server_id)
+        .bind(self.enabled)
+        .bind(self.mode.as_str())
+        .bind(self.webhook_url.as_deref())
+        .bind(self.webhook_token.as_deref())
+        .bind(&self.trigger_severities)
+        .bind(self.min_confidence)
+        .bind(self.ban_duration_seconds)
+        .bind(&self.reason)
+        .bind(self.evidence_base_url.as_deref())
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+}
+
+/// A stored finding, as seen by the ban rule.
+#[derive(Debug, Clone)]
+pub struct StoredFinding {
+    pub finding_id: Uuid,
+    pub player_uuid: Uuid,
+    pub detector_name: String,
+    pub severity: String,
+    pub title: String,
+    pub confidence: Option<f64>,
+}
+
+/// A finding that made the rule fire.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BanEvidence {
+    pub finding_id: Uuid,
+    pub detector: String,
+    pub severity: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+}
+
+/// The exported ban, LiteBans field names (also the webhook body).
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BanExport {
+    pub r#type: String,
+    pub source: String,
+    pub server_id: String,
+    /// Player UUID.
+    pub uuid: String,
+    pub name: Option<String>,
+    pub reason: String,
+    pub banned_by_name: String,
+    /// Epoch ms.
+    pub time: i64,
+    /// Epoch ms; -1 for permanent.
+    pub until: i64,
+    pub silent: bool,
+    pub ipban: bool,
+    /// Dashboard page with the player's findings.
+    pub evidence_url: String,
+    pub evidence: Vec<BanEvidence>,
+}
+
+/// `until` for a ban issued at `time_ms`.
+pub fn ban_until(time_ms: i64, duration_seconds: Option<i64>) -> i64 {
+    match duration_seconds {
+        Some(s) if s > 0 => time_ms.saturating_add(s.saturating_mul(1000)),
+        _ => -1,
+    }
+}
+
+/// The ban reason, with `{detectors}` replaced by the triggering detectors.
+pub fn ban_reason(template: &str, evidence: &[BanEvidence]) -> String {
+    let mut detectors: Vec<&str> = evidence.iter().map(|e| e.detector.as_str()).collect();
+    detectors.dedup();
+    template.replace("{detectors}", &detectors.join(", "))
+}
+
+pub fn evidence_url(base: Option<&str>, player_uuid: Uuid) -> String {
+    format!(
+        "{}/dashboard/findings?player={}",
+        base.unwrap_or(DEFAULT_EVIDENCE_BASE_URL)
+            .trim_end_matches('/'),
+        player_uuid
+    )
+}
+
+/// The ban actions for `findings` under `rule`, one per player (before deduplication).
+pub fn ban_exports(
+    rule: &BanRule,
+    server_id: &str,
+    findings: &[StoredFinding],
+    player_names: &BTreeMap<Uuid, String>,
+    now: DateTime<Utc>,
+) -> Vec<BanExport> {
+    let mut by_player: BTreeMap<Uuid, Vec<BanEvidence>> = BTreeMap::new();
+    for f in findings {
+        if !rule.fires(&f.severity, f.confidence) {
+            continue;
+        }
+        by_player
+            .entry(f.player_uuid)
+            .or_default()
+            .push(BanEvidence {
+                finding_id: f.finding_id,
+                detector: f.detector_name.clone(),
+                severity: f.severity.clone(),
+                title: f.title.clone(),
+                confidence: f.confidence,
+            });
+    }
+
+    let time = now.timestamp_millis();
+    by_player
+        .into_iter()
+        .map(|(player, mut evidence)| {
+            evidence.sort_by(|a, b| a.detector.cmp(&b.detector));
+            BanExport {
+                r#type: "ban".to_string(),
+                source: "asyncanticheat".to_string(),
+                server_id: server_id.to_string(),
+                uuid: player.to_string(),
+                name: player_names.get(&player).cloned(),
+                reason: ban_reason(&rule.reason, &evidence),
+                banned_by_name: EXECUTOR_NAME.to_string(),
+                time,
+                until: ban_until(time, rule.ban_duration_seconds),
+                silent: false,
+                ipban: false,
+                evidence_url: evidence_url(rule.evidence_base_url.as_deref(), player),
+                evidence,
+            }
+        })
+        .collect()
+}
+
+/// Apply the server's ban rule to freshly stored findings and export the resulting bans.
+pub async fn export_bans(state: &AppState, server_id: &str, findings: &[StoredFinding]) {
+    let rule = match BanRule::load(&state.db, server_id).await {
+        Ok(Some(rule)) if rule.enabled => rule,
+        Ok(_) => return,
+        Err(e) => {
+            tracing::warn!(server_id = %server_id, "ban rule lookup failed: {:?}", e);
+            return;
+        }
+    };
+    if !findings
+        .iter()
+        .any(|f| rule.fires(&f.severity, f.confidence))
+    {
+        return;
+    }
+
+    let players: Vec<Uuid> = findings.iter().map(|f| f.player_uuid).collect();
+    let names: Vec<(Uuid, String)> = sqlx::query_as(
+        r#"
+        select player_uuid, player_name from public.server_players
+        where server_id = $1 and player_uuid = any($2)
+        "#,
+    )
+    .bind(server_id)
+    .bind(&players)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+    let names: BTreeMap<Uuid, String> = names.into_iter().collect();
+
+    for ban in ban_exports(&rule, server_id, findings, &names, Utc::now()) {
+        match record(&state.db, &rule, &ban).await {
+            Ok(Some(action_id)) => {
+                tracing::info!(
+                    server_id = %server_id,
+                    player_uuid = %ban.uuid,
+                    mode = rule.mode.as_str(),
+                    "ban exported"
+                );
+                if rule.mode == ExportMode::Webhook {
+                    deliver(state, &rule, action_id, &ban).await;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => tracing::error!("ban export failed: {:?}", e),
+        }
+    }
+}
+
+/// Store a ban action unless the player was exported within [`DEDUPE_HOURS`].
+async fn record(db: &PgPool, rule: &BanRule, ban: &BanExport) -> Result<Option<i64>, sqlx::Error> {
+    let delivery = match rule.mode {
+        ExportMode::Queue => "queued",
+        ExportMode::Webhook => "pending",
+    };
+    sqlx::query_scalar(
+        r#"
+        insert into public.punishment_actions
+            (server_id, uuid, player_name, reason, banned_by_name, time, until, silent, ipban,
+             active, evidence_url, evidence, mode, delivery)
+        select $1, $2, $3, $4, $5, $6, $7, $8, $9, true, $10, $11, $12, $13
+        where not exists (
+            select 1 from public.punishment_actions
+            where server_id = $1 and uuid = $2
+              and created_at > now() - make_interval(hours => $14)
+        )
+        returning id
+        "#,
+    )
+    .bind(&ban.server_id)
+    .bind(&ban.uuid)
+    .bind(ban.name.as_deref())
+    .bind(&ban.reason)
+    .bind(&ban.banned_by_name)
+    .bind(ban.time)
+    .bind(ban.until)
+    .bind(ban.silent)
+    .bind(ban.ipban)
+    .bind(&ban.evidence_url)
+    .bind(sqlx::types::Json(&ban.evidence))
+    .bind(rule.mode.as_str())
+    .bind(delivery)
+    .bind(DEDUPE_HOURS as i32)
+    .fetch_optional(db)
+    .await
+}
+
+async fn deliver(state: &AppState, rule: &BanRule, action_id: i64, ban: &BanExport) {
+    let Some(url) = rule.webhook_url.as_deref() else {
+        return;
+    };
+    let mut request = state
+        .http
+        .post(url)
+        .json(ban)
+        .timeout(std::time::Duration::from_secs(5));
+    if let Some(token) = rule.webhook_token.as_deref() {
+        request = request.bearer_auth(token);
+    }
+    let error = match request.send().await {
+        Ok(response) if response.status().is_success() => None,
+        Ok(response) => Some(format!("HTTP {}", response.status())),
+        Err(e) => Some(e.to_string()),
+    };
+    if let Some(ref e) = error {
+        tracing::warn!(server_id = %ban.server_id, error = %e, "ban webhook failed");
+    }
+
+    let res = sqlx::query(
+        r#"
+        update public.punishment_actions
+        set delivery = $2, delivered_at = case when $3::text is null then now() end, last_error = $3
+        where id = $1
+        "#,
+    )
+    .bind(action_id)
+    .bind(if error.is_none() { "sent" } else { "failed" })
+    .bind(error.as_deref())
+    .execute(&state.db)
+    .await;
+    if let Err(e) = res {
+        tracing::error!("ban delivery update failed: {:?}", e);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PunishmentAction {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    pub uuid: String,
+    pub player_name: Option<String>,
+    pub reason: String,
+    pub time: i64,
+    pub until: i64,
+    pub evidence_url: String,
+    pub evidence: Value,
+    /// queue | webhook
+    pub mode: String,
+    /// queued | consumed (queue mode); pending | sent | failed (webhook mode)
+    pub delivery: String,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+pub async fn list(
+    db: &PgPool,
+    server_id: &str,
+    limit: i64,
+) -> Result<Vec<PunishmentAction>, sqlx::Error> {
+    let rows: Vec<(
+        i64,
+        DateTime<Utc>,
+        String,
+        Option<String>,
+        String,
+        i64,
+        i64,
+        String,
+        Value,
+        String,
+        String,
+        Option<DateTime<Utc>>,
+        Option<String>,
+    )> = sqlx::query_as(
+        r#"
+        select id, created_at, uuid, player_name, reason, time, until, evidence_url, evidence,
+               mode, delivery, delivered_at, last_error
+        from public.punishment_actions
+        where server_id = $1
+        order by created_at desc
+        limit $2
+        "#,
+    )
+    .bind(server_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                created_at,
+                uuid,
+                player_name,
+                reason,
+                time,
+                until,
+                evidence_url,
+                evidence,
+                mode,
+                delivery,
+                delivered_at,
+                last_error,
+            )| PunishmentAction {
+                id,
+                created_at,
+                uuid,
+                player_name,
+                reason,
+                time,
+                until,
+                evidence_url,
+                evidence,
+                mode,
+                delivery,
+                delivered_at,
+                last_error,
+            },
+        )
+        .collect())
+}
@@ -0,0 +1,503 @@
+//! Replay of recorded batches through modules, driven by observations.
+//!
+//! `POST /admin/observations/:id/replay` loads the batches received during the observation
+//! window and sends them through one module (or all enabled ones) again. Resulting findings are
+//! stored in `observation_replay_findings`, tagged with the observation, and never touch the
+//! live findings table. This answers "does the new module version catch this recorded cheater?".
+//!
+//! HTTP modules get each batch under a `replay-<replay_id>.<module_id>` session id; their
+//! findings callbacks for such sessions are routed here by `callbacks::store_findings`.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::routes::callbacks::FindingIn;
+use crate::{
+    checks, clock_skew, error::ApiError, module_pipeline, transforms, wasm_runtime, AppState,
+};
+
+pub const SESSION_PREFIX: &str = "replay-";
+
+/// Replays cover at most this many batches (oldest first).
+pub const MAX_REPLAY_BATCHES: i64 = 500;
+
+/// Batches are indexed by receive time; allow for plugin flush delay around the window.
+const WINDOW_SLACK_SECONDS: i64 = 60;
+
+/// Errors kept in the replay report.
+const MAX_REPORTED_ERRORS: usize = 20;
+
+pub fn replay_session(replay_id: Uuid, module_id: Uuid) -> String {
+    format!(
+        "{}{}.{}",
+        SESSION_PREFIX,
+        replay_id.simple(),
+        module_id.simple()
+    )
+}
+
+/// Parse `(replay_id, module_id)` back out of a replay session id.
+pub fn parse_replay_session(session_id: &str) -> Option<(Uuid, Uuid)> {
+    let rest = session_id.strip_prefix(SESSION_PREFIX)?;
+    let (replay, module) = rest.split_once('.')?;
+    Some((Uuid::parse_str(replay).ok()?, Uuid::parse_str(module).ok()?))
+}
+
+#[derive(Debug, Clone)]
+struct ReplayModule {
+    id: Uuid,
+    name: String,
+    base_url: String,
+    transform: String,
+}
+
+#[derive(Debug, Clone)]
+struct ReplayBatch {
+    id: Uuid,
+    session_id: String,
+    s3_key: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayStarted {
+    pub replay_id: Uuid,
+    pub observation_id: Uuid,
+    pub batches: usize,
+    pub modules: Vec<String>,
+}
+
+/// Queue a replay for an observation and run it in the background.
+pub async fn start(
+    state: &AppState,
+    observation_id: Uuid,
+    module_id: Option<Uuid>,
+) -> Result<ReplayStarted, ApiError> {
+    let observation: Option<(
+        String,
+        Option<DateTime<Utc>>,
+        Option<DateTime<Utc>>,
+        Option<String>,
+    )> = sqlx::query_as(
+        r#"
+        select server_id, started_at, ended_at, session_id
+        from public.cheat_observations
+        where id = $1
+        "#,
+    )
+    .bind(observation_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("replay observation lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let Some((server_id, started_at, ended_at, obs_session_id)) = observation else {
+        return Err(ApiError::NotFound(format!(
+            "observation {} not found",
+            observation_id
+        )));
+    };
+    let Some(started_at) = started_at else {
+        return Err(ApiError::BadRequest(
+            "observation has no started_at; nothing to replay".to_string(),
+        ));
+    };
+
+    let modules: Vec<(Uuid, String, String, String)> = sqlx::query_as(
+        r#"
+        select id, name, base_url, transform
+        from public.server_modules
+        where server_id = $1
+          and (($2::uuid is null and enabled = true) or id = $2)
+        order by name asc
+        "#,
+    )
+    .bind(&server_id)
+    .bind(module_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("replay modules lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    if modules.is_empty() {
+        return Err(ApiError::NotFound(match module_id {
+            Some(id) => format!("module {} not found for server {}", id, server_id),
+            None => format!("server {} has no enabled modules", server_id),
+        }));
+    }
+    let modules: Vec<ReplayModule> = modules
+        .into_iter()
+        .map(|(id, name, base_url, transform)| ReplayModule {
+            id,
+            name,
+            base_url,
+            transform,
+        })
+        .collect();
+
+    // Restrict to the observed session when the plugin recorded it.
+    let batches: Vec<(Uuid, String, String)> = sqlx::query_as(
+        r#"
+        select id, session_id, s3_key
+        from public.batch_index
+        where server_id = $1
+          and received_at >= $2 - make_interval(secs => $4)
+          and received_at <= coalesce($3, now()) + make_interval(secs => $4)
+          and ($5::text is null or session_id = $5)
+          and not quarantined
+        order by received_at asc
+        limit $6
+        "#,
+    )
+    .bind(&server_id)
+    .bind(started_at)
+    .bind(ended_at)
+    .bind(WINDOW_SLACK_SECONDS as f64)
+    .bind(obs_session_id.as_deref())
+    .bind(MAX_REPLAY_BATCHES)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("replay batch lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    if batches.is_empty() {
+        return Err(ApiError::BadRequest(
+            "no batches cover the observation window".to_string(),
+        ));
+    }
+    let batches: Vec<ReplayBatch> = batches
+        .into_iter()
+        .map(|(id, session_id, s3_key)| ReplayBatch {
+            id,
+            session_id,
+            s3_key,
+        })
+        .collect();
+
+    let replay_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        insert into public.observation_replays
+            (id, observation_id, server_id, module_id, status, batches_total)
+        values ($1, $2, $3, $4, 'running', $5)
+        "#,
+    )
+    .bind(replay_id)
+    .bind(observation_id)
+    .bind(&server_id)
+    .bind(module_id)
+    .bind(batches.len() as i32)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("replay insert failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let started = ReplayStarted {
+        replay_id,
+        observation_id,
+        batches: batches.len(),
+        modules: modules.iter().map(|m| m.name.clone()).collect(),
+    };
+
+    let run_state = state.clone();
+    tokio::spawn(async move {
+        run(run_state, replay_id, server_id, batches, modules).await;
+    });
+
+    Ok(started)
+}
+
+async fn run(
+    state: AppState,
+    replay_id: Uuid,
+    server_id: String,
+    batches: Vec<ReplayBatch>,
+    modules: Vec<ReplayModule>,
+) {
+    let mut replayed = 0i32;
+    let mut missing = 0i32;
+    let mut errors: Vec<String> = Vec::new();
+    let push_error = |errors: &mut Vec<String>, e: String| {
+        tracing::warn!(replay_id = %replay_id, "replay error: {}", e);
+        if errors.len() < MAX_REPORTED_ERRORS {
+            errors.push(e);
+        }
+    };
+
+    for batch in &batches {
+        let raw = match state.object_store.get_batch(&batch.s3_key).await {
+            Ok(raw) => raw,
+            Err(e) => {
+                missing += 1;
+                push_error(
+                    &mut errors,
+                    format!("batch {} unavailable: {}", batch.id, e),
+                );
+                continue;
+            }
+        };
+        replayed += 1;
+
+        let clock_skew_ms = if modules
+            .iter()
+            .any(|m| transforms::wants_server_time(&m.transform))
+        {
+            clock_skew::for_batch(&state.db, batch.id)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!(replay_id = %replay_id, "clock skew lookup failed: {:?}", e);
+                    None
+                })
+        } else {
+            None
+        };
+
+        for m in &modules {
+            let local = if let Some(engine) = checks::inproc_engine(&m.base_url) {
+                Some(checks::run(engine, &raw).map_err(|e| e.to_string()))
+            } else if let Some(wasm_id) = wasm_runtime::wasm_module_id(&m.base_url) {
+                Some(
+                    wasm_runtime::run(&state, wasm_id, &m.transform, &raw, clock_skew_ms)
+                        .await
+                        .map_err(|e| e.to_string()),
+                )
+            } else {
+                None
+            };
+
+            match local {
+                Some(Ok(findings)) => {
+                    record_findings(&state, replay_id, m.id, &findings).await;
+                }
+                Some(Err(e)) => push_error(&mut errors, format!("{}: {}", m.name, e)),
+                None => {
+                    let payload =
+                        match transforms::apply_transform_with(&m.transform, &raw, clock_skew_ms) {
+                            Ok(p) => p,
+                            Err(e) => {
+                                push_error(
+                                    &mut errors,
+                                    format!("{}: transform failed: {}", m.name, e),
+                                );
+                                continue;
+                            }
+                        };
+                    let resp = module_pipeline::post_ingest(
+                        &state.http,
+                        &m.base_url,
+                        &server_id,
+                        &replay_session(replay_id, m.id),
+                        batch.id,
+                        Some(m.id),
+                        Some(&batch.s3_key),
+                        payload,
+                    )
+                    .await;
+                    match resp {
+                        Ok(r) if r.status().is_success() => {}
+                        Ok(r) => push_error(
+                            &mut errors,
+                            format!("{}: module returned http {}", m.name, r.status()),
+                        ),
+                        Err(e) => push_error(&mut errors, format!("{}: {}", m.name, e)),
+                    }
+                }
+            }
+        }
+        tracing::debug!(replay_id = %replay_id, batch_id = %batch.id, session_id = %batch.session_id, "batch replayed");
+    }
+
+    let status = if replayed == 0 { "failed" } else { "completed" };
+    let res = sqlx::query(
+        r#"
+        update public.observation_replays
+        set status = $2, batches_replayed = $3, batches_missing = $4,
+            errors = $5, completed_at = now()
+        where id = $1
+        "#,
+    )
+    .bind(replay_id)
+    .bind(status)
+    .bind(replayed)
+    .bind(missing)
+    .bind(sqlx::types::Json(&errors))
+    .execute(&state.db)
+    .await;
+    if let Err(e) = res {
+        tracing::error!(replay_id = %replay_id, "replay status update failed: {:?}", e);
+    }
+
+    tracing::info!(
+        replay_id = %replay_id,
+        replayed = replayed,
+        missing = missing,
+        errors = errors.len(),
+        "observation replay finished"
+    );
+}
+
+/// Store replay findings tagged with the replay's observation (best-effort).
+pub async fn record_findings(
+    state: &AppState,
+    replay_id: Uuid,
+    module_id: Uuid,
+    findings: &[FindingIn],
+) -> usize {
+    let mut stored = 0;
+    for f in findings {
+        if f.detector_name.trim().is_empty() {
+            continue;
+        }
+        let res = sqlx::query(
+            r#"
+            insert into public.observation_replay_findings
+                (replay_id, observation_id, module_id, player_uuid, detector_name,
+                 detector_version, severity, title, evidence_json)
+            select $1, r.observation_id, $2, $3, $4, $5, $6, $7, $8
+            from public.observation_replays r
+            where r.id = $1
+            "#,
+        )
+        .bind(replay_id)
+        .bind(module_id)
+        .bind(f.player_uuid)
+        .bind(f.detector_name.trim())
+        .bind(f.detector_version.as_deref())
+        .bind(f.severity.as_deref().unwrap_or("info"))
+        .bind(f.title.trim())
+        .bind(f.evidence_json.as_ref().map(sqlx::types::Json))
+        .execute(&state.db)
+        .await;
+        match res {
+            Ok(r) => stored += r.rows_affected() as usize,
+            Err(e) => {
+                tracing::warn!(replay_id = %replay_id, "replay finding insert failed: {:?}", e);
+            }
+        }
+    }
+    stored
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayDetectorSummary {
+    pub module_id: Uuid,
+    pub detector_name: String,
+    pub findings: i64,
+    /// Findings for the observed player.
+    pub player_findings: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayReport {
+    pub replay_id: Uuid,
+    pub observation_id: Uuid,
+    pub module_id: Option<Uuid>,
+    pub status: String,
+    pub batches_total: i32,
+    pub batches_replayed: i32,
+    pub batches_missing: i32,
+    pub errors: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Whether any module flagged the observed player.
+    pub caught: bool,
+    pub detectors: Vec<ReplayDetectorSummary>,
+}
+
+/// Load a replay with per-detector finding counts.
+pub async fn report(state: &AppState, replay_id: Uuid) -> Result<ReplayReport, ApiError> {
+    let row: Option<(
+        Uuid,
+        Option<Uuid>,
+        String,
+        i32,
+        i32,
+        i32,
+        Option<serde_json::Value>,
+        DateTime<Utc>,
+        Option<DateTime<Utc>>,
+    )> = sqlx::query_as(
+        r#"
+        select observation_id, module_id, status, batches_total, batches_replayed,
+               batches_missing, errors, created_at, completed_at
+        from public.observation_replays
+        where id = $1
+        "#,
+    )
+    .bind(replay_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("replay lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let Some((
+        observation_id,
+        module_id,
+        status,
+        batches_total,
+        batches_replayed,
+        batches_missing,
+        errors,
+        created_at,
+        completed_at,
+    )) = row
+    else {
+        return Err(ApiError::NotFound(format!(
+            "replay {} not found",
+            replay_id
+        )));
+    };
+
+    let detectors: Vec<(Uuid, String, i64, i64)> = sqlx::query_as(
+        r#"
+        select f.module_id, f.detector_name, count(*),
+               count(*) filter (where f.player_uuid = o.player_uuid)
+        from public.observation_replay_findings f
+        join public.cheat_observations o on o.id = f.observation_id
+        where f.replay_id = $1
+        group by f.module_id, f.detector_name
+        order by 4 desc, 3 desc
+        "#,
+    )
+    .bind(replay_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("replay findings summary failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let detectors: Vec<ReplayDetectorSummary> = detectors
+        .into_iter()
+        .map(
+            |(module_id, detector_name, findings, player_findings)| ReplayDetectorSummary {
+                module_id,
+                detector_name,
+                findings,
+                player_findings,
+            },
+        )
+        .collect();
+
+    Ok(ReplayReport {
+        replay_id,
+        observation_id,
+        module_id,
+        status,
+        batches_total,
+        batches_replayed,
+        batches_missing,
+        errors,
+        created_at,
+        completed_at,
+        caught: detectors.iter().any(|d| d.player_findings > 0),
+        detectors,
+    })
+}
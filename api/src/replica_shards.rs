@@ -0,0 +1,136 @@
+//! Per-server ownership across API replicas.
+//!
+//! Ingest can land on any replica, but queued per-server work (shed batches waiting for
+//! dispatch, see `dispatch_shedding`) should have a single owner so retries and any per-server
+//! limits are applied in one place. Each replica heartbeats a row in `api_replicas` every
+//! [`HEARTBEAT_INTERVAL_SECONDS`]; replicas silent for [`STALE_AFTER_SECONDS`] are dropped.
+//! Servers are assigned to the live replicas by rendezvous hashing, so when a replica joins or
+//! leaves only the servers it gains or loses move.
+//!
+//! Until the first heartbeat succeeds (or if this replica can't reach the table) the member list
+//! is empty and the replica treats every server as its own, which is also the single-replica
+//! behaviour.
+
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+use crate::task_lock::instance_id;
+use crate::AppState;
+
+pub const HEARTBEAT_INTERVAL_SECONDS: u64 = 15;
+pub const STALE_AFTER_SECONDS: i32 = 60;
+
+/// The replica owning `server_id` among `members` (highest hash of member and server wins).
+pub fn owner<'a>(members: &'a [String], server_id: &str) -> Option<&'a str> {
+    members
+        .iter()
+        .max_by_key(|member| {
+            let digest = Sha256::new()
+                .chain_update(member.as_bytes())
+                .chain_update([0u8])
+                .chain_update(server_id.as_bytes())
+                .finalize();
+            let mut score = [0u8; 8];
+            score.copy_from_slice(&digest[..8]);
+            (u64::from_be_bytes(score), member.as_str())
+        })
+        .map(String::as_str)
+}
+
+/// Live replicas as of the last heartbeat.
+#[derive(Debug, Clone, Default)]
+pub struct ShardMap(Arc<RwLock<Vec<String>>>);
+
+impl ShardMap {
+    /// Whether this replica owns `server_id`'s queued work.
+    pub fn owns(&self, server_id: &str) -> bool {
+        let members = self.0.read().unwrap_or_else(|e| e.into_inner());
+        match owner(&members, server_id) {
+            Some(owner) => owner == instance_id(),
+            None => true,
+        }
+    }
+
+    pub fn members(&self) -> Vec<String> {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn set(&self, members: Vec<String>) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = members;
+    }
+}
+
+/// Record this replica as alive, drop stale ones and refresh the member list.
+pub async fn heartbeat_tick(state: AppState) {
+    let res = async {
+        sqlx::query(
+            r#"
+            insert into public.api_replicas (instance_id, started_at, heartbeat_at)
+            values ($1, now(), now())
+            on conflict (instance_id) do update set heartbeat_at = now()
+            "#,
+        )
+        .bind(instance_id())
+        .execute(&state.db)
+        .await?;
+        sqlx::query(
+            "delete from public.api_replicas where heartbeat_at < now() - make_interval(secs => $1)",
+        )
+        .bind(STALE_AFTER_SECONDS as f64)
+        .execute(&state.db)
+        .await?;
+        sqlx::query_scalar::<_, String>(
+            "select instance_id from public.api_replicas order by instance_id asc",
+        )
+        .fetch_all(&state.db)
+        .await
+    }
+    .await;
+
+    match res {
+        Ok(members) => {
+            let previous = state.shards.members();
+            if previous != members {
+                tracing::info!(replicas = ?members, "api replica membership changed");
+            }
+            state.shards.set(members);
+        }
+        Err(e) => tracing::warn!("replica heartbeat failed: {:?}", e),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Replica {
+    pub instance_id: String,
+    pub started_at: DateTime<Utc>,
+    pub heartbeat_at: DateTime<Utc>,
+    /// Whether this is the replica answering the request.
+    pub this_replica: bool,
+}
+
+pub async fn list(state: &AppState) -> Result<Vec<Replica>, sqlx::Error> {
+    let rows: Vec<(String, DateTime<Utc>, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        select instance_id, started_at, heartbeat_at
+        from public.api_replicas
+        where heartbeat_at >= now() - make_interval(secs => $1)
+        order by instance_id asc
+        "#,
+    )
+    .bind(STALE_AFTER_SECONDS as f64)
+    .fetch_all(&state.db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(instance_id, started_at, heartbeat_at)| Replica {
+            this_replica: instance_id == crate::task_lock::instance_id(),
+            instance_id,
+            started_at,
+            heartbeat_at,
+        })
+        .collect())
+}
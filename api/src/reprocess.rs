@@ -0,0 +1,537 @@
+//! Reprocessing of historical batches after a transform or module fix.
+//!
+//! `POST /admin/reprocess` queues a `reprocess_jobs` row for one module and a received-at range.
+//! A background worker (`worker_tick`) claims queued jobs and walks `batch_index` in pages,
+//! pulling each raw batch from the object store and running it through the module again (with
+//! an optional transform override). Progress and a resume cursor are saved after every page, so
+//! a restarted API picks a stale running job back up where it stopped.
+//!
+//! Unlike observation replays, results are live: in-process/WASM findings are stored through
+//! `callbacks::store_findings`, and HTTP modules receive the batch under its original session id
+//! so their callbacks land in `findings` as usual (aggregated into the current minute bucket).
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::routes::callbacks::{self, PostFindingsRequest};
+use crate::{
+    checks, clock_skew, error::ApiError, module_pipeline, transforms, wasm_runtime, AppState,
+};
+
+/// How often the worker looks for queued jobs.
+pub const WORKER_INTERVAL_SECONDS: u64 = 10;
+
+/// Batches loaded (and progress saved) per page.
+const PAGE_SIZE: i64 = 100;
+
+/// Widest range a single job may cover.
+pub const MAX_RANGE_DAYS: i64 = 31;
+
+/// A running job whose progress hasn't moved for this long is considered abandoned.
+const STALE_AFTER_SECONDS: f64 = 300.0;
+
+/// Errors kept in the job row.
+const MAX_REPORTED_ERRORS: usize = 20;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReprocessJob {
+    pub id: Uuid,
+    pub server_id: String,
+    pub module_id: Uuid,
+    /// Transform override (None = the module's configured transform).
+    pub transform: Option<String>,
+    pub range_start: DateTime<Utc>,
+    pub range_end: DateTime<Utc>,
+    /// queued | running | completed | failed | cancelled
+    pub status: String,
+    pub batches_total: i32,
+    pub batches_processed: i32,
+    pub batches_missing: i32,
+    pub findings: i32,
+    pub errors: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+type JobRow = (
+    Uuid,
+    String,
+    Uuid,
+    Option<String>,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    String,
+    i32,
+    i32,
+    i32,
+    i32,
+    Option<serde_json::Value>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+);
+
+const JOB_COLUMNS: &str = "id, server_id, module_id, transform, range_start, range_end, status, \
+     batches_total, batches_processed, batches_missing, findings, errors, created_at, \
+     started_at, completed_at";
+
+fn job_from_row(row: JobRow) -> ReprocessJob {
+    let (
+        id,
+        server_id,
+        module_id,
+        transform,
+        range_start,
+        range_end,
+        status,
+        batches_total,
+        batches_processed,
+        batches_missing,
+        findings,
+        errors,
+        created_at,
+        started_at,
+        completed_at,
+    ) = row;
+    ReprocessJob {
+        id,
+        server_id,
+        module_id,
+        transform,
+        range_start,
+        range_end,
+        status,
+        batches_total,
+        batches_processed,
+        batches_missing,
+        findings,
+        errors,
+        created_at,
+        started_at,
+        completed_at,
+    }
+}
+
+/// Validate and queue a job; the worker picks it up on its next tick.
+pub async fn create(
+    state: &AppState,
+    module_id: Uuid,
+    transform: Option<&str>,
+    range_start: DateTime<Utc>,
+    range_end: DateTime<Utc>,
+) -> Result<ReprocessJob, ApiError> {
+    if range_end <= range_start {
+        return Err(ApiError::BadRequest("to must be after from".to_string()));
+    }
+    if range_end - range_start > Duration::days(MAX_RANGE_DAYS) {
+        return Err(ApiError::BadRequest(format!(
+            "range too wide (max {} days)",
+            MAX_RANGE_DAYS
+        )));
+    }
+    let transform = transform.map(str::trim).filter(|t| !t.is_empty());
+    if let Some(t) = transform {
+        if !transforms::is_supported(t) {
+            return Err(ApiError::BadRequest(format!(
+                "unsupported transform: {}",
+                t
+            )));
+        }
+    }
+
+    let server_id: Option<String> =
+        sqlx::query_scalar("select server_id from public.server_modules where id = $1")
+            .bind(module_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!("reprocess module lookup failed: {:?}", e);
+                ApiError::Internal
+            })?;
+    let Some(server_id) = server_id else {
+        return Err(ApiError::NotFound(format!(
+            "module {} not found",
+            module_id
+        )));
+    };
+
+    let batches_total: i64 = sqlx::query_scalar(
+        r#"
+        select count(*) from public.batch_index
+        where server_id = $1 and received_at >= $2 and received_at < $3 and not quarantined
+        "#,
+    )
+    .bind(&server_id)
+    .bind(range_start)
+    .bind(range_end)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("reprocess batch count failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    if batches_total == 0 {
+        return Err(ApiError::BadRequest(
+            "no batches were received in that range".to_string(),
+        ));
+    }
+
+    let row: JobRow = sqlx::query_as(&format!(
+        r#"
+        insert into public.reprocess_jobs
+            (server_id, module_id, transform, range_start, range_end, batches_total)
+        values ($1, $2, $3, $4, $5, $6)
+        returning {}
+        "#,
+        JOB_COLUMNS
+    ))
+    .bind(&server_id)
+    .bind(module_id)
+    .bind(transform)
+    .bind(range_start)
+    .bind(range_end)
+    .bind(batches_total.min(i32::MAX as i64) as i32)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("reprocess job insert failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let job = job_from_row(row);
+    tracing::info!(job_id = %job.id, module_id = %module_id, batches = batches_total, "reprocess job queued");
+    Ok(job)
+}
+
+pub async fn get(state: &AppState, job_id: Uuid) -> Result<ReprocessJob, ApiError> {
+    let row: Option<JobRow> = sqlx::query_as(&format!(
+        "select {} from public.reprocess_jobs where id = $1",
+        JOB_COLUMNS
+    ))
+    .bind(job_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("reprocess job lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    row.map(job_from_row)
+        .ok_or_else(|| ApiError::NotFound(format!("reprocess job {} not found", job_id)))
+}
+
+/// Stop a queued or running job (the worker notices at the next page).
+pub async fn cancel(state: &AppState, job_id: Uuid) -> Result<ReprocessJob, ApiError> {
+    let res = sqlx::query(
+        r#"
+        update public.reprocess_jobs
+        set status = 'cancelled', completed_at = now(), updated_at = now()
+        where id = $1 and status in ('queued', 'running')
+        "#,
+    )
+    .bind(job_id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("reprocess cancel failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let job = get(state, job_id).await?;
+    if res.rows_affected() == 0 {
+        return Err(ApiError::Conflict(format!(
+            "reprocess job {} is already {}",
+            job_id, job.status
+        )));
+    }
+    Ok(job)
+}
+
+struct ClaimedJob {
+    id: Uuid,
+    server_id: String,
+    module_id: Uuid,
+    transform: Option<String>,
+    range_end: DateTime<Utc>,
+    cursor: (DateTime<Utc>, Uuid),
+    errors: Vec<String>,
+}
+
+/// Claim and run at most one job (queued, or running but abandoned).
+pub async fn worker_tick(state: AppState) {
+    let claimed: Result<
+        Option<(
+            Uuid,
+            String,
+            Uuid,
+            Option<String>,
+            DateTime<Utc>,
+            DateTime<Utc>,
+            Option<DateTime<Utc>>,
+            Option<Uuid>,
+            Option<serde_json::Value>,
+        )>,
+        _,
+    > = sqlx::query_as(
+        r#"
+        update public.reprocess_jobs
+        set status = 'running', started_at = coalesce(started_at, now()), updated_at = now()
+        where id = (
+            select id from public.reprocess_jobs
+            where status = 'queued'
+               or (status = 'running' and updated_at < now() - make_interval(secs => $1))
+            order by created_at asc
+            limit 1
+            for update skip locked
+        )
+        returning id, server_id, module_id, transform, range_start, range_end,
+                  cursor_received_at, cursor_batch_id, errors
+        "#,
+    )
+    .bind(STALE_AFTER_SECONDS)
+    .fetch_optional(&state.db)
+    .await;
+
+    let job = match claimed {
+        Ok(Some((
+            id,
+            server_id,
+            module_id,
+            transform,
+            range_start,
+            range_end,
+            cursor_received_at,
+            cursor_batch_id,
+            errors,
+        ))) => ClaimedJob {
+            id,
+            server_id,
+            module_id,
+            transform,
+            range_end,
+            cursor: (
+                cursor_received_at.unwrap_or(range_start),
+                cursor_batch_id.unwrap_or(Uuid::nil()),
+            ),
+            errors: errors
+                .and_then(|e| serde_json::from_value(e).ok())
+                .unwrap_or_default(),
+        },
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!("reprocess job claim failed: {:?}", e);
+            return;
+        }
+    };
+
+    let job_id = job.id;
+    let status = match run(&state, job).await {
+        Ok(status) => status,
+        Err(e) => {
+            tracing::error!(job_id = %job_id, "reprocess job failed: {}", e);
+            "failed"
+        }
+    };
+    if status != "cancelled" {
+        let res = sqlx::query(
+            r#"
+            update public.reprocess_jobs
+            set status = $2, completed_at = now(), updated_at = now()
+            where id = $1 and status = 'running'
+            "#,
+        )
+        .bind(job_id)
+        .bind(status)
+        .execute(&state.db)
+        .await;
+        if let Err(e) = res {
+            tracing::error!(job_id = %job_id, "reprocess status update failed: {:?}", e);
+        }
+    }
+    tracing::info!(job_id = %job_id, status = status, "reprocess job finished");
+}
+
+/// Process the job page by page; returns the final status.
+async fn run(state: &AppState, mut job: ClaimedJob) -> Result<&'static str, String> {
+    let module: Option<(String, String, String)> =
+        sqlx::query_as("select name, base_url, transform from public.server_modules where id = $1")
+            .bind(job.module_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| format!("module lookup failed: {}", e))?;
+    let Some((name, base_url, module_transform)) = module else {
+        return Err("module was deleted".to_string());
+    };
+    let transform = job.transform.clone().unwrap_or(module_transform);
+
+    loop {
+        let page: Vec<(Uuid, String, String, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            select id, session_id, s3_key, received_at
+            from public.batch_index
+            where server_id = $1
+              and (received_at, id) > ($2, $3)
+              and received_at < $4
+              and not quarantined
+            order by received_at asc, id asc
+            limit $5
+            "#,
+        )
+        .bind(&job.server_id)
+        .bind(job.cursor.0)
+        .bind(job.cursor.1)
+        .bind(job.range_end)
+        .bind(PAGE_SIZE)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| format!("batch page query failed: {}", e))?;
+        if page.is_empty() {
+            return Ok("completed");
+        }
+
+        let (mut processed, mut missing, mut findings) = (0i32, 0i32, 0i32);
+        for (batch_id, session_id, s3_key, received_at) in &page {
+            job.cursor = (*received_at, *batch_id);
+            let raw = match state.object_store.get_batch(s3_key).await {
+                Ok(raw) => raw,
+                Err(e) => {
+                    missing += 1;
+                    push_error(&mut job, format!("batch {} unavailable: {}", batch_id, e));
+                    continue;
+                }
+            };
+            processed += 1;
+
+            match process_batch(
+                state,
+                &job.server_id,
+                job.module_id,
+                &base_url,
+                &transform,
+                session_id,
+                *batch_id,
+                s3_key,
+                raw,
+            )
+            .await
+            {
+                Ok(n) => findings += n as i32,
+                Err(e) => push_error(&mut job, format!("{}: batch {}: {}", name, batch_id, e)),
+            }
+        }
+
+        // Save progress; a cancelled job no longer matches and stops here.
+        let still_running: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            update public.reprocess_jobs
+            set batches_processed = batches_processed + $2,
+                batches_missing = batches_missing + $3,
+                findings = findings + $4,
+                cursor_received_at = $5,
+                cursor_batch_id = $6,
+                errors = $7,
+                updated_at = now()
+            where id = $1 and status = 'running'
+            returning id
+            "#,
+        )
+        .bind(job.id)
+        .bind(processed)
+        .bind(missing)
+        .bind(findings)
+        .bind(job.cursor.0)
+        .bind(job.cursor.1)
+        .bind(sqlx::types::Json(&job.errors))
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| format!("progress update failed: {}", e))?;
+        if still_running.is_none() {
+            return Ok("cancelled");
+        }
+    }
+}
+
+fn push_error(job: &mut ClaimedJob, e: String) {
+    tracing::warn!(job_id = %job.id, "reprocess error: {}", e);
+    if job.errors.len() < MAX_REPORTED_ERRORS {
+        job.errors.push(e);
+    }
+}
+
+/// Run one batch through the module. Returns the number of findings stored locally (HTTP
+/// modules report theirs through the callback).
+#[allow(clippy::too_many_arguments)]
+async fn process_batch(
+    state: &AppState,
+    server_id: &str,
+    module_id: Uuid,
+    base_url: &str,
+    transform: &str,
+    session_id: &str,
+    batch_id: Uuid,
+    s3_key: &str,
+    raw: Vec<u8>,
+) -> Result<usize, String> {
+    let clock_skew_ms = if transforms::wants_server_time(transform) {
+        clock_skew::for_batch(&state.db, batch_id)
+            .await
+            .map_err(|e| format!("clock skew lookup failed: {}", e))?
+    } else {
+        None
+    };
+
+    let local = if let Some(engine) = checks::inproc_engine(base_url) {
+        let config = checks::CheckConfig::load(&state.db, server_id)
+            .await
+            .map_err(|e| format!("check config lookup failed: {}", e))?;
+        Some(checks::run_with(engine, &raw, &config).map_err(|e| e.to_string()))
+    } else if let Some(wasm_id) = wasm_runtime::wasm_module_id(base_url) {
+        Some(
+            wasm_runtime::run(state, wasm_id, transform, &raw, clock_skew_ms)
+                .await
+                .map_err(|e| e.to_string()),
+        )
+    } else {
+        None
+    };
+
+    if let Some(result) = local {
+        let mut findings = result?;
+        if findings.is_empty() {
+            return Ok(0);
+        }
+        for f in &mut findings {
+            f.evidence_s3_key = Some(s3_key.to_string());
+        }
+        let req = PostFindingsRequest {
+            server_id: server_id.to_string(),
+            module_id: Some(module_id),
+            session_id: Some(session_id.to_string()),
+            batch_id: Some(batch_id),
+            findings,
+        };
+        return callbacks::store_findings(state, &req)
+            .await
+            .map_err(|e| format!("storing findings failed: {:?}", e));
+    }
+
+    let payload = transforms::apply_transform_with(transform, &raw, clock_skew_ms)
+        .map_err(|e| format!("transform '{}' failed: {}", transform, e))?;
+    let resp = module_pipeline::post_ingest(
+        &state.http,
+        base_url,
+        server_id,
+        session_id,
+        batch_id,
+        Some(module_id),
+        Some(s3_key),
+        payload,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("module returned http {}", resp.status()));
+    }
+    Ok(0)
+}
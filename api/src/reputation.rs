@@ -0,0 +1,169 @@
+//! Cross-server player reputation.
+//!
+//! Opt-in on both ends: the subsystem runs only with `REPUTATION_ENABLED`, and only servers with
+//! `servers.reputation_opt_in` contribute findings or may query scores. A background tick
+//! aggregates findings from the lookback window into `player_reputation`:
+//!
+//! - each finding weighs by severity (critical 5, high 3, medium 1.5, low 0.5) times
+//!   `ln(1 + occurrences)`, tripled when a reviewer confirmed it
+//! - false positives and dismissed findings don't count, nor do findings imported from other
+//!   anticheats' logs (`source` set, see `findings_import`): only our own modules' verdicts
+//! - `score = 100 * (1 - exp(-weight / 20))`, so it saturates instead of growing unbounded
+//!
+//! Scores are per player UUID; server identities are not exposed to querying servers.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppState;
+
+/// Weight at which the score reaches ~63.
+pub const SCORE_SCALE: f64 = 20.0;
+
+/// The 0-100 score for an accumulated finding weight.
+pub fn score(weight: f64) -> f64 {
+    100.0 * (1.0 - (-weight.max(0.0) / SCORE_SCALE).exp())
+}
+
+pub async fn aggregate_tick(state: AppState) {
+    if !state.reputation_enabled {
+        return;
+    }
+
+    let started = Utc::now();
+    let res = async {
+        let mut tx = state.db.begin().await?;
+
+        let upserted = sqlx::query(
+            r#"
+            with scored as (
+                select
+                    f.player_uuid,
+                    f.server_id,
+                    f.status,
+                    f.last_seen_at,
+                    (case f.severity
+                        when 'critical' then 5.0
+                        when 'high' then 3.0
+                        when 'medium' then 1.5
+                        when 'low' then 0.5
+                        else 0.0 end)
+                    * (case f.status when 'confirmed' then 3.0 else 1.0 end)
+                    * ln(1 + f.occurrences) as weight
+                from public.findings f
+                join public.servers s on s.id = f.server_id and s.reputation_opt_in = true
+                where f.player_uuid is not null
+                  and f.last_seen_at >= now() - make_interval(days => $1)
+                  and f.status not in ('false_positive', 'dismissed')
+                  and f.source is null
+            )
+            insert into public.player_reputation
+                (player_uuid, score, weight, servers_flagged, findings, confirmed_findings,
+                 last_flagged_at, updated_at)
+            select
+                player_uuid,
+                100.0 * (1.0 - exp(-sum(weight) / $2)),
+                sum(weight),
+                count(distinct server_id),
+                count(*),
+                count(*) filter (where status = 'confirmed'),
+                max(last_seen_at),
+                now()
+            from scored
+            group by player_uuid
+            having sum(weight) > 0
+            on conflict (player_uuid) do update set
+                score = excluded.score,
+                weight = excluded.weight,
+                servers_flagged = excluded.servers_flagged,
+                findings = excluded.findings,
+                confirmed_findings = excluded.confirmed_findings,
+                last_flagged_at = excluded.last_flagged_at,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(state.reputation_lookback_days as i32)
+        .bind(SCORE_SCALE)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        // Players whose history aged out (or whose findings were all rejected).
+        let removed = sqlx::query("delete from public.player_reputation where updated_at < $1")
+            .bind(started)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>((upserted, removed))
+    }
+    .await;
+
+    match res {
+        Ok((upserted, removed)) => {
+            tracing::info!(
+                players = upserted,
+                removed = removed,
+                "reputation aggregation finished"
+            );
+        }
+        Err(e) => tracing::error!("reputation aggregation failed: {:?}", e),
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlayerReputation {
+    pub player_uuid: Uuid,
+    /// 0-100; 0 means no history on participating servers.
+    pub score: f64,
+    pub servers_flagged: i32,
+    pub findings: i32,
+    pub confirmed_findings: i32,
+    pub last_flagged_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Look up a player's reputation (a zero score when there is no history).
+pub async fn lookup(state: &AppState, player_uuid: Uuid) -> Result<PlayerReputation, sqlx::Error> {
+    let row: Option<(f64, i32, i32, i32, Option<DateTime<Utc>>, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        select score, servers_flagged, findings, confirmed_findings, last_flagged_at, updated_at
+        from public.player_reputation
+        where player_uuid = $1
+        "#,
+    )
+    .bind(player_uuid)
+    .fetch_optional(&state.db)
+    .await?;
+
+    Ok(match row {
+        Some((
+            score,
+            servers_flagged,
+            findings,
+            confirmed_findings,
+            last_flagged_at,
+            updated_at,
+        )) => PlayerReputation {
+            player_uuid,
+            score,
+            servers_flagged,
+            findings,
+            confirmed_findings,
+            last_flagged_at,
+            updated_at: Some(updated_at),
+        },
+        None => PlayerReputation {
+            player_uuid,
+            score: 0.0,
+            servers_flagged: 0,
+            findings: 0,
+            confirmed_findings: 0,
+            last_flagged_at: None,
+            updated_at: None,
+        },
+    })
+}
@@ -0,0 +1,72 @@
+//! `X-Request-Id` assignment and propagation.
+//!
+//! Every request gets an id: the caller's `X-Request-Id` when it is sane (so a proxy's id carries
+//! through), otherwise a fresh UUID. The id is echoed in the response header, recorded on a
+//! `request` span wrapping the handler (so every log line of the request carries it, along with
+//! the `server_id` once plugin auth has verified it), and added
+//! to error bodies as `request_id`, which lets a "500 internal error" reported by a plugin owner
+//! be matched to the server logs.
+
+use axum::{
+    http::{HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+    static REQUEST_SPAN: tracing::Span;
+}
+
+/// The id of the request being handled, if called from within one.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Record the authenticated server on the request's span, if called from within a request.
+pub fn record_server_id(server_id: &str) {
+    let _ = REQUEST_SPAN.try_with(|span| {
+        span.record("server_id", server_id);
+    });
+}
+
+/// A caller-supplied id, if it is short and made of visible ASCII.
+pub fn sanitize(raw: &str) -> Option<String> {
+    let id = raw.trim();
+    let valid = !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.bytes().all(|b| b.is_ascii_graphic());
+    valid.then(|| id.to_string())
+}
+
+/// Middleware: assign or propagate the request id (outermost layer).
+pub async fn propagate<B>(req: Request<B>, next: Next<B>) -> Response {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(sanitize)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %req.method(),
+        path = %req.uri().path(),
+        server_id = tracing::field::Empty,
+    );
+    let mut resp = REQUEST_ID
+        .scope(id.clone(), REQUEST_SPAN.scope(span.clone(), next.run(req)))
+        .instrument(span)
+        .await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        resp.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    resp
+}
@@ -0,0 +1,663 @@
+//! Operator-only endpoints under `/admin` (protected by `ADMIN_TOKEN`).
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::conformance::{self, ConformanceReport};
+use crate::privacy::{self, PlayerDeletionJob};
+use crate::replay::{self, ReplayReport, ReplayStarted};
+use crate::replica_shards::{self, Replica};
+use crate::reprocess::{self, ReprocessJob};
+use crate::server_deletion::{self, PurgeJob};
+use crate::simulation::{self, Scenario, SimulationReport};
+use crate::task_lock::{self, TaskLease};
+use crate::traffic_stats::{self, RankBy, ServerTraffic};
+use crate::transform_pool::PoolSnapshot;
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct ConformanceRequest {
+    /// Wait this long for a findings callback carrying the run marker (max 60s).
+    pub wait_for_callback_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConformanceResponse {
+    pub ok: bool,
+    pub report: ConformanceReport,
+}
+
+/// POST /admin/modules/:module_id/conformance
+///
+/// Sends the module a canned batch and stores a pass/fail report.
+#[utoipa::path(
+    post,
+    path = "/admin/modules/{module_id}/conformance",
+    tag = "admin",
+    params(
+        ("module_id" = Uuid, Path, description = "Module id"),
+    ),
+    request_body = Option<ConformanceRequest>,
+    responses(
+        (status = 200, body = ConformanceResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn run_module_conformance(
+    State(state): State<AppState>,
+    Path(module_id): Path<Uuid>,
+    req: Option<Json<ConformanceRequest>>,
+) -> Result<Json<ConformanceResponse>, ApiError> {
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+    let wait = req
+        .wait_for_callback_seconds
+        .filter(|s| *s > 0)
+        .map(Duration::from_secs);
+
+    let report = conformance::run(&state, module_id, wait).await?;
+    Ok(Json(ConformanceResponse { ok: true, report }))
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct ReplayRequest {
+    /// Replay through this module only (default: all enabled modules of the server).
+    pub module_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayStartedResponse {
+    pub ok: bool,
+    pub replay: ReplayStarted,
+}
+
+/// POST /admin/observations/:observation_id/replay
+///
+/// Replays the batches covering the observation window through a module (or all) in the
+/// background. Poll `GET /admin/replays/:replay_id` for the result.
+#[utoipa::path(
+    post,
+    path = "/admin/observations/{observation_id}/replay",
+    tag = "admin",
+    params(
+        ("observation_id" = Uuid, Path, description = "Observation id"),
+    ),
+    request_body = Option<ReplayRequest>,
+    responses(
+        (status = 200, body = ReplayStartedResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn replay_observation(
+    State(state): State<AppState>,
+    Path(observation_id): Path<Uuid>,
+    req: Option<Json<ReplayRequest>>,
+) -> Result<Json<ReplayStartedResponse>, ApiError> {
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+    let replay = replay::start(&state, observation_id, req.module_id).await?;
+    Ok(Json(ReplayStartedResponse { ok: true, replay }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplayReportResponse {
+    pub ok: bool,
+    pub replay: ReplayReport,
+}
+
+/// GET /admin/replays/:replay_id
+#[utoipa::path(
+    get,
+    path = "/admin/replays/{replay_id}",
+    tag = "admin",
+    params(
+        ("replay_id" = Uuid, Path, description = "Replay id"),
+    ),
+    responses(
+        (status = 200, body = ReplayReportResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn get_replay(
+    State(state): State<AppState>,
+    Path(replay_id): Path<Uuid>,
+) -> Result<Json<ReplayReportResponse>, ApiError> {
+    let replay = replay::report(&state, replay_id).await?;
+    Ok(Json(ReplayReportResponse { ok: true, replay }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReprocessRequest {
+    pub module_id: Uuid,
+    /// Batches received at or after this time.
+    pub from: DateTime<Utc>,
+    /// Batches received before this time.
+    pub to: DateTime<Utc>,
+    /// Transform to apply instead of the module's configured one.
+    pub transform: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReprocessJobResponse {
+    pub ok: bool,
+    pub job: ReprocessJob,
+}
+
+/// POST /admin/reprocess
+///
+/// Queues a job that re-runs stored batches from a received-at range through a module (e.g.
+/// after a transform fix). Poll `GET /admin/reprocess/:job_id` for progress.
+#[utoipa::path(
+    post,
+    path = "/admin/reprocess",
+    tag = "admin",
+    request_body = ReprocessRequest,
+    responses(
+        (status = 200, body = ReprocessJobResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn create_reprocess_job(
+    State(state): State<AppState>,
+    Json(req): Json<ReprocessRequest>,
+) -> Result<Json<ReprocessJobResponse>, ApiError> {
+    let job = reprocess::create(
+        &state,
+        req.module_id,
+        req.transform.as_deref(),
+        req.from,
+        req.to,
+    )
+    .await?;
+    Ok(Json(ReprocessJobResponse { ok: true, job }))
+}
+
+/// GET /admin/reprocess/:job_id
+#[utoipa::path(
+    get,
+    path = "/admin/reprocess/{job_id}",
+    tag = "admin",
+    params(
+        ("job_id" = Uuid, Path, description = "Reprocess job id"),
+    ),
+    responses(
+        (status = 200, body = ReprocessJobResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn get_reprocess_job(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<ReprocessJobResponse>, ApiError> {
+    let job = reprocess::get(&state, job_id).await?;
+    Ok(Json(ReprocessJobResponse { ok: true, job }))
+}
+
+/// POST /admin/reprocess/:job_id/cancel
+#[utoipa::path(
+    post,
+    path = "/admin/reprocess/{job_id}/cancel",
+    tag = "admin",
+    params(
+        ("job_id" = Uuid, Path, description = "Reprocess job id"),
+    ),
+    responses(
+        (status = 200, body = ReprocessJobResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn cancel_reprocess_job(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<ReprocessJobResponse>, ApiError> {
+    let job = reprocess::cancel(&state, job_id).await?;
+    Ok(Json(ReprocessJobResponse { ok: true, job }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SimulateRequest {
+    pub server_id: String,
+    /// Scenarios to run: `speed`, `reach`, `autoclicker` (default: all).
+    pub scenarios: Option<Vec<String>>,
+    /// Wait this long for HTTP modules to call back (default 5s, max 30s).
+    pub wait_seconds: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SimulationResponse {
+    pub ok: bool,
+    pub simulation: SimulationReport,
+}
+
+/// POST /admin/simulate
+///
+/// Runs curated cheat scenarios through the server's enabled modules and reports which checks
+/// fired. Findings stay out of the live findings table.
+#[utoipa::path(
+    post,
+    path = "/admin/simulate",
+    tag = "admin",
+    request_body = SimulateRequest,
+    responses(
+        (status = 200, body = SimulationResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn simulate(
+    State(state): State<AppState>,
+    Json(req): Json<SimulateRequest>,
+) -> Result<Json<SimulationResponse>, ApiError> {
+    if req.server_id.trim().is_empty() {
+        return Err(ApiError::BadRequest("server_id is required".to_string()));
+    }
+    let scenarios = match req.scenarios {
+        None => Scenario::ALL.to_vec(),
+        Some(names) if names.is_empty() => {
+            return Err(ApiError::BadRequest("scenarios is empty".to_string()));
+        }
+        Some(names) => names
+            .iter()
+            .map(|n| {
+                Scenario::parse(n)
+                    .ok_or_else(|| ApiError::BadRequest(format!("unknown scenario: {}", n)))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    let wait = req
+        .wait_seconds
+        .map(Duration::from_secs)
+        .unwrap_or(simulation::DEFAULT_WAIT);
+
+    let simulation = simulation::run(&state, &req.server_id, scenarios, wait).await?;
+    Ok(Json(SimulationResponse {
+        ok: true,
+        simulation,
+    }))
+}
+
+/// GET /admin/simulations/:run_id
+#[utoipa::path(
+    get,
+    path = "/admin/simulations/{run_id}",
+    tag = "admin",
+    params(
+        ("run_id" = Uuid, Path, description = "Simulation run id"),
+    ),
+    responses(
+        (status = 200, body = SimulationResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn get_simulation(
+    State(state): State<AppState>,
+    Path(run_id): Path<Uuid>,
+) -> Result<Json<SimulationResponse>, ApiError> {
+    let simulation = simulation::report(&state, run_id).await?;
+    Ok(Json(SimulationResponse {
+        ok: true,
+        simulation,
+    }))
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct PurgeRequest {
+    /// Purge before `SERVER_PURGE_GRACE_DAYS` have passed since the deletion.
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PurgeJobResponse {
+    pub ok: bool,
+    pub job: PurgeJob,
+}
+
+/// POST /admin/servers/:server_id/purge
+///
+/// Queues removal of a deleted server's findings, batches, module rows and stored objects.
+/// The server must have been deleted (`DELETE /dashboard/:server_id`) at least
+/// `SERVER_PURGE_GRACE_DAYS` ago unless `force` is set. Poll `GET /admin/server-purges/:job_id`.
+#[utoipa::path(
+    post,
+    path = "/admin/servers/{server_id}/purge",
+    tag = "admin",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = Option<PurgeRequest>,
+    responses(
+        (status = 200, body = PurgeJobResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "Server not deleted, grace period not over or purge already queued", body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn purge_server(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    req: Option<Json<PurgeRequest>>,
+) -> Result<Json<PurgeJobResponse>, ApiError> {
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+    let job = server_deletion::create_purge(&state, server_id.trim(), req.force).await?;
+    Ok(Json(PurgeJobResponse { ok: true, job }))
+}
+
+/// GET /admin/server-purges/:job_id
+#[utoipa::path(
+    get,
+    path = "/admin/server-purges/{job_id}",
+    tag = "admin",
+    params(
+        ("job_id" = Uuid, Path, description = "Purge job id"),
+    ),
+    responses(
+        (status = 200, body = PurgeJobResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn get_server_purge(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<PurgeJobResponse>, ApiError> {
+    let job = server_deletion::get_purge(&state, job_id).await?;
+    Ok(Json(PurgeJobResponse { ok: true, job }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DeletePlayerRequest {
+    pub player_uuid: Uuid,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlayerDeletionResponse {
+    pub ok: bool,
+    pub job: PlayerDeletionJob,
+}
+
+/// POST /admin/privacy/delete-player
+///
+/// Queues erasure of a player's data on every server: their records are scrubbed from stored
+/// raw batches and their findings, module state, sessions and other rows are deleted. Asking
+/// again while a job is pending returns that job. Poll `GET /admin/privacy/deletions/:job_id`.
+#[utoipa::path(
+    post,
+    path = "/admin/privacy/delete-player",
+    tag = "admin",
+    request_body = DeletePlayerRequest,
+    responses(
+        (status = 200, body = PlayerDeletionResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn delete_player(
+    State(state): State<AppState>,
+    Json(req): Json<DeletePlayerRequest>,
+) -> Result<Json<PlayerDeletionResponse>, ApiError> {
+    if req.player_uuid.is_nil() {
+        return Err(ApiError::BadRequest("player_uuid must not be nil".into()));
+    }
+    let job = privacy::create_deletion(&state, req.player_uuid).await?;
+    Ok(Json(PlayerDeletionResponse { ok: true, job }))
+}
+
+/// GET /admin/privacy/deletions/:job_id
+#[utoipa::path(
+    get,
+    path = "/admin/privacy/deletions/{job_id}",
+    tag = "admin",
+    params(
+        ("job_id" = Uuid, Path, description = "Deletion job id"),
+    ),
+    responses(
+        (status = 200, body = PlayerDeletionResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn get_player_deletion(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<PlayerDeletionResponse>, ApiError> {
+    let job = privacy::get_deletion(&state, job_id).await?;
+    Ok(Json(PlayerDeletionResponse { ok: true, job }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TransformPoolResponse {
+    pub ok: bool,
+    pub pool: PoolSnapshot,
+}
+
+/// GET /admin/transform-pool
+///
+/// Saturation and counters of the transform worker pool since the API started.
+#[utoipa::path(
+    get,
+    path = "/admin/transform-pool",
+    tag = "admin",
+    responses(
+        (status = 200, body = TransformPoolResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn get_transform_pool(State(state): State<AppState>) -> Json<TransformPoolResponse> {
+    Json(TransformPoolResponse {
+        ok: true,
+        pool: state.transform_pool.snapshot(),
+    })
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TaskLeasesResponse {
+    pub ok: bool,
+    /// This replica's holder name.
+    pub instance_id: String,
+    pub tasks: Vec<TaskLease>,
+}
+
+/// GET /admin/tasks
+///
+/// Which replica runs each background task, and when it last finished a run.
+#[utoipa::path(
+    get,
+    path = "/admin/tasks",
+    tag = "admin",
+    responses(
+        (status = 200, body = TaskLeasesResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn list_task_leases(
+    State(state): State<AppState>,
+) -> Result<Json<TaskLeasesResponse>, ApiError> {
+    let tasks = task_lock::list(&state.db).await.map_err(|e| {
+        tracing::error!("task lease query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    Ok(Json(TaskLeasesResponse {
+        ok: true,
+        instance_id: task_lock::instance_id().to_string(),
+        tasks,
+    }))
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReplicasQuery {
+    /// Also report which replica owns this server's queued work.
+    pub server_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReplicasResponse {
+    pub ok: bool,
+    pub replicas: Vec<Replica>,
+    /// Owner of `server_id` among the live replicas, when asked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+}
+
+/// GET /admin/replicas
+///
+/// Live API replicas sharing per-server work, and optionally the owner of one server.
+#[utoipa::path(
+    get,
+    path = "/admin/replicas",
+    tag = "admin",
+    params(ReplicasQuery),
+    responses(
+        (status = 200, body = ReplicasResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn list_replicas(
+    State(state): State<AppState>,
+    Query(params): Query<ReplicasQuery>,
+) -> Result<Json<ReplicasResponse>, ApiError> {
+    let replicas = replica_shards::list(&state).await.map_err(|e| {
+        tracing::error!("replica query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let members: Vec<String> = replicas.iter().map(|r| r.instance_id.clone()).collect();
+    let owner = params
+        .server_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .and_then(|server_id| replica_shards::owner(&members, server_id))
+        .map(str::to_string);
+    Ok(Json(ReplicasResponse {
+        ok: true,
+        replicas,
+        owner,
+    }))
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TopTalkersQuery {
+    /// Hours to cover, 1-24 (default 1).
+    pub hours: Option<i64>,
+    /// total (default) | in | out | requests
+    pub by: Option<RankBy>,
+    /// Servers to list (default 20, max 200).
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TopTalker {
+    #[serde(flatten)]
+    pub traffic: ServerTraffic,
+    /// Batch payload bytes stored for the server in the period, across replicas.
+    pub stored_bytes: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TopTalkersResponse {
+    pub ok: bool,
+    /// The replica whose request counters are reported.
+    pub instance_id: String,
+    pub hours: i64,
+    pub by: RankBy,
+    pub servers: Vec<TopTalker>,
+}
+
+/// GET /admin/top-talkers
+///
+/// Servers ranked by request / response bytes with their busiest endpoints, to find which
+/// server is behind unexpected bandwidth or storage growth.
+#[utoipa::path(
+    get,
+    path = "/admin/top-talkers",
+    tag = "admin",
+    params(TopTalkersQuery),
+    responses(
+        (status = 200, body = TopTalkersResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("admin_token" = [])),
+)]
+pub async fn get_top_talkers(
+    State(state): State<AppState>,
+    Query(q): Query<TopTalkersQuery>,
+) -> Result<Json<TopTalkersResponse>, ApiError> {
+    let hours = q.hours.unwrap_or(1);
+    if !(1..=traffic_stats::RETENTION_HOURS).contains(&hours) {
+        return Err(ApiError::BadRequest(format!(
+            "hours must be 1-{}",
+            traffic_stats::RETENTION_HOURS
+        )));
+    }
+    let by = q.by.unwrap_or_default();
+    let limit = q.limit.unwrap_or(20).clamp(1, 200);
+
+    let ranked = state.traffic.top(hours, by, limit);
+    let server_ids: Vec<String> = ranked.iter().filter_map(|s| s.server_id.clone()).collect();
+    let stored: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        select server_id, coalesce(sum(payload_bytes), 0)::bigint
+        from public.batch_index
+        where server_id = any($1) and received_at > now() - make_interval(hours => $2)
+        group by server_id
+        "#,
+    )
+    .bind(&server_ids)
+    .bind(hours as i32)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("top talkers storage query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let stored: HashMap<String, i64> = stored.into_iter().collect();
+
+    let servers = ranked
+        .into_iter()
+        .map(|traffic| TopTalker {
+            stored_bytes: traffic
+                .server_id
+                .as_ref()
+                .map(|id| stored.get(id).copied().unwrap_or(0)),
+            traffic,
+        })
+        .collect();
+    Ok(Json(TopTalkersResponse {
+        ok: true,
+        instance_id: task_lock::instance_id().to_string(),
+        hours,
+        by,
+        servers,
+    }))
+}
@@ -1,11 +1,11 @@
 use axum::{
     extract::State,
-    http::{header::AUTHORIZATION, Request, StatusCode},
+    http::{Request, StatusCode},
     middleware::Next,
     response::Response,
 };
 
-use crate::AppState;
+use crate::{auth, AppState};
 
 /// Middleware that protects dashboard endpoints with a static bearer token.
 /// If `DASHBOARD_TOKEN` is unset, the middleware is a no-op (useful for local dev).
@@ -19,18 +19,27 @@ pub async fn require_dashboard<B>(
         return Ok(next.run(req).await);
     };
 
-    // Extract bearer token
-    let auth = req
-        .headers()
-        .get(AUTHORIZATION)
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("");
-    let prefix = "bearer ";
-    if auth.len() <= prefix.len() || !auth[..prefix.len()].eq_ignore_ascii_case(prefix) {
+    let provided = auth::parse_bearer_token(req.headers()).ok_or(StatusCode::UNAUTHORIZED)?;
+    if !auth::constant_time_eq(&provided, expected) {
         return Err(StatusCode::UNAUTHORIZED);
     }
-    let provided = auth[prefix.len()..].trim();
-    if provided != expected {
+
+    Ok(next.run(req).await)
+}
+
+/// Middleware that protects `/admin/*` endpoints with `ADMIN_TOKEN`.
+/// Unlike the dashboard, admin routes are rejected entirely when no token is configured.
+pub async fn require_admin<B>(
+    State(state): State<AppState>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Result<Response, StatusCode> {
+    let Some(expected) = state.admin_token.as_ref() else {
+        return Err(StatusCode::UNAUTHORIZED);
+    };
+
+    let provided = auth::parse_bearer_token(req.headers()).ok_or(StatusCode::UNAUTHORIZED)?;
+    if !auth::constant_time_eq(&provided, expected) {
         return Err(StatusCode::UNAUTHORIZED);
     }
 
@@ -0,0 +1,253 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::packet_viewer::{self, PacketFilter};
+use crate::{error::ApiError, pagination, AppState};
+
+/// Lines are sent to the client in chunks of about this size.
+const CHUNK_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PacketsQuery {
+    /// Only records of this player.
+    pub player: Option<Uuid>,
+    /// Comma-separated packet names (canonical names or aliases).
+    pub pkt: Option<String>,
+    /// Matching lines to skip. Defaults to 0.
+    pub offset: Option<usize>,
+    /// Defaults to 500, at most 5000.
+    pub limit: Option<usize>,
+}
+
+/// GET /dashboard/:server_id/batches/:batch_id/packets
+///
+/// Stream a stored batch's records as NDJSON (`application/x-ndjson`), decoded and filtered
+/// server-side for the dashboard's packet inspector. The next page starts at `offset + limit`;
+/// a page with fewer than `limit` lines is the last.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/batches/{batch_id}/packets",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("batch_id" = Uuid, Path, description = "Batch id"),
+        PacketsQuery,
+    ),
+    responses(
+        (status = 200, description = "Matching records, one JSON object per line", content_type = "application/x-ndjson"),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_batch_packets(
+    State(state): State<AppState>,
+    Path((server_id, batch_id)): Path<(String, Uuid)>,
+    Query(params): Query<PacketsQuery>,
+) -> Result<Response, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let filter = PacketFilter::new(params.player, params.pkt.as_deref());
+    let offset = params.offset.unwrap_or(0);
+    let limit = params
+        .limit
+        .unwrap_or(packet_viewer::DEFAULT_LIMIT)
+        .clamp(1, packet_viewer::MAX_LIMIT);
+
+    let s3_key: Option<String> = sqlx::query_scalar(
+        "select s3_key from public.batch_index where id = $1 and server_id = $2",
+    )
+    .bind(batch_id)
+    .bind(&server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("batch lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let Some(s3_key) = s3_key else {
+        return Err(ApiError::NotFound(format!("batch {} not found", batch_id)));
+    };
+    let raw = state.object_store.get_batch(&s3_key).await.map_err(|e| {
+        tracing::warn!("batch object {} unavailable: {:?}", s3_key, e);
+        ApiError::NotFound(format!("batch {} is no longer stored", batch_id))
+    })?;
+
+    // Decode on a blocking thread and hand chunks to the response body as they fill up.
+    let (mut tx, body) = Body::channel();
+    let handle = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || {
+        let mut chunk = String::new();
+        let mut open = true;
+        let res = packet_viewer::scan(&raw, &filter, offset, limit, |line| {
+            chunk.push_str(line);
+            chunk.push('\n');
+            if chunk.len() >= CHUNK_BYTES {
+                let bytes = Bytes::from(std::mem::take(&mut chunk));
+                open = handle.block_on(tx.send_data(bytes)).is_ok();
+            }
+            open
+        });
+        match res {
+            Ok(_) if open && !chunk.is_empty() => {
+                let _ = handle.block_on(tx.send_data(Bytes::from(chunk)));
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!(batch_id = %batch_id, "packet viewer decode failed: {:?}", e);
+                tx.abort();
+            }
+        }
+    });
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        axum::body::boxed(body),
+    )
+        .into_response())
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BatchesQuery {
+    /// Only batches of this session.
+    pub session_id: Option<String>,
+    /// Batches per page (default 50, at most 200).
+    pub limit: Option<i64>,
+    /// `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchItem {
+    pub id: Uuid,
+    pub received_at: DateTime<Utc>,
+    pub session_id: String,
+    pub payload_bytes: i32,
+    pub event_count: Option<i32>,
+    pub batch_seq: Option<i64>,
+    /// Held back from modules (see ingest anomalies).
+    pub quarantined: bool,
+    pub dispatch_status: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchesResponse {
+    pub ok: bool,
+    /// Newest first.
+    pub batches: Vec<BatchItem>,
+    /// Pass as `cursor` for the next page; null on the last one.
+    pub next_cursor: Option<String>,
+}
+
+type BatchRow = (
+    Uuid,
+    DateTime<Utc>,
+    String,
+    i32,
+    Option<i32>,
+    Option<i64>,
+    bool,
+    Option<String>,
+);
+
+/// GET /dashboard/:server_id/batches
+///
+/// The server's stored batches, newest first, with cursor pagination.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/batches",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        BatchesQuery,
+    ),
+    responses(
+        (status = 200, body = BatchesResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_batches(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Query(params): Query<BatchesQuery>,
+) -> Result<Json<BatchesResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let limit = pagination::limit(params.limit, 50, 200);
+    let cursor = pagination::parse(params.cursor.as_deref())?;
+    let session_id = params
+        .session_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    let rows: Vec<BatchRow> = sqlx::query_as(
+        r#"
+        select id, received_at, session_id, payload_bytes, event_count, batch_seq, quarantined,
+               dispatch_status
+        from public.batch_index
+        where server_id = $1
+          and ($2::text is null or session_id = $2)
+          and ($3::timestamptz is null or (received_at, id) < ($3, $4))
+        order by received_at desc, id desc
+        limit $5
+        "#,
+    )
+    .bind(&server_id)
+    .bind(session_id)
+    .bind(cursor.map(|c| c.at))
+    .bind(cursor.map(|c| c.id))
+    .bind(limit + 1)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("list batches failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let (rows, next_cursor) = pagination::page(rows, limit, |row| pagination::Cursor {
+        at: row.1,
+        id: row.0,
+    });
+    let batches = rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                received_at,
+                session_id,
+                payload_bytes,
+                event_count,
+                batch_seq,
+                quarantined,
+                dispatch_status,
+            )| BatchItem {
+                id,
+                received_at,
+                session_id,
+                payload_bytes,
+                event_count,
+                batch_seq,
+                quarantined,
+                dispatch_status,
+            },
+        )
+        .collect();
+
+    Ok(Json(BatchesResponse {
+        ok: true,
+        batches,
+        next_cursor,
+    }))
+}
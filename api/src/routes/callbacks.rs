@@ -4,12 +4,19 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use subtle::ConstantTimeEq;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::{error::ApiError, webhooks, AppState};
+use crate::async_dispatch::{self, Completion};
+use crate::chaos::FaultPoint;
+use crate::finding_latency;
+use crate::{
+    auth, conformance, correlation, error::ApiError, exemptions, module_canary, punishments,
+    replay, routes::observations, severity_overrides::SeverityOverrides, shadow, simulation,
+    webhooks, AppState,
+};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FindingIn {
     pub player_uuid: Option<Uuid>,
     pub detector_name: String,
@@ -19,50 +26,35 @@ pub struct FindingIn {
     pub description: Option<String>,
     pub evidence_s3_key: Option<String>,
     pub evidence_json: Option<Value>,
+    /// How sure the detector is, 0-1 (absent: certain). Servers can downgrade or drop webhook
+    /// notifications below a minimum confidence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PostFindingsRequest {
     pub server_id: String,
+    /// The `X-Module-Id` the batch was dispatched with; required on `/callbacks/findings`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module_id: Option<Uuid>,
     pub session_id: Option<String>,
     pub batch_id: Option<Uuid>,
     pub findings: Vec<FindingIn>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PostFindingsResponse {
     pub ok: bool,
     pub inserted: usize,
 }
 
 fn require_callback_auth(state: &AppState, headers: &HeaderMap) -> Result<(), ApiError> {
-    let auth = headers
-        .get("authorization")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
-    let expected = format!("Bearer {}", state.module_callback_token);
-
-    // Use constant-time comparison to prevent timing attacks
     if state.module_callback_token.is_empty() {
         return Err(ApiError::Unauthorized);
     }
-
-    let auth_bytes = auth.as_bytes();
-    let expected_bytes = expected.as_bytes();
-
-    // Constant-time comparison: both length check and content check
-    // are done in a way that doesn't leak timing information
-    let len_match = auth_bytes.len() == expected_bytes.len();
-    let content_match = if len_match {
-        auth_bytes.ct_eq(expected_bytes).into()
-    } else {
-        // Still do a comparison to maintain constant time even on length mismatch
-        let dummy = vec![0u8; expected_bytes.len()];
-        let _ = dummy.as_slice().ct_eq(expected_bytes);
-        false
-    };
-
-    if !content_match {
+    let provided = auth::parse_bearer_token(headers).ok_or(ApiError::Unauthorized)?;
+    if !auth::constant_time_eq(&provided, &state.module_callback_token) {
         return Err(ApiError::Unauthorized);
     }
     Ok(())
@@ -71,6 +63,18 @@ fn require_callback_auth(state: &AppState, headers: &HeaderMap) -> Result<(), Ap
 /// POST /callbacks/findings
 ///
 /// Accepts findings from modules and stores them in Postgres/Supabase.
+#[utoipa::path(
+    post,
+    path = "/callbacks/findings",
+    tag = "callbacks",
+    request_body = PostFindingsRequest,
+    responses(
+        (status = 200, body = PostFindingsResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("module_token" = [])),
+)]
 pub async fn post_findings(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -81,6 +85,230 @@ pub async fn post_findings(
     if req.server_id.trim().is_empty() {
         return Err(ApiError::BadRequest("server_id is required".to_string()));
     }
+    if req.module_id.is_none() {
+        return Err(ApiError::BadRequest(
+            "module_id is required (the X-Module-Id header of the dispatched batch)".to_string(),
+        ));
+    }
+    if let Some(f) = req
+        .findings
+        .iter()
+        .find(|f| f.confidence.is_some_and(|c| !(0.0..=1.0).contains(&c)))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "confidence of {} must be between 0 and 1",
+            f.detector_name
+        )));
+    }
+
+    let inserted = store_findings(&state, &req).await?;
+    Ok(Json(PostFindingsResponse { ok: true, inserted }))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DispatchCompleteRequest {
+    pub server_id: String,
+    /// The id the module returned with its 202 response.
+    pub processing_id: String,
+    /// Whether processing succeeded (default true).
+    #[serde(default = "default_true")]
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DispatchCompleteResponse {
+    pub ok: bool,
+    /// New dispatch status: completed | failed.
+    pub status: String,
+}
+
+/// POST /callbacks/dispatch-complete
+///
+/// Completes an async dispatch (one the module answered with 202 and a processing id). Post
+/// its findings to `/callbacks/findings` first.
+#[utoipa::path(
+    post,
+    path = "/callbacks/dispatch-complete",
+    tag = "callbacks",
+    request_body = DispatchCompleteRequest,
+    responses(
+        (status = 200, body = DispatchCompleteResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, body = ErrorBody),
+    ),
+    security(("module_token" = [])),
+)]
+pub async fn dispatch_complete(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<DispatchCompleteRequest>,
+) -> Result<Json<DispatchCompleteResponse>, ApiError> {
+    require_callback_auth(&state, &headers)?;
+
+    let server_id = req.server_id.trim();
+    let processing_id = req.processing_id.trim();
+    if server_id.is_empty() || processing_id.is_empty() {
+        return Err(ApiError::BadRequest(
+            "server_id and processing_id are required".to_string(),
+        ));
+    }
+    let error = req
+        .error
+        .as_deref()
+        .map(str::trim)
+        .filter(|e| !e.is_empty());
+
+    let completion = async_dispatch::complete(&state, server_id, processing_id, req.ok, error)
+        .await
+        .map_err(|e| {
+            tracing::error!("dispatch completion failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    match completion {
+        Completion::Recorded(status) => Ok(Json(DispatchCompleteResponse {
+            ok: true,
+            status: status.to_string(),
+        })),
+        Completion::NotFound => Err(ApiError::NotFound(format!(
+            "no async dispatch {} for server {}",
+            processing_id, server_id
+        ))),
+        Completion::AlreadyFinished(status) => Err(ApiError::Conflict(format!(
+            "dispatch {} already {}",
+            processing_id, status
+        ))),
+    }
+}
+
+/// Aggregate and persist findings, then fire webhook notifications.
+///
+/// Shared by the module callback endpoint and the in-process check engine so both
+/// paths produce identical rows.
+pub async fn store_findings(
+    state: &AppState,
+    req: &PostFindingsRequest,
+) -> Result<usize, ApiError> {
+    // Conformance runs only need to know the callback arrived; don't store canned findings.
+    if conformance::is_conformance_session(req.session_id.as_deref()) {
+        let marker = req.session_id.as_deref().unwrap_or_default();
+        let recorded =
+            conformance::record_callback(state, req.server_id.trim(), marker, req.findings.len())
+                .await
+                .map_err(|e| {
+                    tracing::error!("conformance callback lookup failed: {:?}", e);
+                    ApiError::Internal
+                })?;
+        if recorded {
+            return Ok(0);
+        }
+    }
+    // Replay findings are tagged with the observation instead of going live.
+    if let Some((replay_id, module_id)) = req
+        .session_id
+        .as_deref()
+        .and_then(replay::parse_replay_session)
+    {
+        return Ok(replay::record_findings(state, replay_id, module_id, &req.findings).await);
+    }
+    // Simulation findings are reported per scenario and never go live.
+    if let Some((run_id, module_id)) = req
+        .session_id
+        .as_deref()
+        .and_then(simulation::parse_simulation_session)
+    {
+        return Ok(simulation::record_findings(state, run_id, module_id, &req.findings).await);
+    }
+
+    if let Err(e) = state
+        .faults
+        .inject(FaultPoint::DbFindings, req.server_id.trim())
+        .await
+    {
+        tracing::error!("findings insert failed: {:?}", e);
+        return Err(ApiError::Internal);
+    }
+
+    // Findings are attributed to the module that reported them; it must belong to the server.
+    let mut shadow_module = None;
+    if let Some(module_id) = req.module_id {
+        let known: Option<String> = sqlx::query_scalar(
+            "select mode from public.server_modules where id = $1 and server_id = $2",
+        )
+        .bind(module_id)
+        .bind(req.server_id.trim())
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("findings module lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+        match known {
+            None => {
+                return Err(ApiError::BadRequest(format!(
+                    "module {} is not registered for server {}",
+                    module_id,
+                    req.server_id.trim()
+                )))
+            }
+            Some(mode) if mode == shadow::MODE_SHADOW => shadow_module = Some(module_id),
+            Some(_) => {}
+        }
+    }
+
+    // Exempted players (staff, creative worlds, ...) produce no findings.
+    let findings =
+        exemptions::filter_findings(&state.db, req.server_id.trim(), &req.findings).await;
+    if findings.is_empty() {
+        return Ok(0);
+    }
+
+    // Canary comparisons count findings per variant that processed the batch.
+    let variant = match (req.module_id, req.batch_id) {
+        (Some(module_id), Some(batch_id)) => {
+            module_canary::record_findings(&state.db, module_id, batch_id, findings.len()).await
+        }
+        _ => None,
+    };
+
+    // Shadow modules' findings are kept aside and never go live.
+    if let Some(module_id) = shadow_module {
+        return Ok(shadow::record_findings(
+            state,
+            req.server_id.trim(),
+            module_id,
+            req.session_id.as_deref(),
+            req.batch_id,
+            &findings,
+        )
+        .await);
+    }
+
+    // The owner's severity for a detector replaces the module's.
+    let overrides = SeverityOverrides::load(&state.db, req.server_id.trim())
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(
+                "severity override lookup failed, keeping module severities: {:?}",
+                e
+            );
+            SeverityOverrides::default()
+        });
+
+    // Several independent detectors on one player within minutes add a combined finding.
+    let combined = correlation::correlate(&state.db, req.server_id.trim(), &findings, &overrides)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("finding correlation failed: {:?}", e);
+            Vec::new()
+        });
+    let findings: Vec<&FindingIn> = findings.into_iter().chain(combined.iter()).collect();
 
     let mut tx = state.db.begin().await.map_err(|e| {
         tracing::error!("begin tx failed: {:?}", e);
@@ -98,7 +326,7 @@ pub async fn post_findings(
     // Using DO NOTHING to avoid deadlocks from concurrent upserts.
     // We only need the row to exist for the FK constraint; last_seen_at is updated elsewhere.
     let mut player_uuids: HashSet<Uuid> = HashSet::new();
-    for f in &req.findings {
+    for f in &findings {
         if let Some(u) = f.player_uuid {
             player_uuids.insert(u);
         }
@@ -120,6 +348,15 @@ pub async fn post_findings(
         })?;
     }
 
+    // Findings of a batch are stamped with its arrival, for the ingest → finding latency.
+    let batch_received_at =
+        finding_latency::batch_received_at(&state.db, req.server_id.trim(), req.batch_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("finding batch lookup failed: {:?}", e);
+                None
+            });
+
     let mut inserted = 0usize;
     // Aggregate per (player_uuid, detector_name) per minute.
     #[derive(Debug, Clone)]
@@ -131,6 +368,7 @@ pub async fn post_findings(
         description: Option<String>,
         evidence_s3_key: Option<String>,
         evidence_json: Option<Value>,
+        confidence: Option<f64>,
     }
 
     fn sev_rank(sev: &str) -> i32 {
@@ -144,7 +382,7 @@ pub async fn post_findings(
     }
 
     let mut agg: HashMap<(Uuid, String), Agg> = HashMap::new();
-    for f in &req.findings {
+    for f in &findings {
         let Some(player_uuid) = f.player_uuid else {
             continue;
         };
@@ -153,7 +391,9 @@ pub async fn post_findings(
             continue;
         }
 
-        let sev = f.severity.as_deref().unwrap_or("info").to_string();
+        let sev = overrides
+            .severity(detector_name, f.severity.as_deref())
+            .to_string();
         let key = (player_uuid, detector_name.to_string());
         let entry = agg.entry(key).or_insert_with(|| Agg {
             count: 0,
@@ -163,9 +403,15 @@ pub async fn post_findings(
             description: f.description.clone(),
             evidence_s3_key: f.evidence_s3_key.clone(),
             evidence_json: f.evidence_json.clone(),
+            confidence: None,
         });
 
         entry.count += 1;
+        // The bucket is as credible as its most confident hit (in-process callers are not
+        // validated, so out-of-range values are ignored).
+        if let Some(c) = f.confidence.filter(|c| (0.0..=1.0).contains(c)) {
+            entry.confidence = Some(entry.confidence.map_or(c, |prev| prev.max(c)));
+        }
         entry.detector_version = f
             .detector_version
             .clone()
@@ -181,18 +427,30 @@ pub async fn post_findings(
         }
     }
 
+    let mut stored: Vec<punishments::StoredFinding> = Vec::with_capacity(agg.len());
     for ((player_uuid, detector_name), a) in &agg {
         let evidence_json = a.evidence_json.as_ref().map(sqlx::types::Json);
+        let evidence_world = a
+            .evidence_json
+            .as_ref()
+            .and_then(|e| e.get("world"))
+            .and_then(|w| w.as_str());
 
         // Upsert minute-bucket row and increment occurrences.
-        sqlx::query(
+        let finding_id: Uuid = sqlx::query_scalar(
             r#"
             insert into public.findings
                 (server_id, player_uuid, session_id, detector_name, detector_version, severity, title, description, evidence_s3_key, evidence_json,
-                 occurrences, window_start_at, first_seen_at, last_seen_at)
+                 occurrences, window_start_at, first_seen_at, last_seen_at, world, confidence,
+                 module_id, batch_received_at, ingest_latency_ms, module_variant)
             values
                 ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10,
-                 $11, $12, now(), now())
+                 $11, $12, now(), now(),
+                 coalesce($13, (select sp.world from public.server_players sp
+                                where sp.server_id = $1 and sp.player_uuid = $2)),
+                 $14, $15, $16,
+                 least(greatest(extract(epoch from now() - $16) * 1000, 0), 2147483647)::int,
+                 $17)
             on conflict (server_id, player_uuid, detector_name, window_start_at)
                 where player_uuid is not null
             do update set
@@ -219,7 +477,15 @@ pub async fn post_findings(
                 title = excluded.title,
                 description = excluded.description,
                 evidence_s3_key = excluded.evidence_s3_key,
-                evidence_json = excluded.evidence_json
+                evidence_json = excluded.evidence_json,
+                world = coalesce(excluded.world, public.findings.world),
+                confidence = greatest(excluded.confidence, public.findings.confidence),
+                module_id = coalesce(excluded.module_id, public.findings.module_id),
+                module_variant = coalesce(excluded.module_variant, public.findings.module_variant),
+                -- latency of the bucket's first occurrence
+                batch_received_at = coalesce(public.findings.batch_received_at, excluded.batch_received_at),
+                ingest_latency_ms = coalesce(public.findings.ingest_latency_ms, excluded.ingest_latency_ms)
+            returning id
             "#,
         )
         .bind(req.server_id.trim())
@@ -234,13 +500,31 @@ pub async fn post_findings(
         .bind(evidence_json)
         .bind(a.count)
         .bind(window_start_at)
-        .execute(&mut *tx)
+        .bind(evidence_world)
+        .bind(a.confidence)
+        // Combined findings span several modules.
+        .bind(req.module_id.filter(|_| detector_name != correlation::COMBINED_DETECTOR))
+        .bind(batch_received_at)
+        .bind(
+            variant
+                .as_deref()
+                .filter(|_| detector_name != correlation::COMBINED_DETECTOR),
+        )
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| {
             tracing::error!("upsert aggregated finding failed: {:?}", e);
             ApiError::Internal
         })?;
         inserted += 1;
+        stored.push(punishments::StoredFinding {
+            finding_id,
+            player_uuid: *player_uuid,
+            detector_name: detector_name.clone(),
+            severity: a.severity.clone(),
+            title: a.title.clone(),
+            confidence: a.confidence,
+        });
     }
 
     tx.commit().await.map_err(|e| {
@@ -256,6 +540,12 @@ pub async fn post_findings(
         "callbacks/findings stored"
     );
 
+    if inserted > 0 {
+        let players: Vec<Uuid> = agg.keys().map(|(u, _)| *u).collect();
+        observations::link_recent_findings(state, req.server_id.trim(), &players, window_start_at)
+            .await;
+    }
+
     // Send webhook notifications (fire-and-forget)
     if inserted > 0 {
         let server_id = req.server_id.trim().to_string();
@@ -263,20 +553,28 @@ pub async fn post_findings(
             if settings.webhook_enabled {
                 if let Some(ref webhook_url) = settings.webhook_url {
                     // Build notifications for findings that match severity filters
+                    // (after low-confidence findings are downgraded or dropped).
                     let notifications: Vec<webhooks::FindingNotification> = agg
                         .iter()
-                        .filter(|((_, _), a)| webhooks::should_notify(&settings, &a.severity))
-                        .map(|((player_uuid, detector_name), a)| {
-                            webhooks::FindingNotification {
-                                server_id: server_id.clone(),
-                                player_uuid: Some(*player_uuid),
-                                player_name: None, // Would need to look up from players table
-                                detector_name: detector_name.clone(),
-                                severity: a.severity.clone(),
-                                title: a.title.clone(),
-                                description: a.description.clone(),
-                                occurrences: a.count,
-                            }
+                        .filter_map(|((player_uuid, detector_name), a)| {
+                            let severity = webhooks::confidence_adjusted_severity(
+                                &settings,
+                                &a.severity,
+                                a.confidence,
+                            )?;
+                            webhooks::should_notify(&settings, &severity).then(|| {
+                                webhooks::FindingNotification {
+                                    server_id: server_id.clone(),
+                                    player_uuid: Some(*player_uuid),
+                                    player_name: None, // Would need to look up from players table
+                                    detector_name: detector_name.clone(),
+                                    severity,
+                                    title: a.title.clone(),
+                                    description: a.description.clone(),
+                                    occurrences: a.count,
+                                    confidence: a.confidence,
+                                }
+                            })
                         })
                         .collect();
 
@@ -292,6 +590,8 @@ pub async fn post_findings(
 
                         webhooks::spawn_webhook_notifications(
                             state.http.clone(),
+                            state.shared.clone(),
+                            std::time::Duration::from_secs(state.webhook_dedup_seconds),
                             webhook_url.clone(),
                             notifications,
                             server_name,
@@ -302,7 +602,16 @@ pub async fn post_findings(
         }
     }
 
-    Ok(Json(PostFindingsResponse { ok: true, inserted }))
+    // Automated ban rule -> LiteBans-style export (fire-and-forget)
+    if !stored.is_empty() {
+        let state = state.clone();
+        let server_id = req.server_id.trim().to_string();
+        tokio::spawn(async move {
+            punishments::export_bans(&state, &server_id, &stored).await;
+        });
+    }
+
+    Ok(inserted)
 }
 
 // ============================================================================
@@ -312,21 +621,21 @@ pub async fn post_findings(
 // enabling NCP-style checks that track violation levels, attack patterns, etc.
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GetPlayerStateRequest {
     pub server_id: String,
     pub player_uuid: Uuid,
     pub module_name: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PlayerStateResponse {
     pub ok: bool,
     pub state: Option<Value>,
     pub updated_at: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SetPlayerStateRequest {
     pub server_id: String,
     pub player_uuid: Uuid,
@@ -334,45 +643,45 @@ pub struct SetPlayerStateRequest {
     pub state: Value,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SetPlayerStateResponse {
     pub ok: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchGetPlayerStatesRequest {
     pub server_id: String,
     pub player_uuids: Vec<Uuid>,
     pub module_name: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchPlayerState {
     pub player_uuid: Uuid,
     pub state: Value,
     pub updated_at: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchGetPlayerStatesResponse {
     pub ok: bool,
     pub states: Vec<BatchPlayerState>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchSetPlayerStatesRequest {
     pub server_id: String,
     pub module_name: String,
     pub states: Vec<PlayerStateEntry>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PlayerStateEntry {
     pub player_uuid: Uuid,
     pub state: Value,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct BatchSetPlayerStatesResponse {
     pub ok: bool,
     pub updated: usize,
@@ -381,6 +690,18 @@ pub struct BatchSetPlayerStatesResponse {
 /// GET /callbacks/player-state
 ///
 /// Retrieves persisted state for a single player from a module.
+#[utoipa::path(
+    get,
+    path = "/callbacks/player-state",
+    tag = "callbacks",
+    request_body = GetPlayerStateRequest,
+    responses(
+        (status = 200, body = PlayerStateResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("module_token" = [])),
+)]
 pub async fn get_player_state(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -415,6 +736,18 @@ pub async fn get_player_state(
 /// POST /callbacks/player-state
 ///
 /// Sets/updates persisted state for a single player from a module.
+#[utoipa::path(
+    post,
+    path = "/callbacks/player-state",
+    tag = "callbacks",
+    request_body = SetPlayerStateRequest,
+    responses(
+        (status = 200, body = SetPlayerStateResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("module_token" = [])),
+)]
 pub async fn set_player_state(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -464,6 +797,18 @@ pub async fn set_player_state(
 ///
 /// Retrieves persisted state for multiple players in a single request.
 /// Useful when processing a batch with many players.
+#[utoipa::path(
+    post,
+    path = "/callbacks/player-states/batch-get",
+    tag = "callbacks",
+    request_body = BatchGetPlayerStatesRequest,
+    responses(
+        (status = 200, body = BatchGetPlayerStatesResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("module_token" = [])),
+)]
 pub async fn batch_get_player_states(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -511,6 +856,18 @@ pub async fn batch_get_player_states(
 ///
 /// Sets/updates persisted state for multiple players in a single request.
 /// Useful when processing a batch with many players.
+#[utoipa::path(
+    post,
+    path = "/callbacks/player-states/batch-set",
+    tag = "callbacks",
+    request_body = BatchSetPlayerStatesRequest,
+    responses(
+        (status = 200, body = BatchSetPlayerStatesResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("module_token" = [])),
+)]
 pub async fn batch_set_player_states(
     State(state): State<AppState>,
     headers: HeaderMap,
@@ -0,0 +1,154 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::capture_config::{self, CaptureConfig};
+use crate::{auth, error::ApiError, AppState};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CaptureConfigResponse {
+    pub ok: bool,
+    pub config: CaptureConfig,
+    /// When the config was last saved; null while the server uses the defaults.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// GET /config
+///
+/// Capture settings for the calling plugin. Authenticated with the per-server token.
+#[utoipa::path(
+    get,
+    path = "/config",
+    tag = "plugin",
+    params(
+        ("X-Server-Id" = String, Header, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = CaptureConfigResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("server_token" = [])),
+)]
+pub async fn get_plugin_config(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<CaptureConfigResponse>, ApiError> {
+    let server_id = auth::authenticate_server(&state, &headers).await?;
+    respond(&state, &server_id).await
+}
+
+/// GET /dashboard/:server_id/capture-config
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/capture-config",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = CaptureConfigResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_capture_config(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<CaptureConfigResponse>, ApiError> {
+    respond(&state, server_id.trim()).await
+}
+
+async fn respond(
+    state: &AppState,
+    server_id: &str,
+) -> Result<Json<CaptureConfigResponse>, ApiError> {
+    let (config, updated_at) = capture_config::load(&state.db, server_id, state.max_body_bytes)
+        .await
+        .map_err(|e| {
+            tracing::error!("capture config lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    Ok(Json(CaptureConfigResponse {
+        ok: true,
+        config,
+        updated_at,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateCaptureConfigRequest {
+    /// Full config; omitted fields fall back to their defaults.
+    pub config: CaptureConfig,
+    pub updated_by: Option<String>,
+}
+
+/// POST /dashboard/:server_id/capture-config
+///
+/// Replace the server's capture settings. The plugin receives them as a `config_sync` action on
+/// its next heartbeat (and from `GET /config`).
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/capture-config",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = UpdateCaptureConfigRequest,
+    responses(
+        (status = 200, body = CaptureConfigResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn update_capture_config(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<UpdateCaptureConfigRequest>,
+) -> Result<Json<CaptureConfigResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let config = req
+        .config
+        .validate(state.max_body_bytes)
+        .map_err(ApiError::BadRequest)?;
+
+    let exists: Option<String> = sqlx::query_scalar("select id from public.servers where id = $1")
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("capture config server lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    let updated_by = req
+        .updated_by
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let updated_at = capture_config::save(&state.db, &server_id, &config, updated_by)
+        .await
+        .map_err(|e| {
+            tracing::error!("capture config save failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    tracing::info!(server_id = %server_id, "capture config updated");
+    Ok(Json(CaptureConfigResponse {
+        ok: true,
+        config,
+        updated_at: Some(updated_at),
+    }))
+}
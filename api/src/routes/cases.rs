@@ -0,0 +1,812 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::cases::{self, ItemKind};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Case {
+    pub id: Uuid,
+    pub server_id: String,
+    pub player_uuid: Uuid,
+    pub title: String,
+    /// open | closed
+    pub status: String,
+    /// confirmed_cheater | cleared | insufficient_evidence, once closed.
+    pub outcome: Option<String>,
+    pub summary: Option<String>,
+    pub opened_by: Option<String>,
+    pub closed_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub items: i64,
+    pub actions: i64,
+}
+
+type CaseRow = (
+    Uuid,
+    String,
+    Uuid,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+    i64,
+    i64,
+);
+
+const CASE_COLUMNS: &str = r#"
+    c.id, c.server_id, c.player_uuid, c.title, c.status, c.outcome, c.summary, c.opened_by,
+    c.closed_by, c.created_at, c.updated_at, c.closed_at,
+    (select count(*) from public.case_items i where i.case_id = c.id),
+    (select count(*) from public.case_actions a where a.case_id = c.id)
+"#;
+
+fn case_from_row(row: CaseRow) -> Case {
+    let (
+        id,
+        server_id,
+        player_uuid,
+        title,
+        status,
+        outcome,
+        summary,
+        opened_by,
+        closed_by,
+        created_at,
+        updated_at,
+        closed_at,
+        items,
+        actions,
+    ) = row;
+    Case {
+        id,
+        server_id,
+        player_uuid,
+        title,
+        status,
+        outcome,
+        summary,
+        opened_by,
+        closed_by,
+        created_at,
+        updated_at,
+        closed_at,
+        items,
+        actions,
+    }
+}
+
+fn internal(what: &'static str) -> impl FnOnce(sqlx::Error) -> ApiError {
+    move |e| {
+        tracing::error!("{} failed: {:?}", what, e);
+        ApiError::Internal
+    }
+}
+
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+async fn fetch_case(state: &AppState, server_id: &str, case_id: Uuid) -> Result<Case, ApiError> {
+    let row: Option<CaseRow> = sqlx::query_as(&format!(
+        "select {} from public.player_cases c where c.id = $1 and c.server_id = $2",
+        CASE_COLUMNS
+    ))
+    .bind(case_id)
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(internal("get case"))?;
+    row.map(case_from_row)
+        .ok_or_else(|| ApiError::NotFound(format!("case {} not found", case_id)))
+}
+
+async fn fetch_open_case(
+    state: &AppState,
+    server_id: &str,
+    case_id: Uuid,
+) -> Result<Case, ApiError> {
+    let case = fetch_case(state, server_id, case_id).await?;
+    if case.status != cases::OPEN {
+        return Err(ApiError::Conflict(format!("case {} is closed", case_id)));
+    }
+    Ok(case)
+}
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CasesQuery {
+    pub player_uuid: Option<Uuid>,
+    /// open | closed
+    pub status: Option<String>,
+    /// Defaults to 50, at most 200.
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CasesResponse {
+    pub ok: bool,
+    pub cases: Vec<Case>,
+}
+
+/// GET /dashboard/:server_id/cases
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/cases",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        CasesQuery,
+    ),
+    responses(
+        (status = 200, body = CasesResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_cases(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Query(q): Query<CasesQuery>,
+) -> Result<Json<CasesResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+
+    let rows: Vec<CaseRow> = sqlx::query_as(&format!(
+        r#"
+        select {}
+        from public.player_cases c
+        where c.server_id = $1
+          and ($2::uuid is null or c.player_uuid = $2)
+          and ($3::text is null or c.status = $3)
+        order by c.updated_at desc
+        limit $4
+        "#,
+        CASE_COLUMNS
+    ))
+    .bind(&server_id)
+    .bind(q.player_uuid)
+    .bind(q.status.as_deref().map(str::to_ascii_lowercase))
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal("list cases"))?;
+
+    Ok(Json(CasesResponse {
+        ok: true,
+        cases: rows.into_iter().map(case_from_row).collect(),
+    }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateCaseRequest {
+    pub player_uuid: Uuid,
+    pub title: String,
+    pub summary: Option<String>,
+    /// Moderator opening the case.
+    pub opened_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CaseResponse {
+    pub ok: bool,
+    pub case: Case,
+}
+
+/// POST /dashboard/:server_id/cases
+///
+/// Open a case for a player. A player has at most one open case per server.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/cases",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = CreateCaseRequest,
+    responses(
+        (status = 200, body = CaseResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The player already has an open case", body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn create_case(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<CreateCaseRequest>,
+) -> Result<Json<CaseResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let title = cases::validate_title(&req.title).map_err(ApiError::BadRequest)?;
+
+    let exists: Option<String> = sqlx::query_scalar("select id from public.servers where id = $1")
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(internal("case server lookup"))?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    // The partial unique index keeps a second open case out.
+    let case_id: Option<Uuid> = sqlx::query_scalar(
+        r#"
+        insert into public.player_cases (server_id, player_uuid, title, summary, opened_by)
+        values ($1, $2, $3, $4, $5)
+        on conflict (server_id, player_uuid) where status = 'open' do nothing
+        returning id
+        "#,
+    )
+    .bind(&server_id)
+    .bind(req.player_uuid)
+    .bind(&title)
+    .bind(trimmed(req.summary))
+    .bind(trimmed(req.opened_by))
+    .fetch_optional(&state.db)
+    .await
+    .map_err(internal("create case"))?;
+    let Some(case_id) = case_id else {
+        return Err(ApiError::Conflict(format!(
+            "player {} already has an open case",
+            req.player_uuid
+        )));
+    };
+
+    tracing::info!(server_id = %server_id, case_id = %case_id, player_uuid = %req.player_uuid, "case opened");
+    Ok(Json(CaseResponse {
+        ok: true,
+        case: fetch_case(&state, &server_id, case_id).await?,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CaseAttachment {
+    pub id: Uuid,
+    pub kind: ItemKind,
+    /// Id of the finding, observation or evidence bundle.
+    pub ref_id: Uuid,
+    /// Finding title, observation label / cheat type, or bundle creation time.
+    pub label: Option<String>,
+    pub note: Option<String>,
+    pub added_by: Option<String>,
+    pub added_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CaseAction {
+    pub id: Uuid,
+    pub action: String,
+    pub details: Option<String>,
+    pub taken_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CaseDetailResponse {
+    pub ok: bool,
+    pub case: Case,
+    /// Oldest first.
+    pub items: Vec<CaseAttachment>,
+    /// Oldest first.
+    pub actions: Vec<CaseAction>,
+}
+
+/// GET /dashboard/:server_id/cases/:case_id
+///
+/// The case with its attached findings, observations and evidence bundles and its actions.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/cases/{case_id}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("case_id" = Uuid, Path, description = "Case id"),
+    ),
+    responses(
+        (status = 200, body = CaseDetailResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_case(
+    State(state): State<AppState>,
+    Path((server_id, case_id)): Path<(String, Uuid)>,
+) -> Result<Json<CaseDetailResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let case = fetch_case(&state, &server_id, case_id).await?;
+
+    #[allow(clippy::type_complexity)]
+    let items: Vec<(
+        Uuid,
+        Option<Uuid>,
+        Option<Uuid>,
+        Option<Uuid>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        DateTime<Utc>,
+    )> = sqlx::query_as(
+        r#"
+        select i.id, i.finding_id, i.observation_id, i.evidence_bundle_id,
+               coalesce(f.title, o.label, o.cheat_type, b.created_at::text),
+               i.note, i.added_by, i.added_at
+        from public.case_items i
+        left join public.findings f on f.id = i.finding_id
+        left join public.cheat_observations o on o.id = i.observation_id
+        left join public.evidence_bundles b on b.id = i.evidence_bundle_id
+        where i.case_id = $1
+        order by i.added_at asc
+        "#,
+    )
+    .bind(case_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal("get case items"))?;
+
+    let actions: Vec<(Uuid, String, Option<String>, Option<String>, DateTime<Utc>)> =
+        sqlx::query_as(
+            r#"
+            select id, action, details, taken_by, created_at
+            from public.case_actions
+            where case_id = $1
+            order by created_at asc
+            "#,
+        )
+        .bind(case_id)
+        .fetch_all(&state.db)
+        .await
+        .map_err(internal("get case actions"))?;
+
+    Ok(Json(CaseDetailResponse {
+        ok: true,
+        case,
+        items: items
+            .into_iter()
+            .filter_map(
+                |(id, finding, observation, bundle, label, note, added_by, added_at)| {
+                    let (kind, ref_id) = match (finding, observation, bundle) {
+                        (Some(r), _, _) => (ItemKind::Finding, r),
+                        (_, Some(r), _) => (ItemKind::Observation, r),
+                        (_, _, Some(r)) => (ItemKind::EvidenceBundle, r),
+                        _ => return None,
+                    };
+                    Some(CaseAttachment {
+                        id,
+                        kind,
+                        ref_id,
+                        label,
+                        note,
+                        added_by,
+                        added_at,
+                    })
+                },
+            )
+            .collect(),
+        actions: actions
+            .into_iter()
+            .map(|(id, action, details, taken_by, created_at)| CaseAction {
+                id,
+                action,
+                details,
+                taken_by,
+                created_at,
+            })
+            .collect(),
+    }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateCaseRequest {
+    pub title: Option<String>,
+    pub summary: Option<String>,
+}
+
+/// PATCH /dashboard/:server_id/cases/:case_id
+///
+/// Rename a case or replace its summary (also once closed).
+#[utoipa::path(
+    patch,
+    path = "/dashboard/{server_id}/cases/{case_id}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("case_id" = Uuid, Path, description = "Case id"),
+    ),
+    request_body = UpdateCaseRequest,
+    responses(
+        (status = 200, body = CaseResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn update_case(
+    State(state): State<AppState>,
+    Path((server_id, case_id)): Path<(String, Uuid)>,
+    Json(req): Json<UpdateCaseRequest>,
+) -> Result<Json<CaseResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let title = req
+        .title
+        .as_deref()
+        .map(cases::validate_title)
+        .transpose()
+        .map_err(ApiError::BadRequest)?;
+
+    let updated = sqlx::query(
+        r#"
+        update public.player_cases
+        set title = coalesce($3, title),
+            summary = coalesce($4, summary),
+            updated_at = now()
+        where id = $1 and server_id = $2
+        "#,
+    )
+    .bind(case_id)
+    .bind(&server_id)
+    .bind(title)
+    .bind(trimmed(req.summary))
+    .execute(&state.db)
+    .await
+    .map_err(internal("update case"))?;
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!("case {} not found", case_id)));
+    }
+
+    Ok(Json(CaseResponse {
+        ok: true,
+        case: fetch_case(&state, &server_id, case_id).await?,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeleteCaseResponse {
+    pub ok: bool,
+    pub case_id: Uuid,
+}
+
+/// DELETE /dashboard/:server_id/cases/:case_id
+///
+/// Delete a case with its items and actions. Attached findings, observations and bundles stay.
+#[utoipa::path(
+    delete,
+    path = "/dashboard/{server_id}/cases/{case_id}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("case_id" = Uuid, Path, description = "Case id"),
+    ),
+    responses(
+        (status = 200, body = DeleteCaseResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn delete_case(
+    State(state): State<AppState>,
+    Path((server_id, case_id)): Path<(String, Uuid)>,
+) -> Result<Json<DeleteCaseResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let deleted = sqlx::query("delete from public.player_cases where id = $1 and server_id = $2")
+        .bind(case_id)
+        .bind(&server_id)
+        .execute(&state.db)
+        .await
+        .map_err(internal("delete case"))?;
+    if deleted.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!("case {} not found", case_id)));
+    }
+    tracing::info!(server_id = %server_id, case_id = %case_id, "case deleted");
+    Ok(Json(DeleteCaseResponse { ok: true, case_id }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddCaseItemRequest {
+    pub kind: ItemKind,
+    /// Id of the finding, observation or evidence bundle to attach.
+    pub id: Uuid,
+    pub note: Option<String>,
+    pub added_by: Option<String>,
+}
+
+/// POST /dashboard/:server_id/cases/:case_id/items
+///
+/// Attach a finding or observation of the case's player, or an evidence bundle of one of their
+/// findings. Attaching the same row twice is a no-op.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/cases/{case_id}/items",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("case_id" = Uuid, Path, description = "Case id"),
+    ),
+    request_body = AddCaseItemRequest,
+    responses(
+        (status = 200, body = CaseResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The case is closed", body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn add_case_item(
+    State(state): State<AppState>,
+    Path((server_id, case_id)): Path<(String, Uuid)>,
+    Json(req): Json<AddCaseItemRequest>,
+) -> Result<Json<CaseResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let case = fetch_open_case(&state, &server_id, case_id).await?;
+
+    let owner_sql = match req.kind {
+        ItemKind::Finding => {
+            "select player_uuid from public.findings where id = $1 and server_id = $2"
+        }
+        ItemKind::Observation => {
+            "select player_uuid from public.cheat_observations where id = $1 and server_id = $2"
+        }
+        ItemKind::EvidenceBundle => {
+            r#"
+            select f.player_uuid from public.evidence_bundles b
+            join public.findings f on f.id = b.finding_id
+            where b.id = $1 and b.server_id = $2
+            "#
+        }
+    };
+    let owner: Option<Option<Uuid>> = sqlx::query_scalar(owner_sql)
+        .bind(req.id)
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(internal("case item lookup"))?;
+    match owner {
+        None => {
+            return Err(ApiError::NotFound(format!(
+                "{:?} {} not found on this server",
+                req.kind, req.id
+            )))
+        }
+        Some(player) if player != Some(case.player_uuid) => {
+            return Err(ApiError::BadRequest(format!(
+                "{:?} {} is not about the case's player",
+                req.kind, req.id
+            )))
+        }
+        Some(_) => {}
+    }
+
+    sqlx::query(&format!(
+        r#"
+        insert into public.case_items (case_id, {column}, note, added_by)
+        select $1, $2, $3, $4
+        where not exists (
+            select 1 from public.case_items where case_id = $1 and {column} = $2
+        )
+        "#,
+        column = req.kind.column()
+    ))
+    .bind(case_id)
+    .bind(req.id)
+    .bind(trimmed(req.note))
+    .bind(trimmed(req.added_by))
+    .execute(&state.db)
+    .await
+    .map_err(internal("add case item"))?;
+    sqlx::query("update public.player_cases set updated_at = now() where id = $1")
+        .bind(case_id)
+        .execute(&state.db)
+        .await
+        .map_err(internal("touch case"))?;
+
+    Ok(Json(CaseResponse {
+        ok: true,
+        case: fetch_case(&state, &server_id, case_id).await?,
+    }))
+}
+
+/// DELETE /dashboard/:server_id/cases/:case_id/items/:item_id
+#[utoipa::path(
+    delete,
+    path = "/dashboard/{server_id}/cases/{case_id}/items/{item_id}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("case_id" = Uuid, Path, description = "Case id"),
+        ("item_id" = Uuid, Path, description = "Case item id"),
+    ),
+    responses(
+        (status = 200, body = CaseResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The case is closed", body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn remove_case_item(
+    State(state): State<AppState>,
+    Path((server_id, case_id, item_id)): Path<(String, Uuid, Uuid)>,
+) -> Result<Json<CaseResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    fetch_open_case(&state, &server_id, case_id).await?;
+
+    let deleted = sqlx::query("delete from public.case_items where id = $1 and case_id = $2")
+        .bind(item_id)
+        .bind(case_id)
+        .execute(&state.db)
+        .await
+        .map_err(internal("remove case item"))?;
+    if deleted.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "case item {} not found",
+            item_id
+        )));
+    }
+
+    Ok(Json(CaseResponse {
+        ok: true,
+        case: fetch_case(&state, &server_id, case_id).await?,
+    }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddCaseActionRequest {
+    /// warning | kick | mute | temp_ban | ban | unban | other
+    pub action: String,
+    pub details: Option<String>,
+    pub taken_by: Option<String>,
+}
+
+/// POST /dashboard/:server_id/cases/:case_id/actions
+///
+/// Record an action taken against the player. Only recorded: nothing is sent to the server
+/// (bans go through `punishments`).
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/cases/{case_id}/actions",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("case_id" = Uuid, Path, description = "Case id"),
+    ),
+    request_body = AddCaseActionRequest,
+    responses(
+        (status = 200, body = CaseResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The case is closed", body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn add_case_action(
+    State(state): State<AppState>,
+    Path((server_id, case_id)): Path<(String, Uuid)>,
+    Json(req): Json<AddCaseActionRequest>,
+) -> Result<Json<CaseResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let action = cases::validate_choice("action", &req.action, &cases::ACTIONS)
+        .map_err(ApiError::BadRequest)?;
+    fetch_open_case(&state, &server_id, case_id).await?;
+
+    sqlx::query(
+        "insert into public.case_actions (case_id, action, details, taken_by) values ($1, $2, $3, $4)",
+    )
+    .bind(case_id)
+    .bind(&action)
+    .bind(trimmed(req.details))
+    .bind(trimmed(req.taken_by))
+    .execute(&state.db)
+    .await
+    .map_err(internal("add case action"))?;
+    sqlx::query("update public.player_cases set updated_at = now() where id = $1")
+        .bind(case_id)
+        .execute(&state.db)
+        .await
+        .map_err(internal("touch case"))?;
+
+    Ok(Json(CaseResponse {
+        ok: true,
+        case: fetch_case(&state, &server_id, case_id).await?,
+    }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CloseCaseRequest {
+    /// confirmed_cheater | cleared | insufficient_evidence
+    pub outcome: String,
+    /// Replaces the case summary when set.
+    pub summary: Option<String>,
+    pub closed_by: Option<String>,
+}
+
+/// POST /dashboard/:server_id/cases/:case_id/close
+///
+/// Close a case with an outcome. `confirmed_cheater` confirms its still-open attached findings.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/cases/{case_id}/close",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("case_id" = Uuid, Path, description = "Case id"),
+    ),
+    request_body = CloseCaseRequest,
+    responses(
+        (status = 200, body = CaseResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The case is already closed", body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn close_case(
+    State(state): State<AppState>,
+    Path((server_id, case_id)): Path<(String, Uuid)>,
+    Json(req): Json<CloseCaseRequest>,
+) -> Result<Json<CaseResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let outcome = cases::validate_choice("outcome", &req.outcome, &cases::OUTCOMES)
+        .map_err(ApiError::BadRequest)?;
+
+    let closed = sqlx::query(
+        r#"
+        update public.player_cases
+        set status = $3,
+            outcome = $4,
+            summary = coalesce($5, summary),
+            closed_by = $6,
+            closed_at = now(),
+            updated_at = now()
+        where id = $1 and server_id = $2 and status = 'open'
+        "#,
+    )
+    .bind(case_id)
+    .bind(&server_id)
+    .bind(cases::CLOSED)
+    .bind(&outcome)
+    .bind(trimmed(req.summary))
+    .bind(trimmed(req.closed_by))
+    .execute(&state.db)
+    .await
+    .map_err(internal("close case"))?;
+    if closed.rows_affected() == 0 {
+        // Either missing (404) or already closed (409).
+        fetch_open_case(&state, &server_id, case_id).await?;
+        return Err(ApiError::Internal);
+    }
+
+    if outcome == cases::CONFIRMED_CHEATER {
+        cases::confirm_case_findings(&state.db, &server_id, case_id).await;
+    }
+
+    tracing::info!(server_id = %server_id, case_id = %case_id, outcome = %outcome, "case closed");
+    Ok(Json(CaseResponse {
+        ok: true,
+        case: fetch_case(&state, &server_id, case_id).await?,
+    }))
+}
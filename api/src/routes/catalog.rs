@@ -0,0 +1,242 @@
+//! Module catalog ("marketplace").
+//!
+//! Lists the built-in modules together with curated community modules from the
+//! `module_catalog` table, and lets the dashboard enable any of them for a server in one click.
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{builtin_modules, error::ApiError, routes::modules::ServerModule, AppState};
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CatalogEntry {
+    pub name: String,
+    /// "builtin" | "community"
+    pub source: String,
+    /// "core" | "advanced" | "community"
+    pub tier: String,
+    pub short_description: String,
+    pub checks: Vec<String>,
+    /// Transform the module expects (set on `server_modules.transform` when enabled).
+    pub transform: String,
+    pub default_base_url: Option<String>,
+    pub install_instructions: Option<String>,
+    pub homepage_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CatalogResponse {
+    pub ok: bool,
+    pub modules: Vec<CatalogEntry>,
+}
+
+fn builtin_entries() -> Vec<CatalogEntry> {
+    builtin_modules::BUILTIN_MODULES
+        .iter()
+        .map(|m| CatalogEntry {
+            name: m.name.to_string(),
+            source: "builtin".to_string(),
+            tier: m.tier.as_str().to_string(),
+            short_description: m.short_description.to_string(),
+            checks: m.check_names(),
+            transform: m.transform.to_string(),
+            default_base_url: Some(builtin_modules::default_base_url(m.default_port)),
+            install_instructions: Some(format!(
+                "Run the {} module binary listening on 127.0.0.1:{} (or add it to MODULE_SUPERVISOR_CONFIG).",
+                m.name, m.default_port
+            )),
+            homepage_url: None,
+        })
+        .collect()
+}
+
+async fn community_entries(state: &AppState) -> Result<Vec<CatalogEntry>, ApiError> {
+    let rows: Vec<(
+        String,
+        String,
+        String,
+        Vec<String>,
+        String,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    )> = sqlx::query_as(
+        r#"
+        select name, tier, short_description, checks, transform,
+               default_base_url, install_instructions, homepage_url
+        from public.module_catalog
+        where listed = true
+        order by name asc
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("module catalog query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(
+                name,
+                tier,
+                short_description,
+                checks,
+                transform,
+                default_base_url,
+                install_instructions,
+                homepage_url,
+            )| CatalogEntry {
+                name,
+                source: "community".to_string(),
+                tier,
+                short_description,
+                checks,
+                transform,
+                default_base_url,
+                install_instructions,
+                homepage_url,
+            },
+        )
+        .collect())
+}
+
+/// GET /modules/catalog
+///
+/// Built-in modules followed by listed community modules.
+#[utoipa::path(
+    get,
+    path = "/modules/catalog",
+    tag = "modules",
+    responses(
+        (status = 200, body = CatalogResponse),
+        (status = 500, body = ErrorBody),
+    ),
+)]
+pub async fn get_catalog(State(state): State<AppState>) -> Result<Json<CatalogResponse>, ApiError> {
+    let mut modules = builtin_entries();
+    // Built-ins win on name clashes so the catalog can't shadow them.
+    for entry in community_entries(&state).await? {
+        if !modules.iter().any(|m| m.name == entry.name) {
+            modules.push(entry);
+        }
+    }
+    Ok(Json(CatalogResponse { ok: true, modules }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct EnableCatalogModuleRequest {
+    pub name: String,
+    /// Overrides the catalog's default base_url (required when the catalog has none).
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EnableCatalogModuleResponse {
+    pub ok: bool,
+    pub module: ServerModule,
+}
+
+/// POST /dashboard/:server_id/modules/catalog/enable
+///
+/// Creates (or re-enables) the `server_modules` row for a catalog entry using its defaults.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/modules/catalog/enable",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = EnableCatalogModuleRequest,
+    responses(
+        (status = 200, body = EnableCatalogModuleResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn enable_catalog_module(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<EnableCatalogModuleRequest>,
+) -> Result<Json<EnableCatalogModuleResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let name = req.name.trim();
+    if server_id.is_empty() {
+        return Err(ApiError::BadRequest("server_id is required".to_string()));
+    }
+
+    let entry = match builtin_entries().into_iter().find(|m| m.name == name) {
+        Some(e) => e,
+        None => community_entries(&state)
+            .await?
+            .into_iter()
+            .find(|m| m.name == name)
+            .ok_or_else(|| ApiError::BadRequest(format!("unknown catalog module: {}", name)))?,
+    };
+
+    let base_url = req
+        .base_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .or(entry.default_base_url.clone())
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!("base_url is required for module {}", entry.name))
+        })?;
+
+    let module = sqlx::query_as::<_, ServerModule>(
+        r#"
+        insert into public.server_modules
+            (server_id, name, base_url, enabled, transform, created_at, updated_at)
+        values
+            ($1, $2, $3, true, $4, now(), now())
+        on conflict (server_id, name) do update set
+            base_url = excluded.base_url,
+            enabled = true,
+            transform = excluded.transform,
+            updated_at = now()
+        returning
+            id,
+            server_id,
+            name,
+            base_url,
+            backup_base_urls,
+            canary_base_url,
+            canary_percent,
+            mode,
+            enabled,
+            transform,
+            last_healthcheck_ok,
+            last_error,
+            content_encoding,
+            processing_deadline_seconds
+        "#,
+    )
+    .bind(&server_id)
+    .bind(&entry.name)
+    .bind(&base_url)
+    .bind(&entry.transform)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("enable catalog module failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    tracing::info!(
+        server_id = %server_id,
+        module = %entry.name,
+        source = %entry.source,
+        "catalog module enabled"
+    );
+
+    Ok(Json(EnableCatalogModuleResponse { ok: true, module }))
+}
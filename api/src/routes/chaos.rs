@@ -0,0 +1,58 @@
+//! `/admin/chaos`: fault rules of builds with the `chaos` feature (see `crate::chaos`).
+//!
+//! Dev-only, so the routes are registered only with the feature and left out of the OpenAPI doc.
+
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::chaos::{FaultRule, MAX_RULES};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Deserialize)]
+pub struct SetFaultsRequest {
+    pub rules: Vec<FaultRule>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FaultsResponse {
+    pub ok: bool,
+    pub rules: Vec<FaultRule>,
+}
+
+/// GET /admin/chaos
+pub async fn get_faults(State(state): State<AppState>) -> Json<FaultsResponse> {
+    Json(FaultsResponse {
+        ok: true,
+        rules: state.faults.list(),
+    })
+}
+
+/// PUT /admin/chaos
+///
+/// Replace the fault rules; an empty list turns injection off.
+pub async fn set_faults(
+    State(state): State<AppState>,
+    Json(req): Json<SetFaultsRequest>,
+) -> Result<Json<FaultsResponse>, ApiError> {
+    if req.rules.len() > MAX_RULES {
+        return Err(ApiError::BadRequest(format!("at most {} rules", MAX_RULES)));
+    }
+    for rule in &req.rules {
+        rule.validate().map_err(ApiError::BadRequest)?;
+    }
+    tracing::warn!(rules = req.rules.len(), "fault injection rules replaced");
+    state.faults.set(req.rules);
+    Ok(Json(FaultsResponse {
+        ok: true,
+        rules: state.faults.list(),
+    }))
+}
+
+/// DELETE /admin/chaos
+pub async fn clear_faults(State(state): State<AppState>) -> Json<FaultsResponse> {
+    state.faults.set(Vec::new());
+    Json(FaultsResponse {
+        ok: true,
+        rules: Vec::new(),
+    })
+}
@@ -0,0 +1,85 @@
+//! Per-check reference documentation (`/checks/:check_name`) from the built-in module
+//! definitions, for contextual help next to a finding's detector name.
+
+use axum::{extract::Path, Json};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::builtin_modules::{self, BuiltinTier};
+use crate::error::ApiError;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CheckParameter {
+    pub name: String,
+    pub default: f64,
+    pub unit: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CheckDoc {
+    pub name: String,
+    /// Built-in module that runs the check.
+    pub module: String,
+    pub tier: BuiltinTier,
+    pub description: String,
+    /// Typical causes of false positives.
+    pub false_positives: Vec<String>,
+    /// Overridable per server via `/dashboard/:server_id/check-thresholds/:detector_name`.
+    pub parameters: Vec<CheckParameter>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CheckDocResponse {
+    pub ok: bool,
+    pub check: CheckDoc,
+}
+
+/// GET /checks/:check_name
+#[utoipa::path(
+    get,
+    path = "/checks/{check_name}",
+    tag = "modules",
+    params(
+        ("check_name" = String, Path, description = "Detector name, e.g. movement_core_flight_ascend"),
+    ),
+    responses(
+        (status = 200, body = CheckDocResponse),
+        (status = 404, body = ErrorBody),
+    ),
+)]
+pub async fn get_check_doc(
+    Path(check_name): Path<String>,
+) -> Result<Json<CheckDocResponse>, ApiError> {
+    let Some((module, check)) = builtin_modules::check_by_name(&check_name) else {
+        return Err(ApiError::NotFound(format!(
+            "no documentation for check {}",
+            check_name.trim()
+        )));
+    };
+
+    Ok(Json(CheckDocResponse {
+        ok: true,
+        check: CheckDoc {
+            name: check.name.to_string(),
+            module: module.name.to_string(),
+            tier: module.tier,
+            description: check.description.to_string(),
+            false_positives: check
+                .false_positives
+                .iter()
+                .map(|f| (*f).to_string())
+                .collect(),
+            parameters: check
+                .parameters
+                .iter()
+                .map(|p| CheckParameter {
+                    name: p.name.to_string(),
+                    default: p.default,
+                    unit: p.unit.to_string(),
+                    description: p.description.to_string(),
+                })
+                .collect(),
+        },
+    }))
+}
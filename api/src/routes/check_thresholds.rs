@@ -0,0 +1,189 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::checks::{self, ProtocolThreshold, ThresholdOverride, TUNABLE_CHECKS};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CheckThreshold {
+    pub detector_name: String,
+    /// Built-in threshold.
+    pub default: f64,
+    /// Built-in threshold for 1.8 clients (protocol 47 and lower), where it differs.
+    pub legacy_default: Option<f64>,
+    /// Saved override for Java players (and Bedrock players without their own).
+    pub threshold: Option<f64>,
+    /// Saved override for Bedrock (Geyser) players.
+    pub bedrock_threshold: Option<f64>,
+    /// Saved overrides per client protocol version range.
+    pub protocol_thresholds: Vec<ProtocolThreshold>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CheckThresholdsResponse {
+    pub ok: bool,
+    pub checks: Vec<CheckThreshold>,
+}
+
+/// GET /dashboard/:server_id/check-thresholds
+///
+/// Thresholds of the in-process checks: built-in values and the server's overrides.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/check-thresholds",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = CheckThresholdsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_check_thresholds(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<CheckThresholdsResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let rows: Vec<(String, sqlx::types::Json<ThresholdOverride>, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        select detector_name, config_json, updated_at
+        from public.detector_configs
+        where server_id = $1
+        "#,
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("check thresholds query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let mut saved: HashMap<String, (ThresholdOverride, DateTime<Utc>)> = rows
+        .into_iter()
+        .map(|(name, o, updated_at)| (name, (o.0, updated_at)))
+        .collect();
+
+    let checks = TUNABLE_CHECKS
+        .iter()
+        .map(|(name, default)| {
+            let (o, updated_at) = match saved.remove(*name) {
+                Some((o, updated_at)) => (o, Some(updated_at)),
+                None => (ThresholdOverride::default(), None),
+            };
+            CheckThreshold {
+                detector_name: name.to_string(),
+                default: *default,
+                legacy_default: legacy_default(name),
+                threshold: o.threshold,
+                bedrock_threshold: o.bedrock_threshold,
+                protocol_thresholds: o.protocol_thresholds,
+                updated_at,
+            }
+        })
+        .collect();
+
+    Ok(Json(CheckThresholdsResponse { ok: true, checks }))
+}
+
+/// POST /dashboard/:server_id/check-thresholds/:detector_name
+///
+/// Replace a check's overrides; omitted values fall back to the built-in threshold. Applied to
+/// batches ingested from then on (and to reprocessing jobs).
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/check-thresholds/{detector_name}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("detector_name" = String, Path, description = "Detector name, e.g. movement_core_speed_blatant"),
+    ),
+    request_body = ThresholdOverride,
+    responses(
+        (status = 200, body = CheckThreshold),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn update_check_threshold(
+    State(state): State<AppState>,
+    Path((server_id, detector_name)): Path<(String, String)>,
+    Json(req): Json<ThresholdOverride>,
+) -> Result<Json<CheckThreshold>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let detector_name = detector_name.trim().to_string();
+    let Some((_, default)) = TUNABLE_CHECKS
+        .iter()
+        .find(|(name, _)| *name == detector_name)
+    else {
+        return Err(ApiError::NotFound(format!(
+            "check {} has no tunable threshold",
+            detector_name
+        )));
+    };
+    let o = req.validate().map_err(ApiError::BadRequest)?;
+
+    let exists: Option<String> = sqlx::query_scalar("select id from public.servers where id = $1")
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("check threshold server lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    let updated_at: DateTime<Utc> = sqlx::query_scalar(
+        r#"
+        insert into public.detector_configs (server_id, detector_name, config_json, updated_at)
+        values ($1, $2, $3, now())
+        on conflict (server_id, detector_name) do update set
+            config_json = excluded.config_json,
+            updated_at = excluded.updated_at
+        returning updated_at
+        "#,
+    )
+    .bind(&server_id)
+    .bind(&detector_name)
+    .bind(sqlx::types::Json(&o))
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("check threshold save failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    tracing::info!(server_id = %server_id, detector = %detector_name, "check threshold updated");
+    Ok(Json(CheckThreshold {
+        legacy_default: legacy_default(&detector_name),
+        detector_name,
+        default: *default,
+        threshold: o.threshold,
+        bedrock_threshold: o.bedrock_threshold,
+        protocol_thresholds: o.protocol_thresholds,
+        updated_at: Some(updated_at),
+    }))
+}
+
+fn legacy_default(detector_name: &str) -> Option<f64> {
+    checks::LEGACY_DEFAULTS
+        .iter()
+        .find(|(name, _)| *name == detector_name)
+        .map(|(_, legacy)| *legacy)
+}
@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::webhooks::LowConfidenceAction;
+use crate::{error::ApiError, AppState};
+
+/// Webhook handling of findings their detector is unsure about.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConfidenceFilter {
+    /// Findings reported below this confidence (0-1) get `low_confidence_action`; null disables
+    /// the filter. Findings without a confidence always pass.
+    pub min_confidence: Option<f64>,
+    /// downgrade (notify one severity level lower) | drop (don't notify)
+    pub low_confidence_action: String,
+}
+
+impl ConfidenceFilter {
+    fn validate(&self) -> Result<(Option<f64>, LowConfidenceAction), ApiError> {
+        if let Some(min) = self.min_confidence {
+            if !(0.0..=1.0).contains(&min) {
+                return Err(ApiError::BadRequest(
+                    "min_confidence must be between 0 and 1".to_string(),
+                ));
+            }
+        }
+        let action = LowConfidenceAction::parse(&self.low_confidence_action).ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "low_confidence_action must be one of {}",
+                LowConfidenceAction::ALL.join(", ")
+            ))
+        })?;
+        Ok((self.min_confidence, action))
+    }
+}
+
+/// GET /dashboard/:server_id/confidence-filter
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/confidence-filter",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = ConfidenceFilter),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_confidence_filter(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<ConfidenceFilter>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let row: Option<(Option<f64>, String)> = sqlx::query_as(
+        "select min_confidence, low_confidence_action from public.servers where id = $1",
+    )
+    .bind(&server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("confidence filter query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let Some((min_confidence, low_confidence_action)) = row else {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    };
+
+    Ok(Json(ConfidenceFilter {
+        min_confidence,
+        low_confidence_action,
+    }))
+}
+
+/// POST /dashboard/:server_id/confidence-filter
+///
+/// Replace the server's confidence filter; applied to webhook notifications from then on.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/confidence-filter",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = ConfidenceFilter,
+    responses(
+        (status = 200, body = ConfidenceFilter),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn update_confidence_filter(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<ConfidenceFilter>,
+) -> Result<Json<ConfidenceFilter>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let (min_confidence, action) = req.validate()?;
+
+    let res = sqlx::query(
+        "update public.servers set min_confidence = $2, low_confidence_action = $3 where id = $1",
+    )
+    .bind(&server_id)
+    .bind(min_confidence)
+    .bind(action.as_str())
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("confidence filter update failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    if res.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    Ok(Json(ConfidenceFilter {
+        min_confidence,
+        low_confidence_action: action.as_str().to_string(),
+    }))
+}
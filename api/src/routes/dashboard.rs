@@ -3,12 +3,19 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
-use tokio::net::TcpStream;
-use tokio::time::timeout;
+use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::{builtin_modules, error::ApiError, AppState};
+use crate::batch_sequence::{self, IngestIntegrity};
+use crate::builtin_modules::{self, BuiltinModuleInfo, BuiltinTier};
+use crate::cases::{self, CaseSummary};
+use crate::module_failover::{self, EndpointHealth};
+use crate::notes::{self, ModeratorNote};
+use crate::{
+    detector_metrics, error::ApiError, identity, pagination, server_ping, server_presence,
+    supervisor::SupervisedStatus, AppState,
+};
 
 // ============================================================================
 // Dashboard API Routes
@@ -16,7 +23,7 @@ use crate::{builtin_modules, error::ApiError, AppState};
 // These endpoints serve the asyncanticheat.com dashboard frontend.
 // ============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DashboardStats {
     pub total_findings: i64,
     pub active_modules: i64,
@@ -24,20 +31,51 @@ pub struct DashboardStats {
     pub findings_today: i64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WorldStats {
+    /// None for findings without world context.
+    pub world: Option<String>,
+    pub findings: i64,
+    pub players: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DashboardStatsResponse {
     pub ok: bool,
     pub stats: DashboardStats,
+    /// Findings grouped by world, busiest first.
+    pub by_world: Vec<WorldStats>,
 }
 
+/// How long homepage stats are served from the shared cache.
+const STATS_CACHE_SECONDS: u64 = 30;
+
 /// GET /dashboard/:server_id/stats
 ///
-/// Returns aggregate stats for the dashboard homepage.
+/// Returns aggregate stats for the dashboard homepage (cached for 30s).
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/stats",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = DashboardStatsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
 pub async fn get_stats(
     State(state): State<AppState>,
     Path(server_id): Path<String>,
 ) -> Result<Json<DashboardStatsResponse>, ApiError> {
     let server_id = server_id.trim().to_string();
+    let cache_key = format!("dashboard_stats:{}", server_id);
+    if let Some(cached) = state.shared.get_json(&cache_key).await {
+        return Ok(Json(cached));
+    }
+
     // Total findings for this server
     let total_findings: (i64,) = sqlx::query_as(
         "SELECT COALESCE(SUM(occurrences), 0) FROM public.findings WHERE server_id = $1",
@@ -74,26 +112,64 @@ pub async fn get_stats(
     .await
     .unwrap_or((0,));
 
-    Ok(Json(DashboardStatsResponse {
+    let by_world: Vec<(Option<String>, i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT world, COALESCE(SUM(occurrences), 0), COUNT(DISTINCT player_uuid)
+        FROM public.findings
+        WHERE server_id = $1
+        GROUP BY world
+        ORDER BY 2 DESC
+        LIMIT 50
+        "#,
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let resp = DashboardStatsResponse {
         ok: true,
+        by_world: by_world
+            .into_iter()
+            .map(|(world, findings, players)| WorldStats {
+                world,
+                findings,
+                players,
+            })
+            .collect(),
         stats: DashboardStats {
             total_findings: total_findings.0,
             active_modules: active_modules.0,
             players_monitored: players_monitored.0,
             findings_today: findings_today.0,
         },
-    }))
+    };
+    state
+        .shared
+        .set_json(
+            &cache_key,
+            &resp,
+            std::time::Duration::from_secs(STATS_CACHE_SECONDS),
+        )
+        .await;
+    Ok(Json(resp))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct FindingsQuery {
     pub severity: Option<String>,
     pub player: Option<String>,
+    pub world: Option<String>,
+    /// Only findings at least this confident (findings without a confidence always match).
+    pub min_confidence: Option<f64>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    /// `next_cursor` of the previous page; takes precedence over `offset`.
+    pub cursor: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct FindingItem {
     pub id: Uuid,
     pub player_uuid: Option<Uuid>,
@@ -103,27 +179,109 @@ pub struct FindingItem {
     pub title: String,
     pub description: Option<String>,
     pub occurrences: i32,
+    /// open | confirmed | dismissed | false_positive
+    pub status: String,
+    pub world: Option<String>,
+    /// Detector confidence 0-1, when reported.
+    pub confidence: Option<f64>,
+    /// Anticheat log the finding was imported from (`ncp`, `grim`, `vulcan`); None for our own.
+    pub source: Option<String>,
     pub created_at: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct FindingsResponse {
     pub ok: bool,
     pub findings: Vec<FindingItem>,
     pub total: i64,
+    /// Pass as `cursor` for the next page; null on the last one.
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+type FindingRow = (
+    Uuid,
+    Option<Uuid>,
+    Option<String>,
+    String,
+    String,
+    String,
+    Option<String>,
+    i32,
+    String,
+    Option<String>,
+    Option<f64>,
+    Option<String>,
+    chrono::DateTime<chrono::Utc>,
+    // created_at: the findings list's keyset.
+    chrono::DateTime<chrono::Utc>,
+);
+
+fn finding_item(row: FindingRow) -> FindingItem {
+    let (
+        id,
+        player_uuid,
+        player_name,
+        detector_name,
+        severity,
+        title,
+        description,
+        occurrences,
+        status,
+        world,
+        confidence,
+        source,
+        last_seen_at,
+        _created_at,
+    ) = row;
+    FindingItem {
+        id,
+        player_uuid,
+        player_name,
+        detector_name,
+        severity,
+        title,
+        description,
+        occurrences,
+        status,
+        world,
+        confidence,
+        source,
+        created_at: last_seen_at.to_rfc3339(),
+    }
 }
 
 /// GET /dashboard/:server_id/findings
 ///
-/// Returns paginated findings for the findings page.
+/// Returns paginated findings for the findings page, most recently created first.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/findings",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        FindingsQuery,
+    ),
+    responses(
+        (status = 200, body = FindingsResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
 pub async fn get_findings(
     State(state): State<AppState>,
     Path(server_id): Path<String>,
     Query(params): Query<FindingsQuery>,
 ) -> Result<Json<FindingsResponse>, ApiError> {
     let server_id = server_id.trim().to_string();
-    let limit = params.limit.unwrap_or(50).min(100);
-    let offset = params.offset.unwrap_or(0);
+    let limit = pagination::limit(params.limit, 50, 100);
+    let cursor = pagination::parse(params.cursor.as_deref())?;
+    let offset = if cursor.is_some() {
+        0
+    } else {
+        params.offset.unwrap_or(0).max(0)
+    };
 
     // Build dynamic query based on filters
     let mut conditions: Vec<String> = vec!["f.server_id = $1".to_string()];
@@ -148,7 +306,31 @@ pub async fn get_findings(
         bind_idx += 1;
     }
 
+    // Optional world filter
+    if params.world.is_some() {
+        conditions.push(format!("f.world = ${}", bind_idx));
+        bind_idx += 1;
+    }
+
+    // Optional confidence floor
+    if params.min_confidence.is_some() {
+        conditions.push(format!("coalesce(f.confidence, 1) >= ${}", bind_idx));
+        bind_idx += 1;
+    }
+
     let where_clause = conditions.join(" AND ");
+    // The cursor only narrows the page, not the total. Pages are keyed on the creation time:
+    // last_seen_at moves whenever a finding recurs, which would shift it across pages.
+    let page_clause = if cursor.is_some() {
+        bind_idx += 2;
+        format!(
+            "AND (f.created_at, f.id) < (${}, ${})",
+            bind_idx - 2,
+            bind_idx - 1
+        )
+    } else {
+        String::new()
+    };
 
     let base_query = format!(
         r#"
@@ -161,14 +343,20 @@ pub async fn get_findings(
             f.title, 
             f.description,
             f.occurrences,
-            f.last_seen_at
+            f.status,
+            f.world,
+            f.confidence,
+            f.source,
+            f.last_seen_at,
+            f.created_at
         FROM public.findings f
         LEFT JOIN public.players p ON f.player_uuid = p.uuid
-        WHERE {}
-        ORDER BY f.last_seen_at DESC
+        WHERE {} {}
+        ORDER BY f.created_at DESC, f.id DESC
         LIMIT ${} OFFSET ${}
         "#,
         where_clause,
+        page_clause,
         bind_idx,
         bind_idx + 1
     );
@@ -184,20 +372,8 @@ pub async fn get_findings(
         where_clause
     );
 
-    let findings: Vec<(
-        Uuid,
-        Option<Uuid>,
-        Option<String>,
-        String,
-        String,
-        String,
-        Option<String>,
-        i32,
-        chrono::DateTime<chrono::Utc>,
-    )>;
-    let total: (i64,);
-
-    // Build queries with consistent bind ordering: server_id, severity?, player?, limit, offset
+    // Build queries with consistent bind ordering: server_id, severity?, player?, world?,
+    // min_confidence?, cursor?, limit, offset
     let mut q = sqlx::query_as(&base_query).bind(&server_id);
     let mut q_count = sqlx::query_as(&count_query).bind(&server_id);
 
@@ -216,8 +392,22 @@ pub async fn get_findings(
         }
     }
 
-    findings = q
-        .bind(limit)
+    if let Some(ref world) = params.world {
+        q = q.bind(world);
+        q_count = q_count.bind(world);
+    }
+
+    if let Some(min_confidence) = params.min_confidence {
+        q = q.bind(min_confidence);
+        q_count = q_count.bind(min_confidence);
+    }
+
+    if let Some(cursor) = cursor {
+        q = q.bind(cursor.at).bind(cursor.id);
+    }
+
+    let findings: Vec<FindingRow> = q
+        .bind(limit + 1)
         .bind(offset)
         .fetch_all(&state.db)
         .await
@@ -226,45 +416,94 @@ pub async fn get_findings(
             ApiError::Internal
         })?;
 
-    total = q_count.fetch_one(&state.db).await.unwrap_or((0,));
+    let total: (i64,) = q_count.fetch_one(&state.db).await.unwrap_or((0,));
 
-    let items: Vec<FindingItem> = findings
-        .into_iter()
-        .map(
-            |(
-                id,
-                player_uuid,
-                player_name,
-                detector_name,
-                severity,
-                title,
-                description,
-                occurrences,
-                last_seen_at,
-            )| {
-                FindingItem {
-                    id,
-                    player_uuid,
-                    player_name,
-                    detector_name,
-                    severity,
-                    title,
-                    description,
-                    occurrences,
-                    created_at: last_seen_at.to_rfc3339(),
-                }
-            },
-        )
-        .collect();
+    let (findings, next_cursor) = pagination::page(findings, limit, |row| pagination::Cursor {
+        at: row.13,
+        id: row.0,
+    });
+    let items: Vec<FindingItem> = findings.into_iter().map(finding_item).collect();
 
     Ok(Json(FindingsResponse {
         ok: true,
         findings: items,
         total: total.0,
+        next_cursor,
     }))
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FindingDetailResponse {
+    pub ok: bool,
+    pub finding: FindingItem,
+    /// Moderator comments, oldest first.
+    pub comments: Vec<ModeratorNote>,
+}
+
+/// GET /dashboard/:server_id/findings/:finding_id
+///
+/// One finding with its moderator comments.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/findings/{finding_id}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("finding_id" = Uuid, Path, description = "Finding id"),
+    ),
+    responses(
+        (status = 200, body = FindingDetailResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_finding(
+    State(state): State<AppState>,
+    Path((server_id, finding_id)): Path<(String, Uuid)>,
+) -> Result<Json<FindingDetailResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let row: Option<FindingRow> = sqlx::query_as(
+        r#"
+        SELECT f.id, f.player_uuid, p.username, f.detector_name, f.severity, f.title,
+               f.description, f.occurrences, f.status, f.world, f.confidence, f.source,
+               f.last_seen_at, f.created_at
+        FROM public.findings f
+        LEFT JOIN public.players p ON f.player_uuid = p.uuid
+        WHERE f.id = $1 AND f.server_id = $2
+        "#,
+    )
+    .bind(finding_id)
+    .bind(&server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("get finding failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let Some(row) = row else {
+        return Err(ApiError::NotFound(format!(
+            "finding {} not found",
+            finding_id
+        )));
+    };
+
+    let comments = notes::finding_comments(&state.db, &server_id, finding_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("get finding comments failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    Ok(Json(FindingDetailResponse {
+        ok: true,
+        finding: finding_item(row),
+        comments,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PlayerItem {
     pub uuid: Uuid,
     pub username: String,
@@ -274,51 +513,96 @@ pub struct PlayerItem {
     pub detectors: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ActivePlayer {
     pub uuid: Uuid,
     pub username: String,
     pub last_seen: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct PlayersResponse {
     pub ok: bool,
     pub players: Vec<PlayerItem>,
+    /// Only on the first page.
     pub active_players: Vec<ActivePlayer>,
+    /// Pass as `cursor` for the next page of `players`; null on the last one.
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PlayersQuery {
+    /// Players per page (default 50, at most 100).
+    pub limit: Option<i64>,
+    /// `next_cursor` of the previous page.
+    pub cursor: Option<String>,
 }
 
 /// GET /dashboard/:server_id/players
 ///
-/// Returns players with their findings summary for the dashboard.
+/// Returns players with their findings summary for the dashboard, most occurrences first.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/players",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        PlayersQuery,
+    ),
+    responses(
+        (status = 200, body = PlayersResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
 pub async fn get_players(
     State(state): State<AppState>,
     Path(server_id): Path<String>,
+    Query(params): Query<PlayersQuery>,
 ) -> Result<Json<PlayersResponse>, ApiError> {
     let server_id = server_id.trim().to_string();
-    // Get players with aggregated stats
+    let limit = pagination::limit(params.limit, 50, 100);
+    let cursor: Option<pagination::RankCursor> = pagination::parse_as(params.cursor.as_deref())?;
+    // Get players with aggregated stats. The ranking is recomputed for every page (see
+    // `pagination`); the cursor skips the players ranked above it.
     let rows: Vec<(Uuid, String, i64, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
         r#"
-        SELECT 
-            p.uuid,
-            p.username,
-            COALESCE(SUM(f.occurrences), 0) as findings_count,
-            MAX(f.last_seen_at) as last_finding
-        FROM public.players p
-        INNER JOIN public.findings f ON p.uuid = f.player_uuid
-        WHERE f.server_id = $1
-        GROUP BY p.uuid, p.username
-        ORDER BY COALESCE(SUM(f.occurrences), 0) DESC
-        LIMIT 50
+        WITH ranked AS (
+            SELECT 
+                p.uuid,
+                p.username,
+                COALESCE(SUM(f.occurrences), 0)::bigint as findings_count,
+                MAX(f.last_seen_at) as last_finding
+            FROM public.players p
+            INNER JOIN public.findings f ON p.uuid = f.player_uuid
+            WHERE f.server_id = $1
+            GROUP BY p.uuid, p.username
+        )
+        SELECT uuid, username, findings_count, last_finding
+        FROM ranked
+        WHERE $2::bigint IS NULL OR (findings_count, uuid) < ($2, $3)
+        ORDER BY findings_count DESC, uuid DESC
+        LIMIT $4
         "#,
     )
     .bind(&server_id)
+    .bind(cursor.map(|c| c.rank))
+    .bind(cursor.map(|c| c.id))
+    .bind(limit + 1)
     .fetch_all(&state.db)
     .await
     .map_err(|e| {
         tracing::error!("get players failed: {:?}", e);
         ApiError::Internal
     })?;
+    let (rows, next_cursor) = pagination::page(rows, limit, |(uuid, _, findings_count, _)| {
+        pagination::RankCursor {
+            rank: *findings_count,
+            id: *uuid,
+        }
+    });
 
     let mut players = Vec::new();
     for (uuid, username, findings_count, last_finding) in rows {
@@ -373,8 +657,11 @@ pub async fn get_players(
     let players_with_findings: std::collections::HashSet<Uuid> =
         players.iter().map(|p| p.uuid).collect();
 
-    let active_rows: Vec<(Uuid, String, chrono::DateTime<chrono::Utc>)> = match sqlx::query_as(
-        r#"
+    let active_rows: Vec<(Uuid, String, chrono::DateTime<chrono::Utc>)> = if cursor.is_some() {
+        Vec::new()
+    } else {
+        match sqlx::query_as(
+            r#"
         select player_uuid, player_name, last_seen_at
         from public.server_players
         where server_id = $1
@@ -382,15 +669,16 @@ pub async fn get_players(
         order by last_seen_at desc
         limit 200
         "#,
-    )
-    .bind(&server_id)
-    .fetch_all(&state.db)
-    .await
-    {
-        Ok(rows) => rows,
-        Err(e) => {
-            tracing::error!(server_id = %server_id, "get active players failed: {:?}", e);
-            Vec::new()
+        )
+        .bind(&server_id)
+        .fetch_all(&state.db)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::error!(server_id = %server_id, "get active players failed: {:?}", e);
+                Vec::new()
+            }
         }
     };
 
@@ -409,51 +697,426 @@ pub async fn get_players(
         ok: true,
         players,
         active_players,
+        next_cursor,
     }))
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PlayerClientSession {
+    pub session_id: String,
+    pub brand: Option<String>,
+    pub channels: Vec<String>,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PlayerDetailResponse {
+    pub ok: bool,
+    pub uuid: Uuid,
+    pub username: Option<String>,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+    /// Last known world / gamemode on this server.
+    pub world: Option<String>,
+    pub gamemode: Option<String>,
+    /// java | bedrock (Geyser), when the plugin reported it.
+    pub client_type: Option<String>,
+    /// Protocol version the player joined with (ViaVersion), when the plugin reported it.
+    pub client_protocol: Option<i32>,
+    pub findings_count: i64,
+    pub detectors: Vec<String>,
+    /// Client brand and registered plugin channels, most recent session first.
+    pub client_sessions: Vec<PlayerClientSession>,
+    /// Moderation cases, open ones first.
+    pub cases: Vec<CaseSummary>,
+    /// Moderator notes on the player, oldest first.
+    pub notes: Vec<ModeratorNote>,
+}
+
+/// GET /dashboard/:server_id/players/:player_uuid
+///
+/// Player detail: presence on this server, findings summary, client brand/channels,
+/// moderation cases and moderator notes.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/players/{player_uuid}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("player_uuid" = Uuid, Path, description = "Player UUID"),
+    ),
+    responses(
+        (status = 200, body = PlayerDetailResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_player(
+    State(state): State<AppState>,
+    Path((server_id, player_uuid)): Path<(String, Uuid)>,
+) -> Result<Json<PlayerDetailResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let presence: Option<(
+        String,
+        chrono::DateTime<chrono::Utc>,
+        chrono::DateTime<chrono::Utc>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<i32>,
+    )> = sqlx::query_as(
+        r#"
+        select player_name, first_seen_at, last_seen_at, world, gamemode, client_type,
+               client_protocol
+        from public.server_players
+        where server_id = $1 and player_uuid = $2
+        "#,
+    )
+    .bind(&server_id)
+    .bind(player_uuid)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("get player failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let (findings_count, detectors): (i64, Vec<String>) = sqlx::query_as(
+        r#"
+        select
+            coalesce(sum(occurrences), 0)::bigint,
+            coalesce(array_agg(distinct detector_name), '{}')
+        from public.findings
+        where server_id = $1 and player_uuid = $2
+        "#,
+    )
+    .bind(&server_id)
+    .bind(player_uuid)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("get player findings failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let sessions: Vec<(
+        String,
+        Option<String>,
+        Vec<String>,
+        chrono::DateTime<chrono::Utc>,
+        chrono::DateTime<chrono::Utc>,
+    )> = sqlx::query_as(
+        r#"
+        select session_id, brand, channels, first_seen_at, last_seen_at
+        from public.player_session_clients
+        where server_id = $1 and player_uuid = $2
+        order by last_seen_at desc
+        limit 20
+        "#,
+    )
+    .bind(&server_id)
+    .bind(player_uuid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("get player client sessions failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let cases = cases::player_cases(&state.db, &server_id, player_uuid)
+        .await
+        .map_err(|e| {
+            tracing::error!("get player cases failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    let notes = notes::player_notes(&state.db, &server_id, player_uuid)
+        .await
+        .map_err(|e| {
+            tracing::error!("get player notes failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    if presence.is_none()
+        && findings_count == 0
+        && sessions.is_empty()
+        && cases.is_empty()
+        && notes.is_empty()
+    {
+        return Err(ApiError::NotFound(format!(
+            "player {} not seen on this server",
+            player_uuid
+        )));
+    }
+
+    let client_sessions = sessions
+        .into_iter()
+        .map(
+            |(session_id, brand, channels, first_seen, last_seen)| PlayerClientSession {
+                session_id,
+                brand,
+                channels,
+                first_seen: first_seen.to_rfc3339(),
+                last_seen: last_seen.to_rfc3339(),
+            },
+        )
+        .collect();
+
+    let (username, first_seen, last_seen, world, gamemode, client_type, client_protocol) =
+        match presence {
+            Some((name, first, last, world, gamemode, client_type, client_protocol)) => (
+                Some(name),
+                Some(first.to_rfc3339()),
+                Some(last.to_rfc3339()),
+                world,
+                gamemode,
+                client_type,
+                client_protocol,
+            ),
+            None => (None, None, None, None, None, None, None),
+        };
+
+    Ok(Json(PlayerDetailResponse {
+        ok: true,
+        uuid: player_uuid,
+        username,
+        first_seen,
+        last_seen,
+        world,
+        gamemode,
+        client_type,
+        client_protocol,
+        findings_count,
+        detectors,
+        client_sessions,
+        cases,
+        notes,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RelatedPlayer {
+    pub uuid: Uuid,
+    pub username: Option<String>,
+    /// Identifier kinds shared with the requested player (ip_hash, fingerprint).
+    pub shared: Vec<String>,
+    pub same_client_brand: bool,
+    pub findings_count: i64,
+    pub confirmed_findings: i64,
+    pub last_seen: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RelatedPlayersResponse {
+    pub ok: bool,
+    pub player_uuid: Uuid,
+    pub client_brands: Vec<String>,
+    pub related: Vec<RelatedPlayer>,
+}
+
+/// GET /dashboard/:server_id/players/:uuid/related
+///
+/// Accounts on this server sharing an IP hash or fingerprint with the player, with their
+/// findings history (accounts with confirmed findings first).
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/players/{player_uuid}/related",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("player_uuid" = Uuid, Path, description = "Player UUID"),
+    ),
+    responses(
+        (status = 200, body = RelatedPlayersResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_related_players(
+    State(state): State<AppState>,
+    Path((server_id, player_uuid)): Path<(String, Uuid)>,
+) -> Result<Json<RelatedPlayersResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let linking_kinds: Vec<&str> = identity::LINKING_KINDS.to_vec();
+
+    let rows: Vec<(
+        Uuid,
+        Option<String>,
+        Vec<String>,
+        bool,
+        i64,
+        i64,
+        chrono::DateTime<chrono::Utc>,
+    )> = sqlx::query_as(
+        r#"
+        select
+            o.player_uuid,
+            p.username,
+            array_agg(distinct o.kind) as shared,
+            exists (
+                select 1
+                from public.player_identifiers a
+                join public.player_identifiers b
+                  on b.server_id = a.server_id and b.kind = a.kind and b.value = a.value
+                where a.server_id = $1 and a.player_uuid = $2 and a.kind = $4
+                  and b.player_uuid = o.player_uuid
+            ) as same_client_brand,
+            coalesce((
+                select sum(f.occurrences)::bigint from public.findings f
+                where f.server_id = $1 and f.player_uuid = o.player_uuid
+            ), 0) as findings_count,
+            (
+                select count(*) from public.findings f
+                where f.server_id = $1 and f.player_uuid = o.player_uuid
+                  and f.status = 'confirmed'
+            ) as confirmed_findings,
+            max(o.last_seen_at) as last_seen
+        from public.player_identifiers me
+        join public.player_identifiers o
+          on o.server_id = me.server_id
+         and o.kind = me.kind
+         and o.value = me.value
+         and o.player_uuid <> me.player_uuid
+        left join public.players p on p.uuid = o.player_uuid
+        where me.server_id = $1 and me.player_uuid = $2 and me.kind = any($3)
+        group by o.player_uuid, p.username
+        order by confirmed_findings desc, findings_count desc, last_seen desc
+        limit 50
+        "#,
+    )
+    .bind(&server_id)
+    .bind(player_uuid)
+    .bind(&linking_kinds)
+    .bind(identity::CLIENT_BRAND)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("related players query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let client_brands: Vec<String> = sqlx::query_scalar(
+        r#"
+        select value from public.player_identifiers
+        where server_id = $1 and player_uuid = $2 and kind = $3
+        order by last_seen_at desc
+        "#,
+    )
+    .bind(&server_id)
+    .bind(player_uuid)
+    .bind(identity::CLIENT_BRAND)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("client brand query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let related = rows
+        .into_iter()
+        .map(
+            |(
+                uuid,
+                username,
+                shared,
+                same_client_brand,
+                findings_count,
+                confirmed_findings,
+                last_seen,
+            )| RelatedPlayer {
+                uuid,
+                username,
+                shared,
+                same_client_brand,
+                findings_count,
+                confirmed_findings,
+                last_seen: last_seen.to_rfc3339(),
+            },
+        )
+        .collect();
+
+    Ok(Json(RelatedPlayersResponse {
+        ok: true,
+        player_uuid,
+        client_brands,
+        related,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ModuleItem {
     pub id: Uuid,
     pub name: String,
     pub base_url: String,
     pub enabled: bool,
+    /// active | shadow (findings kept aside, see `/modules/:module_id/shadow-findings`)
+    pub mode: String,
     pub healthy: bool,
     pub last_error: Option<String>,
     pub detections: i64,
     /// If this module is one of the built-in modules shipped with AsyncAnticheat.
     pub builtin: bool,
-    pub tier: Option<builtin_modules::BuiltinTier>,
+    pub tier: Option<BuiltinTier>,
     pub default_port: Option<u16>,
     pub short_description: Option<String>,
     pub full_description: Option<String>,
     pub checks: Vec<String>,
+    /// Process status when the module binary is managed by the API's supervisor.
+    pub supervised: Option<SupervisedStatus>,
+    /// Health of the primary and each backup endpoint; empty for modules without backups.
+    pub endpoints: Vec<EndpointHealth>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ModulesResponse {
     pub ok: bool,
     pub modules: Vec<ModuleItem>,
     /// Registry of built-in modules (used by the dashboard to avoid hardcoding).
-    pub builtin_modules: Vec<builtin_modules::BuiltinModuleInfo>,
+    pub builtin_modules: Vec<BuiltinModuleInfo>,
 }
 
 /// GET /dashboard/:server_id/modules
 ///
 /// Returns modules for the modules page.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/modules",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = ModulesResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
 pub async fn get_modules(
     State(state): State<AppState>,
     Path(server_id): Path<String>,
 ) -> Result<Json<ModulesResponse>, ApiError> {
     let server_id = server_id.trim().to_string();
 
-    let rows: Vec<(Uuid, String, String, bool, Option<bool>, Option<String>)> = sqlx::query_as(
+    let rows: Vec<(
+        Uuid,
+        String,
+        String,
+        bool,
+        String,
+        Option<bool>,
+        Option<String>,
+    )> = sqlx::query_as(
         r#"
         SELECT 
             id,
             name,
             base_url,
             enabled,
+            mode,
             last_healthcheck_ok,
             last_error
         FROM public.server_modules
@@ -469,38 +1132,44 @@ pub async fn get_modules(
         ApiError::Internal
     })?;
 
-    let mut modules = Vec::new();
-    let builtin_registry = builtin_modules::builtin_modules_info();
-    for (id, name, base_url, enabled, last_healthcheck_ok, last_error) in rows {
-        // Detection count based on detector_name prefix (e.g., "Combat Core" -> "combat_core_%")
-        let detector_like = format!("{}_%", name.trim().to_ascii_lowercase().replace(' ', "_"));
+    // Detection counts of the findings each module reported.
+    let detections: HashMap<Uuid, i64> = sqlx::query_as::<_, (Uuid, i64)>(
+        r#"
+        SELECT module_id, COALESCE(SUM(occurrences), 0)
+        FROM public.findings
+        WHERE server_id = $1 AND module_id IS NOT NULL
+        GROUP BY module_id
+        "#,
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db)
+    .await
+    .map(|rows| rows.into_iter().collect())
+    .unwrap_or_default();
 
-        let detections: (i64,) = sqlx::query_as(
-            r#"
-            SELECT COALESCE(SUM(occurrences), 0) FROM public.findings 
-            WHERE server_id = $1 AND detector_name LIKE $2
-            "#,
-        )
-        .bind(&server_id)
-        .bind(detector_like)
-        .fetch_one(&state.db)
-        .await
-        .unwrap_or((0,));
+    let module_ids: Vec<Uuid> = rows.iter().map(|r| r.0).collect();
+    let mut endpoints = load_endpoints(&state, &module_ids).await;
 
+    let mut modules = Vec::new();
+    let builtin_registry = builtin_modules::builtin_modules_info();
+    for (id, name, base_url, enabled, mode, last_healthcheck_ok, last_error) in rows {
         let mut item = ModuleItem {
             id,
             name,
             base_url,
             enabled,
+            mode,
             healthy: last_healthcheck_ok.unwrap_or(true),
             last_error,
-            detections: detections.0,
+            detections: detections.get(&id).copied().unwrap_or(0),
             builtin: false,
             tier: None,
             default_port: None,
             short_description: None,
             full_description: None,
             checks: Vec::new(),
+            supervised: None,
+            endpoints: endpoints.remove(&id).unwrap_or_default(),
         };
 
         if let Some(b) = builtin_modules::builtin_by_name(&item.name) {
@@ -509,8 +1178,9 @@ pub async fn get_modules(
             item.default_port = Some(b.default_port);
             item.short_description = Some(b.short_description.to_string());
             item.full_description = Some(b.full_description.to_string());
-            item.checks = b.checks.iter().map(|c| (*c).to_string()).collect();
+            item.checks = b.check_names();
         }
+        item.supervised = state.supervisor.status(&item.name);
 
         modules.push(item);
     }
@@ -522,12 +1192,29 @@ pub async fn get_modules(
     }))
 }
 
-#[derive(Debug, Deserialize)]
+/// Endpoint health of the modules that have backups.
+async fn load_endpoints(
+    state: &AppState,
+    module_ids: &[Uuid],
+) -> HashMap<Uuid, Vec<EndpointHealth>> {
+    match module_failover::load(&state.db, module_ids).await {
+        Ok(mut endpoints) => {
+            endpoints.retain(|_, e| e.len() > 1);
+            endpoints
+        }
+        Err(e) => {
+            tracing::warn!("module endpoint health lookup failed: {:?}", e);
+            HashMap::new()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ToggleModuleRequest {
     pub enabled: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ToggleModuleResponse {
     pub ok: bool,
 }
@@ -535,6 +1222,22 @@ pub struct ToggleModuleResponse {
 /// POST /dashboard/:server_id/modules/:module_id/toggle
 ///
 /// Toggles a module's enabled state.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/modules/{module_id}/toggle",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("module_id" = Uuid, Path, description = "Module id"),
+    ),
+    request_body = ToggleModuleRequest,
+    responses(
+        (status = 200, body = ToggleModuleResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
 pub async fn toggle_module(
     State(state): State<AppState>,
     Path((server_id, module_id)): Path<(String, Uuid)>,
@@ -588,13 +1291,13 @@ pub async fn toggle_module(
     Ok(Json(ToggleModuleResponse { ok: true }))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateModuleRequest {
     pub name: String,
     pub base_url: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CreateModuleResponse {
     pub ok: bool,
     pub module: ModuleItem,
@@ -603,6 +1306,21 @@ pub struct CreateModuleResponse {
 /// POST /dashboard/:server_id/modules
 ///
 /// Creates or updates a module for a server.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/modules",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = CreateModuleRequest,
+    responses(
+        (status = 200, body = CreateModuleResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
 pub async fn create_module(
     State(state): State<AppState>,
     Path(server_id): Path<String>,
@@ -639,14 +1357,14 @@ pub async fn create_module(
     })?;
 
     // Insert or update the module
-    let row: (Uuid, String, String, bool, Option<bool>, Option<String>) = sqlx::query_as(
+    let row: (Uuid, String, String, bool, String, Option<bool>, Option<String>) = sqlx::query_as(
         r#"
         insert into public.server_modules (server_id, name, base_url, enabled, transform, created_at, updated_at)
         values ($1, $2, $3, true, 'raw_ndjson_gz', now(), now())
         on conflict (server_id, name) do update set
             base_url = excluded.base_url,
             updated_at = now()
-        returning id, name, base_url, enabled, last_healthcheck_ok, last_error
+        returning id, name, base_url, enabled, mode, last_healthcheck_ok, last_error
         "#,
     )
     .bind(&server_id)
@@ -660,18 +1378,18 @@ pub async fn create_module(
     })?;
 
     // Query actual detection count for this module
-    let detector_like = format!("{}_%", name.to_ascii_lowercase().replace(' ', "_"));
     let detections: (i64,) = sqlx::query_as(
         r#"
         SELECT COALESCE(SUM(occurrences), 0) FROM public.findings
-        WHERE server_id = $1 AND detector_name LIKE $2
+        WHERE server_id = $1 AND module_id = $2
         "#,
     )
     .bind(&server_id)
-    .bind(&detector_like)
+    .bind(row.0)
     .fetch_one(&state.db)
     .await
     .unwrap_or((0,));
+    let mut endpoints = load_endpoints(&state, &[row.0]).await;
 
     Ok(Json(CreateModuleResponse {
         ok: true,
@@ -681,8 +1399,9 @@ pub async fn create_module(
                 name: row.1,
                 base_url: row.2,
                 enabled: row.3,
-                healthy: row.4.unwrap_or(true),
-                last_error: row.5,
+                mode: row.4,
+                healthy: row.5.unwrap_or(true),
+                last_error: row.6,
                 detections: detections.0,
                 builtin: false,
                 tier: None,
@@ -690,6 +1409,8 @@ pub async fn create_module(
                 short_description: None,
                 full_description: None,
                 checks: Vec::new(),
+                supervised: None,
+                endpoints: endpoints.remove(&row.0).unwrap_or_default(),
             };
 
             if let Some(b) = builtin_modules::builtin_by_name(&item.name) {
@@ -698,23 +1419,40 @@ pub async fn create_module(
                 item.default_port = Some(b.default_port);
                 item.short_description = Some(b.short_description.to_string());
                 item.full_description = Some(b.full_description.to_string());
-                item.checks = b.checks.iter().map(|c| (*c).to_string()).collect();
+                item.checks = b.check_names();
             }
+            item.supervised = state.supervisor.status(&item.name);
 
             item
         },
     }))
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ServerInfo {
     pub id: String,
     pub name: Option<String>,
     pub platform: Option<String>,
     pub last_seen_at: String,
+    /// Proxy group the server is a backend of (from `X-Proxy-Id`).
+    #[serde(default)]
+    pub proxy_group_id: Option<Uuid>,
+    /// Plugin version last reported in `X-Plugin-Version`.
+    #[serde(default)]
+    pub plugin_version: Option<String>,
+    #[serde(default)]
+    pub enabled_modules: i64,
+    /// Enabled modules whose last healthcheck or dispatch failed.
+    #[serde(default)]
+    pub unhealthy_modules: i64,
+    /// Finding occurrences seen in the last 24 hours (as `findings_today` in the stats).
+    #[serde(default)]
+    pub findings_24h: i64,
+    #[serde(default)]
+    pub last_batch_at: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ServersResponse {
     pub ok: bool,
     pub servers: Vec<ServerInfo>,
@@ -722,15 +1460,64 @@ pub struct ServersResponse {
 
 /// GET /dashboard/servers
 ///
-/// Returns all registered servers.
+/// Returns all registered servers, except deleted ones.
+#[utoipa::path(
+    get,
+    path = "/dashboard/servers",
+    tag = "dashboard",
+    responses(
+        (status = 200, body = ServersResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
 pub async fn get_servers(State(state): State<AppState>) -> Result<Json<ServersResponse>, ApiError> {
+    // Module health, recent findings and the last batch of every server in one round trip.
     let rows: Vec<(
         String,
         Option<String>,
         Option<String>,
         chrono::DateTime<chrono::Utc>,
+        Option<Uuid>,
+        Option<String>,
+        i64,
+        i64,
+        i64,
+        Option<chrono::DateTime<chrono::Utc>>,
     )> = sqlx::query_as(
-        "SELECT id, name, platform, last_seen_at FROM public.servers ORDER BY last_seen_at DESC",
+        r#"
+        SELECT
+            s.id,
+            s.name,
+            s.platform,
+            s.last_seen_at,
+            s.proxy_group_id,
+            s.plugin_version,
+            m.enabled_modules,
+            m.unhealthy_modules,
+            f.findings_24h,
+            b.last_batch_at
+        FROM public.servers s
+        CROSS JOIN LATERAL (
+            SELECT
+                COUNT(*) AS enabled_modules,
+                COUNT(*) FILTER (WHERE last_healthcheck_ok = false) AS unhealthy_modules
+            FROM public.server_modules
+            WHERE server_id = s.id AND enabled = true
+        ) m
+        CROSS JOIN LATERAL (
+            SELECT COALESCE(SUM(occurrences), 0)::bigint AS findings_24h
+            FROM public.findings
+            WHERE server_id = s.id AND last_seen_at > NOW() - INTERVAL '24 hours'
+        ) f
+        CROSS JOIN LATERAL (
+            SELECT MAX(received_at) AS last_batch_at
+            FROM public.batch_index
+            WHERE server_id = s.id
+        ) b
+        WHERE s.deleted_at IS NULL
+        ORDER BY s.last_seen_at DESC
+        "#,
     )
     .fetch_all(&state.db)
     .await
@@ -741,12 +1528,31 @@ pub async fn get_servers(State(state): State<AppState>) -> Result<Json<ServersRe
 
     let servers = rows
         .into_iter()
-        .map(|(id, name, platform, last_seen_at)| ServerInfo {
-            id,
-            name,
-            platform,
-            last_seen_at: last_seen_at.to_rfc3339(),
-        })
+        .map(
+            |(
+                id,
+                name,
+                platform,
+                last_seen_at,
+                proxy_group_id,
+                plugin_version,
+                enabled_modules,
+                unhealthy_modules,
+                findings_24h,
+                last_batch_at,
+            )| ServerInfo {
+                id,
+                name,
+                platform,
+                last_seen_at: last_seen_at.to_rfc3339(),
+                proxy_group_id,
+                plugin_version,
+                enabled_modules,
+                unhealthy_modules,
+                findings_24h,
+                last_batch_at: last_batch_at.map(|t| t.to_rfc3339()),
+            },
+        )
         .collect();
 
     Ok(Json(ServersResponse { ok: true, servers }))
@@ -756,123 +1562,191 @@ pub async fn get_servers(State(state): State<AppState>) -> Result<Json<ServersRe
 // Connection Status Endpoint
 // ============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ConnectionStatus {
     /// Milliseconds since the plugin last sent data
     pub plugin_last_seen_ms: i64,
-    /// Whether the plugin is considered online (seen within last 30s)
+    /// Whether the plugin is considered online (seen within `SERVER_OFFLINE_AFTER_SECONDS`)
     pub plugin_online: bool,
-    /// TCP ping to the Minecraft server in ms (if reachable)
+    /// Last contact before the current outage (null while online)
+    pub offline_since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Percentage of the last 24 hours the plugin was online
+    pub uptime_24h: Option<f64>,
+    /// Percentage of the last 7 days the plugin was online
+    pub uptime_7d: Option<f64>,
+    /// Server List Ping round trip to the Minecraft server in ms (if reachable)
     pub server_ping_ms: Option<i64>,
-    /// Whether the server responded to TCP ping
+    /// Whether the server accepted the connection
     pub server_reachable: bool,
     /// The server address that was pinged
     pub server_address: Option<String>,
+    /// MOTD as plain text, from the server's status response
+    pub motd: Option<String>,
+    pub players_online: Option<i64>,
+    pub players_max: Option<i64>,
+    /// Version name the server advertises (e.g. "Paper 1.21.1")
+    pub server_version: Option<String>,
+    /// When the background prober last pinged the server (null until the first probe)
+    pub server_probed_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct StatusResponse {
-    pub ok: bool,
-    pub status: ConnectionStatus,
+/// One heartbeat performance sample, with the findings raised since the previous sample.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HeartbeatSample {
+    pub received_at: chrono::DateTime<chrono::Utc>,
+    pub tps: Option<f64>,
+    pub mspt: Option<f64>,
+    pub online_players: Option<i32>,
+    pub findings: i64,
 }
 
-/// Perform a TCP ping to the Minecraft server
-async fn tcp_ping(address: &str, port: u16) -> Option<i64> {
-    let addr = format!("{}:{}", address, port);
-    let start = Instant::now();
-
-    match timeout(Duration::from_secs(3), TcpStream::connect(&addr)).await {
-        Ok(Ok(_stream)) => {
-            let elapsed = start.elapsed().as_millis() as i64;
-            Some(elapsed)
-        }
-        _ => None,
-    }
+/// Latest reported server performance and versions.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ServerPerformance {
+    pub received_at: chrono::DateTime<chrono::Utc>,
+    pub tps: Option<f64>,
+    pub mspt: Option<f64>,
+    pub online_players: Option<i32>,
+    pub plugin_version: Option<String>,
+    pub mc_version: Option<String>,
 }
 
-/// Best-effort parse of a host[:port] or URL into (host, port).
-/// - Supports `http(s)://host[:port]/...`
-/// - Supports `host[:port]`
-/// - Supports bracketed IPv6: `[::1]:25565`
-fn extract_host_port(raw: &str) -> Option<(String, u16)> {
-    let trimmed = raw.trim();
-    if trimmed.is_empty() {
-        return None;
-    }
-
-    let without_scheme = trimmed
-        .strip_prefix("http://")
-        .or_else(|| trimmed.strip_prefix("https://"))
-        .unwrap_or(trimmed);
-
-    // Drop any path/query fragment.
-    let authority = without_scheme
-        .split('/')
-        .next()
-        .unwrap_or(without_scheme)
-        .trim();
-    if authority.is_empty() {
-        return None;
-    }
-
-    // Bracketed IPv6 form: [::1]:25565
-    if let Some(rest) = authority.strip_prefix('[') {
-        if let Some(end) = rest.find(']') {
-            let host = rest[..end].trim();
-            if host.is_empty() {
-                return None;
-            }
-            let after = rest[end + 1..].trim(); // may start with :port
-            let port = after
-                .strip_prefix(':')
-                .and_then(|p| p.parse::<u16>().ok())
-                .unwrap_or(25565);
-            return Some((host.to_string(), port));
-        }
-    }
-
-    // Detect bare IPv6 addresses (multiple colons, no brackets).
-    // These are invalid input; require bracketed form [::1]:port instead.
-    let colon_count = authority.chars().filter(|&c| c == ':').count();
-    if colon_count > 1 {
-        // More than one colon without brackets = bare IPv6, reject it.
-        return None;
-    }
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StatusResponse {
+    pub ok: bool,
+    pub status: ConnectionStatus,
+    /// Absent until the plugin sends heartbeat metrics.
+    #[serde(default)]
+    pub performance: Option<ServerPerformance>,
+    /// Last hour of heartbeat samples, oldest first.
+    #[serde(default)]
+    pub heartbeats: Vec<HeartbeatSample>,
+    /// Gaps / reordering / duplicates in the plugin's batch sequence (last 24h).
+    #[serde(default)]
+    pub ingest_integrity: Option<IngestIntegrity>,
+}
 
-    // Now safe to split by single colon: host:port or just host.
-    let mut parts = authority.split(':');
-    let host = parts.next().unwrap_or("").trim();
-    if host.is_empty() {
-        return None;
-    }
-    let port = parts
-        .next()
-        .and_then(|p| p.trim().parse::<u16>().ok())
-        .unwrap_or(25565);
+/// Latest performance sample plus the last hour of samples for lag / detection correlation.
+async fn load_performance(
+    db: &sqlx::PgPool,
+    server_id: &str,
+) -> Result<(Option<ServerPerformance>, Vec<HeartbeatSample>), sqlx::Error> {
+    let latest: Option<(
+        chrono::DateTime<chrono::Utc>,
+        Option<f64>,
+        Option<f64>,
+        Option<i32>,
+        Option<String>,
+        Option<String>,
+    )> = sqlx::query_as(
+        r#"
+        select received_at, tps, mspt, online_players, plugin_version, mc_version
+        from public.server_heartbeats
+        where server_id = $1
+        order by received_at desc
+        limit 1
+        "#,
+    )
+    .bind(server_id)
+    .fetch_optional(db)
+    .await?;
 
-    Some((host.to_string(), port))
+    let samples: Vec<(
+        chrono::DateTime<chrono::Utc>,
+        Option<f64>,
+        Option<f64>,
+        Option<i32>,
+        i64,
+    )> = sqlx::query_as(
+        r#"
+        with h as (
+            select received_at, tps, mspt, online_players,
+                   lag(received_at) over (order by received_at) as prev_at
+            from public.server_heartbeats
+            where server_id = $1 and received_at > now() - interval '1 hour'
+        )
+        select h.received_at, h.tps, h.mspt, h.online_players,
+               (select count(*) from public.findings f
+                where f.server_id = $1
+                  and f.created_at <= h.received_at
+                  and f.created_at > coalesce(h.prev_at, h.received_at - interval '30 seconds'))
+        from h
+        order by h.received_at asc
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db)
+    .await?;
+
+    let performance = latest.map(
+        |(received_at, tps, mspt, online_players, plugin_version, mc_version)| ServerPerformance {
+            received_at,
+            tps,
+            mspt,
+            online_players,
+            plugin_version,
+            mc_version,
+        },
+    );
+    let heartbeats = samples
+        .into_iter()
+        .map(
+            |(received_at, tps, mspt, online_players, findings)| HeartbeatSample {
+                received_at,
+                tps,
+                mspt,
+                online_players,
+                findings,
+            },
+        )
+        .collect();
+    Ok((performance, heartbeats))
 }
 
 /// GET /dashboard/:server_id/status
 ///
-/// Returns connection status including plugin heartbeat and server ping.
+/// Returns connection status including plugin heartbeat, uptime over the last 24h / 7 days and
+/// the latest background Server List Ping of the server (latency, MOTD, players, version;
+/// refreshed every 30s), plus the
+/// performance reported in heartbeat bodies (TPS / MSPT samples with findings per interval) and
+/// a warning when the plugin's batch sequence shows gaps, reordering or duplicates.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/status",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = StatusResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
 pub async fn get_status(
     State(state): State<AppState>,
     Path(server_id): Path<String>,
 ) -> Result<Json<StatusResponse>, ApiError> {
     let server_id = server_id.trim().to_string();
     // Get server info including last_seen_at and callback_url
-    let server: Option<(chrono::DateTime<chrono::Utc>, Option<String>)> =
-        sqlx::query_as("SELECT last_seen_at, callback_url FROM public.servers WHERE id = $1")
-            .bind(&server_id)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| {
-                tracing::error!("get server status failed: {:?}", e);
-                ApiError::Internal
-            })?;
+    let server: Option<(
+        chrono::DateTime<chrono::Utc>,
+        Option<String>,
+        chrono::DateTime<chrono::Utc>,
+        Option<chrono::DateTime<chrono::Utc>>,
+    )> = sqlx::query_as(
+        "SELECT last_seen_at, callback_url, first_seen_at, offline_since FROM public.servers WHERE id = $1",
+    )
+    .bind(&server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("get server status failed: {:?}", e);
+        ApiError::Internal
+    })?;
 
-    let (last_seen_at, callback_url) = match server {
+    let (last_seen_at, callback_url, first_seen_at, offline_since) = match server {
         Some(s) => s,
         None => {
             tracing::warn!(server_id = %server_id, "status requested for unknown server");
@@ -881,59 +1755,84 @@ pub async fn get_status(
                 status: ConnectionStatus {
                     plugin_last_seen_ms: -1,
                     plugin_online: false,
+                    offline_since: None,
+                    uptime_24h: None,
+                    uptime_7d: None,
                     server_ping_ms: None,
                     server_reachable: false,
                     server_address: None,
+                    motd: None,
+                    players_online: None,
+                    players_max: None,
+                    server_version: None,
+                    server_probed_at: None,
                 },
+                performance: None,
+                heartbeats: Vec::new(),
+                ingest_integrity: None,
             }));
         }
     };
 
     let now = chrono::Utc::now();
     let plugin_last_seen_ms = (now - last_seen_at).num_milliseconds();
-    // Plugin uploads batches when there's data. With no players, only PING/PONG
-    // keep-alive packets accumulate (~hourly). Use 2-hour threshold for "online".
-    let plugin_online = plugin_last_seen_ms < 2 * 60 * 60 * 1000; // Online if seen within 2h
-
-    // Try to ping the server if we have an address
-    // The callback_url might be like "http://ip:port" or just "ip:port"
-    // Or we might need to extract from server_id if it contains IP info
-    let ping_source: Option<&str> = if let Some(ref url) = callback_url {
-        Some(url.as_str())
-    } else {
-        // Only fall back to server_id if it doesn't look like a UUID.
-        // Avoid slow 3s timeouts every 5s poll on UUID-like ids.
-        if Uuid::parse_str(&server_id).is_ok() {
-            None
-        } else {
-            Some(server_id.as_str())
-        }
-    };
+    // Same threshold as the offline detection (`server_presence`); the plugin heartbeats every
+    // 30s even when no batches are uploaded.
+    let plugin_online = plugin_last_seen_ms < state.server_offline_after_seconds as i64 * 1000;
+    let (uptime_24h, uptime_7d) =
+        server_presence::uptime(&state.db, &server_id, first_seen_at, now)
+            .await
+            .map_err(|e| {
+                tracing::error!("get server uptime failed: {:?}", e);
+                ApiError::Internal
+            })?;
 
-    let (server_ping_ms, server_reachable, server_address) = if let Some(raw) = ping_source {
-        if let Some((host, port)) = extract_host_port(raw) {
-            if host != "127.0.0.1" && host != "localhost" {
-                let ping = tcp_ping(&host, port).await;
-                (ping, ping.is_some(), Some(format!("{}:{}", host, port)))
-            } else {
-                (None, false, None)
-            }
-        } else {
-            (None, false, None)
-        }
-    } else {
-        (None, false, None)
+    // Probed in the background (see `server_ping::probe_tick`); never ping inline.
+    let probe = server_ping::load(&state.db, &server_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("get server probe failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    let server_address = match &probe {
+        Some(p) => Some(p.address.clone()),
+        None => server_ping::ping_target(&server_id, callback_url.as_deref())
+            .map(|(host, port)| format!("{}:{}", host, port)),
     };
+    let server_probed_at = probe.as_ref().map(|p| p.probed_at);
+    let ping = probe.and_then(|p| p.status);
+
+    let (performance, heartbeats) = load_performance(&state.db, &server_id).await.map_err(|e| {
+        tracing::error!("get server performance failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let ingest_integrity = batch_sequence::summary(&state.db, &server_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("get ingest integrity failed: {:?}", e);
+            ApiError::Internal
+        })?;
 
     Ok(Json(StatusResponse {
         ok: true,
         status: ConnectionStatus {
             plugin_last_seen_ms,
             plugin_online,
-            server_ping_ms,
-            server_reachable,
+            offline_since,
+            uptime_24h,
+            uptime_7d,
+            server_ping_ms: ping.as_ref().map(|p| p.latency_ms),
+            server_reachable: ping.is_some(),
             server_address,
+            motd: ping.as_ref().and_then(|p| p.motd.clone()),
+            players_online: ping.as_ref().and_then(|p| p.players_online),
+            players_max: ping.as_ref().and_then(|p| p.players_max),
+            server_version: ping.and_then(|p| p.version),
+            server_probed_at,
         },
+        performance,
+        heartbeats,
+        ingest_integrity: Some(ingest_integrity),
     }))
 }
 
@@ -941,7 +1840,7 @@ pub async fn get_status(
 // Module Audit Log Endpoint
 // ============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ModuleAuditEntry {
     pub id: String,
     pub created_at: String,
@@ -955,7 +1854,7 @@ pub struct ModuleAuditEntry {
     pub query_preview: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ModuleAuditResponse {
     pub ok: bool,
     pub entries: Vec<ModuleAuditEntry>,
@@ -964,6 +1863,19 @@ pub struct ModuleAuditResponse {
 /// GET /dashboard/:server_id/modules/audit
 ///
 /// Returns recent audit log entries for module enabled changes.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/modules/audit",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = ModuleAuditResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
 pub async fn get_module_audit(
     State(state): State<AppState>,
     Path(server_id): Path<String>,
@@ -1039,3 +1951,277 @@ pub async fn get_module_audit(
 
     Ok(Json(ModuleAuditResponse { ok: true, entries }))
 }
+
+// ============================================================================
+// Module Conformance Reports Endpoint
+// ============================================================================
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConformanceRunEntry {
+    pub id: String,
+    pub created_at: String,
+    /// None while the run is still in progress.
+    pub passed: Option<bool>,
+    pub callback_received_at: Option<String>,
+    pub callback_findings: Option<i32>,
+    pub report: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConformanceRunsResponse {
+    pub ok: bool,
+    pub runs: Vec<ConformanceRunEntry>,
+}
+
+/// GET /dashboard/:server_id/modules/:module_id/conformance
+///
+/// Returns the most recent conformance reports for a module.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/modules/{module_id}/conformance",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("module_id" = Uuid, Path, description = "Module id"),
+    ),
+    responses(
+        (status = 200, body = ConformanceRunsResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_module_conformance(
+    State(state): State<AppState>,
+    Path((server_id, module_id)): Path<(String, Uuid)>,
+) -> Result<Json<ConformanceRunsResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let rows: Vec<(
+        Uuid,
+        chrono::DateTime<chrono::Utc>,
+        Option<bool>,
+        Option<chrono::DateTime<chrono::Utc>>,
+        Option<i32>,
+        Option<serde_json::Value>,
+    )> = sqlx::query_as(
+        r#"
+        SELECT id, created_at, passed, callback_received_at, callback_findings, report_json
+        FROM public.module_conformance_runs
+        WHERE server_id = $1 AND module_id = $2
+        ORDER BY created_at DESC
+        LIMIT 20
+        "#,
+    )
+    .bind(&server_id)
+    .bind(module_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("get module conformance failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let runs = rows
+        .into_iter()
+        .map(
+            |(id, created_at, passed, callback_received_at, callback_findings, report)| {
+                ConformanceRunEntry {
+                    id: id.to_string(),
+                    created_at: created_at.to_rfc3339(),
+                    passed,
+                    callback_received_at: callback_received_at.map(|t| t.to_rfc3339()),
+                    callback_findings,
+                    report,
+                }
+            },
+        )
+        .collect();
+
+    Ok(Json(ConformanceRunsResponse { ok: true, runs }))
+}
+
+// ============================================================================
+// Finding Review + Detector Quality Endpoints
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateFindingStatusRequest {
+    /// open | confirmed | dismissed | false_positive
+    pub status: String,
+    pub reviewed_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateFindingStatusResponse {
+    pub ok: bool,
+    pub finding_id: Uuid,
+    pub previous_status: String,
+    pub status: String,
+}
+
+/// POST /dashboard/:server_id/findings/:finding_id/status
+///
+/// Review a finding; false positives are counted against the detector's precision.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/findings/{finding_id}/status",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("finding_id" = Uuid, Path, description = "Finding id"),
+    ),
+    request_body = UpdateFindingStatusRequest,
+    responses(
+        (status = 200, body = UpdateFindingStatusResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn update_finding_status(
+    State(state): State<AppState>,
+    Path((server_id, finding_id)): Path<(String, Uuid)>,
+    Json(req): Json<UpdateFindingStatusRequest>,
+) -> Result<Json<UpdateFindingStatusResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let status = req.status.trim().to_lowercase();
+    if !detector_metrics::FINDING_STATUSES.contains(&status.as_str()) {
+        return Err(ApiError::BadRequest(format!(
+            "invalid status: {} (must be one of {})",
+            status,
+            detector_metrics::FINDING_STATUSES.join(", ")
+        )));
+    }
+
+    let previous = detector_metrics::set_finding_status(
+        &state.db,
+        &server_id,
+        finding_id,
+        &status,
+        req.reviewed_by.as_deref(),
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!("update finding status failed: {:?}", e);
+        ApiError::Internal
+    })?
+    .ok_or_else(|| ApiError::NotFound(format!("finding {} not found", finding_id)))?;
+
+    Ok(Json(UpdateFindingStatusResponse {
+        ok: true,
+        finding_id,
+        previous_status: previous,
+        status,
+    }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DetectorQuality {
+    pub detector_name: String,
+    pub findings: i64,
+    pub occurrences: i64,
+    pub confirmed: i64,
+    pub false_positives: i64,
+    pub dismissed: i64,
+    /// confirmed / (confirmed + false_positives); None until a finding is reviewed.
+    pub precision: Option<f64>,
+    pub last_false_positive_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DetectorQualityResponse {
+    pub ok: bool,
+    pub detectors: Vec<DetectorQuality>,
+}
+
+/// GET /dashboard/:server_id/detectors/quality
+///
+/// Per-detector review stats, noisiest (most false positives) first.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/detectors/quality",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = DetectorQualityResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_detector_quality(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<DetectorQualityResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let rows: Vec<(
+        String,
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+        Option<chrono::DateTime<chrono::Utc>>,
+    )> = sqlx::query_as(
+        r#"
+        WITH totals AS (
+            SELECT detector_name, COUNT(*) AS findings, COALESCE(SUM(occurrences), 0) AS occurrences
+            FROM public.findings
+            WHERE server_id = $1
+            GROUP BY detector_name
+        )
+        SELECT
+            COALESCE(t.detector_name, m.detector_name),
+            COALESCE(t.findings, 0),
+            COALESCE(t.occurrences, 0),
+            COALESCE(m.confirmed, 0),
+            COALESCE(m.false_positives, 0),
+            COALESCE(m.dismissed, 0),
+            m.last_false_positive_at
+        FROM totals t
+        FULL OUTER JOIN (
+            SELECT * FROM public.detector_metrics WHERE server_id = $1
+        ) m ON m.detector_name = t.detector_name
+        ORDER BY COALESCE(m.false_positives, 0) DESC, COALESCE(t.findings, 0) DESC
+        "#,
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("get detector quality failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let detectors = rows
+        .into_iter()
+        .map(
+            |(
+                detector_name,
+                findings,
+                occurrences,
+                confirmed,
+                false_positives,
+                dismissed,
+                last_false_positive_at,
+            )| DetectorQuality {
+                detector_name,
+                findings,
+                occurrences,
+                confirmed,
+                false_positives,
+                dismissed,
+                precision: detector_metrics::precision(confirmed, false_positives),
+                last_false_positive_at: last_false_positive_at.map(|t| t.to_rfc3339()),
+            },
+        )
+        .collect();
+
+    Ok(Json(DetectorQualityResponse {
+        ok: true,
+        detectors,
+    }))
+}
@@ -0,0 +1,299 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::dispatch_stats::{self, DispatchStatsHour, DispatchStatsTotals};
+use crate::{error::ApiError, pagination, AppState};
+
+pub const DEFAULT_HOURS: i32 = 24;
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DispatchStatsQuery {
+    /// Hours back from now (default 24, at most 90 days).
+    pub hours: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DispatchStatsResponse {
+    pub ok: bool,
+    pub module_id: Uuid,
+    /// Oldest first; the current hour is live.
+    pub hours: Vec<DispatchStatsHour>,
+    pub totals: DispatchStatsTotals,
+}
+
+type StatsRow = (DateTime<Utc>, i64, i64, i64, i64, Option<f64>, Option<f64>);
+
+/// GET /dashboard/:server_id/modules/:module_id/dispatch-stats
+///
+/// Hourly dispatch outcomes and latency of a module, from the hourly rollups plus raw dispatch
+/// rows not rolled up yet.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/modules/{module_id}/dispatch-stats",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("module_id" = Uuid, Path, description = "Module id"),
+        DispatchStatsQuery,
+    ),
+    responses(
+        (status = 200, body = DispatchStatsResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_dispatch_stats(
+    State(state): State<AppState>,
+    Path((server_id, module_id)): Path<(String, Uuid)>,
+    Query(params): Query<DispatchStatsQuery>,
+) -> Result<Json<DispatchStatsResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let hours = params
+        .hours
+        .unwrap_or(DEFAULT_HOURS)
+        .clamp(1, dispatch_stats::STATS_RETENTION_DAYS * 24);
+
+    ensure_module(&state, &server_id, module_id).await?;
+
+    let rows: Vec<StatsRow> = sqlx::query_as(
+        r#"
+        with rolled as (
+            select hour, dispatches::bigint, succeeded::bigint, failed::bigint,
+                   timed_out::bigint, avg_latency_ms, p95_latency_ms
+            from public.module_dispatch_stats
+            where module_id = $1 and server_id = $2
+              and hour >= date_trunc('hour', now()) - make_interval(hours => $3)
+        ),
+        live as (
+            select
+                date_trunc('hour', created_at) as hour,
+                count(*),
+                count(*) filter (where status in ('sent', 'completed')),
+                count(*) filter (where status = 'failed'),
+                count(*) filter (where status = 'timed_out'),
+                avg(latency_ms)::float8,
+                percentile_cont(0.95) within group (order by latency_ms)
+            from public.module_dispatches
+            where module_id = $1 and server_id = $2
+              and created_at >= coalesce(
+                  (select max(hour) + interval '1 hour' from rolled),
+                  date_trunc('hour', now()) - make_interval(hours => $3)
+              )
+            group by 1
+        )
+        select * from rolled
+        union all
+        select * from live
+        order by hour asc
+        "#,
+    )
+    .bind(module_id)
+    .bind(&server_id)
+    .bind(hours)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("dispatch stats query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let hours: Vec<DispatchStatsHour> = rows
+        .into_iter()
+        .map(
+            |(hour, dispatches, succeeded, failed, timed_out, avg, p95)| DispatchStatsHour {
+                hour,
+                dispatches,
+                succeeded,
+                failed,
+                timed_out,
+                avg_latency_ms: avg,
+                p95_latency_ms: p95,
+            },
+        )
+        .collect();
+    let totals = dispatch_stats::totals(&hours);
+
+    Ok(Json(DispatchStatsResponse {
+        ok: true,
+        module_id,
+        hours,
+        totals,
+    }))
+}
+
+/// 404 unless the module belongs to the server.
+async fn ensure_module(state: &AppState, server_id: &str, module_id: Uuid) -> Result<(), ApiError> {
+    let module: Option<Uuid> =
+        sqlx::query_scalar("select id from public.server_modules where id = $1 and server_id = $2")
+            .bind(module_id)
+            .bind(server_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!("dispatch stats module lookup failed: {:?}", e);
+                ApiError::Internal
+            })?;
+    if module.is_none() {
+        return Err(ApiError::NotFound(format!(
+            "module {} not found",
+            module_id
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DispatchHistoryQuery {
+    /// Only dispatches with this status (sent, failed, processing, completed, timed_out).
+    pub status: Option<String>,
+    /// Dispatches per page (default 50, at most 200).
+    pub limit: Option<i64>,
+    /// `next_cursor` of the previous page.
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DispatchItem {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub batch_id: Uuid,
+    /// sent | failed | processing | completed | timed_out
+    pub status: String,
+    pub http_status: Option<i32>,
+    pub error: Option<String>,
+    pub latency_ms: Option<i32>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// stable | canary, for modules with a canary.
+    pub variant: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DispatchHistoryResponse {
+    pub ok: bool,
+    pub module_id: Uuid,
+    /// Newest first.
+    pub dispatches: Vec<DispatchItem>,
+    /// Pass as `cursor` for the next page; null on the last one.
+    pub next_cursor: Option<String>,
+}
+
+type DispatchRow = (
+    Uuid,
+    DateTime<Utc>,
+    Uuid,
+    String,
+    Option<i32>,
+    Option<String>,
+    Option<i32>,
+    Option<DateTime<Utc>>,
+    Option<String>,
+);
+
+/// GET /dashboard/:server_id/modules/:module_id/dispatches
+///
+/// A module's individual dispatches (kept `MODULE_DISPATCH_RETENTION_DAYS`), newest first, with
+/// cursor pagination.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/modules/{module_id}/dispatches",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("module_id" = Uuid, Path, description = "Module id"),
+        DispatchHistoryQuery,
+    ),
+    responses(
+        (status = 200, body = DispatchHistoryResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_dispatches(
+    State(state): State<AppState>,
+    Path((server_id, module_id)): Path<(String, Uuid)>,
+    Query(params): Query<DispatchHistoryQuery>,
+) -> Result<Json<DispatchHistoryResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let limit = pagination::limit(params.limit, 50, 200);
+    let cursor = pagination::parse(params.cursor.as_deref())?;
+    let status = params
+        .status
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    ensure_module(&state, &server_id, module_id).await?;
+
+    let rows: Vec<DispatchRow> = sqlx::query_as(
+        r#"
+        select id, created_at, batch_id, status, http_status, error, latency_ms, completed_at,
+               variant
+        from public.module_dispatches
+        where module_id = $1 and server_id = $2
+          and ($3::text is null or status = $3)
+          and ($4::timestamptz is null or (created_at, id) < ($4, $5))
+        order by created_at desc, id desc
+        limit $6
+        "#,
+    )
+    .bind(module_id)
+    .bind(&server_id)
+    .bind(status)
+    .bind(cursor.map(|c| c.at))
+    .bind(cursor.map(|c| c.id))
+    .bind(limit + 1)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("dispatch history query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let (rows, next_cursor) = pagination::page(rows, limit, |row| pagination::Cursor {
+        at: row.1,
+        id: row.0,
+    });
+    let dispatches = rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                created_at,
+                batch_id,
+                status,
+                http_status,
+                error,
+                latency_ms,
+                completed_at,
+                variant,
+            )| DispatchItem {
+                id,
+                created_at,
+                batch_id,
+                status,
+                http_status,
+                error,
+                latency_ms,
+                completed_at,
+                variant,
+            },
+        )
+        .collect();
+
+    Ok(Json(DispatchHistoryResponse {
+        ok: true,
+        module_id,
+        dispatches,
+        next_cursor,
+    }))
+}
@@ -0,0 +1,39 @@
+//! API documentation: the OpenAPI spec and a Swagger UI page rendering it.
+
+use std::sync::OnceLock;
+
+use axum::{response::Html, Json};
+
+use crate::openapi;
+
+/// GET /openapi.json
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    static SPEC: OnceLock<utoipa::openapi::OpenApi> = OnceLock::new();
+    Json(SPEC.get_or_init(openapi::spec).clone())
+}
+
+/// GET /docs
+///
+/// Swagger UI (assets loaded from a CDN) pointed at `/openapi.json`. Both routes sit behind
+/// DASHBOARD_TOKEN like the rest of the dashboard API, so browsing them directly only works
+/// without a token (local dev) or through something that adds the bearer header.
+pub async fn swagger_ui() -> Html<&'static str> {
+    Html(SWAGGER_UI_HTML)
+}
+
+const SWAGGER_UI_HTML: &str = r##"<!doctype html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>AsyncAnticheat API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##;
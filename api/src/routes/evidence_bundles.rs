@@ -0,0 +1,104 @@
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::evidence_bundle::{self, EvidenceBundle};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EvidenceBundleResponse {
+    pub ok: bool,
+    pub bundle: EvidenceBundle,
+    /// Dashboard path the gzipped JSON bundle can be downloaded from.
+    pub download_path: String,
+}
+
+/// POST /dashboard/:server_id/findings/:finding_id/bundle
+///
+/// Assemble an evidence bundle for appeal review: the finding, the player's related findings,
+/// their transformed events around it and their module state snapshots. Stored in the object
+/// store as gzipped JSON.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/findings/{finding_id}/bundle",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("finding_id" = Uuid, Path, description = "Finding id"),
+    ),
+    responses(
+        (status = 200, body = EvidenceBundleResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn create_evidence_bundle(
+    State(state): State<AppState>,
+    Path((server_id, finding_id)): Path<(String, Uuid)>,
+) -> Result<Json<EvidenceBundleResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let bundle = evidence_bundle::build(&state, &server_id, finding_id).await?;
+    tracing::info!(
+        server_id = %server_id,
+        finding_id = %finding_id,
+        bundle_id = %bundle.bundle_id,
+        events = bundle.events,
+        size_bytes = bundle.size_bytes,
+        "evidence bundle stored"
+    );
+
+    Ok(Json(EvidenceBundleResponse {
+        ok: true,
+        download_path: format!("/dashboard/{}/bundles/{}", server_id, bundle.bundle_id),
+        bundle,
+    }))
+}
+
+/// GET /dashboard/:server_id/bundles/:bundle_id
+///
+/// Download a stored evidence bundle (`application/gzip`, a gzipped JSON document).
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/bundles/{bundle_id}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("bundle_id" = Uuid, Path, description = "Bundle id"),
+    ),
+    responses(
+        (status = 200, description = "Gzipped JSON bundle", content_type = "application/gzip"),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn download_evidence_bundle(
+    State(state): State<AppState>,
+    Path((server_id, bundle_id)): Path<(String, Uuid)>,
+) -> Result<Response, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let (finding_id, bytes) = evidence_bundle::load(&state, &server_id, bundle_id).await?;
+    let disposition = format!(
+        "attachment; filename=\"finding-{}-bundle-{}.json.gz\"",
+        finding_id, bundle_id
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        bytes,
+    )
+        .into_response())
+}
@@ -0,0 +1,318 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{error::ApiError, exemptions, AppState};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExemptionItem {
+    pub id: Uuid,
+    pub player_uuid: Option<Uuid>,
+    pub permission: Option<String>,
+    pub world: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    pub active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExemptionsResponse {
+    pub ok: bool,
+    pub exemptions: Vec<ExemptionItem>,
+}
+
+/// GET /dashboard/:server_id/exemptions
+///
+/// Current and scheduled exemptions (expired ones are omitted).
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/exemptions",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = ExemptionsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_exemptions(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<ExemptionsResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let rows: Vec<(
+        Uuid,
+        Option<Uuid>,
+        Option<String>,
+        Option<String>,
+        DateTime<Utc>,
+        Option<DateTime<Utc>>,
+        Option<String>,
+        Option<String>,
+        bool,
+    )> = sqlx::query_as(
+        r#"
+        select id, player_uuid, permission, world, starts_at, ends_at, reason, created_by,
+               starts_at <= now() as active
+        from public.server_exemptions
+        where server_id = $1 and (ends_at is null or ends_at > now())
+        order by starts_at desc
+        "#,
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("exemptions query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let exemptions = rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                player_uuid,
+                permission,
+                world,
+                starts_at,
+                ends_at,
+                reason,
+                created_by,
+                active,
+            )| {
+                ExemptionItem {
+                    id,
+                    player_uuid,
+                    permission,
+                    world,
+                    starts_at,
+                    ends_at,
+                    reason,
+                    created_by,
+                    active,
+                }
+            },
+        )
+        .collect();
+
+    Ok(Json(ExemptionsResponse {
+        ok: true,
+        exemptions,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateExemptionRequest {
+    pub player_uuid: Option<Uuid>,
+    /// Permission node, e.g. "asyncanticheat.exempt".
+    pub permission: Option<String>,
+    pub world: Option<String>,
+    /// Defaults to now.
+    pub starts_at: Option<DateTime<Utc>>,
+    /// Omit for an open-ended exemption.
+    pub ends_at: Option<DateTime<Utc>>,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+}
+
+/// A validated exemption, with optional strings trimmed and blanks dropped.
+pub(crate) struct NewExemption {
+    pub player_uuid: Option<Uuid>,
+    pub permission: Option<String>,
+    pub world: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+}
+
+impl CreateExemptionRequest {
+    /// Shared by server and organization exemptions.
+    pub(crate) fn validate(&self) -> Result<NewExemption, ApiError> {
+        let trimmed = |v: &Option<String>| {
+            v.as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let permission = trimmed(&self.permission);
+        let world = trimmed(&self.world);
+
+        if self.player_uuid.is_none() && permission.is_none() && world.is_none() {
+            return Err(ApiError::BadRequest(
+                "at least one of player_uuid, permission or world is required".to_string(),
+            ));
+        }
+        let starts_at = self.starts_at.unwrap_or_else(Utc::now);
+        if let Some(ends_at) = self.ends_at {
+            if ends_at <= starts_at {
+                return Err(ApiError::BadRequest(
+                    "ends_at must be after starts_at".to_string(),
+                ));
+            }
+        }
+
+        Ok(NewExemption {
+            player_uuid: self.player_uuid,
+            permission,
+            world,
+            starts_at,
+            ends_at: self.ends_at,
+            reason: trimmed(&self.reason),
+            created_by: trimmed(&self.created_by),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateExemptionResponse {
+    pub ok: bool,
+    pub id: Uuid,
+}
+
+/// POST /dashboard/:server_id/exemptions
+///
+/// Exempt players matching every given selector (player, permission, world) during a time
+/// window.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/exemptions",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = CreateExemptionRequest,
+    responses(
+        (status = 200, body = CreateExemptionResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn create_exemption(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<CreateExemptionRequest>,
+) -> Result<Json<CreateExemptionResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let new = req.validate()?;
+
+    let exists: Option<(String,)> = sqlx::query_as("SELECT id FROM public.servers WHERE id = $1")
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("exemption server lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    let (id,): (Uuid,) = sqlx::query_as(
+        r#"
+        insert into public.server_exemptions
+            (server_id, player_uuid, permission, world, starts_at, ends_at, reason, created_by)
+        values ($1, $2, $3, $4, $5, $6, $7, $8)
+        returning id
+        "#,
+    )
+    .bind(&server_id)
+    .bind(new.player_uuid)
+    .bind(new.permission.as_deref())
+    .bind(new.world.as_deref())
+    .bind(new.starts_at)
+    .bind(new.ends_at)
+    .bind(new.reason.as_deref())
+    .bind(new.created_by.as_deref())
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("exemption insert failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    if new.permission.is_some() {
+        sync(&state, &server_id).await?;
+    }
+
+    tracing::info!(server_id = %server_id, exemption_id = %id, "exemption created");
+
+    Ok(Json(CreateExemptionResponse { ok: true, id }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeleteExemptionResponse {
+    pub ok: bool,
+}
+
+/// DELETE /dashboard/:server_id/exemptions/:exemption_id
+#[utoipa::path(
+    delete,
+    path = "/dashboard/{server_id}/exemptions/{exemption_id}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("exemption_id" = Uuid, Path, description = "Exemption id"),
+    ),
+    responses(
+        (status = 200, body = DeleteExemptionResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn delete_exemption(
+    State(state): State<AppState>,
+    Path((server_id, exemption_id)): Path<(String, Uuid)>,
+) -> Result<Json<DeleteExemptionResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let deleted: Option<(Option<String>,)> = sqlx::query_as(
+        "DELETE FROM public.server_exemptions WHERE id = $1 AND server_id = $2 RETURNING permission",
+    )
+    .bind(exemption_id)
+    .bind(&server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("exemption delete failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let Some((permission,)) = deleted else {
+        return Err(ApiError::NotFound(format!(
+            "exemption {} not found",
+            exemption_id
+        )));
+    };
+    if permission.is_some() {
+        sync(&state, &server_id).await?;
+    }
+
+    Ok(Json(DeleteExemptionResponse { ok: true }))
+}
+
+async fn sync(state: &AppState, server_id: &str) -> Result<(), ApiError> {
+    exemptions::sync_to_plugin(&state.db, server_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("exemptions sync enqueue failed: {:?}", e);
+            ApiError::Internal
+        })
+}
@@ -0,0 +1,179 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::finding_latency::{self, LatencyDay, LatencySummary};
+use crate::{error::ApiError, AppState};
+
+pub const DEFAULT_DAYS: i32 = 7;
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FindingLatencyQuery {
+    /// Days back, today included (default 7, at most 90).
+    pub days: Option<i32>,
+    /// Only this detector.
+    pub detector: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DetectorLatency {
+    pub detector_name: String,
+    #[serde(flatten)]
+    pub summary: LatencySummary,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FindingLatencyResponse {
+    pub ok: bool,
+    /// The current SLO; past days keep the one they were rolled up with.
+    pub slo_seconds: u64,
+    /// Oldest first; today is live.
+    pub days: Vec<LatencyDay>,
+    /// Per detector, worst attainment first.
+    pub detectors: Vec<DetectorLatency>,
+    pub totals: LatencySummary,
+}
+
+type LatencyRow = (
+    NaiveDate,
+    String,
+    i64,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<i32>,
+    i64,
+    i64,
+);
+
+/// GET /dashboard/:server_id/finding-latency
+///
+/// Ingest → finding latency percentiles per detector and day, and how many findings arrived
+/// within the SLO.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/finding-latency",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        FindingLatencyQuery,
+    ),
+    responses(
+        (status = 200, body = FindingLatencyResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_finding_latency(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Query(q): Query<FindingLatencyQuery>,
+) -> Result<Json<FindingLatencyResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let days = q
+        .days
+        .unwrap_or(DEFAULT_DAYS)
+        .clamp(1, finding_latency::RETENTION_DAYS);
+    let detector = q
+        .detector
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+    let slo_ms = (state.finding_latency_slo_seconds * 1000).min(i32::MAX as u64) as i32;
+
+    let rows: Vec<LatencyRow> = sqlx::query_as(
+        r#"
+        with rolled as (
+            select day, detector_name, findings::bigint, p50_ms, p90_ms, p99_ms, max_ms,
+                   within_slo::bigint, slo_ms::bigint
+            from public.finding_latency_daily
+            where server_id = $1
+              and ($2::text is null or detector_name = $2)
+              and day > (now() at time zone 'utc')::date - $3
+              and day < (now() at time zone 'utc')::date
+        ),
+        live as (
+            select
+                (now() at time zone 'utc')::date,
+                detector_name,
+                count(*),
+                percentile_cont(0.5) within group (order by ingest_latency_ms),
+                percentile_cont(0.9) within group (order by ingest_latency_ms),
+                percentile_cont(0.99) within group (order by ingest_latency_ms),
+                max(ingest_latency_ms),
+                count(*) filter (where ingest_latency_ms <= $4),
+                $4::bigint
+            from public.findings
+            where server_id = $1
+              and ($2::text is null or detector_name = $2)
+              and ingest_latency_ms is not null
+              and first_seen_at >= date_trunc('day', now() at time zone 'utc') at time zone 'utc'
+            group by detector_name
+        )
+        select * from rolled
+        union all
+        select * from live
+        order by 1 asc, 2 asc
+        "#,
+    )
+    .bind(&server_id)
+    .bind(detector)
+    .bind(days)
+    .bind(slo_ms)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("finding latency query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let days: Vec<LatencyDay> = rows
+        .into_iter()
+        .map(
+            |(day, detector_name, findings, p50_ms, p90_ms, p99_ms, max_ms, within_slo, slo_ms)| {
+                LatencyDay {
+                    day,
+                    detector_name,
+                    findings,
+                    p50_ms,
+                    p90_ms,
+                    p99_ms,
+                    max_ms,
+                    within_slo,
+                    slo_ms,
+                }
+            },
+        )
+        .collect();
+
+    let mut names: Vec<&str> = days.iter().map(|d| d.detector_name.as_str()).collect();
+    names.sort_unstable();
+    names.dedup();
+    let mut detectors: Vec<DetectorLatency> = names
+        .into_iter()
+        .map(|name| DetectorLatency {
+            detector_name: name.to_string(),
+            summary: finding_latency::summarize(days.iter().filter(|d| d.detector_name == name)),
+        })
+        .collect();
+    detectors.sort_by(|a, b| {
+        a.summary
+            .attainment
+            .unwrap_or(1.0)
+            .total_cmp(&b.summary.attainment.unwrap_or(1.0))
+            .then_with(|| a.detector_name.cmp(&b.detector_name))
+    });
+
+    Ok(Json(FindingLatencyResponse {
+        ok: true,
+        slo_seconds: state.finding_latency_slo_seconds,
+        totals: finding_latency::summarize(&days),
+        days,
+        detectors,
+    }))
+}
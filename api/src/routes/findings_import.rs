@@ -0,0 +1,74 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::findings_import::{self, ImportSummary, TimeContext, PARSERS};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FindingsImportRequest {
+    /// Log format: `ncp` (NoCheatPlus), `grim` or `vulcan`.
+    pub format: String,
+    /// The exported log, one violation per line.
+    pub log: String,
+    /// Day of time-only timestamps (`[12:03:44 INFO]`), e.g. for a `latest.log`.
+    pub date: Option<NaiveDate>,
+    /// Offset of the log's local time from UTC, in minutes (default 0).
+    pub utc_offset_minutes: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FindingsImportResponse {
+    pub ok: bool,
+    #[serde(flatten)]
+    pub summary: ImportSummary,
+}
+
+/// POST /dashboard/:server_id/import
+///
+/// Import violation history from another anticheat's log as findings tagged with its source.
+/// Importing the same log again does not double the counts.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/import",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = FindingsImportRequest,
+    responses(
+        (status = 200, body = FindingsImportResponse),
+        (status = 400, description = "Unknown format or invalid offset", body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn import_findings(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<FindingsImportRequest>,
+) -> Result<Json<FindingsImportResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let parser = findings_import::parser_for(&req.format).ok_or_else(|| {
+        let known: Vec<&str> = PARSERS.iter().map(|p| p.source()).collect();
+        ApiError::BadRequest(format!(
+            "unknown format {:?} (supported: {})",
+            req.format,
+            known.join(", ")
+        ))
+    })?;
+    let offset = FixedOffset::east_opt(req.utc_offset_minutes.unwrap_or(0).saturating_mul(60))
+        .ok_or_else(|| ApiError::BadRequest("utc_offset_minutes is out of range".to_string()))?;
+    let ctx = TimeContext {
+        date: req.date,
+        offset,
+    };
+
+    let summary = findings_import::import(&state, &server_id, parser, &req.log, &ctx).await?;
+    Ok(Json(FindingsImportResponse { ok: true, summary }))
+}
@@ -0,0 +1,39 @@
+use axum::{extract::State, http::HeaderMap, Json};
+
+use crate::{
+    auth,
+    error::ApiError,
+    graphql::{self, Role},
+    AppState,
+};
+
+/// POST /graphql
+///
+/// Accepts either token: `ADMIN_TOKEN` resolves admin-only fields, `DASHBOARD_TOKEN` everything
+/// else. Like the dashboard routes, an unset `DASHBOARD_TOKEN` allows unauthenticated
+/// (dashboard-role) access for local development.
+pub async fn graphql(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<async_graphql::Request>,
+) -> Result<Json<async_graphql::Response>, ApiError> {
+    let role = resolve_role(&state, &headers)?;
+    let resp = graphql::schema().execute(req.data(state).data(role)).await;
+    Ok(Json(resp))
+}
+
+fn resolve_role(state: &AppState, headers: &HeaderMap) -> Result<Role, ApiError> {
+    let provided = auth::parse_bearer_token(headers);
+    if let (Some(provided), Some(admin)) = (provided.as_deref(), state.admin_token.as_deref()) {
+        if auth::constant_time_eq(provided, admin) {
+            return Ok(Role::Admin);
+        }
+    }
+    match (provided.as_deref(), state.dashboard_token.as_deref()) {
+        (_, None) => Ok(Role::Dashboard),
+        (Some(provided), Some(expected)) if auth::constant_time_eq(provided, expected) => {
+            Ok(Role::Dashboard)
+        }
+        _ => Err(ApiError::Unauthorized),
+    }
+}
@@ -1,11 +1,18 @@
 use axum::Json;
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct HealthResponse {
     pub ok: bool,
 }
 
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "plugin",
+    responses((status = 200, body = HealthResponse)),
+)]
 pub async fn health() -> Json<HealthResponse> {
     Json(HealthResponse { ok: true })
 }
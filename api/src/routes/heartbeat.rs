@@ -2,80 +2,102 @@
 //!
 //! The plugin calls POST /heartbeat every 30 seconds to update last_seen_at,
 //! allowing the dashboard to show accurate "Plugin Status" even when idle.
+//! The response also carries any queued server -> plugin actions (see `pending_actions`), as
+//! does every ingest response.
+//! An optional JSON body reports server performance; samples are kept in `server_heartbeats`
+//! for [`HEARTBEAT_RETENTION_HOURS`] and shown by the dashboard status endpoint.
 
 use axum::{extract::State, http::HeaderMap, Json};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-use crate::{auth, error::ApiError, AppState};
+use crate::pending_actions::{self, PendingAction};
+use crate::{auth, error::ApiError, plugin_version, AppState};
 
-#[derive(Serialize)]
+/// Performance samples older than this are pruned on the next heartbeat.
+pub const HEARTBEAT_RETENTION_HOURS: i64 = 24;
+
+const MAX_VERSION_LEN: usize = 64;
+
+/// Optional heartbeat body; every field may be omitted.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct HeartbeatRequest {
+    /// Ticks per second (20 = healthy).
+    #[serde(default)]
+    pub tps: Option<f64>,
+    /// Milliseconds per tick.
+    #[serde(default)]
+    pub mspt: Option<f64>,
+    #[serde(default)]
+    pub online_players: Option<i32>,
+    #[serde(default)]
+    pub plugin_version: Option<String>,
+    /// Minecraft server version, e.g. "1.20.4".
+    #[serde(default)]
+    pub mc_version: Option<String>,
+}
+
+impl HeartbeatRequest {
+    /// Drop values that can't be real (negative, non-finite, oversized strings).
+    pub fn normalized(self) -> Self {
+        let metric = |v: Option<f64>| v.filter(|v| v.is_finite() && *v >= 0.0);
+        let version = |v: Option<String>| {
+            v.map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty() && s.len() <= MAX_VERSION_LEN)
+        };
+        Self {
+            tps: metric(self.tps),
+            mspt: metric(self.mspt),
+            online_players: self.online_players.filter(|n| *n >= 0),
+            plugin_version: version(self.plugin_version),
+            mc_version: version(self.mc_version),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct HeartbeatResponse {
     pub ok: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_actions: Vec<PendingAction>,
 }
 
 /// POST /heartbeat
 ///
 /// Lightweight endpoint for plugin liveness. Updates last_seen_at without
 /// requiring a full batch upload. Called every 30 seconds by the plugin.
+/// Plugins may include a [`HeartbeatRequest`] body with TPS / MSPT / player count.
 ///
 /// Headers:
 /// - Authorization: Bearer <token>
 /// - X-Server-Id: <server_id>
+#[utoipa::path(
+    post,
+    path = "/heartbeat",
+    tag = "plugin",
+    params(
+        ("X-Server-Id" = String, Header, description = "Server id"),
+        ("X-Plugin-Version" = Option<String>, Header, description = "Plugin version, e.g. 1.4.2"),
+        ("X-Ack-Actions" = Option<String>, Header, description = "Comma-separated ids of applied pending actions"),
+    ),
+    request_body(content = Option<HeartbeatRequest>, content_type = "application/json"),
+    responses(
+        (status = 200, body = HeartbeatResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("server_token" = [])),
+)]
 pub async fn heartbeat(
     State(state): State<AppState>,
     headers: HeaderMap,
+    body: Option<Json<HeartbeatRequest>>,
 ) -> Result<Json<HeartbeatResponse>, ApiError> {
-    // Extract server_id from header
-    let server_id = headers
-        .get("x-server-id")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("")
-        .trim()
-        .to_string();
-
-    if server_id.is_empty() {
-        return Err(ApiError::BadRequest(
-            "X-Server-Id header is required".to_string(),
-        ));
-    }
-
-    // Extract and validate token
-    let token = auth::parse_bearer_token(&headers)
-        .ok_or_else(|| ApiError::BadRequest("Authorization header is required".to_string()))?;
-
-    if token.is_empty() {
-        return Err(ApiError::Unauthorized);
-    }
-
-    let token_hash = auth::sha256_hex(&token);
-
-    // Fetch stored token hash for constant-time comparison
-    let stored_hash: Option<(Option<String>,)> =
-        sqlx::query_as("SELECT auth_token_hash FROM public.servers WHERE id = $1")
-            .bind(&server_id)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| {
-                tracing::error!("heartbeat token lookup failed: {:?}", e);
-                ApiError::Internal
-            })?;
-
-    // Verify token using constant-time comparison
-    match stored_hash {
-        None => {
-            // Server doesn't exist
-            return Err(ApiError::Unauthorized);
-        }
-        Some((None,)) => {
-            // Server exists but has no token stored - reject
-            return Err(ApiError::Unauthorized);
-        }
-        Some((Some(stored),)) => {
-            if !auth::validate_token_hash(&token_hash, &stored) {
-                return Err(ApiError::Unauthorized);
-            }
-        }
-    }
+    let server_id = auth::verify_server_token(&state, &headers).await?;
 
     // Update last_seen_at
     sqlx::query("UPDATE public.servers SET last_seen_at = NOW() WHERE id = $1")
@@ -87,7 +109,66 @@ pub async fn heartbeat(
             ApiError::Internal
         })?;
 
-    tracing::debug!(server_id = %server_id, "heartbeat received");
+    // Best-effort: metrics are for the dashboard only and must not fail the heartbeat.
+    let metrics = body.map(|Json(b)| b.normalized()).unwrap_or_default();
+    if let Some(version) =
+        plugin_version::from_headers(&headers).or_else(|| metrics.plugin_version.clone())
+    {
+        if let Err(e) = plugin_version::record(&state.db, &server_id, &version).await {
+            tracing::warn!("plugin version update failed: {:?}", e);
+        }
+    }
+    if !metrics.is_empty() {
+        if let Err(e) = record_metrics(&state.db, &server_id, &metrics).await {
+            tracing::warn!("heartbeat metrics insert failed: {:?}", e);
+        }
+    }
+
+    let pending_actions = pending_actions::exchange(&state.db, &server_id, &headers).await;
+
+    tracing::debug!(
+        server_id = %server_id,
+        pending_actions = pending_actions.len(),
+        "heartbeat received"
+    );
+
+    Ok(Json(HeartbeatResponse {
+        ok: true,
+        pending_actions,
+    }))
+}
+
+/// Store a performance sample and prune the server's samples past the retention window.
+async fn record_metrics(
+    db: &sqlx::PgPool,
+    server_id: &str,
+    metrics: &HeartbeatRequest,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        insert into public.server_heartbeats
+            (server_id, tps, mspt, online_players, plugin_version, mc_version)
+        values ($1, $2, $3, $4, $5, $6)
+        "#,
+    )
+    .bind(server_id)
+    .bind(metrics.tps)
+    .bind(metrics.mspt)
+    .bind(metrics.online_players)
+    .bind(metrics.plugin_version.as_deref())
+    .bind(metrics.mc_version.as_deref())
+    .execute(db)
+    .await?;
 
-    Ok(Json(HeartbeatResponse { ok: true }))
+    sqlx::query(
+        r#"
+        delete from public.server_heartbeats
+        where server_id = $1 and received_at < now() - make_interval(hours => $2)
+        "#,
+    )
+    .bind(server_id)
+    .bind(HEARTBEAT_RETENTION_HOURS as i32)
+    .execute(db)
+    .await?;
+    Ok(())
 }
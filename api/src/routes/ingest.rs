@@ -1,33 +1,80 @@
 use axum::{
     body::Bytes,
-    extract::State,
+    extract::{rejection::BytesRejection, State},
     http::{HeaderMap, StatusCode},
     Json,
 };
 use flate2::read::GzDecoder;
 use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, QueryBuilder};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, BufReader};
+use tracing::Instrument;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::auth::Admission;
+use crate::batch_meta::{self, BatchMeta};
+use crate::batch_sequence::{self, SeqStatus};
+use crate::chaos::FaultPoint;
+use crate::clock_skew::{self, BatchClock};
+use crate::ingest_anomalies::{self, Anomaly};
+use crate::ingest_filters;
+use crate::ingest_hints::{self, IngestHints, Load};
 use crate::module_pipeline;
-use crate::{auth, error::ApiError, AppState};
+use crate::pending_actions::{self, PendingAction};
+use crate::plugin_version::{self, PluginUpdate};
+use crate::privacy;
+use crate::sampling::{self, Decision};
+use crate::shared_state::fixed_window;
+use crate::transforms::{self, ClientType};
+use crate::{
+    auth, client_channels, dispatch_shedding, error::ApiError, identity, player_sessions,
+    proxy_groups, watchlist, AppState,
+};
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct IngestResponse {
     pub ok: bool,
     pub batch_id: Uuid,
     pub s3_key: String,
+    /// Present when a newer plugin version is recommended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin_update: Option<PluginUpdate>,
+    /// Back-off advice from the API's current load (see `ingest_hints`).
+    pub hints: IngestHints,
+    /// Records dropped by the server's ingest filters (see `ingest_filters`).
+    #[serde(default)]
+    pub filtered_records: usize,
+    /// Queued server -> plugin actions, repeated until acknowledged (see `pending_actions`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_actions: Vec<PendingAction>,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateRequiredResponse {
+    pub ok: bool,
+    /// Always "update_required".
+    pub status: String,
+    pub server_id: String,
+    pub plugin_update: PluginUpdate,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct WaitingForRegistrationResponse {
     pub ok: bool,
     pub status: String,
     pub server_id: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ServerDeletedResponse {
+    pub ok: bool,
+    /// Always "server_deleted".
+    pub status: String,
+    pub server_id: String,
+}
+
 /// POST /ingest
 ///
 /// Receives a gzipped NDJSON batch of packet records.
@@ -35,11 +82,56 @@ pub struct WaitingForRegistrationResponse {
 /// 2. Uploads raw payload to S3
 /// 3. Upserts server identity in Postgres
 /// 4. Inserts batch_index row pointing to S3 object
+///
+/// Suspicious batches (see `ingest_anomalies`) are stored and acknowledged but quarantined:
+/// they are not dispatched to modules or used for player tracking.
+///
+/// Plugins below `MIN_SUPPORTED_PLUGIN_VERSION` get 426 and the batch is dropped, as do
+/// servers deleted from the dashboard (410).
+///
+/// On servers with `hash_player_uuids` every player UUID is replaced by its per-server
+/// pseudonym (`privacy`) before the batch is stored, tracked or dispatched.
+///
+/// On servers with a `sample_rate`, batches whose players are all low-risk are analyzed with
+/// that probability; the others are stored without dispatch (`sampling`).
+///
+/// The response carries `hints` for adaptive batching: while the API is loaded, plugins are
+/// asked to wait longer between uploads and send smaller payloads. It also carries the queued
+/// server -> plugin actions until the plugin acknowledges them in `X-Ack-Actions`.
+#[utoipa::path(
+    post,
+    path = "/ingest",
+    tag = "plugin",
+    params(
+        ("X-Server-Id" = String, Header, description = "Server id"),
+        ("X-Session-Id" = String, Header, description = "Plugin session id"),
+        ("X-Proxy-Id" = Option<String>, Header, description = "Proxy the backend sits behind (BungeeCord/Velocity)"),
+        ("X-Plugin-Version" = Option<String>, Header, description = "Plugin version, e.g. 1.4.2"),
+        ("X-Batch-Seq" = Option<i64>, Header, description = "Per-session batch number, starting at 1"),
+        ("X-Client-Time" = Option<i64>, Header, description = "Plugin clock (epoch ms) when the upload was sent"),
+        ("X-Ack-Actions" = Option<String>, Header, description = "Comma-separated ids of applied pending actions"),
+    ),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream"),
+    responses(
+        (status = 200, body = IngestResponse),
+        (status = 409, description = "Server not registered to an account yet", body = WaitingForRegistrationResponse),
+        (status = 426, description = "Plugin version no longer supported; batch dropped", body = UpdateRequiredResponse),
+        (status = 410, description = "Server deleted from the dashboard; batch dropped", body = ServerDeletedResponse),
+        (status = 413, description = "Body larger than MAX_BODY_BYTES", body = ErrorBody),
+        (status = 429, description = "Server over INGEST_RATE_LIMIT_PER_MINUTE; retry after Retry-After", body = ErrorBody),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("server_token" = [])),
+)]
 pub async fn ingest(
     State(state): State<AppState>,
     headers: HeaderMap,
-    body: Bytes,
+    body: Result<Bytes, BytesRejection>,
 ) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let received_at = chrono::Utc::now();
+    let body = body.map_err(|r| body_rejection(r, &headers, state.max_body_bytes))?;
+
     // --- Extract required headers early (also needed for auth/registration gate) ---
     let server_id = headers
         .get("x-server-id")
@@ -58,14 +150,15 @@ pub async fn ingest(
             "missing X-Server-Id or X-Session-Id".to_string(),
         ));
     }
+    let batch_seq = batch_sequence::from_headers(&headers).map_err(ApiError::BadRequest)?;
+    let client_time = clock_skew::from_headers(&headers).map_err(ApiError::BadRequest)?;
 
     // --- Size check ---
     if body.len() > state.max_body_bytes {
-        return Err(ApiError::BadRequest(format!(
-            "payload too large: {} bytes (max {})",
-            body.len(),
-            state.max_body_bytes
-        )));
+        return Err(ApiError::PayloadTooLarge {
+            size: body.len(),
+            max: state.max_body_bytes,
+        });
     }
 
     // --- Auth (per-server token) ---
@@ -81,54 +174,26 @@ pub async fn ingest(
     // Extract server address for ping feature (explicit header > forwarded-for > real-ip)
     let server_address = auth::extract_server_address(&headers);
 
+    // Backends behind a BungeeCord/Velocity proxy identify it so they can be grouped.
+    let proxy_id = proxy_groups::proxy_id_from_headers(&headers);
+
+    let version = plugin_version::from_headers(&headers);
+    let plugin_update = state.plugin_versions.check(version.as_deref());
+
     // --- Registration gate ---
     // We store the server + token hash the first time we see it, but we do not accept payloads
-    // until the server is linked to a dashboard account (owner_user_id + registered_at).
-    let row: Option<(
-        Option<String>,
-        Option<uuid::Uuid>,
-        Option<chrono::DateTime<chrono::Utc>>,
-    )> = sqlx::query_as(
-        r#"
-            select auth_token_hash, owner_user_id, registered_at
-            from public.servers
-            where id = $1
-            "#,
+    // until the server is linked to a dashboard account or organization (owner + registered_at).
+    let owner_id = match auth::admit_plugin(
+        &state.db,
+        &server_id,
+        &token_hash,
+        platform.as_deref(),
+        server_address.as_deref(),
     )
-    .bind(&server_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| {
-        tracing::error!("ingest registration lookup failed: {:?}", e);
-        ApiError::Internal
-    })?;
-
-    match row {
-        None => {
-            // New server: insert as pending.
-            sqlx::query(
-                r#"
-                insert into public.servers
-                    (id, platform, first_seen_at, last_seen_at, auth_token_hash, auth_token_first_seen_at, callback_url)
-                values
-                    ($1, $2, now(), now(), $3, now(), $4)
-                on conflict (id) do update set
-                    platform = coalesce(excluded.platform, servers.platform),
-                    last_seen_at = now(),
-                    callback_url = coalesce(excluded.callback_url, servers.callback_url)
-                "#,
-            )
-            .bind(&server_id)
-            .bind(platform.as_deref())
-            .bind(&token_hash)
-            .bind(server_address.as_deref())
-            .execute(&state.db)
-            .await
-            .map_err(|e| {
-                tracing::error!("ingest insert pending server failed: {:?}", e);
-                ApiError::Internal
-            })?;
-
+    .await?
+    {
+        Admission::Registered { owner_id } => owner_id,
+        Admission::Pending => {
             let body = WaitingForRegistrationResponse {
                 ok: true,
                 status: "waiting_for_registration".to_string(),
@@ -139,74 +204,145 @@ pub async fn ingest(
                 Json(serde_json::to_value(body).unwrap()),
             ));
         }
-        Some((stored_hash_opt, owner_user_id, registered_at)) => {
-            // Validate token FIRST before updating any state.
-            // This prevents attackers from spoofing last_seen_at with invalid tokens.
-            // Uses constant-time comparison to prevent timing attacks.
-            if let Some(stored_hash) = &stored_hash_opt {
-                if !auth::validate_token_hash(&token_hash, stored_hash) {
-                    return Err(ApiError::Unauthorized);
-                }
-            }
+        Admission::Deleted => {
+            let body = ServerDeletedResponse {
+                ok: false,
+                status: "server_deleted".to_string(),
+                server_id,
+            };
+            return Ok((StatusCode::GONE, Json(serde_json::to_value(body).unwrap())));
+        }
+    };
 
-            // Token is valid (or no token stored yet) - now update last_seen_at and callback_url.
-            let _ = sqlx::query(
-                r#"
-                update public.servers
-                set last_seen_at = now(),
-                    callback_url = coalesce($2, callback_url)
-                where id = $1
-                "#,
+    // --- Per-server rate limit (shared across replicas, see `shared_state`) ---
+    if state.ingest_rate_limit_per_minute > 0 {
+        let now = received_at.timestamp().max(0) as u64;
+        let (window, retry_after) = fixed_window(now, 60);
+        let count = state
+            .shared
+            .incr(
+                &format!("ingest_rate:{}:{}", server_id, window),
+                std::time::Duration::from_secs(60),
             )
-            .bind(&server_id)
-            .bind(server_address.as_deref())
-            .execute(&state.db)
             .await;
+        if count > state.ingest_rate_limit_per_minute {
+            tracing::debug!(server_id = %server_id, count = count, "batch rejected: rate limited");
+            return Err(ApiError::RateLimited {
+                retry_after_seconds: Some(retry_after),
+            });
+        }
+    }
 
-            // First time we see a token for an existing row: store it.
-            if stored_hash_opt.is_none() {
-                let _ = sqlx::query(
-                    r#"
-                    update public.servers
-                    set auth_token_hash = $2,
-                        auth_token_first_seen_at = coalesce(auth_token_first_seen_at, now())
-                    where id = $1
-                    "#,
-                )
-                .bind(&server_id)
-                .bind(&token_hash)
-                .execute(&state.db)
-                .await;
-            }
+    // --- Plugin version gate (known-buggy plugins must not write data) ---
+    if let Some(version) = version.as_deref() {
+        if let Err(e) = plugin_version::record(&state.db, &server_id, version).await {
+            tracing::warn!("plugin version update failed: {:?}", e);
+        }
+    }
+    if let Some(update) = plugin_update.as_ref().filter(|u| u.update_required) {
+        tracing::info!(
+            server_id = %server_id,
+            plugin_version = ?version,
+            "batch rejected: plugin version unsupported"
+        );
+        let body = UpdateRequiredResponse {
+            ok: false,
+            status: "update_required".to_string(),
+            server_id,
+            plugin_update: update.clone(),
+        };
+        return Ok((
+            StatusCode::UPGRADE_REQUIRED,
+            Json(serde_json::to_value(body).unwrap()),
+        ));
+    }
+
+    // --- Batch metadata line (strict for plugins declaring `meta_version`) ---
+    let meta = match batch_meta::first_line(&body) {
+        Some(line) => batch_meta::validate(&line)
+            .map_err(|e| ApiError::BadRequest(format!("invalid batch metadata: {}", e)))?,
+        None => BatchMeta::default(),
+    };
 
-            let is_registered = owner_user_id.is_some() && registered_at.is_some();
-            if !is_registered {
-                let body = WaitingForRegistrationResponse {
-                    ok: true,
-                    status: "waiting_for_registration".to_string(),
-                    server_id,
-                };
-                return Ok((
-                    StatusCode::CONFLICT,
-                    Json(serde_json::to_value(body).unwrap()),
-                ));
+    // --- Server packet filters (best-effort: on error the batch is stored unfiltered) ---
+    let filters = ingest_filters::load(&state.db, &server_id)
+        .await
+        .map(|(filters, _)| filters)
+        .unwrap_or_else(|e| {
+            tracing::warn!(server_id = %server_id, "ingest filters lookup failed: {:?}", e);
+            Default::default()
+        });
+    let (body, filtered_records) = if filters.is_empty() {
+        (body, 0)
+    } else {
+        let raw = body.clone();
+        let filtered =
+            tokio::task::spawn_blocking(move || ingest_filters::apply(&raw, &filters)).await;
+        match filtered {
+            Ok(Ok(Some((filtered, dropped)))) => (Bytes::from(filtered), dropped),
+            Ok(Ok(None)) => (body, 0),
+            Ok(Err(e)) => {
+                tracing::debug!(server_id = %server_id, "ingest filtering skipped: {:?}", e);
+                (body, 0)
+            }
+            Err(e) => {
+                tracing::error!("ingest filtering panicked: {:?}", e);
+                (body, 0)
             }
         }
-    }
+    };
+
+    // --- Player UUID hashing: nothing downstream may see the real UUIDs ---
+    let hashes = privacy::hashes_player_uuids(&state.db, &server_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to look up player UUID hashing: {:?}", e);
+            ApiError::Internal
+        })?;
+    let body = if hashes {
+        let Some(key) = state.player_uuid_hash_key.clone() else {
+            tracing::error!(
+                server_id = %server_id,
+                "server hashes player UUIDs but PLAYER_UUID_HASH_KEY is unset; batch refused"
+            );
+            return Err(ApiError::Internal);
+        };
+        let raw = body.clone();
+        let hash_server_id = server_id.clone();
+        let hashed = tokio::task::spawn_blocking(move || {
+            privacy::pseudonymize_batch(&raw, &key, &hash_server_id)
+        })
+        .await
+        .map_err(|e| {
+            tracing::error!("player UUID hashing panicked: {:?}", e);
+            ApiError::Internal
+        })?
+        .map_err(|e| {
+            ApiError::BadRequest(format!(
+                "batch could not be decoded for player UUID hashing: {}",
+                e
+            ))
+        })?;
+        Bytes::from(hashed)
+    } else {
+        body
+    };
 
     let batch_id = Uuid::new_v4();
     let payload_bytes: i32 = body.len().try_into().unwrap_or(i32::MAX);
 
     // Generate the S3 key upfront (deterministic, doesn't require upload)
     // Returns None if server_id or session_id sanitizes to empty (e.g., malicious "../../../")
-    let s3_key = crate::s3::ObjectStore::batch_key(&server_id, &session_id, &batch_id)
-        .ok_or_else(|| {
+    let s3_key =
+        crate::s3::ObjectStore::batch_key(&server_id, &session_id, &batch_id).ok_or_else(|| {
             tracing::warn!(
                 server_id = %server_id,
                 session_id = %session_id,
                 "Invalid server_id or session_id: sanitizes to empty string"
             );
-            ApiError::BadRequest("Invalid server_id or session_id: sanitizes to empty string".into())
+            ApiError::BadRequest(
+                "Invalid server_id or session_id: sanitizes to empty string".into(),
+            )
         })?;
 
     // --- DB operations FIRST to avoid orphaned S3 objects on failure ---
@@ -218,6 +354,19 @@ pub async fn ingest(
             ApiError::Internal
         })?;
 
+    // Link to the owner's proxy group (registered servers always have an owner here).
+    let proxy_group_id = match proxy_id.as_deref() {
+        Some(proxy_id) => Some(
+            proxy_groups::link_server(&state.db, owner_id, proxy_id, &server_id)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to link proxy group: {:?}", e);
+                    ApiError::Internal
+                })?,
+        ),
+        _ => None,
+    };
+
     // Ensure built-in module entries exist for newly-seen servers.
     // Without this, dispatch_batch is a no-op and the dashboard shows no modules/findings.
     ensure_builtin_modules(&state.db, &server_id)
@@ -227,15 +376,121 @@ pub async fn ingest(
             ApiError::Internal
         })?;
 
+    // Look for poisoned input before anything downstream sees it.
+    let inspection = {
+        let raw = body.clone();
+        let now_ms = received_at.timestamp_millis();
+        tokio::task::spawn_blocking(move || ingest_anomalies::inspect(&raw, now_ms))
+            .await
+            .map_err(|e| {
+                tracing::error!("batch inspection panicked: {:?}", e);
+                ApiError::Internal
+            })?
+    };
+    let mut anomalies = inspection.anomalies;
+    if let Some(sha256) = inspection.sha256.as_deref() {
+        let previous = ingest_anomalies::find_repeat(&state.db, &server_id, sha256)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to look up repeated payload: {:?}", e);
+                ApiError::Internal
+            })?;
+        if let Some(previous_batch_id) = previous {
+            anomalies.push(Anomaly::RepeatedPayload { previous_batch_id });
+        }
+    }
+    let quarantined = !anomalies.is_empty();
+
+    // Low-risk players on sampling servers: only a share of their batches is analyzed.
+    let sample = match sampling::server_rate(&state.db, &server_id).await {
+        Ok(Some(rate)) if !quarantined => {
+            let raw = body.clone();
+            match tokio::task::spawn_blocking(move || sampling::batch_players(&raw)).await {
+                Ok(Ok(players)) => {
+                    sampling::decide(&state.db, &server_id, batch_id, rate, &players).await
+                }
+                Ok(Err(e)) => {
+                    tracing::debug!(server_id = %server_id, "sampling skipped: {:?}", e);
+                    Decision::Full
+                }
+                Err(e) => {
+                    tracing::error!("batch player extraction panicked: {:?}", e);
+                    Decision::Full
+                }
+            }
+        }
+        Ok(_) => Decision::Full,
+        Err(e) => {
+            tracing::warn!(server_id = %server_id, "sample rate lookup failed: {:?}", e);
+            Decision::Full
+        }
+    };
+
+    // Place the batch in the session's sequence (gaps / reordering / duplicates).
+    let seq = match batch_seq {
+        Some(seq) => {
+            let status = batch_sequence::check(&state.db, &server_id, &session_id, seq)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to check batch sequence: {:?}", e);
+                    ApiError::Internal
+                })?;
+            if status != SeqStatus::InOrder {
+                tracing::warn!(
+                    server_id = %server_id,
+                    session_id = %session_id,
+                    batch_seq = seq,
+                    seq_status = status.as_str(),
+                    "batch out of sequence"
+                );
+            }
+            Some((seq, status))
+        }
+        None => None,
+    };
+
+    // Plugin clock skew, for transforms that rebase `ts` onto server time.
+    let offset_ms = client_time.map(|t| clock_skew::offset(t, received_at));
+    let skew_ms = clock_skew::session_skew(&state.db, &server_id, &session_id, offset_ms)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to estimate clock skew: {:?}", e);
+            ApiError::Internal
+        })?;
+    let clock = BatchClock {
+        received_at,
+        offset_ms,
+        skew_ms,
+    };
+
+    // Overloaded: store the batch but leave dispatch to the recovery job (`dispatch_shedding`).
+    let shed = !quarantined
+        && !sample.is_sampled_out()
+        && dispatch_shedding::sheds_dispatch(&Load::current(&state.dispatch_backlog, &state.db));
+
     // Insert batch_index row (before S3 upload to reserve the slot)
-    insert_batch_index(
-        &state.db,
-        &batch_id,
-        &server_id,
-        &session_id,
-        &s3_key,
-        payload_bytes,
-    )
+    async {
+        state
+            .faults
+            .inject(FaultPoint::DbBatchIndex, &server_id)
+            .await?;
+        insert_batch_index(
+            &state.db,
+            &batch_id,
+            &server_id,
+            &session_id,
+            &s3_key,
+            payload_bytes,
+            seq,
+            clock,
+            inspection.sha256.as_deref(),
+            quarantined,
+            shed,
+            sample,
+            &meta,
+        )
+        .await
+    }
     .await
     .map_err(|e| {
         tracing::error!("Failed to insert batch_index: {:?}", e);
@@ -245,59 +500,152 @@ pub async fn ingest(
     // --- Upload to S3 after DB success ---
     // If this fails, we have a batch_index row without data, but that's easier
     // to detect and retry than orphaned S3 objects without DB references
-    state
-        .object_store
-        .put_batch(&server_id, &session_id, &batch_id, body.to_vec())
-        .await
-        .map_err(|e| {
-            tracing::error!("S3 upload failed (batch_index exists): {:?}", e);
-            // Note: batch_index row exists but S3 object doesn't - should be retried
-            ApiError::Internal
-        })?;
+    async {
+        state.faults.inject(FaultPoint::S3Put, &server_id).await?;
+        state
+            .object_store
+            .put_batch(&server_id, &session_id, &batch_id, body.clone())
+            .await
+    }
+    .await
+    .map_err(|e| {
+        tracing::error!("S3 upload failed (batch_index exists): {:?}", e);
+        // Note: batch_index row exists but S3 object doesn't - should be retried
+        ApiError::Internal
+    })?;
+
+    if quarantined {
+        if let Err(e) =
+            ingest_anomalies::record(&state.db, batch_id, &server_id, &session_id, &anomalies).await
+        {
+            tracing::error!("Failed to record ingest anomalies: {:?}", e);
+        }
+        tracing::warn!(
+            batch_id = %batch_id,
+            server_id = %server_id,
+            session_id = %session_id,
+            anomalies = ?anomalies.iter().map(|a| a.kind()).collect::<Vec<_>>(),
+            "batch quarantined"
+        );
+    }
 
     // --- Track players (best-effort, async) ---
     // This allows the dashboard to show "active players" as subtle gray dots even without findings.
-    {
-        let db = state.db.clone();
+    // Batches with watchlisted players also get extended retention; client brand / channel
+    // registrations are stored per player-session. Player sessions are stitched after the
+    // players upsert; proxied players also extend their group-wide session.
+    if !quarantined {
+        let track_state = state.clone();
         let track_server_id = server_id.clone();
-        let gz_body = body.to_vec();
-        tokio::spawn(async move {
-            if let Err(e) = extract_and_upsert_server_players(&db, &track_server_id, &gz_body).await
-            {
-                tracing::debug!("server player tracking failed (non-critical): {:?}", e);
+        let track_session_id = session_id.clone();
+        let track_s3_key = s3_key.clone();
+        let gz_body = body.clone();
+        tokio::spawn(
+            async move {
+                match extract_and_upsert_server_players(&track_state.db, &track_server_id, &gz_body)
+                    .await
+                {
+                    Ok(players) => {
+                        if let Some(group_id) = proxy_group_id {
+                            if let Err(e) = proxy_groups::record_presence(
+                                &track_state.db,
+                                group_id,
+                                &track_server_id,
+                                &players,
+                            )
+                            .await
+                            {
+                                tracing::debug!(
+                                    "proxy session tracking failed (non-critical): {:?}",
+                                    e
+                                );
+                            }
+                        }
+                        watchlist::retain_if_watchlisted(
+                            &track_state,
+                            &track_server_id,
+                            batch_id,
+                            &track_s3_key,
+                            &players,
+                        )
+                        .await;
+                    }
+                    Err(e) => {
+                        tracing::debug!("server player tracking failed (non-critical): {:?}", e);
+                    }
+                }
+                if let Err(e) = player_sessions::record_batch(
+                    &track_state.db,
+                    &track_server_id,
+                    &track_session_id,
+                    &gz_body,
+                )
+                .await
+                {
+                    tracing::debug!("player session tracking failed (non-critical): {:?}", e);
+                }
+                if let Err(e) = client_channels::record_batch(
+                    &track_state.db,
+                    &track_server_id,
+                    &track_session_id,
+                    &gz_body,
+                )
+                .await
+                {
+                    tracing::debug!("client channel tracking failed (non-critical): {:?}", e);
+                }
             }
-        });
+            .in_current_span(),
+        );
     }
 
     // --- Dispatch to modules (best-effort, async) ---
-    {
+    if !quarantined && !shed && !sample.is_sampled_out() {
+        let backlog = state.dispatch_backlog.track();
         let dispatch_state = state.clone();
         let dispatch_server_id = server_id.clone();
         let dispatch_session_id = session_id.clone();
         let dispatch_s3_key = s3_key.clone();
-        let dispatch_body = body.to_vec();
-        tokio::spawn(async move {
-            if let Err(e) = module_pipeline::dispatch_batch(
-                dispatch_state,
-                dispatch_server_id,
-                dispatch_session_id,
-                batch_id,
-                dispatch_s3_key,
-                dispatch_body,
-            )
-            .await
-            {
-                tracing::warn!("module dispatch failed: {:?}", e);
+        let dispatch_body = body.clone();
+        tokio::spawn(
+            async move {
+                if let Err(e) = module_pipeline::dispatch_batch(
+                    dispatch_state,
+                    dispatch_server_id,
+                    dispatch_session_id,
+                    batch_id,
+                    dispatch_s3_key,
+                    dispatch_body,
+                )
+                .await
+                {
+                    tracing::warn!("module dispatch failed: {:?}", e);
+                }
+                drop(backlog);
             }
-        });
+            .in_current_span(),
+        );
     }
 
+    let hints = ingest_hints::hints(
+        &Load::current(&state.dispatch_backlog, &state.db),
+        state.max_body_bytes,
+    );
+
+    let pending_actions = pending_actions::exchange(&state.db, &server_id, &headers).await;
+
     tracing::info!(
         batch_id = %batch_id,
         server_id = %server_id,
         session_id = %session_id,
         s3_key = %s3_key,
         bytes = payload_bytes,
+        filtered_records,
+        quarantined,
+        dispatch_shed = shed,
+        sample_rate = ?sample.sample_rate(),
+        sampled_out = sample.is_sampled_out(),
+        backlog = ?hints.backlog,
         "batch ingested"
     );
 
@@ -308,12 +656,30 @@ pub async fn ingest(
                 ok: true,
                 batch_id,
                 s3_key,
+                plugin_update,
+                hints,
+                filtered_records,
+                pending_actions,
             })
             .unwrap(),
         ),
     ))
 }
 
+/// Map a failed body read. The route's body limit is `MAX_BODY_BYTES`; hitting it reports the
+/// limit (HTTP 413 `payload_too_large`) instead of axum's plain-text rejection.
+pub fn body_rejection(rejection: BytesRejection, headers: &HeaderMap, max: usize) -> ApiError {
+    if rejection.status() != StatusCode::PAYLOAD_TOO_LARGE {
+        return ApiError::BadRequest(rejection.body_text());
+    }
+    let size = headers
+        .get(axum::http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    ApiError::PayloadTooLarge { size, max }
+}
+
 /// Upsert a server record (update last_seen_at if exists).
 async fn upsert_server(
     db: &PgPool,
@@ -405,7 +771,7 @@ async fn ensure_builtin_modules(db: &PgPool, server_id: &str) -> Result<(), sqlx
                 .push_bind(m.name)
                 .push_bind(crate::builtin_modules::default_base_url(m.default_port))
                 .push_bind(true)
-                .push_bind("raw_ndjson_gz")
+                .push_bind(m.transform)
                 .push_bind(now)
                 .push_bind(now);
         });
@@ -418,6 +784,7 @@ async fn ensure_builtin_modules(db: &PgPool, server_id: &str) -> Result<(), sqlx
 }
 
 /// Insert a batch_index row pointing to the S3 object.
+#[allow(clippy::too_many_arguments)]
 async fn insert_batch_index(
     db: &PgPool,
     batch_id: &Uuid,
@@ -425,13 +792,24 @@ async fn insert_batch_index(
     session_id: &str,
     s3_key: &str,
     payload_bytes: i32,
+    seq: Option<(i64, SeqStatus)>,
+    clock: BatchClock,
+    payload_sha256: Option<&str>,
+    quarantined: bool,
+    shed: bool,
+    sample: Decision,
+    meta: &BatchMeta,
 ) -> Result<(), sqlx::Error> {
     sqlx::query(
         r#"
         insert into public.batch_index
-            (id, server_id, session_id, s3_key, payload_bytes)
+            (id, server_id, session_id, s3_key, payload_bytes, batch_seq, seq_status, seq_missing,
+             payload_sha256, quarantined, received_at, clock_offset_ms, clock_skew_ms,
+             dispatch_status, meta_version, plugin_version, protocol_version, mc_version, world,
+             tick_rate, player_count, sample_rate)
         values
-            ($1, $2, $3, $4, $5)
+            ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19,
+             $20, $21, $22)
         "#,
     )
     .bind(batch_id)
@@ -439,31 +817,93 @@ async fn insert_batch_index(
     .bind(session_id)
     .bind(s3_key)
     .bind(payload_bytes)
+    .bind(seq.map(|(seq, _)| seq))
+    .bind(seq.map(|(_, status)| status.as_str()))
+    .bind(
+        seq.and_then(|(_, status)| status.missing())
+            .map(|m| i32::try_from(m).unwrap_or(i32::MAX)),
+    )
+    .bind(payload_sha256)
+    .bind(quarantined)
+    .bind(clock.received_at)
+    .bind(clock.offset_ms)
+    .bind(clock.skew_ms)
+    .bind(if sample.is_sampled_out() {
+        Some(sampling::SAMPLED_OUT)
+    } else {
+        shed.then_some(dispatch_shedding::STORED_NOT_DISPATCHED)
+    })
+    .bind(meta.meta_version as i32)
+    .bind(meta.plugin_version.as_deref())
+    .bind(meta.protocol_version)
+    .bind(meta.mc_version.as_deref())
+    .bind(meta.world.as_deref())
+    .bind(meta.tick_rate)
+    .bind(meta.player_count)
+    .bind(sample.sample_rate())
     .execute(db)
     .await?;
     Ok(())
 }
 
-/// Minimal packet record for player extraction (only uuid and name)
+/// Minimal packet record for player extraction (uuid, name, identity signals, world context)
 #[derive(Debug, Deserialize)]
 struct PacketRecordPartial {
     #[serde(default)]
     uuid: Option<String>,
     #[serde(default)]
     name: Option<String>,
+    #[serde(default)]
+    ip_hash: Option<String>,
+    #[serde(default)]
+    fingerprint: Option<String>,
+    #[serde(default)]
+    client_brand: Option<String>,
+    #[serde(default)]
+    pkt: Option<String>,
+    #[serde(default)]
+    world: Option<String>,
+    #[serde(default)]
+    gamemode: Option<String>,
+    #[serde(default)]
+    fields: Option<PlayerStateFieldsPartial>,
 }
 
+/// World / gamemode / exemption-permission context carried by `PLAYER_STATE` records.
+#[derive(Debug, Deserialize)]
+struct PlayerStateFieldsPartial {
+    #[serde(default)]
+    world: Option<String>,
+    #[serde(default)]
+    gamemode: Option<String>,
+    #[serde(default)]
+    permissions: Option<Vec<String>>,
+}
+
+/// Latest context seen for a player in a batch.
+#[derive(Debug, Default, Clone)]
+struct PlayerContextPartial {
+    world: Option<String>,
+    gamemode: Option<String>,
+    permissions: Option<Vec<String>>,
+}
+
+/// Upsert the batch's players and their identity signals; returns their UUIDs.
 async fn extract_and_upsert_server_players(
     db: &PgPool,
     server_id: &str,
     gz_body: &[u8],
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Vec<Uuid>> {
     const MAX_LINES: usize = 2000;
 
     let decoder = GzDecoder::new(gz_body);
     let reader = BufReader::new(decoder);
 
     let mut seen: HashSet<(Uuid, String)> = HashSet::new();
+    let mut identifiers: HashSet<(Uuid, &'static str, String)> = HashSet::new();
+    let mut context: HashMap<Uuid, PlayerContextPartial> = HashMap::new();
+    let mut client_types: HashMap<Uuid, ClientType> = HashMap::new();
+    let mut client_protocols: HashMap<Uuid, i32> = HashMap::new();
 
     for (i, line_result) in reader.lines().enumerate() {
         if i >= MAX_LINES {
@@ -476,6 +916,14 @@ async fn extract_and_upsert_server_players(
         if line.is_empty() {
             continue;
         }
+        if i == 0 {
+            // Batch metadata: only the per-player client types and protocols are of interest here.
+            if let Ok(meta) = serde_json::from_str::<serde_json::Value>(&line) {
+                client_types = transforms::client_types(&meta);
+                client_protocols = transforms::client_protocols(&meta);
+            }
+            continue;
+        }
 
         let record: PacketRecordPartial = match serde_json::from_str(&line) {
             Ok(r) => r,
@@ -494,13 +942,50 @@ async fn extract_and_upsert_server_players(
             continue;
         };
 
+        for (kind, value) in [
+            (identity::IP_HASH, record.ip_hash),
+            (identity::FINGERPRINT, record.fingerprint),
+            (identity::CLIENT_BRAND, record.client_brand),
+        ] {
+            if let Some(value) = value.and_then(|v| identity::normalize(kind, &v)) {
+                identifiers.insert((uuid, kind, value));
+            }
+        }
+
+        let is_state = record
+            .pkt
+            .as_deref()
+            .is_some_and(|p| p.eq_ignore_ascii_case("PLAYER_STATE"));
+        let (state_world, state_gamemode, permissions) = match record.fields {
+            Some(f) if is_state => (f.world, f.gamemode, f.permissions),
+            _ => (None, None, None),
+        };
+        let world = record.world.or(state_world);
+        let gamemode = record.gamemode.or(state_gamemode);
+        if world.is_some() || gamemode.is_some() || permissions.is_some() {
+            let entry = context.entry(uuid).or_default();
+            if world.is_some() {
+                entry.world = world;
+            }
+            if gamemode.is_some() {
+                entry.gamemode = gamemode.map(|g| g.to_ascii_uppercase());
+            }
+            if permissions.is_some() {
+                entry.permissions = permissions;
+            }
+        }
+
         seen.insert((uuid, name));
     }
 
     if seen.is_empty() {
-        return Ok(());
+        return Ok(Vec::new());
     }
 
+    let mut players: Vec<Uuid> = seen.iter().map(|(uuid, _)| *uuid).collect();
+    players.sort();
+    players.dedup();
+
     for (uuid, username) in seen {
         // Upsert into global players
         let _ = sqlx::query(
@@ -517,22 +1002,40 @@ async fn extract_and_upsert_server_players(
         .execute(db)
         .await;
 
-        // Upsert per-server last seen
+        // Upsert per-server last seen (and last known world / gamemode / permissions / client /
+        // protocol)
+        let ctx = context.get(&uuid).cloned().unwrap_or_default();
         let _ = sqlx::query(
             r#"
-            insert into public.server_players (server_id, player_uuid, player_name, first_seen_at, last_seen_at)
-            values ($1, $2, $3, now(), now())
+            insert into public.server_players
+                (server_id, player_uuid, player_name, world, gamemode, permissions, client_type, client_protocol, first_seen_at, last_seen_at)
+            values ($1, $2, $3, $4, $5, coalesce($6, '{}'), $7, $8, now(), now())
             on conflict (server_id, player_uuid) do update set
                 player_name = excluded.player_name,
+                world = coalesce(excluded.world, server_players.world),
+                gamemode = coalesce(excluded.gamemode, server_players.gamemode),
+                permissions = coalesce($6, server_players.permissions),
+                client_type = coalesce(excluded.client_type, server_players.client_type),
+                client_protocol = coalesce(excluded.client_protocol, server_players.client_protocol),
                 last_seen_at = now()
             "#,
         )
         .bind(server_id)
         .bind(uuid)
         .bind(&username)
+        .bind(ctx.world.as_deref())
+        .bind(ctx.gamemode.as_deref())
+        .bind(ctx.permissions.as_deref())
+        .bind(client_types.get(&uuid).map(|t| t.as_str()))
+        .bind(client_protocols.get(&uuid).copied())
         .execute(db)
         .await;
     }
 
-    Ok(())
+    // After the players upsert (player_identifiers references players).
+    if !identifiers.is_empty() {
+        identity::record(db, server_id, &identifiers).await?;
+    }
+
+    Ok(players)
 }
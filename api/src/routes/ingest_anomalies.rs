@@ -0,0 +1,60 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::ingest_anomalies::{self, IngestAnomaly};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct IngestAnomaliesQuery {
+    /// Defaults to 50, at most 200.
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IngestAnomaliesResponse {
+    pub ok: bool,
+    pub anomalies: Vec<IngestAnomaly>,
+}
+
+/// GET /dashboard/:server_id/ingest-anomalies
+///
+/// Why recent batches were quarantined (stored but not analyzed), newest first.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/ingest-anomalies",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        IngestAnomaliesQuery,
+    ),
+    responses(
+        (status = 200, body = IngestAnomaliesResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_ingest_anomalies(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Query(params): Query<IngestAnomaliesQuery>,
+) -> Result<Json<IngestAnomaliesResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+
+    let anomalies = ingest_anomalies::list(&state.db, &server_id, limit)
+        .await
+        .map_err(|e| {
+            tracing::error!("ingest anomalies query failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    Ok(Json(IngestAnomaliesResponse {
+        ok: true,
+        anomalies,
+    }))
+}
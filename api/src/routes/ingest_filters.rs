@@ -0,0 +1,119 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::ingest_filters::{self, IngestFilters};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IngestFiltersResponse {
+    pub ok: bool,
+    pub filters: IngestFilters,
+    /// When the filters were last saved; null while the server has none.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// GET /dashboard/:server_id/ingest-filters
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/ingest-filters",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = IngestFiltersResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_ingest_filters(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<IngestFiltersResponse>, ApiError> {
+    let (filters, updated_at) = ingest_filters::load(&state.db, server_id.trim())
+        .await
+        .map_err(|e| {
+            tracing::error!("ingest filters lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    Ok(Json(IngestFiltersResponse {
+        ok: true,
+        filters,
+        updated_at,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UpdateIngestFiltersRequest {
+    /// Full rule set; an empty `rules` list stops filtering.
+    pub filters: IngestFilters,
+    pub updated_by: Option<String>,
+}
+
+/// POST /dashboard/:server_id/ingest-filters
+///
+/// Replace the server's ingest filters. They apply from the server's next batch; stored
+/// batches are left as they are.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/ingest-filters",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = UpdateIngestFiltersRequest,
+    responses(
+        (status = 200, body = IngestFiltersResponse),
+        (status = 400, description = "Invalid rule, or a rule dropping a packet the built-in checks need", body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn update_ingest_filters(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<UpdateIngestFiltersRequest>,
+) -> Result<Json<IngestFiltersResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let filters = req.filters.validate().map_err(ApiError::BadRequest)?;
+
+    let exists: Option<String> = sqlx::query_scalar("select id from public.servers where id = $1")
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("ingest filters server lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    let updated_by = req
+        .updated_by
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let updated_at = ingest_filters::save(&state.db, &server_id, &filters, updated_by)
+        .await
+        .map_err(|e| {
+            tracing::error!("ingest filters save failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    tracing::info!(server_id = %server_id, rules = filters.rules.len(), "ingest filters updated");
+    Ok(Json(IngestFiltersResponse {
+        ok: true,
+        filters,
+        updated_at: Some(updated_at),
+    }))
+}
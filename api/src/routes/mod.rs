@@ -1,9 +1,48 @@
+pub mod admin;
 pub mod auth;
+pub mod batches;
 pub mod callbacks;
+pub mod capture_config;
+pub mod cases;
+pub mod catalog;
+pub mod chaos;
+pub mod check_docs;
+pub mod check_thresholds;
+pub mod confidence_filter;
 pub mod dashboard;
+pub mod dispatch_stats;
+pub mod docs;
+pub mod evidence_bundles;
+pub mod exemptions;
+pub mod finding_latency;
+pub mod findings_import;
+pub mod graphql;
 pub mod handshake;
 pub mod health;
 pub mod heartbeat;
 pub mod ingest;
+pub mod ingest_anomalies;
+pub mod ingest_filters;
+pub mod module_canary;
+pub mod module_maintenance;
+pub mod module_usage;
 pub mod modules;
+pub mod notes;
 pub mod observations;
+pub mod organizations;
+pub mod player_path;
+pub mod plugin_findings;
+pub mod privacy;
+pub mod proxy_groups;
+pub mod punishments;
+pub mod reports;
+pub mod reputation;
+pub mod sampling;
+pub mod server_deletion;
+pub mod server_export;
+pub mod sessions;
+pub mod severity_overrides;
+pub mod shadow;
+pub mod status;
+pub mod wasm_modules;
+pub mod watchlist;
@@ -0,0 +1,117 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::module_canary::{self, VariantDay, VariantTotals};
+use crate::{error::ApiError, AppState};
+
+pub const DEFAULT_DAYS: i32 = 7;
+pub const MAX_DAYS: i32 = 90;
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CanaryReportQuery {
+    /// Days back from today (default 7, at most 90).
+    pub days: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CanaryReportResponse {
+    pub ok: bool,
+    pub module_id: Uuid,
+    pub canary_base_url: Option<String>,
+    pub canary_percent: i32,
+    /// Oldest first; today is live.
+    pub days: Vec<VariantDay>,
+    pub stable: VariantTotals,
+    pub canary: VariantTotals,
+}
+
+/// GET /dashboard/:server_id/modules/:module_id/canary
+///
+/// Batches and findings of a module's stable and canary endpoints per day, to compare finding
+/// rates before promoting the canary.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/modules/{module_id}/canary",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("module_id" = Uuid, Path, description = "Module id"),
+        CanaryReportQuery,
+    ),
+    responses(
+        (status = 200, body = CanaryReportResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_canary_report(
+    State(state): State<AppState>,
+    Path((server_id, module_id)): Path<(String, Uuid)>,
+    Query(params): Query<CanaryReportQuery>,
+) -> Result<Json<CanaryReportResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let days = params.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+
+    let module: Option<(Option<String>, i32)> = sqlx::query_as(
+        "select canary_base_url, canary_percent from public.server_modules where id = $1 and server_id = $2",
+    )
+    .bind(module_id)
+    .bind(&server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("canary report module lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let Some((canary_base_url, canary_percent)) = module else {
+        return Err(ApiError::NotFound(format!(
+            "module {} not found",
+            module_id
+        )));
+    };
+
+    let rows: Vec<(NaiveDate, String, i64, i64)> = sqlx::query_as(
+        r#"
+        select day, variant, batches, findings
+        from public.module_variant_stats
+        where module_id = $1 and day > current_date - $2
+        order by day asc, variant asc
+        "#,
+    )
+    .bind(module_id)
+    .bind(days)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("canary report query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let days: Vec<VariantDay> = rows
+        .into_iter()
+        .map(|(day, variant, batches, findings)| VariantDay {
+            day,
+            variant,
+            batches,
+            findings,
+        })
+        .collect();
+
+    Ok(Json(CanaryReportResponse {
+        ok: true,
+        module_id,
+        canary_base_url,
+        canary_percent,
+        stable: module_canary::totals(&days, module_canary::STABLE),
+        canary: module_canary::totals(&days, module_canary::CANARY),
+        days,
+    }))
+}
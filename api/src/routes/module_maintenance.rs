@@ -0,0 +1,245 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::module_maintenance::{self, MaintenanceWindow, Suspension};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModuleMaintenanceResponse {
+    pub ok: bool,
+    pub module_id: Uuid,
+    pub paused_until: Option<DateTime<Utc>>,
+    pub windows: Vec<MaintenanceWindow>,
+    /// Set while the module gets no batches.
+    pub suspended: Option<Suspension>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PauseModuleRequest {
+    /// How long to pause (1 second to 7 days).
+    pub duration_seconds: i64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MaintenanceWindowsRequest {
+    /// Replaces the module's windows; empty removes them.
+    pub windows: Vec<MaintenanceWindow>,
+}
+
+/// The module's pause, windows and current suspension.
+async fn load(
+    state: &AppState,
+    server_id: &str,
+    module_id: Uuid,
+) -> Result<ModuleMaintenanceResponse, ApiError> {
+    let res = async {
+        let row: Option<(Option<DateTime<Utc>>,)> = sqlx::query_as(
+            "select paused_until from public.server_modules where id = $1 and server_id = $2",
+        )
+        .bind(module_id)
+        .bind(server_id)
+        .fetch_optional(&state.db)
+        .await?;
+        let Some((paused_until,)) = row else {
+            return Ok(None);
+        };
+        let windows = module_maintenance::load_windows(&state.db, &[module_id])
+            .await?
+            .remove(&module_id)
+            .unwrap_or_default();
+        Ok::<_, sqlx::Error>(Some((paused_until, windows)))
+    }
+    .await
+    .map_err(|e| {
+        tracing::error!("module maintenance lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let Some((paused_until, windows)) = res else {
+        return Err(ApiError::NotFound(format!(
+            "module {} not found",
+            module_id
+        )));
+    };
+
+    let now = Utc::now();
+    Ok(ModuleMaintenanceResponse {
+        ok: true,
+        module_id,
+        paused_until: paused_until.filter(|until| *until > now),
+        suspended: module_maintenance::suspension(paused_until, &windows, now),
+        windows,
+    })
+}
+
+/// Set (or clear) the module's `paused_until`.
+async fn set_paused_until(
+    state: &AppState,
+    server_id: &str,
+    module_id: Uuid,
+    paused_until: Option<DateTime<Utc>>,
+) -> Result<(), ApiError> {
+    let updated = sqlx::query(
+        "update public.server_modules set paused_until = $3, updated_at = now() where id = $1 and server_id = $2",
+    )
+    .bind(module_id)
+    .bind(server_id)
+    .bind(paused_until)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("module pause update failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "module {} not found",
+            module_id
+        )));
+    }
+    Ok(())
+}
+
+/// GET /dashboard/:server_id/modules/:module_id/maintenance
+///
+/// The module's pause, maintenance windows and whether it is suspended right now.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/modules/{module_id}/maintenance",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("module_id" = Uuid, Path, description = "Module id"),
+    ),
+    responses(
+        (status = 200, body = ModuleMaintenanceResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_module_maintenance(
+    State(state): State<AppState>,
+    Path((server_id, module_id)): Path<(String, Uuid)>,
+) -> Result<Json<ModuleMaintenanceResponse>, ApiError> {
+    Ok(Json(load(&state, server_id.trim(), module_id).await?))
+}
+
+/// PUT /dashboard/:server_id/modules/:module_id/maintenance
+///
+/// Replace the module's recurring maintenance windows.
+#[utoipa::path(
+    put,
+    path = "/dashboard/{server_id}/modules/{module_id}/maintenance",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("module_id" = Uuid, Path, description = "Module id"),
+    ),
+    request_body = MaintenanceWindowsRequest,
+    responses(
+        (status = 200, body = ModuleMaintenanceResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn put_module_maintenance(
+    State(state): State<AppState>,
+    Path((server_id, module_id)): Path<(String, Uuid)>,
+    Json(req): Json<MaintenanceWindowsRequest>,
+) -> Result<Json<ModuleMaintenanceResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    module_maintenance::validate(&req.windows).map_err(ApiError::BadRequest)?;
+
+    // Checks the module belongs to the server before touching its windows.
+    load(&state, &server_id, module_id).await?;
+    module_maintenance::replace_windows(&state.db, module_id, &req.windows)
+        .await
+        .map_err(|e| {
+            tracing::error!("maintenance windows update failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    tracing::info!(server_id = %server_id, module_id = %module_id, windows = req.windows.len(), "module maintenance windows changed");
+
+    Ok(Json(load(&state, &server_id, module_id).await?))
+}
+
+/// POST /dashboard/:server_id/modules/:module_id/pause
+///
+/// Stop dispatching to the module for `duration_seconds` (e.g. during a restart) without
+/// counting its failures. Pausing again replaces the previous pause.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/modules/{module_id}/pause",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("module_id" = Uuid, Path, description = "Module id"),
+    ),
+    request_body = PauseModuleRequest,
+    responses(
+        (status = 200, body = ModuleMaintenanceResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn pause_module(
+    State(state): State<AppState>,
+    Path((server_id, module_id)): Path<(String, Uuid)>,
+    Json(req): Json<PauseModuleRequest>,
+) -> Result<Json<ModuleMaintenanceResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    if !(1..=module_maintenance::MAX_PAUSE_SECONDS).contains(&req.duration_seconds) {
+        return Err(ApiError::BadRequest(format!(
+            "duration_seconds must be 1-{}",
+            module_maintenance::MAX_PAUSE_SECONDS
+        )));
+    }
+
+    let until = Utc::now() + chrono::Duration::seconds(req.duration_seconds);
+    set_paused_until(&state, &server_id, module_id, Some(until)).await?;
+
+    tracing::info!(server_id = %server_id, module_id = %module_id, until = %until, "module paused");
+
+    Ok(Json(load(&state, &server_id, module_id).await?))
+}
+
+/// POST /dashboard/:server_id/modules/:module_id/resume
+///
+/// End the module's pause early. Maintenance windows still apply.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/modules/{module_id}/resume",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("module_id" = Uuid, Path, description = "Module id"),
+    ),
+    responses(
+        (status = 200, body = ModuleMaintenanceResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn resume_module(
+    State(state): State<AppState>,
+    Path((server_id, module_id)): Path<(String, Uuid)>,
+) -> Result<Json<ModuleMaintenanceResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    set_paused_until(&state, &server_id, module_id, None).await?;
+
+    tracing::info!(server_id = %server_id, module_id = %module_id, "module resumed");
+
+    Ok(Json(load(&state, &server_id, module_id).await?))
+}
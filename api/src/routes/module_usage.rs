@@ -0,0 +1,108 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::module_usage::{self, ModuleUsage, UsageDay};
+use crate::{error::ApiError, AppState};
+
+pub const DEFAULT_DAYS: i32 = 7;
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ModuleUsageQuery {
+    /// Days back from today (default 7, at most 90).
+    pub days: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ModuleUsageResponse {
+    pub ok: bool,
+    pub days: i32,
+    /// Most bandwidth first.
+    pub modules: Vec<ModuleUsage>,
+    /// Per module and day, oldest first; today is live.
+    pub daily: Vec<UsageDay>,
+}
+
+/// GET /dashboard/:server_id/modules/usage
+///
+/// Payload bytes sent to and transform time spent on each of the server's modules, so operators
+/// can see which modules drive bandwidth and CPU cost.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/modules/usage",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ModuleUsageQuery,
+    ),
+    responses(
+        (status = 200, body = ModuleUsageResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_module_usage(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Query(params): Query<ModuleUsageQuery>,
+) -> Result<Json<ModuleUsageResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let days = params
+        .days
+        .unwrap_or(DEFAULT_DAYS)
+        .clamp(1, module_usage::RETENTION_DAYS);
+
+    let modules: Vec<(Uuid, String)> = sqlx::query_as(
+        "select id, name from public.server_modules where server_id = $1 order by name",
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("module usage module lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let rows: Vec<(Uuid, NaiveDate, i64, i64, i64)> = sqlx::query_as(
+        r#"
+        select module_id, day, dispatches, payload_bytes, transform_us
+        from public.module_usage_daily
+        where server_id = $1 and day > current_date - $2
+        order by day asc, module_id asc
+        "#,
+    )
+    .bind(&server_id)
+    .bind(days)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("module usage query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let daily: Vec<UsageDay> = rows
+        .into_iter()
+        .map(
+            |(module_id, day, dispatches, payload_bytes, transform_us)| UsageDay {
+                module_id,
+                day,
+                dispatches,
+                payload_bytes,
+                transform_us,
+            },
+        )
+        .collect();
+
+    Ok(Json(ModuleUsageResponse {
+        ok: true,
+        days,
+        modules: module_usage::summarize(&modules, &daily),
+        daily,
+    }))
+}
@@ -5,38 +5,67 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::{error::ApiError, AppState};
+use crate::dispatch_encoding::{self, ContentEncoding};
+use crate::{
+    async_dispatch, auth, error::ApiError, module_canary, module_failover, shadow, AppState,
+};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpsertModuleRequest {
     pub name: String,
     pub base_url: String,
+    /// Endpoints tried in order when `base_url` fails (http(s) only, at most 4). Omit to keep the
+    /// current ones; `[]` removes them.
+    pub backup_base_urls: Option<Vec<String>>,
+    /// New module version that gets `canary_percent` of the batches (http(s) only). Omit to keep
+    /// the current one; `""` removes it.
+    pub canary_base_url: Option<String>,
+    /// Share of batches sent to the canary, 0 to 100. Omit to keep the current one (0 by default).
+    pub canary_percent: Option<i32>,
+    /// `active` or `shadow` (findings kept aside, see `shadow`). Omit to keep the current one.
+    pub mode: Option<String>,
     pub enabled: Option<bool>,
     /// e.g. "raw_ndjson_gz" | "movement_events_v1_ndjson_gz"
     pub transform: Option<String>,
+    /// Encodings the module accepts for dispatched batches, as an `Accept-Encoding` value
+    /// (e.g. "zstd, gzip"). Omit to keep the negotiated one (gzip by default).
+    pub accept_encoding: Option<String>,
+    /// Deadline for async processing (202 with a processing id), 1 to 86400 seconds.
+    /// Omit to keep the current one (300 by default).
+    pub processing_deadline_seconds: Option<i32>,
 }
 
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Serialize, FromRow, ToSchema)]
 pub struct ServerModule {
     pub id: Uuid,
     pub server_id: String,
     pub name: String,
     pub base_url: String,
+    pub backup_base_urls: Vec<String>,
+    pub canary_base_url: Option<String>,
+    /// Share of batches sent to the canary (0-100).
+    pub canary_percent: i32,
+    /// active | shadow
+    pub mode: String,
     pub enabled: bool,
     pub transform: String,
     pub last_healthcheck_ok: Option<bool>,
     pub last_error: Option<String>,
+    /// Encoding batches are dispatched with: gzip | zstd | identity.
+    pub content_encoding: String,
+    /// How long an async dispatch (202 with a processing id) may take before it times out.
+    pub processing_deadline_seconds: i32,
 }
 
 fn require_ingest_auth(state: &AppState, headers: &HeaderMap) -> Result<(), ApiError> {
-    let auth = headers
-        .get("authorization")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
-    let expected = format!("Bearer {}", state.ingest_token);
-    if state.ingest_token.is_empty() || auth != expected {
+    if state.ingest_token.is_empty() {
+        return Err(ApiError::Unauthorized);
+    }
+    let provided = auth::parse_bearer_token(headers).ok_or(ApiError::Unauthorized)?;
+    if !auth::constant_time_eq(&provided, &state.ingest_token) {
         return Err(ApiError::Unauthorized);
     }
     Ok(())
@@ -45,6 +74,21 @@ fn require_ingest_auth(state: &AppState, headers: &HeaderMap) -> Result<(), ApiE
 /// POST /servers/:server_id/modules
 ///
 /// Register or update a module subscription for a server.
+#[utoipa::path(
+    post,
+    path = "/servers/{server_id}/modules",
+    tag = "modules",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = UpsertModuleRequest,
+    responses(
+        (status = 200, body = ServerModule),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("ingest_token" = [])),
+)]
 pub async fn upsert_module(
     State(state): State<AppState>,
     Path(server_id): Path<String>,
@@ -79,29 +123,99 @@ pub async fn upsert_module(
         return Err(ApiError::BadRequest("base_url is required".to_string()));
     }
 
+    let backup_base_urls = req
+        .backup_base_urls
+        .as_deref()
+        .map(|urls| module_failover::normalize_backups(&req.base_url, urls))
+        .transpose()
+        .map_err(ApiError::BadRequest)?;
+
+    let canary_base_url = req
+        .canary_base_url
+        .as_deref()
+        .map(module_canary::normalize_url)
+        .transpose()
+        .map_err(ApiError::BadRequest)?;
+    if req.canary_percent.is_some_and(|p| !(0..=100).contains(&p)) {
+        return Err(ApiError::BadRequest(
+            "canary_percent must be between 0 and 100".to_string(),
+        ));
+    }
+
+    let mode = req
+        .mode
+        .as_deref()
+        .map(|m| {
+            shadow::parse_mode(m)
+                .ok_or_else(|| ApiError::BadRequest("mode must be active or shadow".to_string()))
+        })
+        .transpose()?;
+
     let enabled = req.enabled.unwrap_or(true);
     let transform = req.transform.unwrap_or_else(|| "raw_ndjson_gz".to_string());
+    let content_encoding = match req.accept_encoding.as_deref() {
+        Some(accept) => {
+            let accepted = dispatch_encoding::parse_accepted(accept);
+            if accepted.is_empty() {
+                return Err(ApiError::BadRequest(format!(
+                    "accept_encoding must list one of {}",
+                    ContentEncoding::ALL.join(", ")
+                )));
+            }
+            Some(dispatch_encoding::negotiate(req.base_url.trim(), &accepted).as_str())
+        }
+        None => None,
+    };
+
+    if let Some(secs) = req.processing_deadline_seconds {
+        if !(1..=async_dispatch::MAX_DEADLINE_SECONDS).contains(&secs) {
+            return Err(ApiError::BadRequest(format!(
+                "processing_deadline_seconds must be between 1 and {}",
+                async_dispatch::MAX_DEADLINE_SECONDS
+            )));
+        }
+    }
 
     let rec = sqlx::query_as::<_, ServerModule>(
         r#"
         insert into public.server_modules
-            (server_id, name, base_url, enabled, transform, updated_at)
+            (server_id, name, base_url, enabled, transform, content_encoding,
+             processing_deadline_seconds, backup_base_urls, canary_base_url, canary_percent,
+             mode, updated_at)
         values
-            ($1, $2, $3, $4, $5, now())
+            ($1, $2, $3, $4, $5, coalesce($6, 'gzip'), coalesce($7, $8), coalesce($9, '{}'), $11,
+             coalesce($12, 0), coalesce($13, 'active'), now())
         on conflict (server_id, name) do update set
             base_url = excluded.base_url,
+            backup_base_urls = case
+                when excluded.base_url ~ '^https?://'
+                    then coalesce($9, server_modules.backup_base_urls)
+                else '{}'
+            end,
+            canary_base_url = case when $10 then $11 else server_modules.canary_base_url end,
+            canary_percent = coalesce($12, server_modules.canary_percent),
+            mode = coalesce($13, server_modules.mode),
             enabled = excluded.enabled,
             transform = excluded.transform,
+            content_encoding = coalesce($6, server_modules.content_encoding),
+            processing_deadline_seconds =
+                coalesce($7, server_modules.processing_deadline_seconds),
             updated_at = now()
         returning
             id,
             server_id,
             name,
             base_url,
+            backup_base_urls,
+            canary_base_url,
+            canary_percent,
+            mode,
             enabled,
             transform,
             last_healthcheck_ok,
-            last_error
+            last_error,
+            content_encoding,
+            processing_deadline_seconds
         "#,
     )
     .bind(server_id)
@@ -109,17 +223,41 @@ pub async fn upsert_module(
     .bind(req.base_url.trim())
     .bind(enabled)
     .bind(transform)
+    .bind(content_encoding)
+    .bind(req.processing_deadline_seconds)
+    .bind(async_dispatch::DEFAULT_DEADLINE_SECONDS)
+    .bind(backup_base_urls)
+    .bind(canary_base_url.is_some())
+    .bind(canary_base_url.flatten())
+    .bind(req.canary_percent)
+    .bind(mode)
     .fetch_one(&state.db)
     .await
     .map_err(|e| {
         tracing::error!("upsert_module failed: {:?}", e);
         ApiError::Internal
     })?;
+    if let Err(e) = module_failover::prune(&state.db, rec.id).await {
+        tracing::warn!(module = %rec.name, "endpoint health cleanup failed: {:?}", e);
+    }
 
     Ok(Json(rec))
 }
 
 /// GET /servers/:server_id/modules
+#[utoipa::path(
+    get,
+    path = "/servers/{server_id}/modules",
+    tag = "modules",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = Vec<ServerModule>),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("ingest_token" = [])),
+)]
 pub async fn list_modules(
     State(state): State<AppState>,
     Path(server_id): Path<String>,
@@ -134,10 +272,16 @@ pub async fn list_modules(
             server_id,
             name,
             base_url,
+            backup_base_urls,
+            canary_base_url,
+            canary_percent,
+            mode,
             enabled,
             transform,
             last_healthcheck_ok,
-            last_error
+            last_error,
+            content_encoding,
+            processing_deadline_seconds
         from public.server_modules
         where server_id = $1
         order by name asc
@@ -0,0 +1,189 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::notes::{self, ModeratorNote};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddNoteRequest {
+    /// Staff member writing the note.
+    pub author: String,
+    /// At most 4000 characters.
+    pub body: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NoteResponse {
+    pub ok: bool,
+    pub note: ModeratorNote,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NotesResponse {
+    pub ok: bool,
+    /// Oldest first.
+    pub notes: Vec<ModeratorNote>,
+}
+
+/// GET /dashboard/:server_id/players/:player_uuid/notes
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/players/{player_uuid}/notes",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("player_uuid" = Uuid, Path, description = "Player UUID"),
+    ),
+    responses(
+        (status = 200, body = NotesResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_player_notes(
+    State(state): State<AppState>,
+    Path((server_id, player_uuid)): Path<(String, Uuid)>,
+) -> Result<Json<NotesResponse>, ApiError> {
+    let notes = notes::player_notes(&state.db, server_id.trim(), player_uuid)
+        .await
+        .map_err(|e| {
+            tracing::error!("list player notes failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    Ok(Json(NotesResponse { ok: true, notes }))
+}
+
+/// POST /dashboard/:server_id/players/:player_uuid/notes
+///
+/// Leave a note on a player for the rest of the staff team.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/players/{player_uuid}/notes",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("player_uuid" = Uuid, Path, description = "Player UUID"),
+    ),
+    request_body = AddNoteRequest,
+    responses(
+        (status = 200, body = NoteResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn add_player_note(
+    State(state): State<AppState>,
+    Path((server_id, player_uuid)): Path<(String, Uuid)>,
+    Json(req): Json<AddNoteRequest>,
+) -> Result<Json<NoteResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let (author, body) = notes::validate(&req.author, &req.body).map_err(ApiError::BadRequest)?;
+
+    let exists: Option<String> = sqlx::query_scalar("select id from public.servers where id = $1")
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("note server lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    let note = notes::add_player_note(&state.db, &server_id, player_uuid, &author, &body)
+        .await
+        .map_err(|e| {
+            tracing::error!("add player note failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    Ok(Json(NoteResponse { ok: true, note }))
+}
+
+/// POST /dashboard/:server_id/findings/:finding_id/comments
+///
+/// Comment on a finding; comments show in the finding detail.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/findings/{finding_id}/comments",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("finding_id" = Uuid, Path, description = "Finding id"),
+    ),
+    request_body = AddNoteRequest,
+    responses(
+        (status = 200, body = NoteResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn add_finding_comment(
+    State(state): State<AppState>,
+    Path((server_id, finding_id)): Path<(String, Uuid)>,
+    Json(req): Json<AddNoteRequest>,
+) -> Result<Json<NoteResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let (author, body) = notes::validate(&req.author, &req.body).map_err(ApiError::BadRequest)?;
+
+    let note = notes::add_finding_comment(&state.db, &server_id, finding_id, &author, &body)
+        .await
+        .map_err(|e| {
+            tracing::error!("add finding comment failed: {:?}", e);
+            ApiError::Internal
+        })?
+        .ok_or_else(|| ApiError::NotFound(format!("finding {} not found", finding_id)))?;
+    Ok(Json(NoteResponse { ok: true, note }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeleteNoteResponse {
+    pub ok: bool,
+    pub note_id: Uuid,
+}
+
+/// DELETE /dashboard/:server_id/notes/:note_id
+///
+/// Delete a player note or finding comment.
+#[utoipa::path(
+    delete,
+    path = "/dashboard/{server_id}/notes/{note_id}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("note_id" = Uuid, Path, description = "Note id"),
+    ),
+    responses(
+        (status = 200, body = DeleteNoteResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn delete_note(
+    State(state): State<AppState>,
+    Path((server_id, note_id)): Path<(String, Uuid)>,
+) -> Result<Json<DeleteNoteResponse>, ApiError> {
+    let deleted = notes::delete(&state.db, server_id.trim(), note_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("delete note failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if !deleted {
+        return Err(ApiError::NotFound(format!("note {} not found", note_id)));
+    }
+    Ok(Json(DeleteNoteResponse { ok: true, note_id }))
+}
@@ -1,15 +1,16 @@
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
     Json,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::{auth, error::ApiError, AppState};
+use crate::{auth, detector_metrics, error::ApiError, AppState};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateObservation {
     pub observation_type: String,
     pub player_uuid: Uuid,
@@ -23,7 +24,7 @@ pub struct CreateObservation {
     pub session_id: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct CreateObservationResponse {
     pub ok: bool,
     pub observation_id: Uuid,
@@ -33,71 +34,27 @@ pub struct CreateObservationResponse {
 ///
 /// Creates a new cheat observation (recording) from the plugin.
 /// Authenticated via per-server token (same as ingest).
+#[utoipa::path(
+    post,
+    path = "/observations",
+    tag = "plugin",
+    params(
+        ("X-Server-Id" = String, Header, description = "Server id"),
+    ),
+    request_body = CreateObservation,
+    responses(
+        (status = 201, body = CreateObservationResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("server_token" = [])),
+)]
 pub async fn create_observation(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(payload): Json<CreateObservation>,
 ) -> Result<(StatusCode, Json<CreateObservationResponse>), ApiError> {
-    // --- Extract server_id from header ---
-    let server_id = headers
-        .get("x-server-id")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("")
-        .trim()
-        .to_string();
-
-    if server_id.is_empty() {
-        return Err(ApiError::BadRequest(
-            "missing X-Server-Id header".to_string(),
-        ));
-    }
-
-    // --- Auth (per-server token) ---
-    let token = auth::parse_bearer_token(&headers).ok_or(ApiError::Unauthorized)?;
-    let token_hash = auth::sha256_hex(&token);
-
-    // --- Validate server is registered and token matches ---
-    let row: Option<(Option<String>, Option<Uuid>, Option<DateTime<Utc>>)> = sqlx::query_as(
-        r#"
-        SELECT auth_token_hash, owner_user_id, registered_at
-        FROM public.servers
-        WHERE id = $1
-        "#,
-    )
-    .bind(&server_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| {
-        tracing::error!("observation server lookup failed: {:?}", e);
-        ApiError::Internal
-    })?;
-
-    match row {
-        None => {
-            return Err(ApiError::BadRequest(format!(
-                "server {} not found",
-                server_id
-            )));
-        }
-        Some((stored_hash_opt, owner_user_id, registered_at)) => {
-            // Token must match (using constant-time comparison to prevent timing attacks)
-            if let Some(stored_hash) = stored_hash_opt {
-                if !auth::validate_token_hash(&token_hash, &stored_hash) {
-                    return Err(ApiError::Unauthorized);
-                }
-            } else {
-                return Err(ApiError::Unauthorized);
-            }
-
-            // Server must be registered
-            let is_registered = owner_user_id.is_some() && registered_at.is_some();
-            if !is_registered {
-                return Err(ApiError::BadRequest(
-                    "server not registered - please link it in the dashboard first".to_string(),
-                ));
-            }
-        }
-    }
+    let server_id = auth::authenticate_server(&state, &headers).await?;
 
     // --- Validate observation_type ---
     let observation_type = payload.observation_type.to_lowercase();
@@ -156,6 +113,8 @@ pub async fn create_observation(
         ApiError::Internal
     })?;
 
+    link_observation_findings(&state, observation_id).await;
+
     tracing::info!(
         observation_id = %observation_id,
         server_id = %server_id,
@@ -173,3 +132,502 @@ pub async fn create_observation(
         }),
     ))
 }
+
+// ============================================================================
+// Finding linkage
+// ============================================================================
+//
+// Findings for the observed player whose [first_seen_at, last_seen_at] overlaps the observation
+// window are linked automatically, so labeled sessions form a ground-truth dataset. Open
+// recordings (no ended_at) keep collecting findings until they are ended. Ending a recording
+// (or moving its end) relinks it: findings no longer overlapping the window are unlinked.
+
+/// Link all overlapping findings to one observation and unlink the ones outside its window
+/// (best-effort).
+pub async fn link_observation_findings(state: &AppState, observation_id: Uuid) {
+    let res = sqlx::query(
+        r#"
+        with pruned as (
+            delete from public.observation_findings l
+            using public.cheat_observations o, public.findings f
+            where l.observation_id = $1
+              and o.id = l.observation_id
+              and f.id = l.finding_id
+              and (o.started_at is null
+                   or f.last_seen_at < o.started_at
+                   or f.first_seen_at > coalesce(o.ended_at, now()))
+        )
+        insert into public.observation_findings (observation_id, finding_id)
+        select o.id, f.id
+        from public.cheat_observations o
+        join public.findings f
+          on f.server_id = o.server_id
+         and f.player_uuid = o.player_uuid
+         and f.last_seen_at >= o.started_at
+         and f.first_seen_at <= coalesce(o.ended_at, now())
+        where o.id = $1 and o.started_at is not null
+        on conflict do nothing
+        "#,
+    )
+    .bind(observation_id)
+    .execute(&state.db)
+    .await;
+    if let Err(e) = res {
+        tracing::warn!(observation_id = %observation_id, "observation finding link failed: {:?}", e);
+    }
+}
+
+/// Link findings seen since `since` to the players' observations covering that time (best-effort).
+pub async fn link_recent_findings(
+    state: &AppState,
+    server_id: &str,
+    player_uuids: &[Uuid],
+    since: DateTime<Utc>,
+) {
+    if player_uuids.is_empty() {
+        return;
+    }
+    let res = sqlx::query(
+        r#"
+        insert into public.observation_findings (observation_id, finding_id)
+        select o.id, f.id
+        from public.findings f
+        join public.cheat_observations o
+          on o.server_id = f.server_id
+         and o.player_uuid = f.player_uuid
+         and o.started_at is not null
+         and f.last_seen_at >= o.started_at
+         and f.first_seen_at <= coalesce(o.ended_at, now())
+        where f.server_id = $1
+          and f.player_uuid = any($2)
+          and f.last_seen_at >= $3
+        on conflict do nothing
+        "#,
+    )
+    .bind(server_id)
+    .bind(player_uuids)
+    .bind(since)
+    .execute(&state.db)
+    .await;
+    if let Err(e) = res {
+        tracing::warn!(server_id = %server_id, "recent finding link failed: {:?}", e);
+    }
+}
+
+// ============================================================================
+// Plugin: end/update a recording
+// ============================================================================
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PluginUpdateObservation {
+    /// Defaults to now, ending the recording.
+    pub ended_at: Option<DateTime<Utc>>,
+    pub cheat_type: Option<String>,
+    pub label: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct UpdateObservationResponse {
+    pub ok: bool,
+    pub observation_id: Uuid,
+}
+
+/// PATCH /observations/:observation_id
+///
+/// Ends a recording from the plugin (per-server token, same as create).
+#[utoipa::path(
+    patch,
+    path = "/observations/{observation_id}",
+    tag = "plugin",
+    params(
+        ("observation_id" = Uuid, Path, description = "Observation id"),
+        ("X-Server-Id" = String, Header, description = "Server id"),
+    ),
+    request_body = PluginUpdateObservation,
+    responses(
+        (status = 200, body = UpdateObservationResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("server_token" = [])),
+)]
+pub async fn end_observation(
+    State(state): State<AppState>,
+    Path(observation_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<PluginUpdateObservation>,
+) -> Result<Json<UpdateObservationResponse>, ApiError> {
+    let server_id = auth::authenticate_server(&state, &headers).await?;
+    let ended_at = payload.ended_at.unwrap_or_else(Utc::now);
+
+    let updated = sqlx::query(
+        r#"
+        UPDATE public.cheat_observations
+        SET ended_at = $3,
+            cheat_type = coalesce($4, cheat_type),
+            label = coalesce($5, label),
+            updated_at = now()
+        WHERE id = $1 AND server_id = $2
+        "#,
+    )
+    .bind(observation_id)
+    .bind(&server_id)
+    .bind(ended_at)
+    .bind(&payload.cheat_type)
+    .bind(&payload.label)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("end observation failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "observation {} not found",
+            observation_id
+        )));
+    }
+
+    link_observation_findings(&state, observation_id).await;
+
+    tracing::info!(observation_id = %observation_id, server_id = %server_id, "observation ended");
+
+    Ok(Json(UpdateObservationResponse {
+        ok: true,
+        observation_id,
+    }))
+}
+
+// ============================================================================
+// Dashboard: list / get / review
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct ObservationItem {
+    pub id: Uuid,
+    pub observation_type: String,
+    pub source: String,
+    pub player_uuid: Uuid,
+    pub player_name: Option<String>,
+    pub cheat_type: Option<String>,
+    pub label: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub session_id: Option<String>,
+    pub recorded_by_name: Option<String>,
+    pub status: String,
+    pub admin_notes: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub linked_findings: i64,
+}
+
+type ObservationRow = (
+    Uuid,
+    String,
+    String,
+    Uuid,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+    Option<String>,
+    Option<String>,
+    String,
+    Option<String>,
+    Option<DateTime<Utc>>,
+    DateTime<Utc>,
+    i64,
+);
+
+const OBSERVATION_COLUMNS: &str = r#"
+    o.id, o.observation_type, o.source, o.player_uuid, o.player_name, o.cheat_type, o.label,
+    o.started_at, o.ended_at, o.session_id, o.recorded_by_name, o.status, o.admin_notes,
+    o.reviewed_at, o.created_at,
+    (select count(*) from public.observation_findings l where l.observation_id = o.id)
+"#;
+
+fn observation_item(row: ObservationRow) -> ObservationItem {
+    let (
+        id,
+        observation_type,
+        source,
+        player_uuid,
+        player_name,
+        cheat_type,
+        label,
+        started_at,
+        ended_at,
+        session_id,
+        recorded_by_name,
+        status,
+        admin_notes,
+        reviewed_at,
+        created_at,
+        linked_findings,
+    ) = row;
+    ObservationItem {
+        id,
+        observation_type,
+        source,
+        player_uuid,
+        player_name,
+        cheat_type,
+        label,
+        started_at,
+        ended_at,
+        session_id,
+        recorded_by_name,
+        status,
+        admin_notes,
+        reviewed_at,
+        created_at,
+        linked_findings,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ObservationsQuery {
+    pub player_uuid: Option<Uuid>,
+    pub observation_type: Option<String>,
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ObservationsResponse {
+    pub ok: bool,
+    pub observations: Vec<ObservationItem>,
+}
+
+/// GET /dashboard/:server_id/observations
+pub async fn list_observations(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Query(q): Query<ObservationsQuery>,
+) -> Result<Json<ObservationsResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+
+    let rows: Vec<ObservationRow> = sqlx::query_as(&format!(
+        r#"
+        SELECT {}
+        FROM public.cheat_observations o
+        WHERE o.server_id = $1
+          AND ($2::uuid IS NULL OR o.player_uuid = $2)
+          AND ($3::text IS NULL OR o.observation_type = $3)
+          AND ($4::text IS NULL OR o.status = $4)
+        ORDER BY o.created_at DESC
+        LIMIT $5
+        "#,
+        OBSERVATION_COLUMNS
+    ))
+    .bind(&server_id)
+    .bind(q.player_uuid)
+    .bind(q.observation_type.as_deref())
+    .bind(q.status.as_deref())
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("list observations failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    Ok(Json(ObservationsResponse {
+        ok: true,
+        observations: rows.into_iter().map(observation_item).collect(),
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct LinkedFinding {
+    pub id: Uuid,
+    pub detector_name: String,
+    pub severity: String,
+    pub title: String,
+    pub occurrences: i32,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ObservationDetailResponse {
+    pub ok: bool,
+    pub observation: ObservationItem,
+    pub findings: Vec<LinkedFinding>,
+}
+
+/// GET /dashboard/:server_id/observations/:observation_id
+///
+/// Returns the observation with its linked findings.
+pub async fn get_observation(
+    State(state): State<AppState>,
+    Path((server_id, observation_id)): Path<(String, Uuid)>,
+) -> Result<Json<ObservationDetailResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let row: Option<ObservationRow> = sqlx::query_as(&format!(
+        "SELECT {} FROM public.cheat_observations o WHERE o.id = $1 AND o.server_id = $2",
+        OBSERVATION_COLUMNS
+    ))
+    .bind(observation_id)
+    .bind(&server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("get observation failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let Some(row) = row else {
+        return Err(ApiError::NotFound(format!(
+            "observation {} not found",
+            observation_id
+        )));
+    };
+
+    let findings: Vec<(Uuid, String, String, String, i32, DateTime<Utc>, DateTime<Utc>)> =
+        sqlx::query_as(
+            r#"
+            SELECT f.id, f.detector_name, f.severity, f.title, f.occurrences, f.first_seen_at, f.last_seen_at
+            FROM public.observation_findings l
+            JOIN public.findings f ON f.id = l.finding_id
+            WHERE l.observation_id = $1
+            ORDER BY f.first_seen_at ASC
+            "#,
+        )
+        .bind(observation_id)
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("get observation findings failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    Ok(Json(ObservationDetailResponse {
+        ok: true,
+        observation: observation_item(row),
+        findings: findings
+            .into_iter()
+            .map(
+                |(id, detector_name, severity, title, occurrences, first_seen_at, last_seen_at)| {
+                    LinkedFinding {
+                        id,
+                        detector_name,
+                        severity,
+                        title,
+                        occurrences,
+                        first_seen_at,
+                        last_seen_at,
+                    }
+                },
+            )
+            .collect(),
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewObservation {
+    pub status: Option<String>,
+    pub cheat_type: Option<String>,
+    pub label: Option<String>,
+    pub admin_notes: Option<String>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Dashboard user performing the review (auth.users.id).
+    pub reviewed_by: Option<Uuid>,
+}
+
+/// PATCH /dashboard/:server_id/observations/:observation_id
+///
+/// Review/label an observation. Setting a status other than `new`/`pending` marks it reviewed.
+pub async fn review_observation(
+    State(state): State<AppState>,
+    Path((server_id, observation_id)): Path<(String, Uuid)>,
+    Json(payload): Json<ReviewObservation>,
+) -> Result<Json<UpdateObservationResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let status = payload.status.as_deref().map(str::to_lowercase);
+    if let Some(status) = status.as_deref() {
+        if ![
+            "new",
+            "pending",
+            "reviewed",
+            "confirmed",
+            "resolved",
+            "dismissed",
+        ]
+        .contains(&status)
+        {
+            return Err(ApiError::BadRequest(format!("invalid status: {}", status)));
+        }
+    }
+    let reviewed = status
+        .as_deref()
+        .is_some_and(|s| !matches!(s, "new" | "pending"));
+
+    let observation_type: Option<(String,)> = sqlx::query_as(
+        r#"
+        UPDATE public.cheat_observations
+        SET status = coalesce($3, status),
+            cheat_type = coalesce($4, cheat_type),
+            label = coalesce($5, label),
+            admin_notes = coalesce($6, admin_notes),
+            ended_at = coalesce($7, ended_at),
+            reviewed_at = CASE WHEN $8 THEN now() ELSE reviewed_at END,
+            reviewed_by = CASE WHEN $8 THEN coalesce($9, reviewed_by) ELSE reviewed_by END,
+            updated_at = now()
+        WHERE id = $1 AND server_id = $2
+        RETURNING observation_type
+        "#,
+    )
+    .bind(observation_id)
+    .bind(&server_id)
+    .bind(status.as_deref())
+    .bind(&payload.cheat_type)
+    .bind(&payload.label)
+    .bind(&payload.admin_notes)
+    .bind(payload.ended_at)
+    .bind(reviewed)
+    .bind(payload.reviewed_by)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("review observation failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let Some((observation_type,)) = observation_type else {
+        return Err(ApiError::NotFound(format!(
+            "observation {} not found",
+            observation_id
+        )));
+    };
+
+    if payload.ended_at.is_some() {
+        link_observation_findings(&state, observation_id).await;
+    }
+
+    // A confirmed observation labels its still-open linked findings, feeding detector_metrics.
+    if status.as_deref() == Some("confirmed") {
+        let finding_status = if observation_type == "false_positive" {
+            "false_positive"
+        } else {
+            "confirmed"
+        };
+        detector_metrics::label_observation_findings(
+            &state.db,
+            &server_id,
+            observation_id,
+            finding_status,
+        )
+        .await;
+    }
+
+    Ok(Json(UpdateObservationResponse {
+        ok: true,
+        observation_id,
+    }))
+}
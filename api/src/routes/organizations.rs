@@ -0,0 +1,997 @@
+//! Organization-scoped dashboard endpoints (`/dashboard/organizations/...`).
+//!
+//! An organization groups the servers of one network (proxy + backends) under shared members,
+//! webhook settings and exemptions. Membership is stored for the dashboard frontend, which
+//! checks it against the signed-in user; the API itself is still gated by `DASHBOARD_TOKEN`.
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    error::ApiError,
+    exemptions,
+    routes::exemptions::{CreateExemptionRequest, CreateExemptionResponse, ExemptionItem},
+    AppState,
+};
+
+const ROLES: [&str; 3] = ["owner", "admin", "member"];
+const SEVERITY_LEVELS: [&str; 4] = ["critical", "high", "medium", "low"];
+
+fn internal(what: &str) -> impl FnOnce(sqlx::Error) -> ApiError + '_ {
+    move |e| {
+        tracing::error!("{} failed: {:?}", what, e);
+        ApiError::Internal
+    }
+}
+
+async fn ensure_organization(state: &AppState, org_id: Uuid) -> Result<(), ApiError> {
+    let exists: Option<Uuid> =
+        sqlx::query_scalar("select id from public.organizations where id = $1")
+            .bind(org_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(internal("organization lookup"))?;
+    match exists {
+        Some(_) => Ok(()),
+        None => Err(ApiError::NotFound(format!(
+            "organization {} not found",
+            org_id
+        ))),
+    }
+}
+
+async fn sync_exemptions(state: &AppState, org_id: Uuid) -> Result<(), ApiError> {
+    exemptions::sync_organization(&state.db, org_id)
+        .await
+        .map_err(internal("organization exemptions sync"))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub servers: i64,
+    pub members: i64,
+    /// The queried user's role (only with `?user_id=`).
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationsResponse {
+    pub ok: bool,
+    pub organizations: Vec<OrganizationSummary>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OrganizationsQuery {
+    /// Only organizations this user is a member of.
+    pub user_id: Option<Uuid>,
+}
+
+/// GET /dashboard/organizations
+#[utoipa::path(
+    get,
+    path = "/dashboard/organizations",
+    tag = "dashboard",
+    params(OrganizationsQuery),
+    responses(
+        (status = 200, body = OrganizationsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_organizations(
+    State(state): State<AppState>,
+    Query(q): Query<OrganizationsQuery>,
+) -> Result<Json<OrganizationsResponse>, ApiError> {
+    let rows: Vec<(Uuid, String, DateTime<Utc>, i64, i64, Option<String>)> = sqlx::query_as(
+        r#"
+        select o.id, o.name, o.created_at,
+               (select count(*) from public.servers s where s.organization_id = o.id),
+               (select count(*) from public.organization_members m where m.organization_id = o.id),
+               me.role
+        from public.organizations o
+        left join public.organization_members me
+          on me.organization_id = o.id and me.user_id = $1
+        where $1::uuid is null or me.user_id is not null
+        order by o.name asc
+        "#,
+    )
+    .bind(q.user_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal("organizations query"))?;
+
+    let organizations = rows
+        .into_iter()
+        .map(
+            |(id, name, created_at, servers, members, role)| OrganizationSummary {
+                id,
+                name,
+                created_at,
+                servers,
+                members,
+                role,
+            },
+        )
+        .collect();
+    Ok(Json(OrganizationsResponse {
+        ok: true,
+        organizations,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+    /// Becomes the organization's first owner.
+    pub owner_user_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateOrganizationResponse {
+    pub ok: bool,
+    pub id: Uuid,
+}
+
+/// POST /dashboard/organizations
+#[utoipa::path(
+    post,
+    path = "/dashboard/organizations",
+    tag = "dashboard",
+    request_body = CreateOrganizationRequest,
+    responses(
+        (status = 200, body = CreateOrganizationResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn create_organization(
+    State(state): State<AppState>,
+    Json(req): Json<CreateOrganizationRequest>,
+) -> Result<Json<CreateOrganizationResponse>, ApiError> {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::BadRequest("name is required".to_string()));
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(internal("organization transaction"))?;
+    let id: Uuid =
+        sqlx::query_scalar("insert into public.organizations (name) values ($1) returning id")
+            .bind(name)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(internal("organization insert"))?;
+    sqlx::query(
+        r#"
+        insert into public.organization_members (organization_id, user_id, role)
+        values ($1, $2, 'owner')
+        "#,
+    )
+    .bind(id)
+    .bind(req.owner_user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(internal("organization owner insert"))?;
+    tx.commit()
+        .await
+        .map_err(internal("organization transaction commit"))?;
+
+    tracing::info!(organization_id = %id, "organization created");
+    Ok(Json(CreateOrganizationResponse { ok: true, id }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationServer {
+    pub id: String,
+    pub name: Option<String>,
+    pub platform: Option<String>,
+    pub last_seen_at: DateTime<Utc>,
+    pub open_findings: i64,
+    /// Whether the server has its own enabled webhook (otherwise the organization's is used).
+    pub own_webhook: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationMember {
+    pub user_id: Uuid,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationDetail {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub webhook_url: Option<String>,
+    pub webhook_enabled: bool,
+    pub webhook_severity_levels: Vec<String>,
+    pub servers: Vec<OrganizationServer>,
+    pub members: Vec<OrganizationMember>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationResponse {
+    pub ok: bool,
+    pub organization: OrganizationDetail,
+}
+
+/// GET /dashboard/organizations/:org_id
+///
+/// Organization settings with its servers (open findings per server) and members.
+#[utoipa::path(
+    get,
+    path = "/dashboard/organizations/{org_id}",
+    tag = "dashboard",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization id"),
+    ),
+    responses(
+        (status = 200, body = OrganizationResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_organization(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<OrganizationResponse>, ApiError> {
+    let org: Option<(String, DateTime<Utc>, Option<String>, bool, Vec<String>)> = sqlx::query_as(
+        r#"
+        select name, created_at, webhook_url, webhook_enabled, webhook_severity_levels
+        from public.organizations
+        where id = $1
+        "#,
+    )
+    .bind(org_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(internal("organization lookup"))?;
+    let Some((name, created_at, webhook_url, webhook_enabled, webhook_severity_levels)) = org
+    else {
+        return Err(ApiError::NotFound(format!(
+            "organization {} not found",
+            org_id
+        )));
+    };
+
+    let servers: Vec<(
+        String,
+        Option<String>,
+        Option<String>,
+        DateTime<Utc>,
+        i64,
+        bool,
+    )> = sqlx::query_as(
+        r#"
+            select s.id, s.name, s.platform, s.last_seen_at,
+                   (select count(*) from public.findings f
+                    where f.server_id = s.id and f.status = 'open'),
+                   s.webhook_enabled and s.webhook_url is not null
+            from public.servers s
+            where s.organization_id = $1 and s.deleted_at is null
+            order by s.name asc nulls last, s.id asc
+            "#,
+    )
+    .bind(org_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal("organization servers query"))?;
+
+    let members: Vec<(Uuid, String, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        select user_id, role, created_at
+        from public.organization_members
+        where organization_id = $1
+        order by created_at asc
+        "#,
+    )
+    .bind(org_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal("organization members query"))?;
+
+    Ok(Json(OrganizationResponse {
+        ok: true,
+        organization: OrganizationDetail {
+            id: org_id,
+            name,
+            created_at,
+            webhook_url,
+            webhook_enabled,
+            webhook_severity_levels,
+            servers: servers
+                .into_iter()
+                .map(
+                    |(id, name, platform, last_seen_at, open_findings, own_webhook)| {
+                        OrganizationServer {
+                            id,
+                            name,
+                            platform,
+                            last_seen_at,
+                            open_findings,
+                            own_webhook,
+                        }
+                    },
+                )
+                .collect(),
+            members: members
+                .into_iter()
+                .map(|(user_id, role, created_at)| OrganizationMember {
+                    user_id,
+                    role,
+                    created_at,
+                })
+                .collect(),
+        },
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationOkResponse {
+    pub ok: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AttachServerRequest {
+    pub server_id: String,
+}
+
+/// POST /dashboard/organizations/:org_id/servers
+///
+/// Attach a server. Attached servers count as registered, so their plugin can ingest without
+/// an owning user account. A server belongs to at most one organization.
+#[utoipa::path(
+    post,
+    path = "/dashboard/organizations/{org_id}/servers",
+    tag = "dashboard",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization id"),
+    ),
+    request_body = AttachServerRequest,
+    responses(
+        (status = 200, body = OrganizationOkResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn attach_server(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<AttachServerRequest>,
+) -> Result<Json<OrganizationOkResponse>, ApiError> {
+    ensure_organization(&state, org_id).await?;
+    let server_id = req.server_id.trim();
+
+    let current: Option<(Option<Uuid>,)> =
+        sqlx::query_as("select organization_id from public.servers where id = $1")
+            .bind(server_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(internal("organization server lookup"))?;
+    match current {
+        None => {
+            return Err(ApiError::NotFound(format!(
+                "server {} not found",
+                server_id
+            )))
+        }
+        Some((Some(other),)) if other != org_id => {
+            return Err(ApiError::Conflict(format!(
+                "server {} belongs to another organization",
+                server_id
+            )))
+        }
+        Some(_) => {}
+    }
+
+    sqlx::query(
+        r#"
+        update public.servers
+        set organization_id = $2,
+            registered_at = coalesce(registered_at, now())
+        where id = $1
+        "#,
+    )
+    .bind(server_id)
+    .bind(org_id)
+    .execute(&state.db)
+    .await
+    .map_err(internal("organization server attach"))?;
+
+    // Organization exemptions now apply to this server.
+    exemptions::sync_to_plugin(&state.db, server_id)
+        .await
+        .map_err(internal("exemptions sync"))?;
+
+    tracing::info!(organization_id = %org_id, server_id = %server_id, "server attached to organization");
+    Ok(Json(OrganizationOkResponse { ok: true }))
+}
+
+/// DELETE /dashboard/organizations/:org_id/servers/:server_id
+///
+/// Detach a server. It stays registered only if it also has an owning user account.
+#[utoipa::path(
+    delete,
+    path = "/dashboard/organizations/{org_id}/servers/{server_id}",
+    tag = "dashboard",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization id"),
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = OrganizationOkResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn detach_server(
+    State(state): State<AppState>,
+    Path((org_id, server_id)): Path<(Uuid, String)>,
+) -> Result<Json<OrganizationOkResponse>, ApiError> {
+    let server_id = server_id.trim();
+    let res = sqlx::query(
+        "update public.servers set organization_id = null where id = $1 and organization_id = $2",
+    )
+    .bind(server_id)
+    .bind(org_id)
+    .execute(&state.db)
+    .await
+    .map_err(internal("organization server detach"))?;
+    if res.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "server {} is not in organization {}",
+            server_id, org_id
+        )));
+    }
+
+    exemptions::sync_to_plugin(&state.db, server_id)
+        .await
+        .map_err(internal("exemptions sync"))?;
+
+    Ok(Json(OrganizationOkResponse { ok: true }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetMemberRequest {
+    pub user_id: Uuid,
+    /// owner | admin | member
+    pub role: String,
+}
+
+/// POST /dashboard/organizations/:org_id/members
+///
+/// Add a member or change their role.
+#[utoipa::path(
+    post,
+    path = "/dashboard/organizations/{org_id}/members",
+    tag = "dashboard",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization id"),
+    ),
+    request_body = SetMemberRequest,
+    responses(
+        (status = 200, body = OrganizationOkResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn set_member(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<SetMemberRequest>,
+) -> Result<Json<OrganizationOkResponse>, ApiError> {
+    let role = req.role.trim().to_ascii_lowercase();
+    if !ROLES.contains(&role.as_str()) {
+        return Err(ApiError::BadRequest(format!(
+            "role must be one of: {}",
+            ROLES.join(", ")
+        )));
+    }
+    ensure_organization(&state, org_id).await?;
+    if role != "owner" && is_last_owner(&state, org_id, req.user_id).await? {
+        return Err(ApiError::Conflict(
+            "an organization needs at least one owner".to_string(),
+        ));
+    }
+
+    sqlx::query(
+        r#"
+        insert into public.organization_members (organization_id, user_id, role)
+        values ($1, $2, $3)
+        on conflict (organization_id, user_id) do update set role = excluded.role
+        "#,
+    )
+    .bind(org_id)
+    .bind(req.user_id)
+    .bind(&role)
+    .execute(&state.db)
+    .await
+    .map_err(internal("organization member upsert"))?;
+
+    Ok(Json(OrganizationOkResponse { ok: true }))
+}
+
+/// DELETE /dashboard/organizations/:org_id/members/:user_id
+#[utoipa::path(
+    delete,
+    path = "/dashboard/organizations/{org_id}/members/{user_id}",
+    tag = "dashboard",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization id"),
+        ("user_id" = Uuid, Path, description = "Member user id"),
+    ),
+    responses(
+        (status = 200, body = OrganizationOkResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn remove_member(
+    State(state): State<AppState>,
+    Path((org_id, user_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<OrganizationOkResponse>, ApiError> {
+    if is_last_owner(&state, org_id, user_id).await? {
+        return Err(ApiError::Conflict(
+            "an organization needs at least one owner".to_string(),
+        ));
+    }
+    let res = sqlx::query(
+        "delete from public.organization_members where organization_id = $1 and user_id = $2",
+    )
+    .bind(org_id)
+    .bind(user_id)
+    .execute(&state.db)
+    .await
+    .map_err(internal("organization member delete"))?;
+    if res.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "user {} is not a member of organization {}",
+            user_id, org_id
+        )));
+    }
+    Ok(Json(OrganizationOkResponse { ok: true }))
+}
+
+/// Whether `user_id` is currently the organization's only owner.
+async fn is_last_owner(state: &AppState, org_id: Uuid, user_id: Uuid) -> Result<bool, ApiError> {
+    let (is_owner, owners): (bool, i64) = sqlx::query_as(
+        r#"
+        select coalesce(bool_or(user_id = $2), false), count(*)
+        from public.organization_members
+        where organization_id = $1 and role = 'owner'
+        "#,
+    )
+    .bind(org_id)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(internal("organization owners query"))?;
+    Ok(is_owner && owners == 1)
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationWebhookRequest {
+    /// Empty or null clears the URL.
+    pub webhook_url: Option<String>,
+    pub webhook_enabled: bool,
+    /// Subset of critical, high, medium, low (unknown levels are ignored).
+    pub webhook_severity_levels: Option<Vec<String>>,
+}
+
+/// POST /dashboard/organizations/:org_id/webhook
+///
+/// Webhook used for findings on member servers that have no enabled webhook of their own.
+#[utoipa::path(
+    post,
+    path = "/dashboard/organizations/{org_id}/webhook",
+    tag = "dashboard",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization id"),
+    ),
+    request_body = OrganizationWebhookRequest,
+    responses(
+        (status = 200, body = OrganizationOkResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn set_webhook(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<OrganizationWebhookRequest>,
+) -> Result<Json<OrganizationOkResponse>, ApiError> {
+    let url = req
+        .webhook_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|u| !u.is_empty());
+    if let Some(url) = url {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|_| ApiError::BadRequest("invalid webhook_url".to_string()))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(ApiError::BadRequest("invalid webhook_url".to_string()));
+        }
+    }
+    if req.webhook_enabled && url.is_none() {
+        return Err(ApiError::BadRequest(
+            "webhook_url is required to enable the webhook".to_string(),
+        ));
+    }
+    let levels: Option<Vec<String>> = req.webhook_severity_levels.map(|levels| {
+        levels
+            .iter()
+            .map(|l| l.trim().to_ascii_lowercase())
+            .filter(|l| SEVERITY_LEVELS.contains(&l.as_str()))
+            .collect()
+    });
+
+    let res = sqlx::query(
+        r#"
+        update public.organizations
+        set webhook_url = $2,
+            webhook_enabled = $3,
+            webhook_severity_levels = coalesce($4, webhook_severity_levels)
+        where id = $1
+        "#,
+    )
+    .bind(org_id)
+    .bind(url)
+    .bind(req.webhook_enabled)
+    .bind(levels)
+    .execute(&state.db)
+    .await
+    .map_err(internal("organization webhook update"))?;
+    if res.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "organization {} not found",
+            org_id
+        )));
+    }
+    Ok(Json(OrganizationOkResponse { ok: true }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationExemptionsResponse {
+    pub ok: bool,
+    pub exemptions: Vec<ExemptionItem>,
+}
+
+/// GET /dashboard/organizations/:org_id/exemptions
+///
+/// Current and scheduled organization-wide exemptions (expired ones are omitted).
+#[utoipa::path(
+    get,
+    path = "/dashboard/organizations/{org_id}/exemptions",
+    tag = "dashboard",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization id"),
+    ),
+    responses(
+        (status = 200, body = OrganizationExemptionsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_exemptions(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<OrganizationExemptionsResponse>, ApiError> {
+    let rows: Vec<(
+        Uuid,
+        Option<Uuid>,
+        Option<String>,
+        Option<String>,
+        DateTime<Utc>,
+        Option<DateTime<Utc>>,
+        Option<String>,
+        Option<String>,
+        bool,
+    )> = sqlx::query_as(
+        r#"
+        select id, player_uuid, permission, world, starts_at, ends_at, reason, created_by,
+               starts_at <= now() as active
+        from public.organization_exemptions
+        where organization_id = $1 and (ends_at is null or ends_at > now())
+        order by starts_at desc
+        "#,
+    )
+    .bind(org_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal("organization exemptions query"))?;
+
+    let exemptions = rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                player_uuid,
+                permission,
+                world,
+                starts_at,
+                ends_at,
+                reason,
+                created_by,
+                active,
+            )| ExemptionItem {
+                id,
+                player_uuid,
+                permission,
+                world,
+                starts_at,
+                ends_at,
+                reason,
+                created_by,
+                active,
+            },
+        )
+        .collect();
+    Ok(Json(OrganizationExemptionsResponse {
+        ok: true,
+        exemptions,
+    }))
+}
+
+/// POST /dashboard/organizations/:org_id/exemptions
+///
+/// Same selectors as server exemptions; applies to every server of the organization.
+#[utoipa::path(
+    post,
+    path = "/dashboard/organizations/{org_id}/exemptions",
+    tag = "dashboard",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization id"),
+    ),
+    request_body = CreateExemptionRequest,
+    responses(
+        (status = 200, body = CreateExemptionResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn create_exemption(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<CreateExemptionRequest>,
+) -> Result<Json<CreateExemptionResponse>, ApiError> {
+    let new = req.validate()?;
+    ensure_organization(&state, org_id).await?;
+
+    let id: Uuid = sqlx::query_scalar(
+        r#"
+        insert into public.organization_exemptions
+            (organization_id, player_uuid, permission, world, starts_at, ends_at, reason, created_by)
+        values ($1, $2, $3, $4, $5, $6, $7, $8)
+        returning id
+        "#,
+    )
+    .bind(org_id)
+    .bind(new.player_uuid)
+    .bind(new.permission.as_deref())
+    .bind(new.world.as_deref())
+    .bind(new.starts_at)
+    .bind(new.ends_at)
+    .bind(new.reason.as_deref())
+    .bind(new.created_by.as_deref())
+    .fetch_one(&state.db)
+    .await
+    .map_err(internal("organization exemption insert"))?;
+
+    if new.permission.is_some() {
+        sync_exemptions(&state, org_id).await?;
+    }
+
+    tracing::info!(organization_id = %org_id, exemption_id = %id, "organization exemption created");
+    Ok(Json(CreateExemptionResponse { ok: true, id }))
+}
+
+/// DELETE /dashboard/organizations/:org_id/exemptions/:exemption_id
+#[utoipa::path(
+    delete,
+    path = "/dashboard/organizations/{org_id}/exemptions/{exemption_id}",
+    tag = "dashboard",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization id"),
+        ("exemption_id" = Uuid, Path, description = "Exemption id"),
+    ),
+    responses(
+        (status = 200, body = OrganizationOkResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn delete_exemption(
+    State(state): State<AppState>,
+    Path((org_id, exemption_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<OrganizationOkResponse>, ApiError> {
+    let deleted: Option<(Option<String>,)> = sqlx::query_as(
+        r#"
+        delete from public.organization_exemptions
+        where id = $1 and organization_id = $2
+        returning permission
+        "#,
+    )
+    .bind(exemption_id)
+    .bind(org_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(internal("organization exemption delete"))?;
+
+    let Some((permission,)) = deleted else {
+        return Err(ApiError::NotFound(format!(
+            "exemption {} not found",
+            exemption_id
+        )));
+    };
+    if permission.is_some() {
+        sync_exemptions(&state, org_id).await?;
+    }
+    Ok(Json(OrganizationOkResponse { ok: true }))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OrganizationFindingsQuery {
+    pub severity: Option<String>,
+    pub status: Option<String>,
+    /// Default 50, max 200.
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationFinding {
+    pub id: Uuid,
+    pub server_id: String,
+    pub server_name: Option<String>,
+    pub player_uuid: Option<Uuid>,
+    pub player_name: Option<String>,
+    pub detector_name: String,
+    pub severity: String,
+    pub title: String,
+    pub occurrences: i32,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrganizationFindingsResponse {
+    pub ok: bool,
+    pub findings: Vec<OrganizationFinding>,
+    pub total: i64,
+}
+
+/// GET /dashboard/organizations/:org_id/findings
+///
+/// Findings across every server of the organization, newest first.
+#[utoipa::path(
+    get,
+    path = "/dashboard/organizations/{org_id}/findings",
+    tag = "dashboard",
+    params(
+        ("org_id" = Uuid, Path, description = "Organization id"),
+        OrganizationFindingsQuery,
+    ),
+    responses(
+        (status = 200, body = OrganizationFindingsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_findings(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Query(q): Query<OrganizationFindingsQuery>,
+) -> Result<Json<OrganizationFindingsResponse>, ApiError> {
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let offset = q.offset.unwrap_or(0).max(0);
+    let severity = q
+        .severity
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let status = q.status.as_deref().map(str::trim).filter(|s| !s.is_empty());
+
+    let rows: Vec<(
+        Uuid,
+        String,
+        Option<String>,
+        Option<Uuid>,
+        Option<String>,
+        String,
+        String,
+        String,
+        i32,
+        String,
+        DateTime<Utc>,
+        DateTime<Utc>,
+        i64,
+    )> = sqlx::query_as(
+        r#"
+        select f.id, f.server_id, s.name, f.player_uuid, p.username, f.detector_name,
+               f.severity, f.title, f.occurrences, f.status, f.created_at, f.last_seen_at,
+               count(*) over ()
+        from public.findings f
+        join public.servers s on s.id = f.server_id
+        left join public.players p on p.uuid = f.player_uuid
+        where s.organization_id = $1
+          and ($2::text is null or f.severity = $2)
+          and ($3::text is null or f.status = $3)
+        order by f.created_at desc
+        limit $4 offset $5
+        "#,
+    )
+    .bind(org_id)
+    .bind(severity)
+    .bind(status)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal("organization findings query"))?;
+
+    let total = rows.first().map(|r| r.12).unwrap_or(0);
+    let findings = rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                server_id,
+                server_name,
+                player_uuid,
+                player_name,
+                detector_name,
+                severity,
+                title,
+                occurrences,
+                status,
+                created_at,
+                last_seen_at,
+                _,
+            )| OrganizationFinding {
+                id,
+                server_id,
+                server_name,
+                player_uuid,
+                player_name,
+                detector_name,
+                severity,
+                title,
+                occurrences,
+                status,
+                created_at,
+                last_seen_at,
+            },
+        )
+        .collect();
+
+    Ok(Json(OrganizationFindingsResponse {
+        ok: true,
+        findings,
+        total,
+    }))
+}
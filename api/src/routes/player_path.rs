@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::movement_path::{self, MovementPath};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PlayerPathQuery {
+    /// Defaults to 5 minutes before `to`.
+    pub from: Option<DateTime<Utc>>,
+    /// Defaults to now (or 5 minutes after `from`).
+    pub to: Option<DateTime<Utc>>,
+    /// Defaults to 1000, at most 5000.
+    pub max_points: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PlayerPathResponse {
+    pub ok: bool,
+    pub player_uuid: Uuid,
+    pub path: MovementPath,
+}
+
+/// GET /dashboard/:server_id/players/:player_uuid/path
+///
+/// The player's position trace between `from` and `to` (at most 30 minutes apart), rebuilt
+/// from stored batches and downsampled to a polyline for the trajectory view.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/players/{player_uuid}/path",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("player_uuid" = Uuid, Path, description = "Player UUID"),
+        PlayerPathQuery,
+    ),
+    responses(
+        (status = 200, body = PlayerPathResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_player_path(
+    State(state): State<AppState>,
+    Path((server_id, player_uuid)): Path<(String, Uuid)>,
+    Query(params): Query<PlayerPathQuery>,
+) -> Result<Json<PlayerPathResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let window = Duration::minutes(movement_path::DEFAULT_WINDOW_MINUTES);
+    let (from, to) = match (params.from, params.to) {
+        (Some(from), Some(to)) => (from, to),
+        (Some(from), None) => (from, from + window),
+        (None, Some(to)) => (to - window, to),
+        (None, None) => (Utc::now() - window, Utc::now()),
+    };
+    if to <= from {
+        return Err(ApiError::BadRequest("to must be after from".to_string()));
+    }
+    if to - from > Duration::minutes(movement_path::MAX_WINDOW_MINUTES) {
+        return Err(ApiError::BadRequest(format!(
+            "from and to must be at most {} minutes apart",
+            movement_path::MAX_WINDOW_MINUTES
+        )));
+    }
+    let max_points = params
+        .max_points
+        .unwrap_or(movement_path::DEFAULT_MAX_POINTS)
+        .clamp(2, movement_path::MAX_POINTS);
+
+    let path =
+        movement_path::reconstruct(&state, &server_id, player_uuid, from, to, max_points).await?;
+
+    Ok(Json(PlayerPathResponse {
+        ok: true,
+        player_uuid,
+        path,
+    }))
+}
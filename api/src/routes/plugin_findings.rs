@@ -0,0 +1,253 @@
+//! In-game player lookup (`/aac lookup <player>`).
+//!
+//! The plugin calls this from a command, usually with a staff member waiting in chat, so the
+//! response is kept small (at most [`MAX_FINDINGS`] findings with titles cut to
+//! [`MAX_TITLE_CHARS`], well under 2 KiB) and the lookup gives up after [`LOOKUP_BUDGET_MS`]
+//! rather than holding the callback. The risk score is the reputation formula
+//! (`reputation::score`) over the player's findings on this server only, from the last
+//! [`RISK_LOOKBACK_DAYS`]. On servers that hash player UUIDs a UUID query is mapped to the
+//! player's pseudonym on this server (`privacy::pseudonymize_player`), so only the owning
+//! server can look players up by UUID.
+
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{auth, error::ApiError, privacy, reputation, AppState};
+
+pub const DEFAULT_FINDINGS: i64 = 5;
+pub const MAX_FINDINGS: i64 = 10;
+pub const MAX_TITLE_CHARS: usize = 64;
+pub const LOOKUP_BUDGET_MS: u64 = 750;
+pub const RISK_LOOKBACK_DAYS: i32 = 30;
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PluginFindingsQuery {
+    /// Player UUID or last known name on this server.
+    pub player: String,
+    /// Defaults to 5, at most 10.
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PluginFinding {
+    pub detector: String,
+    pub severity: String,
+    pub title: String,
+    pub count: i32,
+    /// Seconds since the finding was last seen (avoids plugin clock skew).
+    pub seconds_ago: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PluginFindingsResponse {
+    pub ok: bool,
+    pub player_uuid: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_name: Option<String>,
+    /// 0-100 from this server's findings of the last 30 days.
+    pub risk: u8,
+    pub watchlisted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watchlist_reason: Option<String>,
+    /// Newest first; dismissed and false-positive findings are left out.
+    pub findings: Vec<PluginFinding>,
+}
+
+/// `title`, cut to [`MAX_TITLE_CHARS`] characters.
+pub fn compact_title(title: &str) -> String {
+    let title = title.trim();
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return title.to_string();
+    }
+    let mut cut: String = title.chars().take(MAX_TITLE_CHARS - 1).collect();
+    cut.push('…');
+    cut
+}
+
+/// Integer risk from an accumulated finding weight.
+pub fn risk(weight: f64) -> u8 {
+    reputation::score(weight).round().clamp(0.0, 100.0) as u8
+}
+
+async fn resolve_player(
+    state: &AppState,
+    server_id: &str,
+    player: &str,
+) -> Result<Option<(Uuid, Option<String>)>, sqlx::Error> {
+    if let Ok(uuid) = Uuid::parse_str(player) {
+        let uuid = match state.player_uuid_hash_key.as_deref() {
+            Some(key) if privacy::hashes_player_uuids(&state.db, server_id).await? => {
+                privacy::pseudonymize_player(key, server_id, uuid)
+            }
+            _ => uuid,
+        };
+        let name: Option<String> = sqlx::query_scalar(
+            "select player_name from public.server_players where server_id = $1 and player_uuid = $2",
+        )
+        .bind(server_id)
+        .bind(uuid)
+        .fetch_optional(&state.db)
+        .await?;
+        return Ok(Some((uuid, name)));
+    }
+    let row: Option<(Uuid, String)> = sqlx::query_as(
+        r#"
+        select player_uuid, player_name from public.server_players
+        where server_id = $1 and lower(player_name) = lower($2)
+        order by last_seen_at desc
+        limit 1
+        "#,
+    )
+    .bind(server_id)
+    .bind(player)
+    .fetch_optional(&state.db)
+    .await?;
+    Ok(row.map(|(uuid, name)| (uuid, Some(name))))
+}
+
+async fn lookup(
+    state: &AppState,
+    server_id: &str,
+    player: &str,
+    limit: i64,
+) -> Result<Option<PluginFindingsResponse>, sqlx::Error> {
+    let Some((player_uuid, player_name)) = resolve_player(state, server_id, player).await? else {
+        return Ok(None);
+    };
+
+    let findings = sqlx::query_as::<_, (String, String, String, i32, DateTime<Utc>)>(
+        r#"
+        select detector_name, severity, title, occurrences, last_seen_at
+        from public.findings
+        where player_uuid = $2 and server_id = $1
+          and status not in ('dismissed', 'false_positive')
+        order by last_seen_at desc
+        limit $3
+        "#,
+    )
+    .bind(server_id)
+    .bind(player_uuid)
+    .bind(limit)
+    .fetch_all(&state.db);
+
+    // Same weighting as the cross-server reputation aggregate.
+    let weight = sqlx::query_scalar::<_, Option<f64>>(
+        r#"
+        select sum(
+            (case severity
+                when 'critical' then 5.0
+                when 'high' then 3.0
+                when 'medium' then 1.5
+                when 'low' then 0.5
+                else 0.0 end)
+            * (case status when 'confirmed' then 3.0 else 1.0 end)
+            * ln(1 + occurrences))
+        from public.findings
+        where player_uuid = $2 and server_id = $1
+          and last_seen_at >= now() - make_interval(days => $3)
+          and status not in ('false_positive', 'dismissed')
+        "#,
+    )
+    .bind(server_id)
+    .bind(player_uuid)
+    .bind(RISK_LOOKBACK_DAYS)
+    .fetch_one(&state.db);
+
+    let watchlist = sqlx::query_scalar::<_, Option<String>>(
+        r#"
+        select reason from public.server_watchlist
+        where server_id = $1 and player_uuid = $2
+          and (expires_at is null or expires_at > now())
+        "#,
+    )
+    .bind(server_id)
+    .bind(player_uuid)
+    .fetch_optional(&state.db);
+
+    let (findings, weight, watchlist) = tokio::try_join!(findings, weight, watchlist)?;
+
+    let now = Utc::now();
+    Ok(Some(PluginFindingsResponse {
+        ok: true,
+        player_uuid,
+        player_name,
+        risk: risk(weight.unwrap_or(0.0)),
+        watchlisted: watchlist.is_some(),
+        watchlist_reason: watchlist.flatten(),
+        findings: findings
+            .into_iter()
+            .map(
+                |(detector, severity, title, count, last_seen_at)| PluginFinding {
+                    detector,
+                    severity,
+                    title: compact_title(&title),
+                    count,
+                    seconds_ago: (now - last_seen_at).num_seconds().max(0),
+                },
+            )
+            .collect(),
+    }))
+}
+
+/// GET /plugin/findings?player=
+///
+/// Recent findings, risk score and watchlist status of a player, for the in-game lookup
+/// command. Authenticated with the per-server token.
+#[utoipa::path(
+    get,
+    path = "/plugin/findings",
+    tag = "plugin",
+    params(
+        PluginFindingsQuery,
+        ("X-Server-Id" = String, Header, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = PluginFindingsResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 500, body = ErrorBody),
+    ),
+    security(("server_token" = [])),
+)]
+pub async fn get_plugin_findings(
+    State(state): State<AppState>,
+    Query(params): Query<PluginFindingsQuery>,
+    headers: HeaderMap,
+) -> Result<Json<PluginFindingsResponse>, ApiError> {
+    let server_id = auth::authenticate_server(&state, &headers).await?;
+
+    let player = params.player.trim();
+    if player.is_empty() || player.len() > 36 {
+        return Err(ApiError::BadRequest(
+            "player must be a UUID or a player name".to_string(),
+        ));
+    }
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_FINDINGS)
+        .clamp(1, MAX_FINDINGS);
+
+    let budget = std::time::Duration::from_millis(LOOKUP_BUDGET_MS);
+    let res = tokio::time::timeout(budget, lookup(&state, &server_id, player, limit))
+        .await
+        .map_err(|_| {
+            tracing::warn!(server_id = %server_id, "plugin findings lookup exceeded its budget");
+            ApiError::Internal
+        })?
+        .map_err(|e| {
+            tracing::error!("plugin findings lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    res.map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("player {} not seen on this server", player)))
+}
@@ -0,0 +1,80 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PrivacySettingsRequest {
+    /// Store a keyed hash instead of player UUIDs from now on.
+    pub hash_player_uuids: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PrivacySettingsResponse {
+    pub ok: bool,
+    pub hash_player_uuids: bool,
+}
+
+/// POST /dashboard/:server_id/privacy
+///
+/// Turn player UUID hashing on or off for a server. Only batches received afterwards are
+/// affected; enabling it needs `PLAYER_UUID_HASH_KEY` on the API.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/privacy",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = PrivacySettingsRequest,
+    responses(
+        (status = 200, body = PrivacySettingsResponse),
+        (status = 400, description = "PLAYER_UUID_HASH_KEY is not configured", body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn set_privacy_settings(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<PrivacySettingsRequest>,
+) -> Result<Json<PrivacySettingsResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    if req.hash_player_uuids && state.player_uuid_hash_key.is_none() {
+        return Err(ApiError::BadRequest(
+            "player UUID hashing needs PLAYER_UUID_HASH_KEY to be set on the API".to_string(),
+        ));
+    }
+
+    let updated = sqlx::query("UPDATE public.servers SET hash_player_uuids = $2 WHERE id = $1")
+        .bind(&server_id)
+        .bind(req.hash_player_uuids)
+        .execute(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("privacy settings update failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    tracing::info!(
+        server_id = %server_id,
+        hash_player_uuids = req.hash_player_uuids,
+        "privacy settings changed"
+    );
+
+    Ok(Json(PrivacySettingsResponse {
+        ok: true,
+        hash_player_uuids: req.hash_player_uuids,
+    }))
+}
@@ -0,0 +1,549 @@
+//! Proxy group dashboard endpoints (`/dashboard/proxy-groups/...`): findings and players
+//! aggregated across every backend behind one proxy (see `crate::proxy_groups`).
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{error::ApiError, AppState};
+
+fn internal(what: &str) -> impl FnOnce(sqlx::Error) -> ApiError + '_ {
+    move |e| {
+        tracing::error!("{} failed: {:?}", what, e);
+        ApiError::Internal
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxyGroupSummary {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub proxy_id: String,
+    pub name: Option<String>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub servers: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxyGroupsResponse {
+    pub ok: bool,
+    pub proxy_groups: Vec<ProxyGroupSummary>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProxyGroupsQuery {
+    /// Only groups of this owner (user or organization id).
+    pub owner_id: Option<Uuid>,
+}
+
+/// GET /dashboard/proxy-groups
+#[utoipa::path(
+    get,
+    path = "/dashboard/proxy-groups",
+    tag = "dashboard",
+    params(ProxyGroupsQuery),
+    responses(
+        (status = 200, body = ProxyGroupsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_proxy_groups(
+    State(state): State<AppState>,
+    Query(q): Query<ProxyGroupsQuery>,
+) -> Result<Json<ProxyGroupsResponse>, ApiError> {
+    let rows: Vec<(
+        Uuid,
+        Uuid,
+        String,
+        Option<String>,
+        DateTime<Utc>,
+        DateTime<Utc>,
+        i64,
+    )> = sqlx::query_as(
+        r#"
+            select g.id, g.owner_id, g.proxy_id, g.name, g.first_seen_at, g.last_seen_at,
+                   (select count(*) from public.servers s where s.proxy_group_id = g.id)
+            from public.proxy_groups g
+            where $1::uuid is null or g.owner_id = $1
+            order by g.last_seen_at desc
+            "#,
+    )
+    .bind(q.owner_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal("proxy groups query"))?;
+
+    let proxy_groups = rows
+        .into_iter()
+        .map(
+            |(id, owner_id, proxy_id, name, first_seen_at, last_seen_at, servers)| {
+                ProxyGroupSummary {
+                    id,
+                    owner_id,
+                    proxy_id,
+                    name,
+                    first_seen_at,
+                    last_seen_at,
+                    servers,
+                }
+            },
+        )
+        .collect();
+    Ok(Json(ProxyGroupsResponse {
+        ok: true,
+        proxy_groups,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxyGroupServer {
+    pub id: String,
+    pub name: Option<String>,
+    pub platform: Option<String>,
+    pub last_seen_at: DateTime<Utc>,
+    /// Players whose group session is currently on this backend.
+    pub players_online: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxyGroupResponse {
+    pub ok: bool,
+    pub proxy_group: ProxyGroupSummary,
+    pub servers: Vec<ProxyGroupServer>,
+}
+
+/// GET /dashboard/proxy-groups/:group_id
+///
+/// A proxy group and its backends, with the players currently on each.
+#[utoipa::path(
+    get,
+    path = "/dashboard/proxy-groups/{group_id}",
+    tag = "dashboard",
+    params(
+        ("group_id" = Uuid, Path, description = "Proxy group id"),
+    ),
+    responses(
+        (status = 200, body = ProxyGroupResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_proxy_group(
+    State(state): State<AppState>,
+    Path(group_id): Path<Uuid>,
+) -> Result<Json<ProxyGroupResponse>, ApiError> {
+    let group: Option<(Uuid, String, Option<String>, DateTime<Utc>, DateTime<Utc>)> =
+        sqlx::query_as(
+            r#"
+            select owner_id, proxy_id, name, first_seen_at, last_seen_at
+            from public.proxy_groups
+            where id = $1
+            "#,
+        )
+        .bind(group_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(internal("proxy group lookup"))?;
+    let Some((owner_id, proxy_id, name, first_seen_at, last_seen_at)) = group else {
+        return Err(ApiError::NotFound(format!(
+            "proxy group {} not found",
+            group_id
+        )));
+    };
+
+    let servers: Vec<(String, Option<String>, Option<String>, DateTime<Utc>, i64)> =
+        sqlx::query_as(
+            r#"
+            select s.id, s.name, s.platform, s.last_seen_at,
+                   (select count(*) from public.proxy_sessions ps
+                    where ps.proxy_group_id = $1
+                      and ps.last_server_id = s.id
+                      and ps.last_seen_at > now() - make_interval(secs => $2))
+            from public.servers s
+            where s.proxy_group_id = $1 and s.deleted_at is null
+            order by s.name asc nulls last, s.id asc
+            "#,
+        )
+        .bind(group_id)
+        .bind(crate::proxy_groups::SESSION_GAP_SECONDS as f64)
+        .fetch_all(&state.db)
+        .await
+        .map_err(internal("proxy group servers query"))?;
+
+    Ok(Json(ProxyGroupResponse {
+        ok: true,
+        proxy_group: ProxyGroupSummary {
+            id: group_id,
+            owner_id,
+            proxy_id,
+            name,
+            first_seen_at,
+            last_seen_at,
+            servers: servers.len() as i64,
+        },
+        servers: servers
+            .into_iter()
+            .map(
+                |(id, name, platform, last_seen_at, players_online)| ProxyGroupServer {
+                    id,
+                    name,
+                    platform,
+                    last_seen_at,
+                    players_online,
+                },
+            )
+            .collect(),
+    }))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProxyGroupFindingsQuery {
+    pub severity: Option<String>,
+    pub status: Option<String>,
+    pub player_uuid: Option<Uuid>,
+    /// Default 50, max 200.
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxyGroupFinding {
+    pub id: Uuid,
+    pub server_id: String,
+    pub player_uuid: Option<Uuid>,
+    pub player_name: Option<String>,
+    pub detector_name: String,
+    pub severity: String,
+    pub title: String,
+    pub occurrences: i32,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxyGroupFindingsResponse {
+    pub ok: bool,
+    pub findings: Vec<ProxyGroupFinding>,
+    pub total: i64,
+}
+
+/// GET /dashboard/proxy-groups/:group_id/findings
+///
+/// Findings across every backend of the group, newest first.
+#[utoipa::path(
+    get,
+    path = "/dashboard/proxy-groups/{group_id}/findings",
+    tag = "dashboard",
+    params(
+        ("group_id" = Uuid, Path, description = "Proxy group id"),
+        ProxyGroupFindingsQuery,
+    ),
+    responses(
+        (status = 200, body = ProxyGroupFindingsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_findings(
+    State(state): State<AppState>,
+    Path(group_id): Path<Uuid>,
+    Query(q): Query<ProxyGroupFindingsQuery>,
+) -> Result<Json<ProxyGroupFindingsResponse>, ApiError> {
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let offset = q.offset.unwrap_or(0).max(0);
+    let severity = q
+        .severity
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let status = q.status.as_deref().map(str::trim).filter(|s| !s.is_empty());
+
+    let rows: Vec<(
+        Uuid,
+        String,
+        Option<Uuid>,
+        Option<String>,
+        String,
+        String,
+        String,
+        i32,
+        String,
+        DateTime<Utc>,
+        DateTime<Utc>,
+        i64,
+    )> = sqlx::query_as(
+        r#"
+        select f.id, f.server_id, f.player_uuid, p.username, f.detector_name, f.severity,
+               f.title, f.occurrences, f.status, f.created_at, f.last_seen_at,
+               count(*) over ()
+        from public.findings f
+        join public.servers s on s.id = f.server_id
+        left join public.players p on p.uuid = f.player_uuid
+        where s.proxy_group_id = $1
+          and ($2::text is null or f.severity = $2)
+          and ($3::text is null or f.status = $3)
+          and ($4::uuid is null or f.player_uuid = $4)
+        order by f.created_at desc
+        limit $5 offset $6
+        "#,
+    )
+    .bind(group_id)
+    .bind(severity)
+    .bind(status)
+    .bind(q.player_uuid)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal("proxy group findings query"))?;
+
+    let total = rows.first().map(|r| r.11).unwrap_or(0);
+    let findings = rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                server_id,
+                player_uuid,
+                player_name,
+                detector_name,
+                severity,
+                title,
+                occurrences,
+                status,
+                created_at,
+                last_seen_at,
+                _,
+            )| ProxyGroupFinding {
+                id,
+                server_id,
+                player_uuid,
+                player_name,
+                detector_name,
+                severity,
+                title,
+                occurrences,
+                status,
+                created_at,
+                last_seen_at,
+            },
+        )
+        .collect();
+
+    Ok(Json(ProxyGroupFindingsResponse {
+        ok: true,
+        findings,
+        total,
+    }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxySession {
+    pub id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub last_server_id: String,
+    /// Backends visited during the session, in first-visit order.
+    pub server_ids: Vec<String>,
+    /// Findings on any backend of the group during the session.
+    pub findings: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxyGroupPlayer {
+    pub player_uuid: Uuid,
+    pub player_name: Option<String>,
+    /// Whether the latest session is still within the session gap.
+    pub online: bool,
+    pub latest_session: ProxySession,
+    /// Findings across the group, all time.
+    pub total_findings: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxyGroupPlayersResponse {
+    pub ok: bool,
+    pub players: Vec<ProxyGroupPlayer>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProxyGroupPlayersQuery {
+    /// Default 50, max 200.
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// GET /dashboard/proxy-groups/:group_id/players
+///
+/// Players on the group, most recently seen first, each with their latest group-wide session.
+#[utoipa::path(
+    get,
+    path = "/dashboard/proxy-groups/{group_id}/players",
+    tag = "dashboard",
+    params(
+        ("group_id" = Uuid, Path, description = "Proxy group id"),
+        ProxyGroupPlayersQuery,
+    ),
+    responses(
+        (status = 200, body = ProxyGroupPlayersResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_players(
+    State(state): State<AppState>,
+    Path(group_id): Path<Uuid>,
+    Query(q): Query<ProxyGroupPlayersQuery>,
+) -> Result<Json<ProxyGroupPlayersResponse>, ApiError> {
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let offset = q.offset.unwrap_or(0).max(0);
+
+    let rows: Vec<(
+        Uuid,
+        Option<String>,
+        bool,
+        Uuid,
+        DateTime<Utc>,
+        DateTime<Utc>,
+        String,
+        Vec<String>,
+        i64,
+        i64,
+    )> = sqlx::query_as(
+        r#"
+        with latest as (
+            select distinct on (player_uuid) *
+            from public.proxy_sessions
+            where proxy_group_id = $1
+            order by player_uuid, last_seen_at desc
+        )
+        select l.player_uuid, p.username,
+               l.last_seen_at > now() - make_interval(secs => $2),
+               l.id, l.started_at, l.last_seen_at, l.last_server_id, l.server_ids,
+               (select count(*) from public.findings f
+                join public.servers s on s.id = f.server_id
+                where s.proxy_group_id = $1 and f.player_uuid = l.player_uuid
+                  and f.last_seen_at >= l.started_at and f.created_at <= l.last_seen_at),
+               (select count(*) from public.findings f
+                join public.servers s on s.id = f.server_id
+                where s.proxy_group_id = $1 and f.player_uuid = l.player_uuid)
+        from latest l
+        left join public.players p on p.uuid = l.player_uuid
+        order by l.last_seen_at desc
+        limit $3 offset $4
+        "#,
+    )
+    .bind(group_id)
+    .bind(crate::proxy_groups::SESSION_GAP_SECONDS as f64)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal("proxy group players query"))?;
+
+    let players = rows
+        .into_iter()
+        .map(
+            |(
+                player_uuid,
+                player_name,
+                online,
+                id,
+                started_at,
+                last_seen_at,
+                last_server_id,
+                server_ids,
+                findings,
+                total_findings,
+            )| ProxyGroupPlayer {
+                player_uuid,
+                player_name,
+                online,
+                latest_session: ProxySession {
+                    id,
+                    started_at,
+                    last_seen_at,
+                    last_server_id,
+                    server_ids,
+                    findings,
+                },
+                total_findings,
+            },
+        )
+        .collect();
+    Ok(Json(ProxyGroupPlayersResponse { ok: true, players }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProxySessionsResponse {
+    pub ok: bool,
+    pub sessions: Vec<ProxySession>,
+}
+
+/// GET /dashboard/proxy-groups/:group_id/players/:player_uuid/sessions
+///
+/// A player's group-wide sessions, newest first (last 100).
+#[utoipa::path(
+    get,
+    path = "/dashboard/proxy-groups/{group_id}/players/{player_uuid}/sessions",
+    tag = "dashboard",
+    params(
+        ("group_id" = Uuid, Path, description = "Proxy group id"),
+        ("player_uuid" = Uuid, Path, description = "Player UUID"),
+    ),
+    responses(
+        (status = 200, body = ProxySessionsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_player_sessions(
+    State(state): State<AppState>,
+    Path((group_id, player_uuid)): Path<(Uuid, Uuid)>,
+) -> Result<Json<ProxySessionsResponse>, ApiError> {
+    let rows: Vec<(Uuid, DateTime<Utc>, DateTime<Utc>, String, Vec<String>, i64)> = sqlx::query_as(
+        r#"
+            select ps.id, ps.started_at, ps.last_seen_at, ps.last_server_id, ps.server_ids,
+                   (select count(*) from public.findings f
+                    join public.servers s on s.id = f.server_id
+                    where s.proxy_group_id = $1 and f.player_uuid = $2
+                      and f.last_seen_at >= ps.started_at and f.created_at <= ps.last_seen_at)
+            from public.proxy_sessions ps
+            where ps.proxy_group_id = $1 and ps.player_uuid = $2
+            order by ps.last_seen_at desc
+            limit 100
+            "#,
+    )
+    .bind(group_id)
+    .bind(player_uuid)
+    .fetch_all(&state.db)
+    .await
+    .map_err(internal("proxy sessions query"))?;
+
+    let sessions = rows
+        .into_iter()
+        .map(
+            |(id, started_at, last_seen_at, last_server_id, server_ids, findings)| ProxySession {
+                id,
+                started_at,
+                last_seen_at,
+                last_server_id,
+                server_ids,
+                findings,
+            },
+        )
+        .collect();
+    Ok(Json(ProxySessionsResponse { ok: true, sessions }))
+}
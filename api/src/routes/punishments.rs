@@ -0,0 +1,280 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::punishments::{self, BanRule, ExportMode, PunishmentAction};
+use crate::severity_overrides::parse_severity;
+use crate::{error::ApiError, AppState};
+
+const MAX_REASON_LEN: usize = 256;
+
+/// A server's automated ban rule and where its bans are exported.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PunishmentExportSettings {
+    pub enabled: bool,
+    /// queue (rows in `punishment_actions` for a bridge to consume) | webhook
+    pub mode: String,
+    /// LiteBans-style REST/webhook endpoint (webhook mode).
+    pub webhook_url: Option<String>,
+    /// Sent as `Authorization: Bearer <token>`. Write-only: omit to keep the stored token, send
+    /// an empty string to remove it.
+    #[serde(default, skip_serializing)]
+    pub webhook_token: Option<String>,
+    #[serde(default)]
+    pub webhook_token_set: bool,
+    /// Finding severities (after overrides) that fire a ban.
+    pub trigger_severities: Vec<String>,
+    /// Findings reported below this confidence (0-1) don't fire; findings without one do.
+    pub min_confidence: Option<f64>,
+    /// Null or 0 for permanent bans.
+    pub ban_duration_seconds: Option<i64>,
+    /// `{detectors}` is replaced by the detectors that fired.
+    pub reason: String,
+    /// Dashboard base URL for evidence links (defaults to https://asyncanticheat.com).
+    pub evidence_base_url: Option<String>,
+}
+
+impl From<BanRule> for PunishmentExportSettings {
+    fn from(rule: BanRule) -> Self {
+        Self {
+            enabled: rule.enabled,
+            mode: rule.mode.as_str().to_string(),
+            webhook_url: rule.webhook_url,
+            webhook_token: None,
+            webhook_token_set: rule.webhook_token.is_some(),
+            trigger_severities: rule.trigger_severities,
+            min_confidence: rule.min_confidence,
+            ban_duration_seconds: rule.ban_duration_seconds,
+            reason: rule.reason,
+            evidence_base_url: rule.evidence_base_url,
+        }
+    }
+}
+
+pub(crate) fn http_url(field: &str, raw: Option<&str>) -> Result<Option<String>, ApiError> {
+    let Some(url) = raw.map(str::trim).filter(|u| !u.is_empty()) else {
+        return Ok(None);
+    };
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(ApiError::BadRequest(format!(
+            "{} must be an http(s) URL",
+            field
+        )));
+    }
+    Ok(Some(url.to_string()))
+}
+
+impl PunishmentExportSettings {
+    /// The rule to store; `current_token` is kept when no token is sent.
+    fn validate(&self, current_token: Option<String>) -> Result<BanRule, ApiError> {
+        let mode = ExportMode::parse(&self.mode).ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "mode must be one of {}",
+                ExportMode::ALL.join(", ")
+            ))
+        })?;
+        let webhook_url = http_url("webhook_url", self.webhook_url.as_deref())?;
+        if self.enabled && mode == ExportMode::Webhook && webhook_url.is_none() {
+            return Err(ApiError::BadRequest(
+                "webhook mode requires webhook_url".to_string(),
+            ));
+        }
+        let mut trigger_severities = Vec::new();
+        for raw in &self.trigger_severities {
+            let severity = parse_severity(raw)
+                .ok_or_else(|| ApiError::BadRequest(format!("unknown severity {}", raw)))?;
+            if !trigger_severities.iter().any(|s| s == severity) {
+                trigger_severities.push(severity.to_string());
+            }
+        }
+        if trigger_severities.is_empty() {
+            return Err(ApiError::BadRequest(
+                "trigger_severities must not be empty".to_string(),
+            ));
+        }
+        if let Some(min) = self.min_confidence {
+            if !(0.0..=1.0).contains(&min) {
+                return Err(ApiError::BadRequest(
+                    "min_confidence must be between 0 and 1".to_string(),
+                ));
+            }
+        }
+        if self.ban_duration_seconds.is_some_and(|s| s < 0) {
+            return Err(ApiError::BadRequest(
+                "ban_duration_seconds must not be negative".to_string(),
+            ));
+        }
+        let reason = self.reason.trim();
+        if reason.is_empty() || reason.len() > MAX_REASON_LEN {
+            return Err(ApiError::BadRequest(format!(
+                "reason must be 1-{} characters",
+                MAX_REASON_LEN
+            )));
+        }
+        let webhook_token = match self.webhook_token.as_deref().map(str::trim) {
+            None => current_token,
+            Some("") => None,
+            Some(token) => Some(token.to_string()),
+        };
+
+        Ok(BanRule {
+            enabled: self.enabled,
+            mode,
+            webhook_url,
+            webhook_token,
+            trigger_severities,
+            min_confidence: self.min_confidence,
+            ban_duration_seconds: self.ban_duration_seconds.filter(|s| *s > 0),
+            reason: reason.to_string(),
+            evidence_base_url: http_url("evidence_base_url", self.evidence_base_url.as_deref())?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PunishmentsQuery {
+    /// Defaults to 50, at most 200.
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PunishmentsResponse {
+    pub ok: bool,
+    pub punishments: Vec<PunishmentAction>,
+}
+
+async fn server_exists(state: &AppState, server_id: &str) -> Result<(), ApiError> {
+    let exists: Option<String> = sqlx::query_scalar("select id from public.servers where id = $1")
+        .bind(server_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("punishment export server lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    match exists {
+        Some(_) => Ok(()),
+        None => Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        ))),
+    }
+}
+
+/// GET /dashboard/:server_id/punishment-export
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/punishment-export",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = PunishmentExportSettings),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_punishment_export(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<PunishmentExportSettings>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    server_exists(&state, &server_id).await?;
+
+    let rule = BanRule::load(&state.db, &server_id).await.map_err(|e| {
+        tracing::error!("punishment export query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    Ok(Json(rule.unwrap_or_default().into()))
+}
+
+/// POST /dashboard/:server_id/punishment-export
+///
+/// Replace the server's ban rule. Applies to findings stored from then on.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/punishment-export",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = PunishmentExportSettings,
+    responses(
+        (status = 200, body = PunishmentExportSettings),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn update_punishment_export(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<PunishmentExportSettings>,
+) -> Result<Json<PunishmentExportSettings>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    server_exists(&state, &server_id).await?;
+
+    let current = BanRule::load(&state.db, &server_id).await.map_err(|e| {
+        tracing::error!("punishment export query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let rule = req.validate(current.and_then(|r| r.webhook_token))?;
+    rule.save(&state.db, &server_id).await.map_err(|e| {
+        tracing::error!("punishment export save failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    tracing::info!(
+        server_id = %server_id,
+        enabled = rule.enabled,
+        mode = rule.mode.as_str(),
+        "punishment export saved"
+    );
+    Ok(Json(rule.into()))
+}
+
+/// GET /dashboard/:server_id/punishments
+///
+/// Exported ban actions with their delivery status, newest first.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/punishments",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        PunishmentsQuery,
+    ),
+    responses(
+        (status = 200, body = PunishmentsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_punishments(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Query(params): Query<PunishmentsQuery>,
+) -> Result<Json<PunishmentsResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+
+    let punishments = punishments::list(&state.db, &server_id, limit)
+        .await
+        .map_err(|e| {
+            tracing::error!("punishments query failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    Ok(Json(PunishmentsResponse {
+        ok: true,
+        punishments,
+    }))
+}
@@ -0,0 +1,304 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::{Html, IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::reports::{self, Delivery, ReportFormat, ReportSchedule, WeeklyReport};
+use crate::routes::punishments::http_url;
+use crate::{error::ApiError, AppState};
+
+/// A server's weekly report schedule and destinations.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReportScheduleSettings {
+    pub enabled: bool,
+    /// ISO weekday the report goes out: 1 = Monday .. 7 = Sunday.
+    pub weekday: u32,
+    /// Hour (UTC, 0-23) the report goes out; the report covers the 7 days before it.
+    pub hour_utc: u32,
+    /// html | json: the email body (webhooks get the JSON report, plus `html` in html format).
+    pub format: String,
+    /// Discord or HTTP webhook receiving the report.
+    pub webhook_url: Option<String>,
+    /// At most 10 addresses; needs the `email-reports` build feature and SMTP_URL.
+    #[serde(default)]
+    pub email_recipients: Vec<String>,
+    /// Whether this API can send email.
+    #[serde(default)]
+    pub email_available: bool,
+    /// End of the last week reported.
+    #[serde(default)]
+    pub last_period_end: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_sent_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+impl ReportScheduleSettings {
+    fn from_schedule(schedule: ReportSchedule, email_available: bool) -> Self {
+        Self {
+            enabled: schedule.enabled,
+            weekday: schedule.weekday,
+            hour_utc: schedule.hour_utc,
+            format: schedule.format.as_str().to_string(),
+            webhook_url: schedule.webhook_url,
+            email_recipients: schedule.email_recipients,
+            email_available,
+            last_period_end: schedule.last_period_end,
+            last_sent_at: schedule.last_sent_at,
+            last_error: schedule.last_error,
+        }
+    }
+
+    /// The schedule to store (delivery state is not taken from the request).
+    fn validate(&self) -> Result<ReportSchedule, ApiError> {
+        if !(1..=7).contains(&self.weekday) {
+            return Err(ApiError::BadRequest(
+                "weekday must be 1 (Monday) to 7 (Sunday)".to_string(),
+            ));
+        }
+        if self.hour_utc > 23 {
+            return Err(ApiError::BadRequest("hour_utc must be 0-23".to_string()));
+        }
+        let format = ReportFormat::parse(&self.format).ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "format must be one of {}",
+                ReportFormat::ALL.join(", ")
+            ))
+        })?;
+        let webhook_url = http_url("webhook_url", self.webhook_url.as_deref())?;
+        let email_recipients =
+            reports::validate_recipients(&self.email_recipients).map_err(ApiError::BadRequest)?;
+        if self.enabled && webhook_url.is_none() && email_recipients.is_empty() {
+            return Err(ApiError::BadRequest(
+                "an enabled schedule needs webhook_url or email_recipients".to_string(),
+            ));
+        }
+        Ok(ReportSchedule {
+            enabled: self.enabled,
+            weekday: self.weekday,
+            hour_utc: self.hour_utc,
+            format,
+            webhook_url,
+            email_recipients,
+            ..ReportSchedule::default()
+        })
+    }
+}
+
+async fn server_exists(state: &AppState, server_id: &str) -> Result<(), ApiError> {
+    let exists: Option<String> = sqlx::query_scalar("select id from public.servers where id = $1")
+        .bind(server_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("report server lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    match exists {
+        Some(_) => Ok(()),
+        None => Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        ))),
+    }
+}
+
+async fn load_schedule(state: &AppState, server_id: &str) -> Result<ReportSchedule, ApiError> {
+    let schedule = ReportSchedule::load(&state.db, server_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("report schedule query failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    Ok(schedule.unwrap_or_default())
+}
+
+/// GET /dashboard/:server_id/reports/schedule
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/reports/schedule",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = ReportScheduleSettings),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_report_schedule(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<ReportScheduleSettings>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    server_exists(&state, &server_id).await?;
+    let schedule = load_schedule(&state, &server_id).await?;
+    Ok(Json(ReportScheduleSettings::from_schedule(
+        schedule,
+        state.report_mailer.enabled(),
+    )))
+}
+
+/// POST /dashboard/:server_id/reports/schedule
+///
+/// Replace the server's weekly report schedule and destinations.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/reports/schedule",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = ReportScheduleSettings,
+    responses(
+        (status = 200, body = ReportScheduleSettings),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn update_report_schedule(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<ReportScheduleSettings>,
+) -> Result<Json<ReportScheduleSettings>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    server_exists(&state, &server_id).await?;
+
+    let schedule = req.validate()?;
+    schedule.save(&state.db, &server_id).await.map_err(|e| {
+        tracing::error!("report schedule save failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    tracing::info!(
+        server_id = %server_id,
+        enabled = schedule.enabled,
+        weekday = schedule.weekday,
+        hour_utc = schedule.hour_utc,
+        "report schedule saved"
+    );
+    let schedule = load_schedule(&state, &server_id).await?;
+    Ok(Json(ReportScheduleSettings::from_schedule(
+        schedule,
+        state.report_mailer.enabled(),
+    )))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WeeklyReportQuery {
+    /// json (default) | html
+    pub format: Option<String>,
+    /// End of the week to report; defaults to now (the last 7 days).
+    pub period_end: Option<DateTime<Utc>>,
+}
+
+/// GET /dashboard/:server_id/reports/weekly
+///
+/// The weekly report of the 7 days before `period_end`, as JSON or as the HTML page emails get.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/reports/weekly",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        WeeklyReportQuery,
+    ),
+    responses(
+        (status = 200, body = WeeklyReport, description = "The report; text/html with `format=html`"),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_weekly_report(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Query(q): Query<WeeklyReportQuery>,
+) -> Result<Response, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let format = match q.format.as_deref() {
+        None => ReportFormat::Json,
+        Some(raw) => ReportFormat::parse(raw).ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "format must be one of {}",
+                ReportFormat::ALL.join(", ")
+            ))
+        })?,
+    };
+    server_exists(&state, &server_id).await?;
+
+    let report = reports::build(&state.db, &server_id, q.period_end.unwrap_or_else(Utc::now))
+        .await
+        .map_err(|e| {
+            tracing::error!("weekly report failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    Ok(match format {
+        ReportFormat::Html => Html(report.render_html()).into_response(),
+        ReportFormat::Json => Json(report).into_response(),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SendReportResponse {
+    pub ok: bool,
+    pub report: WeeklyReport,
+    pub delivery: Delivery,
+}
+
+/// POST /dashboard/:server_id/reports/send
+///
+/// Send the report of the last 7 days to the schedule's destinations now, to check them. The
+/// weekly schedule is unaffected.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/reports/send",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = SendReportResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn send_report(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<SendReportResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    server_exists(&state, &server_id).await?;
+    let schedule = load_schedule(&state, &server_id).await?;
+    if schedule.webhook_url.is_none() && schedule.email_recipients.is_empty() {
+        return Err(ApiError::BadRequest(
+            "the report schedule has no webhook_url or email_recipients".to_string(),
+        ));
+    }
+
+    let report = reports::build(&state.db, &server_id, Utc::now())
+        .await
+        .map_err(|e| {
+            tracing::error!("weekly report failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    let delivery = reports::deliver(&state, &schedule, &report).await;
+    Ok(Json(SendReportResponse {
+        ok: delivery.error.is_none(),
+        report,
+        delivery,
+    }))
+}
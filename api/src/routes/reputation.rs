@@ -0,0 +1,135 @@
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::reputation::{self, PlayerReputation};
+use crate::{auth, error::ApiError, AppState};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReputationResponse {
+    pub ok: bool,
+    pub reputation: PlayerReputation,
+}
+
+/// GET /reputation/:player_uuid
+///
+/// Cross-server history for a joining player. Authenticated with the per-server token; only
+/// servers that contribute (reputation_opt_in) may query.
+#[utoipa::path(
+    get,
+    path = "/reputation/{player_uuid}",
+    tag = "plugin",
+    params(
+        ("player_uuid" = Uuid, Path, description = "Player UUID"),
+        ("X-Server-Id" = String, Header, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = ReputationResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("server_token" = [])),
+)]
+pub async fn get_reputation(
+    State(state): State<AppState>,
+    Path(player_uuid): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<ReputationResponse>, ApiError> {
+    if !state.reputation_enabled {
+        return Err(ApiError::BadRequest(
+            "reputation service is disabled".to_string(),
+        ));
+    }
+
+    let server_id = auth::authenticate_server(&state, &headers).await?;
+
+    let opted_in: Option<bool> =
+        sqlx::query_scalar("SELECT reputation_opt_in FROM public.servers WHERE id = $1")
+            .bind(&server_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!("reputation opt-in lookup failed: {:?}", e);
+                ApiError::Internal
+            })?;
+    if opted_in != Some(true) {
+        return Err(ApiError::BadRequest(
+            "server has not opted in to the reputation service".to_string(),
+        ));
+    }
+
+    let reputation = reputation::lookup(&state, player_uuid).await.map_err(|e| {
+        tracing::error!("reputation lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    Ok(Json(ReputationResponse {
+        ok: true,
+        reputation,
+    }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReputationOptInRequest {
+    pub opt_in: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReputationOptInResponse {
+    pub ok: bool,
+    pub opt_in: bool,
+}
+
+/// POST /dashboard/:server_id/reputation
+///
+/// Opt a server in or out of sharing findings with (and querying) the reputation service.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/reputation",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = ReputationOptInRequest,
+    responses(
+        (status = 200, body = ReputationOptInResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn set_reputation_opt_in(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<ReputationOptInRequest>,
+) -> Result<Json<ReputationOptInResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let updated = sqlx::query("UPDATE public.servers SET reputation_opt_in = $2 WHERE id = $1")
+        .bind(&server_id)
+        .bind(req.opt_in)
+        .execute(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("reputation opt-in update failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    tracing::info!(server_id = %server_id, opt_in = req.opt_in, "reputation opt-in changed");
+
+    Ok(Json(ReputationOptInResponse {
+        ok: true,
+        opt_in: req.opt_in,
+    }))
+}
@@ -0,0 +1,153 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::sampling;
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SamplingBatchCounts {
+    /// Batches with a player who isn't low-risk, or received while sampling was off.
+    pub full: i64,
+    pub sampled_in: i64,
+    pub sampled_out: i64,
+    /// Dispatched batches weighted by 1 / sample_rate: an estimate of the batches that full
+    /// analysis would have covered.
+    pub corrected_dispatched: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SamplingSettingsResponse {
+    pub ok: bool,
+    /// Share of low-risk batches analyzed; null when sampling is off.
+    pub sample_rate: Option<f64>,
+    /// Batches received in the last 24 hours (not quarantined).
+    pub last_24h: SamplingBatchCounts,
+}
+
+async fn settings(state: &AppState, server_id: &str) -> Result<SamplingSettingsResponse, ApiError> {
+    let sample_rate = sampling::server_rate(&state.db, server_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("sample rate lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    let rows: Vec<(Option<f64>, Option<String>, i64)> = sqlx::query_as(
+        r#"
+        select sample_rate, dispatch_status, count(*)
+        from public.batch_index
+        where server_id = $1 and received_at > now() - interval '24 hours'
+          and quarantined = false
+        group by 1, 2
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("sampling batch counts failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let mut counts = SamplingBatchCounts {
+        full: 0,
+        sampled_in: 0,
+        sampled_out: 0,
+        corrected_dispatched: 0.0,
+    };
+    for (rate, status, n) in rows {
+        match (rate, status.as_deref()) {
+            (_, Some(sampling::SAMPLED_OUT)) => counts.sampled_out += n,
+            (Some(_), _) => counts.sampled_in += n,
+            (None, _) => counts.full += n,
+        }
+        if status.as_deref() != Some(sampling::SAMPLED_OUT) {
+            counts.corrected_dispatched += n as f64 * sampling::weight(rate);
+        }
+    }
+
+    Ok(SamplingSettingsResponse {
+        ok: true,
+        sample_rate,
+        last_24h: counts,
+    })
+}
+
+/// GET /dashboard/:server_id/sampling
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/sampling",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = SamplingSettingsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_sampling_settings(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<SamplingSettingsResponse>, ApiError> {
+    Ok(Json(settings(&state, server_id.trim()).await?))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SamplingSettingsRequest {
+    /// Share (0.05-1) of low-risk batches to analyze; null turns sampling off.
+    pub sample_rate: Option<f64>,
+}
+
+/// POST /dashboard/:server_id/sampling
+///
+/// Set the share of batches analyzed when all their players are low-risk. Applies from the
+/// server's next batch.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/sampling",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = SamplingSettingsRequest,
+    responses(
+        (status = 200, body = SamplingSettingsResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn set_sampling_settings(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<SamplingSettingsRequest>,
+) -> Result<Json<SamplingSettingsResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let sample_rate = sampling::validate_rate(req.sample_rate).map_err(ApiError::BadRequest)?;
+
+    let updated = sqlx::query("UPDATE public.servers SET sample_rate = $2 WHERE id = $1")
+        .bind(&server_id)
+        .bind(sample_rate)
+        .execute(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("sampling settings update failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    tracing::info!(server_id = %server_id, sample_rate = ?sample_rate, "sampling settings changed");
+    Ok(Json(settings(&state, &server_id).await?))
+}
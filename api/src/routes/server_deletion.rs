@@ -0,0 +1,88 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::server_deletion;
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ServerDeletionResponse {
+    pub ok: bool,
+    pub server_id: String,
+    /// Null once restored.
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// From when an admin may purge the server's data (`SERVER_PURGE_GRACE_DAYS`).
+    pub purge_eligible_at: Option<DateTime<Utc>>,
+}
+
+/// DELETE /dashboard/:server_id
+///
+/// Soft-deletes a decommissioned server: its plugin's data is refused from now on (410
+/// `server_deleted`) and it is hidden from server lists. Data is kept until an admin purges it
+/// after the grace period; `POST /dashboard/:server_id/restore` undoes the deletion until then.
+#[utoipa::path(
+    delete,
+    path = "/dashboard/{server_id}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = ServerDeletionResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn delete_server(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<ServerDeletionResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let deleted_at = server_deletion::soft_delete(&state, &server_id).await?;
+    Ok(Json(ServerDeletionResponse {
+        ok: true,
+        server_id,
+        deleted_at: Some(deleted_at),
+        purge_eligible_at: Some(server_deletion::purge_eligible_at(
+            deleted_at,
+            state.server_purge_grace_days,
+        )),
+    }))
+}
+
+/// POST /dashboard/:server_id/restore
+///
+/// Undoes a soft delete, unless a purge is already queued or running (409).
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/restore",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = ServerDeletionResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn restore_server(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<ServerDeletionResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    server_deletion::restore(&state, &server_id).await?;
+    Ok(Json(ServerDeletionResponse {
+        ok: true,
+        server_id,
+        deleted_at: None,
+        purge_eligible_at: None,
+    }))
+}
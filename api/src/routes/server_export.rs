@@ -0,0 +1,141 @@
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::server_export::{self, ServerExport};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct ServerExportRequest {
+    /// Add the stored raw batches (up to 256 MiB).
+    #[serde(default)]
+    pub include_raw_batches: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ServerExportResponse {
+    pub ok: bool,
+    pub export: ServerExport,
+    /// Presigned object store link (valid 24h), once completed; S3 storage only.
+    pub download_url: Option<String>,
+    /// Dashboard path the archive can be downloaded from, once completed.
+    pub download_path: Option<String>,
+}
+
+async fn respond(state: &AppState, export: ServerExport) -> Json<ServerExportResponse> {
+    let download_url = server_export::download_url(state, &export).await;
+    let download_path = (export.status == "completed").then(|| {
+        format!(
+            "/dashboard/{}/exports/{}/download",
+            export.server_id, export.id
+        )
+    });
+    Json(ServerExportResponse {
+        ok: true,
+        export,
+        download_url,
+        download_path,
+    })
+}
+
+/// POST /dashboard/:server_id/export
+///
+/// Start a takeout archive of the server (tar.gz of findings, players, module config,
+/// observations and optionally raw batches), e.g. to move off the hosted service or keep a
+/// local backup. Built in the background; poll `GET /dashboard/:server_id/exports/:export_id`.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/export",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = Option<ServerExportRequest>,
+    responses(
+        (status = 200, body = ServerExportResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "An export of the server is already running", body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn create_server_export(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    req: Option<Json<ServerExportRequest>>,
+) -> Result<Json<ServerExportResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let req = req.map(|Json(r)| r).unwrap_or_default();
+    let export = server_export::start(&state, &server_id, req.include_raw_batches).await?;
+    Ok(respond(&state, export).await)
+}
+
+/// GET /dashboard/:server_id/exports/:export_id
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/exports/{export_id}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("export_id" = Uuid, Path, description = "Export id"),
+    ),
+    responses(
+        (status = 200, body = ServerExportResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn get_server_export(
+    State(state): State<AppState>,
+    Path((server_id, export_id)): Path<(String, Uuid)>,
+) -> Result<Json<ServerExportResponse>, ApiError> {
+    let export = server_export::get(&state, server_id.trim(), export_id).await?;
+    Ok(respond(&state, export).await)
+}
+
+/// GET /dashboard/:server_id/exports/:export_id/download
+///
+/// Download a completed export archive (`application/gzip`, a gzipped tar).
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/exports/{export_id}/download",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("export_id" = Uuid, Path, description = "Export id"),
+    ),
+    responses(
+        (status = 200, description = "Gzipped tar archive", content_type = "application/gzip"),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "Export not completed", body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn download_server_export(
+    State(state): State<AppState>,
+    Path((server_id, export_id)): Path<(String, Uuid)>,
+) -> Result<Response, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let bytes = server_export::load(&state, &server_id, export_id).await?;
+    let disposition = format!(
+        "attachment; filename=\"{}-export-{}.tar.gz\"",
+        server_id, export_id
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        bytes,
+    )
+        .into_response())
+}
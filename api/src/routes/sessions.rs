@@ -0,0 +1,173 @@
+//! Player session dashboard endpoint (`/dashboard/:server_id/sessions`), backed by the sessions
+//! stitched at ingest (see `crate::player_sessions`).
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{error::ApiError, player_sessions::SESSION_GAP_MS, AppState};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PlayerSession {
+    pub id: Uuid,
+    pub player_uuid: Uuid,
+    pub player_name: Option<String>,
+    pub plugin_session_id: String,
+    pub started_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    /// Set when the session was closed (quit, rejoin, restart, or timeout noticed at the next sighting).
+    pub ended_at: Option<DateTime<Utc>>,
+    /// quit | timeout | rejoin | restart; `timeout` is also reported for open sessions unseen
+    /// for longer than the session gap.
+    pub end_reason: Option<String>,
+    /// Seen within the session gap and not ended.
+    pub online: bool,
+    pub duration_seconds: i64,
+    /// Findings for the player raised during the session.
+    pub findings: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SessionsResponse {
+    pub ok: bool,
+    pub sessions: Vec<PlayerSession>,
+    pub total: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SessionsQuery {
+    pub player_uuid: Option<Uuid>,
+    /// Only sessions that are still online.
+    pub online: Option<bool>,
+    /// Default 50, max 200.
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// GET /dashboard/:server_id/sessions
+///
+/// Player sessions on the server, most recently active first, with duration and finding counts.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/sessions",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        SessionsQuery,
+    ),
+    responses(
+        (status = 200, body = SessionsResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Query(q): Query<SessionsQuery>,
+) -> Result<Json<SessionsResponse>, ApiError> {
+    let limit = q.limit.unwrap_or(50).clamp(1, 200);
+    let offset = q.offset.unwrap_or(0).max(0);
+
+    // Findings arrive after the batch that triggered them, so the window is padded by the gap.
+    let rows: Vec<(
+        Uuid,
+        Uuid,
+        Option<String>,
+        String,
+        DateTime<Utc>,
+        DateTime<Utc>,
+        Option<DateTime<Utc>>,
+        Option<String>,
+        bool,
+        i64,
+        i64,
+        i64,
+    )> = sqlx::query_as(
+        r#"
+        with s as (
+            select ps.*,
+                   ps.ended_at is null
+                       and ps.last_seen_at > now() - make_interval(secs => $2) as online
+            from public.player_sessions ps
+            where ps.server_id = $1
+              and ($3::uuid is null or ps.player_uuid = $3)
+        )
+        select s.id, s.player_uuid, p.username, s.plugin_session_id, s.started_at,
+               s.last_seen_at, s.ended_at,
+               coalesce(s.end_reason, case when not s.online then 'timeout' end),
+               s.online,
+               extract(epoch from coalesce(s.ended_at, s.last_seen_at) - s.started_at)::bigint,
+               (select count(*) from public.findings f
+                where f.server_id = s.server_id
+                  and f.player_uuid = s.player_uuid
+                  and (f.session_id is null or f.session_id = s.plugin_session_id)
+                  and f.last_seen_at >= s.started_at
+                  and f.created_at <= coalesce(s.ended_at, s.last_seen_at)
+                                      + make_interval(secs => $2)),
+               count(*) over ()
+        from s
+        left join public.players p on p.uuid = s.player_uuid
+        where $4::bool is null or s.online = $4
+        order by s.last_seen_at desc
+        limit $5 offset $6
+        "#,
+    )
+    .bind(&server_id)
+    .bind(SESSION_GAP_MS as f64 / 1000.0)
+    .bind(q.player_uuid)
+    .bind(q.online)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("sessions query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let total = rows.first().map(|r| r.11).unwrap_or(0);
+    let sessions = rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                player_uuid,
+                player_name,
+                plugin_session_id,
+                started_at,
+                last_seen_at,
+                ended_at,
+                end_reason,
+                online,
+                duration_seconds,
+                findings,
+                _,
+            )| PlayerSession {
+                id,
+                player_uuid,
+                player_name,
+                plugin_session_id,
+                started_at,
+                last_seen_at,
+                ended_at,
+                end_reason,
+                online,
+                duration_seconds,
+                findings,
+            },
+        )
+        .collect();
+
+    Ok(Json(SessionsResponse {
+        ok: true,
+        sessions,
+        total,
+    }))
+}
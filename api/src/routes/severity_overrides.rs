@@ -0,0 +1,174 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::severity_overrides::{self, SeverityOverride};
+use crate::{error::ApiError, AppState};
+
+const MAX_DETECTOR_NAME_LEN: usize = 128;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SeverityOverridesResponse {
+    pub ok: bool,
+    pub overrides: Vec<SeverityOverride>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetSeverityOverrideRequest {
+    /// critical | high | medium | low | info
+    pub severity: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeleteSeverityOverrideResponse {
+    pub ok: bool,
+}
+
+fn detector_name(raw: &str) -> Result<String, ApiError> {
+    let name = raw.trim();
+    if name.is_empty() || name.len() > MAX_DETECTOR_NAME_LEN {
+        return Err(ApiError::BadRequest(format!(
+            "detector_name must be 1-{} characters",
+            MAX_DETECTOR_NAME_LEN
+        )));
+    }
+    Ok(name.to_string())
+}
+
+/// GET /dashboard/:server_id/severity-overrides
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/severity-overrides",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = SeverityOverridesResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_severity_overrides(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<SeverityOverridesResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let overrides = severity_overrides::list(&state.db, &server_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("severity overrides query failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    Ok(Json(SeverityOverridesResponse {
+        ok: true,
+        overrides,
+    }))
+}
+
+/// POST /dashboard/:server_id/severity-overrides/:detector_name
+///
+/// Store the detector's findings on this server with the given severity instead of the one the
+/// module reports. Applies to findings received from then on.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/severity-overrides/{detector_name}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("detector_name" = String, Path, description = "Detector name, as reported in findings"),
+    ),
+    request_body = SetSeverityOverrideRequest,
+    responses(
+        (status = 200, body = SeverityOverride),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn set_severity_override(
+    State(state): State<AppState>,
+    Path((server_id, detector_name_raw)): Path<(String, String)>,
+    Json(req): Json<SetSeverityOverrideRequest>,
+) -> Result<Json<SeverityOverride>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let detector_name = detector_name(&detector_name_raw)?;
+    let Some(severity) = severity_overrides::parse_severity(&req.severity) else {
+        return Err(ApiError::BadRequest(format!(
+            "severity must be one of {}",
+            severity_overrides::SEVERITIES.join(", ")
+        )));
+    };
+
+    let exists: Option<String> = sqlx::query_scalar("select id from public.servers where id = $1")
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("severity override server lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    let saved = severity_overrides::upsert(&state.db, &server_id, &detector_name, severity)
+        .await
+        .map_err(|e| {
+            tracing::error!("severity override save failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    tracing::info!(server_id = %server_id, detector = %detector_name, severity, "severity override saved");
+    Ok(Json(saved))
+}
+
+/// DELETE /dashboard/:server_id/severity-overrides/:detector_name
+///
+/// Go back to the severity the module reports.
+#[utoipa::path(
+    delete,
+    path = "/dashboard/{server_id}/severity-overrides/{detector_name}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("detector_name" = String, Path, description = "Detector name"),
+    ),
+    responses(
+        (status = 200, body = DeleteSeverityOverrideResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn delete_severity_override(
+    State(state): State<AppState>,
+    Path((server_id, detector_name_raw)): Path<(String, String)>,
+) -> Result<Json<DeleteSeverityOverrideResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let detector_name = detector_name(&detector_name_raw)?;
+
+    let deleted = severity_overrides::delete(&state.db, &server_id, &detector_name)
+        .await
+        .map_err(|e| {
+            tracing::error!("severity override delete failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if !deleted {
+        return Err(ApiError::NotFound(format!(
+            "no severity override for {}",
+            detector_name
+        )));
+    }
+
+    Ok(Json(DeleteSeverityOverrideResponse { ok: true }))
+}
@@ -0,0 +1,170 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::shadow::{self, ShadowDetectorSummary, ShadowFinding};
+use crate::{error::ApiError, AppState};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ModuleModeRequest {
+    /// `active` (findings go live) or `shadow` (findings are only kept in `shadow_findings`).
+    pub mode: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ModuleModeResponse {
+    pub ok: bool,
+    pub mode: String,
+}
+
+/// POST /dashboard/:server_id/modules/:module_id/mode
+///
+/// Switch a module between active and shadow mode. Only findings reported afterwards are
+/// affected.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/modules/{module_id}/mode",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("module_id" = Uuid, Path, description = "Module id"),
+    ),
+    request_body = ModuleModeRequest,
+    responses(
+        (status = 200, body = ModuleModeResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn set_module_mode(
+    State(state): State<AppState>,
+    Path((server_id, module_id)): Path<(String, Uuid)>,
+    Json(req): Json<ModuleModeRequest>,
+) -> Result<Json<ModuleModeResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let mode = shadow::parse_mode(&req.mode)
+        .ok_or_else(|| ApiError::BadRequest("mode must be active or shadow".to_string()))?;
+
+    let updated = sqlx::query(
+        "update public.server_modules set mode = $3, updated_at = now() where id = $1 and server_id = $2",
+    )
+    .bind(module_id)
+    .bind(&server_id)
+    .bind(mode)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("module mode update failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    if updated.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "module {} not found",
+            module_id
+        )));
+    }
+
+    tracing::info!(server_id = %server_id, module_id = %module_id, mode = mode, "module mode changed");
+
+    Ok(Json(ModuleModeResponse {
+        ok: true,
+        mode: mode.to_string(),
+    }))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ShadowFindingsQuery {
+    /// Hours back from now (default 24, at most the 30 days kept).
+    pub hours: Option<i32>,
+    /// Findings listed (default 50, at most 500); the summary covers all of them.
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ShadowFindingsResponse {
+    pub ok: bool,
+    pub module_id: Uuid,
+    /// active | shadow
+    pub mode: String,
+    pub summary: Vec<ShadowDetectorSummary>,
+    /// Newest first.
+    pub findings: Vec<ShadowFinding>,
+}
+
+/// GET /dashboard/:server_id/modules/:module_id/shadow-findings
+///
+/// Findings a module reported while in shadow mode, with per-detector counts.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/modules/{module_id}/shadow-findings",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("module_id" = Uuid, Path, description = "Module id"),
+        ShadowFindingsQuery,
+    ),
+    responses(
+        (status = 200, body = ShadowFindingsResponse),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_shadow_findings(
+    State(state): State<AppState>,
+    Path((server_id, module_id)): Path<(String, Uuid)>,
+    Query(params): Query<ShadowFindingsQuery>,
+) -> Result<Json<ShadowFindingsResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let hours = params
+        .hours
+        .unwrap_or(24)
+        .clamp(1, shadow::RETENTION_DAYS * 24);
+    let limit = params.limit.unwrap_or(50).clamp(1, 500);
+
+    let mode: Option<String> = sqlx::query_scalar(
+        "select mode from public.server_modules where id = $1 and server_id = $2",
+    )
+    .bind(module_id)
+    .bind(&server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("shadow findings module lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    let Some(mode) = mode else {
+        return Err(ApiError::NotFound(format!(
+            "module {} not found",
+            module_id
+        )));
+    };
+
+    let summary = shadow::summarize(&state, module_id, hours)
+        .await
+        .map_err(|e| {
+            tracing::error!("shadow findings summary failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    let findings = shadow::list(&state, module_id, hours, limit)
+        .await
+        .map_err(|e| {
+            tracing::error!("shadow findings query failed: {:?}", e);
+            ApiError::Internal
+        })?;
+
+    Ok(Json(ShadowFindingsResponse {
+        ok: true,
+        module_id,
+        mode,
+        summary,
+        findings,
+    }))
+}
@@ -0,0 +1,32 @@
+use axum::{extract::State, Json};
+
+use crate::service_status::{self, PublicStatusResponse};
+use crate::AppState;
+
+const CACHE_KEY: &str = "public_status";
+
+/// GET /status/public
+///
+/// Anonymized service health for a public status page (no auth): ingest success rate, dispatch
+/// backlog and background task liveness.
+#[utoipa::path(
+    get,
+    path = "/status/public",
+    tag = "plugin",
+    responses((status = 200, body = PublicStatusResponse)),
+)]
+pub async fn public_status(State(state): State<AppState>) -> Json<PublicStatusResponse> {
+    if let Some(cached) = state.shared.get_json(CACHE_KEY).await {
+        return Json(cached);
+    }
+    let resp = service_status::current(&state).await;
+    state
+        .shared
+        .set_json(
+            CACHE_KEY,
+            &resp,
+            std::time::Duration::from_secs(service_status::CACHE_SECONDS),
+        )
+        .await;
+    Json(resp)
+}
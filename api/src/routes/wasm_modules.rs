@@ -0,0 +1,209 @@
+//! Uploads of sandboxed WASM modules (see `wasm_runtime`).
+
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{error::ApiError, routes::modules::ServerModule, wasm_runtime, AppState};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UploadWasmModuleQuery {
+    pub name: String,
+    /// Transform applied before the module sees the batch (default: raw_ndjson_gz).
+    pub transform: Option<String>,
+    pub fuel: Option<u64>,
+    pub memory_mb: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WasmModuleInfo {
+    pub id: Uuid,
+    pub sha256: String,
+    pub size_bytes: usize,
+    pub fuel_limit: u64,
+    pub memory_limit_bytes: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UploadWasmModuleResponse {
+    pub ok: bool,
+    pub module: ServerModule,
+    pub wasm: WasmModuleInfo,
+}
+
+/// POST /dashboard/:server_id/modules/wasm?name=...&transform=...&fuel=...&memory_mb=...
+///
+/// Body is the raw `.wasm` binary. Validates the blob against the guest ABI, stores it, and
+/// points the server's module `name` at it (`wasm://<id>`), enabled.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/modules/wasm",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        UploadWasmModuleQuery,
+    ),
+    request_body(content = Vec<u8>, content_type = "application/wasm"),
+    responses(
+        (status = 200, body = UploadWasmModuleResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn upload_wasm_module(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Query(q): Query<UploadWasmModuleQuery>,
+    body: Bytes,
+) -> Result<Json<UploadWasmModuleResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+    let name = q.name.trim().to_string();
+    if server_id.is_empty() || name.is_empty() {
+        return Err(ApiError::BadRequest(
+            "server_id and name are required".to_string(),
+        ));
+    }
+    if body.is_empty() || body.len() > wasm_runtime::MAX_WASM_BYTES {
+        return Err(ApiError::BadRequest(format!(
+            "wasm body must be 1..={} bytes",
+            wasm_runtime::MAX_WASM_BYTES
+        )));
+    }
+
+    let transform = q
+        .transform
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or("raw_ndjson_gz")
+        .to_string();
+    let fuel = q
+        .fuel
+        .unwrap_or(wasm_runtime::DEFAULT_FUEL)
+        .min(wasm_runtime::MAX_FUEL);
+    let memory_bytes = q
+        .memory_mb
+        .map(|mb| mb.saturating_mul(1024 * 1024))
+        .unwrap_or(wasm_runtime::DEFAULT_MEMORY_BYTES)
+        .min(wasm_runtime::MAX_MEMORY_BYTES);
+
+    let wasm = body.to_vec();
+    let validation = {
+        let wasm = wasm.clone();
+        tokio::task::spawn_blocking(move || wasm_runtime::validate(&wasm))
+            .await
+            .map_err(|e| {
+                tracing::error!("wasm validation task failed: {:?}", e);
+                ApiError::Internal
+            })?
+    };
+    if let Err(e) = validation {
+        return Err(ApiError::BadRequest(format!("invalid wasm module: {}", e)));
+    }
+
+    let sha256 = wasm_runtime::sha256_hex(&wasm);
+    let (wasm_id,): (Uuid,) = sqlx::query_as(
+        r#"
+        insert into public.wasm_modules
+            (server_id, name, sha256, size_bytes, wasm, fuel_limit, memory_limit_bytes)
+        values ($1, $2, $3, $4, $5, $6, $7)
+        returning id
+        "#,
+    )
+    .bind(&server_id)
+    .bind(&name)
+    .bind(&sha256)
+    .bind(wasm.len() as i32)
+    .bind(&wasm)
+    .bind(fuel as i64)
+    .bind(memory_bytes as i64)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("wasm module insert failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let module = sqlx::query_as::<_, ServerModule>(
+        r#"
+        insert into public.server_modules
+            (server_id, name, base_url, enabled, transform, created_at, updated_at)
+        values
+            ($1, $2, $3, true, $4, now(), now())
+        on conflict (server_id, name) do update set
+            base_url = excluded.base_url,
+            backup_base_urls = '{}',
+            canary_base_url = null,
+            enabled = true,
+            transform = excluded.transform,
+            updated_at = now()
+        returning
+            id,
+            server_id,
+            name,
+            base_url,
+            backup_base_urls,
+            canary_base_url,
+            canary_percent,
+            mode,
+            enabled,
+            transform,
+            last_healthcheck_ok,
+            last_error,
+            content_encoding,
+            processing_deadline_seconds
+        "#,
+    )
+    .bind(&server_id)
+    .bind(&name)
+    .bind(format!("{}{}", wasm_runtime::WASM_SCHEME, wasm_id))
+    .bind(&transform)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("wasm server module upsert failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    // Previous uploads for this module are no longer referenced.
+    let replaced: Vec<Uuid> = sqlx::query_scalar(
+        "delete from public.wasm_modules where server_id = $1 and name = $2 and id <> $3 \
+         returning id",
+    )
+    .bind(&server_id)
+    .bind(&name)
+    .bind(wasm_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+    for id in replaced {
+        wasm_runtime::forget(id);
+    }
+
+    tracing::info!(
+        server_id = %server_id,
+        module = %name,
+        sha256 = %sha256,
+        size_bytes = wasm.len(),
+        "wasm module uploaded"
+    );
+
+    Ok(Json(UploadWasmModuleResponse {
+        ok: true,
+        module,
+        wasm: WasmModuleInfo {
+            id: wasm_id,
+            sha256,
+            size_bytes: wasm.len(),
+            fuel_limit: fuel,
+            memory_limit_bytes: memory_bytes,
+        },
+    }))
+}
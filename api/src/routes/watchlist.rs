@@ -0,0 +1,253 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{error::ApiError, watchlist, AppState};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WatchlistEntry {
+    pub player_uuid: Uuid,
+    pub player_name: Option<String>,
+    pub reason: Option<String>,
+    pub added_by: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WatchlistResponse {
+    pub ok: bool,
+    pub players: Vec<WatchlistEntry>,
+}
+
+/// GET /dashboard/:server_id/watchlist
+///
+/// Active (unexpired) watchlist entries, newest first.
+#[utoipa::path(
+    get,
+    path = "/dashboard/{server_id}/watchlist",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    responses(
+        (status = 200, body = WatchlistResponse),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn list_watchlist(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+) -> Result<Json<WatchlistResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let rows: Vec<(
+        Uuid,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<DateTime<Utc>>,
+        DateTime<Utc>,
+    )> = sqlx::query_as(
+        r#"
+        select w.player_uuid, p.username, w.reason, w.added_by, w.expires_at, w.created_at
+        from public.server_watchlist w
+        left join public.players p on p.uuid = w.player_uuid
+        where w.server_id = $1 and (w.expires_at is null or w.expires_at > now())
+        order by w.created_at desc
+        "#,
+    )
+    .bind(&server_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("watchlist query failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let players = rows
+        .into_iter()
+        .map(
+            |(player_uuid, player_name, reason, added_by, expires_at, created_at)| WatchlistEntry {
+                player_uuid,
+                player_name,
+                reason,
+                added_by,
+                expires_at,
+                created_at,
+            },
+        )
+        .collect();
+
+    Ok(Json(WatchlistResponse { ok: true, players }))
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AddToWatchlistRequest {
+    pub player_uuid: Uuid,
+    pub reason: Option<String>,
+    pub added_by: Option<String>,
+    /// Omit to watch until removed.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WatchlistUpdateResponse {
+    pub ok: bool,
+    pub player_uuid: Uuid,
+}
+
+/// POST /dashboard/:server_id/watchlist
+///
+/// Add (or update) a watchlisted player and push the new set to the plugin.
+#[utoipa::path(
+    post,
+    path = "/dashboard/{server_id}/watchlist",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+    ),
+    request_body = AddToWatchlistRequest,
+    responses(
+        (status = 200, body = WatchlistUpdateResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn add_to_watchlist(
+    State(state): State<AppState>,
+    Path(server_id): Path<String>,
+    Json(req): Json<AddToWatchlistRequest>,
+) -> Result<Json<WatchlistUpdateResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    if let Some(expires_at) = req.expires_at {
+        if expires_at <= Utc::now() {
+            return Err(ApiError::BadRequest(
+                "expires_at must be in the future".to_string(),
+            ));
+        }
+    }
+
+    let exists: Option<(String,)> = sqlx::query_as("SELECT id FROM public.servers WHERE id = $1")
+        .bind(&server_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            tracing::error!("watchlist server lookup failed: {:?}", e);
+            ApiError::Internal
+        })?;
+    if exists.is_none() {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+
+    sqlx::query(
+        r#"
+        insert into public.server_watchlist (server_id, player_uuid, reason, added_by, expires_at)
+        values ($1, $2, $3, $4, $5)
+        on conflict (server_id, player_uuid) do update set
+            reason = excluded.reason,
+            added_by = excluded.added_by,
+            expires_at = excluded.expires_at
+        "#,
+    )
+    .bind(&server_id)
+    .bind(req.player_uuid)
+    .bind(
+        req.reason
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty()),
+    )
+    .bind(
+        req.added_by
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty()),
+    )
+    .bind(req.expires_at)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("watchlist insert failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    sync(&state, &server_id).await?;
+
+    tracing::info!(server_id = %server_id, player_uuid = %req.player_uuid, "player watchlisted");
+
+    Ok(Json(WatchlistUpdateResponse {
+        ok: true,
+        player_uuid: req.player_uuid,
+    }))
+}
+
+/// DELETE /dashboard/:server_id/watchlist/:player_uuid
+#[utoipa::path(
+    delete,
+    path = "/dashboard/{server_id}/watchlist/{player_uuid}",
+    tag = "dashboard",
+    params(
+        ("server_id" = String, Path, description = "Server id"),
+        ("player_uuid" = Uuid, Path, description = "Player UUID"),
+    ),
+    responses(
+        (status = 200, body = WatchlistUpdateResponse),
+        (status = 400, body = ErrorBody),
+        (status = 401, body = ErrorBody),
+    ),
+    security(("dashboard_token" = [])),
+)]
+pub async fn remove_from_watchlist(
+    State(state): State<AppState>,
+    Path((server_id, player_uuid)): Path<(String, Uuid)>,
+) -> Result<Json<WatchlistUpdateResponse>, ApiError> {
+    let server_id = server_id.trim().to_string();
+
+    let deleted = sqlx::query(
+        "DELETE FROM public.server_watchlist WHERE server_id = $1 AND player_uuid = $2",
+    )
+    .bind(&server_id)
+    .bind(player_uuid)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("watchlist delete failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    if deleted.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "player {} is not on the watchlist",
+            player_uuid
+        )));
+    }
+
+    sync(&state, &server_id).await?;
+
+    tracing::info!(server_id = %server_id, player_uuid = %player_uuid, "player removed from watchlist");
+
+    Ok(Json(WatchlistUpdateResponse {
+        ok: true,
+        player_uuid,
+    }))
+}
+
+async fn sync(state: &AppState, server_id: &str) -> Result<(), ApiError> {
+    watchlist::sync_to_plugin(&state.db, server_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("watchlist sync enqueue failed: {:?}", e);
+            ApiError::Internal
+        })
+}
@@ -103,15 +103,86 @@ impl ObjectStore {
         server_id: &str,
         session_id: &str,
         batch_id: &uuid::Uuid,
+        data: bytes::Bytes,
+    ) -> anyhow::Result<String> {
+        let key = Self::batch_key(server_id, session_id, batch_id).ok_or_else(|| {
+            anyhow::anyhow!("Invalid server_id or session_id: sanitizes to empty string")
+        })?;
+        self.put_object(key, data, "application/x-ndjson").await
+    }
+
+    /// Generate the object key for an evidence bundle (see `evidence_bundle`).
+    ///
+    /// Format: `bundles/{server_id}/{finding_id}/{bundle_id}.json.gz`. Bundles live outside
+    /// `events/`, so the batch TTL cleanup leaves them alone.
+    pub fn bundle_key(
+        server_id: &str,
+        finding_id: &uuid::Uuid,
+        bundle_id: &uuid::Uuid,
+    ) -> Option<String> {
+        let safe_server_id = Self::sanitize_path_component(server_id)?;
+        Some(format!(
+            "bundles/{}/{}/{}.json.gz",
+            safe_server_id, finding_id, bundle_id
+        ))
+    }
+
+    /// Upload a gzipped JSON evidence bundle; returns the object key.
+    pub async fn put_bundle(
+        &self,
+        server_id: &str,
+        finding_id: &uuid::Uuid,
+        bundle_id: &uuid::Uuid,
         data: Vec<u8>,
     ) -> anyhow::Result<String> {
-        let key = Self::batch_key(server_id, session_id, batch_id)
-            .ok_or_else(|| anyhow::anyhow!("Invalid server_id or session_id: sanitizes to empty string"))?;
+        let key = Self::bundle_key(server_id, finding_id, bundle_id)
+            .ok_or_else(|| anyhow::anyhow!("Invalid server_id: sanitizes to empty string"))?;
+        self.put_object(key, data, "application/gzip").await
+    }
 
+    /// Generate the object key for a server export archive (see `server_export`).
+    ///
+    /// Format: `exports/{server_id}/{export_id}.tar.gz`, outside the batch TTL cleanup.
+    pub fn export_key(server_id: &str, export_id: &uuid::Uuid) -> Option<String> {
+        let safe_server_id = Self::sanitize_path_component(server_id)?;
+        Some(format!("exports/{}/{}.tar.gz", safe_server_id, export_id))
+    }
+
+    /// Upload a server export archive under `key`.
+    pub async fn put_export(&self, key: &str, data: Vec<u8>) -> anyhow::Result<()> {
+        self.put_object(key.to_string(), data, "application/gzip")
+            .await?;
+        Ok(())
+    }
+
+    /// A time-limited download URL for an object; `None` for the local store, whose objects are
+    /// only reachable through the API.
+    pub async fn presign_get(&self, key: &str, expiry_secs: u32) -> anyhow::Result<Option<String>> {
+        match self {
+            ObjectStore::S3 { bucket } => {
+                Ok(Some(bucket.presign_get(key, expiry_secs, None).await?))
+            }
+            ObjectStore::Local { .. } => Ok(None),
+        }
+    }
+
+    /// Overwrite a stored batch in place (e.g. after scrubbing a player's records from it).
+    pub async fn rewrite_batch(&self, key: &str, data: Vec<u8>) -> anyhow::Result<()> {
+        self.put_object(key.to_string(), data, "application/x-ndjson")
+            .await?;
+        Ok(())
+    }
+
+    async fn put_object(
+        &self,
+        key: String,
+        data: impl AsRef<[u8]>,
+        content_type: &str,
+    ) -> anyhow::Result<String> {
         match self {
             ObjectStore::S3 { bucket } => {
                 bucket
-                    .put_object_with_content_type(&key, &data, "application/x-ndjson")
+                    .put_object_with_content_type(&key, data.as_ref(), content_type)
                     .await?;
                 Ok(key)
             }
@@ -126,8 +197,7 @@ impl ObjectStore {
         }
     }
 
-    /// Retrieve a batch from object storage (for replay/debugging).
-    #[allow(dead_code)]
+    /// Retrieve a batch (or any stored object) from object storage (for replay/debugging).
     pub async fn get_batch(&self, key: &str) -> anyhow::Result<Vec<u8>> {
         match self {
             ObjectStore::S3 { bucket } => {
@@ -141,4 +211,78 @@ impl ObjectStore {
             }
         }
     }
+
+    /// Key prefixes holding a server's objects: raw batches, evidence bundles and exports.
+    pub fn server_prefixes(server_id: &str) -> Option<[String; 3]> {
+        let safe_server_id = Self::sanitize_path_component(server_id)?;
+        Some([
+            format!("events/{}/", safe_server_id),
+            format!("bundles/{}/", safe_server_id),
+            format!("exports/{}/", safe_server_id),
+        ])
+    }
+
+    /// Key prefix holding a finding's evidence bundles.
+    pub fn finding_bundles_prefix(server_id: &str, finding_id: &uuid::Uuid) -> Option<String> {
+        let safe_server_id = Self::sanitize_path_component(server_id)?;
+        Some(format!("bundles/{}/{}/", safe_server_id, finding_id))
+    }
+
+    /// Delete every object under `prefix` (including watchlist-retained ones); returns how many
+    /// were deleted.
+    pub async fn delete_prefix(&self, prefix: &str) -> anyhow::Result<u64> {
+        match self {
+            ObjectStore::S3 { bucket } => {
+                let mut deleted = 0;
+                for page in bucket.list(prefix.to_string(), None).await? {
+                    for object in page.contents {
+                        bucket.delete_object(&object.key).await?;
+                        deleted += 1;
+                    }
+                }
+                Ok(deleted)
+            }
+            ObjectStore::Local { root } => {
+                let dir = root.join(prefix);
+                tokio::task::spawn_blocking(move || {
+                    if !dir.exists() {
+                        return Ok(0);
+                    }
+                    let files = count_files(&dir)?;
+                    std::fs::remove_dir_all(&dir)?;
+                    Ok(files)
+                })
+                .await?
+            }
+        }
+    }
+
+    /// Mark a batch as exempt from TTL cleanup (watchlisted players).
+    ///
+    /// Local stores consult `batch_index.retain_until` during cleanup; on S3 the object is
+    /// tagged `aac-retain=watchlist` so bucket lifecycle rules can exclude it.
+    pub async fn mark_retained(&self, key: &str) -> anyhow::Result<()> {
+        match self {
+            ObjectStore::S3 { bucket } => {
+                bucket
+                    .put_object_tagging(key, &[("aac-retain", "watchlist")])
+                    .await?;
+                Ok(())
+            }
+            ObjectStore::Local { .. } => Ok(()),
+        }
+    }
+}
+
+fn count_files(dir: &std::path::Path) -> std::io::Result<u64> {
+    let mut files = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            files += count_files(&entry.path())?;
+        } else {
+            files += 1;
+        }
+    }
+    Ok(files)
 }
@@ -0,0 +1,168 @@
+//! Sampling batches of low-risk players.
+//!
+//! Servers that set a `sample_rate` (`POST /dashboard/:server_id/sampling`) only analyze that
+//! share of the batches in which every player is low-risk: no findings on the server in the
+//! last [`LOOKBACK_DAYS`] (dismissed and false positives aside), not watchlisted, and a
+//! cross-server reputation below [`MAX_REPUTATION`]. A batch with any other player is always
+//! analyzed in full. Quarantined batches are never dispatched, so they are never sampled.
+//!
+//! Sampled-out batches are still stored and used for player tracking, but skip module dispatch
+//! (`dispatch_status = 'sampled_out'`; the shedding recovery job leaves them alone). Every batch
+//! the sampling applied to records the rate in `batch_index.sample_rate`, so counts over
+//! dispatched batches can be corrected by [`weight`]. The decision is derived from the batch id,
+//! so it is reproducible.
+
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader};
+
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub const SAMPLED_OUT: &str = "sampled_out";
+pub const LOOKBACK_DAYS: i32 = 30;
+/// Reputation score (0-100) from which a player is no longer low-risk.
+pub const MAX_REPUTATION: f64 = 20.0;
+/// Lowest accepted rate; below it too little is analyzed to catch anyone.
+pub const MIN_RATE: f64 = 0.05;
+
+/// How a batch was analyzed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decision {
+    /// Sampling is off for the server, or the batch has a player who isn't low-risk.
+    Full,
+    /// Low-risk batch picked for analysis at this rate.
+    SampledIn(f64),
+    /// Low-risk batch stored without analysis.
+    SampledOut(f64),
+}
+
+impl Decision {
+    /// `batch_index.sample_rate`: the rate when sampling applied to the batch.
+    pub fn sample_rate(&self) -> Option<f64> {
+        match *self {
+            Decision::Full => None,
+            Decision::SampledIn(rate) | Decision::SampledOut(rate) => Some(rate),
+        }
+    }
+
+    pub fn is_sampled_out(&self) -> bool {
+        matches!(self, Decision::SampledOut(_))
+    }
+}
+
+/// Check a rate from the dashboard (null turns sampling off).
+pub fn validate_rate(rate: Option<f64>) -> Result<Option<f64>, String> {
+    match rate {
+        None => Ok(None),
+        Some(r) if r.is_finite() && (MIN_RATE..=1.0).contains(&r) => Ok(Some(r)),
+        Some(r) => Err(format!(
+            "sample_rate must be between {} and 1, got {}",
+            MIN_RATE, r
+        )),
+    }
+}
+
+/// Whether a low-risk batch is analyzed at `rate`; stable for a given batch id.
+pub fn keeps(batch_id: Uuid, rate: f64) -> bool {
+    // The leading bytes of a v4 id are random; the version and variant bits come later.
+    let (high, _) = batch_id.as_u64_pair();
+    (high as f64 / u64::MAX as f64) < rate
+}
+
+/// How much a dispatched batch counts for in corrected statistics.
+pub fn weight(sample_rate: Option<f64>) -> f64 {
+    match sample_rate {
+        Some(rate) if rate > 0.0 => 1.0 / rate,
+        _ => 1.0,
+    }
+}
+
+#[derive(Deserialize)]
+struct RecordPlayer<'a> {
+    #[serde(borrow, default)]
+    uuid: Option<&'a str>,
+}
+
+/// Distinct player UUIDs of a gzipped batch (the metadata line is skipped).
+pub fn batch_players(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<Uuid>> {
+    let reader = BufReader::new(GzDecoder::new(raw_gz_ndjson));
+    let mut players = HashSet::new();
+    for line in reader.lines().skip(1) {
+        let line = line?;
+        if let Ok(RecordPlayer { uuid: Some(uuid) }) = serde_json::from_str(&line) {
+            if let Ok(uuid) = uuid.parse::<Uuid>() {
+                players.insert(uuid);
+            }
+        }
+    }
+    Ok(players.into_iter().collect())
+}
+
+/// The server's sample rate; None when sampling is off.
+pub async fn server_rate(db: &PgPool, server_id: &str) -> Result<Option<f64>, sqlx::Error> {
+    let rate: Option<Option<f64>> =
+        sqlx::query_scalar("select sample_rate from public.servers where id = $1")
+            .bind(server_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(rate.flatten())
+}
+
+/// Whether every one of the players is low-risk on the server.
+pub async fn all_low_risk(
+    db: &PgPool,
+    server_id: &str,
+    players: &[Uuid],
+) -> Result<bool, sqlx::Error> {
+    if players.is_empty() {
+        return Ok(true);
+    }
+    sqlx::query_scalar(
+        r#"
+        select not exists (
+            select 1 from public.findings
+            where server_id = $1 and player_uuid = any($2)
+              and last_seen_at >= now() - make_interval(days => $3)
+              and status not in ('false_positive', 'dismissed')
+        ) and not exists (
+            select 1 from public.server_watchlist
+            where server_id = $1 and player_uuid = any($2)
+              and (expires_at is null or expires_at > now())
+        ) and not exists (
+            select 1 from public.player_reputation
+            where player_uuid = any($2) and score >= $4
+        )
+        "#,
+    )
+    .bind(server_id)
+    .bind(players)
+    .bind(LOOKBACK_DAYS)
+    .bind(MAX_REPUTATION)
+    .fetch_one(db)
+    .await
+}
+
+/// Decide how a batch with these players is analyzed at `rate`. Errors fall back to full
+/// analysis.
+pub async fn decide(
+    db: &PgPool,
+    server_id: &str,
+    batch_id: Uuid,
+    rate: f64,
+    players: &[Uuid],
+) -> Decision {
+    if rate >= 1.0 {
+        return Decision::Full;
+    }
+    match all_low_risk(db, server_id, players).await {
+        Ok(true) if keeps(batch_id, rate) => Decision::SampledIn(rate),
+        Ok(true) => Decision::SampledOut(rate),
+        Ok(false) => Decision::Full,
+        Err(e) => {
+            tracing::warn!(server_id = %server_id, "player risk lookup failed: {:?}", e);
+            Decision::Full
+        }
+    }
+}
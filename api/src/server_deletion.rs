@@ -0,0 +1,380 @@
+//! Decommissioning servers.
+//!
+//! `DELETE /dashboard/:server_id` soft-deletes a server: `servers.deleted_at` is set, the plugin
+//! gets 410 `server_deleted` from handshake and ingest (other plugin endpoints answer `gone`),
+//! and the server drops out of dashboard lists and background probes. Nothing is removed yet,
+//! and `POST /dashboard/:server_id/restore` undoes the deletion.
+//!
+//! `POST /admin/servers/:server_id/purge` queues a `server_purge_jobs` row once the server has
+//! been deleted for `SERVER_PURGE_GRACE_DAYS` (`force` skips the wait). The worker deletes the
+//! server's stored objects (raw batches, evidence bundles and exports) first, then its rows in
+//! one transaction: findings, batches, sessions and module rows, plus everything else that
+//! references the server. Jobs have no foreign key to the server, so they stay behind as the
+//! record of the purge.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::s3::ObjectStore;
+use crate::{error::ApiError, AppState};
+
+/// How often the worker looks for queued purges.
+pub const WORKER_INTERVAL_SECONDS: u64 = 60;
+
+/// A running job whose row hasn't moved for this long is considered abandoned.
+const STALE_AFTER_SECONDS: f64 = 900.0;
+
+/// When a server deleted at `deleted_at` may be purged.
+pub fn purge_eligible_at(deleted_at: DateTime<Utc>, grace_days: i64) -> DateTime<Utc> {
+    deleted_at + Duration::days(grace_days)
+}
+
+/// Why a purge can't be queued yet, if it can't.
+pub fn purge_blocker(
+    deleted_at: Option<DateTime<Utc>>,
+    grace_days: i64,
+    force: bool,
+    now: DateTime<Utc>,
+) -> Option<String> {
+    let Some(deleted_at) = deleted_at else {
+        return Some("server must be deleted before it can be purged".to_string());
+    };
+    let eligible_at = purge_eligible_at(deleted_at, grace_days);
+    (!force && now < eligible_at).then(|| {
+        format!(
+            "grace period ends at {} (pass force to purge now)",
+            eligible_at.to_rfc3339()
+        )
+    })
+}
+
+fn db_error(what: &'static str) -> impl FnOnce(sqlx::Error) -> ApiError {
+    move |e| {
+        tracing::error!("{} failed: {:?}", what, e);
+        ApiError::Internal
+    }
+}
+
+/// Soft-delete a server; deleting it again keeps the original time. Returns `deleted_at`.
+pub async fn soft_delete(state: &AppState, server_id: &str) -> Result<DateTime<Utc>, ApiError> {
+    let deleted_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+        r#"
+        update public.servers
+        set deleted_at = coalesce(deleted_at, now())
+        where id = $1
+        returning deleted_at
+        "#,
+    )
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error("server soft delete"))?;
+    let deleted_at =
+        deleted_at.ok_or_else(|| ApiError::NotFound(format!("server {} not found", server_id)))?;
+    tracing::info!(server_id = %server_id, deleted_at = %deleted_at, "server deleted");
+    Ok(deleted_at)
+}
+
+/// Undo a soft delete, unless a purge is already queued or running.
+pub async fn restore(state: &AppState, server_id: &str) -> Result<(), ApiError> {
+    let purging: bool = sqlx::query_scalar(
+        r#"
+        select exists (
+            select 1 from public.server_purge_jobs
+            where server_id = $1 and status in ('queued', 'running')
+        )
+        "#,
+    )
+    .bind(server_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(db_error("server purge lookup"))?;
+    if purging {
+        return Err(ApiError::Conflict(format!(
+            "server {} is being purged",
+            server_id
+        )));
+    }
+
+    let res = sqlx::query("update public.servers set deleted_at = null where id = $1")
+        .bind(server_id)
+        .execute(&state.db)
+        .await
+        .map_err(db_error("server restore"))?;
+    if res.rows_affected() == 0 {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+    tracing::info!(server_id = %server_id, "server restored");
+    Ok(())
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PurgeJob {
+    pub id: Uuid,
+    pub server_id: String,
+    /// queued | running | completed | failed
+    pub status: String,
+    pub objects_deleted: i64,
+    pub findings_deleted: i64,
+    pub batches_deleted: i64,
+    pub modules_deleted: i64,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+type JobRow = (
+    Uuid,
+    String,
+    String,
+    i64,
+    i64,
+    i64,
+    i64,
+    Option<String>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+);
+
+const JOB_COLUMNS: &str = "id, server_id, status, objects_deleted, findings_deleted, \
+     batches_deleted, modules_deleted, error, created_at, started_at, completed_at";
+
+fn job_from_row(row: JobRow) -> PurgeJob {
+    let (
+        id,
+        server_id,
+        status,
+        objects_deleted,
+        findings_deleted,
+        batches_deleted,
+        modules_deleted,
+        error,
+        created_at,
+        started_at,
+        completed_at,
+    ) = row;
+    PurgeJob {
+        id,
+        server_id,
+        status,
+        objects_deleted,
+        findings_deleted,
+        batches_deleted,
+        modules_deleted,
+        error,
+        created_at,
+        started_at,
+        completed_at,
+    }
+}
+
+/// Queue a purge of a deleted server; the worker picks it up on its next tick.
+pub async fn create_purge(
+    state: &AppState,
+    server_id: &str,
+    force: bool,
+) -> Result<PurgeJob, ApiError> {
+    let deleted_at: Option<Option<DateTime<Utc>>> =
+        sqlx::query_scalar("select deleted_at from public.servers where id = $1")
+            .bind(server_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(db_error("server purge lookup"))?;
+    let Some(deleted_at) = deleted_at else {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    };
+    if let Some(reason) =
+        purge_blocker(deleted_at, state.server_purge_grace_days, force, Utc::now())
+    {
+        return Err(ApiError::Conflict(reason));
+    }
+
+    let row: Option<JobRow> = sqlx::query_as(&format!(
+        r#"
+        insert into public.server_purge_jobs (server_id)
+        select $1
+        where not exists (
+            select 1 from public.server_purge_jobs
+            where server_id = $1 and status in ('queued', 'running')
+        )
+        returning {}
+        "#,
+        JOB_COLUMNS
+    ))
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error("server purge insert"))?;
+    let Some(row) = row else {
+        return Err(ApiError::Conflict(format!(
+            "a purge of server {} is already queued",
+            server_id
+        )));
+    };
+
+    let job = job_from_row(row);
+    tracing::info!(job_id = %job.id, server_id = %server_id, force, "server purge queued");
+    Ok(job)
+}
+
+pub async fn get_purge(state: &AppState, job_id: Uuid) -> Result<PurgeJob, ApiError> {
+    let row: Option<JobRow> = sqlx::query_as(&format!(
+        "select {} from public.server_purge_jobs where id = $1",
+        JOB_COLUMNS
+    ))
+    .bind(job_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error("server purge job lookup"))?;
+    row.map(job_from_row)
+        .ok_or_else(|| ApiError::NotFound(format!("purge job {} not found", job_id)))
+}
+
+#[derive(Debug, Default)]
+struct PurgeCounts {
+    objects: i64,
+    findings: i64,
+    batches: i64,
+    modules: i64,
+}
+
+/// Claim and run at most one purge (queued, or running but abandoned).
+pub async fn worker_tick(state: AppState) {
+    let claimed: Result<Option<(Uuid, String)>, _> = sqlx::query_as(
+        r#"
+        update public.server_purge_jobs
+        set status = 'running', started_at = coalesce(started_at, now()), updated_at = now()
+        where id = (
+            select id from public.server_purge_jobs
+            where status = 'queued'
+               or (status = 'running' and updated_at < now() - make_interval(secs => $1))
+            order by created_at asc
+            limit 1
+            for update skip locked
+        )
+        returning id, server_id
+        "#,
+    )
+    .bind(STALE_AFTER_SECONDS)
+    .fetch_optional(&state.db)
+    .await;
+
+    let (job_id, server_id) = match claimed {
+        Ok(Some(job)) => job,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!("server purge claim failed: {:?}", e);
+            return;
+        }
+    };
+
+    let (status, counts, error) = match purge(&state, &server_id).await {
+        Ok(counts) => ("completed", counts, None),
+        Err(e) => {
+            tracing::error!(job_id = %job_id, server_id = %server_id, "server purge failed: {:#}", e);
+            ("failed", PurgeCounts::default(), Some(format!("{:#}", e)))
+        }
+    };
+    let res = sqlx::query(
+        r#"
+        update public.server_purge_jobs
+        set status = $2, objects_deleted = $3, findings_deleted = $4, batches_deleted = $5,
+            modules_deleted = $6, error = $7, completed_at = now(), updated_at = now()
+        where id = $1
+        "#,
+    )
+    .bind(job_id)
+    .bind(status)
+    .bind(counts.objects)
+    .bind(counts.findings)
+    .bind(counts.batches)
+    .bind(counts.modules)
+    .bind(error)
+    .execute(&state.db)
+    .await;
+    if let Err(e) = res {
+        tracing::error!(job_id = %job_id, "server purge status update failed: {:?}", e);
+    }
+    tracing::info!(
+        job_id = %job_id,
+        server_id = %server_id,
+        status,
+        objects = counts.objects,
+        findings = counts.findings,
+        batches = counts.batches,
+        "server purge finished"
+    );
+}
+
+/// Tables whose rows reference the server without `on delete cascade`, in deletion order.
+const NON_CASCADING_TABLES: [&str; 4] = [
+    "sessions",
+    "detector_configs",
+    "aggregates_hourly",
+    "module_enabled_audit",
+];
+
+async fn purge(state: &AppState, server_id: &str) -> anyhow::Result<PurgeCounts> {
+    // Re-check: the server may have been restored (or purged already) since the job was queued.
+    let deleted: Option<bool> =
+        sqlx::query_scalar("select deleted_at is not null from public.servers where id = $1")
+            .bind(server_id)
+            .fetch_optional(&state.db)
+            .await?;
+    match deleted {
+        None => return Ok(PurgeCounts::default()),
+        Some(false) => anyhow::bail!("server was restored"),
+        Some(true) => {}
+    }
+
+    // Objects first: if this fails, the rows pointing at them are still there for a retry.
+    let mut counts = PurgeCounts::default();
+    let prefixes = ObjectStore::server_prefixes(server_id)
+        .ok_or_else(|| anyhow::anyhow!("server id sanitizes to an empty key prefix"))?;
+    for prefix in &prefixes {
+        counts.objects += state.object_store.delete_prefix(prefix).await? as i64;
+    }
+
+    let mut tx = state.db.begin().await?;
+    counts.findings = sqlx::query("delete from public.findings where server_id = $1")
+        .bind(server_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+    counts.batches = sqlx::query("delete from public.batch_index where server_id = $1")
+        .bind(server_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+    for table in NON_CASCADING_TABLES {
+        sqlx::query(&format!(
+            "delete from public.{} where server_id = $1",
+            table
+        ))
+        .bind(server_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    counts.modules = sqlx::query("delete from public.server_modules where server_id = $1")
+        .bind(server_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+    // Everything else referencing the server cascades.
+    sqlx::query("delete from public.servers where id = $1")
+        .bind(server_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(counts)
+}
@@ -0,0 +1,549 @@
+//! Server takeout: everything stored for a server in one archive.
+//!
+//! `POST /dashboard/:server_id/export` records a `server_exports` row and builds the archive in
+//! the background. It is a gzipped tar (`tar xzf` unpacks it) holding:
+//!
+//! - `server.json`: the server row (without its token hash)
+//! - `findings.ndjson`, `players.ndjson` (`server_players`), `observations.ndjson`,
+//!   `cases.ndjson` (`player_cases`), `notes.ndjson` (`moderator_notes`)
+//! - `modules.json`: module registrations, capture config, severity overrides and detector
+//!   configs
+//! - with `include_raw_batches`: `batches.ndjson` (`batch_index` rows) and the stored batches
+//!   as `batches/{batch_id}.ndjson.gz`, up to [`MAX_RAW_BATCH_BYTES`] in total
+//! - `manifest.json` last, with the archive version and counts
+//!
+//! Rows are exported as Postgres renders them (`to_jsonb`), so every column is kept. The archive
+//! is stored under `exports/` (outside the batch TTL, removed with the server by a purge) and
+//! downloaded through a presigned link on S3, or through the API otherwise.
+
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+use flate2::{write::GzEncoder, Compression};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::s3::ObjectStore;
+use crate::{error::ApiError, AppState};
+
+pub const ARCHIVE_VERSION: u32 = 1;
+/// Raw batches stop being added once this much has been archived.
+pub const MAX_RAW_BATCH_BYTES: u64 = 256 * 1024 * 1024;
+/// Lifetime of presigned download links.
+pub const DOWNLOAD_LINK_EXPIRY_SECONDS: u32 = 24 * 60 * 60;
+/// A running export older than this was lost to a restart and no longer blocks a new one.
+const STALE_AFTER_MINUTES: i32 = 60;
+const PAGE_SIZE: i64 = 5000;
+const TAR_BLOCK: usize = 512;
+
+/// Writes a gzipped ustar archive into memory.
+pub struct TarGzWriter {
+    gz: GzEncoder<Vec<u8>>,
+    mtime: u64,
+}
+
+impl TarGzWriter {
+    /// `mtime` (seconds since the epoch) is stamped on every entry.
+    pub fn new(mtime: u64) -> Self {
+        Self {
+            gz: GzEncoder::new(Vec::new(), Compression::default()),
+            mtime,
+        }
+    }
+
+    /// Add a regular file. Paths must be ASCII and shorter than 100 bytes.
+    pub fn append(&mut self, path: &str, data: &[u8]) -> std::io::Result<()> {
+        self.gz
+            .write_all(&tar_header(path, data.len() as u64, self.mtime)?)?;
+        self.gz.write_all(data)?;
+        let padding = (TAR_BLOCK - data.len() % TAR_BLOCK) % TAR_BLOCK;
+        self.gz.write_all(&[0u8; TAR_BLOCK][..padding])
+    }
+
+    /// End the archive (two zero blocks) and return the gzipped bytes.
+    pub fn finish(mut self) -> std::io::Result<Vec<u8>> {
+        self.gz.write_all(&[0u8; TAR_BLOCK * 2])?;
+        self.gz.finish()
+    }
+}
+
+fn tar_header(path: &str, size: u64, mtime: u64) -> std::io::Result<[u8; TAR_BLOCK]> {
+    if !path.is_ascii() || path.is_empty() || path.len() >= 100 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("unsupported tar path: {}", path),
+        ));
+    }
+    let mut header = [0u8; TAR_BLOCK];
+    let mut field = |offset: usize, value: &[u8]| {
+        header[offset..offset + value.len()].copy_from_slice(value);
+    };
+    field(0, path.as_bytes());
+    field(100, b"0000644\0");
+    field(108, b"0000000\0");
+    field(116, b"0000000\0");
+    field(124, format!("{:011o}\0", size).as_bytes());
+    field(136, format!("{:011o}\0", mtime).as_bytes());
+    field(156, b"0");
+    field(257, b"ustar\0");
+    field(263, b"00");
+    // The checksum is computed with its own field set to spaces.
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u32 = header.iter().map(|b| *b as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    Ok(header)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ServerExport {
+    pub id: Uuid,
+    pub server_id: String,
+    /// running | completed | failed
+    pub status: String,
+    pub include_raw_batches: bool,
+    pub findings: i64,
+    pub players: i64,
+    pub observations: i64,
+    pub raw_batches: i64,
+    /// Raw batches were left out after [`MAX_RAW_BATCH_BYTES`].
+    pub raw_batches_truncated: bool,
+    /// Size of the gzipped archive.
+    pub size_bytes: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+type ExportRow = (
+    Uuid,
+    String,
+    String,
+    bool,
+    i64,
+    i64,
+    i64,
+    i64,
+    bool,
+    Option<i64>,
+    Option<String>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+);
+
+const EXPORT_COLUMNS: &str = "id, server_id, status, include_raw_batches, findings, players, \
+     observations, raw_batches, raw_batches_truncated, size_bytes, error, created_at, completed_at";
+
+fn export_from_row(row: ExportRow) -> ServerExport {
+    let (
+        id,
+        server_id,
+        status,
+        include_raw_batches,
+        findings,
+        players,
+        observations,
+        raw_batches,
+        raw_batches_truncated,
+        size_bytes,
+        error,
+        created_at,
+        completed_at,
+    ) = row;
+    ServerExport {
+        id,
+        server_id,
+        status,
+        include_raw_batches,
+        findings,
+        players,
+        observations,
+        raw_batches,
+        raw_batches_truncated,
+        size_bytes,
+        error,
+        created_at,
+        completed_at,
+    }
+}
+
+fn db_error(what: &'static str) -> impl FnOnce(sqlx::Error) -> ApiError {
+    move |e| {
+        tracing::error!("{} failed: {:?}", what, e);
+        ApiError::Internal
+    }
+}
+
+/// Record an export and build it in the background.
+pub async fn start(
+    state: &AppState,
+    server_id: &str,
+    include_raw_batches: bool,
+) -> Result<ServerExport, ApiError> {
+    let exists: bool =
+        sqlx::query_scalar("select exists (select 1 from public.servers where id = $1)")
+            .bind(server_id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(db_error("export server lookup"))?;
+    if !exists {
+        return Err(ApiError::NotFound(format!(
+            "server {} not found",
+            server_id
+        )));
+    }
+    let export_id = Uuid::new_v4();
+    let s3_key = ObjectStore::export_key(server_id, &export_id).ok_or_else(|| {
+        ApiError::BadRequest("Invalid server_id: sanitizes to empty string".into())
+    })?;
+
+    let row: Option<ExportRow> = sqlx::query_as(&format!(
+        r#"
+        insert into public.server_exports (id, server_id, include_raw_batches, s3_key)
+        select $1, $2, $3, $4
+        where not exists (
+            select 1 from public.server_exports
+            where server_id = $2 and status = 'running'
+              and created_at > now() - make_interval(mins => $5)
+        )
+        returning {}
+        "#,
+        EXPORT_COLUMNS
+    ))
+    .bind(export_id)
+    .bind(server_id)
+    .bind(include_raw_batches)
+    .bind(&s3_key)
+    .bind(STALE_AFTER_MINUTES)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error("export insert"))?;
+    let Some(row) = row else {
+        return Err(ApiError::Conflict(format!(
+            "an export of server {} is already running",
+            server_id
+        )));
+    };
+    let export = export_from_row(row);
+
+    let run_state = state.clone();
+    let run_server_id = server_id.to_string();
+    tokio::spawn(async move {
+        run(
+            run_state,
+            export_id,
+            run_server_id,
+            s3_key,
+            include_raw_batches,
+        )
+        .await;
+    });
+    tracing::info!(export_id = %export_id, server_id = %server_id, include_raw_batches, "server export started");
+    Ok(export)
+}
+
+pub async fn get(
+    state: &AppState,
+    server_id: &str,
+    export_id: Uuid,
+) -> Result<ServerExport, ApiError> {
+    let row: Option<ExportRow> = sqlx::query_as(&format!(
+        "select {} from public.server_exports where id = $1 and server_id = $2",
+        EXPORT_COLUMNS
+    ))
+    .bind(export_id)
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error("export lookup"))?;
+    row.map(export_from_row)
+        .ok_or_else(|| ApiError::NotFound(format!("export {} not found", export_id)))
+}
+
+/// A presigned download URL for a completed export (S3 only).
+pub async fn download_url(state: &AppState, export: &ServerExport) -> Option<String> {
+    if export.status != "completed" {
+        return None;
+    }
+    let key = ObjectStore::export_key(&export.server_id, &export.id)?;
+    match state
+        .object_store
+        .presign_get(&key, DOWNLOAD_LINK_EXPIRY_SECONDS)
+        .await
+    {
+        Ok(url) => url,
+        Err(e) => {
+            tracing::warn!(export_id = %export.id, "export presign failed: {:?}", e);
+            None
+        }
+    }
+}
+
+/// The archive of a completed export, for download through the API.
+pub async fn load(state: &AppState, server_id: &str, export_id: Uuid) -> Result<Vec<u8>, ApiError> {
+    let row: Option<(String, String)> = sqlx::query_as(
+        "select status, s3_key from public.server_exports where id = $1 and server_id = $2",
+    )
+    .bind(export_id)
+    .bind(server_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error("export lookup"))?;
+    let Some((status, s3_key)) = row else {
+        return Err(ApiError::NotFound(format!(
+            "export {} not found",
+            export_id
+        )));
+    };
+    if status != "completed" {
+        return Err(ApiError::Conflict(format!(
+            "export {} is {}",
+            export_id, status
+        )));
+    }
+    state.object_store.get_batch(&s3_key).await.map_err(|e| {
+        tracing::error!("export download failed: {:?}", e);
+        ApiError::NotFound(format!("export {} is no longer stored", export_id))
+    })
+}
+
+#[derive(Debug, Default)]
+struct ExportCounts {
+    findings: i64,
+    players: i64,
+    observations: i64,
+    raw_batches: i64,
+    raw_batches_missing: i64,
+    raw_batches_truncated: bool,
+}
+
+async fn run(
+    state: AppState,
+    export_id: Uuid,
+    server_id: String,
+    s3_key: String,
+    include_raw_batches: bool,
+) {
+    let result = async {
+        let (archive, counts) = build(&state, &server_id, include_raw_batches).await?;
+        let size_bytes = archive.len() as i64;
+        state.object_store.put_export(&s3_key, archive).await?;
+        anyhow::Ok((counts, size_bytes))
+    }
+    .await;
+
+    let res = match &result {
+        Ok((counts, size_bytes)) => {
+            tracing::info!(
+                export_id = %export_id,
+                server_id = %server_id,
+                findings = counts.findings,
+                raw_batches = counts.raw_batches,
+                size_bytes,
+                "server export completed"
+            );
+            sqlx::query(
+                r#"
+                update public.server_exports
+                set status = 'completed', findings = $2, players = $3, observations = $4,
+                    raw_batches = $5, raw_batches_truncated = $6, size_bytes = $7,
+                    completed_at = now()
+                where id = $1
+                "#,
+            )
+            .bind(export_id)
+            .bind(counts.findings)
+            .bind(counts.players)
+            .bind(counts.observations)
+            .bind(counts.raw_batches)
+            .bind(counts.raw_batches_truncated)
+            .bind(size_bytes)
+            .execute(&state.db)
+            .await
+        }
+        Err(e) => {
+            tracing::error!(export_id = %export_id, server_id = %server_id, "server export failed: {:#}", e);
+            sqlx::query(
+                r#"
+                update public.server_exports
+                set status = 'failed', error = $2, completed_at = now()
+                where id = $1
+                "#,
+            )
+            .bind(export_id)
+            .bind(format!("{:#}", e))
+            .execute(&state.db)
+            .await
+        }
+    };
+    if let Err(e) = res {
+        tracing::error!(export_id = %export_id, "export status update failed: {:?}", e);
+    }
+}
+
+async fn build(
+    state: &AppState,
+    server_id: &str,
+    include_raw_batches: bool,
+) -> anyhow::Result<(Vec<u8>, ExportCounts)> {
+    let created_at = Utc::now();
+    let mut archive = TarGzWriter::new(created_at.timestamp().max(0) as u64);
+    let mut counts = ExportCounts::default();
+
+    let server: Value = sqlx::query_scalar(
+        "select to_jsonb(s) - 'auth_token_hash' from public.servers s where s.id = $1",
+    )
+    .bind(server_id)
+    .fetch_one(&state.db)
+    .await?;
+    archive.append("server.json", &serde_json::to_vec_pretty(&server)?)?;
+
+    let (findings, n) = ndjson_rows(&state.db, "findings", "id", server_id).await?;
+    archive.append("findings.ndjson", &findings)?;
+    counts.findings = n;
+    let (players, n) = ndjson_rows(&state.db, "server_players", "player_uuid", server_id).await?;
+    archive.append("players.ndjson", &players)?;
+    counts.players = n;
+    let (observations, n) = ndjson_rows(&state.db, "cheat_observations", "id", server_id).await?;
+    archive.append("observations.ndjson", &observations)?;
+    counts.observations = n;
+    let (cases, _) = ndjson_rows(&state.db, "player_cases", "id", server_id).await?;
+    archive.append("cases.ndjson", &cases)?;
+    let (notes, _) = ndjson_rows(&state.db, "moderator_notes", "id", server_id).await?;
+    archive.append("notes.ndjson", &notes)?;
+
+    let mut modules = serde_json::Map::new();
+    for (name, sql) in [
+        ("modules", "select to_jsonb(t) from public.server_modules t where t.server_id = $1 order by t.name"),
+        ("capture_config", "select to_jsonb(t) from public.server_capture_config t where t.server_id = $1"),
+        ("ingest_filters", "select to_jsonb(t) from public.server_ingest_filters t where t.server_id = $1"),
+        ("severity_overrides", "select to_jsonb(t) from public.severity_overrides t where t.server_id = $1 order by t.detector_name"),
+        ("detector_configs", "select to_jsonb(t) from public.detector_configs t where t.server_id = $1 order by t.detector_name"),
+    ] {
+        let rows: Vec<Value> = sqlx::query_scalar(sql)
+            .bind(server_id)
+            .fetch_all(&state.db)
+            .await?;
+        modules.insert(name.to_string(), Value::Array(rows));
+    }
+    archive.append("modules.json", &serde_json::to_vec_pretty(&modules)?)?;
+
+    if include_raw_batches {
+        append_raw_batches(state, server_id, &mut archive, &mut counts).await?;
+    }
+
+    let manifest = json!({
+        "version": ARCHIVE_VERSION,
+        "server_id": server_id,
+        "created_at": created_at,
+        "findings": counts.findings,
+        "players": counts.players,
+        "observations": counts.observations,
+        "include_raw_batches": include_raw_batches,
+        "raw_batches": counts.raw_batches,
+        "raw_batches_missing": counts.raw_batches_missing,
+        "raw_batches_truncated": counts.raw_batches_truncated,
+    });
+    archive.append("manifest.json", &serde_json::to_vec_pretty(&manifest)?)?;
+    Ok((archive.finish()?, counts))
+}
+
+/// Every row of `table` belonging to the server as NDJSON, paged by the uuid column `key`.
+async fn ndjson_rows(
+    db: &PgPool,
+    table: &str,
+    key: &str,
+    server_id: &str,
+) -> anyhow::Result<(Vec<u8>, i64)> {
+    let mut out = Vec::new();
+    let mut count = 0;
+    let mut after: Option<Uuid> = None;
+    loop {
+        let rows: Vec<(Value, Uuid)> = sqlx::query_as(&format!(
+            r#"
+            select to_jsonb(t), t.{key} from public.{table} t
+            where t.server_id = $1 and ($2::uuid is null or t.{key} > $2)
+            order by t.{key}
+            limit $3
+            "#,
+        ))
+        .bind(server_id)
+        .bind(after)
+        .bind(PAGE_SIZE)
+        .fetch_all(db)
+        .await?;
+        let Some((_, last)) = rows.last() else {
+            break;
+        };
+        after = Some(*last);
+        let full_page = rows.len() as i64 == PAGE_SIZE;
+        for (row, _) in rows {
+            serde_json::to_writer(&mut out, &row)?;
+            out.push(b'\n');
+            count += 1;
+        }
+        if !full_page {
+            break;
+        }
+    }
+    Ok((out, count))
+}
+
+async fn append_raw_batches(
+    state: &AppState,
+    server_id: &str,
+    archive: &mut TarGzWriter,
+    counts: &mut ExportCounts,
+) -> anyhow::Result<()> {
+    let mut index = Vec::new();
+    let mut archived_bytes = 0u64;
+    let mut after: Option<Uuid> = None;
+    'pages: loop {
+        let rows: Vec<(Value, Uuid, String)> = sqlx::query_as(
+            r#"
+            select to_jsonb(t), t.id, t.s3_key from public.batch_index t
+            where t.server_id = $1 and ($2::uuid is null or t.id > $2)
+            order by t.id
+            limit $3
+            "#,
+        )
+        .bind(server_id)
+        .bind(after)
+        .bind(PAGE_SIZE)
+        .fetch_all(&state.db)
+        .await?;
+        let Some((_, last, _)) = rows.last() else {
+            break;
+        };
+        after = Some(*last);
+        let full_page = rows.len() as i64 == PAGE_SIZE;
+        for (row, batch_id, s3_key) in rows {
+            let data = match state.object_store.get_batch(&s3_key).await {
+                Ok(data) => data,
+                Err(_) => {
+                    // Removed by TTL cleanup; the index row is still exported.
+                    counts.raw_batches_missing += 1;
+                    serde_json::to_writer(&mut index, &row)?;
+                    index.push(b'\n');
+                    continue;
+                }
+            };
+            if archived_bytes + data.len() as u64 > MAX_RAW_BATCH_BYTES {
+                counts.raw_batches_truncated = true;
+                break 'pages;
+            }
+            archived_bytes += data.len() as u64;
+            archive.append(&format!("batches/{}.ndjson.gz", batch_id), &data)?;
+            serde_json::to_writer(&mut index, &row)?;
+            index.push(b'\n');
+            counts.raw_batches += 1;
+        }
+        if !full_page {
+            break;
+        }
+    }
+    archive.append("batches.ndjson", &index)?;
+    Ok(())
+}
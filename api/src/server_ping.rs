@@ -0,0 +1,491 @@
+//! Minecraft Server List Ping (the status exchange the client's server list uses).
+//!
+//! `probe` connects over TCP, sends a handshake (next state = status) plus a status request,
+//! reads the JSON status (MOTD, players, version) and times a ping/pong round trip. Servers that
+//! accept the connection but don't answer the status exchange (status disabled, non-Minecraft
+//! port) are still reported reachable, with the connect time as latency.
+//!
+//! A background task (`probe_tick`, every [`PROBE_INTERVAL_SECONDS`]) pings each recently active
+//! server and stores the result in `server_probes`, so the status endpoint answers from the
+//! database instead of pinging on every dashboard poll.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::PgPool;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::task::JoinSet;
+use tokio::time::timeout;
+use uuid::Uuid;
+
+use crate::AppState;
+
+/// Budget for the whole exchange (connect, status, ping).
+pub const PING_TIMEOUT: Duration = Duration::from_secs(3);
+/// Each server is pinged at most once per interval.
+pub const PROBE_INTERVAL_SECONDS: u64 = 30;
+/// Only servers whose plugin was seen this recently are probed.
+const PROBE_WINDOW_DAYS: i32 = 7;
+const PROBE_CONCURRENCY: usize = 16;
+
+/// "Any version": the server answers the status request whatever protocol we claim.
+const PROTOCOL_VERSION: i32 = -1;
+const NEXT_STATE_STATUS: i32 = 1;
+/// Status JSON can carry a base64 favicon; anything beyond this is not a sane response.
+const MAX_PACKET_LEN: usize = 512 * 1024;
+const MAX_MOTD_LEN: usize = 256;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerStatus {
+    /// Ping/pong round trip, or the connect time when the status exchange failed.
+    pub latency_ms: i64,
+    /// MOTD as plain text (formatting codes stripped).
+    pub motd: Option<String>,
+    pub players_online: Option<i64>,
+    pub players_max: Option<i64>,
+    /// Version name as advertised (e.g. "Paper 1.21.1", or a proxy's range).
+    pub version: Option<String>,
+    pub protocol: Option<i64>,
+}
+
+pub fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7f == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+fn write_string(buf: &mut Vec<u8>, s: &str) {
+    write_varint(buf, s.len() as i32);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// Prefix a packet (id + payload) with its length.
+fn frame(packet: Vec<u8>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(packet.len() + 5);
+    write_varint(&mut out, packet.len() as i32);
+    out.extend(packet);
+    out
+}
+
+/// Handshake (next state = status) followed by the empty status request.
+pub fn status_request(host: &str, port: u16) -> Vec<u8> {
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, 0x00);
+    write_varint(&mut handshake, PROTOCOL_VERSION);
+    write_string(&mut handshake, host);
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, NEXT_STATE_STATUS);
+
+    let mut out = frame(handshake);
+    out.extend(frame(vec![0x00]));
+    out
+}
+
+/// Decode a varint from the front of `buf`: `(value, bytes read)`.
+pub fn read_varint(buf: &[u8]) -> Option<(i32, usize)> {
+    let mut value: u32 = 0;
+    for (i, byte) in buf.iter().take(5).enumerate() {
+        value |= ((byte & 0x7f) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value as i32, i + 1));
+        }
+    }
+    None
+}
+
+async fn read_varint_from<R: AsyncRead + Unpin>(reader: &mut R) -> anyhow::Result<i32> {
+    let mut bytes = Vec::with_capacity(5);
+    loop {
+        let byte = reader.read_u8().await?;
+        bytes.push(byte);
+        if byte & 0x80 == 0 {
+            break;
+        }
+        if bytes.len() == 5 {
+            anyhow::bail!("varint too long");
+        }
+    }
+    Ok(read_varint(&bytes).map(|(v, _)| v).unwrap_or_default())
+}
+
+/// Read one length-prefixed packet: `(packet id, payload)`.
+async fn read_packet<R: AsyncRead + Unpin>(reader: &mut R) -> anyhow::Result<(i32, Vec<u8>)> {
+    let len = read_varint_from(reader).await?;
+    let len = usize::try_from(len).map_err(|_| anyhow::anyhow!("negative packet length"))?;
+    if len == 0 || len > MAX_PACKET_LEN {
+        anyhow::bail!("bad packet length {}", len);
+    }
+    let mut packet = vec![0u8; len];
+    reader.read_exact(&mut packet).await?;
+    let (id, n) = read_varint(&packet).ok_or_else(|| anyhow::anyhow!("bad packet id"))?;
+    Ok((id, packet.split_off(n)))
+}
+
+fn flatten_text(component: &Value, out: &mut String) {
+    match component {
+        Value::String(s) => out.push_str(s),
+        Value::Array(parts) => parts.iter().for_each(|p| flatten_text(p, out)),
+        Value::Object(obj) => {
+            if let Some(text) = obj.get("text") {
+                flatten_text(text, out);
+            } else if let Some(key) = obj.get("translate").and_then(|t| t.as_str()) {
+                out.push_str(key);
+            }
+            if let Some(extra) = obj.get("extra") {
+                flatten_text(extra, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Plain text of a chat component (string, object with `text` / `extra`, or array), with
+/// legacy `§x` formatting codes removed and lines trimmed.
+pub fn motd_text(description: &Value) -> String {
+    let mut raw = String::new();
+    flatten_text(description, &mut raw);
+
+    let mut text = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            text.push(c);
+        }
+    }
+    let text = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.chars().take(MAX_MOTD_LEN).collect()
+}
+
+/// Parse the status JSON; `latency_ms` is left at 0.
+pub fn parse_status(json: &str) -> Option<ServerStatus> {
+    let v: Value = serde_json::from_str(json).ok()?;
+    if !v.is_object() {
+        return None;
+    }
+    let players = v.get("players");
+    let version = v.get("version");
+    Some(ServerStatus {
+        latency_ms: 0,
+        motd: v
+            .get("description")
+            .map(motd_text)
+            .filter(|m| !m.is_empty()),
+        players_online: players
+            .and_then(|p| p.get("online"))
+            .and_then(|x| x.as_i64()),
+        players_max: players.and_then(|p| p.get("max")).and_then(|x| x.as_i64()),
+        version: version
+            .and_then(|x| x.get("name"))
+            .and_then(|x| x.as_str())
+            .map(|s| motd_text(&Value::String(s.to_string())))
+            .filter(|s| !s.is_empty()),
+        protocol: version
+            .and_then(|x| x.get("protocol"))
+            .and_then(|x| x.as_i64()),
+    })
+}
+
+async fn status_exchange(
+    stream: &mut TcpStream,
+    host: &str,
+    port: u16,
+) -> anyhow::Result<ServerStatus> {
+    stream.write_all(&status_request(host, port)).await?;
+    let (id, payload) = read_packet(stream).await?;
+    if id != 0x00 {
+        anyhow::bail!("unexpected status packet id {}", id);
+    }
+    let (len, n) = read_varint(&payload).ok_or_else(|| anyhow::anyhow!("bad status string"))?;
+    let json = payload
+        .get(n..n + usize::try_from(len)?)
+        .ok_or_else(|| anyhow::anyhow!("truncated status string"))?;
+    let mut status = parse_status(std::str::from_utf8(json)?)
+        .ok_or_else(|| anyhow::anyhow!("invalid status json"))?;
+
+    let nonce = chrono::Utc::now().timestamp_millis();
+    let mut ping = vec![0x01];
+    ping.extend_from_slice(&nonce.to_be_bytes());
+    let sent = Instant::now();
+    stream.write_all(&frame(ping)).await?;
+    match read_packet(stream).await {
+        Ok((0x01, pong)) if pong == nonce.to_be_bytes() => {
+            status.latency_ms = sent.elapsed().as_millis() as i64;
+        }
+        // Some servers close after the status response; the status itself is still good.
+        _ => {}
+    }
+    Ok(status)
+}
+
+async fn probe_uncached(host: &str, port: u16) -> Option<ServerStatus> {
+    let start = Instant::now();
+    let mut stream = TcpStream::connect((host, port)).await.ok()?;
+    let connect_ms = start.elapsed().as_millis() as i64;
+    match status_exchange(&mut stream, host, port).await {
+        Ok(mut status) => {
+            if status.latency_ms == 0 {
+                status.latency_ms = connect_ms;
+            }
+            Some(status)
+        }
+        Err(e) => {
+            tracing::debug!(host = %host, port, "server list ping failed: {:?}", e);
+            Some(ServerStatus {
+                latency_ms: connect_ms,
+                motd: None,
+                players_online: None,
+                players_max: None,
+                version: None,
+                protocol: None,
+            })
+        }
+    }
+}
+
+/// Ping `host:port`; `None` if it can't be reached within [`PING_TIMEOUT`].
+pub async fn probe(host: &str, port: u16) -> Option<ServerStatus> {
+    timeout(PING_TIMEOUT, probe_uncached(host, port))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Best-effort parse of a host[:port] or URL into (host, port).
+/// - Supports `http(s)://host[:port]/...`
+/// - Supports `host[:port]`
+/// - Supports bracketed IPv6: `[::1]:25565`
+pub fn extract_host_port(raw: &str) -> Option<(String, u16)> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let without_scheme = trimmed
+        .strip_prefix("http://")
+        .or_else(|| trimmed.strip_prefix("https://"))
+        .unwrap_or(trimmed);
+
+    // Drop any path/query fragment.
+    let authority = without_scheme
+        .split('/')
+        .next()
+        .unwrap_or(without_scheme)
+        .trim();
+    if authority.is_empty() {
+        return None;
+    }
+
+    // Bracketed IPv6 form: [::1]:25565
+    if let Some(rest) = authority.strip_prefix('[') {
+        if let Some(end) = rest.find(']') {
+            let host = rest[..end].trim();
+            if host.is_empty() {
+                return None;
+            }
+            let after = rest[end + 1..].trim(); // may start with :port
+            let port = after
+                .strip_prefix(':')
+                .and_then(|p| p.parse::<u16>().ok())
+                .unwrap_or(25565);
+            return Some((host.to_string(), port));
+        }
+    }
+
+    // Detect bare IPv6 addresses (multiple colons, no brackets).
+    // These are invalid input; require bracketed form [::1]:port instead.
+    let colon_count = authority.chars().filter(|&c| c == ':').count();
+    if colon_count > 1 {
+        // More than one colon without brackets = bare IPv6, reject it.
+        return None;
+    }
+
+    // Now safe to split by single colon: host:port or just host.
+    let mut parts = authority.split(':');
+    let host = parts.next().unwrap_or("").trim();
+    if host.is_empty() {
+        return None;
+    }
+    let port = parts
+        .next()
+        .and_then(|p| p.trim().parse::<u16>().ok())
+        .unwrap_or(25565);
+
+    Some((host.to_string(), port))
+}
+
+/// The address to ping for a server: its callback URL's host, else the server id when it looks
+/// like an address. Loopback addresses are never pinged.
+pub fn ping_target(server_id: &str, callback_url: Option<&str>) -> Option<(String, u16)> {
+    // UUID-like ids are not addresses; don't waste a timeout on them.
+    let source = callback_url.or(Some(server_id).filter(|id| Uuid::parse_str(id).is_err()))?;
+    extract_host_port(source).filter(|(host, _)| host != "127.0.0.1" && host != "localhost")
+}
+
+/// The last stored probe of a server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredProbe {
+    pub address: String,
+    pub reachable: bool,
+    pub status: Option<ServerStatus>,
+    pub probed_at: DateTime<Utc>,
+}
+
+pub async fn load(db: &PgPool, server_id: &str) -> Result<Option<StoredProbe>, sqlx::Error> {
+    let row: Option<(
+        String,
+        bool,
+        Option<i64>,
+        Option<String>,
+        Option<i64>,
+        Option<i64>,
+        Option<String>,
+        Option<i64>,
+        DateTime<Utc>,
+    )> = sqlx::query_as(
+        r#"
+        select address, reachable, latency_ms, motd, players_online, players_max,
+               version, protocol, probed_at
+        from public.server_probes
+        where server_id = $1
+        "#,
+    )
+    .bind(server_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.map(
+        |(
+            address,
+            reachable,
+            latency_ms,
+            motd,
+            players_online,
+            players_max,
+            version,
+            protocol,
+            probed_at,
+        )| {
+            StoredProbe {
+                address,
+                reachable,
+                status: latency_ms
+                    .filter(|_| reachable)
+                    .map(|latency_ms| ServerStatus {
+                        latency_ms,
+                        motd,
+                        players_online,
+                        players_max,
+                        version,
+                        protocol,
+                    }),
+                probed_at,
+            }
+        },
+    ))
+}
+
+async fn store(
+    db: &PgPool,
+    server_ids: &[String],
+    address: &str,
+    status: Option<&ServerStatus>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        insert into public.server_probes
+            (server_id, address, reachable, latency_ms, motd, players_online, players_max,
+             version, protocol, probed_at)
+        select id, $2, $3, $4, $5, $6, $7, $8, $9, now()
+        from unnest($1::text[]) as id
+        on conflict (server_id) do update set
+            address = excluded.address,
+            reachable = excluded.reachable,
+            latency_ms = excluded.latency_ms,
+            motd = excluded.motd,
+            players_online = excluded.players_online,
+            players_max = excluded.players_max,
+            version = excluded.version,
+            protocol = excluded.protocol,
+            probed_at = excluded.probed_at
+        "#,
+    )
+    .bind(server_ids)
+    .bind(address)
+    .bind(status.is_some())
+    .bind(status.map(|s| s.latency_ms))
+    .bind(status.and_then(|s| s.motd.as_deref()))
+    .bind(status.and_then(|s| s.players_online))
+    .bind(status.and_then(|s| s.players_max))
+    .bind(status.and_then(|s| s.version.as_deref()))
+    .bind(status.and_then(|s| s.protocol))
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Probe every recently active server once (servers sharing an address are pinged once) and
+/// store the results for `GET /dashboard/:server_id/status`.
+pub async fn probe_tick(state: AppState) {
+    let servers: Vec<(String, Option<String>)> = match sqlx::query_as(
+        r#"
+        select id, callback_url
+        from public.servers
+        where last_seen_at > now() - make_interval(days => $1) and deleted_at is null
+        "#,
+    )
+    .bind(PROBE_WINDOW_DAYS)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            tracing::error!("server probe: listing servers failed: {:?}", e);
+            return;
+        }
+    };
+
+    let mut by_target: HashMap<(String, u16), Vec<String>> = HashMap::new();
+    for (server_id, callback_url) in servers {
+        if let Some(target) = ping_target(&server_id, callback_url.as_deref()) {
+            by_target.entry(target).or_default().push(server_id);
+        }
+    }
+
+    let mut probes = JoinSet::new();
+    for ((host, port), server_ids) in by_target {
+        if probes.len() >= PROBE_CONCURRENCY {
+            if let Some(Err(e)) = probes.join_next().await {
+                tracing::error!("server probe task failed: {:?}", e);
+            }
+        }
+        let db = state.db.clone();
+        probes.spawn(async move {
+            let status = probe(&host, port).await;
+            let address = format!("{}:{}", host, port);
+            if let Err(e) = store(&db, &server_ids, &address, status.as_ref()).await {
+                tracing::error!(address = %address, "server probe: storing result failed: {:?}", e);
+            }
+        });
+    }
+    while let Some(res) = probes.join_next().await {
+        if let Err(e) = res {
+            tracing::error!("server probe task failed: {:?}", e);
+        }
+    }
+}
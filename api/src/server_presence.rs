@@ -0,0 +1,223 @@
+//! Server offline detection.
+//!
+//! The plugin heartbeats every 30 seconds, so a server whose `last_seen_at` is older than
+//! `SERVER_OFFLINE_AFTER_SECONDS` is offline. `presence_tick` (every
+//! [`CHECK_INTERVAL_SECONDS`]) sets or clears `servers.offline_since` on each transition, records
+//! it in `server_presence_events` and notifies the server's webhook ("plugin went offline" /
+//! "plugin back online"). Servers that were already silent for more than
+//! [`NOTIFY_WINDOW_HOURS`] when flagged (abandoned servers, the first run after deploying this)
+//! are marked offline without a notification.
+//!
+//! A down event is dated at the plugin's last contact and an up event at its first contact
+//! after the outage (to within a tick). The status endpoint reports uptime over the last 24
+//! hours and 7 days from these events; servers count as online before their first event.
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+
+use crate::webhooks::{self, PresenceNotification};
+use crate::AppState;
+
+pub const CHECK_INTERVAL_SECONDS: u64 = 30;
+/// Servers silent for longer than this when flagged offline are not notified.
+pub const NOTIFY_WINDOW_HOURS: i32 = 24;
+pub const EVENT_RETENTION_DAYS: i32 = 30;
+
+/// A recorded transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PresenceEvent {
+    pub at: DateTime<Utc>,
+    pub online: bool,
+}
+
+/// Share of `[from, to)` the server was online, as a percentage, from its transitions sorted
+/// oldest first (events before `from` give the state at the start). None for an empty window.
+pub fn uptime_percent(
+    events: &[PresenceEvent],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Option<f64> {
+    if to <= from {
+        return None;
+    }
+    let mut online = events
+        .iter()
+        .take_while(|e| e.at <= from)
+        .last()
+        .is_none_or(|e| e.online);
+    let mut cursor = from;
+    let mut up = Duration::zero();
+    for e in events.iter().filter(|e| e.at > from && e.at < to) {
+        if online {
+            up += e.at - cursor;
+        }
+        cursor = e.at;
+        online = e.online;
+    }
+    if online {
+        up += to - cursor;
+    }
+    Some(100.0 * up.num_milliseconds() as f64 / (to - from).num_milliseconds() as f64)
+}
+
+/// Uptime of a server over the last 24 hours and 7 days (windows start no earlier than the
+/// server's first contact).
+pub async fn uptime(
+    db: &PgPool,
+    server_id: &str,
+    first_seen_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<(Option<f64>, Option<f64>), sqlx::Error> {
+    let week_ago = now - Duration::days(7);
+    let rows: Vec<(DateTime<Utc>, bool)> = sqlx::query_as(
+        r#"
+        (select at, online from public.server_presence_events
+         where server_id = $1 and at <= $2
+         order by at desc
+         limit 1)
+        union all
+        (select at, online from public.server_presence_events
+         where server_id = $1 and at > $2
+         order by at asc)
+        "#,
+    )
+    .bind(server_id)
+    .bind(week_ago)
+    .fetch_all(db)
+    .await?;
+    let mut events: Vec<PresenceEvent> = rows
+        .into_iter()
+        .map(|(at, online)| PresenceEvent { at, online })
+        .collect();
+    events.sort_by_key(|e| e.at);
+
+    let window = |days: i64| {
+        uptime_percent(
+            &events,
+            (now - Duration::days(days)).max(first_seen_at),
+            now,
+        )
+    };
+    Ok((window(1), window(7)))
+}
+
+/// Flag servers that went offline or came back, record the transitions and notify webhooks.
+pub async fn presence_tick(state: AppState) {
+    let offline_after = state.server_offline_after_seconds as f64;
+
+    let went_offline: Result<Vec<(String, Option<String>, DateTime<Utc>, bool)>, _> =
+        sqlx::query_as(
+            r#"
+            with changed as (
+                update public.servers
+                set offline_since = last_seen_at
+                where offline_since is null
+                  and deleted_at is null
+                  and last_seen_at < now() - make_interval(secs => $1)
+                returning id, name, last_seen_at
+            ),
+            logged as (
+                insert into public.server_presence_events (server_id, online, at)
+                select id, false, last_seen_at from changed
+            )
+            select id, name, last_seen_at,
+                   last_seen_at > now() - make_interval(hours => $2)
+            from changed
+            "#,
+        )
+        .bind(offline_after)
+        .bind(NOTIFY_WINDOW_HOURS)
+        .fetch_all(&state.db)
+        .await;
+    match went_offline {
+        Ok(rows) => {
+            for (server_id, name, last_seen_at, notify) in rows {
+                tracing::info!(server_id = %server_id, last_seen_at = %last_seen_at, "server went offline");
+                if notify {
+                    let notification = PresenceNotification {
+                        server_id,
+                        online: false,
+                        last_seen_at,
+                        offline_since: last_seen_at,
+                    };
+                    notify_webhook(&state, notification, name).await;
+                }
+            }
+        }
+        Err(e) => tracing::warn!("server offline detection failed: {:?}", e),
+    }
+
+    // `prev` is read before the update, so it still carries the outage start.
+    let came_back: Result<Vec<(String, Option<String>, DateTime<Utc>, DateTime<Utc>)>, _> =
+        sqlx::query_as(
+            r#"
+            with changed as (
+                update public.servers s
+                set offline_since = null
+                from public.servers prev
+                where prev.id = s.id
+                  and s.offline_since is not null
+                  and s.last_seen_at >= now() - make_interval(secs => $1)
+                returning s.id, s.name, s.last_seen_at, prev.offline_since
+            ),
+            logged as (
+                insert into public.server_presence_events (server_id, online, at)
+                select id, true, last_seen_at from changed
+            )
+            select id, name, last_seen_at, offline_since from changed
+            "#,
+        )
+        .bind(offline_after)
+        .fetch_all(&state.db)
+        .await;
+    match came_back {
+        Ok(rows) => {
+            for (server_id, name, last_seen_at, offline_since) in rows {
+                tracing::info!(server_id = %server_id, offline_since = %offline_since, "server back online");
+                let notification = PresenceNotification {
+                    server_id,
+                    online: true,
+                    last_seen_at,
+                    offline_since,
+                };
+                notify_webhook(&state, notification, name).await;
+            }
+        }
+        Err(e) => tracing::warn!("server online detection failed: {:?}", e),
+    }
+
+    // Each server keeps its latest event, which gives the state at the start of any window.
+    let _ = sqlx::query(
+        r#"
+        delete from public.server_presence_events e
+        where e.at < now() - make_interval(days => $1)
+          and exists (
+              select 1 from public.server_presence_events later
+              where later.server_id = e.server_id and later.at > e.at
+          )
+        "#,
+    )
+    .bind(EVENT_RETENTION_DAYS)
+    .execute(&state.db)
+    .await;
+}
+
+/// Send a transition to the server's webhook, if it has one enabled. Presence notifications
+/// ignore the severity filter.
+async fn notify_webhook(
+    state: &AppState,
+    notification: PresenceNotification,
+    name: Option<String>,
+) {
+    let Some(settings) = webhooks::get_webhook_settings(&state.db, &notification.server_id).await
+    else {
+        return;
+    };
+    let Some(url) = settings.webhook_url.filter(|_| settings.webhook_enabled) else {
+        return;
+    };
+    let client = state.http.clone();
+    tokio::spawn(async move {
+        webhooks::send_presence_notification(&client, &url, &notification, name.as_deref()).await;
+    });
+}
@@ -0,0 +1,252 @@
+//! Public service status (`GET /status/public`).
+//!
+//! Backs the status page of a hosted deployment, so it is served without auth and says nothing
+//! about individual servers, players or replicas: only aggregate service health.
+//!
+//! - ingest: share of `/ingest` requests answered successfully over the last
+//!   [`WINDOW_MINUTES`]. Client errors (auth, payload too large, rate limits, outdated plugins)
+//!   don't count either way; 5xx responses are failures. Counted per replica, which behind a
+//!   load balancer is a fair sample of the whole deployment.
+//! - dispatch: the backlog level plugins are told in ingest hints, and the batches stored
+//!   without dispatch under overload that still wait for recovery.
+//! - background tasks: whether each periodic task has a live holder (its lease in
+//!   `task_leases` is unexpired; a task whose replica stopped stays expired until another
+//!   replica takes it over). Leases expired for over [`RETIRED_TASK_HOURS`] belong to tasks no
+//!   replica runs any more (turned off in the config) and are left out.
+//!
+//! The response is cached for [`CACHE_SECONDS`] (in Redis when shared state is), so polling it
+//! doesn't reach the database.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use axum::{extract::State, http::Request, middleware::Next, response::Response};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+use crate::dispatch_shedding;
+use crate::ingest_hints::{self, Backlog, Load};
+use crate::AppState;
+
+pub const WINDOW_MINUTES: u64 = 15;
+pub const CACHE_SECONDS: u64 = 15;
+pub const RETIRED_TASK_HOURS: i32 = 24;
+/// Below this many ingest requests in the window the success rate doesn't affect the status.
+pub const MIN_REQUESTS: u64 = 20;
+/// Ingest success rates below these are degraded / a major outage.
+pub const DEGRADED_SUCCESS_RATE: f64 = 0.99;
+pub const OUTAGE_SUCCESS_RATE: f64 = 0.9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Operational,
+    Degraded,
+    MajorOutage,
+}
+
+/// `/ingest` responses of this replica, per minute.
+#[derive(Debug, Clone, Default)]
+pub struct IngestOutcomes(Arc<Mutex<VecDeque<(u64, u64, u64)>>>);
+
+impl IngestOutcomes {
+    pub fn record(&self, succeeded: bool) {
+        self.record_at(Utc::now().timestamp() as u64 / 60, succeeded);
+    }
+
+    /// Count a response in minute `minute` (Unix minutes).
+    pub fn record_at(&self, minute: u64, succeeded: bool) {
+        let mut minutes = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        match minutes.back_mut() {
+            Some((m, ok, failed)) if *m == minute => {
+                if succeeded {
+                    *ok += 1;
+                } else {
+                    *failed += 1;
+                }
+            }
+            _ => minutes.push_back((minute, succeeded as u64, !succeeded as u64)),
+        }
+        while minutes
+            .front()
+            .is_some_and(|(m, _, _)| *m + WINDOW_MINUTES <= minute)
+        {
+            minutes.pop_front();
+        }
+    }
+
+    pub fn totals(&self) -> (u64, u64) {
+        self.totals_at(Utc::now().timestamp() as u64 / 60)
+    }
+
+    /// Succeeded and failed responses in the [`WINDOW_MINUTES`] up to and including `minute`.
+    pub fn totals_at(&self, minute: u64) -> (u64, u64) {
+        let minutes = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        minutes
+            .iter()
+            .filter(|(m, _, _)| *m + WINDOW_MINUTES > minute && *m <= minute)
+            .fold((0, 0), |(ok, failed), (_, o, f)| (ok + o, failed + f))
+    }
+}
+
+/// Middleware on `/ingest`: count the response for the public success rate.
+pub async fn track_ingest<B>(
+    State(state): State<AppState>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let response = next.run(req).await;
+    let status = response.status();
+    if status.is_success() {
+        state.ingest_outcomes.record(true);
+    } else if status.is_server_error() {
+        state.ingest_outcomes.record(false);
+    }
+    response
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IngestStatus {
+    pub status: ComponentStatus,
+    pub window_minutes: u64,
+    /// Successful and failed (5xx) ingest requests in the window.
+    pub requests: u64,
+    /// None without requests in the window.
+    pub success_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DispatchStatus {
+    pub status: ComponentStatus,
+    pub backlog: Backlog,
+    /// Batches stored without module dispatch under overload, awaiting recovery.
+    pub awaiting_recovery: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TaskStatus {
+    pub task: String,
+    /// Some replica holds the task's lease.
+    pub alive: bool,
+    pub last_finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublicStatusResponse {
+    /// Worst of the components.
+    pub status: ComponentStatus,
+    pub generated_at: DateTime<Utc>,
+    pub database: ComponentStatus,
+    pub ingest: IngestStatus,
+    pub dispatch: DispatchStatus,
+    pub background_tasks_status: ComponentStatus,
+    pub background_tasks: Vec<TaskStatus>,
+}
+
+/// Ingest status from succeeded / failed request counts.
+pub fn ingest_status(succeeded: u64, failed: u64) -> IngestStatus {
+    let requests = succeeded + failed;
+    let success_rate = (requests > 0).then(|| succeeded as f64 / requests as f64);
+    let status = match success_rate {
+        Some(rate) if requests >= MIN_REQUESTS && rate < OUTAGE_SUCCESS_RATE => {
+            ComponentStatus::MajorOutage
+        }
+        Some(rate) if requests >= MIN_REQUESTS && rate < DEGRADED_SUCCESS_RATE => {
+            ComponentStatus::Degraded
+        }
+        _ => ComponentStatus::Operational,
+    };
+    IngestStatus {
+        status,
+        window_minutes: WINDOW_MINUTES,
+        requests,
+        success_rate,
+    }
+}
+
+/// Dispatch is degraded while the backlog is saturated or shed batches wait for recovery.
+pub fn dispatch_status(backlog: Backlog, awaiting_recovery: Option<i64>) -> DispatchStatus {
+    let status = match backlog {
+        Backlog::Saturated => ComponentStatus::Degraded,
+        _ if awaiting_recovery.is_some_and(|n| n > 0) => ComponentStatus::Degraded,
+        _ => ComponentStatus::Operational,
+    };
+    DispatchStatus {
+        status,
+        backlog,
+        awaiting_recovery,
+    }
+}
+
+/// Degraded when a task has no live holder.
+pub fn tasks_status(tasks: &[TaskStatus]) -> ComponentStatus {
+    if tasks.iter().all(|t| t.alive) {
+        ComponentStatus::Operational
+    } else {
+        ComponentStatus::Degraded
+    }
+}
+
+async fn tasks(db: &PgPool) -> Result<Vec<TaskStatus>, sqlx::Error> {
+    let rows: Vec<(String, bool, Option<DateTime<Utc>>)> = sqlx::query_as(
+        r#"
+        select task, expires_at > now(), last_finished_at
+        from public.task_leases
+        where expires_at > now() - make_interval(hours => $1)
+        order by task
+        "#,
+    )
+    .bind(RETIRED_TASK_HOURS)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(task, alive, last_finished_at)| TaskStatus {
+            task,
+            alive,
+            last_finished_at,
+        })
+        .collect())
+}
+
+async fn awaiting_recovery(db: &PgPool) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("select count(*) from public.batch_index where dispatch_status = $1")
+        .bind(dispatch_shedding::STORED_NOT_DISPATCHED)
+        .fetch_one(db)
+        .await
+}
+
+/// Current status. Database errors make the database a major outage rather than failing.
+pub async fn current(state: &AppState) -> PublicStatusResponse {
+    let (succeeded, failed) = state.ingest_outcomes.totals();
+    let ingest = ingest_status(succeeded, failed);
+
+    let load = Load::current(&state.dispatch_backlog, &state.db);
+    let backlog = ingest_hints::hints(&load, state.max_body_bytes).backlog;
+
+    let (database, awaiting, background_tasks) =
+        match tokio::try_join!(awaiting_recovery(&state.db), tasks(&state.db)) {
+            Ok((awaiting, tasks)) => (ComponentStatus::Operational, Some(awaiting), tasks),
+            Err(e) => {
+                tracing::warn!("public status query failed: {:?}", e);
+                (ComponentStatus::MajorOutage, None, Vec::new())
+            }
+        };
+    let dispatch = dispatch_status(backlog, awaiting);
+    let background_tasks_status = tasks_status(&background_tasks);
+
+    PublicStatusResponse {
+        status: database
+            .max(ingest.status)
+            .max(dispatch.status)
+            .max(background_tasks_status),
+        generated_at: Utc::now(),
+        database,
+        ingest,
+        dispatch,
+        background_tasks_status,
+        background_tasks,
+    }
+}
@@ -0,0 +1,139 @@
+//! Per-server severity remapping.
+//!
+//! Modules assign each finding a severity, but server owners don't always agree (a noisy
+//! detector flagged `critical` on their network, a check they consider decisive reported as
+//! `medium`). A `severity_overrides` row replaces the severity of every finding of one detector
+//! on one server. Overrides are applied in `callbacks::store_findings` before findings are
+//! aggregated, so the stored findings, their minute-bucket "strongest severity" and the webhook
+//! severity filter all see the remapped value.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+/// Severities a finding can carry (strongest first).
+pub const SEVERITIES: [&str; 5] = ["critical", "high", "medium", "low", "info"];
+
+/// The canonical spelling of a severity, if it is one.
+pub fn parse_severity(raw: &str) -> Option<&'static str> {
+    let raw = raw.trim();
+    SEVERITIES
+        .iter()
+        .find(|s| s.eq_ignore_ascii_case(raw))
+        .copied()
+}
+
+/// A server's overrides, keyed by detector name.
+#[derive(Debug, Clone, Default)]
+pub struct SeverityOverrides {
+    by_detector: HashMap<String, String>,
+}
+
+impl SeverityOverrides {
+    pub fn with_override(mut self, detector_name: &str, severity: &str) -> Self {
+        self.by_detector
+            .insert(detector_name.trim().to_string(), severity.to_string());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_detector.is_empty()
+    }
+
+    /// The severity a finding of `detector_name` is stored with.
+    pub fn severity<'a>(&'a self, detector_name: &str, reported: Option<&'a str>) -> &'a str {
+        self.by_detector
+            .get(detector_name.trim())
+            .map(String::as_str)
+            .or(reported)
+            .unwrap_or("info")
+    }
+
+    pub async fn load(db: &PgPool, server_id: &str) -> Result<Self, sqlx::Error> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "select detector_name, severity from public.severity_overrides where server_id = $1",
+        )
+        .bind(server_id)
+        .fetch_all(db)
+        .await?;
+        Ok(Self {
+            by_detector: rows.into_iter().collect(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SeverityOverride {
+    pub detector_name: String,
+    /// critical | high | medium | low | info
+    pub severity: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub async fn list(db: &PgPool, server_id: &str) -> Result<Vec<SeverityOverride>, sqlx::Error> {
+    let rows: Vec<(String, String, DateTime<Utc>)> = sqlx::query_as(
+        r#"
+        select detector_name, severity, updated_at
+        from public.severity_overrides
+        where server_id = $1
+        order by detector_name asc
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(detector_name, severity, updated_at)| SeverityOverride {
+            detector_name,
+            severity,
+            updated_at,
+        })
+        .collect())
+}
+
+pub async fn upsert(
+    db: &PgPool,
+    server_id: &str,
+    detector_name: &str,
+    severity: &str,
+) -> Result<SeverityOverride, sqlx::Error> {
+    let updated_at: DateTime<Utc> = sqlx::query_scalar(
+        r#"
+        insert into public.severity_overrides (server_id, detector_name, severity, created_at, updated_at)
+        values ($1, $2, $3, now(), now())
+        on conflict (server_id, detector_name) do update set
+            severity = excluded.severity,
+            updated_at = excluded.updated_at
+        returning updated_at
+        "#,
+    )
+    .bind(server_id)
+    .bind(detector_name)
+    .bind(severity)
+    .fetch_one(db)
+    .await?;
+    Ok(SeverityOverride {
+        detector_name: detector_name.to_string(),
+        severity: severity.to_string(),
+        updated_at,
+    })
+}
+
+/// Returns whether an override existed.
+pub async fn delete(
+    db: &PgPool,
+    server_id: &str,
+    detector_name: &str,
+) -> Result<bool, sqlx::Error> {
+    let res = sqlx::query(
+        "delete from public.severity_overrides where server_id = $1 and detector_name = $2",
+    )
+    .bind(server_id)
+    .bind(detector_name)
+    .execute(db)
+    .await?;
+    Ok(res.rows_affected() > 0)
+}
@@ -0,0 +1,211 @@
+//! Shadow-mode modules.
+//!
+//! A module's `mode` is `active` (the default) or `shadow`. Shadow modules get batches like
+//! active ones, but the findings they report go to `shadow_findings` instead of `findings`: no
+//! webhooks, punishment rules, combined findings or dashboard alerts. That lets a new check run
+//! on production traffic until its owner trusts it. Exemptions still apply, so shadow findings
+//! match what the module would report live. Shadow findings are kept `RETENTION_DAYS`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::routes::callbacks::FindingIn;
+use crate::AppState;
+
+pub const MODE_ACTIVE: &str = "active";
+pub const MODE_SHADOW: &str = "shadow";
+
+pub const RETENTION_DAYS: i32 = 30;
+pub const PURGE_INTERVAL_SECONDS: u64 = 3600;
+
+/// `active` or `shadow`, normalized.
+pub fn parse_mode(raw: &str) -> Option<&'static str> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        MODE_ACTIVE => Some(MODE_ACTIVE),
+        MODE_SHADOW => Some(MODE_SHADOW),
+        _ => None,
+    }
+}
+
+/// Store a shadow module's findings; returns how many were stored.
+pub async fn record_findings(
+    state: &AppState,
+    server_id: &str,
+    module_id: Uuid,
+    session_id: Option<&str>,
+    batch_id: Option<Uuid>,
+    findings: &[&FindingIn],
+) -> usize {
+    let mut stored = 0;
+    for f in findings {
+        if f.detector_name.trim().is_empty() || f.title.trim().is_empty() {
+            continue;
+        }
+        let res = sqlx::query(
+            r#"
+            insert into public.shadow_findings
+                (server_id, module_id, session_id, batch_id, player_uuid, detector_name,
+                 detector_version, severity, title, description, confidence, evidence_s3_key,
+                 evidence_json)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            "#,
+        )
+        .bind(server_id)
+        .bind(module_id)
+        .bind(session_id)
+        .bind(batch_id)
+        .bind(f.player_uuid)
+        .bind(f.detector_name.trim())
+        .bind(f.detector_version.as_deref())
+        .bind(f.severity.as_deref().unwrap_or("low"))
+        .bind(f.title.trim())
+        .bind(f.description.as_deref())
+        .bind(f.confidence)
+        .bind(f.evidence_s3_key.as_deref())
+        .bind(f.evidence_json.as_ref().map(sqlx::types::Json))
+        .execute(&state.db)
+        .await;
+        match res {
+            Ok(_) => stored += 1,
+            Err(e) => {
+                tracing::warn!(module_id = %module_id, "shadow finding insert failed: {:?}", e);
+            }
+        }
+    }
+    stored
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ShadowFinding {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub player_uuid: Option<Uuid>,
+    pub detector_name: String,
+    pub detector_version: Option<String>,
+    pub severity: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub confidence: Option<f64>,
+    pub batch_id: Option<Uuid>,
+}
+
+/// Shadow findings of one detector over the listed period.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ShadowDetectorSummary {
+    pub detector_name: String,
+    pub findings: i64,
+    pub players: i64,
+}
+
+type ShadowRow = (
+    Uuid,
+    DateTime<Utc>,
+    Option<Uuid>,
+    String,
+    Option<String>,
+    String,
+    String,
+    Option<String>,
+    Option<f64>,
+    Option<Uuid>,
+);
+
+/// Latest shadow findings of a module reported in the last `hours`.
+pub async fn list(
+    state: &AppState,
+    module_id: Uuid,
+    hours: i32,
+    limit: i64,
+) -> Result<Vec<ShadowFinding>, sqlx::Error> {
+    let rows: Vec<ShadowRow> = sqlx::query_as(
+        r#"
+        select id, created_at, player_uuid, detector_name, detector_version, severity, title,
+               description, confidence, batch_id
+        from public.shadow_findings
+        where module_id = $1 and created_at >= now() - make_interval(hours => $2)
+        order by created_at desc
+        limit $3
+        "#,
+    )
+    .bind(module_id)
+    .bind(hours)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(
+                id,
+                created_at,
+                player_uuid,
+                detector_name,
+                detector_version,
+                severity,
+                title,
+                description,
+                confidence,
+                batch_id,
+            )| ShadowFinding {
+                id,
+                created_at,
+                player_uuid,
+                detector_name,
+                detector_version,
+                severity,
+                title,
+                description,
+                confidence,
+                batch_id,
+            },
+        )
+        .collect())
+}
+
+/// Per-detector counts of a module's shadow findings in the last `hours`.
+pub async fn summarize(
+    state: &AppState,
+    module_id: Uuid,
+    hours: i32,
+) -> Result<Vec<ShadowDetectorSummary>, sqlx::Error> {
+    let rows: Vec<(String, i64, i64)> = sqlx::query_as(
+        r#"
+        select detector_name, count(*), count(distinct player_uuid)
+        from public.shadow_findings
+        where module_id = $1 and created_at >= now() - make_interval(hours => $2)
+        group by detector_name
+        order by count(*) desc, detector_name asc
+        "#,
+    )
+    .bind(module_id)
+    .bind(hours)
+    .fetch_all(&state.db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(detector_name, findings, players)| ShadowDetectorSummary {
+            detector_name,
+            findings,
+            players,
+        })
+        .collect())
+}
+
+/// Drop shadow findings older than `RETENTION_DAYS`.
+pub async fn purge_tick(state: AppState) {
+    let res = sqlx::query(
+        "delete from public.shadow_findings where created_at < now() - make_interval(days => $1)",
+    )
+    .bind(RETENTION_DAYS)
+    .execute(&state.db)
+    .await;
+    match res {
+        Ok(r) if r.rows_affected() > 0 => {
+            tracing::info!(purged = r.rows_affected(), "purged old shadow findings")
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("shadow finding purge failed: {:?}", e),
+    }
+}
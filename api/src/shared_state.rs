@@ -0,0 +1,239 @@
+//! Short-lived state shared across API replicas.
+//!
+//! Some state only works when every replica sees the same copy: the dashboard stats cache, the
+//! per-server ingest rate limit and the webhook dedup window. With `REDIS_URL` set (and the
+//! `redis` feature built in) that state lives in Redis under `aac:` keys; otherwise each replica
+//! keeps its own copy in memory, which is exact for a single replica and a per-replica
+//! approximation for several.
+//!
+//! Everything here is best effort. A Redis error is logged and the caller proceeds as if nothing
+//! was stored: cache misses, rate limits let the request through and dedup claims succeed.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Local maps are swept of expired entries once they grow past this.
+const LOCAL_SWEEP_AT: usize = 4096;
+
+#[derive(Debug, Default)]
+struct Local {
+    values: HashMap<String, (String, Instant)>,
+    counters: HashMap<String, (u64, Instant)>,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Local(Arc<Mutex<Local>>),
+    #[cfg(feature = "redis")]
+    Redis(Box<redis::aio::ConnectionManager>),
+}
+
+#[derive(Clone)]
+pub struct SharedState {
+    backend: Backend,
+}
+
+impl Default for SharedState {
+    fn default() -> Self {
+        Self::local()
+    }
+}
+
+impl std::fmt::Debug for SharedState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedState")
+            .field("backend", &self.backend())
+            .finish()
+    }
+}
+
+/// The fixed window containing `now_secs` (Unix seconds): its index and the seconds left in it.
+pub fn fixed_window(now_secs: u64, window_secs: u64) -> (u64, u64) {
+    let window_secs = window_secs.max(1);
+    (now_secs / window_secs, window_secs - now_secs % window_secs)
+}
+
+#[cfg(feature = "redis")]
+fn key(name: &str) -> String {
+    format!("aac:{}", name)
+}
+
+impl SharedState {
+    /// In-process state for this replica only.
+    pub fn local() -> Self {
+        Self {
+            backend: Backend::Local(Arc::default()),
+        }
+    }
+
+    /// State in the Redis server at `url`.
+    #[cfg(feature = "redis")]
+    pub async fn connect_redis(url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url)?;
+        let conn = redis::aio::ConnectionManager::new(client).await?;
+        Ok(Self {
+            backend: Backend::Redis(Box::new(conn)),
+        })
+    }
+
+    /// "redis" or "local".
+    pub fn backend(&self) -> &'static str {
+        match self.backend {
+            Backend::Local(_) => "local",
+            #[cfg(feature = "redis")]
+            Backend::Redis(_) => "redis",
+        }
+    }
+
+    /// The cached value of `name`, if present, unexpired and still of type `T`.
+    pub async fn get_json<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        let raw = match &self.backend {
+            Backend::Local(local) => {
+                let local = local.lock().unwrap_or_else(|e| e.into_inner());
+                local
+                    .values
+                    .get(name)
+                    .filter(|(_, expires)| *expires > Instant::now())
+                    .map(|(v, _)| v.clone())
+            }
+            #[cfg(feature = "redis")]
+            Backend::Redis(conn) => {
+                let res: redis::RedisResult<Option<String>> = redis::cmd("GET")
+                    .arg(key(name))
+                    .query_async(&mut (**conn).clone())
+                    .await;
+                res.unwrap_or_else(|e| {
+                    tracing::warn!(key = name, "shared state get failed: {:?}", e);
+                    None
+                })
+            }
+        }?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    /// Cache `value` under `name` for `ttl`.
+    pub async fn set_json<T: Serialize>(&self, name: &str, value: &T, ttl: Duration) {
+        let Ok(raw) = serde_json::to_string(value) else {
+            return;
+        };
+        match &self.backend {
+            Backend::Local(local) => {
+                let mut local = local.lock().unwrap_or_else(|e| e.into_inner());
+                local.sweep();
+                local
+                    .values
+                    .insert(name.to_string(), (raw, Instant::now() + ttl));
+            }
+            #[cfg(feature = "redis")]
+            Backend::Redis(conn) => {
+                let res: redis::RedisResult<()> = redis::cmd("SET")
+                    .arg(key(name))
+                    .arg(raw)
+                    .arg("PX")
+                    .arg(ttl.as_millis().max(1) as u64)
+                    .query_async(&mut (**conn).clone())
+                    .await;
+                if let Err(e) = res {
+                    tracing::warn!(key = name, "shared state set failed: {:?}", e);
+                }
+            }
+        }
+    }
+
+    /// Add one to the counter `name` and return the new count. The counter starts at the first
+    /// increment and is dropped `window` later. Returns 0 if the count is unavailable.
+    pub async fn incr(&self, name: &str, window: Duration) -> u64 {
+        match &self.backend {
+            Backend::Local(local) => {
+                let mut local = local.lock().unwrap_or_else(|e| e.into_inner());
+                local.sweep();
+                let now = Instant::now();
+                let entry = local
+                    .counters
+                    .entry(name.to_string())
+                    .or_insert((0, now + window));
+                if entry.1 <= now {
+                    *entry = (0, now + window);
+                }
+                entry.0 += 1;
+                entry.0
+            }
+            #[cfg(feature = "redis")]
+            Backend::Redis(conn) => {
+                let mut conn = (**conn).clone();
+                let res: redis::RedisResult<u64> = async {
+                    let count: u64 = redis::cmd("INCR")
+                        .arg(key(name))
+                        .query_async(&mut conn)
+                        .await?;
+                    if count == 1 {
+                        redis::cmd("PEXPIRE")
+                            .arg(key(name))
+                            .arg(window.as_millis().max(1) as u64)
+                            .query_async::<()>(&mut conn)
+                            .await?;
+                    }
+                    Ok(count)
+                }
+                .await;
+                res.unwrap_or_else(|e| {
+                    tracing::warn!(key = name, "shared state incr failed: {:?}", e);
+                    0
+                })
+            }
+        }
+    }
+
+    /// Claim `name` for `ttl`: true for the first caller, false while the claim holds.
+    pub async fn claim(&self, name: &str, ttl: Duration) -> bool {
+        match &self.backend {
+            Backend::Local(local) => {
+                let mut local = local.lock().unwrap_or_else(|e| e.into_inner());
+                local.sweep();
+                let now = Instant::now();
+                match local.values.get(name) {
+                    Some((_, expires)) if *expires > now => false,
+                    _ => {
+                        local
+                            .values
+                            .insert(name.to_string(), (String::new(), now + ttl));
+                        true
+                    }
+                }
+            }
+            #[cfg(feature = "redis")]
+            Backend::Redis(conn) => {
+                let res: redis::RedisResult<Option<String>> = redis::cmd("SET")
+                    .arg(key(name))
+                    .arg(1)
+                    .arg("NX")
+                    .arg("PX")
+                    .arg(ttl.as_millis().max(1) as u64)
+                    .query_async(&mut (**conn).clone())
+                    .await;
+                match res {
+                    Ok(set) => set.is_some(),
+                    Err(e) => {
+                        tracing::warn!(key = name, "shared state claim failed: {:?}", e);
+                        true
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Local {
+    fn sweep(&mut self) {
+        let now = Instant::now();
+        if self.values.len() >= LOCAL_SWEEP_AT {
+            self.values.retain(|_, (_, expires)| *expires > now);
+        }
+        if self.counters.len() >= LOCAL_SWEEP_AT {
+            self.counters.retain(|_, (_, expires)| *expires > now);
+        }
+    }
+}
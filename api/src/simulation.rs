@@ -0,0 +1,482 @@
+//! Simulation mode: run known-cheat scenarios through a server's enabled modules.
+//!
+//! `POST /admin/simulate` builds a raw batch per scenario (speed, reach, autoclicker), sends it
+//! through every enabled module exactly like live dispatch, waits briefly for findings callbacks
+//! and reports which checks fired. Each scenario uses its own fixture player, so findings are
+//! attributed to a scenario by `player_uuid`.
+//!
+//! HTTP modules get the batches under a `simulate-<run_id>.<module_id>` session id; their
+//! callbacks for such sessions land in `simulation_findings` (see `callbacks::store_findings`)
+//! and never reach the live findings table.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use flate2::{write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Write;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::routes::callbacks::FindingIn;
+use crate::{checks, error::ApiError, module_pipeline, transforms, wasm_runtime, AppState};
+
+pub const SESSION_PREFIX: &str = "simulate-";
+
+/// Default and maximum time to wait for HTTP modules to call back.
+pub const DEFAULT_WAIT: Duration = Duration::from_secs(5);
+pub const MAX_WAIT: Duration = Duration::from_secs(30);
+
+const MAX_REPORTED_ERRORS: usize = 20;
+
+/// A curated cheat scenario.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scenario {
+    Speed,
+    Reach,
+    Autoclicker,
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 3] = [Scenario::Speed, Scenario::Reach, Scenario::Autoclicker];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Scenario::Speed => "speed",
+            Scenario::Reach => "reach",
+            Scenario::Autoclicker => "autoclicker",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        Self::ALL
+            .into_iter()
+            .find(|s| s.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Fixture player for the scenario (never a real Mojang UUID: version nibble 4, fixed tail).
+    pub fn player(self) -> Uuid {
+        let tail: u128 = match self {
+            Scenario::Speed => 0x51_0001,
+            Scenario::Reach => 0x51_0002,
+            Scenario::Autoclicker => 0x51_0003,
+        };
+        Uuid::from_u128(0x0000_0000_0000_4000_8000_0000_0000_0000 | tail)
+    }
+
+    /// Whether a detector name counts as catching this scenario.
+    pub fn matches(self, detector_name: &str) -> bool {
+        let d = detector_name.to_ascii_lowercase();
+        match self {
+            Scenario::Speed => d.contains("speed"),
+            Scenario::Reach => d.contains("reach"),
+            Scenario::Autoclicker => d.contains("autoclick") || d.contains("cps"),
+        }
+    }
+
+    /// Raw packets (without the metadata line) reproducing the cheat.
+    fn packets(self, base_ts: u64) -> Vec<Value> {
+        let player = self.player().to_string();
+        let position = |ts: u64, x: f64| {
+            json!({
+                "ts": ts, "dir": "serverbound", "pkt": "PLAYER_POSITION_AND_ROTATION",
+                "uuid": player, "name": "simulation",
+                "fields": { "x": x, "y": 64.0, "z": 0.0, "yaw": 270.0, "pitch": 0.0, "on_ground": true }
+            })
+        };
+        let spawn = |ts: u64, entity_id: i64, x: f64| {
+            json!({
+                "ts": ts, "dir": "clientbound", "pkt": "SPAWN_ENTITY",
+                "fields": { "entity_id": entity_id, "x": x, "y": 64.0, "z": 0.0 }
+            })
+        };
+        let attack = |ts: u64, entity_id: i64| {
+            json!({
+                "ts": ts, "dir": "serverbound", "pkt": "INTERACT_ENTITY",
+                "uuid": player, "name": "simulation",
+                "fields": { "entity_id": entity_id, "action": "ATTACK", "sneaking": false }
+            })
+        };
+
+        match self {
+            // 2 blocks per 50ms tick on the ground: 40 blocks/s.
+            Scenario::Speed => (0..20u64)
+                .map(|i| position(base_ts + 50 * i, i as f64 * 2.0))
+                .collect(),
+            // Hitting a target 9 blocks away.
+            Scenario::Reach => {
+                let mut packets = vec![spawn(base_ts, 5102, 9.0), position(base_ts + 50, 0.0)];
+                packets.extend((0..3u64).map(|i| attack(base_ts + 500 + 500 * i, 5102)));
+                packets
+            }
+            // 30 attacks 30ms apart (~33 CPS) on an adjacent target.
+            Scenario::Autoclicker => {
+                let mut packets = vec![spawn(base_ts, 5103, 2.0), position(base_ts + 50, 0.0)];
+                packets.extend((0..30u64).map(|i| attack(base_ts + 100 + 30 * i, 5103)));
+                packets
+            }
+        }
+    }
+}
+
+/// Gzipped NDJSON batch for a scenario, shaped like a plugin upload.
+pub fn scenario_batch(
+    scenario: Scenario,
+    server_id: &str,
+    session_id: &str,
+) -> anyhow::Result<Vec<u8>> {
+    let base_ts = Utc::now().timestamp_millis().max(0) as u64;
+    let mut out = Vec::new();
+    let mut encoder = GzEncoder::new(&mut out, Compression::default());
+    let meta = json!({
+        "server_id": server_id,
+        "session_id": session_id,
+        "simulation": scenario.name(),
+    });
+    writeln!(encoder, "{}", meta)?;
+    for packet in scenario.packets(base_ts) {
+        writeln!(encoder, "{}", packet)?;
+    }
+    encoder.finish()?;
+    Ok(out)
+}
+
+pub fn simulation_session(run_id: Uuid, module_id: Uuid) -> String {
+    format!(
+        "{}{}.{}",
+        SESSION_PREFIX,
+        run_id.simple(),
+        module_id.simple()
+    )
+}
+
+/// Parse `(run_id, module_id)` back out of a simulation session id.
+pub fn parse_simulation_session(session_id: &str) -> Option<(Uuid, Uuid)> {
+    let rest = session_id.strip_prefix(SESSION_PREFIX)?;
+    let (run, module) = rest.split_once('.')?;
+    Some((Uuid::parse_str(run).ok()?, Uuid::parse_str(module).ok()?))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FiredCheck {
+    pub module_id: Uuid,
+    pub module_name: String,
+    pub detector_name: String,
+    pub severity: String,
+    pub findings: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScenarioResult {
+    pub scenario: String,
+    pub player_uuid: Uuid,
+    /// Whether any module reported a detector matching the scenario.
+    pub detected: bool,
+    /// Every check that fired for the scenario's player.
+    pub fired: Vec<FiredCheck>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SimulationReport {
+    pub run_id: Uuid,
+    pub server_id: String,
+    pub modules: Vec<String>,
+    pub scenarios: Vec<ScenarioResult>,
+    pub errors: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+struct SimModule {
+    id: Uuid,
+    name: String,
+    base_url: String,
+    transform: String,
+}
+
+/// Run the scenarios against the server's enabled modules and store the report.
+pub async fn run(
+    state: &AppState,
+    server_id: &str,
+    scenarios: Vec<Scenario>,
+    wait: Duration,
+) -> Result<SimulationReport, ApiError> {
+    let server_id = server_id.trim();
+    let modules: Vec<(Uuid, String, String, String)> = sqlx::query_as(
+        r#"
+        select id, name, base_url, transform
+        from public.server_modules
+        where server_id = $1 and enabled = true
+        order by name asc
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("simulation modules lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+    if modules.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "server {} has no enabled modules",
+            server_id
+        )));
+    }
+    let modules: Vec<SimModule> = modules
+        .into_iter()
+        .map(|(id, name, base_url, transform)| SimModule {
+            id,
+            name,
+            base_url,
+            transform,
+        })
+        .collect();
+
+    let run_id = Uuid::new_v4();
+    let created_at: DateTime<Utc> = sqlx::query_scalar(
+        r#"
+        insert into public.simulation_runs (id, server_id, scenarios)
+        values ($1, $2, $3)
+        returning created_at
+        "#,
+    )
+    .bind(run_id)
+    .bind(server_id)
+    .bind(scenarios.iter().map(|s| s.name()).collect::<Vec<_>>())
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("simulation run insert failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    let mut errors: Vec<String> = Vec::new();
+    let push_error = |errors: &mut Vec<String>, e: String| {
+        tracing::warn!(run_id = %run_id, "simulation error: {}", e);
+        if errors.len() < MAX_REPORTED_ERRORS {
+            errors.push(e);
+        }
+    };
+
+    let mut http_dispatched = false;
+    for m in &modules {
+        let session_id = simulation_session(run_id, m.id);
+        for scenario in &scenarios {
+            let raw = scenario_batch(*scenario, server_id, &session_id).map_err(|e| {
+                tracing::error!("simulation fixture failed: {:?}", e);
+                ApiError::Internal
+            })?;
+
+            let local = if let Some(engine) = checks::inproc_engine(&m.base_url) {
+                Some(checks::run(engine, &raw).map_err(|e| e.to_string()))
+            } else if let Some(wasm_id) = wasm_runtime::wasm_module_id(&m.base_url) {
+                Some(
+                    wasm_runtime::run(state, wasm_id, &m.transform, &raw, None)
+                        .await
+                        .map_err(|e| e.to_string()),
+                )
+            } else {
+                None
+            };
+
+            match local {
+                Some(Ok(findings)) => {
+                    record_findings(state, run_id, m.id, &findings).await;
+                }
+                Some(Err(e)) => push_error(&mut errors, format!("{}: {}", m.name, e)),
+                None => {
+                    let payload = match transforms::apply_transform(&m.transform, &raw) {
+                        Ok(p) => p,
+                        Err(e) => {
+                            push_error(&mut errors, format!("{}: transform failed: {}", m.name, e));
+                            continue;
+                        }
+                    };
+                    let resp = module_pipeline::post_ingest(
+                        &state.http,
+                        &m.base_url,
+                        server_id,
+                        &session_id,
+                        Uuid::new_v4(),
+                        Some(m.id),
+                        None,
+                        payload,
+                    )
+                    .await;
+                    match resp {
+                        Ok(r) if r.status().is_success() => http_dispatched = true,
+                        Ok(r) => push_error(
+                            &mut errors,
+                            format!(
+                                "{}: {}: module returned http {}",
+                                m.name,
+                                scenario.name(),
+                                r.status()
+                            ),
+                        ),
+                        Err(e) => push_error(
+                            &mut errors,
+                            format!("{}: {}: {}", m.name, scenario.name(), e),
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    // HTTP modules report asynchronously; wait until every scenario is caught or time runs out.
+    let deadline = Instant::now() + wait.min(MAX_WAIT);
+    let mut results = scenario_results(state, run_id, &scenarios).await?;
+    while http_dispatched && !results.iter().all(|r| r.detected) && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        results = scenario_results(state, run_id, &scenarios).await?;
+    }
+
+    let report = SimulationReport {
+        run_id,
+        server_id: server_id.to_string(),
+        modules: modules.iter().map(|m| m.name.clone()).collect(),
+        scenarios: results,
+        errors,
+        created_at,
+    };
+
+    sqlx::query(
+        "update public.simulation_runs set completed_at = now(), report_json = $2 where id = $1",
+    )
+    .bind(run_id)
+    .bind(sqlx::types::Json(&report))
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("simulation run update failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    tracing::info!(
+        run_id = %run_id,
+        server_id = %server_id,
+        detected = report.scenarios.iter().filter(|r| r.detected).count(),
+        scenarios = report.scenarios.len(),
+        "simulation finished"
+    );
+    Ok(report)
+}
+
+async fn scenario_results(
+    state: &AppState,
+    run_id: Uuid,
+    scenarios: &[Scenario],
+) -> Result<Vec<ScenarioResult>, ApiError> {
+    let rows: Vec<(Uuid, Option<Uuid>, Option<String>, String, String, i64)> = sqlx::query_as(
+        r#"
+        select f.player_uuid, f.module_id, m.name, f.detector_name, f.severity, count(*)
+        from public.simulation_findings f
+        left join public.server_modules m on m.id = f.module_id
+        where f.run_id = $1
+        group by f.player_uuid, f.module_id, m.name, f.detector_name, f.severity
+        order by f.detector_name asc
+        "#,
+    )
+    .bind(run_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("simulation findings lookup failed: {:?}", e);
+        ApiError::Internal
+    })?;
+
+    Ok(scenarios
+        .iter()
+        .map(|scenario| {
+            let player_uuid = scenario.player();
+            let fired: Vec<FiredCheck> = rows
+                .iter()
+                .filter(|(player, ..)| *player == player_uuid)
+                .map(
+                    |(_, module_id, module_name, detector_name, severity, findings)| FiredCheck {
+                        module_id: module_id.unwrap_or(Uuid::nil()),
+                        module_name: module_name.clone().unwrap_or_default(),
+                        detector_name: detector_name.clone(),
+                        severity: severity.clone(),
+                        findings: *findings,
+                    },
+                )
+                .collect();
+            ScenarioResult {
+                scenario: scenario.name().to_string(),
+                player_uuid,
+                detected: fired.iter().any(|f| scenario.matches(&f.detector_name)),
+                fired,
+            }
+        })
+        .collect())
+}
+
+/// Store simulation findings against the run (best-effort). Findings without a fixture
+/// player are dropped; they can't be attributed to a scenario.
+pub async fn record_findings(
+    state: &AppState,
+    run_id: Uuid,
+    module_id: Uuid,
+    findings: &[FindingIn],
+) -> usize {
+    let mut stored = 0;
+    for f in findings {
+        let Some(player_uuid) = f.player_uuid else {
+            continue;
+        };
+        if f.detector_name.trim().is_empty() {
+            continue;
+        }
+        let res = sqlx::query(
+            r#"
+            insert into public.simulation_findings
+                (run_id, module_id, player_uuid, detector_name, severity)
+            select $1, $2, $3, $4, $5
+            from public.simulation_runs r
+            where r.id = $1
+            "#,
+        )
+        .bind(run_id)
+        .bind(module_id)
+        .bind(player_uuid)
+        .bind(f.detector_name.trim())
+        .bind(f.severity.as_deref().unwrap_or("info"))
+        .execute(&state.db)
+        .await;
+        match res {
+            Ok(r) => stored += r.rows_affected() as usize,
+            Err(e) => {
+                tracing::warn!(run_id = %run_id, "simulation finding insert failed: {:?}", e);
+            }
+        }
+    }
+    stored
+}
+
+/// Load a stored simulation report.
+pub async fn report(state: &AppState, run_id: Uuid) -> Result<SimulationReport, ApiError> {
+    let row: Option<(Option<sqlx::types::Json<SimulationReport>>,)> =
+        sqlx::query_as("select report_json from public.simulation_runs where id = $1")
+            .bind(run_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!("simulation run lookup failed: {:?}", e);
+                ApiError::Internal
+            })?;
+    match row {
+        None => Err(ApiError::NotFound(format!(
+            "simulation {} not found",
+            run_id
+        ))),
+        Some((None,)) => Err(ApiError::Conflict(format!(
+            "simulation {} is still running",
+            run_id
+        ))),
+        Some((Some(report),)) => Ok(report.0),
+    }
+}
@@ -0,0 +1,181 @@
+//! Optional process supervisor for module binaries.
+//!
+//! Single-host deployments run the built-in modules on localhost ports 4030-4035. Instead of
+//! maintaining one systemd unit per module, the API can spawn and monitor the module binaries
+//! itself. Supervised modules are restarted with exponential backoff when they exit.
+//!
+//! The supervisor is configured with a JSON file (`MODULE_SUPERVISOR_CONFIG`):
+//!
+//! ```json
+//! [
+//!   {
+//!     "name": "Movement Core",
+//!     "command": "/opt/modules/movement_core",
+//!     "args": ["--port", "4030"],
+//!     "env": { "RUST_LOG": "info" }
+//!   }
+//! ]
+//! ```
+//!
+//! `name` should match the `server_modules.name` so the dashboard can show the process status
+//! next to the module.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+/// A module binary the supervisor should keep running.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SupervisedModuleConfig {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub working_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessState {
+    Starting,
+    Running,
+    Restarting,
+    /// The binary could not be spawned (missing file, permissions, ...).
+    SpawnFailed,
+}
+
+/// Supervised process status as shown in the dashboard.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SupervisedStatus {
+    pub state: ProcessState,
+    pub pid: Option<u32>,
+    pub restarts: u32,
+    pub last_exit: Option<String>,
+    pub started_at: Option<String>,
+}
+
+/// Handle to the supervisor state. Cheap to clone; empty when supervision is disabled.
+#[derive(Clone, Default)]
+pub struct Supervisor {
+    statuses: Arc<RwLock<HashMap<String, SupervisedStatus>>>,
+}
+
+/// Backoff is reset once a process has stayed up for this long.
+pub const STABLE_RUN: Duration = Duration::from_secs(60);
+pub const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Delay before restarting a process that ran for `ran_for`, given the delay before its previous
+/// restart (none for the first): doubling up to [`MAX_BACKOFF`], back to [`INITIAL_BACKOFF`]
+/// after a [`STABLE_RUN`].
+pub fn restart_delay(previous: Option<Duration>, ran_for: Duration) -> Duration {
+    match previous {
+        Some(previous) if ran_for < STABLE_RUN => (previous * 2).min(MAX_BACKOFF),
+        _ => INITIAL_BACKOFF,
+    }
+}
+
+impl Supervisor {
+    /// Load the supervisor config file (JSON array of modules).
+    pub fn load_config(path: &str) -> anyhow::Result<Vec<SupervisedModuleConfig>> {
+        let raw = std::fs::read_to_string(path)?;
+        let modules: Vec<SupervisedModuleConfig> = serde_json::from_str(&raw)?;
+        for m in &modules {
+            if m.name.trim().is_empty() || m.command.trim().is_empty() {
+                anyhow::bail!("supervised module entries require a name and a command");
+            }
+        }
+        Ok(modules)
+    }
+
+    /// Spawn a monitor task for every configured module.
+    pub fn spawn_all(&self, modules: Vec<SupervisedModuleConfig>) {
+        for m in modules {
+            self.set_status(
+                &m.name,
+                SupervisedStatus {
+                    state: ProcessState::Starting,
+                    pid: None,
+                    restarts: 0,
+                    last_exit: None,
+                    started_at: None,
+                },
+            );
+            let supervisor = self.clone();
+            tokio::spawn(async move { supervisor.run_module(m).await });
+        }
+    }
+
+    /// Status for a supervised module, if it is supervised.
+    pub fn status(&self, name: &str) -> Option<SupervisedStatus> {
+        self.statuses.read().ok().and_then(|s| s.get(name).cloned())
+    }
+
+    fn set_status(&self, name: &str, status: SupervisedStatus) {
+        if let Ok(mut s) = self.statuses.write() {
+            s.insert(name.to_string(), status);
+        }
+    }
+
+    fn update_status(&self, name: &str, f: impl FnOnce(&mut SupervisedStatus)) {
+        if let Ok(mut s) = self.statuses.write() {
+            if let Some(status) = s.get_mut(name) {
+                f(status);
+            }
+        }
+    }
+
+    async fn run_module(self, cfg: SupervisedModuleConfig) {
+        let mut backoff = None;
+
+        loop {
+            let mut cmd = tokio::process::Command::new(&cfg.command);
+            cmd.args(&cfg.args).envs(&cfg.env).kill_on_drop(true);
+            if let Some(dir) = &cfg.working_dir {
+                cmd.current_dir(dir);
+            }
+
+            let started = Instant::now();
+            match cmd.spawn() {
+                Ok(mut child) => {
+                    let pid = child.id();
+                    tracing::info!(module = %cfg.name, pid = ?pid, "supervised module started");
+                    self.update_status(&cfg.name, |s| {
+                        s.state = ProcessState::Running;
+                        s.pid = pid;
+                        s.started_at = Some(chrono::Utc::now().to_rfc3339());
+                    });
+
+                    let exit = match child.wait().await {
+                        Ok(status) => status.to_string(),
+                        Err(e) => format!("wait error: {}", e),
+                    };
+                    tracing::warn!(module = %cfg.name, exit = %exit, "supervised module exited");
+                    self.update_status(&cfg.name, |s| {
+                        s.state = ProcessState::Restarting;
+                        s.pid = None;
+                        s.restarts += 1;
+                        s.last_exit = Some(exit);
+                    });
+                }
+                Err(e) => {
+                    tracing::error!(module = %cfg.name, command = %cfg.command, error = %e, "failed to spawn supervised module");
+                    self.update_status(&cfg.name, |s| {
+                        s.state = ProcessState::SpawnFailed;
+                        s.pid = None;
+                        s.last_exit = Some(format!("spawn error: {}", e));
+                    });
+                }
+            }
+
+            let delay = restart_delay(backoff, started.elapsed());
+            backoff = Some(delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
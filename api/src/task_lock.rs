@@ -0,0 +1,187 @@
+//! One replica per background task.
+//!
+//! Every API replica starts the same periodic tasks (healthchecks, cleanup, rollups, job
+//! workers). Before each run a replica takes the task's lease in `task_leases`; only the holder
+//! runs it, the others skip that tick. The holder keeps the lease by renewing it while running
+//! and on every later tick, so a task stays on one replica. If that replica stops, its leases
+//! expire after [`lease_for`] the task interval and the next replica to tick takes over.
+//!
+//! Leases are rows rather than advisory locks so no pool connection is held while a task runs.
+//! `GET /admin/tasks` lists them.
+
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::AppState;
+
+/// Extra lease time on top of two intervals, for slow runs and clock jitter.
+pub const LEASE_MARGIN_SECONDS: u64 = 60;
+
+/// How long a lease lasts: two task intervals plus [`LEASE_MARGIN_SECONDS`].
+pub fn lease_for(interval: Duration) -> Duration {
+    interval * 2 + Duration::from_secs(LEASE_MARGIN_SECONDS)
+}
+
+/// This process's lease holder name: host name (pod name in Kubernetes) and a per-process id.
+pub fn instance_id() -> &'static str {
+    static INSTANCE: OnceLock<String> = OnceLock::new();
+    INSTANCE.get_or_init(|| {
+        let host = std::env::var("HOSTNAME")
+            .ok()
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+            .unwrap_or_else(|| "api".to_string());
+        let id = Uuid::new_v4().simple().to_string();
+        format!("{}-{}", host, &id[..8])
+    })
+}
+
+/// Take or renew the lease of `task` for `holder`; true if `holder` holds it.
+pub async fn acquire(
+    db: &PgPool,
+    task: &str,
+    holder: &str,
+    lease: Duration,
+) -> Result<bool, sqlx::Error> {
+    let held: Option<String> = sqlx::query_scalar(
+        r#"
+        insert into public.task_leases (task, holder, acquired_at, expires_at)
+        values ($1, $2, now(), now() + make_interval(secs => $3))
+        on conflict (task) do update set
+            holder = excluded.holder,
+            acquired_at = case
+                when public.task_leases.holder = excluded.holder
+                then public.task_leases.acquired_at
+                else now()
+            end,
+            expires_at = excluded.expires_at
+        where public.task_leases.holder = excluded.holder
+           or public.task_leases.expires_at < now()
+        returning holder
+        "#,
+    )
+    .bind(task)
+    .bind(holder)
+    .bind(lease.as_secs_f64())
+    .fetch_optional(db)
+    .await?;
+    Ok(held.is_some())
+}
+
+/// Run `tick` if this replica holds (or can take) the lease of `task`, which runs every
+/// `interval`. Returns whether it ran.
+pub async fn run_exclusive<F>(db: &PgPool, task: &str, interval: Duration, tick: F) -> bool
+where
+    F: Future<Output = ()>,
+{
+    let lease = lease_for(interval);
+    match acquire(db, task, instance_id(), lease).await {
+        Ok(true) => {}
+        Ok(false) => {
+            tracing::debug!(task = task, "task lease held by another replica; skipping");
+            return false;
+        }
+        Err(e) => {
+            // Without the lease table we can't tell who runs the task; skip rather than
+            // risk running it on every replica.
+            tracing::warn!(task = task, "task lease check failed: {:?}", e);
+            return false;
+        }
+    }
+
+    // Keep the lease while a long run goes on.
+    let renew = async {
+        let mut ticker = tokio::time::interval(lease / 3);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if let Err(e) = acquire(db, task, instance_id(), lease).await {
+                tracing::warn!(task = task, "task lease renewal failed: {:?}", e);
+            }
+        }
+    };
+    tokio::pin!(tick);
+    tokio::select! {
+        _ = &mut tick => {}
+        _ = renew => {}
+    }
+
+    let _ = sqlx::query(
+        "update public.task_leases set last_finished_at = now() where task = $1 and holder = $2",
+    )
+    .bind(task)
+    .bind(instance_id())
+    .execute(db)
+    .await;
+    true
+}
+
+/// Spawn the periodic background task `task`: every `period`, run `tick` under its lease (see
+/// [`run_exclusive`]). A run longer than the period delays the next tick rather than queueing
+/// catch-up runs.
+pub fn spawn_exclusive<F, Fut>(state: AppState, task: &'static str, period: Duration, tick: F)
+where
+    F: Fn(AppState) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            run_exclusive(&state.db, task, period, tick(state.clone())).await;
+        }
+    });
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TaskLease {
+    pub task: String,
+    pub holder: String,
+    pub acquired_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub last_finished_at: Option<DateTime<Utc>>,
+    /// Whether this API replica is the holder.
+    pub held_here: bool,
+}
+
+type LeaseRow = (
+    String,
+    String,
+    DateTime<Utc>,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+);
+
+/// All task leases, by task name.
+pub async fn list(db: &PgPool) -> Result<Vec<TaskLease>, sqlx::Error> {
+    let rows: Vec<LeaseRow> = sqlx::query_as(
+        r#"
+        select task, holder, acquired_at, expires_at, last_finished_at
+        from public.task_leases
+        order by task asc
+        "#,
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(task, holder, acquired_at, expires_at, last_finished_at)| TaskLease {
+                held_here: holder == instance_id(),
+                task,
+                holder,
+                acquired_at,
+                expires_at,
+                last_finished_at,
+            },
+        )
+        .collect())
+}
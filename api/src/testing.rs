@@ -0,0 +1,272 @@
+//! Test harness for end-to-end pipeline tests without external services.
+//!
+//! Enabled with the `testing` feature (implies `module-sdk`):
+//!
+//! - [`MockModule`]: a module HTTP server on an ephemeral port that records every batch it
+//!   receives and can post canned findings back
+//! - [`CallbackSink`]: stands in for the API's `/callbacks/findings` and records what modules post
+//!   (rejecting callbacks without a `module_id`, like the API)
+//! - [`dispatch`]: transform a raw batch and post it to a module exactly like the pipeline does
+//! - [`fixtures`]: builders for gzipped NDJSON batches and findings
+//!
+//! ```ignore
+//! let sink = CallbackSink::spawn("cb-token");
+//! let module = MockModule::spawn(MockResponse {
+//!     findings: vec![fixtures::finding("speed", "high", player)],
+//!     callback: Some((sink.base_url.clone(), "cb-token".to_string())),
+//!     ..Default::default()
+//! });
+//! dispatch(&http, &module.base_url, module_id, "s1", "sess", batch_id, "movement_events_v1_ndjson_gz", raw).await?;
+//! let posted = sink.wait_for_findings(1, Duration::from_secs(2)).await;
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::{http::HeaderMap, routing::post, Json, Router};
+use uuid::Uuid;
+
+use crate::{
+    auth,
+    error::ApiError,
+    module_pipeline,
+    module_sdk::{self, FindingIn, PostFindingsRequest, PostFindingsResponse, ProcessBatchRequest},
+    transforms,
+};
+
+/// Serve `app` on 127.0.0.1 with an ephemeral port; must be called inside a tokio runtime.
+fn serve(app: Router) -> String {
+    let server = axum::Server::bind(&"127.0.0.1:0".parse().expect("valid socket address"))
+        .serve(app.into_make_service());
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    format!("http://{}", addr)
+}
+
+/// Poll `read` until it holds at least `n` items or `timeout` passes; returns what was seen.
+async fn wait_for<T>(n: usize, timeout: Duration, read: impl Fn() -> Vec<T>) -> Vec<T> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let items = read();
+        if items.len() >= n || tokio::time::Instant::now() >= deadline {
+            return items;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// How a [`MockModule`] answers dispatched batches.
+#[derive(Debug, Clone, Default)]
+pub struct MockResponse {
+    /// Findings reported for every batch received.
+    pub findings: Vec<FindingIn>,
+    /// `(api_base_url, callback_token)` to post findings to. Without it findings are dropped.
+    pub callback: Option<(String, String)>,
+    /// Answer `/ingest` with HTTP 500 (dispatch recorded as failed).
+    pub fail: bool,
+}
+
+/// A module server that records received batches.
+pub struct MockModule {
+    pub base_url: String,
+    received: Arc<Mutex<Vec<ProcessBatchRequest>>>,
+}
+
+impl MockModule {
+    pub fn spawn(response: MockResponse) -> Self {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let recorded = received.clone();
+        let http = reqwest::Client::new();
+        let app = module_sdk::router(move |batch: ProcessBatchRequest| {
+            let recorded = recorded.clone();
+            let response = response.clone();
+            let http = http.clone();
+            async move {
+                recorded.lock().unwrap().push(batch.clone());
+                if response.fail {
+                    return Err("mock module failure".to_string());
+                }
+                if let (Some((api_base_url, token)), false) =
+                    (&response.callback, response.findings.is_empty())
+                {
+                    let req = batch.findings_request(response.findings.clone());
+                    module_sdk::post_findings(&http, api_base_url, token, &req)
+                        .await
+                        .map_err(|e| format!("findings callback failed: {}", e))?;
+                }
+                Ok(())
+            }
+        });
+        Self {
+            base_url: serve(app),
+            received,
+        }
+    }
+
+    /// Batches received so far, in arrival order.
+    pub fn batches(&self) -> Vec<ProcessBatchRequest> {
+        self.received.lock().unwrap().clone()
+    }
+
+    pub async fn wait_for_batches(&self, n: usize, timeout: Duration) -> Vec<ProcessBatchRequest> {
+        wait_for(n, timeout, || self.batches()).await
+    }
+}
+
+/// Records `POST /callbacks/findings` calls, checking the callback token like the API does.
+pub struct CallbackSink {
+    pub base_url: String,
+    received: Arc<Mutex<Vec<PostFindingsRequest>>>,
+}
+
+impl CallbackSink {
+    pub fn spawn(callback_token: &str) -> Self {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let recorded = received.clone();
+        let expected = callback_token.to_string();
+        let app = Router::new().route(
+            "/callbacks/findings",
+            post(
+                move |headers: HeaderMap, Json(req): Json<PostFindingsRequest>| {
+                    let recorded = recorded.clone();
+                    let expected = expected.clone();
+                    async move {
+                        let token =
+                            auth::parse_bearer_token(&headers).ok_or(ApiError::Unauthorized)?;
+                        if !auth::constant_time_eq(&token, &expected) {
+                            return Err(ApiError::Unauthorized);
+                        }
+                        if req.module_id.is_none() {
+                            return Err(ApiError::BadRequest("module_id is required".to_string()));
+                        }
+                        let inserted = req.findings.len();
+                        recorded.lock().unwrap().push(req);
+                        Ok(Json(PostFindingsResponse { ok: true, inserted }))
+                    }
+                },
+            ),
+        );
+        Self {
+            base_url: serve(app),
+            received,
+        }
+    }
+
+    /// Findings callbacks received so far, in arrival order.
+    pub fn findings(&self) -> Vec<PostFindingsRequest> {
+        self.received.lock().unwrap().clone()
+    }
+
+    pub async fn wait_for_findings(&self, n: usize, timeout: Duration) -> Vec<PostFindingsRequest> {
+        wait_for(n, timeout, || self.findings()).await
+    }
+}
+
+/// Transform a raw plugin batch and post it to a module's `/ingest` with the pipeline's headers.
+#[allow(clippy::too_many_arguments)]
+pub async fn dispatch(
+    http: &reqwest::Client,
+    module_base_url: &str,
+    module_id: Uuid,
+    server_id: &str,
+    session_id: &str,
+    batch_id: Uuid,
+    transform: &str,
+    raw_gz_ndjson: &[u8],
+) -> anyhow::Result<reqwest::Response> {
+    let payload = transforms::apply_transform(transform, raw_gz_ndjson)?;
+    Ok(module_pipeline::post_ingest(
+        http,
+        module_base_url,
+        server_id,
+        session_id,
+        batch_id,
+        Some(module_id),
+        None,
+        payload,
+    )
+    .await?)
+}
+
+/// Builders for plugin batches (gzipped NDJSON: metadata line, then one packet per line).
+pub mod fixtures {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    use crate::module_sdk::FindingIn;
+
+    pub fn gzip(ndjson: &str) -> Vec<u8> {
+        let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+        enc.write_all(ndjson.as_bytes())
+            .expect("writing to a Vec cannot fail");
+        enc.finish().expect("writing to a Vec cannot fail")
+    }
+
+    /// A raw batch as uploaded by the plugin.
+    pub fn raw_batch(server_id: &str, session_id: &str, packets: &[Value]) -> Vec<u8> {
+        let mut ndjson = json!({ "server_id": server_id, "session_id": session_id }).to_string();
+        for p in packets {
+            ndjson.push('\n');
+            ndjson.push_str(&p.to_string());
+        }
+        ndjson.push('\n');
+        gzip(&ndjson)
+    }
+
+    /// Serverbound `PLAYER_POSITION` packet.
+    pub fn position(ts: i64, player: Uuid, x: f64, y: f64, z: f64, on_ground: bool) -> Value {
+        json!({
+            "ts": ts,
+            "dir": "serverbound",
+            "pkt": "PLAYER_POSITION",
+            "uuid": player,
+            "name": "player",
+            "fields": { "x": x, "y": y, "z": z, "on_ground": on_ground },
+        })
+    }
+
+    /// Serverbound `INTERACT_ENTITY` attack packet.
+    pub fn attack(ts: i64, player: Uuid, entity_id: i64) -> Value {
+        json!({
+            "ts": ts,
+            "dir": "serverbound",
+            "pkt": "INTERACT_ENTITY",
+            "uuid": player,
+            "name": "player",
+            "fields": { "entity_id": entity_id, "action": "ATTACK", "sneaking": false },
+        })
+    }
+
+    /// Positions moving along +x at `blocks_per_tick` every 50ms, starting at `ts`.
+    pub fn walk(ts: i64, player: Uuid, ticks: usize, blocks_per_tick: f64) -> Vec<Value> {
+        (0..ticks)
+            .map(|i| {
+                position(
+                    ts + 50 * i as i64,
+                    player,
+                    blocks_per_tick * i as f64,
+                    64.0,
+                    0.0,
+                    true,
+                )
+            })
+            .collect()
+    }
+
+    pub fn finding(detector_name: &str, severity: &str, player: Uuid) -> FindingIn {
+        FindingIn {
+            player_uuid: Some(player),
+            detector_name: detector_name.to_string(),
+            detector_version: None,
+            severity: Some(severity.to_string()),
+            title: format!("{} flagged", detector_name),
+            description: None,
+            evidence_s3_key: None,
+            evidence_json: None,
+            confidence: None,
+        }
+    }
+}
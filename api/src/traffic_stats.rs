@@ -0,0 +1,309 @@
+//! Request and response bytes per server and endpoint (`GET /admin/top-talkers`).
+//!
+//! A middleware around every route counts each request under its server (the `X-Server-Id`
+//! header of plugin requests, else the `:server_id` path segment) and endpoint (method and route
+//! template, e.g. `POST /ingest`), in hourly buckets kept for [`RETENTION_HOURS`]. Requests
+//! answered 401/403 and those naming no server count without a server, so unauthenticated
+//! callers can't grow the table; past [`MAX_KEYS_PER_HOUR`] keys in an hour, new servers are
+//! counted without a server too.
+//!
+//! Bytes in are the request's declared length (`Content-Length`); bytes out are counted as the
+//! response body is sent, so streamed downloads count what was actually sent. Counters are kept
+//! in memory per replica: `GET /admin/top-talkers` reports the replica it reaches, alongside
+//! the batch bytes stored per server (from `batch_index`, for every replica).
+
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use axum::{
+    body::{boxed, Bytes, HttpBody},
+    extract::{MatchedPath, State},
+    http::{header::CONTENT_LENGTH, HeaderMap, Request, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use bytes::Buf;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::AppState;
+
+pub const RETENTION_HOURS: i64 = 24;
+pub const MAX_KEYS_PER_HOUR: usize = 20_000;
+/// Endpoints listed per server.
+pub const TOP_ENDPOINTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Traffic {
+    pub requests: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+impl Traffic {
+    fn add(&mut self, other: Traffic) {
+        self.requests += other.requests;
+        self.bytes_in += other.bytes_in;
+        self.bytes_out += other.bytes_out;
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.bytes_in + self.bytes_out
+    }
+}
+
+type Key = (Option<String>, String);
+
+#[derive(Debug)]
+struct Hour {
+    hour: i64,
+    counters: HashMap<Key, Traffic>,
+}
+
+/// Hourly traffic counters of this replica.
+#[derive(Debug, Clone, Default)]
+pub struct TrafficStats(Arc<Mutex<VecDeque<Hour>>>);
+
+/// What to rank servers by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RankBy {
+    #[default]
+    Total,
+    In,
+    Out,
+    Requests,
+}
+
+impl RankBy {
+    fn value(self, t: &Traffic) -> u64 {
+        match self {
+            RankBy::Total => t.total_bytes(),
+            RankBy::In => t.bytes_in,
+            RankBy::Out => t.bytes_out,
+            RankBy::Requests => t.requests,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct EndpointTraffic {
+    /// Method and route template, e.g. `POST /ingest`.
+    pub endpoint: String,
+    #[serde(flatten)]
+    pub traffic: Traffic,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ServerTraffic {
+    /// None: requests naming no server, or unauthenticated.
+    pub server_id: Option<String>,
+    #[serde(flatten)]
+    pub traffic: Traffic,
+    /// The server's busiest endpoints by the same ranking.
+    pub endpoints: Vec<EndpointTraffic>,
+}
+
+impl TrafficStats {
+    /// Count `traffic` in the current hour.
+    pub fn record(&self, server_id: Option<&str>, endpoint: &str, traffic: Traffic) {
+        self.record_at(current_hour(), server_id, endpoint, traffic);
+    }
+
+    pub fn record_at(&self, hour: i64, server_id: Option<&str>, endpoint: &str, traffic: Traffic) {
+        let mut hours = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if hours.back().is_none_or(|h| h.hour < hour) {
+            hours.push_back(Hour {
+                hour,
+                counters: HashMap::new(),
+            });
+        }
+        while hours
+            .front()
+            .is_some_and(|h| h.hour + RETENTION_HOURS <= hour)
+        {
+            hours.pop_front();
+        }
+        // A late addition (a response still streaming) for an hour already dropped is lost.
+        let Some(bucket) = hours.iter_mut().rev().find(|h| h.hour == hour) else {
+            return;
+        };
+        let key = (server_id.map(str::to_string), endpoint.to_string());
+        let key = if bucket.counters.contains_key(&key) || bucket.counters.len() < MAX_KEYS_PER_HOUR
+        {
+            key
+        } else {
+            (None, endpoint.to_string())
+        };
+        bucket.counters.entry(key).or_default().add(traffic);
+    }
+
+    /// Servers ranked by `by` over the `hours` up to and including `hour`.
+    pub fn top_at(&self, hour: i64, hours: i64, by: RankBy, limit: usize) -> Vec<ServerTraffic> {
+        let mut servers: HashMap<Option<String>, (Traffic, HashMap<String, Traffic>)> =
+            HashMap::new();
+        {
+            let buckets = self.0.lock().unwrap_or_else(|e| e.into_inner());
+            for bucket in buckets
+                .iter()
+                .filter(|b| b.hour <= hour && b.hour + hours > hour)
+            {
+                for ((server_id, endpoint), traffic) in &bucket.counters {
+                    let (total, endpoints) = servers.entry(server_id.clone()).or_default();
+                    total.add(*traffic);
+                    endpoints.entry(endpoint.clone()).or_default().add(*traffic);
+                }
+            }
+        }
+
+        let mut ranked: Vec<ServerTraffic> = servers
+            .into_iter()
+            .map(|(server_id, (traffic, endpoints))| {
+                let mut endpoints: Vec<EndpointTraffic> = endpoints
+                    .into_iter()
+                    .map(|(endpoint, traffic)| EndpointTraffic { endpoint, traffic })
+                    .collect();
+                endpoints.sort_by(|a, b| {
+                    by.value(&b.traffic)
+                        .cmp(&by.value(&a.traffic))
+                        .then_with(|| a.endpoint.cmp(&b.endpoint))
+                });
+                endpoints.truncate(TOP_ENDPOINTS);
+                ServerTraffic {
+                    server_id,
+                    traffic,
+                    endpoints,
+                }
+            })
+            .collect();
+        ranked.sort_by(|a, b| {
+            by.value(&b.traffic)
+                .cmp(&by.value(&a.traffic))
+                .then_with(|| a.server_id.cmp(&b.server_id))
+        });
+        ranked.truncate(limit);
+        ranked
+    }
+
+    pub fn top(&self, hours: i64, by: RankBy, limit: usize) -> Vec<ServerTraffic> {
+        self.top_at(current_hour(), hours, by, limit)
+    }
+}
+
+fn current_hour() -> i64 {
+    Utc::now().timestamp() / 3600
+}
+
+/// The server a request is for: `X-Server-Id`, else the `:server_id` segment of the route.
+pub fn request_server(headers: &HeaderMap, route: Option<&str>, path: &str) -> Option<String> {
+    let from_header = headers
+        .get("x-server-id")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    if let Some(id) = from_header {
+        return Some(id.to_string());
+    }
+    let position = route?.split('/').position(|s| s == ":server_id")?;
+    path.split('/')
+        .nth(position)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Middleware around every route: count request and response bytes.
+pub async fn track<B>(State(state): State<AppState>, req: Request<B>, next: Next<B>) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string());
+    let endpoint = format!(
+        "{} {}",
+        req.method(),
+        route.as_deref().unwrap_or("(unmatched)")
+    );
+    let server_id = request_server(req.headers(), route.as_deref(), req.uri().path());
+    let bytes_in = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+
+    let response = next.run(req).await;
+    let server_id = match response.status() {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => None,
+        _ => server_id,
+    };
+    state.traffic.record(
+        server_id.as_deref(),
+        &endpoint,
+        Traffic {
+            requests: 1,
+            bytes_in,
+            bytes_out: 0,
+        },
+    );
+    response.map(|body| {
+        boxed(CountingBody {
+            inner: body,
+            stats: state.traffic.clone(),
+            server_id,
+            endpoint,
+        })
+    })
+}
+
+/// Response body counting the bytes sent.
+struct CountingBody<B> {
+    inner: B,
+    stats: TrafficStats,
+    server_id: Option<String>,
+    endpoint: String,
+}
+
+impl<B> HttpBody for CountingBody<B>
+where
+    B: HttpBody<Data = Bytes> + Unpin,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let polled = Pin::new(&mut self.inner).poll_data(cx);
+        if let Poll::Ready(Some(Ok(ref chunk))) = polled {
+            self.stats.record(
+                self.server_id.as_deref(),
+                &self.endpoint,
+                Traffic {
+                    requests: 0,
+                    bytes_in: 0,
+                    bytes_out: chunk.remaining() as u64,
+                },
+            );
+        }
+        polled
+    }
+
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Pin::new(&mut self.inner).poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+}
@@ -0,0 +1,152 @@
+//! Lines produced by the standard transforms.
+//!
+//! The transforms serialize these structs directly, and modules built on the `module-sdk`
+//! feature decode the same types, so both sides agree on the shape. Optional fields are left
+//! out of the line when unset. Floats are always finite: transforms write 0 in place of NaN or
+//! infinity.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::transforms::ClientType;
+
+/// Line emitted by `movement_events_v1`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MovementEvent {
+    pub ts: u64,
+    pub uuid: Uuid,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_ground: Option<bool>,
+    /// Deltas from the player's previous position in the batch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dt_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dx: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dy: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dz: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_bps: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gamemode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_flying: Option<bool>,
+    /// Creative/spectator or `/fly`: flight checks should skip these events.
+    #[serde(default)]
+    pub flight_allowed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_type: Option<ClientType>,
+    /// The player's protocol version (ViaVersion), when it differs from or isn't the server's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_protocol: Option<i32>,
+}
+
+/// Line emitted by `combat_events_v1`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CombatEvent {
+    pub ts: u64,
+    pub uuid: Uuid,
+    pub entity_id: i64,
+    pub sneaking: bool,
+    /// Last known pose of the attacker.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_x: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_y: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_z: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_yaw: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_pitch: Option<f64>,
+    /// Compared with the player's previous attack in the batch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dt_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attacks_per_second: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_switched: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub yaw_diff: Option<f64>,
+    /// Milliseconds since the target was last shown hurt in the batch (clientbound hurt packets).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_hurt_ago_ms: Option<f64>,
+    /// The attack landed within the target's invulnerability window (10 ticks after a hurt).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_invulnerable: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_type: Option<ClientType>,
+    /// The player's protocol version (ViaVersion), when it differs from or isn't the server's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_protocol: Option<i32>,
+}
+
+/// Line emitted by `ncp_fight_v1`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FightEvent {
+    pub ts: u64,
+    pub uuid: Uuid,
+    pub entity_id: i64,
+    pub player_x: f64,
+    pub player_y: f64,
+    pub player_z: f64,
+    pub player_yaw: f64,
+    pub player_pitch: f64,
+    /// Target position at the attack and geometry, when the target was tracked in the batch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_x: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_y: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_z: Option<f64>,
+    /// Target entity type from its spawn packet (`zombie`, `player`), when seen in the batch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_type: Option<String>,
+    /// Eyes to the target's position (its feet).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reach_distance: Option<f64>,
+    /// Eyes to the center of the target's hitbox.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reach_center: Option<f64>,
+    /// Eyes to the nearest point of the target's hitbox (grown by 0.1 for 1.8 clients), as NCP
+    /// measures reach.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reach_hitbox: Option<f64>,
+    /// How far the target may be off the position used: positions between packets are
+    /// interpolated (or extrapolated) to the attack, and every reach value is within this of the
+    /// one at the true position.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reach_uncertainty: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aim_off: Option<f64>,
+    /// Milliseconds since the target was last shown hurt in the batch (clientbound hurt packets).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_hurt_ago_ms: Option<f64>,
+    /// The attack landed within the target's invulnerability window (10 ticks after a hurt).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_invulnerable: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_type: Option<ClientType>,
+    /// The player's protocol version (ViaVersion), when it differs from or isn't the server's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_protocol: Option<i32>,
+}
+
+/// Line emitted by `client_channels_v1`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClientChannelEvent {
+    pub ts: u64,
+    pub uuid: Uuid,
+    /// "brand" | "register"
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brand: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channels: Option<Vec<String>>,
+}
@@ -0,0 +1,183 @@
+//! Worker pool for batch transforms.
+//!
+//! Transforms and in-process checks decompress, parse and re-encode whole batches. Run inline in
+//! dispatch tasks they would hold runtime threads and starve request handling under load, so
+//! the module pipeline hands them to this pool instead. It runs jobs on the blocking thread
+//! pool, at most `TRANSFORM_WORKERS` at a time; up to `TRANSFORM_QUEUE_CAPACITY` more wait for a
+//! worker, and further jobs are rejected right away rather than queued without bound. A job
+//! that exceeds its time budget (`TRANSFORM_BUDGET_MS`, counted from when it starts) is
+//! abandoned: the dispatch fails, and the job keeps its worker until it finishes so the pool
+//! never runs more than its size.
+//!
+//! Counters for waiting, running, rejected and timed out jobs are served on
+//! `GET /admin/transform-pool`.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use utoipa::ToSchema;
+
+#[derive(Debug)]
+pub enum PoolError {
+    /// Workers and queue were full.
+    Saturated,
+    /// The job ran past the time budget.
+    TimedOut(Duration),
+    Failed(anyhow::Error),
+}
+
+impl std::fmt::Display for PoolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoolError::Saturated => write!(f, "transform pool saturated"),
+            PoolError::TimedOut(budget) => {
+                write!(f, "transform exceeded its {}ms budget", budget.as_millis())
+            }
+            PoolError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    waiting: AtomicUsize,
+    running: AtomicUsize,
+    completed: AtomicU64,
+    failed: AtomicU64,
+    rejected: AtomicU64,
+    timed_out: AtomicU64,
+    started: AtomicU64,
+    wait_us: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransformPool {
+    workers: usize,
+    queue_capacity: usize,
+    budget: Duration,
+    /// Workers and queue slots together: a job holds one from submission to completion.
+    slots: Arc<Semaphore>,
+    running: Arc<Semaphore>,
+    counters: Arc<Counters>,
+}
+
+/// Pool counters at one point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PoolSnapshot {
+    pub workers: usize,
+    pub queue_capacity: usize,
+    pub budget_ms: u64,
+    /// Jobs waiting for a worker.
+    pub waiting: usize,
+    pub running: usize,
+    /// Share (0-1) of workers and queue slots in use; jobs are rejected at 1.
+    pub saturation: f64,
+    pub completed: u64,
+    pub failed: u64,
+    pub rejected: u64,
+    pub timed_out: u64,
+    /// Average time jobs waited for a worker; None before the first one started.
+    pub avg_wait_ms: Option<f64>,
+}
+
+impl TransformPool {
+    pub fn new(workers: usize, queue_capacity: usize, budget: Duration) -> Self {
+        let workers = workers.max(1);
+        Self {
+            workers,
+            queue_capacity,
+            budget,
+            slots: Arc::new(Semaphore::new(workers + queue_capacity)),
+            running: Arc::new(Semaphore::new(workers)),
+            counters: Arc::default(),
+        }
+    }
+
+    /// Run `job` on a pool worker, waiting for one if needed.
+    pub async fn run<T, F>(&self, job: F) -> Result<T, PoolError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+    {
+        let Ok(slot) = self.slots.clone().try_acquire_owned() else {
+            self.counters.rejected.fetch_add(1, Ordering::Relaxed);
+            return Err(PoolError::Saturated);
+        };
+
+        let queued_at = Instant::now();
+        self.counters.waiting.fetch_add(1, Ordering::Relaxed);
+        let worker = self.running.clone().acquire_owned().await;
+        self.counters.waiting.fetch_sub(1, Ordering::Relaxed);
+        let Ok(worker) = worker else {
+            // The semaphore is never closed.
+            return Err(PoolError::Saturated);
+        };
+        let waited = queued_at.elapsed().as_micros().min(u64::MAX as u128) as u64;
+        self.counters.wait_us.fetch_add(waited, Ordering::Relaxed);
+        self.counters.started.fetch_add(1, Ordering::Relaxed);
+
+        self.counters.running.fetch_add(1, Ordering::Relaxed);
+        let counters = self.counters.clone();
+        let handle = tokio::task::spawn_blocking(move || {
+            let result = job();
+            counters.running.fetch_sub(1, Ordering::Relaxed);
+            drop((worker, slot));
+            result
+        });
+
+        match tokio::time::timeout(self.budget, handle).await {
+            Ok(Ok(Ok(value))) => {
+                self.counters.completed.fetch_add(1, Ordering::Relaxed);
+                Ok(value)
+            }
+            Ok(Ok(Err(e))) => {
+                self.counters.failed.fetch_add(1, Ordering::Relaxed);
+                Err(PoolError::Failed(e))
+            }
+            Ok(Err(join)) => {
+                self.counters.failed.fetch_add(1, Ordering::Relaxed);
+                Err(PoolError::Failed(anyhow::anyhow!(
+                    "transform panicked: {}",
+                    join
+                )))
+            }
+            Err(_) => {
+                self.counters.timed_out.fetch_add(1, Ordering::Relaxed);
+                Err(PoolError::TimedOut(self.budget))
+            }
+        }
+    }
+
+    pub fn snapshot(&self) -> PoolSnapshot {
+        let c = &self.counters;
+        let (waiting, running) = (
+            c.waiting.load(Ordering::Relaxed),
+            c.running.load(Ordering::Relaxed),
+        );
+        let (completed, failed, timed_out) = (
+            c.completed.load(Ordering::Relaxed),
+            c.failed.load(Ordering::Relaxed),
+            c.timed_out.load(Ordering::Relaxed),
+        );
+        let capacity = self.workers + self.queue_capacity;
+        let in_use = capacity - self.slots.available_permits();
+        let started = c.started.load(Ordering::Relaxed);
+        PoolSnapshot {
+            workers: self.workers,
+            queue_capacity: self.queue_capacity,
+            budget_ms: self.budget.as_millis().min(u64::MAX as u128) as u64,
+            waiting,
+            running,
+            saturation: in_use as f64 / capacity as f64,
+            completed,
+            failed,
+            rejected: c.rejected.load(Ordering::Relaxed),
+            timed_out,
+            avg_wait_ms: (started > 0)
+                .then(|| c.wait_us.load(Ordering::Relaxed) as f64 / started as f64 / 1000.0),
+        }
+    }
+}
@@ -6,12 +6,121 @@
 //! ## Available Transforms
 //!
 //! - `raw_ndjson_gz`: Pass-through, no transformation
-//! - `movement_events_v1_ndjson_gz`: Normalized movement events with deltas and speed
+//! - `movement_events_v1_ndjson_gz`: Normalized movement events with deltas and speed, plus the
+//!   player's world / gamemode context
 //! - `combat_events_v1_ndjson_gz`: Attack events with timing and target info for killaura/reach
+//! - `client_channels_v1_ndjson_gz`: Client brand and plugin-channel registrations
+//!
+//! The metadata line is standardized first (see `batch_meta`). Every record goes through a
+//! pre-pass, so the transforms see the same names whatever produced the batch: packet names are
+//! mapped to the canonical ones for the metadata line's `mc_version` (see `packet_names`), and
+//! batches from plugins older than [`CANONICAL_FIELDS_SINCE`] (per `plugin_version`) have
+//! legacy field names (`yRot`, `ground`, ...) renamed.
+//!
+//! The metadata line may also carry `client_types` (`{"<uuid>": "bedrock"}`) for servers running
+//! Geyser; movement and combat events of those players get a `client_type` field so checks can
+//! apply their Bedrock thresholds. Likewise `client_protocols` (`{"<uuid>": 47}`), the protocol
+//! version each player joined with as reported by ViaVersion, becomes a `client_protocol` field:
+//! 1.8 and 1.9+ clients time combat very differently, and checks pick per-version thresholds.
+//!
+//! A transform may be requested with the `+server_time` option (`movement_events_v1_ndjson_gz+server_time`):
+//! record `ts` values are then rebased from the plugin's clock onto the API's by subtracting the
+//! batch's clock skew (see `clock_skew`), and the metadata line says which clock `ts` is on
+//! (`ts_base`: `server`, or `client` when no skew is known for the batch) and the `clock_skew_ms`
+//! applied.
+
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::batch_meta;
+use crate::checks::LEGACY_COMBAT_MAX_PROTOCOL;
+use crate::hitboxes;
+use crate::ndjson::RecordParser;
+use crate::packet_names;
+use crate::plugin_version::Version;
+use crate::transform_events::{ClientChannelEvent, CombatEvent, FightEvent, MovementEvent};
+
+/// Transform names accepted by `apply_transform`.
+pub const SUPPORTED: [&str; 5] = [
+    "raw_ndjson_gz",
+    "movement_events_v1_ndjson_gz",
+    "combat_events_v1_ndjson_gz",
+    "ncp_fight_v1_ndjson_gz",
+    "client_channels_v1_ndjson_gz",
+];
+
+/// Transform option: rebase record `ts` onto API time.
+pub const SERVER_TIME_OPTION: &str = "server_time";
+
+/// Split `<transform>[+<option>...]` into the transform name and whether `+server_time` is set.
+fn parse_options(transform: &str) -> anyhow::Result<(&str, bool)> {
+    let mut parts = transform.trim().split('+');
+    let name = parts.next().unwrap_or("").trim();
+    let mut server_time = false;
+    for option in parts {
+        if option.trim().eq_ignore_ascii_case(SERVER_TIME_OPTION) {
+            server_time = true;
+        } else {
+            anyhow::bail!("unsupported transform option: {}", option);
+        }
+    }
+    Ok((name, server_time))
+}
+
+pub fn is_supported(transform: &str) -> bool {
+    let Ok((t, _)) = parse_options(transform) else {
+        return false;
+    };
+    SUPPORTED.iter().any(|s| s.eq_ignore_ascii_case(t))
+}
 
+/// Whether the transform was requested with `+server_time` (callers then look up the skew).
+pub fn wants_server_time(transform: &str) -> bool {
+    parse_options(transform).is_ok_and(|(_, server_time)| server_time)
+}
+
+/// Apply a transform without a known clock skew (`+server_time` leaves `ts` on the plugin clock).
 pub fn apply_transform(transform: &str, raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
-    let t = transform.trim();
-    if t.is_empty() || t.eq_ignore_ascii_case("raw_ndjson_gz") {
+    apply_transform_with(transform, raw_gz_ndjson, None)
+}
+
+/// Apply a transform to a batch whose session's clock skew is `clock_skew_ms`.
+pub fn apply_transform_with(
+    transform: &str,
+    raw_gz_ndjson: &[u8],
+    clock_skew_ms: Option<i64>,
+) -> anyhow::Result<Vec<u8>> {
+    let (t, server_time) = parse_options(transform)?;
+    if server_time {
+        let rebased = rebase_ts(raw_gz_ndjson, clock_skew_ms)?;
+        return apply_named(t, &rebased);
+    }
+    apply_named(t, raw_gz_ndjson)
+}
+
+/// Like [`apply_transform_with`], but a raw passthrough shares `raw_gz_ndjson`'s buffer instead
+/// of copying the batch.
+pub fn apply_transform_shared(
+    transform: &str,
+    raw_gz_ndjson: &Bytes,
+    clock_skew_ms: Option<i64>,
+) -> anyhow::Result<Bytes> {
+    let (t, server_time) = parse_options(transform)?;
+    if !server_time && is_raw(t) {
+        return Ok(raw_gz_ndjson.clone());
+    }
+    apply_transform_with(transform, raw_gz_ndjson, clock_skew_ms).map(Bytes::from)
+}
+
+fn is_raw(t: &str) -> bool {
+    t.is_empty() || t.eq_ignore_ascii_case("raw_ndjson_gz")
+}
+
+fn apply_named(t: &str, raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
+    if is_raw(t) {
         return Ok(raw_gz_ndjson.to_vec());
     }
 
@@ -27,7 +136,63 @@ pub fn apply_transform(transform: &str, raw_gz_ndjson: &[u8]) -> anyhow::Result<
         return ncp_fight_v1(raw_gz_ndjson);
     }
 
-    anyhow::bail!("unsupported transform: {}", transform)
+    if t.eq_ignore_ascii_case("client_channels_v1_ndjson_gz") {
+        return client_channels_v1(raw_gz_ndjson);
+    }
+
+    anyhow::bail!("unsupported transform: {}", t)
+}
+
+/// Rebase record `ts` onto API time (`ts - clock_skew_ms`) and note the clock on the metadata
+/// line. Without a skew the records are left on the plugin clock (`ts_base: client`).
+pub fn rebase_ts(raw_gz_ndjson: &[u8], clock_skew_ms: Option<i64>) -> anyhow::Result<Vec<u8>> {
+    use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+    use serde_json::Value;
+    use std::io::{BufRead, BufReader, Write};
+
+    let reader = BufReader::new(GzDecoder::new(raw_gz_ndjson));
+    let mut out = Vec::new();
+    let mut encoder = GzEncoder::new(&mut out, Compression::default());
+    let mut parser = RecordParser::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if i == 0 {
+            let mut meta = batch_meta::standardized(&line);
+            if let Some(obj) = meta.as_object_mut() {
+                let base = if clock_skew_ms.is_some() {
+                    "server"
+                } else {
+                    "client"
+                };
+                obj.insert("ts_base".to_string(), Value::String(base.to_string()));
+                if let Some(skew) = clock_skew_ms {
+                    obj.insert("clock_skew_ms".to_string(), Value::Number(skew.into()));
+                }
+            }
+            writeln!(encoder, "{}", serde_json::to_string(&meta)?)?;
+            continue;
+        }
+
+        let Some(skew) = clock_skew_ms else {
+            writeln!(encoder, "{}", line)?;
+            continue;
+        };
+        let Some(mut v) = parser.parse(&line) else {
+            writeln!(encoder, "{}", line)?;
+            continue;
+        };
+        match v.get("ts").and_then(|x| x.as_i64()) {
+            Some(ts) => {
+                v["ts"] = Value::Number(ts.saturating_sub(skew).max(0).into());
+                writeln!(encoder, "{}", serde_json::to_string(&v)?)?;
+            }
+            None => writeln!(encoder, "{}", line)?,
+        }
+    }
+
+    encoder.finish()?;
+    Ok(out)
 }
 
 fn movement_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
@@ -50,11 +215,16 @@ fn movement_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
 
     let mut out = Vec::new();
     let mut encoder = GzEncoder::new(&mut out, Compression::default());
+    let mut parser = RecordParser::new();
 
     let mut buf = String::new();
     let mut line_no = 0usize;
+    let mut prepass = Prepass::default();
+    let mut clients: HashMap<Uuid, ClientType> = HashMap::new();
+    let mut protocols: HashMap<Uuid, i32> = HashMap::new();
     // (kept for future metrics: output event count)
     let mut last: HashMap<Uuid, LastPos> = HashMap::new();
+    let mut context: HashMap<Uuid, PlayerContext> = HashMap::new();
 
     while {
         buf.clear();
@@ -69,8 +239,10 @@ fn movement_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
 
         // First line: pass through, but annotate transform.
         if line_no == 1 {
-            let mut meta: Value =
-                serde_json::from_str(line).unwrap_or(Value::Object(Default::default()));
+            let mut meta = batch_meta::standardized(line);
+            prepass = Prepass::from_meta(&meta);
+            clients = client_types(&meta);
+            protocols = client_protocols(&meta);
             if let Some(obj) = meta.as_object_mut() {
                 obj.insert(
                     "transform".to_string(),
@@ -81,10 +253,10 @@ fn movement_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
             continue;
         }
 
-        let v: Value = match serde_json::from_str(line) {
-            Ok(v) => v,
-            Err(_) => continue,
+        let Some(mut v) = parser.parse(line) else {
+            continue;
         };
+        prepass.apply(&mut v);
         let uuid = v
             .get("uuid")
             .and_then(|x| x.as_str())
@@ -93,6 +265,10 @@ fn movement_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
         let Some(uuid) = uuid else { continue };
         let Some(ts) = ts else { continue };
 
+        // Context comes from any record (PLAYER_STATE mostly), not just position packets.
+        let ctx = context.entry(uuid).or_default();
+        ctx.update(&v);
+
         let fields = v.get("fields").and_then(|x| x.as_object());
         let Some(fields) = fields else { continue };
         let x = fields.get("x").and_then(|x| x.as_f64());
@@ -108,15 +284,25 @@ fn movement_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
             continue;
         }
 
-        let mut obj = serde_json::Map::new();
-        obj.insert("ts".to_string(), Value::Number(ts.into()));
-        obj.insert("uuid".to_string(), Value::String(uuid.to_string()));
-        obj.insert("x".to_string(), json_f64(x));
-        obj.insert("y".to_string(), json_f64(y));
-        obj.insert("z".to_string(), json_f64(z));
-        if let Some(og) = on_ground {
-            obj.insert("on_ground".to_string(), Value::Bool(og));
-        }
+        let mut event = MovementEvent {
+            ts,
+            uuid,
+            x,
+            y,
+            z,
+            on_ground,
+            dt_ms: None,
+            dx: None,
+            dy: None,
+            dz: None,
+            speed_bps: None,
+            world: ctx.world.clone(),
+            gamemode: ctx.gamemode.clone(),
+            allow_flying: ctx.allow_flying,
+            flight_allowed: flight_allowed(ctx.gamemode.as_deref(), ctx.allow_flying),
+            client_type: clients.get(&uuid).copied(),
+            client_protocol: protocols.get(&uuid).copied(),
+        };
 
         if let Some(prev) = last.get(&uuid).copied() {
             if ts > prev.ts {
@@ -131,16 +317,16 @@ fn movement_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
                 } else {
                     0.0
                 };
-                obj.insert("dt_ms".to_string(), json_f64(dt_ms));
-                obj.insert("dx".to_string(), json_f64(dx));
-                obj.insert("dy".to_string(), json_f64(dy));
-                obj.insert("dz".to_string(), json_f64(dz));
-                obj.insert("speed_bps".to_string(), json_f64(bps));
+                event.dt_ms = Some(dt_ms);
+                event.dx = Some(dx);
+                event.dy = Some(dy);
+                event.dz = Some(dz);
+                event.speed_bps = Some(finite(bps));
             }
         }
 
         last.insert(uuid, LastPos { ts, x, y, z });
-        writeln!(encoder, "{}", Value::Object(obj))?;
+        write_event(&mut encoder, &event)?;
     }
 
     encoder.finish()?;
@@ -153,6 +339,8 @@ fn movement_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
 /// - Time since last attack (for attack speed checks)
 /// - Yaw/pitch changes between attacks (for angle/killaura detection)
 /// - Target switching patterns
+/// - Time since the target was last hurt, and whether the attack fell inside its invulnerability
+///   window (from clientbound hurt packets)
 ///
 /// Based on NoCheatPlus checks:
 /// - Angle: Tracks yaw changes when switching targets rapidly
@@ -170,7 +358,6 @@ fn combat_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
         ts: u64,
         target_entity_id: i64,
         yaw: Option<f64>,
-        pitch: Option<f64>,
     }
 
     let decoder = GzDecoder::new(raw_gz_ndjson);
@@ -178,10 +365,15 @@ fn combat_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
 
     let mut out = Vec::new();
     let mut encoder = GzEncoder::new(&mut out, Compression::default());
+    let mut parser = RecordParser::new();
 
     let mut buf = String::new();
     let mut line_no = 0usize;
+    let mut prepass = Prepass::default();
+    let mut clients: HashMap<Uuid, ClientType> = HashMap::new();
+    let mut protocols: HashMap<Uuid, i32> = HashMap::new();
     let mut last_attacks: HashMap<Uuid, LastAttack> = HashMap::new();
+    let mut hurt = HurtTracker::default();
     // Track last known position/rotation per player (from position packets)
     let mut last_pos: HashMap<Uuid, (f64, f64, f64, f64, f64)> = HashMap::new(); // (x, y, z, yaw, pitch)
 
@@ -198,8 +390,11 @@ fn combat_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
 
         // First line: pass through, but annotate transform.
         if line_no == 1 {
-            let mut meta: Value =
-                serde_json::from_str(line).unwrap_or(Value::Object(Default::default()));
+            let mut meta = batch_meta::standardized(line);
+            prepass = Prepass::from_meta(&meta);
+            clients = client_types(&meta);
+            protocols = client_protocols(&meta);
+            hurt = HurtTracker::from_meta(&meta);
             if let Some(obj) = meta.as_object_mut() {
                 obj.insert(
                     "transform".to_string(),
@@ -210,10 +405,10 @@ fn combat_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
             continue;
         }
 
-        let v: Value = match serde_json::from_str(line) {
-            Ok(v) => v,
-            Err(_) => continue,
+        let Some(mut v) = parser.parse(line) else {
+            continue;
         };
+        prepass.apply(&mut v);
 
         let uuid = v
             .get("uuid")
@@ -222,13 +417,20 @@ fn combat_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
         let ts = v.get("ts").and_then(|x| x.as_u64());
         let pkt = v.get("pkt").and_then(|x| x.as_str()).unwrap_or("");
 
-        let Some(uuid) = uuid else { continue };
         let Some(ts) = ts else { continue };
 
         let fields = v.get("fields").and_then(|x| x.as_object());
 
+        // Hurt packets of the target (clientbound, possibly without the receiver's uuid).
+        let dir = v.get("dir").and_then(|x| x.as_str()).unwrap_or("");
+        if fields.is_some_and(|f| hurt.observe(dir, pkt, f, ts)) {
+            continue;
+        }
+
+        let Some(uuid) = uuid else { continue };
+
         // Track position updates for context
-        if pkt.contains("POSITION") || pkt.contains("ROTATION") {
+        if packet_names::is_player_movement(pkt) {
             if let Some(fields) = fields {
                 let x = fields.get("x").and_then(|v| v.as_f64());
                 let y = fields.get("y").and_then(|v| v.as_f64());
@@ -255,7 +457,7 @@ fn combat_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
         }
 
         // Only emit attack events
-        if !pkt.contains("INTERACT") && !pkt.contains("USE_ENTITY") {
+        if pkt != packet_names::INTERACT_ENTITY {
             continue;
         }
 
@@ -274,42 +476,50 @@ fn combat_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
             .and_then(|x| x.as_bool())
             .unwrap_or(false);
 
-        let mut obj = serde_json::Map::new();
-        obj.insert("ts".to_string(), Value::Number(ts.into()));
-        obj.insert("uuid".to_string(), Value::String(uuid.to_string()));
-        obj.insert("entity_id".to_string(), Value::Number(entity_id.into()));
-        obj.insert("sneaking".to_string(), Value::Bool(sneaking));
-
-        // Add player position/rotation context
-        if let Some((x, y, z, yaw, pitch)) = last_pos.get(&uuid).copied() {
-            obj.insert("player_x".to_string(), json_f64(x));
-            obj.insert("player_y".to_string(), json_f64(y));
-            obj.insert("player_z".to_string(), json_f64(z));
-            obj.insert("player_yaw".to_string(), json_f64(yaw));
-            obj.insert("player_pitch".to_string(), json_f64(pitch));
-        }
+        let pose = last_pos.get(&uuid).copied();
+        let (target_hurt_ago_ms, target_invulnerable) = hurt.annotate(entity_id, ts);
+        let mut event = CombatEvent {
+            ts,
+            uuid,
+            entity_id,
+            sneaking,
+            // Player position/rotation context
+            player_x: pose.map(|p| finite(p.0)),
+            player_y: pose.map(|p| finite(p.1)),
+            player_z: pose.map(|p| finite(p.2)),
+            player_yaw: pose.map(|p| finite(p.3)),
+            player_pitch: pose.map(|p| finite(p.4)),
+            dt_ms: None,
+            attacks_per_second: None,
+            target_switched: None,
+            yaw_diff: None,
+            target_hurt_ago_ms,
+            target_invulnerable,
+            client_type: clients.get(&uuid).copied(),
+            client_protocol: protocols.get(&uuid).copied(),
+        };
 
         // Calculate deltas from last attack (for NCP-style checks)
         if let Some(prev) = last_attacks.get(&uuid).cloned() {
             let dt_ms = ts.saturating_sub(prev.ts) as f64;
-            obj.insert("dt_ms".to_string(), json_f64(dt_ms));
+            event.dt_ms = Some(dt_ms);
 
             // Attacks per second based on this interval
             if dt_ms > 0.0 {
                 let aps = 1000.0 / dt_ms;
-                obj.insert("attacks_per_second".to_string(), json_f64(aps));
+                event.attacks_per_second = Some(aps);
             }
 
             // Target switching detection (key for angle/killaura checks)
             let target_changed = entity_id != prev.target_entity_id;
-            obj.insert("target_switched".to_string(), Value::Bool(target_changed));
+            event.target_switched = Some(target_changed);
 
             // Yaw difference (critical for angle check)
             if let (Some(prev_yaw), Some((_, _, _, curr_yaw, _))) =
                 (prev.yaw, last_pos.get(&uuid).copied())
             {
                 let yaw_diff = yaw_difference(curr_yaw, prev_yaw);
-                obj.insert("yaw_diff".to_string(), json_f64(yaw_diff));
+                event.yaw_diff = Some(finite(yaw_diff));
             }
         }
 
@@ -320,44 +530,357 @@ fn combat_events_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
                 ts,
                 target_entity_id: entity_id,
                 yaw: last_pos.get(&uuid).map(|p| p.3),
-                pitch: last_pos.get(&uuid).map(|p| p.4),
             },
         );
 
-        writeln!(encoder, "{}", Value::Object(obj))?;
+        write_event(&mut encoder, &event)?;
     }
 
     encoder.finish()?;
     Ok(out)
 }
 
-fn json_f64(v: f64) -> serde_json::Value {
-    serde_json::Value::Number(
-        serde_json::Number::from_f64(v).unwrap_or_else(|| serde_json::Number::from(0)),
-    )
+/// Plugins before this version sent some fields under other names (see [`FIELD_ALIASES`]).
+pub const CANONICAL_FIELDS_SINCE: Version = Version(0, 4, 0);
+
+/// `(legacy, canonical)` packet field names.
+pub const FIELD_ALIASES: [(&str, &str); 6] = [
+    ("yRot", "yaw"),
+    ("xRot", "pitch"),
+    ("ground", "on_ground"),
+    ("onGround", "on_ground"),
+    ("entityId", "entity_id"),
+    ("isSneaking", "sneaking"),
+];
+
+/// Whether a batch may use legacy field names: its metadata line reports a `plugin_version`
+/// older than [`CANONICAL_FIELDS_SINCE`], or no parseable version at all.
+pub fn uses_legacy_fields(meta: &serde_json::Value) -> bool {
+    meta.get("plugin_version")
+        .and_then(|v| v.as_str())
+        .and_then(Version::parse)
+        .is_none_or(|v| v < CANONICAL_FIELDS_SINCE)
+}
+
+/// Rename legacy keys in a record's `fields` to their canonical names. A canonical key that is
+/// already present wins, so normalizing a current record changes nothing.
+pub fn normalize_legacy_fields(record: &mut serde_json::Value) {
+    let Some(fields) = record.get_mut("fields").and_then(|f| f.as_object_mut()) else {
+        return;
+    };
+    for (legacy, canonical) in FIELD_ALIASES {
+        if let Some(value) = fields.remove(legacy) {
+            fields.entry(canonical).or_insert(value);
+        }
+    }
+}
+
+/// Edition of the client a player connected with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientType {
+    Java,
+    /// Bedrock client joined through Geyser.
+    Bedrock,
 }
 
-/// Calculate the absolute yaw difference, handling wraparound at 360°
-fn yaw_difference(yaw1: f64, yaw2: f64) -> f64 {
-    let mut diff = (yaw1 - yaw2).abs();
-    if diff > 180.0 {
-        diff = 360.0 - diff;
+impl ClientType {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "java" => Some(ClientType::Java),
+            "bedrock" => Some(ClientType::Bedrock),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClientType::Java => "java",
+            ClientType::Bedrock => "bedrock",
+        }
     }
-    diff
+}
+
+/// Per-player client types from a batch metadata line (`client_types`). Unknown values are
+/// ignored.
+pub fn client_types(meta: &serde_json::Value) -> HashMap<Uuid, ClientType> {
+    meta.get("client_types")
+        .and_then(|x| x.as_object())
+        .map(|types| {
+            types
+                .iter()
+                .filter_map(|(uuid, ty)| {
+                    Some((
+                        Uuid::parse_str(uuid).ok()?,
+                        ClientType::parse(ty.as_str()?)?,
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Per-player protocol versions from a batch metadata line (`client_protocols`). Values that
+/// aren't positive integers are ignored.
+pub fn client_protocols(meta: &serde_json::Value) -> HashMap<Uuid, i32> {
+    meta.get("client_protocols")
+        .and_then(|x| x.as_object())
+        .map(|protocols| {
+            protocols
+                .iter()
+                .filter_map(|(uuid, protocol)| {
+                    let protocol = i32::try_from(protocol.as_i64()?).ok()?;
+                    if protocol <= 0 {
+                        return None;
+                    }
+                    Some((Uuid::parse_str(uuid).ok()?, protocol))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Normalization applied to each record of a batch before a transform reads it.
+#[derive(Debug, Clone, Copy, Default)]
+struct Prepass {
+    legacy_fields: bool,
+    mc_version: Option<Version>,
+}
+
+impl Prepass {
+    fn from_meta(meta: &serde_json::Value) -> Self {
+        Self {
+            legacy_fields: uses_legacy_fields(meta),
+            mc_version: packet_names::batch_version(meta),
+        }
+    }
+
+    fn apply(&self, record: &mut serde_json::Value) {
+        packet_names::normalize_record(record, self.mc_version);
+        if self.legacy_fields {
+            normalize_legacy_fields(record);
+        }
+    }
+}
+
+/// Per-player world / gamemode context, tracked across a batch.
+///
+/// Records may carry `world`, `gamemode` and `allow_flying` at the top level; `PLAYER_STATE`
+/// records carry them in `fields`. The latest value wins.
+#[derive(Debug, Default, Clone)]
+struct PlayerContext {
+    world: Option<String>,
+    gamemode: Option<String>,
+    allow_flying: Option<bool>,
+}
+
+impl PlayerContext {
+    fn update(&mut self, record: &serde_json::Value) {
+        let state_fields = record
+            .get("pkt")
+            .and_then(|x| x.as_str())
+            .filter(|pkt| pkt.eq_ignore_ascii_case("PLAYER_STATE"))
+            .and_then(|_| record.get("fields"));
+        for src in std::iter::once(record).chain(state_fields) {
+            if let Some(world) = src.get("world").and_then(|x| x.as_str()) {
+                self.world = Some(world.to_string());
+            }
+            if let Some(gamemode) = src.get("gamemode").and_then(|x| x.as_str()) {
+                self.gamemode = Some(gamemode.to_ascii_uppercase());
+            }
+            if let Some(allow) = src.get("allow_flying").and_then(|x| x.as_bool()) {
+                self.allow_flying = Some(allow);
+            }
+        }
+    }
+}
+
+/// Longest a target is extrapolated past its last position: entity trackers send moving
+/// entities' positions every 1-3 ticks, so after that the target has most likely stopped.
+pub const MAX_EXTRAPOLATE_MS: u64 = 150;
+
+/// The last two positions of an entity (clientbound spawn / teleport / move packets).
+#[derive(Debug, Default)]
+struct EntityTrack {
+    samples: [Option<(u64, [f64; 3])>; 2],
+}
+
+impl EntityTrack {
+    fn push(&mut self, ts: u64, pos: [f64; 3]) {
+        self.samples = [self.samples[1], Some((ts, pos))];
+    }
+
+    fn last(&self) -> Option<(u64, [f64; 3])> {
+        self.samples[1]
+    }
+
+    /// Where the entity was at `ts`, and how far off that may be: interpolated between the
+    /// positions around `ts` (off by at most the distance to the farther one), or extrapolated
+    /// up to [`MAX_EXTRAPOLATE_MS`] past the last one (off by the extrapolated distance). Positions
+    /// sent within a tick of each other give no speed; the last one is kept.
+    fn at(&self, ts: u64) -> Option<([f64; 3], f64)> {
+        let (last_ts, last) = self.last()?;
+        let Some((prev_ts, prev)) = self.samples[0].filter(|(prev_ts, _)| *prev_ts < last_ts)
+        else {
+            return Some((last, 0.0));
+        };
+        let lerp = |f: f64| [0, 1, 2].map(|i| prev[i] + (last[i] - prev[i]) * f);
+        let dist = |a: [f64; 3], b: [f64; 3]| {
+            ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+        };
+        let span = (last_ts - prev_ts) as f64;
+        if ts <= prev_ts {
+            Some((prev, 0.0))
+        } else if ts <= last_ts {
+            let pos = lerp((ts - prev_ts) as f64 / span);
+            Some((pos, dist(pos, prev).max(dist(pos, last))))
+        } else if span < 50.0 {
+            // Sent within a tick: no speed to go by, but it may take another such step.
+            Some((last, dist(prev, last)))
+        } else {
+            let ahead = (ts - last_ts).min(MAX_EXTRAPOLATE_MS) as f64;
+            let pos = lerp(1.0 + ahead / span);
+            Some((pos, dist(pos, last)))
+        }
+    }
+}
+
+/// Hurt ticks after which an entity takes full damage again: for the first half of its 20 tick
+/// invulnerability, a hit only does damage beyond the previous one (NCP FightSpeed's window).
+pub const INVULNERABLE_TICKS: f64 = 10.0;
+
+/// When each entity was last shown hurt (clientbound hurt packets), tracked across a batch.
+#[derive(Debug, Default)]
+struct HurtTracker {
+    last_hurt: HashMap<i64, u64>,
+    /// [`INVULNERABLE_TICKS`] at the batch's tick rate.
+    invulnerable_ms: f64,
+}
+
+impl HurtTracker {
+    fn from_meta(meta: &serde_json::Value) -> Self {
+        let tick_rate = meta
+            .get("tick_rate")
+            .and_then(|x| x.as_f64())
+            .filter(|t| t.is_finite())
+            .map_or(20.0, |t| t.clamp(1.0, 20.0));
+        Self {
+            last_hurt: HashMap::new(),
+            invulnerable_ms: INVULNERABLE_TICKS * 1000.0 / tick_rate,
+        }
+    }
+
+    /// Record a hurt packet; returns whether the record was one.
+    fn observe(
+        &mut self,
+        dir: &str,
+        pkt: &str,
+        fields: &serde_json::Map<String, serde_json::Value>,
+        ts: u64,
+    ) -> bool {
+        if dir != "clientbound" {
+            return false;
+        }
+        let Some(entity_id) = packet_names::hurt_entity(pkt, fields) else {
+            return false;
+        };
+        // Every viewer gets the packet: copies within a tick are the same hurt.
+        let last = self.last_hurt.entry(entity_id).or_insert(ts);
+        if ts >= last.saturating_add(50) {
+            *last = ts;
+        }
+        true
+    }
+
+    /// Milliseconds since `entity_id` was last hurt, and whether an attack at `ts` lands inside
+    /// its invulnerability window.
+    fn annotate(&self, entity_id: i64, ts: u64) -> (Option<f64>, Option<bool>) {
+        match self.last_hurt.get(&entity_id) {
+            Some(&hurt) if hurt <= ts => {
+                let ago = (ts - hurt) as f64;
+                (Some(ago), Some(ago < self.invulnerable_ms))
+            }
+            _ => (None, None),
+        }
+    }
+}
+
+/// Whether the server lets the player fly (creative/spectator or `/fly`), so flight checks
+/// should not apply.
+pub fn flight_allowed(gamemode: Option<&str>, allow_flying: Option<bool>) -> bool {
+    allow_flying == Some(true)
+        || gamemode.is_some_and(|g| {
+            g.eq_ignore_ascii_case("CREATIVE") || g.eq_ignore_ascii_case("SPECTATOR")
+        })
+}
+
+/// `v`, or 0 for NaN / infinity (JSON has neither).
+fn finite(v: f64) -> f64 {
+    if v.is_finite() {
+        v
+    } else {
+        0.0
+    }
+}
+
+/// Write one event as an NDJSON line.
+fn write_event<W: std::io::Write, T: serde::Serialize>(
+    out: &mut W,
+    event: &T,
+) -> anyhow::Result<()> {
+    serde_json::to_writer(&mut *out, event)?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Absolute difference between two yaws in degrees, in [0, 180]. Clients send yaw unwrapped (it
+/// keeps growing past ±360 as the player turns), so the difference is taken modulo 360. NaN when
+/// either yaw is.
+pub fn yaw_difference(yaw1: f64, yaw2: f64) -> f64 {
+    let diff = (yaw1 - yaw2).abs() % 360.0;
+    diff.min(360.0 - diff)
+}
+
+/// Unit look vector for a yaw and pitch in degrees (yaw 0 faces +Z and 90 faces -X, pitch 90
+/// looks straight down).
+pub fn view_vector(yaw: f64, pitch: f64) -> [f64; 3] {
+    let (yaw, pitch) = (yaw.to_radians(), pitch.to_radians());
+    [
+        -pitch.cos() * yaw.sin(),
+        -pitch.sin(),
+        pitch.cos() * yaw.cos(),
+    ]
+}
+
+/// Distance from `target` to the line of sight through `eye` along `view` (need not be unit
+/// length).
+pub fn aim_off(eye: [f64; 3], view: [f64; 3], target: [f64; 3]) -> f64 {
+    let r = [0, 1, 2].map(|i| target[i] - eye[i]);
+    let d = view;
+    let d_len = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt().max(1e-9);
+    let cross = [
+        r[1] * d[2] - r[2] * d[1],
+        r[2] * d[0] - r[0] * d[2],
+        r[0] * d[1] - r[1] * d[0],
+    ];
+    (cross[0] * cross[0] + cross[1] * cross[1] + cross[2] * cross[2]).sqrt() / d_len
 }
 
 /// NCP-oriented fight transform.
 ///
 /// Outputs *enriched attack events* with best-effort target position tracking using
-/// clientbound entity spawn/teleport/relative-move packets within the batch.
+/// clientbound entity spawn/teleport/relative-move packets within the batch, and the target's
+/// hurt timing from clientbound hurt packets (`target_hurt_ago_ms`, `target_invulnerable`).
 ///
 /// Why:
 /// - NCP Reach/Direction rely on (player eye → target) geometry.
 /// - The ingest API can cheaply provide `reach_distance` and `aim_off` so modules remain simple.
+/// - NCP measures reach to the target's hitbox, not its feet: `reach_hitbox` uses the target's
+///   box by entity type from its spawn packet (see [`hitboxes`]), `reach_center` its center.
 ///
 /// Output lines (after meta):
 /// ```json
-/// {"ts":..., "uuid":"...", "entity_id":123, "player_x":..., "player_y":..., "player_z":..., "player_yaw":..., "player_pitch":..., "target_x":..., "target_y":..., "target_z":..., "reach_distance":..., "aim_off":...}
+/// {"ts":..., "uuid":"...", "entity_id":123, "player_x":..., "player_y":..., "player_z":..., "player_yaw":..., "player_pitch":..., "target_x":..., "target_y":..., "target_z":..., "target_type":"player", "reach_distance":..., "reach_center":..., "reach_hitbox":..., "aim_off":...}
 /// ```
 fn ncp_fight_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
     use flate2::{read::GzDecoder, write::GzEncoder, Compression};
@@ -385,18 +908,77 @@ fn ncp_fight_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
     // 1.62 is a good default eye height for standing players (NCP uses Bukkit eye height).
     const DEFAULT_EYE_HEIGHT: f64 = 1.62;
 
+    /// Fill in the target's position at the attack and the geometry from the attacker's pose.
+    fn fill_target(event: &mut FightEvent, t: Pos, uncertainty: f64) {
+        event.target_x = Some(finite(t.x));
+        event.target_y = Some(finite(t.y));
+        event.target_z = Some(finite(t.z));
+        // Distances move at most as far as the target may be off.
+        event.reach_uncertainty = Some(finite(uncertainty));
+
+        // Geometry-based values.
+        let eye = Pos {
+            x: event.player_x,
+            y: event.player_y + DEFAULT_EYE_HEIGHT,
+            z: event.player_z,
+        };
+        let r = Pos {
+            x: t.x - eye.x,
+            y: t.y - eye.y,
+            z: t.z - eye.z,
+        };
+        let dist = (r.x * r.x + r.y * r.y + r.z * r.z).sqrt();
+        event.reach_distance = Some(finite(dist));
+
+        let hitbox = hitboxes::for_type(event.target_type.as_deref());
+        let expand = if event
+            .client_protocol
+            .is_some_and(|p| p <= LEGACY_COMBAT_MAX_PROTOCOL)
+        {
+            hitboxes::LEGACY_EXPANSION
+        } else {
+            0.0
+        };
+        let (center, nearest) =
+            hitboxes::reach([eye.x, eye.y, eye.z], [t.x, t.y, t.z], hitbox, expand);
+        event.reach_center = Some(finite(center));
+        event.reach_hitbox = Some(finite(nearest));
+
+        let view = view_vector(event.player_yaw, event.player_pitch);
+        let off = aim_off([eye.x, eye.y, eye.z], view, [t.x, t.y, t.z]);
+        event.aim_off = Some(finite(off));
+    }
+
+    /// Place the target of `event` at the attack from its track.
+    fn resolve(event: &mut FightEvent, track: &EntityTrack) {
+        if let Some(([x, y, z], uncertainty)) = track.at(event.ts) {
+            fill_target(event, Pos { x, y, z }, uncertainty);
+        }
+    }
+
     let decoder = GzDecoder::new(raw_gz_ndjson);
     let mut reader = BufReader::new(decoder);
 
     let mut out = Vec::new();
     let mut encoder = GzEncoder::new(&mut out, Compression::default());
+    let mut parser = RecordParser::new();
 
     let mut buf = String::new();
     let mut line_no = 0usize;
+    let mut prepass = Prepass::default();
+    let mut clients: HashMap<Uuid, ClientType> = HashMap::new();
+    let mut protocols: HashMap<Uuid, i32> = HashMap::new();
 
     // Within-batch trackers.
-    let mut entity_pos: HashMap<i64, Pos> = HashMap::new();
+    let mut entity_tracks: HashMap<i64, EntityTrack> = HashMap::new();
+    let mut entity_types: HashMap<i64, String> = HashMap::new();
     let mut player_pose: HashMap<Uuid, PlayerPose> = HashMap::new();
+    let mut hurt = HurtTracker::default();
+
+    // Attack events, written at the end in input order; an attack waits in `pending` (event
+    // index, target) until its target's next position, to interpolate between the two.
+    let mut events: Vec<FightEvent> = Vec::new();
+    let mut pending: Vec<(usize, i64)> = Vec::new();
 
     while {
         buf.clear();
@@ -411,8 +993,11 @@ fn ncp_fight_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
 
         // First line: pass through, but annotate transform.
         if line_no == 1 {
-            let mut meta: Value =
-                serde_json::from_str(line).unwrap_or(Value::Object(Default::default()));
+            let mut meta = batch_meta::standardized(line);
+            prepass = Prepass::from_meta(&meta);
+            clients = client_types(&meta);
+            protocols = client_protocols(&meta);
+            hurt = HurtTracker::from_meta(&meta);
             if let Some(obj) = meta.as_object_mut() {
                 obj.insert(
                     "transform".to_string(),
@@ -423,10 +1008,10 @@ fn ncp_fight_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
             continue;
         }
 
-        let v: Value = match serde_json::from_str(line) {
-            Ok(v) => v,
-            Err(_) => continue,
+        let Some(mut v) = parser.parse(line) else {
+            continue;
         };
+        prepass.apply(&mut v);
 
         let ts = v.get("ts").and_then(|x| x.as_u64());
         let pkt = v.get("pkt").and_then(|x| x.as_str()).unwrap_or("");
@@ -435,40 +1020,77 @@ fn ncp_fight_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
         let Some(ts) = ts else { continue };
         let Some(fields) = fields else { continue };
 
+        if hurt.observe(dir, pkt, fields, ts) {
+            continue;
+        }
+
         // --- Track entity position from clientbound packets ---
         if dir == "clientbound" {
             // Spawn / teleport are absolute (x,y,z)
-            if pkt.contains("SPAWN") || pkt.contains("ENTITY_TELEPORT") {
+            if packet_names::is_entity_spawn_or_teleport(pkt) {
                 let entity_id = fields.get("entity_id").and_then(|x| x.as_i64());
                 let x = fields.get("x").and_then(|x| x.as_f64());
                 let y = fields.get("y").and_then(|x| x.as_f64());
                 let z = fields.get("z").and_then(|x| x.as_f64());
                 if let (Some(entity_id), Some(x), Some(y), Some(z)) = (entity_id, x, y, z) {
-                    entity_pos.insert(entity_id, Pos { x, y, z });
+                    let track = entity_tracks.entry(entity_id).or_default();
+                    track.push(ts, [x, y, z]);
+                    pending.retain(|&(i, id)| {
+                        let done = id == entity_id && events[i].ts <= ts;
+                        if done {
+                            resolve(&mut events[i], track);
+                        }
+                        !done
+                    });
+                }
+                // Spawns name the type; teleports keep the one known.
+                // Older plugins serialized the registry key as `{"namespace", "key"}`.
+                let entity_type = fields
+                    .get("entity_type")
+                    .and_then(|x| x.as_str().or_else(|| x.get("key")?.as_str()))
+                    .and_then(hitboxes::normalize_type);
+                if let (Some(entity_id), Some(entity_type)) = (entity_id, entity_type) {
+                    entity_types.insert(entity_id, entity_type);
                 }
                 continue;
             }
 
             // Relative move: dx,dy,dz
-            if pkt.contains("ENTITY_RELATIVE_MOVE") {
+            if packet_names::is_entity_relative_move(pkt) {
                 let entity_id = fields.get("entity_id").and_then(|x| x.as_i64());
                 let dx = fields.get("dx").and_then(|x| x.as_f64()).unwrap_or(0.0);
                 let dy = fields.get("dy").and_then(|x| x.as_f64()).unwrap_or(0.0);
                 let dz = fields.get("dz").and_then(|x| x.as_f64()).unwrap_or(0.0);
                 if let Some(entity_id) = entity_id {
-                    if let Some(p) = entity_pos.get_mut(&entity_id) {
-                        p.x += dx;
-                        p.y += dy;
-                        p.z += dz;
+                    if let Some(track) = entity_tracks.get_mut(&entity_id) {
+                        if let Some((_, [x, y, z])) = track.last() {
+                            track.push(ts, [x + dx, y + dy, z + dz]);
+                            pending.retain(|&(i, id)| {
+                                let done = id == entity_id && events[i].ts <= ts;
+                                if done {
+                                    resolve(&mut events[i], track);
+                                }
+                                !done
+                            });
+                        }
                     }
                 }
                 continue;
             }
 
-            if pkt.contains("DESTROY_ENTITIES") {
+            if pkt == packet_names::DESTROY_ENTITIES {
                 if let Some(arr) = fields.get("entity_ids").and_then(|x| x.as_array()) {
                     for id in arr.iter().filter_map(|v| v.as_i64()) {
-                        entity_pos.remove(&id);
+                        if let Some(track) = entity_tracks.remove(&id) {
+                            pending.retain(|&(i, target)| {
+                                if target == id {
+                                    resolve(&mut events[i], &track);
+                                }
+                                target != id
+                            });
+                        }
+                        entity_types.remove(&id);
+                        hurt.last_hurt.remove(&id);
                     }
                 }
                 continue;
@@ -476,9 +1098,7 @@ fn ncp_fight_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
         }
 
         // --- Track player pose from serverbound movement packets ---
-        if dir == "serverbound"
-            && (pkt.contains("POSITION") || pkt.contains("ROTATION") || pkt.contains("FLYING"))
-        {
+        if dir == "serverbound" && packet_names::is_player_movement(pkt) {
             let uuid = v
                 .get("uuid")
                 .and_then(|x| x.as_str())
@@ -525,7 +1145,7 @@ fn ncp_fight_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
         }
 
         // --- Emit enriched attack events ---
-        if dir == "serverbound" && (pkt.contains("INTERACT_ENTITY") || pkt.contains("USE_ENTITY")) {
+        if dir == "serverbound" && pkt == packet_names::INTERACT_ENTITY {
             let action = fields.get("action").and_then(|x| x.as_str()).unwrap_or("");
             if action != "ATTACK" {
                 continue;
@@ -545,59 +1165,200 @@ fn ncp_fight_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
             }
             let pose = pose.unwrap();
 
-            let target = entity_pos.get(&entity_id).copied();
-
-            let mut obj = serde_json::Map::new();
-            obj.insert("ts".to_string(), Value::Number(ts.into()));
-            obj.insert("uuid".to_string(), Value::String(uuid.to_string()));
-            obj.insert("entity_id".to_string(), Value::Number(entity_id.into()));
-            obj.insert("player_x".to_string(), json_f64(pose.x));
-            obj.insert("player_y".to_string(), json_f64(pose.y));
-            obj.insert("player_z".to_string(), json_f64(pose.z));
-            obj.insert("player_yaw".to_string(), json_f64(pose.yaw));
-            obj.insert("player_pitch".to_string(), json_f64(pose.pitch));
-
-            if let Some(t) = target {
-                obj.insert("target_x".to_string(), json_f64(t.x));
-                obj.insert("target_y".to_string(), json_f64(t.y));
-                obj.insert("target_z".to_string(), json_f64(t.z));
-
-                // Geometry-based values.
-                let eye = Pos {
-                    x: pose.x,
-                    y: pose.y + DEFAULT_EYE_HEIGHT,
-                    z: pose.z,
-                };
-                let r = Pos {
-                    x: t.x - eye.x,
-                    y: t.y - eye.y,
-                    z: t.z - eye.z,
-                };
-                let dist = (r.x * r.x + r.y * r.y + r.z * r.z).sqrt();
-                obj.insert("reach_distance".to_string(), json_f64(dist));
-
-                // View direction from yaw/pitch (degrees).
-                // Minecraft: yaw rotates around Y, pitch up/down.
-                let yaw_rad = (pose.yaw as f64).to_radians();
-                let pitch_rad = (pose.pitch as f64).to_radians();
-                let d = Pos {
-                    x: -pitch_rad.cos() * yaw_rad.sin(),
-                    y: -pitch_rad.sin(),
-                    z: pitch_rad.cos() * yaw_rad.cos(),
-                };
-                let d_len = (d.x * d.x + d.y * d.y + d.z * d.z).sqrt().max(1e-9);
-                let cross = Pos {
-                    x: r.y * d.z - r.z * d.y,
-                    y: r.z * d.x - r.x * d.z,
-                    z: r.x * d.y - r.y * d.x,
-                };
-                let off =
-                    ((cross.x * cross.x + cross.y * cross.y + cross.z * cross.z).sqrt()) / d_len;
-                obj.insert("aim_off".to_string(), json_f64(off));
+            let (target_hurt_ago_ms, target_invulnerable) = hurt.annotate(entity_id, ts);
+
+            let mut event = FightEvent {
+                ts,
+                uuid,
+                entity_id,
+                player_x: finite(pose.x),
+                player_y: finite(pose.y),
+                player_z: finite(pose.z),
+                player_yaw: finite(pose.yaw),
+                player_pitch: finite(pose.pitch),
+                target_x: None,
+                target_y: None,
+                target_z: None,
+                target_type: entity_types.get(&entity_id).cloned(),
+                reach_distance: None,
+                reach_center: None,
+                reach_hitbox: None,
+                reach_uncertainty: None,
+                aim_off: None,
+                target_hurt_ago_ms,
+                target_invulnerable,
+                client_type: clients.get(&uuid).copied(),
+                client_protocol: protocols.get(&uuid).copied(),
+            };
+
+            if let Some(track) = entity_tracks.get(&entity_id) {
+                if track.last().is_some_and(|(last_ts, _)| last_ts >= ts) {
+                    resolve(&mut event, track);
+                } else {
+                    pending.push((events.len(), entity_id));
+                }
             }
+            events.push(event);
+        }
+    }
+
+    // No position after these attacks: extrapolate from the last ones.
+    for (i, entity_id) in pending {
+        if let Some(track) = entity_tracks.get(&entity_id) {
+            resolve(&mut events[i], track);
+        }
+    }
+    for event in &events {
+        write_event(&mut encoder, event)?;
+    }
+
+    encoder.finish()?;
+    Ok(out)
+}
+
+/// Longest brand / channel name kept (longer values are truncated).
+const MAX_CHANNEL_LEN: usize = 128;
+/// Most channels kept from a single registration packet.
+const MAX_CHANNELS_PER_PACKET: usize = 64;
+
+/// What a client disclosed over a plugin channel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientChannel {
+    /// `minecraft:brand` (`MC|Brand` before 1.13), e.g. "vanilla", "fabric".
+    Brand(String),
+    /// `minecraft:register` (`REGISTER` before 1.13): channels the client listens on.
+    Register(Vec<String>),
+}
 
-            writeln!(encoder, "{}", Value::Object(obj))?;
+/// Brand and channel-registration packets in a raw batch, as `(ts, player, info)`.
+///
+/// Expects serverbound `PLUGIN_MESSAGE` records (or an alias such as `CUSTOM_PAYLOAD`) with
+/// `fields.channel` and the payload decoded to a string in `fields.data`; registration payloads
+/// are NUL-separated channel names.
+pub fn client_channel_records(
+    raw_gz_ndjson: &[u8],
+) -> anyhow::Result<Vec<(u64, uuid::Uuid, ClientChannel)>> {
+    use flate2::read::GzDecoder;
+    use std::io::{BufRead, BufReader};
+    use uuid::Uuid;
+
+    let decoder = GzDecoder::new(raw_gz_ndjson);
+    let mut reader = BufReader::new(decoder);
+
+    let mut buf = String::new();
+    let mut line_no = 0usize;
+    let mut records = Vec::new();
+    let mut parser = RecordParser::new();
+
+    while {
+        buf.clear();
+        reader.read_line(&mut buf)?
+    } != 0
+    {
+        line_no += 1;
+        let line = buf.trim_end_matches(&['\n', '\r'][..]);
+        // First line is batch metadata.
+        if line.is_empty() || line_no == 1 {
+            continue;
+        }
+
+        let Some(v) = parser.parse(line) else {
+            continue;
+        };
+        let pkt = v.get("pkt").and_then(|x| x.as_str()).unwrap_or("");
+        let dir = v.get("dir").and_then(|x| x.as_str()).unwrap_or("");
+        if packet_names::canonical(pkt, dir, None) != packet_names::PLUGIN_MESSAGE {
+            continue;
         }
+        let uuid = v
+            .get("uuid")
+            .and_then(|x| x.as_str())
+            .and_then(|s| Uuid::parse_str(s).ok());
+        let ts = v.get("ts").and_then(|x| x.as_u64());
+        let (Some(uuid), Some(ts)) = (uuid, ts) else {
+            continue;
+        };
+        let Some(fields) = v.get("fields").and_then(|x| x.as_object()) else {
+            continue;
+        };
+        let channel = fields.get("channel").and_then(|x| x.as_str()).unwrap_or("");
+        let data = fields.get("data").and_then(|x| x.as_str()).unwrap_or("");
+
+        let info = match channel {
+            "minecraft:brand" | "MC|Brand" => match clean_channel_string(data) {
+                Some(brand) => ClientChannel::Brand(brand),
+                None => continue,
+            },
+            "minecraft:register" | "REGISTER" => {
+                let channels: Vec<String> = data
+                    .split('\0')
+                    .filter_map(clean_channel_string)
+                    .take(MAX_CHANNELS_PER_PACKET)
+                    .collect();
+                if channels.is_empty() {
+                    continue;
+                }
+                ClientChannel::Register(channels)
+            }
+            _ => continue,
+        };
+        records.push((ts, uuid, info));
+    }
+
+    Ok(records)
+}
+
+/// Strip control characters (brand payloads carry a length prefix) and cap the length.
+fn clean_channel_string(s: &str) -> Option<String> {
+    let cleaned: String = s
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_CHANNEL_LEN)
+        .collect();
+    let cleaned = cleaned.trim();
+    (!cleaned.is_empty()).then(|| cleaned.to_string())
+}
+
+/// Client fingerprinting transform.
+///
+/// Output lines (after meta):
+/// ```json
+/// {"ts":..., "uuid":"...", "kind":"brand", "brand":"fabric"}
+/// {"ts":..., "uuid":"...", "kind":"register", "channels":["meteor-client:main", ...]}
+/// ```
+fn client_channels_v1(raw_gz_ndjson: &[u8]) -> anyhow::Result<Vec<u8>> {
+    use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+    use serde_json::Value;
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut out = Vec::new();
+    let mut encoder = GzEncoder::new(&mut out, Compression::default());
+
+    // Meta line: pass through, but annotate transform.
+    let mut first = String::new();
+    BufReader::new(GzDecoder::new(raw_gz_ndjson)).read_line(&mut first)?;
+    let mut meta = batch_meta::standardized(first.trim_end());
+    if let Some(obj) = meta.as_object_mut() {
+        obj.insert(
+            "transform".to_string(),
+            Value::String("client_channels_v1".to_string()),
+        );
+    }
+    writeln!(encoder, "{}", serde_json::to_string(&meta)?)?;
+
+    for (ts, uuid, info) in client_channel_records(raw_gz_ndjson)? {
+        let (kind, brand, channels) = match info {
+            ClientChannel::Brand(brand) => ("brand", Some(brand), None),
+            ClientChannel::Register(channels) => ("register", None, Some(channels)),
+        };
+        let event = ClientChannelEvent {
+            ts,
+            uuid,
+            kind: kind.to_string(),
+            brand,
+            channels,
+        };
+        write_event(&mut encoder, &event)?;
     }
 
     encoder.finish()?;
@@ -0,0 +1,376 @@
+//! Sandboxed WASM modules.
+//!
+//! A module can be uploaded as a WASM blob instead of being deployed as an HTTP service. It runs
+//! in-process against the output of its transform, with fuel (CPU) and memory limits, so
+//! untrusted community checks can run without a network hop. Requires the `wasm-modules`
+//! feature; without it, `wasm://` modules fail dispatch with a clear error.
+//!
+//! A `server_modules` row opts in with a `wasm://<wasm_module_id>` base URL (created by the
+//! upload endpoint).
+//!
+//! Guest ABI:
+//!
+//! - export `memory`
+//! - export `alloc(len: i32) -> i32`: returns a pointer to `len` writable bytes
+//! - export `process(ptr: i32, len: i32) -> i32`: receives the batch as plain NDJSON (metadata
+//!   line first, same as HTTP modules after decompression); non-zero means failure
+//! - import `aac.emit_finding(ptr: i32, len: i32)`: one finding as JSON (`FindingIn` shape)
+//! - import `aac.log(ptr: i32, len: i32)`: UTF-8 debug message
+
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::{Arc, Mutex, OnceLock};
+use uuid::Uuid;
+
+use crate::routes::callbacks::FindingIn;
+use crate::{transforms, AppState};
+
+pub const WASM_SCHEME: &str = "wasm://";
+
+/// Uploads larger than the default request body limit are rejected anyway.
+pub const MAX_WASM_BYTES: usize = 2 * 1024 * 1024;
+
+pub const DEFAULT_FUEL: u64 = 500_000_000;
+pub const MAX_FUEL: u64 = 5_000_000_000;
+pub const DEFAULT_MEMORY_BYTES: u64 = 64 * 1024 * 1024;
+pub const MAX_MEMORY_BYTES: u64 = 256 * 1024 * 1024;
+
+/// A single batch can't flood the findings table.
+pub const MAX_FINDINGS_PER_BATCH: usize = 1000;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub fuel: u64,
+    pub memory_bytes: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            fuel: DEFAULT_FUEL,
+            memory_bytes: DEFAULT_MEMORY_BYTES,
+        }
+    }
+}
+
+/// Whether this build can execute WASM modules.
+pub const fn enabled() -> bool {
+    cfg!(feature = "wasm-modules")
+}
+
+/// Return the uploaded module id if the base URL targets the WASM runtime.
+pub fn wasm_module_id(base_url: &str) -> Option<Uuid> {
+    base_url
+        .trim()
+        .strip_prefix(WASM_SCHEME)
+        .and_then(|s| Uuid::parse_str(s.trim_end_matches('/')).ok())
+}
+
+pub fn sha256_hex(wasm: &[u8]) -> String {
+    hex::encode(Sha256::digest(wasm))
+}
+
+/// Compile a blob and check it implements the guest ABI.
+pub fn validate(wasm: &[u8]) -> anyhow::Result<()> {
+    sandbox::compile(wasm).map(|_| ())
+}
+
+/// Compile and run a blob against plain NDJSON, bypassing storage and the compile cache.
+pub fn run_ndjson(wasm: &[u8], limits: Limits, ndjson: &[u8]) -> anyhow::Result<Vec<FindingIn>> {
+    let module = sandbox::compile(wasm)?;
+    sandbox::execute(&module, limits, ndjson)
+}
+
+/// Compiled modules by upload id, with the hash of the blob each was compiled from.
+///
+/// An entry only serves the blob it was compiled from, so a module whose blob changed is
+/// recompiled; the new entry replaces the old one.
+pub struct CompiledCache<T> {
+    entries: HashMap<Uuid, (String, Arc<T>)>,
+}
+
+impl<T> Default for CompiledCache<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<T> CompiledCache<T> {
+    /// The module compiled for `id` from the blob hashed `sha256`.
+    pub fn get(&self, id: Uuid, sha256: &str) -> Option<Arc<T>> {
+        self.entries
+            .get(&id)
+            .filter(|(hash, _)| hash == sha256)
+            .map(|(_, module)| module.clone())
+    }
+
+    /// Cache `module` for `id`, replacing the entry of an earlier blob.
+    pub fn insert(&mut self, id: Uuid, sha256: &str, module: Arc<T>) {
+        self.entries.insert(id, (sha256.to_string(), module));
+    }
+
+    pub fn remove(&mut self, id: Uuid) {
+        self.entries.remove(&id);
+    }
+
+    /// Drop the entries of modules not in `live`.
+    pub fn retain(&mut self, live: &[Uuid]) {
+        self.entries.retain(|id, _| live.contains(id));
+    }
+
+    pub fn ids(&self) -> Vec<Uuid> {
+        self.entries.keys().copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+static COMPILED: OnceLock<Mutex<CompiledCache<sandbox::Compiled>>> = OnceLock::new();
+
+fn compiled_cache() -> &'static Mutex<CompiledCache<sandbox::Compiled>> {
+    COMPILED.get_or_init(Default::default)
+}
+
+/// Drop the compiled module of a replaced or deleted upload.
+pub fn forget(id: Uuid) {
+    compiled_cache().lock().unwrap().remove(id);
+}
+
+async fn compiled(
+    state: &AppState,
+    id: Uuid,
+    sha256: &str,
+) -> anyhow::Result<Arc<sandbox::Compiled>> {
+    if let Some(m) = compiled_cache().lock().unwrap().get(id, sha256) {
+        return Ok(m);
+    }
+
+    let (wasm,): (Vec<u8>,) = sqlx::query_as("select wasm from public.wasm_modules where id = $1")
+        .bind(id)
+        .fetch_one(&state.db)
+        .await?;
+    let module = Arc::new(tokio::task::spawn_blocking(move || sandbox::compile(&wasm)).await??);
+
+    // Compiling is rare: also drop modules deleted meanwhile, including by other replicas.
+    let cached = compiled_cache().lock().unwrap().ids();
+    let live: Vec<Uuid> =
+        sqlx::query_scalar("select id from public.wasm_modules where id = any($1)")
+            .bind(&cached)
+            .fetch_all(&state.db)
+            .await?;
+    let mut cache = compiled_cache().lock().unwrap();
+    cache.retain(&live);
+    cache.insert(id, sha256, module.clone());
+    Ok(module)
+}
+
+/// Run an uploaded module against a raw gzipped NDJSON batch (`clock_skew_ms` is the batch's
+/// skew, used by `+server_time` transforms).
+pub async fn run(
+    state: &AppState,
+    id: Uuid,
+    transform: &str,
+    raw_gz_ndjson: &[u8],
+    clock_skew_ms: Option<i64>,
+) -> anyhow::Result<Vec<FindingIn>> {
+    if !enabled() {
+        anyhow::bail!("this build does not include the WASM runtime (feature wasm-modules)");
+    }
+
+    let row: Option<(String, i64, i64)> = sqlx::query_as(
+        "select sha256, fuel_limit, memory_limit_bytes from public.wasm_modules where id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await?;
+    let Some((sha256, fuel, memory_bytes)) = row else {
+        forget(id);
+        anyhow::bail!("wasm module {} not found", id);
+    };
+    let limits = Limits {
+        fuel: fuel.max(0) as u64,
+        memory_bytes: memory_bytes.max(0) as u64,
+    };
+
+    let events_gz = transforms::apply_transform_with(transform, raw_gz_ndjson, clock_skew_ms)?;
+    let mut ndjson = Vec::new();
+    GzDecoder::new(events_gz.as_slice()).read_to_end(&mut ndjson)?;
+
+    let module = compiled(state, id, &sha256).await?;
+    tokio::task::spawn_blocking(move || sandbox::execute(&module, limits, &ndjson)).await?
+}
+
+/// Whether an uploaded module exists and this build can run it.
+pub async fn is_available(state: &AppState, id: Uuid) -> Result<(), String> {
+    if !enabled() {
+        return Err("WASM runtime not enabled in this build".to_string());
+    }
+    let exists: Result<Option<(Uuid,)>, _> =
+        sqlx::query_as("select id from public.wasm_modules where id = $1")
+            .bind(id)
+            .fetch_optional(&state.db)
+            .await;
+    match exists {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(format!("wasm module {} not found", id)),
+        Err(e) => Err(format!("wasm module lookup failed: {}", e)),
+    }
+}
+
+#[cfg(feature = "wasm-modules")]
+mod sandbox {
+    use super::{FindingIn, Limits, MAX_FINDINGS_PER_BATCH};
+    use std::sync::OnceLock;
+    use wasmtime::{
+        Caller, Config, Engine, Extern, Linker, Module, Store, StoreLimits, StoreLimitsBuilder,
+    };
+
+    pub type Compiled = Module;
+
+    struct HostState {
+        limits: StoreLimits,
+        findings: Vec<FindingIn>,
+    }
+
+    fn engine() -> &'static Engine {
+        static ENGINE: OnceLock<Engine> = OnceLock::new();
+        ENGINE.get_or_init(|| {
+            let mut config = Config::new();
+            config.consume_fuel(true);
+            Engine::new(&config).expect("wasm engine config is valid")
+        })
+    }
+
+    pub fn compile(wasm: &[u8]) -> anyhow::Result<Module> {
+        let module = Module::new(engine(), wasm)?;
+
+        for export in ["memory", "alloc", "process"] {
+            if module.get_export(export).is_none() {
+                anyhow::bail!("module does not export `{}`", export);
+            }
+        }
+        for import in module.imports() {
+            if import.module() != "aac" || !matches!(import.name(), "emit_finding" | "log") {
+                anyhow::bail!(
+                    "module imports unsupported host function {}.{}",
+                    import.module(),
+                    import.name()
+                );
+            }
+        }
+        Ok(module)
+    }
+
+    fn guest_bytes(
+        caller: &mut Caller<'_, HostState>,
+        ptr: i32,
+        len: i32,
+    ) -> anyhow::Result<Vec<u8>> {
+        let Some(Extern::Memory(memory)) = caller.get_export("memory") else {
+            anyhow::bail!("module has no memory export");
+        };
+        let start = ptr as u32 as usize;
+        let end = start.saturating_add(len as u32 as usize);
+        memory
+            .data(&caller)
+            .get(start..end)
+            .map(|b| b.to_vec())
+            .ok_or_else(|| anyhow::anyhow!("out of bounds guest pointer"))
+    }
+
+    pub fn execute(
+        module: &Module,
+        limits: Limits,
+        ndjson: &[u8],
+    ) -> anyhow::Result<Vec<FindingIn>> {
+        let mut store = Store::new(
+            engine(),
+            HostState {
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(limits.memory_bytes as usize)
+                    .instances(1)
+                    .build(),
+                findings: Vec::new(),
+            },
+        );
+        store.limiter(|s| &mut s.limits);
+        store.set_fuel(limits.fuel)?;
+
+        let mut linker = Linker::new(engine());
+        linker.func_wrap(
+            "aac",
+            "emit_finding",
+            |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> anyhow::Result<()> {
+                let bytes = guest_bytes(&mut caller, ptr, len)?;
+                let finding: FindingIn = serde_json::from_slice(&bytes)
+                    .map_err(|e| anyhow::anyhow!("invalid finding JSON: {}", e))?;
+                let findings = &mut caller.data_mut().findings;
+                if findings.len() >= MAX_FINDINGS_PER_BATCH {
+                    anyhow::bail!(
+                        "module emitted more than {} findings",
+                        MAX_FINDINGS_PER_BATCH
+                    );
+                }
+                findings.push(finding);
+                Ok(())
+            },
+        )?;
+        linker.func_wrap(
+            "aac",
+            "log",
+            |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> anyhow::Result<()> {
+                let bytes = guest_bytes(&mut caller, ptr, len.min(4096))?;
+                tracing::debug!(message = %String::from_utf8_lossy(&bytes), "wasm module log");
+                Ok(())
+            },
+        )?;
+
+        let instance = linker.instantiate(&mut store, module)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| anyhow::anyhow!("module has no memory export"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let process = instance.get_typed_func::<(i32, i32), i32>(&mut store, "process")?;
+
+        let len = i32::try_from(ndjson.len())?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory.write(&mut store, ptr as u32 as usize, ndjson)?;
+
+        let code = process.call(&mut store, (ptr, len))?;
+        if code != 0 {
+            anyhow::bail!("module returned error code {}", code);
+        }
+
+        Ok(std::mem::take(&mut store.data_mut().findings))
+    }
+}
+
+#[cfg(not(feature = "wasm-modules"))]
+mod sandbox {
+    use super::{FindingIn, Limits};
+
+    /// Never constructed: compiling always fails without the runtime.
+    pub struct Compiled;
+
+    pub fn compile(_wasm: &[u8]) -> anyhow::Result<Compiled> {
+        anyhow::bail!("this build does not include the WASM runtime (feature wasm-modules)")
+    }
+
+    pub fn execute(
+        _module: &Compiled,
+        _limits: Limits,
+        _ndjson: &[u8],
+    ) -> anyhow::Result<Vec<FindingIn>> {
+        anyhow::bail!("this build does not include the WASM runtime (feature wasm-modules)")
+    }
+}
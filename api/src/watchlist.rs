@@ -0,0 +1,107 @@
+//! Player watchlist.
+//!
+//! Watchlisted players get enhanced capture: the set is pushed to the plugin as a
+//! `watchlist_sync` pending action (full state, replaces any undelivered sync) so it can raise
+//! capture rate/verbosity for them. Batches containing a watchlisted player are kept for
+//! `WATCHLIST_RETENTION_DAYS` (`batch_index.retain_until`); anything that drops batches
+//! (cleanup, sampling) must check `retain_until` / [`any_watchlisted`] first.
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{pending_actions, AppState};
+
+pub const SYNC_ACTION: &str = "watchlist_sync";
+
+/// Push the server's current watchlist to the plugin.
+pub async fn sync_to_plugin(db: &PgPool, server_id: &str) -> Result<(), sqlx::Error> {
+    let players: Vec<(Uuid, Option<DateTime<Utc>>)> = sqlx::query_as(
+        r#"
+        select player_uuid, expires_at
+        from public.server_watchlist
+        where server_id = $1 and (expires_at is null or expires_at > now())
+        order by created_at asc
+        "#,
+    )
+    .bind(server_id)
+    .fetch_all(db)
+    .await?;
+
+    pending_actions::enqueue(db, server_id, SYNC_ACTION, &sync_payload(&players), true).await
+}
+
+/// `watchlist_sync` payload: the full set plus the capture settings to apply to it.
+pub fn sync_payload(players: &[(Uuid, Option<DateTime<Utc>>)]) -> Value {
+    json!({
+        "capture": { "sample_rate": 1.0, "verbose": true },
+        "players": players
+            .iter()
+            .map(|(uuid, expires_at)| json!({ "uuid": uuid, "expires_at": expires_at }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Whether any of the players is on the server's (unexpired) watchlist.
+pub async fn any_watchlisted(
+    db: &PgPool,
+    server_id: &str,
+    players: &[Uuid],
+) -> Result<bool, sqlx::Error> {
+    if players.is_empty() {
+        return Ok(false);
+    }
+    sqlx::query_scalar(
+        r#"
+        select exists (
+            select 1 from public.server_watchlist
+            where server_id = $1
+              and player_uuid = any($2)
+              and (expires_at is null or expires_at > now())
+        )
+        "#,
+    )
+    .bind(server_id)
+    .bind(players)
+    .fetch_one(db)
+    .await
+}
+
+/// Extend retention of a batch if it contains a watchlisted player (best-effort).
+pub async fn retain_if_watchlisted(
+    state: &AppState,
+    server_id: &str,
+    batch_id: Uuid,
+    s3_key: &str,
+    players: &[Uuid],
+) {
+    match any_watchlisted(&state.db, server_id, players).await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            tracing::debug!("watchlist lookup failed (non-critical): {:?}", e);
+            return;
+        }
+    }
+
+    let res = sqlx::query(
+        r#"
+        update public.batch_index
+        set retain_until = now() + make_interval(days => $2)
+        where id = $1
+        "#,
+    )
+    .bind(batch_id)
+    .bind(state.watchlist_retention_days as i32)
+    .execute(&state.db)
+    .await;
+    if let Err(e) = res {
+        tracing::warn!(batch_id = %batch_id, "watchlist retention update failed: {:?}", e);
+        return;
+    }
+    if let Err(e) = state.object_store.mark_retained(s3_key).await {
+        tracing::warn!(s3_key = %s3_key, "watchlist object tagging failed: {:?}", e);
+    }
+    tracing::debug!(batch_id = %batch_id, "batch retained (watchlisted player)");
+}
@@ -1,18 +1,59 @@
 //! Webhook notifications for findings
 //!
-//! Sends Discord/Slack/HTTP webhooks when findings match configured severity levels.
+//! Sends Discord/Slack/HTTP webhooks when findings match configured severity levels, and when a
+//! server's plugin goes offline or comes back or one of its modules goes down (whatever the
+//! severity levels).
+//!
+//! Servers may also set a minimum confidence: findings a detector reports with a lower
+//! `confidence` are either notified one severity level lower (`downgrade`) or not at all
+//! (`drop`). Findings without a confidence count as certain. Stored findings are unaffected.
+
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use serde_json::Value;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::shared_state::SharedState;
+
 /// Server webhook settings from the database
 #[derive(Debug)]
 pub struct WebhookSettings {
     pub webhook_url: Option<String>,
     pub webhook_enabled: bool,
     pub webhook_severity_levels: Vec<String>,
+    /// Findings below this confidence get `low_confidence_action` (server setting).
+    pub min_confidence: Option<f64>,
+    pub low_confidence_action: LowConfidenceAction,
+}
+
+/// What happens to a notification below the server's minimum confidence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LowConfidenceAction {
+    #[default]
+    Downgrade,
+    Drop,
+}
+
+impl LowConfidenceAction {
+    pub const ALL: [&'static str; 2] = ["downgrade", "drop"];
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "downgrade" => Some(Self::Downgrade),
+            "drop" => Some(Self::Drop),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Downgrade => "downgrade",
+            Self::Drop => "drop",
+        }
+    }
 }
 
 /// A finding to potentially notify about
@@ -26,6 +67,30 @@ pub struct FindingNotification {
     pub title: String,
     pub description: Option<String>,
     pub occurrences: i32,
+    pub confidence: Option<f64>,
+}
+
+/// A server whose plugin went offline or came back (see `server_presence`)
+#[derive(Debug, Clone)]
+pub struct PresenceNotification {
+    pub server_id: String,
+    pub online: bool,
+    pub last_seen_at: DateTime<Utc>,
+    /// Start of the outage (the last contact before it).
+    pub offline_since: DateTime<Utc>,
+}
+
+/// A module that stopped taking batches (see `module_outage`)
+#[derive(Debug, Clone)]
+pub struct ModuleDownNotification {
+    pub server_id: String,
+    pub module_id: Uuid,
+    pub module_name: String,
+    pub consecutive_failures: i32,
+    pub last_error: String,
+    /// Enabled modules of the server that are down, this one included.
+    pub modules_down: i64,
+    pub modules_enabled: i64,
 }
 
 /// Discord webhook embed structure
@@ -66,6 +131,32 @@ struct GenericWebhookPayload {
     timestamp: String,
 }
 
+/// Generic webhook payload for `server_offline` / `server_online`
+#[derive(Debug, Serialize)]
+struct GenericPresencePayload {
+    r#type: String,
+    source: String,
+    server_id: String,
+    last_seen_at: String,
+    offline_since: String,
+    timestamp: String,
+}
+
+/// Generic webhook payload for `module_down`
+#[derive(Debug, Serialize)]
+struct GenericModuleDownPayload {
+    r#type: String,
+    source: String,
+    server_id: String,
+    module_id: String,
+    module_name: String,
+    consecutive_failures: i32,
+    last_error: String,
+    modules_down: i64,
+    modules_enabled: i64,
+    timestamp: String,
+}
+
 #[derive(Debug, Serialize)]
 struct GenericFinding {
     player_uuid: Option<String>,
@@ -75,6 +166,8 @@ struct GenericFinding {
     title: String,
     description: Option<String>,
     occurrences: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confidence: Option<f64>,
 }
 
 fn severity_color(severity: &str) -> u32 {
@@ -97,18 +190,32 @@ fn severity_emoji(severity: &str) -> &'static str {
     }
 }
 
-fn is_discord_webhook(url: &str) -> bool {
+pub(crate) fn is_discord_webhook(url: &str) -> bool {
     url.starts_with("https://discord.com/api/webhooks/")
         || url.starts_with("https://discordapp.com/api/webhooks/")
 }
 
-/// Fetch webhook settings for a server
+/// Fetch webhook settings for a server.
+///
+/// Servers without an enabled webhook of their own use their organization's webhook.
 pub async fn get_webhook_settings(db: &PgPool, server_id: &str) -> Option<WebhookSettings> {
-    let row: Option<(Option<String>, bool, Vec<String>)> = sqlx::query_as(
+    let row: Option<(
+        Option<String>,
+        bool,
+        Vec<String>,
+        Option<String>,
+        Option<bool>,
+        Option<Vec<String>>,
+        Option<f64>,
+        String,
+    )> = sqlx::query_as(
         r#"
-        SELECT webhook_url, webhook_enabled, webhook_severity_levels
-        FROM public.servers
-        WHERE id = $1
+        SELECT s.webhook_url, s.webhook_enabled, s.webhook_severity_levels,
+               o.webhook_url, o.webhook_enabled, o.webhook_severity_levels,
+               s.min_confidence, s.low_confidence_action
+        FROM public.servers s
+        LEFT JOIN public.organizations o ON o.id = s.organization_id
+        WHERE s.id = $1
         "#,
     )
     .bind(server_id)
@@ -116,11 +223,56 @@ pub async fn get_webhook_settings(db: &PgPool, server_id: &str) -> Option<Webhoo
     .await
     .ok()?;
 
-    row.map(|(url, enabled, levels)| WebhookSettings {
-        webhook_url: url,
-        webhook_enabled: enabled,
-        webhook_severity_levels: levels,
-    })
+    row.map(
+        |(url, enabled, levels, org_url, org_enabled, org_levels, min_confidence, action)| {
+            let low_confidence_action = LowConfidenceAction::parse(&action).unwrap_or_default();
+            match (enabled && url.is_some(), org_enabled) {
+                (false, Some(true)) => WebhookSettings {
+                    webhook_url: org_url,
+                    webhook_enabled: true,
+                    webhook_severity_levels: org_levels.unwrap_or_default(),
+                    min_confidence,
+                    low_confidence_action,
+                },
+                _ => WebhookSettings {
+                    webhook_url: url,
+                    webhook_enabled: enabled,
+                    webhook_severity_levels: levels,
+                    min_confidence,
+                    low_confidence_action,
+                },
+            }
+        },
+    )
+}
+
+/// The next severity level down (`info` stays `info`).
+pub fn downgrade(severity: &str) -> &'static str {
+    match severity {
+        "critical" => "high",
+        "high" => "medium",
+        "medium" => "low",
+        _ => "info",
+    }
+}
+
+/// The severity a finding is notified with after the confidence filter, or `None` if dropped.
+pub fn confidence_adjusted_severity(
+    settings: &WebhookSettings,
+    severity: &str,
+    confidence: Option<f64>,
+) -> Option<String> {
+    let below = matches!(
+        (settings.min_confidence, confidence),
+        (Some(min), Some(c)) if c < min
+    );
+    if !below {
+        return Some(severity.to_string());
+    }
+    match settings.low_confidence_action {
+        LowConfidenceAction::Downgrade => Some(downgrade(severity).to_string()),
+        LowConfidenceAction::Drop => None,
+    }
 }
 
 /// Check if a finding should trigger a webhook notification
@@ -173,7 +325,14 @@ pub async fn send_finding_notification(
                     value: finding.occurrences.to_string(),
                     inline: true,
                 },
-            ],
+            ]
+            .into_iter()
+            .chain(finding.confidence.map(|c| DiscordField {
+                name: "Confidence".to_string(),
+                value: format!("{:.0}%", c * 100.0),
+                inline: true,
+            }))
+            .collect(),
             footer: DiscordFooter {
                 text: format!(
                     "AsyncAnticheat • {}",
@@ -200,15 +359,168 @@ pub async fn send_finding_notification(
                 title: finding.title.clone(),
                 description: finding.description.clone(),
                 occurrences: finding.occurrences,
+                confidence: finding.confidence,
+            },
+            timestamp,
+        })
+        .unwrap_or_default()
+    };
+
+    post_webhook(http_client, webhook_url, &finding.server_id, &payload).await;
+}
+
+/// Send webhook notification for a server going offline or coming back (fire-and-forget, logs
+/// errors)
+pub async fn send_presence_notification(
+    http_client: &reqwest::Client,
+    webhook_url: &str,
+    notification: &PresenceNotification,
+    server_name: Option<&str>,
+) {
+    let timestamp = chrono::Utc::now().to_rfc3339();
+    let display_name = server_name.unwrap_or(&notification.server_id);
+
+    let payload: Value = if is_discord_webhook(webhook_url) {
+        let (title, description, color) = if notification.online {
+            (
+                "🟢 Plugin back online".to_string(),
+                format!(
+                    "**{}** is sending data again after {}.",
+                    display_name,
+                    format_outage(notification.last_seen_at - notification.offline_since)
+                ),
+                0x16A34A, // Green
+            )
+        } else {
+            (
+                "🔴 Plugin went offline".to_string(),
+                format!("No data from **{}** since its last contact.", display_name),
+                0xDC2626, // Red
+            )
+        };
+        let embed = DiscordEmbed {
+            title,
+            description,
+            color,
+            fields: vec![DiscordField {
+                name: if notification.online {
+                    "Offline since".to_string()
+                } else {
+                    "Last seen".to_string()
+                },
+                value: format!("<t:{}:f>", notification.offline_since.timestamp()),
+                inline: true,
+            }],
+            footer: DiscordFooter {
+                text: format!("AsyncAnticheat • {}", display_name),
+            },
+            timestamp,
+        };
+        serde_json::to_value(DiscordWebhookPayload {
+            embeds: vec![embed],
+        })
+        .unwrap_or_default()
+    } else {
+        serde_json::to_value(GenericPresencePayload {
+            r#type: if notification.online {
+                "server_online"
+            } else {
+                "server_offline"
+            }
+            .to_string(),
+            source: "asyncanticheat".to_string(),
+            server_id: notification.server_id.clone(),
+            last_seen_at: notification.last_seen_at.to_rfc3339(),
+            offline_since: notification.offline_since.to_rfc3339(),
+            timestamp,
+        })
+        .unwrap_or_default()
+    };
+
+    post_webhook(http_client, webhook_url, &notification.server_id, &payload).await;
+}
+
+/// Send webhook notification for a module that went down (fire-and-forget, logs errors)
+pub async fn send_module_down_notification(
+    http_client: &reqwest::Client,
+    webhook_url: &str,
+    notification: &ModuleDownNotification,
+    server_name: Option<&str>,
+) {
+    let timestamp = chrono::Utc::now().to_rfc3339();
+    let display_name = server_name.unwrap_or(&notification.server_id);
+
+    let payload: Value = if is_discord_webhook(webhook_url) {
+        let embed = DiscordEmbed {
+            title: format!("🔌 Module {} is offline", notification.module_name),
+            description: format!(
+                "**{}** stopped receiving batches after {} failures in a row. Its checks are not running until it recovers.",
+                notification.module_name, notification.consecutive_failures
+            ),
+            color: 0xF97316, // Orange
+            fields: vec![
+                DiscordField {
+                    name: "Modules offline".to_string(),
+                    value: format!(
+                        "{} of {}",
+                        notification.modules_down, notification.modules_enabled
+                    ),
+                    inline: true,
+                },
+                DiscordField {
+                    name: "Last error".to_string(),
+                    // Discord caps field values at 1024 characters.
+                    value: notification.last_error.chars().take(1000).collect(),
+                    inline: false,
+                },
+            ],
+            footer: DiscordFooter {
+                text: format!("AsyncAnticheat • {}", display_name),
             },
             timestamp,
+        };
+        serde_json::to_value(DiscordWebhookPayload {
+            embeds: vec![embed],
+        })
+        .unwrap_or_default()
+    } else {
+        serde_json::to_value(GenericModuleDownPayload {
+            r#type: "module_down".to_string(),
+            source: "asyncanticheat".to_string(),
+            server_id: notification.server_id.clone(),
+            module_id: notification.module_id.to_string(),
+            module_name: notification.module_name.clone(),
+            consecutive_failures: notification.consecutive_failures,
+            last_error: notification.last_error.clone(),
+            modules_down: notification.modules_down,
+            modules_enabled: notification.modules_enabled,
+            timestamp,
         })
         .unwrap_or_default()
     };
 
+    post_webhook(http_client, webhook_url, &notification.server_id, &payload).await;
+}
+
+/// "3h 12m", "5m", "40s".
+fn format_outage(outage: chrono::Duration) -> String {
+    let secs = outage.num_seconds().max(0);
+    match (secs / 3600, secs % 3600 / 60) {
+        (0, 0) => format!("{}s", secs),
+        (0, m) => format!("{}m", m),
+        (h, m) => format!("{}h {}m", h, m),
+    }
+}
+
+async fn post_webhook(
+    http_client: &reqwest::Client,
+    webhook_url: &str,
+    server_id: &str,
+    payload: &Value,
+) {
     match http_client
         .post(webhook_url)
-        .json(&payload)
+        .json(payload)
         .timeout(std::time::Duration::from_secs(5))
         .send()
         .await
@@ -216,7 +528,7 @@ pub async fn send_finding_notification(
         Ok(response) => {
             if !response.status().is_success() {
                 tracing::warn!(
-                    server_id = %finding.server_id,
+                    server_id = %server_id,
                     status = %response.status(),
                     "webhook request failed"
                 );
@@ -224,7 +536,7 @@ pub async fn send_finding_notification(
         }
         Err(e) => {
             tracing::warn!(
-                server_id = %finding.server_id,
+                server_id = %server_id,
                 error = %e,
                 "webhook request error"
             );
@@ -232,9 +544,14 @@ pub async fn send_finding_notification(
     }
 }
 
-/// Batch send webhook notifications (spawns background tasks)
+/// Batch send webhook notifications (spawns background tasks).
+///
+/// A (detector, severity) notification already sent for the server within `dedup` is dropped,
+/// across replicas when `shared` is Redis-backed. A zero `dedup` sends every batch.
 pub fn spawn_webhook_notifications(
     http_client: reqwest::Client,
+    shared: SharedState,
+    dedup: Duration,
     webhook_url: String,
     findings: Vec<FindingNotification>,
     server_name: Option<String>,
@@ -256,10 +573,25 @@ pub fn spawn_webhook_notifications(
 
     for (_, finding) in grouped {
         let client = http_client.clone();
+        let shared = shared.clone();
         let url = webhook_url.clone();
         let name = server_name.clone();
 
         tokio::spawn(async move {
+            if !dedup.is_zero() {
+                let key = format!(
+                    "webhook_dedup:{}:{}:{}",
+                    finding.server_id, finding.detector_name, finding.severity
+                );
+                if !shared.claim(&key, dedup).await {
+                    tracing::debug!(
+                        server_id = %finding.server_id,
+                        detector = %finding.detector_name,
+                        "webhook notification deduplicated"
+                    );
+                    return;
+                }
+            }
             send_finding_notification(&client, &url, &finding, name.as_deref()).await;
         });
     }
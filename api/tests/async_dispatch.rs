@@ -0,0 +1,36 @@
+use async_anticheat_api::async_dispatch::{processing_id, MAX_PROCESSING_ID_LEN};
+
+#[test]
+fn processing_id_from_header() {
+    assert_eq!(
+        processing_id(Some(" job-42 "), b"").as_deref(),
+        Some("job-42")
+    );
+}
+
+#[test]
+fn header_wins_over_body() {
+    let body = br#"{"processing_id":"from-body"}"#;
+    assert_eq!(
+        processing_id(Some("from-header"), body).as_deref(),
+        Some("from-header")
+    );
+}
+
+#[test]
+fn processing_id_from_json_body() {
+    let body = br#"{"ok":true,"processing_id":"abc"}"#;
+    assert_eq!(processing_id(None, body).as_deref(), Some("abc"));
+    assert_eq!(processing_id(Some("  "), body).as_deref(), Some("abc"));
+}
+
+#[test]
+fn missing_or_invalid_id_is_none() {
+    assert_eq!(processing_id(None, b""), None);
+    assert_eq!(processing_id(None, b"accepted"), None);
+    assert_eq!(processing_id(None, br#"{"processing_id":42}"#), None);
+    assert_eq!(processing_id(None, br#"{"processing_id":""}"#), None);
+
+    let long = "x".repeat(MAX_PROCESSING_ID_LEN + 1);
+    assert_eq!(processing_id(Some(&long), b""), None);
+}
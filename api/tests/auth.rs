@@ -0,0 +1,78 @@
+use async_anticheat_api::auth::{
+    constant_time_eq, parse_bearer_token, server_id_from_headers, sha256_hex, validate_token_hash,
+    ServerAuth,
+};
+use axum::http::{HeaderMap, HeaderValue};
+use chrono::Utc;
+use uuid::Uuid;
+
+fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+    let mut h = HeaderMap::new();
+    for (k, v) in pairs {
+        h.insert(*k, HeaderValue::from_str(v).unwrap());
+    }
+    h
+}
+
+#[test]
+fn bearer_token_parsing() {
+    let parse = |v: &str| parse_bearer_token(&headers(&[("authorization", v)]));
+    assert_eq!(parse("Bearer abc"), Some("abc".to_string()));
+    assert_eq!(parse("bearer  abc "), Some("abc".to_string()));
+    assert_eq!(parse("BEARER abc"), Some("abc".to_string()));
+    assert_eq!(parse("Bearer "), None);
+    assert_eq!(parse("Basic abc"), None);
+    assert_eq!(parse_bearer_token(&HeaderMap::new()), None);
+}
+
+#[test]
+fn constant_time_eq_matches_only_identical_strings() {
+    assert!(constant_time_eq("secret-token", "secret-token"));
+    assert!(!constant_time_eq("secret-token", "secret-tokeN"));
+    assert!(!constant_time_eq("secret", "secret-token"));
+    assert!(!constant_time_eq("secret-token", "secret"));
+    assert!(!constant_time_eq("", "secret"));
+    assert!(constant_time_eq("", ""));
+}
+
+#[test]
+fn token_hashes_validate_against_stored_hash() {
+    let stored = sha256_hex("plugin-token");
+    assert_eq!(stored.len(), 64);
+    assert!(validate_token_hash(&sha256_hex("plugin-token"), &stored));
+    assert!(!validate_token_hash(&sha256_hex("other-token"), &stored));
+    // A raw token must never validate against its own hash.
+    assert!(!validate_token_hash("plugin-token", &stored));
+}
+
+#[test]
+fn server_auth_requires_stored_hash_and_registration() {
+    let hash = sha256_hex("plugin-token");
+    let mut server = ServerAuth {
+        auth_token_hash: None,
+        owner_id: Some(Uuid::new_v4()),
+        registered_at: None,
+        deleted_at: None,
+    };
+    assert!(!server.token_matches(&hash));
+    assert!(!server.is_registered());
+
+    server.auth_token_hash = Some(hash.clone());
+    server.registered_at = Some(Utc::now());
+    assert!(server.token_matches(&hash));
+    assert!(!server.token_matches(&sha256_hex("wrong")));
+    assert!(server.is_registered());
+
+    server.owner_id = None;
+    assert!(!server.is_registered());
+}
+
+#[test]
+fn server_id_header_is_required() {
+    assert_eq!(
+        server_id_from_headers(&headers(&[("x-server-id", " srv-1 ")])).unwrap(),
+        "srv-1"
+    );
+    assert!(server_id_from_headers(&headers(&[("x-server-id", "  ")])).is_err());
+    assert!(server_id_from_headers(&HeaderMap::new()).is_err());
+}
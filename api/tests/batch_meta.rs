@@ -0,0 +1,70 @@
+use std::io::Write;
+
+use async_anticheat_api::batch_meta::{first_line, standardized, validate, BatchMeta};
+use flate2::{write::GzEncoder, Compression};
+use serde_json::json;
+
+fn gz(s: &str) -> Vec<u8> {
+    let mut e = GzEncoder::new(Vec::new(), Compression::default());
+    e.write_all(s.as_bytes()).unwrap();
+    e.finish().unwrap()
+}
+
+#[test]
+fn versioned_metadata_is_typed() {
+    let meta = validate(
+        r#"{"meta_version":1,"plugin_version":" 1.4.0 ","protocol_version":767,"mc_version":"1.21","world":"world","tick_rate":19.8,"player_count":42,"client_types":{}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        meta,
+        BatchMeta {
+            meta_version: 1,
+            plugin_version: Some("1.4.0".to_string()),
+            protocol_version: Some(767),
+            mc_version: Some("1.21".to_string()),
+            world: Some("world".to_string()),
+            tick_rate: Some(19.8),
+            player_count: Some(42),
+        }
+    );
+}
+
+#[test]
+fn versioned_metadata_with_bad_fields_is_rejected() {
+    let err = validate(r#"{"meta_version":1,"tick_rate":0,"player_count":"many"}"#).unwrap_err();
+    assert!(err.contains("tick_rate"));
+    assert!(err.contains("player_count"));
+    assert!(validate(r#"{"meta_version":1,"world":""}"#).is_err());
+    assert!(validate(r#"{"meta_version":1,"protocol_version":-5}"#).is_err());
+}
+
+#[test]
+fn unversioned_metadata_is_lenient() {
+    assert_eq!(validate("not json").unwrap(), BatchMeta::default());
+    assert_eq!(validate("[1,2]").unwrap(), BatchMeta::default());
+    let meta = validate(r#"{"plugin_version":"0.3.2","tick_rate":-1}"#).unwrap();
+    assert_eq!(meta.meta_version, 0);
+    assert_eq!(meta.plugin_version.as_deref(), Some("0.3.2"));
+    assert_eq!(meta.tick_rate, None);
+}
+
+#[test]
+fn standardized_line_keeps_extras_and_drops_invalid_fields() {
+    let line = standardized(
+        r#"{"server_id":"s","plugin_version":"1.0.0","tick_rate":"fast","mc_version":"1.20.4"}"#,
+    );
+    assert_eq!(
+        line,
+        json!({"server_id":"s","plugin_version":"1.0.0","mc_version":"1.20.4"})
+    );
+    assert_eq!(standardized("garbage"), json!({}));
+}
+
+#[test]
+fn first_line_reads_the_metadata_line() {
+    let raw = gz("{\"meta_version\":1}\r\n{\"pkt\":\"x\"}\n");
+    assert_eq!(first_line(&raw).as_deref(), Some(r#"{"meta_version":1}"#));
+    assert_eq!(first_line(&gz("")), None);
+    assert_eq!(first_line(b"not gzip"), None);
+}
@@ -0,0 +1,36 @@
+use async_anticheat_api::batch_sequence::{classify, from_headers, SeqStatus};
+use axum::http::{HeaderMap, HeaderValue};
+
+#[test]
+fn classifies_batches_against_the_session_so_far() {
+    assert_eq!(classify(1, None, false), SeqStatus::InOrder);
+    assert_eq!(classify(4, Some(3), false), SeqStatus::InOrder);
+    assert_eq!(classify(7, Some(3), false), SeqStatus::Gap { missing: 3 });
+    // The session's first batches never arrived.
+    assert_eq!(classify(3, None, false), SeqStatus::Gap { missing: 2 });
+    assert_eq!(classify(2, Some(5), false), SeqStatus::Reordered);
+    assert_eq!(classify(5, Some(5), true), SeqStatus::Duplicate);
+    assert_eq!(classify(2, Some(5), true), SeqStatus::Duplicate);
+}
+
+#[test]
+fn gap_reports_missing_count() {
+    assert_eq!(SeqStatus::Gap { missing: 2 }.missing(), Some(2));
+    assert_eq!(SeqStatus::Reordered.missing(), None);
+    assert_eq!(SeqStatus::Gap { missing: 2 }.as_str(), "gap");
+    assert_eq!(SeqStatus::InOrder.as_str(), "ok");
+}
+
+#[test]
+fn parses_batch_seq_header() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(from_headers(&headers), Ok(None));
+
+    headers.insert("X-Batch-Seq", HeaderValue::from_static(" 42 "));
+    assert_eq!(from_headers(&headers), Ok(Some(42)));
+
+    for bad in ["0", "-1", "abc"] {
+        headers.insert("X-Batch-Seq", HeaderValue::from_static(bad));
+        assert!(from_headers(&headers).is_err(), "{} accepted", bad);
+    }
+}
@@ -0,0 +1,53 @@
+use async_anticheat_api::routes::ingest::body_rejection;
+use axum::{
+    body::Bytes,
+    extract::{rejection::BytesRejection, DefaultBodyLimit},
+    http::HeaderMap,
+    routing::post,
+    Router,
+};
+
+const MAX: usize = 1024;
+
+fn serve(app: Router) -> String {
+    let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    format!("http://{}", addr)
+}
+
+async fn handler(
+    headers: HeaderMap,
+    body: Result<Bytes, BytesRejection>,
+) -> Result<String, async_anticheat_api::error::ApiError> {
+    let body = body.map_err(|r| body_rejection(r, &headers, MAX))?;
+    Ok(body.len().to_string())
+}
+
+#[tokio::test]
+async fn oversized_bodies_get_413_with_the_limit() {
+    let base =
+        serve(Router::new().route("/ingest", post(handler).layer(DefaultBodyLimit::max(MAX))));
+    let http = reqwest::Client::new();
+
+    let resp = http
+        .post(format!("{}/ingest", base))
+        .body(vec![0u8; MAX])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status().as_u16(), 200);
+    assert_eq!(resp.text().await.unwrap(), MAX.to_string());
+
+    let resp = http
+        .post(format!("{}/ingest", base))
+        .body(vec![0u8; MAX + 1])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status().as_u16(), 413);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["code"], "payload_too_large");
+    assert_eq!(body["details"]["max_bytes"], MAX);
+    assert_eq!(body["details"]["size_bytes"], MAX + 1);
+}
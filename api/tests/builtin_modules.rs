@@ -0,0 +1,49 @@
+use async_anticheat_api::builtin_modules::{check_by_name, BUILTIN_MODULES};
+use async_anticheat_api::checks::TUNABLE_CHECKS;
+use std::collections::HashSet;
+
+#[test]
+fn every_check_is_documented_once() {
+    let mut seen = HashSet::new();
+    for module in BUILTIN_MODULES {
+        for check in module.checks {
+            assert!(seen.insert(check.name), "{} listed twice", check.name);
+            assert!(
+                !check.description.is_empty(),
+                "{} has no description",
+                check.name
+            );
+        }
+    }
+}
+
+#[test]
+fn documented_parameters_match_the_tunable_thresholds() {
+    for (name, default) in TUNABLE_CHECKS {
+        let (_, check) = check_by_name(name).expect(name);
+        let threshold = check
+            .parameters
+            .iter()
+            .find(|p| p.name == "threshold")
+            .unwrap_or_else(|| panic!("{} documents no threshold", name));
+        assert_eq!(threshold.default, default, "{}", name);
+    }
+    let documented = BUILTIN_MODULES
+        .iter()
+        .flat_map(|m| m.checks)
+        .filter(|c| !c.parameters.is_empty())
+        .count();
+    assert_eq!(documented, TUNABLE_CHECKS.len());
+}
+
+#[test]
+fn checks_are_found_with_their_module() {
+    let (module, check) = check_by_name(" Combat_Core_Reach_Critical ").unwrap();
+    assert_eq!(module.name, "Combat Core");
+    assert_eq!(check.name, "combat_core_reach_critical");
+    assert!(check_by_name("combat_core").is_none());
+    assert_eq!(
+        BUILTIN_MODULES[0].check_names()[0],
+        "movement_core_flight_ascend"
+    );
+}
@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use async_anticheat_api::capture_config::CaptureConfig;
+
+const MAX_BODY: usize = 10 * 1024 * 1024;
+
+#[test]
+fn validate_normalizes_packet_names_and_defaults_batch_size() {
+    let config = CaptureConfig {
+        enabled_packets: vec![" position".into(), "ATTACK".into(), "Position".into()],
+        sample_rates: BTreeMap::from([("flying".to_string(), 0.25)]),
+        ..Default::default()
+    }
+    .validate(MAX_BODY)
+    .unwrap();
+
+    assert_eq!(config.enabled_packets, vec!["ATTACK", "POSITION"]);
+    assert_eq!(config.sample_rates.get("FLYING"), Some(&0.25));
+    assert_eq!(config.max_batch_bytes, MAX_BODY as u64);
+}
+
+#[test]
+fn validate_rejects_out_of_range_values() {
+    let invalid = [
+        CaptureConfig {
+            sample_rate: 1.5,
+            ..Default::default()
+        },
+        CaptureConfig {
+            sample_rates: BTreeMap::from([("POSITION".to_string(), -0.1)]),
+            ..Default::default()
+        },
+        CaptureConfig {
+            flush_interval_ms: 10,
+            ..Default::default()
+        },
+        CaptureConfig {
+            max_batch_bytes: MAX_BODY as u64 + 1,
+            ..Default::default()
+        },
+        CaptureConfig {
+            disabled_packets: vec!["CHAT MESSAGE".into()],
+            ..Default::default()
+        },
+    ];
+    for config in invalid {
+        assert!(config.clone().validate(MAX_BODY).is_err(), "{:?}", config);
+    }
+}
+
+#[test]
+fn partial_json_falls_back_to_defaults() {
+    let config: CaptureConfig = serde_json::from_str(r#"{"sample_rate": 0.5}"#).unwrap();
+    assert_eq!(config.sample_rate, 0.5);
+    assert_eq!(config.flush_interval_ms, 1_000);
+    assert!(config.enabled_packets.is_empty());
+}
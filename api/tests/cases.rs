@@ -0,0 +1,33 @@
+use async_anticheat_api::cases::{validate_choice, validate_title, ItemKind, ACTIONS, OUTCOMES};
+
+#[test]
+fn outcomes_and_actions_are_normalized() {
+    assert_eq!(
+        validate_choice("outcome", " Confirmed_Cheater ", &OUTCOMES).unwrap(),
+        "confirmed_cheater"
+    );
+    assert_eq!(validate_choice("action", "BAN", &ACTIONS).unwrap(), "ban");
+    let err = validate_choice("outcome", "guilty", &OUTCOMES).unwrap_err();
+    assert!(err.contains("invalid outcome"), "{}", err);
+}
+
+#[test]
+fn titles_are_trimmed_and_bounded() {
+    assert_eq!(
+        validate_title("  Kill aura on Alex ").unwrap(),
+        "Kill aura on Alex"
+    );
+    assert!(validate_title("   ").is_err());
+    assert!(validate_title(&"x".repeat(201)).is_err());
+}
+
+#[test]
+fn item_kinds_use_snake_case() {
+    let kind: ItemKind = serde_json::from_str("\"evidence_bundle\"").unwrap();
+    assert_eq!(kind, ItemKind::EvidenceBundle);
+    assert_eq!(kind.column(), "evidence_bundle_id");
+    assert_eq!(
+        serde_json::to_string(&ItemKind::Observation).unwrap(),
+        "\"observation\""
+    );
+}
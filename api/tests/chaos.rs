@@ -0,0 +1,76 @@
+use async_anticheat_api::chaos::{FaultPoint, FaultRule, Faults};
+
+fn rule(point: FaultPoint) -> FaultRule {
+    serde_json::from_value(serde_json::json!({ "point": point })).unwrap()
+}
+
+#[test]
+fn rules_default_to_always_failing_until_cleared() {
+    let r = rule(FaultPoint::S3Put);
+    assert_eq!(r.probability, 1.0);
+    assert_eq!(r.remaining, None);
+    assert_eq!(r.target, None);
+    assert!(r.validate().is_ok());
+
+    let bad = FaultRule {
+        probability: 1.5,
+        ..rule(FaultPoint::S3Put)
+    };
+    assert!(bad.validate().is_err());
+}
+
+#[test]
+fn faults_match_point_and_target() {
+    let faults = Faults::default();
+    faults.set(vec![FaultRule {
+        target: Some("combat_core".to_string()),
+        delay_ms: 250,
+        ..rule(FaultPoint::ModuleDispatch)
+    }]);
+
+    assert_eq!(faults.check_at(FaultPoint::S3Put, "combat_core", 0.0), None);
+    assert_eq!(
+        faults.check_at(FaultPoint::ModuleDispatch, "movement_core", 0.0),
+        None
+    );
+    let fault = faults
+        .check_at(FaultPoint::ModuleDispatch, "combat_core", 0.0)
+        .unwrap();
+    assert_eq!(fault.delay_ms, 250);
+    assert!(fault.to_string().contains("timed out"));
+    assert_eq!(faults.list()[0].fired, 1);
+}
+
+#[test]
+fn probability_and_remaining_limit_firings() {
+    let faults = Faults::default();
+    faults.set(vec![FaultRule {
+        probability: 0.5,
+        remaining: Some(2),
+        ..rule(FaultPoint::DbBatchIndex)
+    }]);
+
+    assert!(faults
+        .check_at(FaultPoint::DbBatchIndex, "srv", 0.7)
+        .is_none());
+    assert!(faults
+        .check_at(FaultPoint::DbBatchIndex, "srv", 0.2)
+        .is_some());
+    assert_eq!(faults.list()[0].remaining, Some(1));
+    assert!(faults
+        .check_at(FaultPoint::DbBatchIndex, "srv", 0.2)
+        .is_some());
+    // Used up: the rule is gone.
+    assert!(faults.list().is_empty());
+    assert!(faults
+        .check_at(FaultPoint::DbBatchIndex, "srv", 0.0)
+        .is_none());
+}
+
+#[tokio::test]
+async fn inject_fails_only_in_chaos_builds() {
+    let faults = Faults::default();
+    faults.set(vec![rule(FaultPoint::DbFindings)]);
+    let result = faults.inject(FaultPoint::DbFindings, "srv").await;
+    assert_eq!(result.is_err(), cfg!(feature = "chaos"));
+}
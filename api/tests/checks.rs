@@ -0,0 +1,182 @@
+use async_anticheat_api::checks::{inproc_engine, run};
+use flate2::{write::GzEncoder, Compression};
+
+fn gzip(s: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut enc = GzEncoder::new(&mut out, Compression::default());
+    std::io::Write::write_all(&mut enc, s.as_bytes()).unwrap();
+    enc.finish().unwrap();
+    out
+}
+
+#[test]
+fn inproc_engine_parses_base_url() {
+    assert_eq!(inproc_engine("inproc://combat_core/"), Some("combat_core"));
+    assert_eq!(inproc_engine("http://127.0.0.1:4032"), None);
+}
+
+#[test]
+fn movement_core_flags_blatant_speed() {
+    let raw = r#"
+{"server_id":"s","session_id":"x"}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":64.0,"z":0.0,"on_ground":true}}
+{"ts":1050,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":3.0,"y":64.0,"z":0.0,"on_ground":true}}
+"#
+    .trim_start();
+
+    let findings = run("movement_core", &gzip(raw)).unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].detector_name, "movement_core_speed_blatant");
+}
+
+#[test]
+fn player_core_flags_impossible_pitch() {
+    let raw = r#"
+{"server_id":"s","session_id":"x"}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_ROTATION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"yaw":0.0,"pitch":120.0}}
+"#
+    .trim_start();
+
+    let findings = run("player_core", &gzip(raw)).unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].detector_name, "player_core_badpackets_pitch");
+}
+
+#[test]
+fn movement_core_ignores_flight_when_allowed() {
+    let raw = r#"
+{"server_id":"s","session_id":"x"}
+{"ts":900,"dir":"synthetic","pkt":"PLAYER_STATE","uuid":"00000000-0000-0000-0000-000000000001","fields":{"gamemode":"CREATIVE"}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":64.0,"z":0.0,"on_ground":true}}
+{"ts":1100,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":65.0,"z":0.0,"on_ground":false}}
+{"ts":1200,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":66.0,"z":0.0,"on_ground":false}}
+"#
+    .trim_start();
+
+    let findings = run("movement_core", &gzip(raw)).unwrap();
+    assert!(findings.is_empty());
+}
+
+#[test]
+fn bedrock_players_get_their_own_thresholds() {
+    use async_anticheat_api::checks::{run_with, CheckConfig, ThresholdOverride};
+
+    // Both players move at 60 b/s.
+    let raw = r#"
+{"server_id":"s","session_id":"x","client_types":{"00000000-0000-0000-0000-000000000002":"bedrock"}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":64.0,"z":0.0,"on_ground":true}}
+{"ts":1050,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":3.0,"y":64.0,"z":0.0,"on_ground":true}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000002","fields":{"x":0.0,"y":64.0,"z":0.0,"on_ground":true}}
+{"ts":1050,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000002","fields":{"x":3.0,"y":64.0,"z":0.0,"on_ground":true}}
+"#
+    .trim_start();
+
+    let config = CheckConfig::default().with_override(
+        "movement_core_speed_blatant",
+        ThresholdOverride {
+            threshold: None,
+            bedrock_threshold: Some(80.0),
+            ..Default::default()
+        },
+    );
+    let findings = run_with("movement_core", &gzip(raw), &config).unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(
+        findings[0].player_uuid.unwrap().to_string(),
+        "00000000-0000-0000-0000-000000000001"
+    );
+
+    // A plain override applies to everyone, Bedrock players included.
+    let config = CheckConfig::default().with_override(
+        "movement_core_speed_blatant",
+        ThresholdOverride {
+            threshold: Some(80.0),
+            bedrock_threshold: None,
+            ..Default::default()
+        },
+    );
+    assert!(run_with("movement_core", &gzip(raw), &config)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn threshold_overrides_must_be_positive() {
+    use async_anticheat_api::checks::ThresholdOverride;
+
+    let bad = ThresholdOverride {
+        threshold: Some(-1.0),
+        bedrock_threshold: None,
+        ..Default::default()
+    };
+    assert!(bad.validate().is_err());
+    assert!(ThresholdOverride::default().validate().is_ok());
+}
+
+#[test]
+fn thresholds_follow_the_client_protocol() {
+    use async_anticheat_api::checks::{CheckConfig, ProtocolThreshold, ThresholdOverride};
+    use async_anticheat_api::transforms::ClientType;
+
+    const CPS: &str = "combat_core_autoclicker_cps";
+
+    // Built-in: 1.8 clients (47) get the looser legacy value, 1.9+ and unknown the default.
+    let config = CheckConfig::default();
+    assert_eq!(config.threshold(CPS, 20.0, None, Some(47)), 26.0);
+    assert_eq!(config.threshold(CPS, 20.0, None, Some(767)), 20.0);
+    assert_eq!(config.threshold(CPS, 20.0, None, None), 20.0);
+    assert_eq!(
+        config.threshold("combat_core_reach_critical", 6.0, None, Some(47)),
+        6.0
+    );
+
+    let config = CheckConfig::default().with_override(
+        CPS,
+        ThresholdOverride {
+            threshold: Some(18.0),
+            bedrock_threshold: Some(30.0),
+            protocol_thresholds: vec![
+                ProtocolThreshold {
+                    max_protocol: 340,
+                    threshold: 22.0,
+                },
+                ProtocolThreshold {
+                    max_protocol: 47,
+                    threshold: 28.0,
+                },
+            ],
+        },
+    );
+    // The narrowest range covering the protocol wins.
+    assert_eq!(config.threshold(CPS, 20.0, None, Some(47)), 28.0);
+    assert_eq!(config.threshold(CPS, 20.0, None, Some(110)), 22.0);
+    assert_eq!(config.threshold(CPS, 20.0, None, Some(767)), 18.0);
+    assert_eq!(config.threshold(CPS, 20.0, None, None), 18.0);
+    assert_eq!(
+        config.threshold(CPS, 20.0, Some(ClientType::Bedrock), Some(47)),
+        30.0
+    );
+}
+
+#[test]
+fn protocol_thresholds_are_validated() {
+    use async_anticheat_api::checks::{ProtocolThreshold, ThresholdOverride};
+
+    let with = |ranges: &[(i32, f64)]| ThresholdOverride {
+        protocol_thresholds: ranges
+            .iter()
+            .map(|&(max_protocol, threshold)| ProtocolThreshold {
+                max_protocol,
+                threshold,
+            })
+            .collect(),
+        ..Default::default()
+    };
+    assert!(with(&[(0, 20.0)]).validate().is_err());
+    assert!(with(&[(47, 0.0)]).validate().is_err());
+    assert!(with(&[(47, 20.0), (47, 25.0)]).validate().is_err());
+    assert!(with(&[(47, 20.0); 9]).validate().is_err());
+
+    let ok = with(&[(340, 22.0), (47, 28.0)]).validate().unwrap();
+    assert_eq!(ok.protocol_thresholds[0].max_protocol, 47);
+}
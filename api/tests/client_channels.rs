@@ -0,0 +1,28 @@
+use async_anticheat_api::{client_channels::collect, transforms::ClientChannel};
+use uuid::Uuid;
+
+#[test]
+fn collect_merges_channels_and_keeps_latest_brand() {
+    let p = Uuid::new_v4();
+    let merged = collect(vec![
+        (1, p, ClientChannel::Brand("vanilla".into())),
+        (
+            2,
+            p,
+            ClientChannel::Register(vec!["b:x".into(), "a:y".into()]),
+        ),
+        (3, p, ClientChannel::Register(vec!["a:y".into()])),
+        (4, p, ClientChannel::Brand("fabric".into())),
+    ]);
+
+    let client = &merged[&p];
+    assert_eq!(client.brand.as_deref(), Some("fabric"));
+    assert_eq!(
+        client
+            .channels
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+        vec!["a:y", "b:x"]
+    );
+}
@@ -0,0 +1,33 @@
+use async_anticheat_api::clock_skew::{estimate, from_headers, offset};
+use axum::http::{HeaderMap, HeaderValue};
+use chrono::{TimeZone, Utc};
+
+#[test]
+fn parses_client_time_header() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(from_headers(&headers), Ok(None));
+
+    headers.insert("X-Client-Time", HeaderValue::from_static("1767225600000"));
+    assert_eq!(from_headers(&headers), Ok(Some(1_767_225_600_000)));
+
+    for bad in ["0", "-5", "yesterday"] {
+        headers.insert("X-Client-Time", HeaderValue::from_static(bad));
+        assert!(from_headers(&headers).is_err(), "{} accepted", bad);
+    }
+}
+
+#[test]
+fn offset_is_client_minus_receive_time() {
+    let received = Utc.timestamp_millis_opt(1_767_225_600_000).unwrap();
+    assert_eq!(offset(1_767_225_690_000, received), 90_000);
+    assert_eq!(offset(1_767_225_599_750, received), -250);
+}
+
+#[test]
+fn estimate_takes_the_least_delayed_offset() {
+    // True skew 90s; uploads took 40ms, 1.2s (spooled retry) and 15ms.
+    assert_eq!(estimate([89_960, 88_800, 89_985]), Some(89_985));
+    // Clock behind the API.
+    assert_eq!(estimate([-30_040, -30_100]), Some(-30_040));
+    assert_eq!(estimate(Vec::new()), None);
+}
@@ -0,0 +1,17 @@
+use async_anticheat_api::{checks, conformance};
+
+#[test]
+fn fixture_batch_triggers_core_checks() {
+    let raw = conformance::fixture_batch("s", "conformance-test").unwrap();
+    let findings = checks::run("movement_core", &raw).unwrap();
+    assert!(findings
+        .iter()
+        .any(|f| f.detector_name == "movement_core_speed_blatant"));
+}
+
+#[test]
+fn conformance_sessions_are_recognized() {
+    assert!(conformance::is_conformance_session(Some("conformance-abc")));
+    assert!(!conformance::is_conformance_session(Some("session-1")));
+    assert!(!conformance::is_conformance_session(None));
+}
@@ -0,0 +1,86 @@
+use async_anticheat_api::correlation::{adds_family, combine, family, COMBINED_DETECTOR};
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+#[test]
+fn families_group_tiers_and_variants_of_a_check() {
+    assert_eq!(family("combat_core_reach_critical"), "combat_reach");
+    assert_eq!(family("combat_advanced_reach_distance"), "combat_reach");
+    assert_eq!(family("combat_advanced_aim_headsnap"), "combat_aim");
+    assert_eq!(family("movement_core_speed_blatant"), "movement_speed");
+    // Names outside the built-in scheme are their own family.
+    assert_eq!(family("my_custom_check"), "my_custom_check");
+    assert_eq!(family("Reach"), "reach");
+}
+
+#[test]
+fn one_family_is_not_combined() {
+    let player = Uuid::new_v4();
+    assert!(combine(
+        player,
+        [
+            "combat_core_reach_critical",
+            "combat_advanced_reach_distance"
+        ]
+    )
+    .is_none());
+    assert!(combine(player, [COMBINED_DETECTOR, "combat_core_reach_critical"]).is_none());
+}
+
+#[test]
+fn independent_families_raise_a_combined_finding() {
+    let player = Uuid::new_v4();
+    let two = combine(
+        player,
+        ["combat_core_reach_critical", "combat_core_autoclicker_cps"],
+    )
+    .unwrap();
+    assert_eq!(two.detector_name, COMBINED_DETECTOR);
+    assert_eq!(two.player_uuid, Some(player));
+    assert_eq!(two.severity.as_deref(), Some("high"));
+    assert_eq!(two.title, "Combined detection: autoclicker + reach");
+
+    let three = combine(
+        player,
+        [
+            "combat_core_reach_critical",
+            "combat_advanced_reach_distance",
+            "combat_advanced_autoclicker_kurtosis",
+            "combat_advanced_aim_headsnap",
+        ],
+    )
+    .unwrap();
+    assert_eq!(three.severity.as_deref(), Some("critical"));
+    let evidence = three.evidence_json.unwrap();
+    assert_eq!(
+        evidence["families"],
+        serde_json::json!(["combat_aim", "combat_autoclicker", "combat_reach"])
+    );
+    assert_eq!(evidence["detectors"].as_array().unwrap().len(), 4);
+}
+
+#[test]
+fn combined_findings_repeat_only_when_a_family_joins() {
+    let player = Uuid::new_v4();
+    let two = combine(
+        player,
+        ["combat_core_reach_critical", "combat_core_autoclicker_cps"],
+    )
+    .unwrap();
+    let covered: BTreeSet<String> = ["combat_autoclicker", "combat_reach"]
+        .map(String::from)
+        .into();
+    assert!(adds_family(&two, &BTreeSet::new()));
+    assert!(!adds_family(&two, &covered));
+
+    let three = combine(
+        player,
+        [
+            "combat_core_reach_critical",
+            "combat_core_autoclicker_cps",
+            "combat_advanced_aim_headsnap",
+        ],
+    )
+    .unwrap();
+    assert!(adds_family(&three, &covered));
+}
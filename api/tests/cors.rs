@@ -0,0 +1,73 @@
+use async_anticheat_api::{app::cors_layer, config::Config};
+use axum::{
+    routing::{delete, patch},
+    Router,
+};
+
+fn serve(app: Router) -> String {
+    let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    format!("http://{}", addr)
+}
+
+fn app(origins: &[&str]) -> Router {
+    let mut cfg = Config::from_env();
+    cfg.cors_allow_origins = origins.iter().map(|o| o.to_string()).collect();
+    cfg.cors_permissive_dev = false;
+    Router::new()
+        .route("/dashboard/srv/observations/1", patch(|| async { "ended" }))
+        .route("/dashboard/srv/notes/1", delete(|| async { "deleted" }))
+        .layer(cors_layer(&cfg))
+}
+
+async fn preflight(base: &str, path: &str, method: &str) -> reqwest::Response {
+    reqwest::Client::new()
+        .request(reqwest::Method::OPTIONS, format!("{}{}", base, path))
+        .header("origin", "https://dash.example.com")
+        .header("access-control-request-method", method)
+        .header(
+            "access-control-request-headers",
+            "authorization,content-type",
+        )
+        .send()
+        .await
+        .unwrap()
+}
+
+fn allowed_methods(resp: &reqwest::Response) -> String {
+    resp.headers()["access-control-allow-methods"]
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn preflights_allow_patch_and_delete_routes() {
+    let base = serve(app(&["https://dash.example.com"]));
+
+    let resp = preflight(&base, "/dashboard/srv/observations/1", "PATCH").await;
+    assert!(resp.status().is_success());
+    assert_eq!(
+        resp.headers()["access-control-allow-origin"],
+        "https://dash.example.com"
+    );
+    assert!(allowed_methods(&resp).contains("PATCH"));
+
+    let resp = preflight(&base, "/dashboard/srv/notes/1", "DELETE").await;
+    assert!(resp.status().is_success());
+    let methods = allowed_methods(&resp);
+    assert!(methods.contains("DELETE"), "{}", methods);
+    assert!(methods.contains("PUT"), "{}", methods);
+}
+
+#[tokio::test]
+async fn same_origin_defaults_list_every_method_too() {
+    let base = serve(app(&[]));
+    let resp = preflight(&base, "/dashboard/srv/notes/1", "DELETE").await;
+    let methods = allowed_methods(&resp);
+    for method in ["GET", "POST", "PUT", "PATCH", "DELETE"] {
+        assert!(methods.contains(method), "{}", methods);
+    }
+    assert!(resp.headers().get("access-control-allow-origin").is_none());
+}
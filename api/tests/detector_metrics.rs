@@ -0,0 +1,40 @@
+use async_anticheat_api::detector_metrics::{precision, transition, FINDING_STATUSES};
+
+#[test]
+fn precision_needs_reviewed_findings() {
+    assert_eq!(precision(0, 0), None);
+    assert_eq!(precision(3, 1), Some(0.75));
+    assert_eq!(precision(0, 2), Some(0.0));
+}
+
+#[test]
+fn reviews_move_one_count_between_counters() {
+    assert_eq!(transition("open", "confirmed"), (1, 0, 0));
+    assert_eq!(transition("open", "false_positive"), (0, 1, 0));
+    assert_eq!(transition("open", "dismissed"), (0, 0, 1));
+    // Changing a verdict moves the count instead of adding one.
+    assert_eq!(transition("confirmed", "false_positive"), (-1, 1, 0));
+    assert_eq!(transition("false_positive", "dismissed"), (0, -1, 1));
+    // Reopening takes the count back.
+    assert_eq!(transition("confirmed", "open"), (-1, 0, 0));
+}
+
+#[test]
+fn unchanged_statuses_leave_the_counters_alone() {
+    for status in FINDING_STATUSES {
+        assert_eq!(transition(status, status), (0, 0, 0), "{}", status);
+    }
+    assert_eq!(transition("open", "resolved"), (0, 0, 0));
+}
+
+#[test]
+fn a_review_round_trip_nets_to_zero() {
+    let path = ["open", "confirmed", "false_positive", "dismissed", "open"];
+    let total = path
+        .windows(2)
+        .map(|w| transition(w[0], w[1]))
+        .fold((0, 0, 0), |(c, f, d), (dc, df, dd)| {
+            (c + dc, f + df, d + dd)
+        });
+    assert_eq!(total, (0, 0, 0));
+}
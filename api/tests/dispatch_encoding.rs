@@ -0,0 +1,81 @@
+use async_anticheat_api::dispatch_encoding::{
+    decode, encode, is_loopback, negotiate, parse_accepted, ContentEncoding,
+};
+use bytes::Bytes;
+use flate2::{write::GzEncoder, Compression};
+use std::io::Write;
+
+const NDJSON: &str = "{\"server_id\":\"srv\"}\n{\"ts\":1,\"pkt\":\"PLAYER_POSITION\"}\n";
+
+fn gzip(s: &str) -> Vec<u8> {
+    let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+    enc.write_all(s.as_bytes()).unwrap();
+    enc.finish().unwrap()
+}
+
+#[test]
+fn parses_accept_encoding_values() {
+    use ContentEncoding::*;
+    assert_eq!(parse_accepted("zstd, gzip"), vec![Zstd, Gzip]);
+    assert_eq!(
+        parse_accepted("br, GZIP;q=0.5, identity;q=0, *"),
+        vec![Gzip]
+    );
+    assert_eq!(
+        parse_accepted("identity, x-gzip, gzip"),
+        vec![Identity, Gzip]
+    );
+    assert!(parse_accepted("").is_empty());
+}
+
+#[test]
+fn detects_loopback_modules() {
+    assert!(is_loopback("http://127.0.0.1:4030"));
+    assert!(is_loopback("http://localhost:4030/"));
+    assert!(is_loopback("http://[::1]:4030"));
+    assert!(!is_loopback("https://modules.example.com"));
+    assert!(!is_loopback("http://10.0.0.5:4030"));
+    assert!(!is_loopback("not a url"));
+}
+
+#[test]
+fn prefers_identity_locally_and_zstd_remotely() {
+    use ContentEncoding::*;
+    let all = [Gzip, Zstd, Identity];
+    assert_eq!(negotiate("http://127.0.0.1:4030", &all), Identity);
+    assert_eq!(negotiate("https://modules.example.com", &all), Zstd);
+    assert_eq!(negotiate("http://127.0.0.1:4030", &[Zstd]), Zstd);
+    assert_eq!(
+        negotiate("https://modules.example.com", &[Identity]),
+        Identity
+    );
+    assert_eq!(negotiate("https://modules.example.com", &[]), Gzip);
+}
+
+#[test]
+fn encoded_payloads_decode_to_the_same_ndjson() {
+    for encoding in [
+        ContentEncoding::Gzip,
+        ContentEncoding::Zstd,
+        ContentEncoding::Identity,
+    ] {
+        let body = encode(gzip(NDJSON).into(), encoding).unwrap();
+        let plain = decode(&body, encoding.header_value()).unwrap();
+        assert_eq!(String::from_utf8(plain).unwrap(), NDJSON, "{:?}", encoding);
+    }
+}
+
+#[test]
+fn gzip_passes_through_unchanged() {
+    let gz = Bytes::from(gzip(NDJSON));
+    let body = encode(gz.clone(), ContentEncoding::Gzip).unwrap();
+    assert_eq!(body, gz);
+    assert_eq!(body.as_ptr(), gz.as_ptr());
+}
+
+#[test]
+fn bodies_without_a_header_are_sniffed() {
+    assert_eq!(decode(&gzip(NDJSON), None).unwrap(), NDJSON.as_bytes());
+    assert_eq!(decode(NDJSON.as_bytes(), None).unwrap(), NDJSON.as_bytes());
+    assert!(decode(NDJSON.as_bytes(), Some("br")).is_err());
+}
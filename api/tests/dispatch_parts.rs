@@ -0,0 +1,106 @@
+use std::io::Write;
+
+use async_anticheat_api::dispatch_encoding::{self, ContentEncoding};
+use async_anticheat_api::dispatch_parts::{encode_parts, split_ndjson, DispatchPart};
+use bytes::Bytes;
+use flate2::{write::GzEncoder, Compression};
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+    enc.write_all(data).unwrap();
+    enc.finish().unwrap()
+}
+
+fn batch(records: usize) -> Vec<u8> {
+    let mut out = b"{\"server_id\":\"s1\",\"transform\":\"raw_ndjson_gz\"}\n".to_vec();
+    // Varying coordinates so the batch doesn't compress below the part limit.
+    let mut x: u64 = 0x9e37_79b9;
+    for i in 0..records {
+        x = x
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        out.extend_from_slice(
+            format!("{{\"seq\":{},\"pkt\":\"FLYING\",\"x\":{}}}\n", i, x >> 11).as_bytes(),
+        );
+    }
+    out
+}
+
+fn lines(part: &[u8]) -> Vec<&str> {
+    std::str::from_utf8(part).unwrap().lines().collect()
+}
+
+#[test]
+fn every_part_starts_with_metadata_and_records_stay_in_order() {
+    let plain = batch(100);
+    let parts = split_ndjson(&plain, 300);
+    assert!(parts.len() > 1);
+
+    let meta = lines(&plain)[0];
+    let mut records = Vec::new();
+    for part in &parts {
+        assert!(part.len() <= 300);
+        let l = lines(part);
+        assert_eq!(l[0], meta);
+        assert!(l.len() > 1);
+        records.extend(l[1..].iter().map(|s| s.to_string()));
+    }
+    let expected: Vec<String> = lines(&plain)[1..].iter().map(|s| s.to_string()).collect();
+    assert_eq!(records, expected);
+}
+
+#[test]
+fn oversize_record_gets_its_own_part() {
+    let mut plain = b"{\"meta\":1}\n{\"a\":1}\n".to_vec();
+    plain.extend_from_slice(format!("{{\"big\":\"{}\"}}\n", "x".repeat(500)).as_bytes());
+    plain.extend_from_slice(b"{\"b\":2}\n");
+
+    let parts = split_ndjson(&plain, 100);
+    assert_eq!(parts.len(), 3);
+    assert_eq!(lines(&parts[1]).len(), 2);
+    assert!(lines(&parts[1])[1].starts_with("{\"big\""));
+}
+
+#[test]
+fn metadata_only_batch_is_one_part() {
+    let parts = split_ndjson(b"{\"meta\":1}\n", 10);
+    assert_eq!(parts, vec![b"{\"meta\":1}\n".to_vec()]);
+}
+
+#[test]
+fn small_payload_is_sent_whole() {
+    let gz = Bytes::from(gzip(&batch(10)));
+    let parts = encode_parts(gz.clone(), ContentEncoding::Gzip, 1024 * 1024).unwrap();
+    assert_eq!(parts, vec![gz.clone()]);
+    assert_eq!(parts[0].as_ptr(), gz.as_ptr());
+
+    let parts = encode_parts(gz.clone(), ContentEncoding::Gzip, 0).unwrap();
+    assert_eq!(parts, vec![gz]);
+}
+
+#[test]
+fn large_payload_splits_in_every_encoding() {
+    let plain = batch(2000);
+    for encoding in [
+        ContentEncoding::Gzip,
+        ContentEncoding::Zstd,
+        ContentEncoding::Identity,
+    ] {
+        let parts = encode_parts(gzip(&plain).into(), encoding, 4096).unwrap();
+        assert!(parts.len() > 1, "{:?}", encoding);
+
+        let mut records = 0;
+        for part in &parts {
+            assert!(part.len() <= 4096, "{:?}", encoding);
+            let decoded = dispatch_encoding::decode(part, encoding.header_value()).unwrap();
+            records += lines(&decoded).len() - 1;
+        }
+        assert_eq!(records, 2000, "{:?}", encoding);
+    }
+}
+
+#[test]
+fn final_part_is_the_last_index() {
+    assert!(!DispatchPart { index: 0, count: 3 }.is_final());
+    assert!(DispatchPart { index: 2, count: 3 }.is_final());
+}
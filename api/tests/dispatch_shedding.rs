@@ -0,0 +1,25 @@
+use async_anticheat_api::dispatch_shedding::{can_recover, sheds_dispatch};
+use async_anticheat_api::ingest_hints::{Load, DISPATCH_BACKLOG_HIGH};
+
+fn load(pending_dispatches: usize, db_busy: u32) -> Load {
+    Load {
+        pending_dispatches,
+        db_connections_busy: db_busy,
+        db_connections_max: 10,
+    }
+}
+
+#[test]
+fn dispatch_is_shed_only_when_full() {
+    assert!(!sheds_dispatch(&load(DISPATCH_BACKLOG_HIGH - 1, 9)));
+    assert!(sheds_dispatch(&load(DISPATCH_BACKLOG_HIGH, 0)));
+    assert!(sheds_dispatch(&load(0, 10)));
+}
+
+#[test]
+fn recovery_waits_for_load_to_drop() {
+    assert!(can_recover(&load(0, 0)));
+    assert!(can_recover(&load(DISPATCH_BACKLOG_HIGH / 4, 4)));
+    assert!(!can_recover(&load(DISPATCH_BACKLOG_HIGH / 2, 0)));
+    assert!(!can_recover(&load(0, 5)));
+}
@@ -0,0 +1,51 @@
+use async_anticheat_api::dispatch_stats::{totals, DispatchStatsHour};
+use chrono::{TimeZone, Utc};
+
+fn hour(
+    h: u32,
+    dispatches: i64,
+    succeeded: i64,
+    failed: i64,
+    timed_out: i64,
+    p95: Option<f64>,
+) -> DispatchStatsHour {
+    DispatchStatsHour {
+        hour: Utc.with_ymd_and_hms(2026, 1, 1, h, 0, 0).unwrap(),
+        dispatches,
+        succeeded,
+        failed,
+        timed_out,
+        avg_latency_ms: None,
+        p95_latency_ms: p95,
+    }
+}
+
+#[test]
+fn totals_sum_hours_and_keep_the_worst_p95() {
+    let t = totals(&[
+        hour(0, 10, 9, 1, 0, Some(120.0)),
+        hour(1, 10, 6, 2, 2, Some(900.0)),
+        hour(2, 0, 0, 0, 0, None),
+    ]);
+    assert_eq!(
+        (t.dispatches, t.succeeded, t.failed, t.timed_out),
+        (20, 15, 3, 2)
+    );
+    assert_eq!(t.success_rate, Some(0.75));
+    assert_eq!(t.max_p95_latency_ms, Some(900.0));
+}
+
+#[test]
+fn processing_dispatches_are_not_in_the_success_rate() {
+    let t = totals(&[hour(0, 5, 3, 1, 0, None)]);
+    assert_eq!(t.dispatches, 5);
+    assert_eq!(t.success_rate, Some(0.75));
+}
+
+#[test]
+fn empty_range_has_no_rate() {
+    let t = totals(&[]);
+    assert_eq!(t.dispatches, 0);
+    assert_eq!(t.success_rate, None);
+    assert_eq!(t.max_p95_latency_ms, None);
+}
@@ -0,0 +1,1398 @@
+//! End-to-end tests against real Postgres and MinIO containers: the API binary runs with the
+//! containers' addresses and is driven over HTTP the way plugins, modules and the dashboard use
+//! it, then the rows and objects it wrote are checked directly.
+//!
+//! Needs a Docker daemon: `cargo test --features docker-tests --test e2e_docker`.
+#![cfg(feature = "docker-tests")]
+
+use std::future::Future;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_anticheat_api::config::Config;
+use async_anticheat_api::module_sdk::MovementEvent;
+use async_anticheat_api::reports::Mailer;
+use async_anticheat_api::routes::callbacks::{self, PostFindingsRequest};
+use async_anticheat_api::s3::ObjectStore;
+use async_anticheat_api::shared_state::SharedState;
+use async_anticheat_api::testing::{fixtures, MockModule, MockResponse};
+use async_anticheat_api::{app, conformance, privacy, reputation, watchlist, AppState};
+use reqwest::StatusCode;
+use s3::creds::Credentials;
+use s3::region::Region;
+use s3::{Bucket, BucketConfiguration};
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use testcontainers_modules::minio::MinIO;
+use testcontainers_modules::postgres::Postgres;
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::{ContainerAsync, ImageExt};
+use tokio::process::{Child, Command};
+use uuid::Uuid;
+
+const INGEST_TOKEN: &str = "e2e-ingest";
+const CALLBACK_TOKEN: &str = "e2e-callback";
+const DASHBOARD_TOKEN: &str = "e2e-dashboard";
+const PLUGIN_TOKEN: &str = "e2e-plugin";
+const BUCKET: &str = "asyncanticheat-e2e";
+const MINIO_CREDENTIALS: &str = "minioadmin";
+const SERVER_ID: &str = "e2e-server";
+const SESSION_ID: &str = "e2e-session";
+const HASH_KEY: &str = "e2e-hash-key";
+
+/// How long the API, the module and the callbacks get to settle.
+const WAIT: Duration = Duration::from_secs(15);
+
+/// Postgres (with `schema.sql` applied), MinIO with an empty bucket, and the API binary
+/// pointed at both. Containers and the API process stop when this is dropped.
+struct Stack {
+    base_url: String,
+    http: reqwest::Client,
+    db: PgPool,
+    bucket: Box<Bucket>,
+    _api: Child,
+    _postgres: ContainerAsync<Postgres>,
+    _minio: ContainerAsync<MinIO>,
+}
+
+impl Stack {
+    async fn start() -> Self {
+        let postgres = Postgres::default()
+            .with_init_sql(include_str!("../schema.sql").as_bytes().to_vec())
+            .with_tag("16-alpine")
+            .start()
+            .await
+            .expect("start postgres container");
+        let database_url = format!(
+            "postgres://postgres:postgres@{}:{}/postgres",
+            postgres.get_host().await.unwrap(),
+            postgres.get_host_port_ipv4(5432).await.unwrap()
+        );
+
+        let minio = MinIO::default()
+            .start()
+            .await
+            .expect("start minio container");
+        let s3_endpoint = format!(
+            "http://{}:{}",
+            minio.get_host().await.unwrap(),
+            minio.get_host_port_ipv4(9000).await.unwrap()
+        );
+        let bucket = Bucket::create_with_path_style(
+            BUCKET,
+            Region::Custom {
+                region: "us-east-1".to_string(),
+                endpoint: s3_endpoint.clone(),
+            },
+            Credentials::new(
+                Some(MINIO_CREDENTIALS),
+                Some(MINIO_CREDENTIALS),
+                None,
+                None,
+                None,
+            )
+            .unwrap(),
+            BucketConfiguration::default(),
+        )
+        .await
+        .expect("create bucket")
+        .bucket;
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|l| l.local_addr())
+            .expect("free port")
+            .port();
+        let api = Command::new(env!("CARGO_BIN_EXE_async_anticheat_api"))
+            .arg("serve")
+            .env_clear()
+            .envs([
+                ("HOST", "127.0.0.1".to_string()),
+                ("PORT", port.to_string()),
+                ("DATABASE_URL", database_url.clone()),
+                ("INGEST_TOKEN", INGEST_TOKEN.to_string()),
+                ("MODULE_CALLBACK_TOKEN", CALLBACK_TOKEN.to_string()),
+                ("DASHBOARD_TOKEN", DASHBOARD_TOKEN.to_string()),
+                ("PLAYER_UUID_HASH_KEY", HASH_KEY.to_string()),
+                ("S3_BUCKET", BUCKET.to_string()),
+                ("S3_REGION", "us-east-1".to_string()),
+                ("S3_ENDPOINT", s3_endpoint),
+                ("S3_ACCESS_KEY", MINIO_CREDENTIALS.to_string()),
+                ("S3_SECRET_KEY", MINIO_CREDENTIALS.to_string()),
+                ("RUST_LOG", "warn".to_string()),
+            ])
+            .stdout(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .expect("start the API binary");
+
+        let stack = Self {
+            base_url: format!("http://127.0.0.1:{}", port),
+            http: reqwest::Client::new(),
+            db: PgPool::connect(&database_url)
+                .await
+                .expect("connect to postgres"),
+            bucket,
+            _api: api,
+            _postgres: postgres,
+            _minio: minio,
+        };
+        let healthy = wait_until(WAIT, || async {
+            let resp = stack.http.get(stack.url("/health")).send().await.ok()?;
+            resp.status().is_success().then_some(())
+        })
+        .await;
+        assert!(healthy.is_some(), "API did not become healthy");
+        stack
+    }
+
+    /// An in-process state over the stack's database, for driving background ticks directly.
+    fn app_state(&self) -> AppState {
+        let mut cfg = Config::from_env();
+        cfg.reputation_enabled = true;
+        let store = ObjectStore::Local {
+            root: std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("e2e_docker"),
+        };
+        app::state(
+            &cfg,
+            self.db.clone(),
+            store,
+            SharedState::local(),
+            Mailer::default(),
+        )
+        .unwrap()
+    }
+
+    /// A finding of `player` on `server_id` as a module would report it; its id.
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_finding(
+        &self,
+        server_id: &str,
+        player: Uuid,
+        detector: &str,
+        severity: &str,
+        status: &str,
+        occurrences: i32,
+        source: Option<&str>,
+    ) -> Uuid {
+        sqlx::query(
+            "insert into public.players (uuid, username) values ($1, $2) on conflict do nothing",
+        )
+        .bind(player)
+        .bind(format!("p{}", &player.simple().to_string()[..8]))
+        .execute(&self.db)
+        .await
+        .unwrap();
+        sqlx::query_scalar(
+            "insert into public.findings \
+             (server_id, player_uuid, detector_name, severity, title, status, occurrences, source) \
+             values ($1, $2, $3, $4, 'Speed', $5, $6, $7) returning id",
+        )
+        .bind(server_id)
+        .bind(player)
+        .bind(detector)
+        .bind(severity)
+        .bind(status)
+        .bind(occurrences)
+        .bind(source)
+        .fetch_one(&self.db)
+        .await
+        .unwrap()
+    }
+
+    /// `(score, findings)` of a player after a reputation tick; none without a row.
+    async fn reputation(&self, player: Uuid) -> Option<(f64, i32)> {
+        sqlx::query_as(
+            "select score, findings from public.player_reputation where player_uuid = $1",
+        )
+        .bind(player)
+        .fetch_optional(&self.db)
+        .await
+        .unwrap()
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Subscribe a module to the test server (what a module operator does once).
+    async fn register_module(&self, base_url: &str, transform: &str) -> Uuid {
+        let resp = self
+            .http
+            .post(self.url(&format!("/servers/{}/modules", SERVER_ID)))
+            .bearer_auth(INGEST_TOKEN)
+            .json(&json!({
+                "name": "e2e-module",
+                "base_url": base_url,
+                "enabled": true,
+                "transform": transform,
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let module: Value = resp.json().await.unwrap();
+        module["id"].as_str().unwrap().parse().unwrap()
+    }
+
+    /// Link the test server to a new organization so its plugin may ingest.
+    async fn register_server(&self) {
+        let org: Value = self
+            .dashboard_post(
+                "/dashboard/organizations",
+                json!({ "name": "e2e", "owner_user_id": Uuid::new_v4() }),
+            )
+            .await;
+        let org_id = org["id"].as_str().unwrap();
+        let attached = self
+            .dashboard_post(
+                &format!("/dashboard/organizations/{}/servers", org_id),
+                json!({ "server_id": SERVER_ID }),
+            )
+            .await;
+        assert_eq!(attached["ok"], true);
+    }
+
+    async fn dashboard_post(&self, path: &str, body: Value) -> Value {
+        let resp = self
+            .http
+            .post(self.url(path))
+            .bearer_auth(DASHBOARD_TOKEN)
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK, "POST {}", path);
+        resp.json().await.unwrap()
+    }
+
+    async fn dashboard_get(&self, path: &str) -> Value {
+        let resp = self
+            .http
+            .get(self.url(path))
+            .bearer_auth(DASHBOARD_TOKEN)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK, "GET {}", path);
+        resp.json().await.unwrap()
+    }
+
+    /// Follow `next_cursor` from `path` (which has a `limit`) to the last page; every page's
+    /// `key` items.
+    async fn walk_pages(&self, path: &str, key: &str) -> Vec<Vec<Value>> {
+        let mut pages = Vec::new();
+        let mut page = self.dashboard_get(path).await;
+        loop {
+            pages.push(page[key].as_array().unwrap().clone());
+            let Some(cursor) = page["next_cursor"].as_str() else {
+                return pages;
+            };
+            page = self
+                .dashboard_get(&format!("{}&cursor={}", path, cursor))
+                .await;
+        }
+    }
+
+    /// Upload a batch the way the plugin does.
+    async fn ingest(&self, raw_gz: Vec<u8>) -> (StatusCode, Value) {
+        let resp = self
+            .http
+            .post(self.url("/ingest"))
+            .bearer_auth(PLUGIN_TOKEN)
+            .header("content-type", "application/x-ndjson")
+            .header("content-encoding", "gzip")
+            .header("x-server-id", SERVER_ID)
+            .header("x-session-id", SESSION_ID)
+            .body(raw_gz)
+            .send()
+            .await
+            .unwrap();
+        (resp.status(), resp.json().await.unwrap())
+    }
+
+    /// Dispatch rows recorded for a batch and module: `(status, http_status)`. Ingest also
+    /// subscribes the server to the built-in modules, which are not running here.
+    async fn dispatches(&self, batch_id: Uuid, module_id: Uuid) -> Vec<(String, Option<i32>)> {
+        sqlx::query_as(
+            "select status, http_status from public.module_dispatches \
+             where batch_id = $1 and module_id = $2",
+        )
+        .bind(batch_id)
+        .bind(module_id)
+        .fetch_all(&self.db)
+        .await
+        .unwrap()
+    }
+}
+
+/// Poll `check` every 100ms until it returns `Some` or `timeout` passes.
+async fn wait_until<T, F, Fut>(timeout: Duration, check: F) -> Option<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Option<T>>,
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if let Some(value) = check().await {
+            return Some(value);
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return None;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+#[tokio::test]
+async fn plugin_batch_flows_through_storage_dispatch_callback_and_dashboard() {
+    let stack = Stack::start().await;
+    let player = Uuid::new_v4();
+    // Packet timestamps far from the API clock would get the batch quarantined, not dispatched.
+    let now = chrono::Utc::now().timestamp_millis();
+    let module = MockModule::spawn(MockResponse {
+        findings: vec![fixtures::finding("speed", "high", player)],
+        callback: Some((stack.base_url.clone(), CALLBACK_TOKEN.to_string())),
+        ..Default::default()
+    });
+    let module_id = stack
+        .register_module(&module.base_url, "movement_events_v1_ndjson_gz")
+        .await;
+    let raw = fixtures::raw_batch(SERVER_ID, SESSION_ID, &fixtures::walk(now, player, 5, 0.9));
+
+    // Unlinked servers are held back; nothing is stored or dispatched.
+    let (status, body) = stack.ingest(raw.clone()).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["status"], "waiting_for_registration");
+
+    stack.register_server().await;
+    let (status, body) = stack.ingest(raw.clone()).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let batch_id: Uuid = body["batch_id"].as_str().unwrap().parse().unwrap();
+    let s3_key = body["s3_key"].as_str().unwrap();
+
+    let (server_id, session_id, indexed_key, payload_bytes): (String, String, String, i32) =
+        sqlx::query_as(
+            "select server_id, session_id, s3_key, payload_bytes from public.batch_index where id = $1",
+        )
+        .bind(batch_id)
+        .fetch_one(&stack.db)
+        .await
+        .unwrap();
+    assert_eq!(
+        (server_id.as_str(), session_id.as_str()),
+        (SERVER_ID, SESSION_ID)
+    );
+    assert_eq!(indexed_key, s3_key);
+    assert_eq!(payload_bytes as usize, raw.len());
+
+    let object = stack.bucket.get_object(s3_key).await.unwrap();
+    assert_eq!(object.status_code(), 200);
+    assert_eq!(object.bytes().as_ref(), raw.as_slice());
+
+    let batches = module.wait_for_batches(1, WAIT).await;
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].batch_id, batch_id);
+    assert_eq!(batches[0].module_id, Some(module_id));
+    assert_eq!(batches[0].transform(), Some("movement_events_v1"));
+    assert_eq!(batches[0].events::<MovementEvent>().count(), 5);
+
+    let dispatched = wait_until(WAIT, || async {
+        let rows = stack.dispatches(batch_id, module_id).await;
+        (!rows.is_empty()).then_some(rows)
+    })
+    .await;
+    assert_eq!(dispatched, Some(vec![("sent".to_string(), Some(200))]));
+
+    let stored = wait_until(WAIT, || async {
+        let (n,): (i64,) = sqlx::query_as(
+            "select count(*) from public.findings where server_id = $1 and player_uuid = $2",
+        )
+        .bind(SERVER_ID)
+        .bind(player)
+        .fetch_one(&stack.db)
+        .await
+        .unwrap();
+        (n > 0).then_some(n)
+    })
+    .await;
+    assert_eq!(stored, Some(1));
+
+    let findings_url = stack.url(&format!("/dashboard/{}/findings", SERVER_ID));
+    let resp = stack.http.get(&findings_url).send().await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let page: Value = stack
+        .http
+        .get(&findings_url)
+        .bearer_auth(DASHBOARD_TOKEN)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(page["total"], 1);
+    assert_eq!(page["findings"][0]["detector_name"], "speed");
+    assert_eq!(page["findings"][0]["severity"], "high");
+    assert_eq!(page["findings"][0]["player_uuid"], player.to_string());
+}
+
+#[tokio::test]
+async fn failing_module_is_recorded_without_losing_the_batch() {
+    let stack = Stack::start().await;
+    let module = MockModule::spawn(MockResponse {
+        fail: true,
+        ..Default::default()
+    });
+    let module_id = stack
+        .register_module(&module.base_url, "combat_events_v1_ndjson_gz")
+        .await;
+    stack.register_server().await;
+
+    let raw = fixtures::raw_batch(
+        SERVER_ID,
+        SESSION_ID,
+        &[fixtures::attack(
+            chrono::Utc::now().timestamp_millis(),
+            Uuid::new_v4(),
+            7,
+        )],
+    );
+    let (status, body) = stack.ingest(raw.clone()).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let batch_id: Uuid = body["batch_id"].as_str().unwrap().parse().unwrap();
+
+    assert!(!module.wait_for_batches(1, WAIT).await.is_empty());
+    let failed = wait_until(WAIT, || async {
+        let rows = stack.dispatches(batch_id, module_id).await;
+        rows.iter()
+            .any(|(status, _)| status == "failed")
+            .then_some(rows)
+    })
+    .await
+    .expect("failed dispatch recorded");
+    assert!(failed.contains(&("failed".to_string(), Some(500))));
+
+    let object = stack
+        .bucket
+        .get_object(body["s3_key"].as_str().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(object.bytes().as_ref(), raw.as_slice());
+}
+
+#[tokio::test]
+async fn canary_findings_are_tagged_and_canary_failures_spare_the_module() {
+    let stack = Stack::start().await;
+    let now = chrono::Utc::now().timestamp_millis();
+    let (stable_player, canary_player) = (Uuid::new_v4(), Uuid::new_v4());
+    let callback = Some((stack.base_url.clone(), CALLBACK_TOKEN.to_string()));
+    let stable = MockModule::spawn(MockResponse {
+        findings: vec![fixtures::finding("speed", "high", stable_player)],
+        callback: callback.clone(),
+        ..Default::default()
+    });
+    // Posts its findings before answering /ingest.
+    let canary = MockModule::spawn(MockResponse {
+        findings: vec![fixtures::finding("speed", "high", canary_player)],
+        callback,
+        ..Default::default()
+    });
+    let module_id = stack
+        .register_module(&stable.base_url, "movement_events_v1_ndjson_gz")
+        .await;
+    stack.register_server().await;
+    let set_canary = |base_url: String| {
+        let stack = &stack;
+        async move {
+            sqlx::query(
+                "update public.server_modules set canary_base_url = $2, canary_percent = 100 \
+                 where id = $1",
+            )
+            .bind(module_id)
+            .bind(base_url)
+            .execute(&stack.db)
+            .await
+            .unwrap();
+        }
+    };
+    let variant_of = |player: Uuid| {
+        let stack = &stack;
+        async move {
+            sqlx::query_scalar::<_, Option<String>>(
+                "select module_variant from public.findings where player_uuid = $1",
+            )
+            .bind(player)
+            .fetch_optional(&stack.db)
+            .await
+            .unwrap()
+        }
+    };
+
+    set_canary(canary.base_url.clone()).await;
+    let raw = fixtures::raw_batch(
+        SERVER_ID,
+        SESSION_ID,
+        &fixtures::walk(now, Uuid::new_v4(), 5, 0.9),
+    );
+    let (status, body) = stack.ingest(raw).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(canary.wait_for_batches(1, WAIT).await.len(), 1);
+    assert!(stable.batches().is_empty());
+    let tagged = wait_until(WAIT, || async { variant_of(canary_player).await }).await;
+    assert_eq!(tagged, Some(Some("canary".to_string())));
+    let stats = wait_until(WAIT, || async {
+        let row: Option<(i64, i64)> = sqlx::query_as(
+            "select batches, findings from public.module_variant_stats \
+             where module_id = $1 and variant = 'canary'",
+        )
+        .bind(module_id)
+        .fetch_optional(&stack.db)
+        .await
+        .unwrap();
+        row.filter(|(batches, _)| *batches > 0)
+    })
+    .await;
+    assert_eq!(stats, Some((1, 1)));
+
+    // A failing canary hands the batch to the stable endpoint and leaves the module healthy.
+    let broken = MockModule::spawn(MockResponse {
+        fail: true,
+        ..Default::default()
+    });
+    set_canary(broken.base_url.clone()).await;
+    let raw = fixtures::raw_batch(
+        SERVER_ID,
+        SESSION_ID,
+        &fixtures::walk(now + 1_000, Uuid::new_v4(), 5, 0.9),
+    );
+    let (status, body) = stack.ingest(raw).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(broken.wait_for_batches(1, WAIT).await.len(), 1);
+    assert_eq!(stable.wait_for_batches(1, WAIT).await.len(), 1);
+    let tagged = wait_until(WAIT, || async { variant_of(stable_player).await }).await;
+    assert_eq!(tagged, Some(Some("stable".to_string())));
+    let (failures, last_error): (i32, Option<String>) = sqlx::query_as(
+        "select consecutive_failures, last_error from public.server_modules where id = $1",
+    )
+    .bind(module_id)
+    .fetch_one(&stack.db)
+    .await
+    .unwrap();
+    assert_eq!((failures, last_error), (0, None));
+}
+
+#[tokio::test]
+async fn hashing_servers_store_no_real_player_uuid_or_name() {
+    let stack = Stack::start().await;
+    stack.register_server().await;
+    let updated = stack
+        .dashboard_post(
+            &format!("/dashboard/{}/privacy", SERVER_ID),
+            json!({ "hash_player_uuids": true }),
+        )
+        .await;
+    assert_eq!(updated["hash_player_uuids"], true);
+
+    let player = Uuid::new_v4();
+    let now = chrono::Utc::now().timestamp_millis();
+    let raw = fixtures::raw_batch(SERVER_ID, SESSION_ID, &fixtures::walk(now, player, 5, 0.9));
+    let (status, body) = stack.ingest(raw).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let pseudonym = privacy::pseudonymize_player(HASH_KEY, SERVER_ID, player);
+    let object = stack
+        .bucket
+        .get_object(body["s3_key"].as_str().unwrap())
+        .await
+        .unwrap();
+    let mut stored = String::new();
+    std::io::Read::read_to_string(
+        &mut flate2::read::GzDecoder::new(object.bytes().as_ref()),
+        &mut stored,
+    )
+    .unwrap();
+    assert!(!stored.contains(&player.to_string()));
+    assert!(!stored.contains(r#""name":"player""#));
+    assert!(stored.contains(&pseudonym.to_string()));
+
+    // Players are upserted in the background, `server_players` last.
+    let expected = vec![(pseudonym, privacy::pseudonymous_name(pseudonym))];
+    let names = wait_until(WAIT, || async {
+        let rows: Vec<(Uuid, String)> =
+            sqlx::query_as("select player_uuid, player_name from public.server_players")
+                .fetch_all(&stack.db)
+                .await
+                .unwrap();
+        (!rows.is_empty()).then_some(rows)
+    })
+    .await;
+    assert_eq!(names.as_ref(), Some(&expected));
+    let players: Vec<(Uuid, String)> = sqlx::query_as("select uuid, username from public.players")
+        .fetch_all(&stack.db)
+        .await
+        .unwrap();
+    assert_eq!(players, expected);
+}
+
+#[tokio::test]
+async fn only_stored_conformance_runs_swallow_callback_findings() {
+    let stack = Stack::start().await;
+    let module_id = stack
+        .register_module("http://127.0.0.1:9", "raw_ndjson_gz")
+        .await;
+    let marker = format!("{}{}", conformance::MARKER_PREFIX, Uuid::new_v4().simple());
+    sqlx::query(
+        "insert into public.module_conformance_runs (module_id, server_id, marker) \
+         values ($1, $2, $3)",
+    )
+    .bind(module_id)
+    .bind(SERVER_ID)
+    .bind(&marker)
+    .execute(&stack.db)
+    .await
+    .unwrap();
+    let player = Uuid::new_v4();
+    sqlx::query("insert into public.players (uuid, username) values ($1, 'alex')")
+        .bind(player)
+        .execute(&stack.db)
+        .await
+        .unwrap();
+
+    let callback = |session: &str, detector: &str| {
+        stack
+            .http
+            .post(stack.url("/callbacks/findings"))
+            .bearer_auth(CALLBACK_TOKEN)
+            .json(&json!({
+                "server_id": SERVER_ID,
+                "module_id": module_id,
+                "session_id": session,
+                "findings": [fixtures::finding(detector, "high", player)],
+            }))
+            .send()
+    };
+    // A plugin-chosen session that only looks like a marker carries real findings.
+    let resp = callback("conformance-lobby", "speed").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.json::<Value>().await.unwrap()["inserted"], 1);
+    let resp = callback(&marker, "reach").await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.json::<Value>().await.unwrap()["inserted"], 0);
+
+    let detectors: Vec<String> =
+        sqlx::query_scalar("select detector_name from public.findings where player_uuid = $1")
+            .bind(player)
+            .fetch_all(&stack.db)
+            .await
+            .unwrap();
+    assert_eq!(detectors, vec!["speed".to_string()]);
+    let recorded: Option<i32> = sqlx::query_scalar(
+        "select callback_findings from public.module_conformance_runs where marker = $1",
+    )
+    .bind(&marker)
+    .fetch_one(&stack.db)
+    .await
+    .unwrap();
+    assert_eq!(recorded, Some(1));
+}
+
+#[tokio::test]
+async fn shadow_module_findings_stay_out_of_findings_webhooks_and_bans() {
+    let stack = Stack::start().await;
+    let module_id = stack
+        .register_module("http://127.0.0.1:9", "raw_ndjson_gz")
+        .await;
+
+    // Webhook endpoint counting deliveries.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let webhook_url = format!("http://{}", listener.local_addr().unwrap());
+    let deliveries = Arc::new(AtomicUsize::new(0));
+    let counted = deliveries.clone();
+    tokio::spawn(async move {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        while let Ok((mut conn, _)) = listener.accept().await {
+            counted.fetch_add(1, Ordering::SeqCst);
+            let mut buf = vec![0; 64 * 1024];
+            let _ = conn.read(&mut buf).await;
+            let _ = conn
+                .write_all(b"HTTP/1.1 204 No Content\r\nconnection: close\r\n\r\n")
+                .await;
+        }
+    });
+    sqlx::query("update public.servers set webhook_url = $2, webhook_enabled = true where id = $1")
+        .bind(SERVER_ID)
+        .bind(&webhook_url)
+        .execute(&stack.db)
+        .await
+        .unwrap();
+    sqlx::query(
+        "insert into public.punishment_exports (server_id, enabled, trigger_severities) \
+         values ($1, true, '{critical}')",
+    )
+    .bind(SERVER_ID)
+    .execute(&stack.db)
+    .await
+    .unwrap();
+
+    let state = stack.app_state();
+    let store = |detector: &str, player: Uuid| {
+        let req = PostFindingsRequest {
+            server_id: SERVER_ID.to_string(),
+            module_id: Some(module_id),
+            session_id: Some(SESSION_ID.to_string()),
+            batch_id: None,
+            findings: vec![fixtures::finding(detector, "critical", player)],
+        };
+        let state = state.clone();
+        async move { callbacks::store_findings(&state, &req).await.unwrap() }
+    };
+    let ban_actions = |player: Uuid| {
+        let stack = &stack;
+        async move {
+            sqlx::query_scalar::<_, i64>(
+                "select count(*) from public.punishment_actions where uuid = $1",
+            )
+            .bind(player.to_string())
+            .fetch_one(&stack.db)
+            .await
+            .unwrap()
+        }
+    };
+
+    // Active: the finding goes live, alerts and is exported.
+    let active_player = Uuid::new_v4();
+    assert_eq!(store("speed", active_player).await, 1);
+    let exported = wait_until(WAIT, || async {
+        let n = ban_actions(active_player).await;
+        (n > 0 && deliveries.load(Ordering::SeqCst) > 0).then_some(n)
+    })
+    .await;
+    assert_eq!(exported, Some(1));
+
+    sqlx::query("update public.server_modules set mode = 'shadow' where id = $1")
+        .bind(module_id)
+        .execute(&stack.db)
+        .await
+        .unwrap();
+    let alerts_before = deliveries.load(Ordering::SeqCst);
+    let shadow_player = Uuid::new_v4();
+    assert_eq!(store("reach", shadow_player).await, 1);
+
+    let shadowed: Vec<(Uuid, String)> = sqlx::query_as(
+        "select module_id, detector_name from public.shadow_findings where player_uuid = $1",
+    )
+    .bind(shadow_player)
+    .fetch_all(&stack.db)
+    .await
+    .unwrap();
+    assert_eq!(shadowed, vec![(module_id, "reach".to_string())]);
+    let live: i64 =
+        sqlx::query_scalar("select count(*) from public.findings where player_uuid = $1")
+            .bind(shadow_player)
+            .fetch_one(&stack.db)
+            .await
+            .unwrap();
+    assert_eq!(live, 0);
+    // Give fire-and-forget webhooks and exports the time the active finding needed.
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(ban_actions(shadow_player).await, 0);
+    assert_eq!(deliveries.load(Ordering::SeqCst), alerts_before);
+}
+
+#[tokio::test]
+async fn pending_actions_repeat_on_ingest_until_acknowledged() {
+    let stack = Stack::start().await;
+    stack.register_server().await;
+    let watched = Uuid::new_v4();
+    sqlx::query("insert into public.server_watchlist (server_id, player_uuid) values ($1, $2)")
+        .bind(SERVER_ID)
+        .bind(watched)
+        .execute(&stack.db)
+        .await
+        .unwrap();
+    watchlist::sync_to_plugin(&stack.db, SERVER_ID)
+        .await
+        .unwrap();
+
+    let upload = |ack: Option<String>| {
+        let now = chrono::Utc::now().timestamp_millis();
+        let raw = fixtures::raw_batch(
+            SERVER_ID,
+            SESSION_ID,
+            &fixtures::walk(now, Uuid::new_v4(), 2, 0.2),
+        );
+        let mut req = stack
+            .http
+            .post(stack.url("/ingest"))
+            .bearer_auth(PLUGIN_TOKEN)
+            .header("content-type", "application/x-ndjson")
+            .header("content-encoding", "gzip")
+            .header("x-server-id", SERVER_ID)
+            .header("x-session-id", SESSION_ID)
+            .body(raw);
+        if let Some(ack) = ack {
+            req = req.header("x-ack-actions", ack);
+        }
+        async move {
+            let resp = req.send().await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            resp.json::<Value>().await.unwrap()
+        }
+    };
+
+    // Linking the server also queued its exemptions; the plugin only applies the watchlist.
+    let kinds = |body: &Value| -> Vec<(String, String)> {
+        body["pending_actions"]
+            .as_array()
+            .map(|actions| {
+                actions
+                    .iter()
+                    .map(|a| {
+                        let field = |k: &str| a[k].as_str().unwrap().to_string();
+                        (field("action"), field("id"))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let first = upload(None).await;
+    let sync = first["pending_actions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["action"] == watchlist::SYNC_ACTION)
+        .expect("watchlist sync sent");
+    assert_eq!(sync["payload"]["players"][0]["uuid"], watched.to_string());
+    let id = sync["id"].as_str().unwrap().to_string();
+
+    // Not acknowledged: sent again.
+    let second = upload(None).await;
+    assert_eq!(kinds(&second), kinds(&first));
+
+    // Acknowledged (unknown ids change nothing); the other action is still pending.
+    let third = upload(Some(format!("{},{}", Uuid::new_v4(), id))).await;
+    let remaining = kinds(&third);
+    assert_eq!(remaining.len(), kinds(&first).len() - 1);
+    assert!(remaining.iter().all(|(_, other)| *other != id));
+    let delivered: Option<chrono::DateTime<chrono::Utc>> = sqlx::query_scalar(
+        "select delivered_at from public.server_pending_actions where id = $1::uuid",
+    )
+    .bind(&id)
+    .fetch_one(&stack.db)
+    .await
+    .unwrap();
+    assert!(delivered.is_some());
+}
+
+#[tokio::test]
+async fn observations_link_findings_in_their_window_and_relink_when_ended() {
+    let stack = Stack::start().await;
+    // The plugin's first request binds its token; then the server is linked.
+    let resp = stack
+        .http
+        .post(stack.url("/handshake"))
+        .bearer_auth(PLUGIN_TOKEN)
+        .header("x-server-id", SERVER_ID)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    stack.register_server().await;
+
+    let player = Uuid::new_v4();
+    let now = chrono::Utc::now();
+    let minutes_ago = |m: i64| now - chrono::Duration::minutes(m);
+    let mut findings = std::collections::HashMap::new();
+    for (detector, first, last) in [("early", 60, 50), ("mid", 30, 25), ("late", 5, 4)] {
+        let id = stack
+            .insert_finding(SERVER_ID, player, detector, "high", "open", 1, None)
+            .await;
+        sqlx::query(
+            "update public.findings set first_seen_at = $2, last_seen_at = $3 where id = $1",
+        )
+        .bind(id)
+        .bind(minutes_ago(first))
+        .bind(minutes_ago(last))
+        .execute(&stack.db)
+        .await
+        .unwrap();
+        findings.insert(detector, id);
+    }
+
+    let plugin = |method: reqwest::Method, path: String, body: Value| {
+        stack
+            .http
+            .request(method, stack.url(&path))
+            .bearer_auth(PLUGIN_TOKEN)
+            .header("x-server-id", SERVER_ID)
+            .json(&body)
+            .send()
+    };
+    let linked = |detail: Value| -> Vec<String> {
+        let mut names: Vec<String> = detail["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["detector_name"].as_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+
+    // An open recording collects everything from its start on.
+    let resp = plugin(
+        reqwest::Method::POST,
+        "/observations".to_string(),
+        json!({
+            "observation_type": "recording",
+            "player_uuid": player,
+            "cheat_type": "speed",
+            "started_at": minutes_ago(40),
+        }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let id = resp.json::<Value>().await.unwrap()["observation_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let detail_path = format!("/dashboard/{}/observations/{}", SERVER_ID, id);
+    assert_eq!(
+        linked(stack.dashboard_get(&detail_path).await),
+        ["late", "mid"]
+    );
+
+    // Ending it before the late finding unlinks that finding; moving the end later relinks it.
+    let end_path = format!("/observations/{}", id);
+    let resp = plugin(
+        reqwest::Method::PATCH,
+        end_path.clone(),
+        json!({ "ended_at": minutes_ago(20) }),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let detail = stack.dashboard_get(&detail_path).await;
+    assert_eq!(linked(detail.clone()), ["mid"]);
+    assert_eq!(detail["observation"]["linked_findings"], 1);
+    let resp = plugin(reqwest::Method::PATCH, end_path, json!({}))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        linked(stack.dashboard_get(&detail_path).await),
+        ["late", "mid"]
+    );
+
+    // Listing filters by player, type and status.
+    let list = |query: String| {
+        let path = format!("/dashboard/{}/observations?{}", SERVER_ID, query);
+        let stack = &stack;
+        async move {
+            stack.dashboard_get(&path).await["observations"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|o| o["id"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(
+        list(format!("player_uuid={}", player)).await,
+        vec![id.as_str()]
+    );
+    assert_eq!(list("status=new".to_string()).await, vec![id.as_str()]);
+    assert!(list("observation_type=false_positive".to_string())
+        .await
+        .is_empty());
+    assert!(list(format!("player_uuid={}", Uuid::new_v4()))
+        .await
+        .is_empty());
+
+    // Confirming (with an earlier end) relinks, then labels only the linked open findings.
+    let review = |body: Value| {
+        stack
+            .http
+            .patch(stack.url(&detail_path))
+            .bearer_auth(DASHBOARD_TOKEN)
+            .json(&body)
+            .send()
+    };
+    let resp = review(json!({ "status": "bogus" })).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = review(json!({
+        "status": "confirmed",
+        "ended_at": minutes_ago(20),
+        "admin_notes": "clear speed",
+    }))
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let detail = stack.dashboard_get(&detail_path).await;
+    assert_eq!(linked(detail.clone()), ["mid"]);
+    assert_eq!(detail["observation"]["status"], "confirmed");
+    assert_eq!(detail["observation"]["admin_notes"], "clear speed");
+    assert!(detail["observation"]["reviewed_at"].is_string());
+    assert_eq!(
+        list("status=confirmed".to_string()).await,
+        vec![id.as_str()]
+    );
+    for (detector, status) in [("early", "open"), ("mid", "confirmed"), ("late", "open")] {
+        let current: String =
+            sqlx::query_scalar("select status from public.findings where id = $1")
+                .bind(findings[detector])
+                .fetch_one(&stack.db)
+                .await
+                .unwrap();
+        assert_eq!(current, status, "{}", detector);
+    }
+
+    // Other servers' and unknown observations are not found.
+    let missing = Uuid::new_v4();
+    let resp = plugin(
+        reqwest::Method::PATCH,
+        format!("/observations/{}", missing),
+        json!({}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = stack
+        .http
+        .get(stack.url(&format!(
+            "/dashboard/{}/observations/{}",
+            SERVER_ID, missing
+        )))
+        .bearer_auth(DASHBOARD_TOKEN)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn cursor_pages_split_ties_without_gaps_or_repeats() {
+    let stack = Stack::start().await;
+    sqlx::query("insert into public.servers (id) values ($1)")
+        .bind(SERVER_ID)
+        .execute(&stack.db)
+        .await
+        .unwrap();
+    // Five players; three share the top occurrence count. All findings share one created_at, so
+    // only the id orders them.
+    let players: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+    for (i, player) in players.iter().enumerate() {
+        sqlx::query("insert into public.players (uuid, username) values ($1, $2)")
+            .bind(player)
+            .bind(format!("player{}", i))
+            .execute(&stack.db)
+            .await
+            .unwrap();
+        sqlx::query(
+            "insert into public.findings \
+             (server_id, player_uuid, detector_name, severity, title, occurrences, created_at) \
+             values ($1, $2, 'speed', 'low', 'Speed', $3, '2024-03-01T12:00:00Z')",
+        )
+        .bind(SERVER_ID)
+        .bind(player)
+        .bind(if i < 3 { 5 } else { 1 })
+        .execute(&stack.db)
+        .await
+        .unwrap();
+    }
+
+    let ids = |pages: &[Vec<Value>], field: &str| -> Vec<String> {
+        pages
+            .iter()
+            .flatten()
+            .map(|item| item[field].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let pages = stack
+        .walk_pages(
+            &format!("/dashboard/{}/findings?limit=2", SERVER_ID),
+            "findings",
+        )
+        .await;
+    assert_eq!(
+        pages.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+    let findings = ids(&pages, "id");
+    let mut sorted = findings.clone();
+    sorted.sort_by(|a, b| b.cmp(a));
+    sorted.dedup();
+    assert_eq!(findings, sorted, "newest first by id, no repeats");
+
+    let pages = stack
+        .walk_pages(
+            &format!("/dashboard/{}/players?limit=2", SERVER_ID),
+            "players",
+        )
+        .await;
+    assert_eq!(
+        pages.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+    let counts: Vec<i64> = pages
+        .iter()
+        .flatten()
+        .map(|p| p["findings_count"].as_i64().unwrap())
+        .collect();
+    assert_eq!(counts, vec![5, 5, 5, 1, 1], "most occurrences first");
+    let mut seen = ids(&pages, "uuid");
+    seen.sort();
+    let mut expected: Vec<String> = players.iter().map(Uuid::to_string).collect();
+    expected.sort();
+    assert_eq!(seen, expected);
+}
+
+#[tokio::test]
+async fn reputation_ignores_imported_findings() {
+    let stack = Stack::start().await;
+    sqlx::query("insert into public.servers (id, reputation_opt_in) values ($1, true)")
+        .bind(SERVER_ID)
+        .execute(&stack.db)
+        .await
+        .unwrap();
+    let imported_only = Uuid::new_v4();
+    let mixed = Uuid::new_v4();
+    for source in ["ncp", "grim", "vulcan"] {
+        stack
+            .insert_finding(
+                SERVER_ID,
+                imported_only,
+                source,
+                "critical",
+                "open",
+                50,
+                Some(source),
+            )
+            .await;
+        stack
+            .insert_finding(
+                SERVER_ID,
+                mixed,
+                source,
+                "critical",
+                "open",
+                50,
+                Some(source),
+            )
+            .await;
+    }
+    stack
+        .insert_finding(SERVER_ID, mixed, "speed", "low", "open", 1, None)
+        .await;
+
+    reputation::aggregate_tick(stack.app_state()).await;
+
+    assert_eq!(stack.reputation(imported_only).await, None);
+    let (score, findings) = stack.reputation(mixed).await.unwrap();
+    assert_eq!(findings, 1);
+    assert!(
+        (score - reputation::score(0.5 * 2f64.ln())).abs() < 1e-9,
+        "{}",
+        score
+    );
+}
+
+#[tokio::test]
+async fn reputation_weighs_opted_in_findings_by_severity_and_review() {
+    let stack = Stack::start().await;
+    sqlx::query(
+        "insert into public.servers (id, reputation_opt_in) values ($1, true), ('private', false)",
+    )
+    .bind(SERVER_ID)
+    .execute(&stack.db)
+    .await
+    .unwrap();
+    let flagged = Uuid::new_v4();
+    let private_only = Uuid::new_v4();
+    let blatant = Uuid::new_v4();
+
+    // One occurrence weighs ln 2; confirmed findings triple their severity weight.
+    stack
+        .insert_finding(SERVER_ID, flagged, "speed", "high", "open", 1, None)
+        .await;
+    stack
+        .insert_finding(SERVER_ID, flagged, "reach", "medium", "confirmed", 1, None)
+        .await;
+    stack
+        .insert_finding(
+            SERVER_ID,
+            flagged,
+            "fly",
+            "critical",
+            "false_positive",
+            9,
+            None,
+        )
+        .await;
+    stack
+        .insert_finding(
+            SERVER_ID,
+            flagged,
+            "timer",
+            "critical",
+            "dismissed",
+            9,
+            None,
+        )
+        .await;
+    // Servers that did not opt in contribute nothing.
+    stack
+        .insert_finding("private", flagged, "speed", "critical", "open", 99, None)
+        .await;
+    stack
+        .insert_finding(
+            "private",
+            private_only,
+            "speed",
+            "critical",
+            "open",
+            99,
+            None,
+        )
+        .await;
+    for detector in ["speed", "reach", "fly", "timer", "killaura"] {
+        stack
+            .insert_finding(
+                SERVER_ID,
+                blatant,
+                detector,
+                "critical",
+                "confirmed",
+                10_000,
+                None,
+            )
+            .await;
+    }
+
+    reputation::aggregate_tick(stack.app_state()).await;
+
+    let (score, findings) = stack.reputation(flagged).await.unwrap();
+    assert_eq!(findings, 2);
+    let weight = (3.0 + 1.5 * 3.0) * 2f64.ln();
+    assert!(
+        (score - reputation::score(weight)).abs() < 1e-9,
+        "{}",
+        score
+    );
+    let (servers_flagged, confirmed): (i32, i32) = sqlx::query_as(
+        "select servers_flagged, confirmed_findings from public.player_reputation \
+         where player_uuid = $1",
+    )
+    .bind(flagged)
+    .fetch_one(&stack.db)
+    .await
+    .unwrap();
+    assert_eq!((servers_flagged, confirmed), (1, 1));
+
+    assert_eq!(stack.reputation(private_only).await, None);
+
+    // 5 * 5 * 3 * ln 10001 ≈ 691: saturated, but never past 100.
+    let (score, _) = stack.reputation(blatant).await.unwrap();
+    assert!(score > 99.9 && score <= 100.0, "{}", score);
+}
+
+#[tokio::test]
+async fn detector_quality_rolls_up_reviews_per_detector() {
+    let stack = Stack::start().await;
+    sqlx::query("insert into public.servers (id) values ($1)")
+        .bind(SERVER_ID)
+        .execute(&stack.db)
+        .await
+        .unwrap();
+    let mut speed = Vec::new();
+    for _ in 0..3 {
+        speed.push(
+            stack
+                .insert_finding(SERVER_ID, Uuid::new_v4(), "speed", "high", "open", 2, None)
+                .await,
+        );
+    }
+    let reach = stack
+        .insert_finding(SERVER_ID, Uuid::new_v4(), "reach", "high", "open", 1, None)
+        .await;
+    let fly = stack
+        .insert_finding(SERVER_ID, Uuid::new_v4(), "fly", "high", "open", 1, None)
+        .await;
+
+    let review = |finding: Uuid, status: &'static str| {
+        let path = format!("/dashboard/{}/findings/{}/status", SERVER_ID, finding);
+        let stack = &stack;
+        async move {
+            stack
+                .dashboard_post(&path, json!({ "status": status }))
+                .await
+        }
+    };
+    review(speed[0], "confirmed").await;
+    review(speed[1], "false_positive").await;
+    // A changed verdict moves the count.
+    review(speed[2], "confirmed").await;
+    let changed = review(speed[2], "false_positive").await;
+    assert_eq!(changed["previous_status"], "confirmed");
+    // Reopening takes it back.
+    review(reach, "dismissed").await;
+    review(reach, "open").await;
+    // Counters outlive the findings they came from.
+    review(fly, "confirmed").await;
+    sqlx::query("delete from public.findings where id = $1")
+        .bind(fly)
+        .execute(&stack.db)
+        .await
+        .unwrap();
+
+    let quality = stack
+        .dashboard_get(&format!("/dashboard/{}/detectors/quality", SERVER_ID))
+        .await;
+    let detectors = quality["detectors"].as_array().unwrap();
+    let names: Vec<&str> = detectors
+        .iter()
+        .map(|d| d["detector_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names[0], "speed", "noisiest first: {:?}", names);
+    let detector = |name: &str| {
+        detectors
+            .iter()
+            .find(|d| d["detector_name"] == name)
+            .unwrap()
+            .clone()
+    };
+
+    let speed = detector("speed");
+    assert_eq!(speed["findings"], 3);
+    assert_eq!(speed["occurrences"], 6);
+    assert_eq!(
+        (
+            &speed["confirmed"],
+            &speed["false_positives"],
+            &speed["dismissed"]
+        ),
+        (&json!(1), &json!(2), &json!(0))
+    );
+    assert!((speed["precision"].as_f64().unwrap() - 1.0 / 3.0).abs() < 1e-9);
+    assert!(speed["last_false_positive_at"].is_string());
+
+    let reach = detector("reach");
+    assert_eq!(
+        (
+            &reach["confirmed"],
+            &reach["false_positives"],
+            &reach["dismissed"]
+        ),
+        (&json!(0), &json!(0), &json!(0))
+    );
+    assert!(reach["precision"].is_null());
+
+    let fly = detector("fly");
+    assert_eq!(fly["findings"], 0);
+    assert_eq!(fly["confirmed"], 1);
+    assert_eq!(fly["precision"], 1.0);
+}
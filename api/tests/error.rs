@@ -0,0 +1,69 @@
+use async_anticheat_api::error::{ApiError, ErrorBody};
+use axum::body::HttpBody;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+async fn respond(err: ApiError) -> (StatusCode, Option<String>, serde_json::Value) {
+    let resp = err.into_response();
+    let status = resp.status();
+    let retry_after = resp
+        .headers()
+        .get("retry-after")
+        .map(|v| v.to_str().unwrap().to_string());
+    let mut body = resp.into_body();
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        bytes.extend_from_slice(&chunk.unwrap());
+    }
+    (status, retry_after, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn errors_carry_status_code_and_details() {
+    let (status, _, body) = respond(ApiError::NotFound("server s1 not found".into())).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "not_found");
+    assert_eq!(body["error"], "server s1 not found");
+    assert!(body.get("details").is_none());
+
+    let (status, _, body) = respond(ApiError::PayloadTooLarge {
+        size: 2048,
+        max: 1024,
+    })
+    .await;
+    assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(body["code"], "payload_too_large");
+    assert_eq!(body["details"]["max_bytes"], 1024);
+
+    let (status, retry_after, body) = respond(ApiError::RateLimited {
+        retry_after_seconds: Some(30),
+    })
+    .await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(retry_after.as_deref(), Some("30"));
+    assert_eq!(body["details"]["retry_after_seconds"], 30);
+
+    let (status, _, body) = respond(ApiError::UpstreamModuleError {
+        module: "combat".into(),
+        message: "HTTP 500".into(),
+    })
+    .await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert_eq!(body["code"], "upstream_module_error");
+    assert_eq!(body["details"]["module"], "combat");
+
+    let (status, _, body) = respond(ApiError::Gone("server s1 was deleted".into())).await;
+    assert_eq!(status, StatusCode::GONE);
+    assert_eq!(body["code"], "gone");
+
+    let (status, _, body) = respond(ApiError::Internal).await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body["code"], "internal");
+}
+
+#[test]
+fn error_body_without_code_still_parses() {
+    let body: ErrorBody = serde_json::from_str(r#"{"error": "unauthorized"}"#).unwrap();
+    assert_eq!(body.code, "");
+    assert!(body.details.is_none());
+}
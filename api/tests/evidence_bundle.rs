@@ -0,0 +1,99 @@
+use async_anticheat_api::evidence_bundle::{excerpt, module_for_detector};
+use async_anticheat_api::s3::ObjectStore;
+use flate2::{write::GzEncoder, Compression};
+use serde_json::json;
+use std::io::Write;
+use uuid::Uuid;
+
+fn gz(lines: &[serde_json::Value]) -> Vec<u8> {
+    let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+    for line in lines {
+        writeln!(enc, "{}", line).unwrap();
+    }
+    enc.finish().unwrap()
+}
+
+fn modules() -> Vec<(String, String)> {
+    vec![
+        (
+            "Movement Core".to_string(),
+            "movement_events_v1".to_string(),
+        ),
+        ("combat".to_string(), "combat_events_v1".to_string()),
+    ]
+}
+
+#[test]
+fn detector_maps_to_module_by_name_prefix() {
+    let modules = modules();
+    assert_eq!(
+        module_for_detector(&modules, "movement_core_flight_a").map(|m| m.1.as_str()),
+        Some("movement_events_v1")
+    );
+    assert_eq!(
+        module_for_detector(&modules, "Combat_Reach").map(|m| m.1.as_str()),
+        Some("combat_events_v1")
+    );
+    assert!(module_for_detector(&modules, "combatant_x").is_none());
+    assert!(module_for_detector(&modules, "combat").is_none());
+}
+
+#[test]
+fn excerpt_keeps_the_players_records_in_the_window() {
+    let player = Uuid::new_v4();
+    let other = Uuid::new_v4();
+    let batch = gz(&[
+        json!({"uuid": player.to_string(), "ts": 900, "pkt": "a"}),
+        json!({"uuid": player.to_string(), "ts": 1000, "pkt": "b"}),
+        json!({"uuid": other.to_string(), "ts": 1500, "pkt": "c"}),
+        json!({"uuid": player.to_string(), "pkt": "no ts"}),
+        json!({"uuid": player.to_string(), "ts": 2000, "pkt": "d"}),
+        json!({"uuid": player.to_string(), "ts": 2001, "pkt": "e"}),
+    ]);
+
+    let (events, truncated) = excerpt(&batch, player, 1000, 2000, 10).unwrap();
+    assert!(!truncated);
+    let pkts: Vec<_> = events.iter().map(|e| e["pkt"].as_str().unwrap()).collect();
+    assert_eq!(pkts, vec!["b", "d"]);
+}
+
+#[test]
+fn excerpt_reports_truncation() {
+    let player = Uuid::new_v4();
+    let records: Vec<_> = (0..5)
+        .map(|i| json!({"uuid": player.to_string(), "ts": i}))
+        .collect();
+    let batch = gz(&records);
+
+    let (events, truncated) = excerpt(&batch, player, 0, 10, 3).unwrap();
+    assert_eq!(events.len(), 3);
+    assert!(truncated);
+
+    let (events, truncated) = excerpt(&batch, player, 0, 10, 5).unwrap();
+    assert_eq!(events.len(), 5);
+    assert!(!truncated);
+}
+
+#[test]
+fn excerpt_skips_malformed_lines() {
+    let player = Uuid::new_v4();
+    let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+    writeln!(enc, "not json").unwrap();
+    writeln!(enc).unwrap();
+    writeln!(enc, "{}", json!({"uuid": player.to_string(), "ts": 5})).unwrap();
+    let batch = enc.finish().unwrap();
+
+    let (events, _) = excerpt(&batch, player, 0, 10, 10).unwrap();
+    assert_eq!(events.len(), 1);
+}
+
+#[test]
+fn bundle_keys_live_under_bundles() {
+    let finding = Uuid::new_v4();
+    let bundle = Uuid::new_v4();
+    assert_eq!(
+        ObjectStore::bundle_key("srv-1", &finding, &bundle),
+        Some(format!("bundles/srv-1/{}/{}.json.gz", finding, bundle))
+    );
+    assert!(ObjectStore::bundle_key("../", &finding, &bundle).is_none());
+}
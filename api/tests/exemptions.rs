@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use async_anticheat_api::exemptions::{strip_exempt, ActiveExemptions, Exemption, PlayerFacts};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use uuid::Uuid;
+
+fn gzip(s: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut enc = GzEncoder::new(&mut out, Compression::default());
+    std::io::Write::write_all(&mut enc, s.as_bytes()).unwrap();
+    enc.finish().unwrap();
+    out
+}
+
+fn gunzip(bytes: &[u8]) -> String {
+    let mut s = String::new();
+    GzDecoder::new(bytes).read_to_string(&mut s).unwrap();
+    s
+}
+
+const P1: &str = "00000000-0000-0000-0000-000000000001";
+const P2: &str = "00000000-0000-0000-0000-000000000002";
+
+#[test]
+fn every_selector_must_match() {
+    let p1 = Uuid::parse_str(P1).unwrap();
+    let rules = ActiveExemptions {
+        rules: vec![Exemption {
+            player_uuid: None,
+            permission: Some("aac.exempt".into()),
+            world: Some("creative".into()),
+        }],
+    };
+    let staff_in_creative = PlayerFacts {
+        world: Some("creative".into()),
+        permissions: vec!["aac.exempt".into()],
+    };
+    let staff_in_survival = PlayerFacts {
+        world: Some("survival".into()),
+        ..staff_in_creative.clone()
+    };
+    assert!(rules.is_exempt(p1, &staff_in_creative));
+    assert!(!rules.is_exempt(p1, &staff_in_survival));
+    assert!(!rules.is_exempt(p1, &PlayerFacts::default()));
+}
+
+#[test]
+fn strip_follows_world_changes_within_batch() {
+    let raw = format!(
+        r#"{{"server_id":"s","session_id":"x"}}
+{{"ts":1,"pkt":"PLAYER_POSITION","uuid":"{P1}","fields":{{}}}}
+{{"ts":2,"pkt":"PLAYER_STATE","uuid":"{P1}","fields":{{"world":"creative"}}}}
+{{"ts":3,"pkt":"PLAYER_POSITION","uuid":"{P1}","fields":{{}}}}
+{{"ts":4,"pkt":"PLAYER_POSITION","uuid":"{P2}","fields":{{}}}}
+"#
+    );
+    let rules = ActiveExemptions {
+        rules: vec![Exemption {
+            player_uuid: None,
+            permission: None,
+            world: Some("creative".into()),
+        }],
+    };
+
+    let (out, removed) = strip_exempt(&gzip(&raw), &rules, &HashMap::new()).unwrap();
+    assert_eq!(removed, 2);
+    let text = gunzip(&out);
+    let ts: Vec<&str> = text.lines().collect();
+    assert_eq!(ts.len(), 3);
+    assert!(ts[1].contains(r#""ts":1"#));
+    assert!(ts[2].contains(P2));
+}
@@ -0,0 +1,42 @@
+use async_anticheat_api::finding_latency::{summarize, LatencyDay};
+use chrono::NaiveDate;
+
+fn day(d: u32, detector: &str, findings: i64, within_slo: i64, p99: Option<f64>) -> LatencyDay {
+    LatencyDay {
+        day: NaiveDate::from_ymd_opt(2024, 3, d).unwrap(),
+        detector_name: detector.to_string(),
+        findings,
+        p50_ms: p99.map(|p| p / 10.0),
+        p90_ms: p99.map(|p| p / 2.0),
+        p99_ms: p99,
+        max_ms: p99.map(|p| p as i32 + 1),
+        within_slo,
+        slo_ms: 30_000,
+    }
+}
+
+#[test]
+fn summary_adds_up_days() {
+    let days = vec![
+        day(1, "combat_reach", 100, 99, Some(12_000.0)),
+        day(2, "combat_reach", 50, 40, Some(45_000.0)),
+        day(2, "movement_speed", 10, 10, Some(800.0)),
+    ];
+    let s = summarize(&days);
+    assert_eq!(s.findings, 160);
+    assert_eq!(s.within_slo, 149);
+    assert!((s.attainment.unwrap() - 149.0 / 160.0).abs() < 1e-9);
+    assert_eq!(s.worst_p99_ms, Some(45_000.0));
+    assert_eq!(s.max_ms, Some(45_001));
+
+    let reach = summarize(days.iter().filter(|d| d.detector_name == "combat_reach"));
+    assert_eq!(reach.findings, 150);
+}
+
+#[test]
+fn summary_without_findings_has_no_attainment() {
+    let s = summarize(&[]);
+    assert_eq!(s.findings, 0);
+    assert_eq!(s.attainment, None);
+    assert_eq!(s.worst_p99_ms, None);
+}
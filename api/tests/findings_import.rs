@@ -0,0 +1,155 @@
+use async_anticheat_api::findings_import::{
+    aggregate, detector_slug, parse_log, parser_for, severity_for_vl, split_timestamp,
+    strip_formatting, LogViolation, TimeContext,
+};
+use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+
+fn violation(format: &str, message: &str) -> Option<LogViolation> {
+    parser_for(format).unwrap().parse_violation(message)
+}
+
+#[test]
+fn parsers_are_looked_up_by_source() {
+    assert_eq!(parser_for("grim").unwrap().source(), "grim");
+    assert_eq!(parser_for(" Vulcan ").unwrap().source(), "vulcan");
+    assert_eq!(parser_for("NoCheatPlus").unwrap().source(), "ncp");
+    assert!(parser_for("matrix").is_none());
+}
+
+#[test]
+fn ncp_lines_are_parsed() {
+    let v = violation(
+        "ncp",
+        "[NoCheatPlus] Steve failed SurvivalFly: moved too quickly. VL 12.3.",
+    )
+    .unwrap();
+    assert_eq!(v.player_name, "Steve");
+    assert_eq!(v.check, "SurvivalFly");
+    assert_eq!(v.vl, Some(12.3));
+    assert_eq!(v.details.as_deref(), Some("moved too quickly"));
+
+    assert!(violation("ncp", "Steve joined the game").is_none());
+}
+
+#[test]
+fn grim_lines_are_parsed() {
+    let v = violation("grim", "Grim » Alex_01 failed Reach (x5) 3.12 blocks").unwrap();
+    assert_eq!(v.player_name, "Alex_01");
+    assert_eq!(v.check, "Reach");
+    assert_eq!(v.vl, Some(5.0));
+    assert_eq!(v.details.as_deref(), Some("3.12 blocks"));
+
+    let v = violation("grim", "Alex_01 failed Timer").unwrap();
+    assert_eq!(v.vl, None);
+    assert_eq!(v.details, None);
+}
+
+#[test]
+fn vulcan_lines_are_parsed() {
+    let v = violation(
+        "vulcan",
+        "[Vulcan] Steve failed Speed (Type A) [VL: 7] offset=0.21",
+    )
+    .unwrap();
+    assert_eq!(v.check, "Speed A");
+    assert_eq!(v.vl, Some(7.0));
+    assert_eq!(v.details.as_deref(), Some("offset=0.21"));
+
+    let v = violation("vulcan", "Steve failed Killaura (Type B) (x3)").unwrap();
+    assert_eq!(v.check, "Killaura B");
+    assert_eq!(v.vl, Some(3.0));
+}
+
+#[test]
+fn colour_codes_are_stripped() {
+    assert_eq!(
+        strip_formatting("§8[§cVulcan§8] &fSteve &7failed"),
+        "[Vulcan] Steve failed"
+    );
+    assert_eq!(strip_formatting("Tom & Jerry"), "Tom & Jerry");
+}
+
+#[test]
+fn leading_timestamps_are_split_off() {
+    let ctx = TimeContext::default();
+    let (at, rest) = split_timestamp("2024-03-01 12:03:44 Steve failed Reach", &ctx);
+    assert_eq!(
+        at,
+        Some(Utc.with_ymd_and_hms(2024, 3, 1, 12, 3, 44).unwrap())
+    );
+    assert_eq!(rest, "Steve failed Reach");
+
+    let (at, rest) = split_timestamp("[01/03/24 12:03:44] Steve failed Reach", &ctx);
+    assert_eq!(
+        at,
+        Some(Utc.with_ymd_and_hms(2024, 3, 1, 12, 3, 44).unwrap())
+    );
+    assert_eq!(rest, "Steve failed Reach");
+
+    // Time-only stamps need the day from the request.
+    let (at, rest) = split_timestamp("[12:03:44 INFO]: Steve failed Reach", &ctx);
+    assert_eq!(at, None);
+    assert_eq!(rest, "[12:03:44 INFO]: Steve failed Reach");
+
+    let dated = TimeContext {
+        date: NaiveDate::from_ymd_opt(2024, 3, 1),
+        offset: FixedOffset::east_opt(3600).unwrap(),
+    };
+    let (at, rest) = split_timestamp("[12:03:44 INFO]: Steve failed Reach", &dated);
+    assert_eq!(
+        at,
+        Some(Utc.with_ymd_and_hms(2024, 3, 1, 11, 3, 44).unwrap())
+    );
+    assert_eq!(rest, "Steve failed Reach");
+}
+
+#[test]
+fn logs_are_bucketed_per_player_check_and_minute() {
+    let log = "\
+2024-03-01 12:03:01 Steve failed Reach (x2) 3.05 blocks
+2024-03-01 12:03:40 steve failed Reach (x21) 3.40 blocks
+
+2024-03-01 12:04:02 Steve failed Reach (x22)
+2024-03-01 12:04:05 Alex failed BadPacketsA (x1)
+Steve failed Reach (x23)
+2024-03-01 12:04:09 [Server] Alex left the game
+";
+    let parser = parser_for("grim").unwrap();
+    let parsed = parse_log(parser, log, &TimeContext::default());
+    assert_eq!(parsed.lines, 6);
+    assert_eq!(parsed.violations.len(), 4);
+    assert_eq!(parsed.undated_lines, 1);
+
+    let findings = aggregate(parser, &parsed.violations);
+    assert_eq!(findings.len(), 3);
+    let first = findings
+        .iter()
+        .find(|f| {
+            f.detector_name == "grim_reach"
+                && f.window_start_at == Utc.with_ymd_and_hms(2024, 3, 1, 12, 3, 0).unwrap()
+        })
+        .unwrap();
+    assert_eq!(first.occurrences, 2);
+    assert_eq!(first.max_vl, Some(21.0));
+    assert_eq!(first.severity, "high");
+    assert_eq!(first.title, "Reach (imported from Grim)");
+    assert_eq!(first.details.as_deref(), Some("3.40 blocks"));
+    assert_eq!(
+        first.last_seen_at,
+        Utc.with_ymd_and_hms(2024, 3, 1, 12, 3, 40).unwrap()
+    );
+    assert!(findings
+        .iter()
+        .any(|f| f.detector_name == "grim_bad_packets_a" && f.severity == "low"));
+}
+
+#[test]
+fn detector_slugs_and_severities() {
+    assert_eq!(detector_slug("SurvivalFly"), "survival_fly");
+    assert_eq!(detector_slug("Speed A"), "speed_a");
+    assert_eq!(detector_slug("MOVING_SURVIVALFLY"), "moving_survivalfly");
+    assert_eq!(detector_slug("AimModulo360"), "aim_modulo360");
+    assert_eq!(severity_for_vl(None), "low");
+    assert_eq!(severity_for_vl(Some(5.0)), "medium");
+    assert_eq!(severity_for_vl(Some(20.0)), "high");
+}
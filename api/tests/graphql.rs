@@ -0,0 +1,33 @@
+use async_anticheat_api::graphql::{self, KeysetCursor};
+use async_graphql::connection::CursorType;
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+#[test]
+fn keyset_cursor_round_trips() {
+    let cursor = KeysetCursor {
+        at: Utc.timestamp_micros(1_700_000_000_123_456).unwrap(),
+        id: Uuid::new_v4(),
+    };
+    let encoded = cursor.encode_cursor();
+    assert_eq!(KeysetCursor::decode_cursor(&encoded).unwrap(), cursor);
+
+    assert!(KeysetCursor::decode_cursor("garbage").is_err());
+    assert!(KeysetCursor::decode_cursor("123:not-a-uuid").is_err());
+}
+
+#[test]
+fn schema_exposes_dashboard_types() {
+    let sdl = graphql::schema().sdl();
+    for ty in [
+        "type Server",
+        "type Player",
+        "type Finding",
+        "type Module",
+        "type Observation",
+        "type FindingConnection",
+    ] {
+        assert!(sdl.contains(ty), "{} missing from schema", ty);
+    }
+    assert!(sdl.contains("findings(first: Int, after: String"));
+}
@@ -0,0 +1,32 @@
+use async_anticheat_api::routes::heartbeat::HeartbeatRequest;
+
+#[test]
+fn normalized_drops_impossible_values() {
+    let req: HeartbeatRequest = serde_json::from_value(serde_json::json!({
+        "tps": 19.5,
+        "mspt": -1.0,
+        "online_players": -3,
+        "plugin_version": "  1.4.2 ",
+        "mc_version": "x".repeat(65),
+    }))
+    .unwrap();
+    let req = req.normalized();
+
+    assert_eq!(req.tps, Some(19.5));
+    assert_eq!(req.mspt, None);
+    assert_eq!(req.online_players, None);
+    assert_eq!(req.plugin_version.as_deref(), Some("1.4.2"));
+    assert_eq!(req.mc_version, None);
+}
+
+#[test]
+fn empty_body_records_nothing() {
+    let req: HeartbeatRequest = serde_json::from_str("{}").unwrap();
+    assert!(req.normalized().is_empty());
+
+    let req = HeartbeatRequest {
+        plugin_version: Some(" ".to_string()),
+        ..Default::default()
+    };
+    assert!(req.normalized().is_empty());
+}
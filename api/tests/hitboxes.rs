@@ -0,0 +1,94 @@
+use async_anticheat_api::hitboxes::{for_type, normalize_type, reach, PLAYER};
+use async_anticheat_api::transform_events::FightEvent;
+use async_anticheat_api::transforms::apply_transform;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::io::Read;
+
+fn gzip(s: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut enc = GzEncoder::new(&mut out, Compression::default());
+    std::io::Write::write_all(&mut enc, s.as_bytes()).unwrap();
+    enc.finish().unwrap();
+    out
+}
+
+fn gunzip(b: &[u8]) -> String {
+    let mut s = String::new();
+    GzDecoder::new(b).read_to_string(&mut s).unwrap();
+    s
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn entity_type_names_are_normalized() {
+    assert_eq!(
+        normalize_type("minecraft:zombie").as_deref(),
+        Some("zombie")
+    );
+    assert_eq!(
+        normalize_type("PIG_ZOMBIE").as_deref(),
+        Some("zombified_piglin")
+    );
+    assert_eq!(normalize_type("Iron Golem").as_deref(), Some("iron_golem"));
+    assert_eq!(normalize_type(" "), None);
+
+    assert_eq!(for_type(Some("iron_golem")).width, 1.4);
+    assert_eq!(for_type(Some("slime")), PLAYER);
+    assert_eq!(for_type(None), PLAYER);
+}
+
+#[test]
+fn reach_is_measured_to_the_nearest_point_of_the_box() {
+    let eye = [0.0, 65.62, 0.0];
+
+    // Target 3 blocks east: the box face is 0.3 closer, its center at eye height-ish.
+    let (center, hitbox) = reach(eye, [3.0, 64.0, 0.0], PLAYER, 0.0);
+    assert!(close(hitbox, 2.7));
+    assert!(close(center, (9.0f64 + 0.72 * 0.72).sqrt()));
+
+    // 1.8 clients' boxes are 0.1 larger.
+    let (_, legacy) = reach(eye, [3.0, 64.0, 0.0], PLAYER, 0.1);
+    assert!(close(legacy, 2.6));
+
+    // Inside the box.
+    assert_eq!(reach(eye, [0.0, 64.0, 0.0], PLAYER, 0.0).1, 0.0);
+}
+
+#[test]
+fn ncp_fight_v1_emits_hitbox_reach_per_target_type() {
+    let raw = r#"
+{"server_id":"s","session_id":"x","client_protocols":{"00000000-0000-0000-0000-000000000002":47}}
+{"ts":900,"dir":"clientbound","pkt":"SPAWN_LIVING_ENTITY","fields":{"entity_id":7,"entity_type":{"namespace":"minecraft","key":"iron_golem"},"x":4.0,"y":64.0,"z":0.0}}
+{"ts":900,"dir":"clientbound","pkt":"SPAWN_ENTITY","fields":{"entity_id":8,"x":4.0,"y":64.0,"z":0.0}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION_AND_ROTATION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":64.0,"z":0.0,"yaw":-90.0,"pitch":0.0,"on_ground":true}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION_AND_ROTATION","uuid":"00000000-0000-0000-0000-000000000002","fields":{"x":0.0,"y":64.0,"z":0.0,"yaw":-90.0,"pitch":0.0,"on_ground":true}}
+{"ts":1100,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+{"ts":1100,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":8,"action":"ATTACK"}}
+{"ts":1100,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000002","fields":{"entity_id":8,"action":"ATTACK"}}
+"#
+    .trim_start();
+
+    let events: Vec<FightEvent> =
+        gunzip(&apply_transform("ncp_fight_v1_ndjson_gz", &gzip(raw)).unwrap())
+            .lines()
+            .skip(1)
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+    assert_eq!(events.len(), 3);
+
+    // Iron golem: 1.4 wide, so the face is 0.7 from its position.
+    assert_eq!(events[0].target_type.as_deref(), Some("iron_golem"));
+    assert!(close(events[0].reach_hitbox.unwrap(), 3.3));
+    assert!(events[0].reach_distance.unwrap() > events[0].reach_hitbox.unwrap());
+
+    // Unknown type: the player box.
+    assert_eq!(events[1].target_type, None);
+    assert!(close(events[1].reach_hitbox.unwrap(), 3.7));
+    assert!(events[1].reach_center.is_some());
+
+    // The same target from a 1.8 client.
+    assert!(close(events[2].reach_hitbox.unwrap(), 3.6));
+}
@@ -0,0 +1,330 @@
+//! Recorded responses of the router to requests that must be turned away: every documented route
+//! without credentials and with a wrong token, plus the plugin ingest checks that run before the
+//! registration lookup. Status, content type and body must match
+//! `tests/fixtures/http/routes.golden.ndjson` line for line, so a change to shared auth or error
+//! handling shows up as a reviewable diff. After an intended change, regenerate with
+//! `UPDATE_GOLDENS=1 cargo test --test http_goldens`.
+//!
+//! The state's database is unreachable: routes that get past their auth checks fail with the
+//! internal error body instead of reading data. The registration outcomes that need a database
+//! (held back, deleted, admitted) are covered by `tests/e2e_docker.rs`.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_anticheat_api::{
+    app, config::Config, openapi, reports::Mailer, s3::ObjectStore, shared_state::SharedState,
+};
+use axum::{
+    body::{Body, HttpBody},
+    http::{header::CONTENT_TYPE, Method, Request},
+    Router,
+};
+use serde_json::{json, Value};
+use sqlx::postgres::PgPoolOptions;
+use tower::ServiceExt;
+
+const INGEST_TOKEN: &str = "golden-ingest";
+const CALLBACK_TOKEN: &str = "golden-callback";
+const DASHBOARD_TOKEN: &str = "golden-dashboard";
+const ADMIN_TOKEN: &str = "golden-admin";
+const MAX_BODY_BYTES: usize = 1024;
+
+/// Routes the OpenAPI spec does not document, with their parameters filled in.
+const UNDOCUMENTED: [(&str, &str); 5] = [
+    ("GET", "/docs"),
+    ("GET", "/openapi.json"),
+    ("POST", "/graphql"),
+    ("GET", "/dashboard/golden/observations"),
+    (
+        "GET",
+        "/dashboard/golden/observations/00000000-0000-0000-0000-000000000001",
+    ),
+];
+
+const UUID_PARAM: &str = "00000000-0000-0000-0000-000000000001";
+
+fn golden_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/http/routes.golden.ndjson")
+}
+
+/// The router over a state whose database is unreachable (failing fast) and a scratch store.
+fn router() -> Router {
+    let mut cfg = Config::from_env();
+    cfg.ingest_token = INGEST_TOKEN.to_string();
+    cfg.module_callback_token = CALLBACK_TOKEN.to_string();
+    cfg.dashboard_token = Some(DASHBOARD_TOKEN.to_string());
+    cfg.admin_token = Some(ADMIN_TOKEN.to_string());
+    cfg.max_body_bytes = MAX_BODY_BYTES;
+    cfg.ingest_rate_limit_per_minute = 0;
+    cfg.reputation_enabled = false;
+    cfg.min_supported_plugin_version = None;
+    cfg.recommended_plugin_version = None;
+
+    let db = PgPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(200))
+        .connect_lazy("postgres://golden@127.0.0.1:1/golden")
+        .unwrap();
+    let store = ObjectStore::Local {
+        root: Path::new(env!("CARGO_TARGET_TMPDIR")).join("http_goldens"),
+    };
+    let state = app::state(&cfg, db, store, SharedState::local(), Mailer::default()).unwrap();
+    app::router(state, &cfg)
+}
+
+/// One request to record.
+struct Case {
+    name: &'static str,
+    method: Method,
+    path: String,
+    headers: Vec<(&'static str, String)>,
+    json_body: bool,
+    body: Vec<u8>,
+}
+
+impl Case {
+    fn new(name: &'static str, method: &str, path: &str) -> Self {
+        Self {
+            name,
+            method: method.parse().unwrap(),
+            path: path.to_string(),
+            headers: Vec::new(),
+            json_body: false,
+            body: Vec::new(),
+        }
+    }
+
+    fn header(mut self, name: &'static str, value: &str) -> Self {
+        self.headers.push((name, value.to_string()));
+        self
+    }
+
+    fn json(mut self) -> Self {
+        self.json_body = true;
+        self.body = b"{}".to_vec();
+        self
+    }
+
+    fn body(mut self, body: Vec<u8>) -> Self {
+        self.body = body;
+        self
+    }
+
+    /// Send through the router; the recorded line as JSON.
+    async fn record(&self, app: &Router) -> Value {
+        // A fixed request id keeps error bodies (which echo it) stable.
+        let mut req = Request::builder()
+            .method(self.method.clone())
+            .uri(&self.path)
+            .header("x-request-id", "golden");
+        for (name, value) in &self.headers {
+            req = req.header(*name, value);
+        }
+        if self.json_body {
+            req = req.header(CONTENT_TYPE, "application/json");
+        }
+        let resp = app
+            .clone()
+            .oneshot(req.body(Body::from(self.body.clone())).unwrap())
+            .await
+            .unwrap();
+
+        let status = resp.status().as_u16();
+        let content_type = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let mut body = resp.into_body();
+        let mut bytes = Vec::new();
+        while let Some(chunk) = body.data().await {
+            bytes.extend_from_slice(&chunk.unwrap());
+        }
+        let mut body = serde_json::from_slice::<Value>(&bytes)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()));
+        redact_timestamps(&mut body);
+
+        json!({
+            "case": self.name,
+            "method": self.method.as_str(),
+            "path": self.path,
+            "status": status,
+            "content_type": content_type,
+            "body": body,
+        })
+    }
+}
+
+/// Replace RFC 3339 timestamps (e.g. `generated_at`) so recordings do not depend on the clock.
+fn redact_timestamps(v: &mut Value) {
+    match v {
+        Value::String(s) if chrono::DateTime::parse_from_rfc3339(s).is_ok() => {
+            *s = "<timestamp>".to_string();
+        }
+        Value::Object(map) => map.values_mut().for_each(redact_timestamps),
+        Value::Array(items) => items.iter_mut().for_each(redact_timestamps),
+        _ => {}
+    }
+}
+
+/// Documented and undocumented operations as `(method, path, has JSON body)`, in a stable
+/// order. Path parameters are a fixed uuid for uuid parameters and `golden` otherwise.
+fn operations() -> Vec<(String, String, bool)> {
+    let spec = serde_json::to_value(openapi::spec()).unwrap();
+    let mut ops = Vec::new();
+    for (template, item) in spec["paths"].as_object().unwrap() {
+        for (method, op) in item.as_object().unwrap() {
+            let mut path = template.clone();
+            for param in op["parameters"].as_array().into_iter().flatten() {
+                if param["in"] != "path" {
+                    continue;
+                }
+                let value = if param["schema"]["format"] == "uuid" {
+                    UUID_PARAM
+                } else {
+                    "golden"
+                };
+                path = path.replace(&format!("{{{}}}", param["name"].as_str().unwrap()), value);
+            }
+            let json_body = op["requestBody"]["content"]
+                .get("application/json")
+                .is_some();
+            ops.push((method.to_uppercase(), path, json_body));
+        }
+    }
+    for (method, path) in UNDOCUMENTED {
+        ops.push((method.to_string(), path.to_string(), method == "POST"));
+    }
+    ops.sort();
+    ops
+}
+
+fn cases() -> Vec<Case> {
+    let mut cases = Vec::new();
+    for (method, path, json_body) in operations() {
+        for (name, token) in [
+            ("anonymous", None),
+            ("wrong_token", Some("Bearer wrong-token")),
+        ] {
+            let mut case = Case::new(name, &method, &path);
+            if let Some(token) = token {
+                case = case.header("authorization", token);
+            }
+            if json_body {
+                case = case.json();
+            }
+            cases.push(case);
+        }
+    }
+
+    // Plugin ingest: the checks in front of the registration lookup, in handler order.
+    let ingest = |name| Case::new(name, "POST", "/ingest");
+    cases.extend([
+        ingest("ingest_missing_server_headers"),
+        ingest("ingest_missing_session").header("x-server-id", "golden"),
+        ingest("ingest_bad_batch_seq")
+            .header("x-server-id", "golden")
+            .header("x-session-id", "golden")
+            .header("x-batch-seq", "first"),
+        ingest("ingest_missing_token")
+            .header("x-server-id", "golden")
+            .header("x-session-id", "golden"),
+        ingest("ingest_body_too_large")
+            .header("x-server-id", "golden")
+            .header("x-session-id", "golden")
+            .header("authorization", "Bearer plugin-token")
+            .header("content-length", &(MAX_BODY_BYTES + 1).to_string())
+            .body(vec![0; MAX_BODY_BYTES + 1]),
+        // The registration lookup cannot run: the batch is refused, not accepted unchecked.
+        ingest("ingest_registration_lookup_failed")
+            .header("x-server-id", "golden")
+            .header("x-session-id", "golden")
+            .header("authorization", "Bearer plugin-token")
+            .body(b"not a batch".to_vec()),
+    ]);
+    // Heartbeats without a token are a bad request (400), not unauthorized.
+    cases.push(
+        Case::new("heartbeat_missing_token", "POST", "/heartbeat").header("x-server-id", "golden"),
+    );
+    cases
+}
+
+/// Where `expected` and `actual` first differ, as a readable message.
+fn first_difference(expected: &str, actual: &str) -> Option<String> {
+    let mut exp = expected.lines();
+    let mut act = actual.lines();
+    for line in 1.. {
+        match (exp.next(), act.next()) {
+            (None, None) => return None,
+            (e, a) if e == a => continue,
+            (e, a) => {
+                return Some(format!(
+                    "line {}:\n  expected: {}\n  actual:   {}",
+                    line,
+                    e.unwrap_or("<end of output>"),
+                    a.unwrap_or("<end of output>"),
+                ))
+            }
+        }
+    }
+    unreachable!()
+}
+
+#[tokio::test]
+async fn rejected_requests_match_recorded_responses() {
+    let app = router();
+    let mut actual = String::new();
+    let mut unrouted = Vec::new();
+    for case in cases() {
+        let line = case.record(&app).await;
+        // The router's own 404/405 have empty bodies; handlers always answer with an error body.
+        if matches!(line["status"].as_u64(), Some(404 | 405)) && line["body"] == "" {
+            unrouted.push(format!("{} {}", case.method, case.path));
+        }
+        actual.push_str(&line.to_string());
+        actual.push('\n');
+    }
+    assert!(
+        unrouted.is_empty(),
+        "documented routes the router does not serve: {:?}",
+        unrouted
+    );
+
+    if std::env::var_os("UPDATE_GOLDENS").is_some() {
+        std::fs::create_dir_all(golden_path().parent().unwrap()).unwrap();
+        std::fs::write(golden_path(), &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(golden_path()).unwrap_or_default();
+    if let Some(diff) = first_difference(&expected, &actual) {
+        panic!(
+            "route responses differ from {:?} (UPDATE_GOLDENS=1 to regenerate):\n{}",
+            golden_path(),
+            diff
+        );
+    }
+}
+
+#[tokio::test]
+async fn protected_routes_reject_missing_and_wrong_tokens() {
+    let app = router();
+    for (method, path, json_body) in operations() {
+        let protected = path.starts_with("/dashboard")
+            || path.starts_with("/admin")
+            || matches!(path.as_str(), "/docs" | "/openapi.json");
+        if !protected {
+            continue;
+        }
+        for token in [None, Some("Bearer wrong-token")] {
+            let mut case = Case::new("check", &method, &path);
+            if let Some(token) = token {
+                case = case.header("authorization", token);
+            }
+            if json_body {
+                case = case.json();
+            }
+            let line = case.record(&app).await;
+            assert_eq!(line["status"], 401, "{} {} ({:?})", method, path, token);
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use async_anticheat_api::identity::{normalize, CLIENT_BRAND, FINGERPRINT, IP_HASH};
+
+#[test]
+fn normalize_trims_and_lowercases() {
+    assert_eq!(
+        normalize(IP_HASH, "  ABCDEF01 ").as_deref(),
+        Some("abcdef01")
+    );
+    assert_eq!(normalize(CLIENT_BRAND, "Fabric").as_deref(), Some("fabric"));
+    assert_eq!(normalize(FINGERPRINT, "x").as_deref(), Some("x"));
+}
+
+#[test]
+fn normalize_rejects_empty_oversized_and_unknown() {
+    assert_eq!(normalize(IP_HASH, "   "), None);
+    assert_eq!(normalize(IP_HASH, &"a".repeat(129)), None);
+    assert_eq!(normalize("raw_ip", "127.0.0.1"), None);
+}
@@ -0,0 +1,134 @@
+use async_anticheat_api::ingest_anomalies::{entropy, inspect, Anomaly, MAX_INSPECT_BYTES};
+use flate2::{write::GzEncoder, Compression};
+
+// 2026-01-01T00:00:00Z
+const NOW_MS: i64 = 1_767_225_600_000;
+
+fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut enc = GzEncoder::new(&mut out, Compression::default());
+    std::io::Write::write_all(&mut enc, bytes).unwrap();
+    enc.finish().unwrap();
+    out
+}
+
+/// A plausible movement batch (well above the entropy size floor) with the given record ts.
+fn batch(ts: impl Fn(usize) -> i64) -> Vec<u8> {
+    let mut s = String::from(r#"{"server_id":"s1","session_id":"sess","plugin_version":"0.5.0"}"#);
+    for i in 0..120 {
+        s.push('\n');
+        s.push_str(&format!(
+            r#"{{"ts":{},"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-00000000000{}","name":"Player{}","fields":{{"x":{:.3},"y":64.0,"z":{:.3},"on_ground":{}}}}}"#,
+            ts(i),
+            i % 7,
+            i % 7,
+            100.0 + i as f64 * 0.271,
+            -20.0 - i as f64 * 0.113,
+            i % 3 == 0
+        ));
+    }
+    s.into_bytes()
+}
+
+fn kinds(anomalies: &[Anomaly]) -> Vec<&'static str> {
+    anomalies.iter().map(|a| a.kind()).collect()
+}
+
+#[test]
+fn plausible_batch_passes() {
+    let raw = batch(|i| NOW_MS - 5_000 + i as i64 * 50);
+    let e = entropy(&raw);
+    assert!(e > 3.0 && e < 6.0, "entropy {}", e);
+
+    let inspection = inspect(&gzip(&raw), NOW_MS);
+    assert!(
+        inspection.anomalies.is_empty(),
+        "{:?}",
+        inspection.anomalies
+    );
+    assert_eq!(inspection.sha256.as_deref().map(str::len), Some(64));
+}
+
+#[test]
+fn identical_content_hashes_alike() {
+    let raw = batch(|i| NOW_MS + i as i64);
+    let a = inspect(&gzip(&raw), NOW_MS);
+    let b = inspect(&gzip(&raw), NOW_MS + 60_000);
+    assert_eq!(a.sha256, b.sha256);
+    let c = inspect(&gzip(&batch(|i| NOW_MS + 1 + i as i64)), NOW_MS);
+    assert_ne!(a.sha256, c.sha256);
+}
+
+#[test]
+fn flags_future_and_impossible_timestamps() {
+    // Small drift is tolerated.
+    let drift = inspect(&gzip(&batch(|_| NOW_MS + 60_000)), NOW_MS);
+    assert!(drift.anomalies.is_empty(), "{:?}", drift.anomalies);
+
+    let future = inspect(&gzip(&batch(|i| NOW_MS + 3_600_000 + i as i64)), NOW_MS);
+    assert_eq!(kinds(&future.anomalies), vec!["future_timestamp"]);
+    let Anomaly::FutureTimestamp { count, .. } = &future.anomalies[0] else {
+        unreachable!()
+    };
+    assert_eq!(*count, 120);
+
+    let ancient = inspect(&gzip(&batch(|i| 1_000 + i as i64)), NOW_MS);
+    assert_eq!(kinds(&ancient.anomalies), vec!["impossible_timestamp"]);
+
+    // Two days within one batch.
+    let span = inspect(
+        &gzip(&batch(|i| NOW_MS - 172_800_000 + i as i64 * 1_000_000)),
+        NOW_MS,
+    );
+    assert_eq!(kinds(&span.anomalies), vec!["impossible_timestamp"]);
+}
+
+#[test]
+fn flags_implausible_entropy() {
+    let padding = vec![b'a'; 8192];
+    let low = inspect(&gzip(&padding), NOW_MS);
+    assert_eq!(kinds(&low.anomalies), vec!["low_entropy"]);
+
+    // Pseudo-random bytes (xorshift), as compressed or encrypted data would look.
+    let mut x: u32 = 0x9e37_79b9;
+    let noise: Vec<u8> = (0..16384)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x as u8
+        })
+        .collect();
+    let high = inspect(&gzip(&noise), NOW_MS);
+    assert_eq!(kinds(&high.anomalies), vec!["high_entropy"]);
+
+    // Too small to judge.
+    let tiny = inspect(&gzip(b"aaaa"), NOW_MS);
+    assert!(tiny.anomalies.is_empty());
+}
+
+#[test]
+fn flags_undecodable_and_oversized_payloads() {
+    let plain = inspect(b"{\"ts\":1}\n", NOW_MS);
+    assert_eq!(kinds(&plain.anomalies), vec!["undecodable"]);
+    assert!(plain.sha256.is_none());
+
+    let bomb = inspect(&gzip(&vec![b'\n'; MAX_INSPECT_BYTES + 1]), NOW_MS);
+    assert_eq!(kinds(&bomb.anomalies), vec!["decompressed_too_large"]);
+}
+
+#[test]
+fn anomaly_details_are_structured() {
+    let a = Anomaly::FutureTimestamp {
+        count: 3,
+        max_ts: 10,
+        now_ms: 5,
+    };
+    assert_eq!(a.details()["records"], 3);
+    let id = uuid::Uuid::new_v4();
+    let r = Anomaly::RepeatedPayload {
+        previous_batch_id: id,
+    };
+    assert_eq!(r.kind(), "repeated_payload");
+    assert_eq!(r.details()["previous_batch_id"], id.to_string());
+}
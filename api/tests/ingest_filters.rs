@@ -0,0 +1,107 @@
+use std::io::{Read, Write};
+
+use async_anticheat_api::ingest_filters::{apply, IngestFilterRule, IngestFilters};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+fn gzip(s: &str) -> Vec<u8> {
+    let mut e = GzEncoder::new(Vec::new(), Compression::default());
+    e.write_all(s.as_bytes()).unwrap();
+    e.finish().unwrap()
+}
+
+fn gunzip(b: &[u8]) -> String {
+    let mut s = String::new();
+    GzDecoder::new(b).read_to_string(&mut s).unwrap();
+    s
+}
+
+fn rule(packet: &str, dir: Option<&str>) -> IngestFilterRule {
+    IngestFilterRule {
+        packet: packet.to_string(),
+        dir: dir.map(str::to_string),
+    }
+}
+
+fn filters(rules: Vec<IngestFilterRule>) -> IngestFilters {
+    IngestFilters { rules }.validate().unwrap()
+}
+
+#[test]
+fn rules_are_normalized() {
+    let f = filters(vec![
+        rule(" keep_alive ", None),
+        rule("KEEP_ALIVE", None),
+        rule("chat_*", Some("Clientbound")),
+    ]);
+    assert_eq!(
+        f.rules,
+        vec![
+            rule("CHAT_*", Some("clientbound")),
+            rule("KEEP_ALIVE", None)
+        ]
+    );
+}
+
+#[test]
+fn rules_dropping_packets_checks_need_are_refused() {
+    for packet in [
+        "PLAYER_POSITION",
+        "player_*",
+        "*",
+        "USE_ENTITY",
+        "PLAYER_STATE",
+    ] {
+        let err = IngestFilters {
+            rules: vec![rule(packet, None)],
+        }
+        .validate()
+        .unwrap_err();
+        assert!(err.contains("built-in checks"), "{}: {}", packet, err);
+    }
+    assert!(IngestFilters {
+        rules: vec![rule("KEEP ALIVE", None)]
+    }
+    .validate()
+    .is_err());
+    assert!(IngestFilters {
+        rules: vec![rule("KEEP_ALIVE", Some("sideways"))]
+    }
+    .validate()
+    .is_err());
+}
+
+#[test]
+fn matching_records_are_dropped() {
+    let raw = concat!(
+        "{\"server_id\":\"s\",\"mc_version\":\"1.21\"}\n",
+        "{\"ts\":1,\"dir\":\"serverbound\",\"pkt\":\"KEEP_ALIVE\"}\n",
+        "{\"ts\":2,\"dir\":\"clientbound\",\"pkt\":\"KEEP_ALIVE\"}\n",
+        "{\"ts\":3,\"dir\":\"clientbound\",\"pkt\":\"CHAT_MESSAGE\"}\n",
+        "{\"ts\":4,\"dir\":\"serverbound\",\"pkt\":\"CHAT_MESSAGE\"}\n",
+        "{\"ts\":5,\"dir\":\"serverbound\",\"pkt\":\"PLAYER_POSITION\"}\n",
+        "not json\n",
+    );
+    let f = filters(vec![
+        rule("KEEP_ALIVE", None),
+        rule("CHAT_*", Some("clientbound")),
+    ]);
+    let (out, dropped) = apply(&gzip(raw), &f).unwrap().unwrap();
+    assert_eq!(dropped, 3);
+    assert_eq!(
+        gunzip(&out),
+        concat!(
+            "{\"server_id\":\"s\",\"mc_version\":\"1.21\"}\n",
+            "{\"ts\":4,\"dir\":\"serverbound\",\"pkt\":\"CHAT_MESSAGE\"}\n",
+            "{\"ts\":5,\"dir\":\"serverbound\",\"pkt\":\"PLAYER_POSITION\"}\n",
+            "not json\n",
+        )
+    );
+}
+
+#[test]
+fn batches_without_matches_are_left_alone() {
+    let raw = gzip("{\"server_id\":\"s\"}\n{\"ts\":1,\"pkt\":\"PLAYER_POSITION\"}\n");
+    let f = filters(vec![rule("KEEP_ALIVE", None)]);
+    assert!(apply(&raw, &f).unwrap().is_none());
+    assert!(apply(&raw, &IngestFilters::default()).unwrap().is_none());
+}
@@ -0,0 +1,63 @@
+use async_anticheat_api::ingest_hints::{
+    hints, Backlog, DispatchBacklog, Load, DISPATCH_BACKLOG_HIGH, MAX_BACKOFF_MS, MIN_BACKOFF_MS,
+};
+
+const MAX_BODY: usize = 10 * 1024 * 1024;
+
+fn load(pending_dispatches: usize, db_busy: u32) -> Load {
+    Load {
+        pending_dispatches,
+        db_connections_busy: db_busy,
+        db_connections_max: 10,
+    }
+}
+
+#[test]
+fn idle_api_gives_no_delay_and_the_full_payload() {
+    let h = hints(&load(3, 1), MAX_BODY);
+    assert_eq!(h.backlog, Backlog::Normal);
+    assert_eq!(h.next_batch_interval_ms, None);
+    assert_eq!(h.max_payload_bytes, MAX_BODY);
+    assert_eq!(h.pending_dispatches, 3);
+}
+
+#[test]
+fn dispatch_backlog_slows_plugins_down() {
+    let h = hints(&load(DISPATCH_BACKLOG_HIGH / 2, 0), MAX_BODY);
+    assert_eq!(h.backlog, Backlog::Elevated);
+    assert_eq!(h.next_batch_interval_ms, Some(MIN_BACKOFF_MS));
+    assert_eq!(h.max_payload_bytes, MAX_BODY / 2);
+
+    let h = hints(&load(DISPATCH_BACKLOG_HIGH * 3, 0), MAX_BODY);
+    assert_eq!(h.backlog, Backlog::Saturated);
+    assert_eq!(h.next_batch_interval_ms, Some(MAX_BACKOFF_MS));
+    assert_eq!(h.max_payload_bytes, MAX_BODY / 4);
+}
+
+#[test]
+fn busy_database_pool_counts_as_load() {
+    let h = hints(&load(0, 7), MAX_BODY);
+    assert_eq!(h.backlog, Backlog::Elevated);
+    let delay = h.next_batch_interval_ms.unwrap();
+    assert!(delay > MIN_BACKOFF_MS && delay < MAX_BACKOFF_MS);
+
+    assert_eq!(hints(&load(0, 10), MAX_BODY).backlog, Backlog::Saturated);
+}
+
+#[test]
+fn backlog_counts_batches_until_their_guard_drops() {
+    let backlog = DispatchBacklog::default();
+    let a = backlog.track();
+    let b = backlog.clone().track();
+    assert_eq!(backlog.pending(), 2);
+    drop(a);
+    drop(b);
+    assert_eq!(backlog.pending(), 0);
+}
+
+#[test]
+fn hints_serialize_with_snake_case_backlog() {
+    let json = serde_json::to_value(hints(&load(0, 0), MAX_BODY)).unwrap();
+    assert_eq!(json["backlog"], "normal");
+    assert!(json["next_batch_interval_ms"].is_null());
+}
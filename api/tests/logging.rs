@@ -0,0 +1,123 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use async_anticheat_api::logging::{subscriber, LogFormat, LogSettings};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Captured {
+    type Writer = Captured;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+impl Captured {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+fn capture(settings: LogSettings, f: impl FnOnce()) -> Vec<String> {
+    let out = Captured::default();
+    let sub = subscriber(settings, EnvFilter::new("trace"), out.clone());
+    tracing::subscriber::with_default(sub, f);
+    out.lines()
+}
+
+#[test]
+fn log_format_parses() {
+    assert_eq!(LogFormat::parse(" JSON "), Some(LogFormat::Json));
+    assert_eq!(LogFormat::parse("text"), Some(LogFormat::Text));
+    assert_eq!(LogFormat::parse(""), Some(LogFormat::Text));
+    assert_eq!(LogFormat::parse("logfmt"), None);
+}
+
+#[test]
+fn json_lines_carry_span_context_at_the_top_level() {
+    let settings = LogSettings {
+        format: LogFormat::Json,
+        debug_sample_every: 1,
+    };
+    let lines = capture(settings, || {
+        let request = tracing::info_span!(
+            "request",
+            request_id = "req-1",
+            method = "POST",
+            server_id = tracing::field::Empty,
+        );
+        let _request = request.enter();
+        request.record("server_id", "srv-1");
+        let dispatch = tracing::info_span!("dispatch", module = "combat_core", batch_id = 7);
+        let _dispatch = dispatch.enter();
+        tracing::warn!(status = 502, "module returned an error");
+    });
+
+    assert_eq!(lines.len(), 1);
+    let line: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+    assert_eq!(line["level"], "WARN");
+    assert_eq!(line["message"], "module returned an error");
+    assert_eq!(line["status"], 502);
+    assert_eq!(line["request_id"], "req-1");
+    assert_eq!(line["server_id"], "srv-1");
+    assert_eq!(line["module"], "combat_core");
+    assert_eq!(line["batch_id"], 7);
+    // Only the context fields are lifted from spans.
+    assert!(line.get("method").is_none());
+}
+
+#[test]
+fn event_fields_win_over_span_fields() {
+    let settings = LogSettings {
+        format: LogFormat::Json,
+        debug_sample_every: 1,
+    };
+    let lines = capture(settings, || {
+        let _span = tracing::info_span!("request", server_id = "outer").entered();
+        tracing::info!(server_id = "inner", "hello");
+    });
+    let line: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+    assert_eq!(line["server_id"], "inner");
+}
+
+#[test]
+fn debug_events_are_sampled_per_call_site() {
+    let settings = LogSettings {
+        format: LogFormat::Text,
+        debug_sample_every: 10,
+    };
+    let lines = capture(settings, || {
+        for i in 0..25 {
+            tracing::debug!(packet = i, "per-packet");
+        }
+        for i in 0..3 {
+            tracing::trace!(other = i, "rare");
+        }
+        for i in 0..5 {
+            tracing::info!(n = i, "important");
+        }
+    });
+    let count = |needle: &str| lines.iter().filter(|l| l.contains(needle)).count();
+    assert_eq!(count("per-packet"), 3);
+    assert_eq!(count("rare"), 1);
+    assert_eq!(count("important"), 5);
+}
@@ -0,0 +1,67 @@
+use std::io::Write;
+
+use async_anticheat_api::maintenance::{
+    inspect_batch, sanitize_fixture, BatchContents, ObjectReport,
+};
+use flate2::{write::GzEncoder, Compression};
+
+fn gz(ndjson: &str) -> Vec<u8> {
+    let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+    enc.write_all(ndjson.as_bytes()).unwrap();
+    enc.finish().unwrap()
+}
+
+#[test]
+fn inspect_batch_counts_records_and_invalid_lines() {
+    let raw = gz("{\"pkt\":\"flying\"}\n\n{\"pkt\":\"arm\"}\nnot json\n[1,2]\n");
+    assert_eq!(
+        inspect_batch(&raw).unwrap(),
+        BatchContents {
+            records: 2,
+            invalid_lines: 2,
+        }
+    );
+    assert!(inspect_batch(b"not gzip").is_err());
+}
+
+#[test]
+fn report_checks_batch_index_row() {
+    let report = |indexed| ObjectReport {
+        key: "events/s1/2024-01-01/sess/b.ndjson.gz".to_string(),
+        bytes: 120,
+        contents: BatchContents {
+            records: 3,
+            invalid_lines: 0,
+        },
+        indexed,
+    };
+    assert!(report(None).is_consistent());
+    assert!(report(Some((120, None))).is_consistent());
+    assert!(report(Some((120, Some(3)))).is_consistent());
+    assert!(!report(Some((119, None))).is_consistent());
+    assert!(!report(Some((120, Some(4)))).is_consistent());
+}
+
+#[test]
+fn sanitize_fixture_replaces_identities_and_rebases_time() {
+    let raw = gz(concat!(
+        r#"{"server_id":"prod-eu-1","session_id":"8c1f","client_types":{"6f1a2b3c-0000-4000-8000-00000000abcd":"bedrock"}}"#,
+        "\n",
+        r#"{"ts":1718000000500,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"9e8d7c6b-0000-4000-8000-000000001234","name":"Notch","ip_hash":"ab12","fields":{"x":1.0}}"#,
+        "\n",
+        "truncated {\n",
+        r#"{"ts":1718000000480,"dir":"clientbound","pkt":"SPAWN_ENTITY","uuid":"6f1a2b3c-0000-4000-8000-00000000abcd","name":"Steve","fields":{"entity_id":7,"entity_uuid":"9e8d7c6b-0000-4000-8000-000000001234"}}"#,
+        "\n",
+    ));
+    let out = sanitize_fixture(&raw).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            r#"{"client_types":{"00000000-0000-0000-0000-000000000001":"bedrock"},"server_id":"fixture","session_id":"fixture"}"#,
+            r#"{"dir":"serverbound","fields":{"x":1.0},"name":"player2","pkt":"PLAYER_POSITION","ts":1000,"uuid":"00000000-0000-0000-0000-000000000002"}"#,
+            r#"{"dir":"clientbound","fields":{"entity_id":7,"entity_uuid":"00000000-0000-0000-0000-000000000002"},"name":"player1","pkt":"SPAWN_ENTITY","ts":980,"uuid":"00000000-0000-0000-0000-000000000001"}"#,
+        ]
+    );
+    assert!(sanitize_fixture(b"not gzip").is_err());
+}
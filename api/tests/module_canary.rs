@@ -0,0 +1,63 @@
+use async_anticheat_api::module_canary::{
+    normalize_url, routes_to_canary, totals, VariantDay, CANARY, STABLE,
+};
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+#[test]
+fn canary_share_follows_the_percentage() {
+    let ids: Vec<Uuid> = (0..10_000).map(|_| Uuid::new_v4()).collect();
+    assert!(!ids.iter().any(|id| routes_to_canary(*id, 0)));
+    assert!(ids.iter().all(|id| routes_to_canary(*id, 100)));
+
+    let share = ids.iter().filter(|id| routes_to_canary(**id, 10)).count();
+    assert!(
+        (700..1300).contains(&share),
+        "{} of 10000 went to canary",
+        share
+    );
+
+    // A batch always lands on the same side.
+    let id = ids[0];
+    assert_eq!(routes_to_canary(id, 37), routes_to_canary(id, 37));
+}
+
+#[test]
+fn canary_urls_are_normalized() {
+    assert_eq!(
+        normalize_url(" https://canary:4010/ ").unwrap().as_deref(),
+        Some("https://canary:4010")
+    );
+    assert_eq!(normalize_url("").unwrap(), None);
+    assert!(normalize_url("wasm://abc").is_err());
+}
+
+#[test]
+fn totals_compare_finding_rates() {
+    let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+    let days = vec![
+        VariantDay {
+            day,
+            variant: STABLE.to_string(),
+            batches: 900,
+            findings: 45,
+        },
+        VariantDay {
+            day: day.succ_opt().unwrap(),
+            variant: STABLE.to_string(),
+            batches: 100,
+            findings: 5,
+        },
+        VariantDay {
+            day,
+            variant: CANARY.to_string(),
+            batches: 100,
+            findings: 9,
+        },
+    ];
+    let stable = totals(&days, STABLE);
+    assert_eq!((stable.batches, stable.findings), (1000, 50));
+    assert_eq!(stable.findings_per_1k_batches, Some(50.0));
+    assert_eq!(totals(&days, CANARY).findings_per_1k_batches, Some(90.0));
+    assert_eq!(totals(&[], CANARY).findings_per_1k_batches, None);
+}
@@ -0,0 +1,77 @@
+use async_anticheat_api::module_failover::{
+    dispatch_order, normalize_backups, should_fail_over, EndpointHealth, MAX_BACKUP_URLS,
+};
+use reqwest::StatusCode;
+
+fn health(base_url: &str, healthy: bool, consecutive_failures: i32) -> EndpointHealth {
+    EndpointHealth {
+        base_url: base_url.to_string(),
+        healthy: Some(healthy),
+        consecutive_failures,
+        last_error: None,
+        last_checked_at: None,
+    }
+}
+
+fn urls(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn backups_are_trimmed_and_deduplicated() {
+    let backups = normalize_backups(
+        "http://a:4010",
+        &urls(&[
+            " http://b:4010/ ",
+            "",
+            "http://a:4010/",
+            "http://b:4010",
+            "https://c",
+        ]),
+    )
+    .unwrap();
+    assert_eq!(backups, urls(&["http://b:4010", "https://c"]));
+    assert!(normalize_backups("http://a", &[]).unwrap().is_empty());
+}
+
+#[test]
+fn backups_need_http_endpoints() {
+    assert!(normalize_backups("http://a", &urls(&["wasm://x"])).is_err());
+    assert!(normalize_backups("inproc://movement", &urls(&["http://b"])).is_err());
+    // Clearing backups is fine whatever the primary.
+    assert!(normalize_backups("inproc://movement", &[]).is_ok());
+
+    let many: Vec<String> = (0..=MAX_BACKUP_URLS)
+        .map(|i| format!("http://backup-{}", i))
+        .collect();
+    assert!(normalize_backups("http://a", &many).is_err());
+}
+
+#[test]
+fn down_endpoints_are_tried_last() {
+    let backups = urls(&["http://b", "http://c"]);
+    assert_eq!(
+        dispatch_order("http://a", &backups, &[]),
+        urls(&["http://a", "http://b", "http://c"])
+    );
+
+    let endpoints = [
+        health("http://a", false, 3),
+        health("http://b", false, 1),
+        health("http://c", true, 0),
+    ];
+    assert_eq!(
+        dispatch_order("http://a", &backups, &endpoints),
+        urls(&["http://b", "http://c", "http://a"])
+    );
+}
+
+#[test]
+fn only_host_side_failures_fail_over() {
+    assert!(should_fail_over(None));
+    assert!(should_fail_over(Some(StatusCode::BAD_GATEWAY)));
+    assert!(should_fail_over(Some(StatusCode::TOO_MANY_REQUESTS)));
+    assert!(should_fail_over(Some(StatusCode::REQUEST_TIMEOUT)));
+    assert!(!should_fail_over(Some(StatusCode::PAYLOAD_TOO_LARGE)));
+    assert!(!should_fail_over(Some(StatusCode::BAD_REQUEST)));
+}
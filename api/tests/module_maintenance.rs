@@ -0,0 +1,79 @@
+use async_anticheat_api::module_maintenance::{
+    suspension, validate, window_active, MaintenanceWindow, MAX_WINDOWS, REASON_PAUSED,
+    REASON_WINDOW,
+};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+
+/// 2024-03-04 was a Monday.
+fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 3, day, hour, minute, 30)
+        .unwrap()
+}
+
+fn window(weekday: Option<i16>, start_minute: i32, duration_minutes: i32) -> MaintenanceWindow {
+    MaintenanceWindow {
+        weekday,
+        start_minute,
+        duration_minutes,
+    }
+}
+
+#[test]
+fn daily_windows_repeat_and_cross_midnight() {
+    let nightly = window(None, 23 * 60 + 30, 60);
+    assert!(window_active(&nightly, at(4, 23, 45)));
+    assert!(window_active(&nightly, at(5, 0, 15)));
+    assert!(window_active(&nightly, at(9, 0, 29)));
+    assert!(!window_active(&nightly, at(5, 0, 30)));
+    assert!(!window_active(&nightly, at(5, 23, 29)));
+}
+
+#[test]
+fn weekly_windows_only_apply_on_their_day() {
+    // Sunday 23:00 for two hours: runs into Monday.
+    let sunday = window(Some(6), 23 * 60, 120);
+    assert!(window_active(&sunday, at(10, 23, 0)));
+    assert!(window_active(&sunday, at(11, 0, 59)));
+    assert!(!window_active(&sunday, at(11, 1, 0)));
+    assert!(!window_active(&sunday, at(9, 23, 30)));
+
+    let monday = window(Some(0), 4 * 60, 15);
+    assert!(window_active(&monday, at(4, 4, 10)));
+    assert!(!window_active(&monday, at(5, 4, 10)));
+}
+
+#[test]
+fn suspension_reports_the_longest_reason() {
+    let now = at(4, 3, 10);
+    let windows = [window(None, 3 * 60, 30)];
+
+    assert_eq!(suspension(None, &[], now), None);
+    // A pause that has run out no longer applies.
+    assert_eq!(suspension(Some(now - Duration::seconds(1)), &[], now), None);
+
+    let s = suspension(None, &windows, now).unwrap();
+    assert_eq!(s.reason, REASON_WINDOW);
+    assert_eq!(s.until, Utc.with_ymd_and_hms(2024, 3, 4, 3, 30, 0).unwrap());
+
+    let short_pause = now + Duration::minutes(5);
+    let s = suspension(Some(short_pause), &windows, now).unwrap();
+    assert_eq!(s.reason, REASON_WINDOW);
+
+    let long_pause = now + Duration::hours(2);
+    let s = suspension(Some(long_pause), &windows, now).unwrap();
+    assert_eq!(s.reason, REASON_PAUSED);
+    assert_eq!(s.until, long_pause);
+}
+
+#[test]
+fn invalid_windows_are_rejected() {
+    assert!(validate(&[]).is_ok());
+    assert!(validate(&[window(Some(6), 0, 720), window(None, 1439, 1)]).is_ok());
+
+    assert!(validate(&[window(Some(7), 0, 10)]).is_err());
+    assert!(validate(&[window(None, 1440, 10)]).is_err());
+    assert!(validate(&[window(None, -1, 10)]).is_err());
+    assert!(validate(&[window(None, 0, 0)]).is_err());
+    assert!(validate(&[window(None, 0, 721)]).is_err());
+    assert!(validate(&vec![window(None, 0, 10); MAX_WINDOWS + 1]).is_err());
+}
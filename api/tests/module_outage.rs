@@ -0,0 +1,73 @@
+use async_anticheat_api::module_outage::{description, title};
+use async_anticheat_api::webhooks::{send_module_down_notification, ModuleDownNotification};
+use axum::{extract::State, routing::post, Json, Router};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+fn notification() -> ModuleDownNotification {
+    ModuleDownNotification {
+        server_id: "srv".to_string(),
+        module_id: Uuid::nil(),
+        module_name: "ncp_fight".to_string(),
+        consecutive_failures: 3,
+        last_error: "request error: connection refused".to_string(),
+        modules_down: 2,
+        modules_enabled: 4,
+    }
+}
+
+#[test]
+fn outage_finding_says_how_much_is_offline() {
+    assert_eq!(title("ncp_fight"), "Module ncp_fight is offline");
+    let text = description(&notification());
+    assert!(
+        text.starts_with("ncp_fight failed 3 times in a row"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("(2 of 4 enabled modules offline)"),
+        "{}",
+        text
+    );
+    assert!(
+        text.ends_with("Last error: request error: connection refused"),
+        "{}",
+        text
+    );
+}
+
+#[tokio::test]
+async fn generic_webhooks_get_a_module_down_payload() {
+    let (tx, mut rx) = mpsc::unbounded_channel::<Value>();
+    let app = Router::new()
+        .route(
+            "/hook",
+            post(
+                |State(tx): State<mpsc::UnboundedSender<Value>>, Json(body): Json<Value>| async move {
+                    let _ = tx.send(body);
+                },
+            ),
+        )
+        .with_state(tx);
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        axum::Server::from_tcp(listener)
+            .unwrap()
+            .serve(app.into_make_service()),
+    );
+
+    let url = format!("http://{}/hook", addr);
+    send_module_down_notification(&reqwest::Client::new(), &url, &notification(), None).await;
+
+    let body = rx.recv().await.unwrap();
+    assert_eq!(body["type"], "module_down");
+    assert_eq!(body["server_id"], "srv");
+    assert_eq!(body["module_id"], Uuid::nil().to_string());
+    assert_eq!(body["module_name"], "ncp_fight");
+    assert_eq!(body["consecutive_failures"], 3);
+    assert_eq!(body["modules_down"], 2);
+    assert_eq!(body["modules_enabled"], 4);
+}
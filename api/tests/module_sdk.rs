@@ -0,0 +1,57 @@
+#![cfg(feature = "module-sdk")]
+
+use async_anticheat_api::module_sdk::{MovementEvent, ProcessBatchRequest};
+use axum::http::{HeaderMap, HeaderValue};
+use flate2::{write::GzEncoder, Compression};
+
+fn gzip(s: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut enc = GzEncoder::new(&mut out, Compression::default());
+    std::io::Write::write_all(&mut enc, s.as_bytes()).unwrap();
+    enc.finish().unwrap();
+    out
+}
+
+#[test]
+fn process_batch_request_decodes_transformed_events() {
+    let body = gzip(
+        r#"{"transform":"movement_events_v1"}
+{"ts":1050,"uuid":"00000000-0000-0000-0000-000000000001","x":1.0,"y":64.0,"z":0.0,"on_ground":false,"dt_ms":50.0,"dx":1.0,"dy":0.0,"dz":0.0,"speed_bps":20.0}
+"#,
+    );
+    let mut headers = HeaderMap::new();
+    headers.insert("x-server-id", HeaderValue::from_static("s"));
+    headers.insert("x-session-id", HeaderValue::from_static("x"));
+    headers.insert(
+        "x-batch-id",
+        HeaderValue::from_static("00000000-0000-0000-0000-0000000000aa"),
+    );
+
+    let batch = ProcessBatchRequest::from_parts(&headers, &body).unwrap();
+    assert_eq!(batch.transform(), Some("movement_events_v1"));
+    let events: Vec<MovementEvent> = batch.events().collect();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].speed_bps, Some(20.0));
+}
+
+#[test]
+fn findings_request_echoes_the_module_id() {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-server-id", HeaderValue::from_static("s"));
+    headers.insert("x-session-id", HeaderValue::from_static("x"));
+    headers.insert(
+        "x-batch-id",
+        HeaderValue::from_static("00000000-0000-0000-0000-0000000000aa"),
+    );
+    headers.insert(
+        "x-module-id",
+        HeaderValue::from_static("00000000-0000-0000-0000-0000000000bb"),
+    );
+
+    let batch = ProcessBatchRequest::from_parts(&headers, &gzip("{}\n")).unwrap();
+    let req = batch.findings_request(Vec::new());
+    assert_eq!(
+        req.module_id.map(|id| id.to_string()).as_deref(),
+        Some("00000000-0000-0000-0000-0000000000bb")
+    );
+}
@@ -0,0 +1,49 @@
+use async_anticheat_api::module_usage::{summarize, UsageDay};
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+#[test]
+fn summary_ranks_modules_by_bandwidth() {
+    let (a, b, idle) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let day = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+    let usage = |module_id, day, dispatches, payload_bytes, transform_us| UsageDay {
+        module_id,
+        day,
+        dispatches,
+        payload_bytes,
+        transform_us,
+    };
+    let days = vec![
+        usage(a, day, 10, 1_000, 4_000),
+        usage(a, day.succ_opt().unwrap(), 10, 1_000, 4_000),
+        usage(b, day, 5, 6_000, 2_000),
+    ];
+    let modules = vec![
+        (a, "movement".to_string()),
+        (b, "combat".to_string()),
+        (idle, "idle".to_string()),
+    ];
+
+    let summary = summarize(&modules, &days);
+    let names: Vec<&str> = summary.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["combat", "movement", "idle"]);
+
+    let movement = &summary[1];
+    assert_eq!((movement.dispatches, movement.payload_bytes), (20, 2_000));
+    assert_eq!(movement.transform_ms, 8.0);
+    assert_eq!(movement.avg_payload_bytes, Some(100.0));
+    assert_eq!(movement.bandwidth_share, Some(0.25));
+    assert_eq!(movement.cpu_share, Some(0.8));
+
+    assert_eq!(summary[2].dispatches, 0);
+    assert_eq!(summary[2].avg_payload_bytes, None);
+    assert_eq!(summary[2].bandwidth_share, Some(0.0));
+}
+
+#[test]
+fn shares_are_none_without_usage() {
+    let id = Uuid::new_v4();
+    let summary = summarize(&[(id, "m".to_string())], &[]);
+    assert_eq!(summary[0].bandwidth_share, None);
+    assert_eq!(summary[0].cpu_share, None);
+}
@@ -0,0 +1,124 @@
+use async_anticheat_api::movement_path::{downsample, mark_segments, points_from, PathPoint};
+use async_anticheat_api::transforms::apply_transform;
+use flate2::{write::GzEncoder, Compression};
+use serde_json::json;
+use std::io::Write;
+use uuid::Uuid;
+
+fn point(ts: i64, x: f64) -> PathPoint {
+    PathPoint {
+        ts,
+        x,
+        y: 64.0,
+        z: 0.0,
+        on_ground: None,
+        world: None,
+        segment_start: false,
+    }
+}
+
+fn gzip(lines: &[serde_json::Value]) -> Vec<u8> {
+    let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+    for line in lines {
+        writeln!(enc, "{}", line).unwrap();
+    }
+    enc.finish().unwrap()
+}
+
+#[test]
+fn reads_the_players_positions_from_the_movement_transform() {
+    let player = Uuid::new_v4();
+    let other = Uuid::new_v4();
+    let pos = |ts: i64, uuid: Uuid, x: f64| {
+        json!({
+            "ts": ts, "dir": "serverbound", "pkt": "PLAYER_POSITION", "uuid": uuid.to_string(),
+            "fields": {"x": x, "y": 64.0, "z": 0.0, "on_ground": true},
+        })
+    };
+    let raw = gzip(&[
+        json!({"server_id": "srv"}),
+        pos(1000, player, 0.0),
+        pos(1050, other, 5.0),
+        pos(1100, player, 0.2),
+        json!({"ts": 1150, "dir": "serverbound", "pkt": "INTERACT_ENTITY", "uuid": player.to_string(), "fields": {}}),
+        pos(3000, player, 0.4),
+    ]);
+    let movement = apply_transform("movement_events_v1_ndjson_gz", &raw).unwrap();
+
+    let points = points_from(&movement, player, 1000, 2000).unwrap();
+    assert_eq!(points.len(), 2);
+    assert_eq!((points[0].ts, points[0].x), (1000, 0.0));
+    assert_eq!((points[1].ts, points[1].x), (1100, 0.2));
+    assert_eq!(points[1].on_ground, Some(true));
+}
+
+#[test]
+fn teleports_world_changes_and_gaps_start_segments() {
+    let mut points = vec![
+        point(200, 1.0),
+        point(0, 0.0),
+        point(300, 50.0),
+        point(400, 50.5),
+        point(10_000, 51.0),
+    ];
+    let mut other_world = point(10_100, 51.0);
+    other_world.world = Some("nether".to_string());
+    points.push(other_world);
+    let mut same_world = point(10_200, 51.2);
+    same_world.world = Some("nether".to_string());
+    points.push(same_world);
+
+    mark_segments(&mut points);
+    let ts: Vec<_> = points.iter().map(|p| p.ts).collect();
+    assert_eq!(ts, vec![0, 200, 300, 400, 10_000, 10_100, 10_200]);
+    let starts: Vec<_> = points.iter().map(|p| p.segment_start).collect();
+    // Unknown world on one side isn't a world change.
+    assert_eq!(starts, vec![false, false, true, false, true, false, false]);
+
+    points[4].world = Some("world".to_string());
+    mark_segments(&mut points);
+    assert!(points[5].segment_start);
+}
+
+#[test]
+fn short_paths_are_returned_as_is() {
+    let points: Vec<_> = (0..10).map(|i| point(i * 50, i as f64 * 0.1)).collect();
+    assert_eq!(downsample(&points, 10), points);
+}
+
+#[test]
+fn downsampling_keeps_the_ends_and_segment_boundaries() {
+    let mut points: Vec<_> = (0..1000).map(|i| point(i * 50, i as f64 * 0.1)).collect();
+    for p in &mut points[500..] {
+        p.x += 100.0;
+    }
+    mark_segments(&mut points);
+    assert!(points[500].segment_start);
+
+    let out = downsample(&points, 50);
+    assert!(out.len() <= 50);
+    assert_eq!(out.first().unwrap().ts, 0);
+    assert_eq!(out.last().unwrap().ts, 999 * 50);
+    assert!(out.iter().any(|p| p.ts == 499 * 50 && !p.segment_start));
+    let starts: Vec<_> = out
+        .iter()
+        .filter(|p| p.segment_start)
+        .map(|p| p.ts)
+        .collect();
+    assert_eq!(starts, vec![500 * 50]);
+}
+
+#[test]
+fn too_many_boundaries_fall_back_to_even_spacing() {
+    // Every point is a teleport.
+    let mut points: Vec<_> = (0..100).map(|i| point(i * 50, i as f64 * 20.0)).collect();
+    mark_segments(&mut points);
+
+    let out = downsample(&points, 10);
+    assert_eq!(out.len(), 10);
+    assert_eq!(out.first().unwrap().ts, 0);
+    assert!(!out[0].segment_start);
+    assert_eq!(out.last().unwrap().ts, 99 * 50);
+    // Dropped segment starts carry over to the next kept point.
+    assert!(out[1..].iter().all(|p| p.segment_start));
+}
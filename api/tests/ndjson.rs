@@ -0,0 +1,47 @@
+use async_anticheat_api::ndjson::{parse_record, RecordParser, BACKEND};
+use serde_json::Value;
+
+/// Lines covering what batches carry, plus some that only one parser might get wrong.
+const CORPUS: &[&str] = &[
+    r#"{"ts":1700000000000,"uuid":"00000000-0000-0000-0000-000000000001","dir":"serverbound","pkt":"PLAYER_POSITION","fields":{"x":-12.5,"y":64.0,"z":1e-7,"on_ground":true}}"#,
+    r#"{"ts":18446744073709551615,"n":-9223372036854775808,"f":1.7976931348623157e308,"tiny":5e-324}"#,
+    r#"{"fields":{"entity_ids":[1,2,3],"data":"\u0000minecraft:brand😀","nested":{"a":[{"b":null}]}}}"#,
+    r#"{"name":"Ünïcödé ✓","escaped":"line\nbreak \"quoted\" \\ slash\/"}"#,
+    r#"  {"padded": true}  "#,
+    r#"{"dup":1,"dup":2}"#,
+    r#"{"x":0.1,"y":0.30000000000000004,"z":123456789.123456789}"#,
+    r#"[1,"two",3.0]"#,
+    "42",
+    r#""just a string""#,
+    "",
+    "not json",
+    r#"{"unterminated":"#,
+    r#"{"trailing":1,}"#,
+    r#"{"a":1} {"b":2}"#,
+    r#"{"nan":NaN}"#,
+];
+
+#[test]
+fn records_parse_like_serde_json() {
+    let mut parser = RecordParser::new();
+    for line in CORPUS {
+        let expected = serde_json::from_str::<Value>(line).ok();
+        assert_eq!(parser.parse(line), expected, "{} on {:?}", BACKEND, line);
+        assert_eq!(parse_record(line), expected, "{} on {:?}", BACKEND, line);
+    }
+}
+
+#[test]
+fn parser_is_reusable_across_lines_of_different_sizes() {
+    let mut parser = RecordParser::new();
+    let long = format!(r#"{{"data":"{}"}}"#, "x".repeat(100_000));
+    for line in [
+        long.as_str(),
+        r#"{"a":1}"#,
+        long.as_str(),
+        "bad",
+        r#"{"b":[]}"#,
+    ] {
+        assert_eq!(parser.parse(line), serde_json::from_str::<Value>(line).ok());
+    }
+}
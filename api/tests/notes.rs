@@ -0,0 +1,21 @@
+use async_anticheat_api::notes::{validate, MAX_BODY_CHARS};
+
+#[test]
+fn notes_are_trimmed() {
+    assert_eq!(
+        validate(" alice ", "  Check his reach on the bridge.\n").unwrap(),
+        (
+            "alice".to_string(),
+            "Check his reach on the bridge.".to_string()
+        )
+    );
+}
+
+#[test]
+fn empty_or_oversized_notes_are_refused() {
+    assert!(validate("", "note").is_err());
+    assert!(validate("alice", "  ").is_err());
+    assert!(validate(&"a".repeat(65), "note").is_err());
+    assert!(validate("alice", &"x".repeat(MAX_BODY_CHARS)).is_ok());
+    assert!(validate("alice", &"x".repeat(MAX_BODY_CHARS + 1)).is_err());
+}
@@ -0,0 +1,62 @@
+use async_anticheat_api::openapi;
+use serde_json::Value;
+
+fn collect_refs(v: &Value, out: &mut Vec<String>) {
+    match v {
+        Value::Object(map) => {
+            if let Some(Value::String(r)) = map.get("$ref") {
+                out.push(r.clone());
+            }
+            map.values().for_each(|x| collect_refs(x, out));
+        }
+        Value::Array(items) => items.iter().for_each(|x| collect_refs(x, out)),
+        _ => {}
+    }
+}
+
+#[test]
+fn spec_covers_main_routes() {
+    let spec = serde_json::to_value(openapi::spec()).unwrap();
+    let paths = spec["paths"].as_object().unwrap();
+
+    for (path, method) in [
+        ("/ingest", "post"),
+        ("/heartbeat", "post"),
+        ("/callbacks/findings", "post"),
+        ("/dashboard/{server_id}/findings", "get"),
+        ("/dashboard/organizations", "post"),
+        ("/dashboard/organizations/{org_id}/findings", "get"),
+        ("/admin/reprocess", "post"),
+    ] {
+        assert!(
+            paths.get(path).and_then(|p| p.get(method)).is_some(),
+            "{} {} missing",
+            method,
+            path
+        );
+    }
+
+    let findings = &paths["/dashboard/{server_id}/findings"]["get"];
+    let params: Vec<&str> = findings["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|p| p["name"].as_str())
+        .collect();
+    assert!(params.contains(&"server_id"));
+    assert!(params.contains(&"world"));
+}
+
+#[test]
+fn every_schema_reference_resolves() {
+    let spec = serde_json::to_value(openapi::spec()).unwrap();
+    let schemas = spec["components"]["schemas"].as_object().unwrap();
+
+    let mut refs = Vec::new();
+    collect_refs(&spec, &mut refs);
+    assert!(!refs.is_empty());
+    for r in refs {
+        let name = r.trim_start_matches("#/components/schemas/");
+        assert!(schemas.contains_key(name), "unresolved schema ref {}", r);
+    }
+}
@@ -0,0 +1,168 @@
+use async_anticheat_api::packet_names::{
+    canonical, hurt_entity, normalize_record, DAMAGE_EVENT, DESTROY_ENTITIES, ENTITY_RELATIVE_MOVE,
+    ENTITY_STATUS, ENTITY_TELEPORT, INTERACT_ENTITY, PLAYER_FLYING, PLAYER_POSITION,
+    PLAYER_POSITION_AND_LOOK, PLAYER_POSITION_AND_ROTATION, PLAYER_ROTATION, SPAWN_ENTITY,
+    SPAWN_LIVING_ENTITY, SPAWN_PLAYER,
+};
+use async_anticheat_api::plugin_version::Version;
+use async_anticheat_api::transforms::apply_transform;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::io::Read;
+
+const SB: &str = "serverbound";
+const CB: &str = "clientbound";
+
+fn gzip(s: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut enc = GzEncoder::new(&mut out, Compression::default());
+    std::io::Write::write_all(&mut enc, s.as_bytes()).unwrap();
+    enc.finish().unwrap();
+    out
+}
+
+fn gunzip(b: &[u8]) -> String {
+    let mut s = String::new();
+    GzDecoder::new(b).read_to_string(&mut s).unwrap();
+    s
+}
+
+#[test]
+fn maps_1_8_names() {
+    let v = Some(Version(1, 8, 8));
+    assert_eq!(canonical("Player Position", SB, v), PLAYER_POSITION);
+    assert_eq!(
+        canonical("PLAYER_POSITION_AND_LOOK", SB, v),
+        PLAYER_POSITION_AND_ROTATION
+    );
+    assert_eq!(canonical("Player Look", SB, v), PLAYER_ROTATION);
+    assert_eq!(canonical("PLAYER", SB, v), PLAYER_FLYING);
+    assert_eq!(canonical("Use Entity", SB, v), INTERACT_ENTITY);
+    assert_eq!(canonical("SPAWN_MOB", CB, v), SPAWN_LIVING_ENTITY);
+    assert_eq!(canonical("ENTITY_DESTROY", CB, v), DESTROY_ENTITIES);
+    // The clientbound packet of the same name is a server teleport.
+    assert_eq!(
+        canonical("PLAYER_POSITION_AND_LOOK", CB, v),
+        PLAYER_POSITION_AND_LOOK
+    );
+}
+
+#[test]
+fn maps_1_12_protocollib_names() {
+    let v = Some(Version(1, 12, 2));
+    assert_eq!(canonical("POSITION", SB, v), PLAYER_POSITION);
+    assert_eq!(
+        canonical("POSITION_LOOK", SB, v),
+        PLAYER_POSITION_AND_ROTATION
+    );
+    assert_eq!(canonical("LOOK", SB, v), PLAYER_ROTATION);
+    assert_eq!(canonical("FLYING", SB, v), PLAYER_FLYING);
+    assert_eq!(canonical("USE_ENTITY", SB, v), INTERACT_ENTITY);
+    assert_eq!(canonical("REL_ENTITY_MOVE", CB, v), ENTITY_RELATIVE_MOVE);
+    assert_eq!(canonical("SPAWN_ENTITY_LIVING", CB, v), SPAWN_LIVING_ENTITY);
+    assert_eq!(canonical("NAMED_ENTITY_SPAWN", CB, v), SPAWN_PLAYER);
+    assert_eq!(canonical("POSITION", CB, v), PLAYER_POSITION_AND_LOOK);
+}
+
+#[test]
+fn maps_1_16_spigot_and_wiki_names() {
+    let v = Some(Version(1, 16, 5));
+    assert_eq!(
+        canonical("PacketPlayInFlying$PacketPlayInPositionLook", SB, v),
+        PLAYER_POSITION_AND_ROTATION
+    );
+    assert_eq!(canonical("PacketPlayInFlying", SB, v), PLAYER_FLYING);
+    assert_eq!(canonical("Player Movement", SB, v), PLAYER_FLYING);
+    assert_eq!(
+        canonical("net.minecraft.server.v1_16_R3.PacketPlayInUseEntity", SB, v),
+        INTERACT_ENTITY
+    );
+    assert_eq!(canonical("Entity Position", CB, v), ENTITY_RELATIVE_MOVE);
+    assert_eq!(
+        canonical("PacketPlayOutEntityTeleport", CB, v),
+        ENTITY_TELEPORT
+    );
+    // `PLAYER` only meant the movement packet before 1.13.
+    assert_eq!(canonical("PLAYER", SB, v), "PLAYER");
+}
+
+#[test]
+fn maps_1_21_mojang_names() {
+    let v = Some(Version(1, 21, 4));
+    assert_eq!(canonical("MOVE_PLAYER_POS", SB, v), PLAYER_POSITION);
+    assert_eq!(
+        canonical("move_player_pos_rot", SB, v),
+        PLAYER_POSITION_AND_ROTATION
+    );
+    assert_eq!(canonical("MOVE_PLAYER_STATUS_ONLY", SB, v), PLAYER_FLYING);
+    assert_eq!(canonical("Set Player Movement Flags", SB, v), PLAYER_FLYING);
+    assert_eq!(canonical("INTERACT", SB, v), INTERACT_ENTITY);
+    assert_eq!(canonical("ADD_ENTITY", CB, v), SPAWN_ENTITY);
+    assert_eq!(canonical("ENTITY_POSITION_SYNC", CB, v), ENTITY_TELEPORT);
+    assert_eq!(canonical("REMOVE_ENTITIES", CB, v), DESTROY_ENTITIES);
+    assert_eq!(
+        canonical("PLAYER_POSITION", CB, v),
+        PLAYER_POSITION_AND_LOOK
+    );
+    // Retired names are left alone on versions that no longer have them.
+    assert_eq!(canonical("ADD_PLAYER", CB, v), "ADD_PLAYER");
+    assert_eq!(canonical("ADD_PLAYER", CB, None), SPAWN_PLAYER);
+}
+
+#[test]
+fn canonical_names_pass_through() {
+    for name in [PLAYER_POSITION, INTERACT_ENTITY, "KEEP_ALIVE"] {
+        assert_eq!(canonical(name, SB, None), name);
+    }
+    assert_eq!(canonical("player_state", "synthetic", None), "PLAYER_STATE");
+
+    let mut record = serde_json::json!({"dir": "serverbound", "pkt": "POSITION_LOOK"});
+    normalize_record(&mut record, None);
+    assert_eq!(record["pkt"], PLAYER_POSITION_AND_ROTATION);
+}
+
+#[test]
+fn transforms_read_legacy_packet_names() {
+    // 1.12 capture with ProtocolLib names: movement, a target spawn and an attack.
+    let raw = r#"
+{"server_id":"s","session_id":"x","mc_version":"1.12.2"}
+{"ts":1000,"dir":"serverbound","pkt":"POSITION_LOOK","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":64.0,"z":0.0,"yaw":0.0,"pitch":0.0,"on_ground":true}}
+{"ts":1010,"dir":"clientbound","pkt":"SPAWN_ENTITY_LIVING","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"x":0.0,"y":64.0,"z":3.0}}
+{"ts":1020,"dir":"clientbound","pkt":"REL_ENTITY_MOVE_LOOK","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"dx":0.0,"dy":0.0,"dz":1.0,"yaw":180.0}}
+{"ts":1050,"dir":"serverbound","pkt":"USE_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+"#
+    .trim_start();
+
+    let fight = gunzip(&apply_transform("ncp_fight_v1_ndjson_gz", &gzip(raw)).unwrap());
+    let attack = fight.lines().nth(1).expect("attack event");
+    assert!(attack.contains(r#""target_z":4.0"#), "{}", attack);
+
+    let combat = gunzip(&apply_transform("combat_events_v1_ndjson_gz", &gzip(raw)).unwrap());
+    let attack = combat.lines().nth(1).expect("attack event");
+    // The clientbound entity rotation must not be taken for the player's yaw.
+    assert!(attack.contains(r#""player_yaw":0.0"#), "{}", attack);
+}
+
+#[test]
+fn hurt_packets_name_the_hurt_entity() {
+    assert_eq!(canonical("ENTITY_EVENT", CB, None), ENTITY_STATUS);
+    assert_eq!(
+        canonical("PacketPlayOutEntityStatus", CB, None),
+        ENTITY_STATUS
+    );
+
+    let fields = |json: &str| serde_json::from_str::<serde_json::Map<_, _>>(json).unwrap();
+    assert_eq!(
+        hurt_entity(ENTITY_STATUS, &fields(r#"{"entity_id":7,"status":2}"#)),
+        Some(7)
+    );
+    // Status 9: finished using an item.
+    assert_eq!(
+        hurt_entity(ENTITY_STATUS, &fields(r#"{"entity_id":7,"status":9}"#)),
+        None
+    );
+    assert_eq!(
+        hurt_entity(DAMAGE_EVENT, &fields(r#"{"entity_id":7}"#)),
+        Some(7)
+    );
+    assert_eq!(hurt_entity(DAMAGE_EVENT, &fields("{}")), None);
+}
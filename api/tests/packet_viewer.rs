@@ -0,0 +1,116 @@
+use async_anticheat_api::packet_viewer::{scan, PacketFilter};
+use flate2::{write::GzEncoder, Compression};
+use serde_json::json;
+use std::io::Write;
+use uuid::Uuid;
+
+fn gzip(lines: &[String]) -> Vec<u8> {
+    let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+    for line in lines {
+        writeln!(enc, "{}", line).unwrap();
+    }
+    enc.finish().unwrap()
+}
+
+fn collect(raw: &[u8], filter: &PacketFilter, offset: usize, limit: usize) -> Vec<String> {
+    let mut out = Vec::new();
+    let n = scan(raw, filter, offset, limit, |line| {
+        out.push(line.to_string());
+        true
+    })
+    .unwrap();
+    assert_eq!(n, out.len());
+    out
+}
+
+fn batch(player: Uuid, other: Uuid) -> Vec<u8> {
+    gzip(&[
+        json!({"server_id": "srv", "mc_version": "1.12.2"}).to_string(),
+        json!({"ts": 1, "dir": "serverbound", "pkt": "POSITION_LOOK", "uuid": player.to_string()}).to_string(),
+        json!({"ts": 2, "dir": "serverbound", "pkt": "USE_ENTITY", "uuid": player.to_string()}).to_string(),
+        "not json".to_string(),
+        String::new(),
+        json!({"ts": 3, "dir": "serverbound", "pkt": "POSITION_LOOK", "uuid": other.to_string()}).to_string(),
+        json!({"ts": 4, "dir": "clientbound", "pkt": "POSITION", "uuid": player.to_string()}).to_string(),
+        json!({"ts": 5, "dir": "serverbound", "pkt": "PLAYER_POSITION_AND_ROTATION", "uuid": player.to_string()}).to_string(),
+    ])
+}
+
+fn ts(lines: &[String]) -> Vec<i64> {
+    lines
+        .iter()
+        .map(|l| {
+            serde_json::from_str::<serde_json::Value>(l).unwrap()["ts"]
+                .as_i64()
+                .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn unfiltered_scan_skips_metadata_and_invalid_lines() {
+    let raw = batch(Uuid::new_v4(), Uuid::new_v4());
+    let lines = collect(&raw, &PacketFilter::default(), 0, 100);
+    assert_eq!(ts(&lines), vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn lines_are_passed_through_verbatim() {
+    let raw = gzip(&[
+        "{}".to_string(),
+        r#"{"pkt":"KEEP_ALIVE",  "ts":1}"#.to_string(),
+    ]);
+    let lines = collect(&raw, &PacketFilter::default(), 0, 10);
+    assert_eq!(lines, vec![r#"{"pkt":"KEEP_ALIVE",  "ts":1}"#.to_string()]);
+}
+
+#[test]
+fn filters_by_player_and_normalized_packet_name() {
+    let player = Uuid::new_v4();
+    let raw = batch(player, Uuid::new_v4());
+
+    let by_player = PacketFilter::new(Some(player), None);
+    assert_eq!(ts(&collect(&raw, &by_player, 0, 100)), vec![1, 2, 4, 5]);
+
+    // The 1.12 alias and the canonical name select the same packets.
+    let moves = PacketFilter::new(Some(player), Some("PLAYER_POSITION_AND_ROTATION"));
+    assert_eq!(ts(&collect(&raw, &moves, 0, 100)), vec![1, 5]);
+    let moves = PacketFilter::new(Some(player), Some("position_look"));
+    assert_eq!(ts(&collect(&raw, &moves, 0, 100)), vec![1, 5]);
+
+    // A clientbound POSITION is a teleport, not movement.
+    let teleports = PacketFilter::new(None, Some("PLAYER_POSITION_AND_LOOK"));
+    assert_eq!(ts(&collect(&raw, &teleports, 0, 100)), vec![4]);
+
+    let several = PacketFilter::new(None, Some("INTERACT_ENTITY, PLAYER_POSITION_AND_LOOK"));
+    assert_eq!(ts(&collect(&raw, &several, 0, 100)), vec![2, 4]);
+}
+
+#[test]
+fn paginates_by_offset_into_matching_lines() {
+    let raw = batch(Uuid::new_v4(), Uuid::new_v4());
+    let all = PacketFilter::default();
+    assert_eq!(ts(&collect(&raw, &all, 0, 2)), vec![1, 2]);
+    assert_eq!(ts(&collect(&raw, &all, 2, 2)), vec![3, 4]);
+    assert_eq!(ts(&collect(&raw, &all, 4, 2)), vec![5]);
+    assert!(collect(&raw, &all, 5, 2).is_empty());
+}
+
+#[test]
+fn stops_when_the_receiver_goes_away() {
+    let raw = batch(Uuid::new_v4(), Uuid::new_v4());
+    let mut seen = 0;
+    let emitted = scan(&raw, &PacketFilter::default(), 0, 100, |_| {
+        seen += 1;
+        seen < 2
+    })
+    .unwrap();
+    assert_eq!(seen, 2);
+    assert_eq!(emitted, 1);
+}
+
+#[test]
+fn rejects_non_gzip_objects() {
+    let res = scan(b"plain text", &PacketFilter::default(), 0, 10, |_| true);
+    assert!(res.is_err());
+}
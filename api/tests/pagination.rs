@@ -0,0 +1,117 @@
+use async_anticheat_api::pagination::{
+    limit, page, parse, parse_as, Cursor, PageCursor, RankCursor,
+};
+use chrono::{Duration, TimeZone, Utc};
+use uuid::Uuid;
+
+fn cursor(n: i64) -> Cursor {
+    Cursor {
+        at: Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap() - Duration::microseconds(n),
+        id: Uuid::from_u128(n as u128),
+    }
+}
+
+#[test]
+fn cursors_round_trip_opaquely() {
+    let c = cursor(123_456);
+    let encoded = c.encode();
+    assert_eq!(encoded.len(), 48);
+    assert!(!encoded.contains(&c.id.to_string()));
+    assert_eq!(Cursor::decode(&encoded).unwrap(), c);
+
+    assert!(Cursor::decode("garbage").is_err());
+    assert!(Cursor::decode(&encoded[..46]).is_err());
+    assert!(Cursor::decode("123:00000000-0000-0000-0000-000000000001").is_err());
+}
+
+#[test]
+fn cursor_parameters_are_optional_but_must_be_valid() {
+    assert_eq!(parse(None).unwrap(), None);
+    assert_eq!(parse(Some(" ")).unwrap(), None);
+    assert_eq!(parse(Some(&cursor(1).encode())).unwrap(), Some(cursor(1)));
+    assert!(parse(Some("not-a-cursor")).is_err());
+}
+
+#[test]
+fn limits_are_clamped() {
+    assert_eq!(limit(None, 50, 100), 50);
+    assert_eq!(limit(Some(500), 50, 100), 100);
+    assert_eq!(limit(Some(0), 50, 100), 1);
+    assert_eq!(limit(Some(-5), 50, 100), 1);
+}
+
+#[test]
+fn pages_end_with_the_cursor_of_their_last_row() {
+    // limit + 1 rows fetched: more follow.
+    let rows: Vec<i64> = (1..=4).collect();
+    let (rows, next) = page(rows, 3, |n| cursor(*n));
+    assert_eq!(rows, vec![1, 2, 3]);
+    assert_eq!(next, Some(cursor(3).encode()));
+
+    // Last page.
+    let (rows, next) = page(vec![4], 3, |n| cursor(*n));
+    assert_eq!(rows, vec![4]);
+    assert_eq!(next, None);
+
+    let (rows, next) = page(Vec::<i64>::new(), 3, |n| cursor(*n));
+    assert!(rows.is_empty());
+    assert_eq!(next, None);
+
+    // Exactly `limit` rows: the page is full but nothing follows.
+    let (rows, next) = page(vec![1, 2, 3], 3, |n| cursor(*n));
+    assert_eq!(rows.len(), 3);
+    assert_eq!(next, None);
+}
+
+/// What the list queries do: rows strictly after the cursor in `(key, id)` descending order,
+/// `limit + 1` of them.
+fn fetch(all: &[(i64, Uuid)], after: Option<RankCursor>, limit: i64) -> Vec<(i64, Uuid)> {
+    let mut rows: Vec<(i64, Uuid)> = all
+        .iter()
+        .copied()
+        .filter(|row| after.is_none_or(|c| *row < (c.rank, c.id)))
+        .collect();
+    rows.sort_by(|a, b| b.cmp(a));
+    rows.truncate(limit as usize + 1);
+    rows
+}
+
+#[test]
+fn walking_pages_visits_every_row_once_even_with_ties_at_the_boundary() {
+    // Players ranked by occurrences: the counts tie across every page boundary.
+    let all: Vec<(i64, Uuid)> = (1..=10u128)
+        .map(|n| (if n <= 5 { 7 } else { 2 }, Uuid::from_u128(n)))
+        .collect();
+    let mut expected = all.clone();
+    expected.sort_by(|a, b| b.cmp(a));
+
+    for limit in 1..=11 {
+        let mut seen = Vec::new();
+        let mut cursor: Option<RankCursor> = None;
+        let mut pages = 0;
+        loop {
+            let (rows, next) = page(fetch(&all, cursor, limit), limit, |(rank, id)| RankCursor {
+                rank: *rank,
+                id: *id,
+            });
+            pages += 1;
+            assert!(rows.len() as i64 <= limit);
+            seen.extend(rows);
+            let Some(next) = next else { break };
+            cursor = parse_as(Some(&next)).unwrap();
+        }
+        assert_eq!(seen, expected, "limit {}", limit);
+        assert_eq!(pages, (10 + limit - 1) / limit, "limit {}", limit);
+    }
+}
+
+#[test]
+fn rank_cursors_round_trip() {
+    let c = RankCursor {
+        rank: 42,
+        id: Uuid::from_u128(7),
+    };
+    assert_eq!(RankCursor::decode(&c.encode()).unwrap(), c);
+    assert_eq!(parse_as::<RankCursor>(None).unwrap(), None);
+    assert!(parse_as::<RankCursor>(Some("zz")).is_err());
+}
@@ -0,0 +1,29 @@
+use async_anticheat_api::pending_actions::{acked_ids, ACK_HEADER};
+use axum::http::{HeaderMap, HeaderValue};
+use uuid::Uuid;
+
+fn headers(value: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(ACK_HEADER, HeaderValue::from_str(value).unwrap());
+    headers
+}
+
+#[test]
+fn acks_are_comma_separated_ids() {
+    let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+    assert_eq!(acked_ids(&headers(&format!("{}, {}", a, b))), vec![a, b]);
+}
+
+#[test]
+fn malformed_or_missing_acks_acknowledge_nothing_else() {
+    let a = Uuid::new_v4();
+    assert_eq!(acked_ids(&headers(&format!("nope,{},", a))), vec![a]);
+    assert!(acked_ids(&headers("")).is_empty());
+    assert!(acked_ids(&HeaderMap::new()).is_empty());
+}
+
+#[test]
+fn one_request_acknowledges_a_bounded_number_of_actions() {
+    let many: Vec<String> = (0..500).map(|_| Uuid::new_v4().to_string()).collect();
+    assert_eq!(acked_ids(&headers(&many.join(","))).len(), 100);
+}
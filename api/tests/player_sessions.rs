@@ -0,0 +1,79 @@
+use async_anticheat_api::player_sessions::{
+    marker, segments, session_records, Marker, Segment, SESSION_GAP_MS,
+};
+use flate2::{write::GzEncoder, Compression};
+use serde_json::json;
+use uuid::Uuid;
+
+const P1: &str = "00000000-0000-0000-0000-000000000001";
+const P2: &str = "00000000-0000-0000-0000-000000000002";
+
+fn seg(start_ts: u64, end_ts: u64, joined: bool, quit: bool) -> Segment {
+    Segment {
+        start_ts,
+        end_ts,
+        joined,
+        quit,
+    }
+}
+
+#[test]
+fn markers_from_packet_names_and_state_events() {
+    assert_eq!(marker(&json!({"pkt": "player_join"})), Some(Marker::Join));
+    assert_eq!(marker(&json!({"pkt": "DISCONNECT"})), Some(Marker::Quit));
+    assert_eq!(
+        marker(&json!({"pkt": "PLAYER_STATE", "fields": {"event": "quit"}})),
+        Some(Marker::Quit)
+    );
+    assert_eq!(
+        marker(&json!({"pkt": "PLAYER_STATE", "fields": {"world": "world"}})),
+        None
+    );
+    assert_eq!(marker(&json!({"pkt": "POSITION"})), None);
+}
+
+#[test]
+fn segments_split_on_markers_and_gaps() {
+    let p1 = Uuid::parse_str(P1).unwrap();
+    let p2 = Uuid::parse_str(P2).unwrap();
+    let gap = SESSION_GAP_MS + 1;
+    let by_player = segments(vec![
+        (2_000, p1, None),
+        (1_000, p1, Some(Marker::Join)),
+        (3_000, p1, Some(Marker::Quit)),
+        (4_000, p1, Some(Marker::Join)),
+        (5_000, p1, None),
+        (5_000 + gap, p1, None),
+        (10, p2, None),
+    ]);
+
+    assert_eq!(
+        by_player[&p1],
+        vec![
+            seg(1_000, 3_000, true, true),
+            seg(4_000, 5_000, true, false),
+            seg(5_000 + gap, 5_000 + gap, false, false),
+        ]
+    );
+    assert_eq!(by_player[&p2], vec![seg(10, 10, false, false)]);
+}
+
+#[test]
+fn session_records_skip_metadata_and_anonymous_lines() {
+    let ndjson = [
+        json!({"server_id": "s1", "session_id": "sess"}).to_string(),
+        json!({"ts": 5, "uuid": P1, "pkt": "LOGIN"}).to_string(),
+        json!({"ts": 6, "pkt": "POSITION"}).to_string(),
+        json!({"ts": 7, "uuid": P1, "pkt": "POSITION"}).to_string(),
+    ]
+    .join("\n");
+    let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+    std::io::Write::write_all(&mut enc, ndjson.as_bytes()).unwrap();
+    let raw = enc.finish().unwrap();
+
+    let p1 = Uuid::parse_str(P1).unwrap();
+    assert_eq!(
+        session_records(&raw).unwrap(),
+        vec![(5, p1, Some(Marker::Join)), (7, p1, None)]
+    );
+}
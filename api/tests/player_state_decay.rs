@@ -0,0 +1,36 @@
+use async_anticheat_api::player_state_decay::{cutoff, summarize};
+use chrono::{Duration, TimeZone, Utc};
+use serde_json::json;
+
+#[test]
+fn summary_keeps_vl_and_scalar_fields() {
+    let state = json!({
+        "vl": 15.5,
+        "buffer": 3,
+        "flagged": true,
+        "last_target": "zombie",
+        "recent_attacks": [{"ts": 1702499900, "yaw_diff": 30.5}],
+        "nested": {"x": 1},
+    });
+    let (vl, summary) = summarize(&state);
+    assert_eq!(vl, Some(15.5));
+    assert_eq!(summary, json!({"vl": 15.5, "buffer": 3, "flagged": true}));
+}
+
+#[test]
+fn summary_of_state_without_vl() {
+    let (vl, summary) = summarize(&json!({"vl": "high", "count": 2}));
+    assert_eq!(vl, None);
+    assert_eq!(summary, json!({"count": 2}));
+
+    let (vl, summary) = summarize(&json!([1, 2, 3]));
+    assert_eq!(vl, None);
+    assert_eq!(summary, json!({}));
+}
+
+#[test]
+fn cutoff_is_at_least_a_day() {
+    let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+    assert_eq!(cutoff(now, 30), now - Duration::days(30));
+    assert_eq!(cutoff(now, 0), now - Duration::days(1));
+}
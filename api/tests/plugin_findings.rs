@@ -0,0 +1,55 @@
+use async_anticheat_api::routes::plugin_findings::{
+    compact_title, risk, PluginFinding, PluginFindingsResponse, MAX_FINDINGS, MAX_TITLE_CHARS,
+};
+use uuid::Uuid;
+
+#[test]
+fn long_titles_are_cut() {
+    assert_eq!(compact_title("  Reach 3.4 blocks "), "Reach 3.4 blocks");
+    let long = "é".repeat(100);
+    let cut = compact_title(&long);
+    assert_eq!(cut.chars().count(), MAX_TITLE_CHARS);
+    assert!(cut.ends_with('…'));
+}
+
+#[test]
+fn risk_saturates_at_100() {
+    assert_eq!(risk(0.0), 0);
+    assert_eq!(risk(-3.0), 0);
+    assert_eq!(risk(20.0), 63);
+    assert_eq!(risk(1e6), 100);
+}
+
+#[test]
+fn full_response_fits_the_size_budget() {
+    let response = PluginFindingsResponse {
+        ok: true,
+        player_uuid: Uuid::new_v4(),
+        player_name: Some("A_Very_Long_Name".to_string()),
+        risk: 87,
+        watchlisted: true,
+        watchlist_reason: Some("reported by several players for reach".to_string()),
+        findings: (0..MAX_FINDINGS)
+            .map(|_| PluginFinding {
+                detector: "combat_advanced_reach_distance".to_string(),
+                severity: "critical".to_string(),
+                title: compact_title(&"x".repeat(500)),
+                count: 1234,
+                seconds_ago: 86_400,
+            })
+            .collect(),
+    };
+    let body = serde_json::to_vec(&response).unwrap();
+    assert!(body.len() < 2048, "{} bytes", body.len());
+
+    let quiet = PluginFindingsResponse {
+        watchlisted: false,
+        watchlist_reason: None,
+        player_name: None,
+        findings: Vec::new(),
+        ..response
+    };
+    let body = serde_json::to_value(&quiet).unwrap();
+    assert!(body.get("watchlist_reason").is_none());
+    assert!(body.get("player_name").is_none());
+}
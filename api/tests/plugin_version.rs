@@ -0,0 +1,41 @@
+use async_anticheat_api::plugin_version::{Version, VersionPolicy};
+
+#[test]
+fn parse_accepts_prefixes_suffixes_and_short_forms() {
+    assert_eq!(Version::parse("1.4.2"), Some(Version(1, 4, 2)));
+    assert_eq!(Version::parse(" v2.0 "), Some(Version(2, 0, 0)));
+    assert_eq!(Version::parse("1.5.0-SNAPSHOT"), Some(Version(1, 5, 0)));
+    assert_eq!(Version::parse("3+build.7"), Some(Version(3, 0, 0)));
+    assert_eq!(Version::parse("1.2.3.4"), None);
+    assert_eq!(Version::parse("dev"), None);
+    assert_eq!(Version::parse(""), None);
+}
+
+#[test]
+fn policy_requires_recommends_or_stays_quiet() {
+    let policy = VersionPolicy {
+        min_supported: Version::parse("1.2.0"),
+        recommended: Version::parse("1.4.0"),
+    };
+
+    let required = policy.check(Some("1.1.9")).unwrap();
+    assert!(required.update_required && required.update_recommended);
+    assert_eq!(required.min_supported_version.as_deref(), Some("1.2.0"));
+
+    let recommended = policy.check(Some("1.3.0")).unwrap();
+    assert!(!recommended.update_required && recommended.update_recommended);
+    assert_eq!(recommended.current_version.as_deref(), Some("1.3.0"));
+
+    assert_eq!(policy.check(Some("1.4.0")), None);
+
+    // Unknown versions can't be judged: nudged, never rejected.
+    let unknown = policy.check(None).unwrap();
+    assert!(!unknown.update_required && unknown.update_recommended);
+    assert!(!policy.check(Some("nightly")).unwrap().update_required);
+}
+
+#[test]
+fn no_policy_means_no_advice() {
+    assert_eq!(VersionPolicy::default().check(None), None);
+    assert_eq!(VersionPolicy::default().check(Some("0.0.1")), None);
+}
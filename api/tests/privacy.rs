@@ -0,0 +1,172 @@
+use std::io::{Read, Write};
+
+use async_anticheat_api::privacy::{
+    pseudonymize_batch, pseudonymize_player, pseudonymous_name, scrub_batch, PLAYER_TABLES,
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use sha2::Digest;
+use uuid::Uuid;
+
+const ALEX: &str = "11111111-1111-1111-1111-111111111111";
+const STEVE: &str = "22222222-2222-2222-2222-222222222222";
+
+fn gz(lines: &[String]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut encoder = GzEncoder::new(&mut out, Compression::default());
+    for line in lines {
+        encoder.write_all(line.as_bytes()).unwrap();
+        encoder.write_all(b"\n").unwrap();
+    }
+    encoder.finish().unwrap();
+    out
+}
+
+fn gunzip(data: &[u8]) -> String {
+    let mut out = String::new();
+    GzDecoder::new(data).read_to_string(&mut out).unwrap();
+    out
+}
+
+fn batch() -> Vec<u8> {
+    gz(&[
+        format!(
+            r#"{{"plugin_version":"1.4.0","client_types":{{"{}":"java","{}":"bedrock"}},"client_protocols":{{"{}":47}}}}"#,
+            ALEX, STEVE, ALEX
+        ),
+        format!(
+            r#"{{"ts":1,"uuid":"{}","name":"Alex","pkt":"FLYING"}}"#,
+            ALEX
+        ),
+        format!(
+            r#"{{"ts":2,"uuid":"{}","name":"Steve","pkt":"FLYING"}}"#,
+            STEVE
+        ),
+        format!(
+            r#"{{"ts":3,"uuid":"{}","name":"Alex","pkt":"ARM_ANIMATION"}}"#,
+            ALEX
+        ),
+        "not json".to_string(),
+    ])
+}
+
+#[test]
+fn removes_the_players_records_and_client_type() {
+    let alex = Uuid::parse_str(ALEX).unwrap();
+    let scrubbed = scrub_batch(&batch(), alex).unwrap().unwrap();
+    assert_eq!(scrubbed.records_removed, 2);
+
+    let text = gunzip(&scrubbed.data);
+    assert!(!text.contains(ALEX));
+    assert!(text.contains(STEVE));
+    assert!(text.contains("not json"));
+    assert_eq!(text.lines().count(), 3);
+    assert_eq!(
+        scrubbed.sha256,
+        hex::encode(sha2::Sha256::digest(text.as_bytes()))
+    );
+}
+
+#[test]
+fn batches_without_the_player_are_left_alone() {
+    let other = Uuid::parse_str("33333333-3333-3333-3333-333333333333").unwrap();
+    assert!(scrub_batch(&batch(), other).unwrap().is_none());
+}
+
+#[test]
+fn scrubbing_twice_changes_nothing() {
+    let alex = Uuid::parse_str(ALEX).unwrap();
+    let once = scrub_batch(&batch(), alex).unwrap().unwrap();
+    assert!(scrub_batch(&once.data, alex).unwrap().is_none());
+}
+
+#[test]
+fn pseudonyms_are_stable_per_server_and_differ_across_servers() {
+    let alex = Uuid::parse_str(ALEX).unwrap();
+    let a = pseudonymize_player("secret", "server-a", alex);
+    assert_eq!(a, pseudonymize_player("secret", "server-a", alex));
+    assert_ne!(a, alex);
+    assert_ne!(a, pseudonymize_player("secret", "server-b", alex));
+    assert_ne!(a, pseudonymize_player("other-secret", "server-a", alex));
+    assert_eq!(a.get_version_num(), 8);
+}
+
+#[test]
+fn hashed_batches_hold_no_player_uuids() {
+    let alex = Uuid::parse_str(ALEX).unwrap();
+    let hashed = pseudonymize_batch(&batch(), "secret", "server-a").unwrap();
+    let text = gunzip(&hashed);
+    assert!(!text.contains(ALEX));
+    assert!(!text.contains(STEVE));
+    assert!(text.contains("not json"));
+    assert_eq!(text.lines().count(), 5);
+
+    // Erasure finds the player under their pseudonym.
+    let pseudonym = pseudonymize_player("secret", "server-a", alex);
+    assert!(text.contains(&pseudonym.to_string()));
+    let scrubbed = scrub_batch(&hashed, pseudonym).unwrap().unwrap();
+    assert_eq!(scrubbed.records_removed, 2);
+}
+
+#[test]
+fn hashed_batches_hold_no_player_names() {
+    let hashed = pseudonymize_batch(&batch(), "secret", "server-a").unwrap();
+    let text = gunzip(&hashed);
+    assert!(!text.contains("Alex"));
+    assert!(!text.contains("Steve"));
+
+    // Every record carries the name derived from its pseudonym, which ingest stores as the
+    // player's username.
+    let alex = pseudonymize_player("secret", "server-a", Uuid::parse_str(ALEX).unwrap());
+    let names: Vec<String> = text
+        .lines()
+        .skip(1)
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|record| record["uuid"] == alex.to_string())
+        .map(|record| record["name"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec![pseudonymous_name(alex); 2]);
+    assert!(pseudonymous_name(alex).len() <= 16);
+}
+
+#[test]
+fn names_without_a_player_uuid_are_dropped() {
+    let hashed = pseudonymize_batch(
+        &gz(&[
+            "{}".to_string(),
+            r#"{"ts":1,"uuid":"not-a-uuid","name":"Alex","pkt":"FLYING"}"#.to_string(),
+            r#"{"ts":2,"pkt":"KEEP_ALIVE"}"#.to_string(),
+        ]),
+        "secret",
+        "server-a",
+    )
+    .unwrap();
+    let text = gunzip(&hashed);
+    assert!(!text.contains("Alex"));
+    assert!(text.contains("not-a-uuid"));
+    assert!(text.contains("KEEP_ALIVE"));
+}
+
+#[test]
+fn erasure_covers_every_table_keyed_by_player() {
+    // Handled separately (findings, unlinked sessions), fixture players or the erasure job itself.
+    let handled = [
+        "findings",
+        "sessions",
+        "simulation_findings",
+        "player_deletion_jobs",
+    ];
+    let schema = include_str!("../schema.sql");
+    let mut table = "";
+    for line in schema.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("create table if not exists public.") {
+            table = rest.split_whitespace().next().unwrap_or_default();
+        } else if line.starts_with("player_uuid ") {
+            assert!(
+                PLAYER_TABLES.contains(&table) || handled.contains(&table),
+                "player erasure misses public.{}",
+                table
+            );
+        }
+    }
+}
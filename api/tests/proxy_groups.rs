@@ -0,0 +1,33 @@
+use async_anticheat_api::proxy_groups::{normalize_proxy_id, proxy_id_from_headers};
+use axum::http::{HeaderMap, HeaderValue};
+
+#[test]
+fn normalize_accepts_typical_proxy_ids() {
+    assert_eq!(
+        normalize_proxy_id("  velocity-eu.1 ").as_deref(),
+        Some("velocity-eu.1")
+    );
+    assert_eq!(
+        normalize_proxy_id("bungee:main_2").as_deref(),
+        Some("bungee:main_2")
+    );
+}
+
+#[test]
+fn normalize_rejects_empty_oversized_and_odd_characters() {
+    assert_eq!(normalize_proxy_id("   "), None);
+    assert_eq!(normalize_proxy_id(&"p".repeat(65)), None);
+    assert_eq!(normalize_proxy_id("proxy/../other"), None);
+    assert_eq!(normalize_proxy_id("my proxy"), None);
+}
+
+#[test]
+fn header_is_optional() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(proxy_id_from_headers(&headers), None);
+    headers.insert("x-proxy-id", HeaderValue::from_static("lobby-proxy"));
+    assert_eq!(
+        proxy_id_from_headers(&headers).as_deref(),
+        Some("lobby-proxy")
+    );
+}
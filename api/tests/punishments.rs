@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use async_anticheat_api::punishments::{
+    ban_exports, ban_until, evidence_url, BanRule, ExportMode, StoredFinding,
+};
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+fn finding(player: Uuid, detector: &str, severity: &str, confidence: Option<f64>) -> StoredFinding {
+    StoredFinding {
+        finding_id: Uuid::new_v4(),
+        player_uuid: player,
+        detector_name: detector.to_string(),
+        severity: severity.to_string(),
+        title: format!("{} flagged", detector),
+        confidence,
+    }
+}
+
+fn rule() -> BanRule {
+    BanRule {
+        enabled: true,
+        mode: ExportMode::Webhook,
+        webhook_url: Some("https://bans.example.com/api/ban".to_string()),
+        min_confidence: Some(0.8),
+        ..BanRule::default()
+    }
+}
+
+#[test]
+fn rule_fires_on_trigger_severities_above_the_confidence_floor() {
+    let rule = rule();
+    assert!(rule.fires("critical", Some(0.9)));
+    assert!(rule.fires("critical", None));
+    assert!(!rule.fires("critical", Some(0.5)));
+    assert!(!rule.fires("high", Some(1.0)));
+    assert!(!BanRule::default().fires("critical", None));
+}
+
+#[test]
+fn one_ban_per_player_with_triggering_findings_as_evidence() {
+    let cheater = Uuid::new_v4();
+    let other = Uuid::new_v4();
+    let findings = vec![
+        finding(
+            cheater,
+            "combat_core_reach_distance",
+            "critical",
+            Some(0.95),
+        ),
+        finding(cheater, "movement_core_speed_horizontal", "critical", None),
+        finding(cheater, "combat_core_aim_snap", "high", Some(0.99)),
+        finding(other, "combat_core_reach_distance", "critical", Some(0.4)),
+    ];
+    let names = BTreeMap::from([(cheater, "Notch".to_string())]);
+    let now = Utc.with_ymd_and_hms(2026, 5, 1, 0, 0, 0).unwrap();
+
+    let bans = ban_exports(&rule(), "srv", &findings, &names, now);
+    assert_eq!(bans.len(), 1);
+    let ban = &bans[0];
+    assert_eq!(ban.uuid, cheater.to_string());
+    assert_eq!(ban.name.as_deref(), Some("Notch"));
+    assert_eq!(ban.time, now.timestamp_millis());
+    assert_eq!(ban.until, -1);
+    assert_eq!(
+        ban.reason,
+        "Unfair advantage (combat_core_reach_distance, movement_core_speed_horizontal)"
+    );
+    assert_eq!(ban.evidence.len(), 2);
+    assert_eq!(
+        ban.evidence_url,
+        format!(
+            "https://asyncanticheat.com/dashboard/findings?player={}",
+            cheater
+        )
+    );
+
+    let body = serde_json::to_value(ban).unwrap();
+    assert_eq!(body["type"], "ban");
+    assert_eq!(body["banned_by_name"], "AsyncAnticheat");
+    assert!(body["evidence"][1].get("confidence").is_none());
+}
+
+#[test]
+fn temporary_bans_and_evidence_links() {
+    assert_eq!(ban_until(1_000, Some(60)), 61_000);
+    assert_eq!(ban_until(1_000, Some(0)), -1);
+    assert_eq!(ban_until(1_000, None), -1);
+
+    let player = Uuid::nil();
+    assert_eq!(
+        evidence_url(Some("https://panel.example.net/"), player),
+        format!(
+            "https://panel.example.net/dashboard/findings?player={}",
+            player
+        )
+    );
+    assert_eq!(ExportMode::parse(" Queue "), Some(ExportMode::Queue));
+    assert_eq!(ExportMode::parse("litebans"), None);
+}
@@ -0,0 +1,10 @@
+use async_anticheat_api::replay::{parse_replay_session, replay_session};
+use uuid::Uuid;
+
+#[test]
+fn replay_session_round_trips() {
+    let (replay_id, module_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let session = replay_session(replay_id, module_id);
+    assert_eq!(parse_replay_session(&session), Some((replay_id, module_id)));
+    assert_eq!(parse_replay_session("session-1"), None);
+}
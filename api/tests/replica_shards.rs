@@ -0,0 +1,44 @@
+use async_anticheat_api::replica_shards::owner;
+
+fn members(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn every_server_has_one_owner() {
+    assert_eq!(owner(&[], "srv"), None);
+    let three = members(&["api-a", "api-b", "api-c"]);
+    assert_eq!(owner(&three, "srv-1"), owner(&three, "srv-1"));
+
+    // Member order doesn't matter.
+    let shuffled = members(&["api-c", "api-a", "api-b"]);
+    for i in 0..50 {
+        let server = format!("srv-{}", i);
+        assert_eq!(owner(&three, &server), owner(&shuffled, &server));
+    }
+}
+
+#[test]
+fn servers_spread_and_only_the_leavers_share_moves() {
+    let three = members(&["api-a", "api-b", "api-c"]);
+    let two = members(&["api-a", "api-b"]);
+    let servers: Vec<String> = (0..600).map(|i| format!("srv-{}", i)).collect();
+
+    let owned_by_c = servers
+        .iter()
+        .filter(|s| owner(&three, s) == Some("api-c"))
+        .count();
+    assert!(
+        (120..280).contains(&owned_by_c),
+        "api-c owns {}",
+        owned_by_c
+    );
+
+    for server in &servers {
+        let before = owner(&three, server).unwrap();
+        let after = owner(&two, server).unwrap();
+        if before != "api-c" {
+            assert_eq!(before, after, "{} moved without its owner leaving", server);
+        }
+    }
+}
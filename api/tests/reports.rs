@@ -0,0 +1,138 @@
+use async_anticheat_api::reports::{
+    period_end, validate_recipients, DetectorSummary, IngestVolume, ModuleUptime, PlayerSummary,
+    ReportFormat, ReportSchedule, WeeklyReport,
+};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use uuid::Uuid;
+
+fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+}
+
+#[test]
+fn period_end_is_the_latest_scheduled_time_not_after_now() {
+    // 2024-03-06 is a Wednesday.
+    let wednesday_noon = at(2024, 3, 6, 12, 0);
+    assert_eq!(period_end(1, 9, wednesday_noon), at(2024, 3, 4, 9, 0));
+    assert_eq!(period_end(3, 9, wednesday_noon), at(2024, 3, 6, 9, 0));
+    // Later today: last week's.
+    assert_eq!(period_end(3, 18, wednesday_noon), at(2024, 2, 28, 18, 0));
+    assert_eq!(period_end(7, 0, wednesday_noon), at(2024, 3, 3, 0, 0));
+    assert_eq!(period_end(3, 12, wednesday_noon), wednesday_noon);
+}
+
+#[test]
+fn schedule_is_due_once_per_week() {
+    let now = at(2024, 3, 6, 12, 0);
+    let mut schedule = ReportSchedule {
+        enabled: true,
+        weekday: 1,
+        hour_utc: 9,
+        ..ReportSchedule::default()
+    };
+    assert_eq!(schedule.due(now), Some(at(2024, 3, 4, 9, 0)));
+
+    schedule.last_period_end = Some(at(2024, 3, 4, 9, 0));
+    assert_eq!(schedule.due(now), None);
+    assert_eq!(schedule.due(at(2024, 3, 11, 8, 59)), None);
+    assert_eq!(
+        schedule.due(at(2024, 3, 11, 9, 0)),
+        Some(at(2024, 3, 11, 9, 0))
+    );
+
+    // After a long downtime only the latest week is due.
+    assert_eq!(
+        schedule.due(at(2024, 4, 2, 10, 0)),
+        Some(at(2024, 4, 1, 9, 0))
+    );
+
+    schedule.enabled = false;
+    assert_eq!(schedule.due(at(2024, 3, 11, 9, 0)), None);
+}
+
+#[test]
+fn recipients_are_trimmed_and_checked() {
+    assert_eq!(
+        validate_recipients(&[" admin@example.com ".to_string()]).unwrap(),
+        vec!["admin@example.com".to_string()]
+    );
+    for bad in [
+        "",
+        "admin",
+        "@example.com",
+        "admin@localhost",
+        "a b@example.com",
+    ] {
+        assert!(validate_recipients(&[bad.to_string()]).is_err(), "{}", bad);
+    }
+    let many: Vec<String> = (0..11).map(|i| format!("m{}@example.com", i)).collect();
+    assert!(validate_recipients(&many).is_err());
+}
+
+#[test]
+fn report_format_parses() {
+    assert_eq!(ReportFormat::parse(" HTML "), Some(ReportFormat::Html));
+    assert_eq!(ReportFormat::parse("json"), Some(ReportFormat::Json));
+    assert_eq!(ReportFormat::parse("pdf"), None);
+}
+
+fn report() -> WeeklyReport {
+    let end = at(2024, 3, 4, 9, 0);
+    WeeklyReport {
+        server_id: "srv".to_string(),
+        server_name: Some("Survival <EU>".to_string()),
+        period_start: end - Duration::days(7),
+        period_end: end,
+        findings: 42,
+        players_flagged: 3,
+        top_detectors: vec![DetectorSummary {
+            detector_name: "combat_reach".to_string(),
+            findings: 30,
+            occurrences: 120,
+            players: 2,
+        }],
+        top_players: vec![PlayerSummary {
+            player_uuid: Uuid::nil(),
+            player_name: Some("Steve&Co".to_string()),
+            findings: 25,
+            detectors: 2,
+            highest_severity: "high".to_string(),
+        }],
+        modules: vec![ModuleUptime {
+            module_id: Uuid::nil(),
+            name: "combat_core".to_string(),
+            enabled: true,
+            dispatches: 1000,
+            succeeded: 990,
+            failed: 10,
+            timed_out: 0,
+            uptime_percent: Some(99.0),
+        }],
+        ingest: IngestVolume {
+            batches: 1000,
+            payload_bytes: 3 * 1024 * 1024,
+            events: 500_000,
+            quarantined: 1,
+        },
+    }
+}
+
+#[test]
+fn html_report_escapes_names_and_lists_sections() {
+    let html = report().render_html();
+    assert!(html.contains("Survival &lt;EU&gt;"));
+    assert!(!html.contains("Survival <EU>"));
+    assert!(html.contains("Steve&amp;Co"));
+    assert!(html.contains("combat_reach"));
+    assert!(html.contains("99.00%"));
+    assert!(html.contains("1000 batches (3.0 MiB)"));
+}
+
+#[test]
+fn json_report_round_trips() {
+    let json = serde_json::to_value(report()).unwrap();
+    assert_eq!(json["findings"], 42);
+    assert_eq!(json["top_detectors"][0]["detector_name"], "combat_reach");
+    let back: WeeklyReport = serde_json::from_value(json).unwrap();
+    assert_eq!(back.top_players[0].player_name.as_deref(), Some("Steve&Co"));
+}
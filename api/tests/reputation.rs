@@ -0,0 +1,21 @@
+use async_anticheat_api::reputation::{score, SCORE_SCALE};
+
+#[test]
+fn scores_saturate_below_100() {
+    assert_eq!(score(0.0), 0.0);
+    // Negative weights cannot come out of the aggregation, but must not go below zero either.
+    assert_eq!(score(-5.0), 0.0);
+    assert!((score(SCORE_SCALE) - 63.212).abs() < 1e-3);
+    assert!(score(10.0) < score(20.0));
+    assert!(score(1_000.0) > 99.99);
+    assert!(score(1e9) <= 100.0);
+}
+
+#[test]
+fn each_extra_finding_adds_less_than_the_last() {
+    let gains: Vec<f64> = [0.0, 10.0, 20.0, 30.0, 40.0]
+        .windows(2)
+        .map(|w| score(w[1]) - score(w[0]))
+        .collect();
+    assert!(gains.windows(2).all(|g| g[1] < g[0]), "{:?}", gains);
+}
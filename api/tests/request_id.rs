@@ -0,0 +1,62 @@
+use async_anticheat_api::{error::ApiError, request_id};
+use axum::{middleware, routing::get, Router};
+
+fn serve(app: Router) -> String {
+    let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    format!("http://{}", addr)
+}
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/ok",
+            get(|| async { request_id::current().unwrap_or_default() }),
+        )
+        .route(
+            "/fail",
+            get(|| async { Err::<String, _>(ApiError::Internal) }),
+        )
+        .layer(middleware::from_fn(request_id::propagate))
+}
+
+#[tokio::test]
+async fn assigns_an_id_and_echoes_it_in_errors() {
+    let base = serve(app());
+    let http = reqwest::Client::new();
+
+    let resp = http.get(format!("{}/fail", base)).send().await.unwrap();
+    assert_eq!(resp.status().as_u16(), 500);
+    let header = resp.headers()["x-request-id"].to_str().unwrap().to_string();
+    assert!(uuid::Uuid::parse_str(&header).is_ok());
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["request_id"], header.as_str());
+    assert_eq!(body["code"], "internal");
+
+    let resp = http.get(format!("{}/ok", base)).send().await.unwrap();
+    let header = resp.headers()["x-request-id"].to_str().unwrap().to_string();
+    assert_eq!(resp.text().await.unwrap(), header);
+}
+
+#[tokio::test]
+async fn propagates_a_caller_supplied_id() {
+    let base = serve(app());
+    let resp = reqwest::Client::new()
+        .get(format!("{}/ok", base))
+        .header("x-request-id", "proxy-abc-123")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.headers()["x-request-id"], "proxy-abc-123");
+    assert_eq!(resp.text().await.unwrap(), "proxy-abc-123");
+}
+
+#[test]
+fn sanitize_rejects_unusable_ids() {
+    assert_eq!(request_id::sanitize(" abc "), Some("abc".to_string()));
+    assert_eq!(request_id::sanitize(""), None);
+    assert_eq!(request_id::sanitize("has space"), None);
+    assert_eq!(request_id::sanitize(&"x".repeat(129)), None);
+    assert!(request_id::current().is_none());
+}
@@ -0,0 +1,61 @@
+use std::io::Write;
+
+use async_anticheat_api::sampling::{batch_players, keeps, validate_rate, weight, Decision};
+use flate2::{write::GzEncoder, Compression};
+use uuid::Uuid;
+
+const ALEX: &str = "11111111-1111-1111-1111-111111111111";
+const STEVE: &str = "22222222-2222-2222-2222-222222222222";
+
+fn gzip(s: &str) -> Vec<u8> {
+    let mut e = GzEncoder::new(Vec::new(), Compression::default());
+    e.write_all(s.as_bytes()).unwrap();
+    e.finish().unwrap()
+}
+
+#[test]
+fn batch_players_are_distinct_and_skip_the_metadata_line() {
+    let raw = format!(
+        "{{\"server_id\":\"s\",\"uuid\":\"{steve}\"}}\n\
+         {{\"ts\":1,\"uuid\":\"{alex}\",\"pkt\":\"PLAYER_POSITION\"}}\n\
+         {{\"ts\":2,\"uuid\":\"{alex}\",\"pkt\":\"PLAYER_ROTATION\"}}\n\
+         {{\"ts\":3,\"uuid\":\"not-a-uuid\"}}\n\
+         {{\"ts\":4,\"pkt\":\"KEEP_ALIVE\"}}\n\
+         not json\n",
+        alex = ALEX,
+        steve = STEVE
+    );
+    assert_eq!(
+        batch_players(&gzip(&raw)).unwrap(),
+        vec![ALEX.parse::<Uuid>().unwrap()]
+    );
+}
+
+#[test]
+fn sampling_keeps_about_the_rate_and_is_stable() {
+    let ids: Vec<Uuid> = (0..10_000).map(|_| Uuid::new_v4()).collect();
+    let kept = ids.iter().filter(|id| keeps(**id, 0.25)).count();
+    assert!((2_000..3_000).contains(&kept), "kept {}", kept);
+    assert!(ids.iter().all(|id| keeps(*id, 0.25) == keeps(*id, 0.25)));
+    assert!(ids.iter().all(|id| keeps(*id, 1.0)));
+}
+
+#[test]
+fn rates_are_validated() {
+    assert_eq!(validate_rate(None), Ok(None));
+    assert_eq!(validate_rate(Some(0.25)), Ok(Some(0.25)));
+    assert_eq!(validate_rate(Some(1.0)), Ok(Some(1.0)));
+    for bad in [0.0, 0.01, 1.5, -0.5, f64::NAN] {
+        assert!(validate_rate(Some(bad)).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn sampled_batches_weigh_by_the_inverse_rate() {
+    assert_eq!(Decision::Full.sample_rate(), None);
+    assert_eq!(Decision::SampledOut(0.25).sample_rate(), Some(0.25));
+    assert!(Decision::SampledOut(0.25).is_sampled_out());
+    assert!(!Decision::SampledIn(0.25).is_sampled_out());
+    assert_eq!(weight(None), 1.0);
+    assert_eq!(weight(Some(0.25)), 4.0);
+}
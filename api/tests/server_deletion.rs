@@ -0,0 +1,32 @@
+use async_anticheat_api::server_deletion::{purge_blocker, purge_eligible_at};
+use chrono::{Duration, TimeZone, Utc};
+
+#[test]
+fn purge_needs_a_deleted_server() {
+    let now = Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap();
+    let reason = purge_blocker(None, 30, true, now).unwrap();
+    assert!(reason.contains("must be deleted"));
+}
+
+#[test]
+fn purge_waits_for_the_grace_period_unless_forced() {
+    let deleted_at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+    assert_eq!(
+        purge_eligible_at(deleted_at, 30),
+        deleted_at + Duration::days(30)
+    );
+
+    let early = deleted_at + Duration::days(29);
+    let reason = purge_blocker(Some(deleted_at), 30, false, early).unwrap();
+    assert!(reason.contains("2026-03-31T12:00:00"));
+    assert_eq!(purge_blocker(Some(deleted_at), 30, true, early), None);
+
+    let later = deleted_at + Duration::days(30);
+    assert_eq!(purge_blocker(Some(deleted_at), 30, false, later), None);
+}
+
+#[test]
+fn zero_grace_days_allow_an_immediate_purge() {
+    let deleted_at = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+    assert_eq!(purge_blocker(Some(deleted_at), 0, false, deleted_at), None);
+}
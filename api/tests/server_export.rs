@@ -0,0 +1,49 @@
+use std::io::Read;
+
+use async_anticheat_api::server_export::TarGzWriter;
+use flate2::read::GzDecoder;
+
+fn gunzip(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut out).unwrap();
+    out
+}
+
+fn octal(field: &[u8]) -> u64 {
+    let s = std::str::from_utf8(field).unwrap();
+    u64::from_str_radix(s.trim_matches(|c: char| c == '\0' || c == ' '), 8).unwrap()
+}
+
+#[test]
+fn entries_are_valid_ustar_blocks() {
+    let mut archive = TarGzWriter::new(1_700_000_000);
+    archive.append("manifest.json", b"{\"version\":1}").unwrap();
+    archive.append("batches/empty.ndjson.gz", b"").unwrap();
+    let tar = gunzip(&archive.finish().unwrap());
+
+    // Header + one data block, header only, two end blocks.
+    assert_eq!(tar.len(), 512 * 5);
+    let header = &tar[..512];
+    assert!(header.starts_with(b"manifest.json\0"));
+    assert_eq!(octal(&header[124..136]), 13);
+    assert_eq!(octal(&header[136..148]), 1_700_000_000);
+    assert_eq!(header[156], b'0');
+    assert_eq!(&header[257..263], b"ustar\0");
+
+    let mut unsummed = header.to_vec();
+    unsummed[148..156].copy_from_slice(b"        ");
+    let sum: u64 = unsummed.iter().map(|b| *b as u64).sum();
+    assert_eq!(octal(&header[148..156]), sum);
+
+    assert_eq!(&tar[512..525], b"{\"version\":1}");
+    assert!(tar[525..1024].iter().all(|b| *b == 0));
+    assert!(tar[1024..].starts_with(b"batches/empty.ndjson.gz\0"));
+    assert!(tar[1536..].iter().all(|b| *b == 0));
+}
+
+#[test]
+fn long_or_non_ascii_paths_are_rejected() {
+    let mut archive = TarGzWriter::new(0);
+    assert!(archive.append(&"a".repeat(100), b"x").is_err());
+    assert!(archive.append("spieler-ü.json", b"x").is_err());
+}
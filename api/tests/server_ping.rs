@@ -0,0 +1,148 @@
+use async_anticheat_api::server_ping::{self, motd_text, parse_status, read_varint, write_varint};
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[test]
+fn varints_round_trip() {
+    for value in [0, 1, 127, 128, 25565, i32::MAX, -1] {
+        let mut buf = Vec::new();
+        write_varint(&mut buf, value);
+        assert_eq!(read_varint(&buf), Some((value, buf.len())));
+    }
+    let mut buf = Vec::new();
+    write_varint(&mut buf, -1);
+    assert_eq!(buf, [0xff, 0xff, 0xff, 0xff, 0x0f]);
+}
+
+#[test]
+fn status_request_encodes_handshake_then_request() {
+    let bytes = server_ping::status_request("mc.example.com", 25565);
+    // length, id 0, protocol -1 (5 bytes), host (1 + 14), port (2), next state 1
+    assert_eq!(bytes[0] as usize, 1 + 5 + 15 + 2 + 1);
+    assert_eq!(bytes[1], 0x00);
+    assert_eq!(&bytes[7..8], &[14]);
+    assert_eq!(&bytes[8..22], b"mc.example.com");
+    assert_eq!(&bytes[22..24], &25565u16.to_be_bytes());
+    assert_eq!(bytes[24], 1);
+    assert_eq!(&bytes[25..], &[0x01, 0x00]);
+}
+
+#[test]
+fn motd_flattens_components_and_strips_codes() {
+    let description = json!({
+        "text": "",
+        "extra": [
+            {"text": "§aMy ", "bold": true},
+            {"text": "Server"},
+            "\n  §7join now ",
+        ]
+    });
+    assert_eq!(motd_text(&description), "My Server\njoin now");
+    assert_eq!(motd_text(&json!("§6Legacy §lMOTD")), "Legacy MOTD");
+}
+
+#[test]
+fn parse_status_reads_players_and_version() {
+    let status = parse_status(
+        r#"{"version":{"name":"Paper 1.21.1","protocol":767},
+            "players":{"max":100,"online":7,"sample":[]},
+            "description":"A server","favicon":"data:image/png;base64,AAAA"}"#,
+    )
+    .unwrap();
+    assert_eq!(status.motd.as_deref(), Some("A server"));
+    assert_eq!(status.players_online, Some(7));
+    assert_eq!(status.players_max, Some(100));
+    assert_eq!(status.version.as_deref(), Some("Paper 1.21.1"));
+    assert_eq!(status.protocol, Some(767));
+
+    assert!(parse_status("not json").is_none());
+    assert!(parse_status("[]").is_none());
+}
+
+async fn read_frame(stream: &mut tokio::net::TcpStream) -> Vec<u8> {
+    let len = stream.read_u8().await.unwrap() as usize;
+    let mut packet = vec![0; len];
+    stream.read_exact(&mut packet).await.unwrap();
+    packet
+}
+
+fn frame(packet: Vec<u8>) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_varint(&mut out, packet.len() as i32);
+    out.extend(packet);
+    out
+}
+
+#[tokio::test]
+async fn probe_talks_to_a_status_server() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let handshake = read_frame(&mut stream).await;
+        assert_eq!(handshake[0], 0x00);
+        assert_eq!(read_frame(&mut stream).await, [0x00]);
+
+        let body = json!({
+            "version": {"name": "Paper 1.21.1", "protocol": 767},
+            "players": {"max": 20, "online": 3},
+            "description": {"text": "§bTest server"},
+        })
+        .to_string();
+        let mut status = vec![0x00];
+        write_varint(&mut status, body.len() as i32);
+        status.extend(body.as_bytes());
+        stream.write_all(&frame(status)).await.unwrap();
+
+        let ping = read_frame(&mut stream).await;
+        assert_eq!(ping[0], 0x01);
+        stream.write_all(&frame(ping)).await.unwrap();
+    });
+
+    let status = server_ping::probe("127.0.0.1", port).await.unwrap();
+    server.await.unwrap();
+    assert_eq!(status.motd.as_deref(), Some("Test server"));
+    assert_eq!(status.players_online, Some(3));
+    assert_eq!(status.players_max, Some(20));
+    assert_eq!(status.version.as_deref(), Some("Paper 1.21.1"));
+}
+
+#[tokio::test]
+async fn probe_reports_reachable_without_status() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        // Accept and hang up without answering, like a non-Minecraft service.
+        let _ = listener.accept().await.unwrap();
+    });
+
+    let status = server_ping::probe("127.0.0.1", port).await.unwrap();
+    assert!(status.motd.is_none());
+    assert!(status.players_online.is_none());
+}
+
+#[test]
+fn ping_target_prefers_callback_url_and_skips_uuid_ids() {
+    assert_eq!(
+        server_ping::ping_target("srv", Some("http://play.example.com:25577/cb")),
+        Some(("play.example.com".to_string(), 25577))
+    );
+    assert_eq!(
+        server_ping::ping_target("play.example.com", None),
+        Some(("play.example.com".to_string(), 25565))
+    );
+    assert_eq!(
+        server_ping::ping_target("[2001:db8::1]:25566", None),
+        Some(("2001:db8::1".to_string(), 25566))
+    );
+    assert_eq!(
+        server_ping::ping_target("6f1c2d3e-4a5b-4c6d-8e9f-0a1b2c3d4e5f", None),
+        None
+    );
+    assert_eq!(
+        server_ping::ping_target("srv", Some("localhost:25565")),
+        None
+    );
+    assert_eq!(server_ping::ping_target("2001:db8::1", None), None);
+}
@@ -0,0 +1,44 @@
+use async_anticheat_api::server_presence::{uptime_percent, PresenceEvent};
+use chrono::{DateTime, TimeZone, Utc};
+
+fn at(h: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 1, 1, h, 0, 0).unwrap()
+}
+
+fn event(h: u32, online: bool) -> PresenceEvent {
+    PresenceEvent { at: at(h), online }
+}
+
+#[test]
+fn servers_without_events_are_fully_up() {
+    assert_eq!(uptime_percent(&[], at(0), at(10)), Some(100.0));
+}
+
+#[test]
+fn outages_inside_the_window_count_as_down() {
+    let events = [event(2, false), event(4, true), event(8, false)];
+    // Up 0-2 and 4-8 of 10 hours.
+    assert_eq!(uptime_percent(&events, at(0), at(10)), Some(60.0));
+}
+
+#[test]
+fn the_last_event_before_the_window_gives_the_starting_state() {
+    let events = [event(1, false), event(6, true)];
+    // Offline since before the window, back at 6.
+    assert_eq!(uptime_percent(&events, at(2), at(10)), Some(50.0));
+
+    let still_down = [event(1, false)];
+    assert_eq!(uptime_percent(&still_down, at(2), at(10)), Some(0.0));
+}
+
+#[test]
+fn events_after_the_window_are_ignored() {
+    let events = [event(5, false), event(12, true)];
+    assert_eq!(uptime_percent(&events, at(0), at(4)), Some(100.0));
+}
+
+#[test]
+fn empty_windows_have_no_uptime() {
+    assert_eq!(uptime_percent(&[], at(3), at(3)), None);
+    assert_eq!(uptime_percent(&[], at(4), at(3)), None);
+}
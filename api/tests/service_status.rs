@@ -0,0 +1,75 @@
+use async_anticheat_api::ingest_hints::Backlog;
+use async_anticheat_api::service_status::{
+    dispatch_status, ingest_status, tasks_status, ComponentStatus, IngestOutcomes, TaskStatus,
+    WINDOW_MINUTES,
+};
+
+#[test]
+fn ingest_outcomes_count_the_last_window() {
+    let outcomes = IngestOutcomes::default();
+    outcomes.record_at(100, true);
+    outcomes.record_at(100, false);
+    outcomes.record_at(105, true);
+    assert_eq!(outcomes.totals_at(105), (2, 1));
+    assert_eq!(outcomes.totals_at(100 + WINDOW_MINUTES), (1, 0));
+
+    // Old minutes are dropped as new ones come in.
+    outcomes.record_at(200, true);
+    assert_eq!(outcomes.totals_at(200), (1, 0));
+}
+
+#[test]
+fn ingest_status_needs_enough_requests() {
+    assert_eq!(ingest_status(0, 0).status, ComponentStatus::Operational);
+    assert_eq!(ingest_status(0, 0).success_rate, None);
+    assert_eq!(ingest_status(5, 5).status, ComponentStatus::Operational);
+    assert_eq!(ingest_status(1000, 0).status, ComponentStatus::Operational);
+    assert_eq!(ingest_status(980, 20).status, ComponentStatus::Degraded);
+    assert_eq!(ingest_status(80, 20).status, ComponentStatus::MajorOutage);
+    assert_eq!(ingest_status(80, 20).success_rate, Some(0.8));
+}
+
+#[test]
+fn dispatch_is_degraded_when_saturated_or_shed() {
+    assert_eq!(
+        dispatch_status(Backlog::Normal, Some(0)).status,
+        ComponentStatus::Operational
+    );
+    assert_eq!(
+        dispatch_status(Backlog::Elevated, None).status,
+        ComponentStatus::Operational
+    );
+    assert_eq!(
+        dispatch_status(Backlog::Saturated, Some(0)).status,
+        ComponentStatus::Degraded
+    );
+    assert_eq!(
+        dispatch_status(Backlog::Normal, Some(12)).status,
+        ComponentStatus::Degraded
+    );
+}
+
+#[test]
+fn tasks_are_degraded_without_a_holder() {
+    let task = |alive| TaskStatus {
+        task: "module_healthcheck".to_string(),
+        alive,
+        last_finished_at: None,
+    };
+    assert_eq!(tasks_status(&[]), ComponentStatus::Operational);
+    assert_eq!(tasks_status(&[task(true)]), ComponentStatus::Operational);
+    assert_eq!(
+        tasks_status(&[task(true), task(false)]),
+        ComponentStatus::Degraded
+    );
+}
+
+#[test]
+fn component_statuses_order_by_severity() {
+    assert!(ComponentStatus::Operational < ComponentStatus::Degraded);
+    assert!(ComponentStatus::Degraded < ComponentStatus::MajorOutage);
+    assert_eq!(
+        serde_json::to_value(ComponentStatus::MajorOutage).unwrap(),
+        "major_outage"
+    );
+}
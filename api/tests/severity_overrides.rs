@@ -0,0 +1,29 @@
+use async_anticheat_api::severity_overrides::{parse_severity, SeverityOverrides};
+
+#[test]
+fn parses_known_severities() {
+    assert_eq!(parse_severity(" High "), Some("high"));
+    assert_eq!(parse_severity("info"), Some("info"));
+    assert_eq!(parse_severity("severe"), None);
+    assert_eq!(parse_severity(""), None);
+}
+
+#[test]
+fn override_replaces_the_module_severity() {
+    let overrides = SeverityOverrides::default()
+        .with_override("combat_autoclicker_cps", "low")
+        .with_override("movement_core_flight_ascend", "critical");
+
+    assert_eq!(
+        overrides.severity("combat_autoclicker_cps", Some("critical")),
+        "low"
+    );
+    assert_eq!(
+        overrides.severity("movement_core_flight_ascend", None),
+        "critical"
+    );
+    // Other detectors keep what the module said (info when it said nothing).
+    assert_eq!(overrides.severity("combat_reach", Some("high")), "high");
+    assert_eq!(overrides.severity("combat_reach", None), "info");
+    assert!(SeverityOverrides::default().is_empty());
+}
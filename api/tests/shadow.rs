@@ -0,0 +1,9 @@
+use async_anticheat_api::shadow::{parse_mode, MODE_ACTIVE, MODE_SHADOW};
+
+#[test]
+fn modes_are_normalized() {
+    assert_eq!(parse_mode("active"), Some(MODE_ACTIVE));
+    assert_eq!(parse_mode(" Shadow "), Some(MODE_SHADOW));
+    assert_eq!(parse_mode(""), None);
+    assert_eq!(parse_mode("dry-run"), None);
+}
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use async_anticheat_api::shared_state::{fixed_window, SharedState};
+
+#[test]
+fn fixed_windows_report_seconds_left() {
+    assert_eq!(fixed_window(120, 60), (2, 60));
+    assert_eq!(fixed_window(179, 60), (2, 1));
+    assert_eq!(fixed_window(5, 0), (5, 1));
+}
+
+#[tokio::test]
+async fn local_counters_reset_after_their_window() {
+    let shared = SharedState::local();
+    assert_eq!(shared.backend(), "local");
+    assert_eq!(shared.incr("a", Duration::from_millis(50)).await, 1);
+    assert_eq!(shared.incr("a", Duration::from_millis(50)).await, 2);
+    assert_eq!(shared.incr("b", Duration::from_millis(50)).await, 1);
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    assert_eq!(shared.incr("a", Duration::from_millis(50)).await, 1);
+}
+
+#[tokio::test]
+async fn local_claims_hold_until_expiry() {
+    let shared = SharedState::local();
+    assert!(shared.claim("k", Duration::from_millis(50)).await);
+    assert!(!shared.claim("k", Duration::from_millis(50)).await);
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    assert!(shared.claim("k", Duration::from_millis(50)).await);
+}
+
+#[tokio::test]
+async fn local_cache_expires_and_is_shared_by_clones() {
+    let shared = SharedState::local();
+    let clone = shared.clone();
+    shared
+        .set_json("stats", &vec![1, 2, 3], Duration::from_millis(50))
+        .await;
+    assert_eq!(
+        clone.get_json::<Vec<i32>>("stats").await,
+        Some(vec![1, 2, 3])
+    );
+    assert_eq!(clone.get_json::<String>("stats").await, None);
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    assert_eq!(shared.get_json::<Vec<i32>>("stats").await, None);
+}
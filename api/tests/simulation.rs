@@ -0,0 +1,43 @@
+use std::collections::HashSet;
+
+use async_anticheat_api::checks;
+use async_anticheat_api::simulation::{
+    parse_simulation_session, scenario_batch, simulation_session, Scenario,
+};
+use uuid::Uuid;
+
+#[test]
+fn every_scenario_is_caught_by_the_inproc_engines() {
+    for scenario in Scenario::ALL {
+        let raw = scenario_batch(scenario, "s1", "simulate-test").unwrap();
+        let fired: Vec<String> = ["movement_core", "combat_core", "player_core"]
+            .into_iter()
+            .flat_map(|engine| checks::run(engine, &raw).unwrap())
+            .filter(|f| f.player_uuid == Some(scenario.player()))
+            .map(|f| f.detector_name)
+            .collect();
+        assert!(
+            fired.iter().any(|d| scenario.matches(d)),
+            "{} not detected; fired: {:?}",
+            scenario.name(),
+            fired
+        );
+    }
+}
+
+#[test]
+fn scenarios_use_distinct_players_and_parse_by_name() {
+    let players: HashSet<Uuid> = Scenario::ALL.iter().map(|s| s.player()).collect();
+    assert_eq!(players.len(), Scenario::ALL.len());
+
+    assert_eq!(Scenario::parse(" Reach "), Some(Scenario::Reach));
+    assert_eq!(Scenario::parse("fly"), None);
+}
+
+#[test]
+fn simulation_session_round_trips() {
+    let (run, module) = (Uuid::new_v4(), Uuid::new_v4());
+    let session = simulation_session(run, module);
+    assert_eq!(parse_simulation_session(&session), Some((run, module)));
+    assert_eq!(parse_simulation_session("replay-abc.def"), None);
+}
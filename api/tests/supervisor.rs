@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_anticheat_api::supervisor::{
+    restart_delay, ProcessState, SupervisedModuleConfig, SupervisedStatus, Supervisor,
+    INITIAL_BACKOFF, MAX_BACKOFF, STABLE_RUN,
+};
+
+fn module(name: &str, command: &str, args: &[&str]) -> SupervisedModuleConfig {
+    SupervisedModuleConfig {
+        name: name.to_string(),
+        command: command.to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+        env: HashMap::new(),
+        working_dir: None,
+    }
+}
+
+/// Poll `name`'s status until `done` holds (or 5s pass).
+async fn wait_for(
+    supervisor: &Supervisor,
+    name: &str,
+    done: impl Fn(&SupervisedStatus) -> bool,
+) -> SupervisedStatus {
+    for _ in 0..100 {
+        if let Some(status) = supervisor.status(name).filter(|s| done(s)) {
+            return status;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("{}: {:?}", name, supervisor.status(name));
+}
+
+#[test]
+fn crashing_processes_back_off_from_1s_to_60s() {
+    let crash = Duration::from_millis(10);
+    let mut delays = Vec::new();
+    let mut previous = None;
+    for _ in 0..9 {
+        let delay = restart_delay(previous, crash);
+        delays.push(delay.as_secs());
+        previous = Some(delay);
+    }
+    assert_eq!(delays, vec![1, 2, 4, 8, 16, 32, 60, 60, 60]);
+}
+
+#[test]
+fn a_stable_run_resets_the_backoff() {
+    assert_eq!(
+        restart_delay(Some(MAX_BACKOFF), STABLE_RUN),
+        INITIAL_BACKOFF
+    );
+    assert_eq!(
+        restart_delay(Some(MAX_BACKOFF), STABLE_RUN - Duration::from_secs(1)),
+        MAX_BACKOFF
+    );
+    assert_eq!(restart_delay(None, Duration::ZERO), INITIAL_BACKOFF);
+}
+
+#[tokio::test]
+async fn statuses_follow_the_process_lifecycle() {
+    let supervisor = Supervisor::default();
+    assert!(supervisor.status("long").is_none());
+    supervisor.spawn_all(vec![
+        module("long", "sleep", &["30"]),
+        module("crashing", "sh", &["-c", "exit 3"]),
+        module("missing", "/nonexistent/module-binary", &[]),
+    ]);
+
+    let running = wait_for(&supervisor, "long", |s| s.state == ProcessState::Running).await;
+    assert!(running.pid.is_some());
+    assert!(running.started_at.is_some());
+    assert_eq!(running.restarts, 0);
+
+    let restarting = wait_for(&supervisor, "crashing", |s| {
+        s.state == ProcessState::Restarting
+    })
+    .await;
+    assert_eq!(restarting.pid, None);
+    assert_eq!(restarting.restarts, 1);
+    assert!(
+        restarting.last_exit.as_deref().unwrap().contains('3'),
+        "{:?}",
+        restarting.last_exit
+    );
+    // Restarted after the 1s backoff, crashing again.
+    wait_for(&supervisor, "crashing", |s| s.restarts >= 2).await;
+
+    let failed = wait_for(&supervisor, "missing", |s| {
+        s.state == ProcessState::SpawnFailed
+    })
+    .await;
+    assert_eq!(failed.restarts, 0);
+    assert!(failed
+        .last_exit
+        .as_deref()
+        .unwrap()
+        .starts_with("spawn error"));
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use async_anticheat_api::task_lock::{instance_id, lease_for, LEASE_MARGIN_SECONDS};
+
+#[test]
+fn leases_outlast_two_intervals() {
+    assert_eq!(
+        lease_for(Duration::from_secs(30)),
+        Duration::from_secs(60 + LEASE_MARGIN_SECONDS)
+    );
+    assert_eq!(
+        lease_for(Duration::from_secs(3600)),
+        Duration::from_secs(7200 + LEASE_MARGIN_SECONDS)
+    );
+}
+
+#[test]
+fn instance_id_is_stable_within_a_process() {
+    assert_eq!(instance_id(), instance_id());
+    assert!(!instance_id().is_empty());
+}
+
+/// Two replicas contending for one lease, against a real Postgres.
+/// Needs a Docker daemon: `cargo test --features docker-tests --test task_lock`.
+#[cfg(feature = "docker-tests")]
+mod leases {
+    use std::time::Duration;
+
+    use async_anticheat_api::task_lock::{acquire, instance_id, list, run_exclusive};
+    use chrono::{DateTime, Utc};
+    use sqlx::PgPool;
+    use testcontainers_modules::postgres::Postgres;
+    use testcontainers_modules::testcontainers::runners::AsyncRunner;
+    use testcontainers_modules::testcontainers::{ContainerAsync, ImageExt};
+
+    const LEASE: Duration = Duration::from_secs(120);
+
+    async fn postgres() -> (PgPool, ContainerAsync<Postgres>) {
+        let container = Postgres::default()
+            .with_init_sql(include_str!("../schema.sql").as_bytes().to_vec())
+            .with_tag("16-alpine")
+            .start()
+            .await
+            .expect("start postgres container");
+        let url = format!(
+            "postgres://postgres:postgres@{}:{}/postgres",
+            container.get_host().await.unwrap(),
+            container.get_host_port_ipv4(5432).await.unwrap()
+        );
+        let db = PgPool::connect(&url).await.expect("connect to postgres");
+        (db, container)
+    }
+
+    /// `(holder, acquired_at, expires_at)` of a task's lease.
+    async fn lease(db: &PgPool, task: &str) -> (String, DateTime<Utc>, DateTime<Utc>) {
+        sqlx::query_as(
+            "select holder, acquired_at, expires_at from public.task_leases where task = $1",
+        )
+        .bind(task)
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn one_holder_at_a_time_until_the_lease_expires() {
+        let (db, _container) = postgres().await;
+
+        // First come, first served.
+        assert!(acquire(&db, "rollup", "replica-a", LEASE).await.unwrap());
+        assert!(!acquire(&db, "rollup", "replica-b", LEASE).await.unwrap());
+        let (holder, acquired_at, expires_at) = lease(&db, "rollup").await;
+        assert_eq!(holder, "replica-a");
+
+        // Renewing extends the lease but keeps when it was taken.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(acquire(&db, "rollup", "replica-a", LEASE).await.unwrap());
+        let (holder, renewed_acquired_at, renewed_expires_at) = lease(&db, "rollup").await;
+        assert_eq!(holder, "replica-a");
+        assert_eq!(renewed_acquired_at, acquired_at);
+        assert!(renewed_expires_at > expires_at);
+        assert!(!acquire(&db, "rollup", "replica-b", LEASE).await.unwrap());
+
+        // Replica A stops renewing; once its lease runs out B takes over.
+        sqlx::query(
+            "update public.task_leases set expires_at = now() - interval '1 second' \
+             where task = 'rollup'",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        assert!(acquire(&db, "rollup", "replica-b", LEASE).await.unwrap());
+        let (holder, taken_at, _) = lease(&db, "rollup").await;
+        assert_eq!(holder, "replica-b");
+        assert!(taken_at > acquired_at);
+        assert!(!acquire(&db, "rollup", "replica-a", LEASE).await.unwrap());
+
+        // Other tasks are leased independently.
+        assert!(acquire(&db, "cleanup", "replica-a", LEASE).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn run_exclusive_skips_ticks_while_another_replica_holds_the_lease() {
+        let (db, _container) = postgres().await;
+        let interval = Duration::from_secs(30);
+
+        assert!(acquire(&db, "reports", "other-replica", LEASE)
+            .await
+            .unwrap());
+        assert!(!run_exclusive(&db, "reports", interval, async {}).await);
+
+        assert!(run_exclusive(&db, "purge", interval, async {}).await);
+        let leases = list(&db).await.unwrap();
+        let purge = leases.iter().find(|l| l.task == "purge").unwrap();
+        assert_eq!(purge.holder, instance_id());
+        assert!(purge.held_here);
+        assert!(purge.last_finished_at.is_some());
+        let reports = leases.iter().find(|l| l.task == "reports").unwrap();
+        assert!(!reports.held_here);
+        assert!(reports.last_finished_at.is_none());
+    }
+}
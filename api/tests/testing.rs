@@ -0,0 +1,76 @@
+#![cfg(feature = "testing")]
+
+use std::time::Duration;
+
+use async_anticheat_api::module_sdk::MovementEvent;
+use async_anticheat_api::testing::{dispatch, fixtures, CallbackSink, MockModule, MockResponse};
+use uuid::Uuid;
+
+const WAIT: Duration = Duration::from_secs(2);
+
+#[tokio::test]
+async fn batch_flows_through_transform_dispatch_and_findings_callback() {
+    let player = Uuid::new_v4();
+    let sink = CallbackSink::spawn("cb-token");
+    let module = MockModule::spawn(MockResponse {
+        findings: vec![fixtures::finding("speed", "high", player)],
+        callback: Some((sink.base_url.clone(), "cb-token".to_string())),
+        ..Default::default()
+    });
+
+    let (batch_id, module_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let raw = fixtures::raw_batch("s1", "sess", &fixtures::walk(1_000, player, 5, 0.9));
+    let resp = dispatch(
+        &reqwest::Client::new(),
+        &module.base_url,
+        module_id,
+        "s1",
+        "sess",
+        batch_id,
+        "movement_events_v1_ndjson_gz",
+        &raw,
+    )
+    .await
+    .unwrap();
+    assert!(resp.status().is_success());
+
+    let batches = module.wait_for_batches(1, WAIT).await;
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].batch_id, batch_id);
+    assert_eq!(batches[0].module_id, Some(module_id));
+    assert_eq!(batches[0].transform(), Some("movement_events_v1"));
+    let events: Vec<MovementEvent> = batches[0].events().collect();
+    assert_eq!(events.len(), 5);
+    assert!(events.iter().all(|e| e.uuid == player));
+
+    let posted = sink.wait_for_findings(1, WAIT).await;
+    assert_eq!(posted.len(), 1);
+    assert_eq!(posted[0].batch_id, Some(batch_id));
+    assert_eq!(posted[0].module_id, Some(module_id));
+    assert_eq!(posted[0].findings[0].detector_name, "speed");
+}
+
+#[tokio::test]
+async fn failing_module_answers_500_and_still_records_the_batch() {
+    let module = MockModule::spawn(MockResponse {
+        fail: true,
+        ..Default::default()
+    });
+    let player = Uuid::new_v4();
+    let raw = fixtures::raw_batch("s1", "sess", &[fixtures::attack(1_000, player, 7)]);
+
+    let resp = dispatch(
+        &reqwest::Client::new(),
+        &module.base_url,
+        Uuid::new_v4(),
+        "s1",
+        "sess",
+        Uuid::new_v4(),
+        "combat_events_v1_ndjson_gz",
+        &raw,
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status().as_u16(), 500);
+    assert_eq!(module.batches().len(), 1);
+}
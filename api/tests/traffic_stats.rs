@@ -0,0 +1,106 @@
+use async_anticheat_api::traffic_stats::{
+    request_server, RankBy, Traffic, TrafficStats, RETENTION_HOURS, TOP_ENDPOINTS,
+};
+use axum::http::HeaderMap;
+
+fn traffic(requests: u64, bytes_in: u64, bytes_out: u64) -> Traffic {
+    Traffic {
+        requests,
+        bytes_in,
+        bytes_out,
+    }
+}
+
+#[test]
+fn servers_are_ranked_with_their_endpoints() {
+    let stats = TrafficStats::default();
+    stats.record_at(100, Some("a"), "POST /ingest", traffic(10, 5_000, 100));
+    stats.record_at(100, Some("a"), "GET /callbacks/pending", traffic(2, 0, 300));
+    stats.record_at(100, Some("b"), "POST /ingest", traffic(1, 100, 10_000));
+    stats.record_at(100, None, "GET /health", traffic(50, 0, 50));
+
+    let top = stats.top_at(100, 1, RankBy::Total, 10);
+    assert_eq!(top[0].server_id.as_deref(), Some("b"));
+    assert_eq!(top[0].traffic, traffic(1, 100, 10_000));
+    assert_eq!(top[1].server_id.as_deref(), Some("a"));
+    assert_eq!(top[1].traffic, traffic(12, 5_000, 400));
+    assert_eq!(top[1].endpoints[0].endpoint, "POST /ingest");
+    assert_eq!(top[1].endpoints.len(), 2);
+
+    let by_in = stats.top_at(100, 1, RankBy::In, 1);
+    assert_eq!(by_in.len(), 1);
+    assert_eq!(by_in[0].server_id.as_deref(), Some("a"));
+    let by_requests = stats.top_at(100, 1, RankBy::Requests, 10);
+    assert_eq!(by_requests[0].server_id, None);
+}
+
+#[test]
+fn hours_add_up_within_the_window_and_expire() {
+    let stats = TrafficStats::default();
+    stats.record_at(100, Some("a"), "POST /ingest", traffic(1, 10, 0));
+    stats.record_at(101, Some("a"), "POST /ingest", traffic(1, 20, 0));
+    // A response finishing after the hour turned still counts in its own hour.
+    stats.record_at(100, Some("a"), "POST /ingest", traffic(0, 0, 5));
+
+    assert_eq!(
+        stats.top_at(101, 1, RankBy::Total, 10)[0].traffic,
+        traffic(1, 20, 0)
+    );
+    assert_eq!(
+        stats.top_at(101, 2, RankBy::Total, 10)[0].traffic,
+        traffic(2, 30, 5)
+    );
+
+    stats.record_at(
+        100 + RETENTION_HOURS,
+        Some("b"),
+        "POST /ingest",
+        traffic(1, 1, 1),
+    );
+    let top = stats.top_at(100 + RETENTION_HOURS, RETENTION_HOURS, RankBy::Total, 10);
+    let a = top
+        .iter()
+        .find(|s| s.server_id.as_deref() == Some("a"))
+        .unwrap();
+    assert_eq!(a.traffic, traffic(1, 20, 0));
+}
+
+#[test]
+fn endpoints_per_server_are_capped() {
+    let stats = TrafficStats::default();
+    for i in 0..TOP_ENDPOINTS + 3 {
+        stats.record_at(
+            1,
+            Some("a"),
+            &format!("GET /e{}", i),
+            traffic(1, i as u64, 0),
+        );
+    }
+    let top = stats.top_at(1, 1, RankBy::In, 10);
+    assert_eq!(top[0].endpoints.len(), TOP_ENDPOINTS);
+    assert_eq!(
+        top[0].endpoints[0].endpoint,
+        format!("GET /e{}", TOP_ENDPOINTS + 2)
+    );
+}
+
+#[test]
+fn server_comes_from_the_header_or_the_route() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(
+        request_server(
+            &headers,
+            Some("/dashboard/:server_id/findings"),
+            "/dashboard/srv-1/findings"
+        ),
+        Some("srv-1".to_string())
+    );
+    assert_eq!(request_server(&headers, Some("/ingest"), "/ingest"), None);
+    assert_eq!(request_server(&headers, None, "/nope"), None);
+
+    headers.insert("x-server-id", " srv-2 ".parse().unwrap());
+    assert_eq!(
+        request_server(&headers, Some("/ingest"), "/ingest"),
+        Some("srv-2".to_string())
+    );
+}
@@ -0,0 +1,268 @@
+//! Properties of the geometry behind combat findings: yaw differences, look vectors, reach and
+//! aim offsets, on their own and through the transforms that emit them.
+
+use async_anticheat_api::hitboxes::{self, Hitbox};
+use async_anticheat_api::transform_events::{CombatEvent, FightEvent};
+use async_anticheat_api::transforms::{aim_off, apply_transform, view_vector, yaw_difference};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use proptest::prelude::*;
+use std::io::{Read, Write};
+
+fn gzip(s: &str) -> Vec<u8> {
+    let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+    enc.write_all(s.as_bytes()).unwrap();
+    enc.finish().unwrap()
+}
+
+fn gunzip(bytes: &[u8]) -> String {
+    let mut s = String::new();
+    GzDecoder::new(bytes).read_to_string(&mut s).unwrap();
+    s
+}
+
+fn dist(a: [f64; 3], b: [f64; 3]) -> f64 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+/// Yaw and pitch (degrees) looking from `eye` at `target`.
+fn look_at(eye: [f64; 3], target: [f64; 3]) -> (f64, f64) {
+    let r = [0, 1, 2].map(|i| target[i] - eye[i]);
+    let horizontal = (r[0] * r[0] + r[2] * r[2]).sqrt();
+    (
+        (-r[0]).atan2(r[2]).to_degrees(),
+        (-r[1]).atan2(horizontal).to_degrees(),
+    )
+}
+
+/// Yaws as clients send them: unwrapped, so they can be any multiple of 360 away from [-180, 180).
+fn yaw() -> impl Strategy<Value = f64> {
+    -1e6..1e6f64
+}
+
+fn pitch() -> impl Strategy<Value = f64> {
+    -90.0..=90.0f64
+}
+
+/// Coordinates within the world border.
+fn coord() -> impl Strategy<Value = f64> {
+    -3e7..3e7f64
+}
+
+fn point() -> impl Strategy<Value = [f64; 3]> {
+    (coord(), -64.0..320.0f64, coord()).prop_map(|(x, y, z)| [x, y, z])
+}
+
+/// Offsets from the attacker within any plausible (or blatant) reach.
+fn offset() -> impl Strategy<Value = [f64; 3]> {
+    [-12.0..12.0f64, -12.0..12.0f64, -12.0..12.0f64]
+}
+
+fn hitbox() -> impl Strategy<Value = Hitbox> {
+    prop_oneof![
+        Just(hitboxes::PLAYER),
+        (0.1..5.0f64, 0.1..5.0f64).prop_map(|(width, height)| Hitbox { width, height }),
+    ]
+}
+
+/// Tolerance for comparisons of distances around `scale` blocks from the origin.
+fn eps(scale: f64) -> f64 {
+    1e-9 * scale.abs().max(1.0) + 1e-9
+}
+
+proptest! {
+    #[test]
+    fn yaw_difference_is_a_symmetric_angle_within_half_a_turn(a in yaw(), b in yaw()) {
+        let d = yaw_difference(a, b);
+        prop_assert!((0.0..=180.0).contains(&d), "{} vs {}: {}", a, b, d);
+        prop_assert!((d - yaw_difference(b, a)).abs() <= eps(a.max(b)));
+    }
+
+    #[test]
+    fn yaw_difference_ignores_whole_turns(a in yaw(), b in -180.0..180.0f64, turns in -1000i32..1000) {
+        let wrapped = b + 360.0 * f64::from(turns);
+        prop_assert!((yaw_difference(a, b) - yaw_difference(a, wrapped)).abs() <= eps(wrapped) * 1e3);
+    }
+
+    #[test]
+    fn yaw_difference_is_plain_within_half_a_turn(a in -180.0..180.0f64, delta in -180.0..=180.0f64) {
+        prop_assert!((yaw_difference(a + delta, a) - delta.abs()).abs() <= 1e-9);
+    }
+
+    #[test]
+    fn view_vector_is_a_unit_vector(yaw in yaw(), pitch in pitch()) {
+        let v = view_vector(yaw, pitch);
+        let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+        prop_assert!((len - 1.0).abs() <= 1e-9);
+        // Looking up (negative pitch) points up.
+        prop_assert!((v[1] + pitch.to_radians().sin()).abs() <= 1e-9);
+    }
+
+    #[test]
+    fn view_vector_ignores_whole_turns(yaw in -180.0..180.0f64, pitch in pitch(), turns in -1000i32..1000) {
+        let a = view_vector(yaw, pitch);
+        let b = view_vector(yaw + 360.0 * f64::from(turns), pitch);
+        prop_assert!(dist(a, b) <= 1e-6);
+    }
+
+    #[test]
+    fn aim_off_is_bounded_by_the_distance(eye in point(), offset in offset(), yaw in yaw(), pitch in pitch()) {
+        let target = [0, 1, 2].map(|i| eye[i] + offset[i]);
+        let off = aim_off(eye, view_vector(yaw, pitch), target);
+        prop_assert!(off >= 0.0);
+        prop_assert!(off <= dist(eye, target) + eps(eye[0].abs().max(eye[2].abs())));
+    }
+
+    #[test]
+    fn aim_off_is_zero_looking_at_the_target(eye in point(), offset in offset()) {
+        let target = [0, 1, 2].map(|i| eye[i] + offset[i]);
+        let (yaw, pitch) = look_at(eye, target);
+        let off = aim_off(eye, view_vector(yaw, pitch), target);
+        prop_assert!(off <= 1e-6 + eps(eye[0].abs().max(eye[2].abs())) * 10.0, "off {}", off);
+    }
+
+    #[test]
+    fn aim_off_does_not_depend_on_the_view_length(eye in point(), offset in offset(), yaw in yaw(), pitch in pitch(), scale in 1e-3..1e3f64) {
+        let target = [0, 1, 2].map(|i| eye[i] + offset[i]);
+        let view = view_vector(yaw, pitch);
+        let scaled = view.map(|c| c * scale);
+        prop_assert!((aim_off(eye, view, target) - aim_off(eye, scaled, target)).abs() <= 1e-6);
+    }
+
+    #[test]
+    fn hitbox_reach_is_at_most_the_center_and_feet_distance(eye in point(), offset in offset(), hitbox in hitbox(), expand in 0.0..0.5f64) {
+        let feet = [0, 1, 2].map(|i| eye[i] + offset[i]);
+        let (center, nearest) = hitboxes::reach(eye, feet, hitbox, expand);
+        let tolerance = eps(eye[0].abs().max(eye[2].abs()));
+        prop_assert!(nearest >= 0.0);
+        prop_assert!(nearest <= center + tolerance);
+        prop_assert!(nearest <= dist(eye, feet) + tolerance);
+    }
+
+    #[test]
+    fn hitbox_reach_shrinks_as_the_box_grows(eye in point(), offset in offset(), hitbox in hitbox(), expand in 0.0..0.5f64) {
+        let feet = [0, 1, 2].map(|i| eye[i] + offset[i]);
+        let (_, tight) = hitboxes::reach(eye, feet, hitbox, 0.0);
+        let (_, grown) = hitboxes::reach(eye, feet, hitbox, expand);
+        prop_assert!(grown <= tight + eps(eye[0].abs().max(eye[2].abs())));
+    }
+
+    #[test]
+    fn hitbox_reach_is_zero_from_inside(feet in point(), hitbox in hitbox(), fx in 0.0..1.0f64, fy in 0.0..1.0f64, fz in 0.0..1.0f64) {
+        let eye = [
+            feet[0] + (fx - 0.5) * hitbox.width,
+            feet[1] + fy * hitbox.height,
+            feet[2] + (fz - 0.5) * hitbox.width,
+        ];
+        prop_assert_eq!(hitboxes::reach(eye, feet, hitbox, 0.0).1, 0.0);
+    }
+}
+
+/// One attack on a target spawned at `target` (feet), by a player at `player` looking `yaw` /
+/// `pitch`; the previous attack was made at `prev_yaw`.
+fn attack_batch(
+    player: [f64; 3],
+    target: [f64; 3],
+    prev_yaw: f64,
+    yaw: f64,
+    pitch: f64,
+) -> Vec<u8> {
+    let pos = |ts: u64, yaw: f64| {
+        format!(
+            r#"{{"ts":{},"dir":"serverbound","pkt":"PLAYER_POSITION_AND_ROTATION","uuid":"00000000-0000-0000-0000-000000000001","fields":{{"x":{:?},"y":{:?},"z":{:?},"yaw":{:?},"pitch":{:?},"on_ground":true}}}}"#,
+            ts, player[0], player[1], player[2], yaw, pitch
+        )
+    };
+    let attack = |ts: u64| {
+        format!(
+            r#"{{"ts":{},"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{{"entity_id":7,"action":"ATTACK"}}}}"#,
+            ts
+        )
+    };
+    let raw = [
+        r#"{"server_id":"s","session_id":"x"}"#.to_string(),
+        format!(
+            r#"{{"ts":900,"dir":"clientbound","pkt":"SPAWN_ENTITY","fields":{{"entity_id":7,"entity_type":"minecraft:player","x":{:?},"y":{:?},"z":{:?}}}}}"#,
+            target[0], target[1], target[2]
+        ),
+        pos(1000, prev_yaw),
+        attack(1010),
+        pos(1500, yaw),
+        attack(1510),
+    ]
+    .join("\n");
+    gzip(&raw)
+}
+
+fn events<T: serde::de::DeserializeOwned>(transform: &str, gz: &[u8]) -> Vec<T> {
+    gunzip(&apply_transform(transform, gz).unwrap())
+        .lines()
+        .skip(1)
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn ncp_fight_v1_geometry_is_consistent(player in point(), offset in offset(), prev_yaw in yaw(), yaw in yaw(), pitch in pitch()) {
+        let target = [0, 1, 2].map(|i| player[i] + offset[i]);
+        let fight: Vec<FightEvent> = events("ncp_fight_v1_ndjson_gz", &attack_batch(player, target, prev_yaw, yaw, pitch));
+        prop_assert_eq!(fight.len(), 2);
+        let e = &fight[1];
+        let (reach, center, hitbox, off) = (
+            e.reach_distance.unwrap(),
+            e.reach_center.unwrap(),
+            e.reach_hitbox.unwrap(),
+            e.aim_off.unwrap(),
+        );
+        let tolerance = eps(player[0].abs().max(player[2].abs()));
+        prop_assert!(hitbox <= center + tolerance && hitbox <= reach + tolerance);
+        prop_assert!((0.0..=reach + tolerance).contains(&off));
+
+        let combat: Vec<CombatEvent> = events("combat_events_v1_ndjson_gz", &attack_batch(player, target, prev_yaw, yaw, pitch));
+        let yaw_diff = combat[1].yaw_diff.unwrap();
+        prop_assert!((0.0..=180.0).contains(&yaw_diff));
+    }
+
+    /// Any finite input, however absurd, gives finite output (JSON has no NaN or infinity).
+    #[test]
+    fn ncp_fight_v1_output_is_finite(
+        player in prop::array::uniform3(prop::num::f64::NORMAL | prop::num::f64::ZERO),
+        target in prop::array::uniform3(prop::num::f64::NORMAL | prop::num::f64::ZERO),
+        prev_yaw in prop::num::f64::NORMAL,
+        yaw in prop::num::f64::NORMAL,
+        pitch in prop::num::f64::NORMAL,
+    ) {
+        let gz = attack_batch(player, target, prev_yaw, yaw, pitch);
+        // Typed decoding fails on the `null` serde_json writes for NaN and infinity.
+        let fight: Vec<FightEvent> = events("ncp_fight_v1_ndjson_gz", &gz);
+        for e in &fight {
+            for v in [e.reach_distance, e.reach_center, e.reach_hitbox, e.aim_off, e.reach_uncertainty].into_iter().flatten() {
+                prop_assert!(v.is_finite());
+            }
+        }
+        let combat: Vec<CombatEvent> = events("combat_events_v1_ndjson_gz", &gz);
+        for e in &combat {
+            if let Some(yaw_diff) = e.yaw_diff {
+                prop_assert!((0.0..=180.0).contains(&yaw_diff));
+            }
+        }
+    }
+}
+
+#[test]
+fn yaw_difference_is_nan_for_nan() {
+    assert!(yaw_difference(f64::NAN, 10.0).is_nan());
+    assert!(yaw_difference(10.0, f64::NAN).is_nan());
+    assert!(yaw_difference(f64::INFINITY, 10.0).is_nan());
+}
+
+#[test]
+fn view_vector_faces_minecraft_axes() {
+    let close = |a: [f64; 3], b: [f64; 3]| assert!(dist(a, b) < 1e-12, "{:?} vs {:?}", a, b);
+    close(view_vector(0.0, 0.0), [0.0, 0.0, 1.0]);
+    close(view_vector(90.0, 0.0), [-1.0, 0.0, 0.0]);
+    close(view_vector(-90.0, 0.0), [1.0, 0.0, 0.0]);
+    close(view_vector(180.0, 0.0), [0.0, 0.0, -1.0]);
+    close(view_vector(37.0, 90.0), [0.0, -1.0, 0.0]);
+    close(view_vector(-12.0, -90.0), [0.0, 1.0, 0.0]);
+}
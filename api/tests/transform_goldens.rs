@@ -0,0 +1,123 @@
+//! Golden outputs of every transform over the batches in `tests/fixtures/transforms`.
+//!
+//! Each `<name>.ndjson` fixture is gzipped and run through every transform; the result must match
+//! `<name>.<transform>.golden.ndjson` line for line. After an intended output change, regenerate
+//! with `UPDATE_GOLDENS=1 cargo test --test transform_goldens` and review the diff. New fixtures
+//! come from stored batches via `async-anticheat-api export-fixture --key <object key>`.
+
+use async_anticheat_api::transforms::{apply_transform, SUPPORTED};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+fn gzip(s: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut enc = GzEncoder::new(&mut out, Compression::default());
+    enc.write_all(s.as_bytes()).unwrap();
+    enc.finish().unwrap();
+    out
+}
+
+fn gunzip(bytes: &[u8]) -> String {
+    let mut dec = GzDecoder::new(bytes);
+    let mut s = String::new();
+    dec.read_to_string(&mut s).unwrap();
+    s
+}
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/transforms")
+}
+
+/// Fixture inputs (goldens excluded), sorted by name.
+fn fixtures() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(fixtures_dir())
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| {
+            let name = p.file_name().unwrap().to_string_lossy();
+            name.ends_with(".ndjson") && !name.ends_with(".golden.ndjson")
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// `movement_events_v1_ndjson_gz` -> `movement_events_v1`
+fn short_name(transform: &str) -> &str {
+    transform.trim_end_matches("_ndjson_gz")
+}
+
+/// Where `expected` and `actual` first differ, as a readable message.
+fn first_difference(expected: &str, actual: &str) -> Option<String> {
+    let mut exp = expected.lines();
+    let mut act = actual.lines();
+    for line in 1.. {
+        match (exp.next(), act.next()) {
+            (None, None) => return None,
+            (e, a) if e == a => continue,
+            (e, a) => {
+                return Some(format!(
+                    "line {}:\n  expected: {}\n  actual:   {}",
+                    line,
+                    e.unwrap_or("<end of output>"),
+                    a.unwrap_or("<end of output>"),
+                ))
+            }
+        }
+    }
+    unreachable!()
+}
+
+#[test]
+fn transforms_match_golden_outputs() {
+    let update = std::env::var_os("UPDATE_GOLDENS").is_some();
+    let inputs = fixtures();
+    assert!(!inputs.is_empty(), "no fixtures in {:?}", fixtures_dir());
+
+    let mut failures = Vec::new();
+    for input in &inputs {
+        let stem = input.file_stem().unwrap().to_string_lossy().to_string();
+        let raw = gzip(&std::fs::read_to_string(input).unwrap());
+
+        for transform in SUPPORTED.iter().filter(|t| **t != "raw_ndjson_gz") {
+            let actual = gunzip(&apply_transform(transform, &raw).unwrap());
+            let golden =
+                fixtures_dir().join(format!("{}.{}.golden.ndjson", stem, short_name(transform)));
+
+            if update {
+                std::fs::write(&golden, &actual).unwrap();
+                continue;
+            }
+            let Ok(expected) = std::fs::read_to_string(&golden) else {
+                failures.push(format!("{}: missing golden {:?}", stem, golden));
+                continue;
+            };
+            if let Some(diff) = first_difference(&expected, &actual) {
+                failures.push(format!("{} / {}: {}", stem, short_name(transform), diff));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "transform output differs from the goldens (UPDATE_GOLDENS=1 to regenerate):\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn transforms_are_deterministic_on_fixtures() {
+    for input in fixtures() {
+        let raw = gzip(&std::fs::read_to_string(&input).unwrap());
+        for transform in SUPPORTED {
+            assert_eq!(
+                apply_transform(transform, &raw).unwrap(),
+                apply_transform(transform, &raw).unwrap(),
+                "{:?} / {}",
+                input,
+                transform
+            );
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use async_anticheat_api::transform_pool::{PoolError, TransformPool};
+
+#[tokio::test]
+async fn jobs_run_and_errors_are_returned() {
+    let pool = TransformPool::new(2, 4, Duration::from_secs(5));
+    assert_eq!(pool.run(|| Ok(21 * 2)).await.unwrap(), 42);
+    let err = pool
+        .run(|| Err::<(), _>(anyhow::anyhow!("bad batch")))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, PoolError::Failed(_)));
+    assert_eq!(err.to_string(), "bad batch");
+
+    let snapshot = pool.snapshot();
+    assert_eq!((snapshot.completed, snapshot.failed), (1, 1));
+    assert_eq!(snapshot.saturation, 0.0);
+    assert!(snapshot.avg_wait_ms.is_some());
+}
+
+#[tokio::test]
+async fn slow_jobs_time_out() {
+    let pool = TransformPool::new(1, 0, Duration::from_millis(20));
+    let err = pool
+        .run(|| {
+            std::thread::sleep(Duration::from_millis(200));
+            Ok(())
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, PoolError::TimedOut(_)));
+    assert_eq!(pool.snapshot().timed_out, 1);
+}
+
+#[tokio::test]
+async fn full_pool_rejects_jobs() {
+    let pool = TransformPool::new(1, 1, Duration::from_secs(5));
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+
+    let busy = pool.clone();
+    let running = tokio::spawn(async move {
+        busy.run(move || {
+            release_rx.recv().ok();
+            Ok(())
+        })
+        .await
+    });
+    let queued_pool = pool.clone();
+    let queued = tokio::spawn(async move { queued_pool.run(|| Ok(())).await });
+    while pool.snapshot().waiting == 0 {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    assert_eq!(pool.snapshot().saturation, 1.0);
+    assert!(matches!(
+        pool.run(|| Ok(())).await,
+        Err(PoolError::Saturated)
+    ));
+    assert_eq!(pool.snapshot().rejected, 1);
+
+    release_tx.send(()).unwrap();
+    running.await.unwrap().unwrap();
+    queued.await.unwrap().unwrap();
+    assert_eq!(pool.snapshot().completed, 2);
+}
@@ -1,4 +1,9 @@
-use async_anticheat_api::transforms::apply_transform;
+use async_anticheat_api::transform_events::{CombatEvent, FightEvent, MovementEvent};
+use async_anticheat_api::transforms::{
+    apply_transform, apply_transform_shared, apply_transform_with, is_supported, wants_server_time,
+    SUPPORTED,
+};
+use bytes::Bytes;
 use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use std::io::Read;
 
@@ -32,3 +37,377 @@ fn movement_events_v1_includes_on_ground_when_present() {
     assert!(text.contains(r#""on_ground":true"#));
     assert!(text.contains(r#""on_ground":false"#));
 }
+
+#[test]
+fn client_channels_v1_extracts_brand_and_registrations() {
+    let raw = r#"
+{"server_id":"s","session_id":"x"}
+{"ts":1000,"dir":"serverbound","pkt":"PLUGIN_MESSAGE","uuid":"00000000-0000-0000-0000-000000000001","name":"p","fields":{"channel":"minecraft:brand","data":"\u0006fabric"}}
+{"ts":1010,"dir":"serverbound","pkt":"PLUGIN_MESSAGE","uuid":"00000000-0000-0000-0000-000000000001","name":"p","fields":{"channel":"minecraft:register","data":"meteor-client:main\u0000fabric:registry/sync"}}
+{"ts":1020,"dir":"serverbound","pkt":"PLUGIN_MESSAGE","uuid":"00000000-0000-0000-0000-000000000001","name":"p","fields":{"channel":"other:thing","data":"x"}}
+{"ts":1030,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","name":"p","fields":{"x":0.0,"y":64.0,"z":0.0}}
+"#
+    .trim_start();
+
+    let out = apply_transform("client_channels_v1_ndjson_gz", &gzip(raw)).unwrap();
+    let text = gunzip(&out);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains(r#""transform":"client_channels_v1""#));
+    assert!(lines[1].contains(r#""kind":"brand""#));
+    assert!(lines[1].contains(r#""brand":"fabric""#));
+    assert!(lines[2].contains(r#""channels":["meteor-client:main","fabric:registry/sync"]"#));
+}
+
+#[test]
+fn movement_events_v1_carries_world_and_gamemode_context() {
+    let raw = r#"
+{"server_id":"s","session_id":"x"}
+{"ts":900,"dir":"synthetic","pkt":"PLAYER_STATE","uuid":"00000000-0000-0000-0000-000000000001","name":"p","fields":{"world":"world_nether","gamemode":"CREATIVE","allow_flying":true}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","name":"p","fields":{"x":0.0,"y":64.0,"z":0.0}}
+{"ts":1100,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000002","name":"q","fields":{"x":0.0,"y":64.0,"z":0.0}}
+"#
+    .trim_start();
+
+    let out = apply_transform("movement_events_v1_ndjson_gz", &gzip(raw)).unwrap();
+    let text = gunzip(&out);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].contains(r#""world":"world_nether""#));
+    assert!(lines[1].contains(r#""gamemode":"CREATIVE""#));
+    assert!(lines[1].contains(r#""flight_allowed":true"#));
+    // No context for the second player.
+    assert!(!lines[2].contains("world"));
+    assert!(lines[2].contains(r#""flight_allowed":false"#));
+}
+
+#[test]
+fn supported_transforms_are_accepted_by_apply_transform() {
+    let raw = gzip(
+        r#"{"server_id":"s1","session_id":"sess"}
+{"ts":1,"pkt":"POSITION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":64.0,"z":0.0}}
+"#,
+    );
+    for t in SUPPORTED {
+        assert!(is_supported(t));
+        assert!(apply_transform(t, &raw).is_ok(), "{} failed", t);
+    }
+    assert!(is_supported(" Movement_Events_V1_NDJSON_GZ "));
+    assert!(!is_supported("movement_events_v2_ndjson_gz"));
+}
+
+#[test]
+fn legacy_field_names_are_normalized_for_old_plugins() {
+    let raw = r#"
+{"server_id":"s","session_id":"x","plugin_version":"0.3.2"}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION_AND_ROTATION","uuid":"00000000-0000-0000-0000-000000000001","name":"p","fields":{"x":0.0,"y":64.0,"z":0.0,"yRot":90.0,"xRot":10.0,"ground":true}}
+{"ts":1050,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","name":"p","fields":{"entityId":7,"action":"ATTACK","isSneaking":true}}
+"#
+    .trim_start();
+
+    let movement = gunzip(&apply_transform("movement_events_v1_ndjson_gz", &gzip(raw)).unwrap());
+    assert!(movement.contains(r#""on_ground":true"#));
+
+    let combat = gunzip(&apply_transform("combat_events_v1_ndjson_gz", &gzip(raw)).unwrap());
+    let attack = combat.lines().nth(1).expect("attack event");
+    assert!(attack.contains(r#""entity_id":7"#));
+    assert!(attack.contains(r#""sneaking":true"#));
+    assert!(attack.contains(r#""player_yaw":90.0"#));
+    assert!(attack.contains(r#""player_pitch":10.0"#));
+}
+
+#[test]
+fn legacy_normalization_is_gated_by_plugin_version() {
+    use async_anticheat_api::transforms::{normalize_legacy_fields, uses_legacy_fields};
+    use serde_json::json;
+
+    assert!(uses_legacy_fields(&json!({"server_id": "s"})));
+    assert!(uses_legacy_fields(&json!({"plugin_version": "0.3.9"})));
+    assert!(!uses_legacy_fields(&json!({"plugin_version": "0.4.0"})));
+    assert!(!uses_legacy_fields(
+        &json!({"plugin_version": "1.2.0-SNAPSHOT"})
+    ));
+
+    // Canonical names win over legacy duplicates.
+    let mut record = json!({"fields": {"ground": false, "on_ground": true, "yRot": 5.0}});
+    normalize_legacy_fields(&mut record);
+    assert_eq!(record, json!({"fields": {"on_ground": true, "yaw": 5.0}}));
+
+    // Current plugins' legacy-looking keys are left alone.
+    let raw = r#"
+{"server_id":"s","session_id":"x","plugin_version":"0.4.1"}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","name":"p","fields":{"x":0.0,"y":64.0,"z":0.0,"ground":true}}
+"#
+    .trim_start();
+    let movement = gunzip(&apply_transform("movement_events_v1_ndjson_gz", &gzip(raw)).unwrap());
+    assert!(!movement.contains("on_ground"));
+}
+
+#[test]
+fn events_carry_client_type_from_batch_metadata() {
+    let raw = r#"
+{"server_id":"s","session_id":"x","client_types":{"00000000-0000-0000-0000-000000000001":"bedrock","00000000-0000-0000-0000-000000000002":"console"}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":64.0,"z":0.0,"on_ground":true}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000002","fields":{"x":0.0,"y":64.0,"z":0.0,"on_ground":true}}
+"#
+    .trim_start();
+
+    let movement = gunzip(&apply_transform("movement_events_v1_ndjson_gz", &gzip(raw)).unwrap());
+    let events: Vec<&str> = movement.lines().skip(1).collect();
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains(r#""client_type":"bedrock""#));
+    // Unknown client types are dropped rather than passed through.
+    assert!(!events[1].contains("client_type"));
+}
+
+#[test]
+fn events_carry_client_protocol_from_batch_metadata() {
+    let raw = r#"
+{"server_id":"s","session_id":"x","protocol_version":767,"client_protocols":{"00000000-0000-0000-0000-000000000001":47,"00000000-0000-0000-0000-000000000002":"1.8"}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":64.0,"z":0.0,"on_ground":true}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000002","fields":{"x":0.0,"y":64.0,"z":0.0,"on_ground":true}}
+"#
+    .trim_start();
+
+    let movement = gunzip(&apply_transform("movement_events_v1_ndjson_gz", &gzip(raw)).unwrap());
+    let events: Vec<&str> = movement.lines().skip(1).collect();
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains(r#""client_protocol":47"#));
+    // Protocols must be numbers.
+    assert!(!events[1].contains("client_protocol"));
+}
+
+#[test]
+fn server_time_option_rebases_ts_by_the_batch_skew() {
+    let raw = r#"
+{"server_id":"s","session_id":"x"}
+{"ts":100000,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","name":"p","fields":{"x":0.0,"y":64.0,"z":0.0}}
+{"ts":100050,"dir":"serverbound","pkt":"PLAYER_POSITION","uuid":"00000000-0000-0000-0000-000000000001","name":"p","fields":{"x":0.2,"y":64.0,"z":0.0}}
+"#
+    .trim_start();
+
+    assert!(is_supported("movement_events_v1_ndjson_gz+server_time"));
+    assert!(!is_supported("movement_events_v1_ndjson_gz+bogus"));
+    assert!(wants_server_time("raw_ndjson_gz+server_time"));
+    assert!(!wants_server_time("raw_ndjson_gz"));
+
+    // Plugin clock 90s ahead of the API.
+    let out = gunzip(
+        &apply_transform_with(
+            "movement_events_v1_ndjson_gz+server_time",
+            &gzip(raw),
+            Some(90_000),
+        )
+        .unwrap(),
+    );
+    let lines: Vec<serde_json::Value> = out
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines[0]["ts_base"], "server");
+    assert_eq!(lines[0]["clock_skew_ms"], 90_000);
+    assert_eq!(lines[0]["transform"], "movement_events_v1");
+    assert_eq!(lines[1]["ts"], 10_000);
+    assert_eq!(lines[2]["ts"], 10_050);
+
+    // Unknown skew: timestamps stay on the plugin clock.
+    let out = gunzip(&apply_transform("raw_ndjson_gz+server_time", &gzip(raw)).unwrap());
+    let first: Vec<serde_json::Value> = out
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(first[0]["ts_base"], "client");
+    assert!(first[0].get("clock_skew_ms").is_none());
+    assert_eq!(first[1]["ts"], 100_000);
+
+    // Without the option the batch is untouched.
+    let plain = gunzip(&apply_transform_with("raw_ndjson_gz", &gzip(raw), Some(90_000)).unwrap());
+    assert_eq!(plain, raw);
+}
+
+#[test]
+fn raw_passthrough_shares_the_batch_buffer() {
+    let raw = Bytes::from(gzip("{\"server_id\":\"s\"}\n{\"ts\":1,\"pkt\":\"X\"}\n"));
+    for transform in ["raw_ndjson_gz", ""] {
+        let out = apply_transform_shared(transform, &raw, None).unwrap();
+        assert_eq!(out.as_ptr(), raw.as_ptr(), "{:?}", transform);
+    }
+
+    let rebased = apply_transform_shared("raw_ndjson_gz+server_time", &raw, Some(5)).unwrap();
+    assert_ne!(rebased.as_ptr(), raw.as_ptr());
+    let movement = apply_transform_shared("movement_events_v1_ndjson_gz", &raw, None).unwrap();
+    assert_eq!(
+        movement,
+        apply_transform("movement_events_v1_ndjson_gz", &raw).unwrap()
+    );
+}
+
+#[test]
+fn transform_lines_decode_as_typed_events() {
+    let raw = r#"
+{"server_id":"s","session_id":"x"}
+{"ts":900,"dir":"clientbound","pkt":"SPAWN_ENTITY","fields":{"entity_id":7,"x":3.0,"y":64.0,"z":0.0}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION_AND_ROTATION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":64.0,"z":0.0,"yaw":-90.0,"pitch":0.0,"on_ground":true}}
+{"ts":1100,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+{"ts":1300,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+"#
+    .trim_start();
+    let gz = gzip(raw);
+    let lines = |transform: &str| {
+        let text = gunzip(&apply_transform(transform, &gz).unwrap());
+        text.lines().skip(1).map(str::to_string).collect::<Vec<_>>()
+    };
+
+    let movement: Vec<MovementEvent> = lines("movement_events_v1_ndjson_gz")
+        .iter()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(movement.len(), 1);
+    assert_eq!(movement[0].on_ground, Some(true));
+    assert_eq!(movement[0].dt_ms, None);
+
+    let combat: Vec<CombatEvent> = lines("combat_events_v1_ndjson_gz")
+        .iter()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(combat.len(), 2);
+    assert_eq!(combat[0].player_yaw, Some(-90.0));
+    assert_eq!(combat[1].dt_ms, Some(200.0));
+    assert_eq!(combat[1].attacks_per_second, Some(5.0));
+    assert_eq!(combat[1].target_switched, Some(false));
+
+    let fight: Vec<FightEvent> = lines("ncp_fight_v1_ndjson_gz")
+        .iter()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(fight.len(), 2);
+    assert_eq!(fight[0].target_x, Some(3.0));
+    assert!(fight[0].reach_distance.is_some_and(|d| d > 2.9 && d < 3.5));
+
+    // Unset optional fields are left out of the line, not written as null.
+    assert!(!lines("movement_events_v1_ndjson_gz")[0].contains("null"));
+}
+
+#[test]
+fn attacks_are_annotated_with_the_target_hurt_time() {
+    // Tick rate 10: the 10 invulnerable ticks last a second.
+    let raw = r#"
+{"server_id":"s","session_id":"x","tick_rate":10.0}
+{"ts":900,"dir":"clientbound","pkt":"SPAWN_ENTITY","fields":{"entity_id":7,"x":3.0,"y":64.0,"z":0.0}}
+{"ts":1000,"dir":"serverbound","pkt":"PLAYER_POSITION_AND_ROTATION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":64.0,"z":0.0,"yaw":-90.0,"pitch":0.0,"on_ground":true}}
+{"ts":1100,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+{"ts":1120,"dir":"clientbound","pkt":"ENTITY_EVENT","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"status":2}}
+{"ts":1125,"dir":"clientbound","pkt":"ENTITY_STATUS","fields":{"entity_id":7,"status":2}}
+{"ts":1130,"dir":"clientbound","pkt":"ENTITY_STATUS","fields":{"entity_id":7,"status":9}}
+{"ts":1500,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+{"ts":2300,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+{"ts":2310,"dir":"clientbound","pkt":"DAMAGE_EVENT","fields":{"entity_id":7}}
+{"ts":2400,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+"#
+    .trim_start();
+    let gz = gzip(raw);
+
+    let combat: Vec<CombatEvent> =
+        gunzip(&apply_transform("combat_events_v1_ndjson_gz", &gz).unwrap())
+            .lines()
+            .skip(1)
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+    let fight: Vec<FightEvent> = gunzip(&apply_transform("ncp_fight_v1_ndjson_gz", &gz).unwrap())
+        .lines()
+        .skip(1)
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(combat.len(), 4);
+    assert_eq!(fight.len(), 4);
+
+    let annotations = |hurt: Vec<(Option<f64>, Option<bool>)>| {
+        assert_eq!(
+            hurt,
+            vec![
+                // Before any hurt packet.
+                (None, None),
+                // Inside the window of the hurt at 1120 (the copy at 1125 and status 9 ignored).
+                (Some(380.0), Some(true)),
+                (Some(1180.0), Some(false)),
+                (Some(90.0), Some(true)),
+            ]
+        );
+    };
+    annotations(
+        combat
+            .iter()
+            .map(|e| (e.target_hurt_ago_ms, e.target_invulnerable))
+            .collect(),
+    );
+    annotations(
+        fight
+            .iter()
+            .map(|e| (e.target_hurt_ago_ms, e.target_invulnerable))
+            .collect(),
+    );
+}
+
+#[test]
+fn ncp_fight_v1_interpolates_the_target_to_the_attack() {
+    // The target moves 1 block east every 100 ms; attacks fall between and after its packets.
+    let raw = r#"
+{"server_id":"s","session_id":"x"}
+{"ts":900,"dir":"clientbound","pkt":"SPAWN_PLAYER","fields":{"entity_id":7,"x":2.0,"y":64.0,"z":0.0}}
+{"ts":900,"dir":"serverbound","pkt":"PLAYER_POSITION_AND_ROTATION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":0.0,"y":64.0,"z":0.0,"yaw":-90.0,"pitch":0.0,"on_ground":true}}
+{"ts":950,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+{"ts":1000,"dir":"clientbound","pkt":"ENTITY_RELATIVE_MOVE","fields":{"entity_id":7,"dx":1.0,"dy":0.0,"dz":0.0}}
+{"ts":1075,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+{"ts":1100,"dir":"clientbound","pkt":"ENTITY_RELATIVE_MOVE","fields":{"entity_id":7,"dx":1.0,"dy":0.0,"dz":0.0}}
+{"ts":1150,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+{"ts":1500,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":7,"action":"ATTACK"}}
+"#
+    .trim_start();
+
+    let events: Vec<FightEvent> =
+        gunzip(&apply_transform("ncp_fight_v1_ndjson_gz", &gzip(raw)).unwrap())
+            .lines()
+            .skip(1)
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+    let placed: Vec<(u64, Option<f64>, Option<f64>)> = events
+        .iter()
+        .map(|e| (e.ts, e.target_x, e.reach_uncertainty))
+        .collect();
+    assert_eq!(
+        placed,
+        vec![
+            // Halfway from the spawn to the first move.
+            (950, Some(2.5), Some(0.5)),
+            // A quarter of the way to the second move.
+            (1075, Some(3.75), Some(0.75)),
+            // Past the last packet: extrapolated at the same speed...
+            (1150, Some(4.5), Some(0.5)),
+            // ...for at most 150 ms.
+            (1500, Some(5.5), Some(1.5)),
+        ]
+    );
+}
+
+#[test]
+fn transforms_survive_extreme_values() {
+    // Found by the `transforms` fuzz target: hurt packets at the largest timestamp overflowed.
+    let raw = r#"
+{"server_id":"s","session_id":"x","tick_rate":1e308,"client_protocols":{"00000000-0000-0000-0000-000000000001":9223372036854775807}}
+{"ts":0,"dir":"clientbound","pkt":"SPAWN_ENTITY","fields":{"entity_id":5,"x":1e308,"y":-1e308,"z":1e308}}
+{"ts":100,"dir":"clientbound","pkt":"ENTITY_RELATIVE_MOVE","fields":{"entity_id":5,"dx":1e308,"dy":0,"dz":0}}
+{"ts":18446744073709551615,"dir":"clientbound","pkt":"ENTITY_STATUS","fields":{"entity_id":5,"status":2}}
+{"ts":18446744073709551615,"dir":"clientbound","pkt":"ENTITY_STATUS","fields":{"entity_id":5,"status":2}}
+{"ts":18446744073709551615,"dir":"serverbound","pkt":"PLAYER_POSITION_AND_ROTATION","uuid":"00000000-0000-0000-0000-000000000001","fields":{"x":1e308,"y":1e308,"z":-1e308,"yaw":1e308,"pitch":-1e308}}
+{"ts":18446744073709551615,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":5,"action":"ATTACK"}}
+{"ts":-5,"dir":"serverbound","pkt":"INTERACT_ENTITY","uuid":"00000000-0000-0000-0000-000000000001","fields":{"entity_id":-9223372036854775808,"action":"ATTACK"}}
+"#
+    .trim_start();
+    let gz = gzip(raw);
+    for transform in SUPPORTED {
+        apply_transform(transform, &gz).unwrap();
+    }
+    for engine in ["movement_core", "combat_core", "player_core"] {
+        async_anticheat_api::checks::run(engine, &gz).unwrap();
+    }
+}
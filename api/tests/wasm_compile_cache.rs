@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use async_anticheat_api::wasm_runtime::CompiledCache;
+use uuid::Uuid;
+
+#[test]
+fn entries_only_serve_the_blob_they_were_compiled_from() {
+    let mut cache = CompiledCache::default();
+    let id = Uuid::from_u128(1);
+    cache.insert(id, "aaa", Arc::new("v1"));
+    assert_eq!(cache.get(id, "aaa").as_deref(), Some(&"v1"));
+    assert!(cache.get(id, "bbb").is_none());
+    // Same blob under another upload id: compiled separately.
+    assert!(cache.get(Uuid::from_u128(2), "aaa").is_none());
+}
+
+#[test]
+fn recompiling_replaces_the_old_entry() {
+    let mut cache = CompiledCache::default();
+    let id = Uuid::from_u128(1);
+    cache.insert(id, "aaa", Arc::new("v1"));
+    cache.insert(id, "bbb", Arc::new("v2"));
+    assert_eq!(cache.len(), 1);
+    assert!(cache.get(id, "aaa").is_none());
+    assert_eq!(cache.get(id, "bbb").as_deref(), Some(&"v2"));
+}
+
+#[test]
+fn removed_and_deleted_modules_are_evicted() {
+    let mut cache = CompiledCache::default();
+    let (a, b, c) = (Uuid::from_u128(1), Uuid::from_u128(2), Uuid::from_u128(3));
+    for id in [a, b, c] {
+        cache.insert(id, "hash", Arc::new(id));
+    }
+
+    cache.remove(a);
+    assert!(cache.get(a, "hash").is_none());
+    assert_eq!(cache.len(), 2);
+
+    // Only `c` is still in the database.
+    cache.retain(&[c]);
+    assert_eq!(cache.ids(), vec![c]);
+    cache.retain(&[]);
+    assert!(cache.is_empty());
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "wasm-modules")]
+
+use async_anticheat_api::wasm_runtime::{run_ndjson, validate, Limits};
+
+const GUEST: &str = r#"
+(module
+  (import "aac" "emit_finding" (func $emit (param i32 i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 0) "{\"player_uuid\":null,\"detector_name\":\"wasm_test\",\"title\":\"t\"}")
+  (func (export "alloc") (param i32) (result i32) i32.const 1024)
+  (func (export "process") (param i32 i32) (result i32)
+    i32.const 0
+    i32.const 60
+    call $emit
+    i32.const 0))
+"#;
+
+const SPIN: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (func (export "alloc") (param i32) (result i32) i32.const 1024)
+  (func (export "process") (param i32 i32) (result i32)
+    (loop $l br $l)
+    i32.const 0))
+"#;
+
+#[test]
+fn guest_findings_are_collected() {
+    let wasm = wat::parse_str(GUEST).unwrap();
+    validate(&wasm).unwrap();
+    let findings = run_ndjson(&wasm, Limits::default(), b"{}\n").unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].detector_name, "wasm_test");
+}
+
+#[test]
+fn fuel_limit_stops_runaway_guest() {
+    let wasm = wat::parse_str(SPIN).unwrap();
+    let limits = Limits {
+        fuel: 10_000,
+        ..Limits::default()
+    };
+    assert!(run_ndjson(&wasm, limits, b"{}\n").is_err());
+}
+
+#[test]
+fn unknown_imports_are_rejected() {
+    let wasm = wat::parse_str(r#"(module (import "wasi" "fd_write" (func)))"#).unwrap();
+    assert!(validate(&wasm).is_err());
+}
@@ -0,0 +1,27 @@
+use async_anticheat_api::watchlist::sync_payload;
+use uuid::Uuid;
+
+#[test]
+fn sync_payload_carries_full_set_and_capture_settings() {
+    let a = Uuid::new_v4();
+    let b = Uuid::new_v4();
+    let expires = chrono::Utc::now() + chrono::Duration::days(7);
+
+    let payload = sync_payload(&[(a, None), (b, Some(expires))]);
+
+    assert_eq!(payload["capture"]["sample_rate"], 1.0);
+    assert_eq!(payload["capture"]["verbose"], true);
+    let players = payload["players"].as_array().unwrap();
+    assert_eq!(players.len(), 2);
+    assert_eq!(players[0]["uuid"], a.to_string());
+    assert!(players[0]["expires_at"].is_null());
+    assert_eq!(players[1]["uuid"], b.to_string());
+    assert!(players[1]["expires_at"].is_string());
+}
+
+#[test]
+fn empty_watchlist_still_syncs() {
+    // Removing the last player must clear the plugin's set, not leave it stale.
+    let payload = sync_payload(&[]);
+    assert_eq!(payload["players"].as_array().unwrap().len(), 0);
+}
@@ -0,0 +1,75 @@
+use async_anticheat_api::routes::callbacks::FindingIn;
+use async_anticheat_api::webhooks::{
+    confidence_adjusted_severity, downgrade, LowConfidenceAction, WebhookSettings,
+};
+
+fn settings(min_confidence: Option<f64>, action: LowConfidenceAction) -> WebhookSettings {
+    WebhookSettings {
+        webhook_url: Some("https://example.com/hook".to_string()),
+        webhook_enabled: true,
+        webhook_severity_levels: vec!["critical".to_string(), "high".to_string()],
+        min_confidence,
+        low_confidence_action: action,
+    }
+}
+
+#[test]
+fn low_confidence_findings_are_downgraded_or_dropped() {
+    let downgrade_below = settings(Some(0.7), LowConfidenceAction::Downgrade);
+    assert_eq!(
+        confidence_adjusted_severity(&downgrade_below, "critical", Some(0.4)).as_deref(),
+        Some("high")
+    );
+    assert_eq!(
+        confidence_adjusted_severity(&downgrade_below, "critical", Some(0.9)).as_deref(),
+        Some("critical")
+    );
+    // No reported confidence counts as certain.
+    assert_eq!(
+        confidence_adjusted_severity(&downgrade_below, "high", None).as_deref(),
+        Some("high")
+    );
+
+    let drop_below = settings(Some(0.7), LowConfidenceAction::Drop);
+    assert_eq!(
+        confidence_adjusted_severity(&drop_below, "critical", Some(0.69)),
+        None
+    );
+
+    let unfiltered = settings(None, LowConfidenceAction::Drop);
+    assert_eq!(
+        confidence_adjusted_severity(&unfiltered, "critical", Some(0.01)).as_deref(),
+        Some("critical")
+    );
+}
+
+#[test]
+fn downgrade_walks_the_severity_ladder() {
+    assert_eq!(downgrade("critical"), "high");
+    assert_eq!(downgrade("medium"), "low");
+    assert_eq!(downgrade("low"), "info");
+    assert_eq!(downgrade("info"), "info");
+    assert_eq!(
+        LowConfidenceAction::parse(" Drop "),
+        Some(LowConfidenceAction::Drop)
+    );
+    assert_eq!(LowConfidenceAction::parse("ignore"), None);
+}
+
+#[test]
+fn confidence_is_optional_in_findings() {
+    let f: FindingIn = serde_json::from_str(
+        r#"{"player_uuid":null,"detector_name":"reach","severity":"high","title":"Reach"}"#,
+    )
+    .unwrap();
+    assert_eq!(f.confidence, None);
+    assert!(serde_json::to_value(&f)
+        .unwrap()
+        .get("confidence")
+        .is_none());
+
+    let f: FindingIn =
+        serde_json::from_str(r#"{"detector_name":"reach","title":"Reach","confidence":0.35}"#)
+            .unwrap();
+    assert_eq!(f.confidence, Some(0.35));
+}
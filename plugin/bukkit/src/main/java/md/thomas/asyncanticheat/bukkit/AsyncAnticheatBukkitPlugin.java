@@ -17,6 +17,7 @@ public final class AsyncAnticheatBukkitPlugin extends JavaPlugin {
 
     private AsyncAnticheatService service;
     private RecordingManager recordingManager;
+    private PlayerLookup playerLookup;
     private BukkitPlayerExemptionTracker exemptionTracker;
     private SchedulerUtil.ScheduledTask stateTask;
     private boolean packetEventsInitialized = false;
@@ -74,6 +75,7 @@ public final class AsyncAnticheatBukkitPlugin extends JavaPlugin {
 
         // Initialize recording manager for in-game cheat recording
         recordingManager = new RecordingManager(this, service.getConfig(), service.getServerId());
+        playerLookup = new PlayerLookup(this, service.getConfig(), service.getServerId());
 
         // Ensure recordings are stopped immediately on logout
         getServer().getPluginManager().registerEvents(new Listener() {
@@ -131,6 +133,10 @@ public final class AsyncAnticheatBukkitPlugin extends JavaPlugin {
             recordingManager.stopAll();
             recordingManager = null;
         }
+        if (playerLookup != null) {
+            playerLookup.shutdown();
+            playerLookup = null;
+        }
         if (service != null) {
             service.stop();
             service = null;
@@ -148,7 +154,7 @@ public final class AsyncAnticheatBukkitPlugin extends JavaPlugin {
     }
 
     private void registerCommand() {
-        final BukkitMainCommand mainCmd = new BukkitMainCommand(service, recordingManager);
+        final BukkitMainCommand mainCmd = new BukkitMainCommand(service, recordingManager, playerLookup);
 
         // Register command from plugin.yml
         PluginCommand pluginCmd = getCommand("aac");
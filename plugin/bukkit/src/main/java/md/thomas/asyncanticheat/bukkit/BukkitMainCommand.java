@@ -25,6 +25,7 @@ import java.util.stream.Collectors;
  * - /aac record <player> <type> [label] - Start recording
  * - /aac record stop [player] - Stop recording
  * - /aac record status - Show active recordings
+ * - /aac lookup <player> - Show recent findings, risk and watchlist status
  */
 final class BukkitMainCommand implements CommandExecutor, TabCompleter {
 
@@ -36,10 +37,13 @@ final class BukkitMainCommand implements CommandExecutor, TabCompleter {
 
     private final AsyncAnticheatService service;
     private final RecordingManager recordingManager;
+    private final PlayerLookup playerLookup;
 
-    BukkitMainCommand(@NotNull AsyncAnticheatService service, @NotNull RecordingManager recordingManager) {
+    BukkitMainCommand(@NotNull AsyncAnticheatService service, @NotNull RecordingManager recordingManager,
+                      @NotNull PlayerLookup playerLookup) {
         this.service = service;
         this.recordingManager = recordingManager;
+        this.playerLookup = playerLookup;
     }
 
     @Override
@@ -59,6 +63,9 @@ final class BukkitMainCommand implements CommandExecutor, TabCompleter {
             case "token" -> {
                 return handleToken(sender, Arrays.copyOfRange(args, 1, args.length));
             }
+            case "lookup" -> {
+                return handleLookup(sender, Arrays.copyOfRange(args, 1, args.length));
+            }
             default -> {
                 return showHelp(sender);
             }
@@ -104,6 +111,25 @@ final class BukkitMainCommand implements CommandExecutor, TabCompleter {
         return true;
     }
 
+    private boolean handleLookup(@NotNull CommandSender sender, @NotNull String[] args) {
+        if (!sender.hasPermission("asyncanticheat.lookup")) {
+            sender.sendMessage("§c[AsyncAnticheat] Missing permission: asyncanticheat.lookup");
+            return true;
+        }
+
+        if (args.length == 0) {
+            sender.sendMessage("§a[AsyncAnticheat] §fUsage: §f/aac lookup <player|uuid>");
+            return true;
+        }
+
+        // Online players resolve to their UUID; anyone else is looked up by last known name.
+        final Player online = Bukkit.getPlayerExact(args[0]);
+        final String player = online != null ? online.getUniqueId().toString() : args[0];
+        sender.sendMessage("§7[AsyncAnticheat] Looking up " + args[0] + "...");
+        playerLookup.lookup(sender, player);
+        return true;
+    }
+
     private boolean handleRecord(@NotNull CommandSender sender, @NotNull String[] args) {
         if (!(sender instanceof Player player)) {
             sender.sendMessage("§c[AsyncAnticheat] This command must be run by a player.");
@@ -240,6 +266,7 @@ final class BukkitMainCommand implements CommandExecutor, TabCompleter {
         sender.sendMessage("§a[AsyncAnticheat] §fCommands:");
         sender.sendMessage("  §f/aac §7- Show dashboard status");
         sender.sendMessage("  §f/aac token <token> §7- Set API token (link to existing server)");
+        sender.sendMessage("  §f/aac lookup <player> §7- Recent findings, risk and watchlist status");
         sender.sendMessage("  §f/aac record <player> <type> [label] §7- Start recording");
         sender.sendMessage("  §f/aac record stop [player] §7- Stop recording");
         sender.sendMessage("  §f/aac record status §7- Show active recordings");
@@ -252,10 +279,17 @@ final class BukkitMainCommand implements CommandExecutor, TabCompleter {
         final List<String> completions = new ArrayList<>();
 
         if (args.length == 1) {
-            completions.addAll(Arrays.asList("link", "record", "status", "token"));
+            completions.addAll(Arrays.asList("link", "lookup", "record", "status", "token"));
             return filter(completions, args[0]);
         }
 
+        if (args.length == 2 && args[0].equalsIgnoreCase("lookup")) {
+            completions.addAll(Bukkit.getOnlinePlayers().stream()
+                    .map(Player::getName)
+                    .collect(Collectors.toList()));
+            return filter(completions, args[1]);
+        }
+
         if (args.length >= 2 && args[0].equalsIgnoreCase("record")) {
             if (args.length == 2) {
                 // /aac record <tab>
@@ -8,10 +8,14 @@ import com.github.retrooper.packetevents.util.Vector3d;
 import com.github.retrooper.packetevents.util.Vector3f;
 import com.github.retrooper.packetevents.util.Vector3i;
 import com.github.retrooper.packetevents.wrapper.play.client.*;
+import com.github.retrooper.packetevents.wrapper.play.server.WrapperPlayServerDamageEvent;
 import com.github.retrooper.packetevents.wrapper.play.server.WrapperPlayServerDestroyEntities;
+import com.github.retrooper.packetevents.wrapper.play.server.WrapperPlayServerEntityStatus;
 import com.github.retrooper.packetevents.wrapper.play.server.WrapperPlayServerEntityRelativeMove;
 import com.github.retrooper.packetevents.wrapper.play.server.WrapperPlayServerEntityRelativeMoveAndRotation;
 import com.github.retrooper.packetevents.wrapper.play.server.WrapperPlayServerEntityTeleport;
+import com.github.retrooper.packetevents.wrapper.play.server.WrapperPlayServerHurtAnimation;
+import com.github.retrooper.packetevents.wrapper.play.server.WrapperPlayServerSpawnEntity;
 import com.github.retrooper.packetevents.wrapper.play.server.WrapperPlayServerSpawnLivingEntity;
 import com.github.retrooper.packetevents.wrapper.play.server.WrapperPlayServerSpawnPlayer;
 import md.thomas.asyncanticheat.core.AsyncAnticheatService;
@@ -252,6 +256,20 @@ final class BukkitPacketCaptureListener implements PacketListener {
             return m;
         }
 
+        // Spawn entity (absolute position; players too since 1.20.2)
+        if (packetType == PacketType.Play.Server.SPAWN_ENTITY) {
+            final WrapperPlayServerSpawnEntity w = new WrapperPlayServerSpawnEntity(event);
+            final Vector3d pos = w.getPosition();
+            final Map<String, Object> m = new HashMap<>();
+            m.put("entity_id", w.getEntityId());
+            m.put("entity_uuid", w.getUUID().map(Object::toString).orElse(null));
+            m.put("entity_type", w.getEntityType() == null ? null : w.getEntityType().getName().toString());
+            m.put("x", pos.getX());
+            m.put("y", pos.getY());
+            m.put("z", pos.getZ());
+            return m;
+        }
+
         // Spawn living entity (absolute position)
         if (packetType == PacketType.Play.Server.SPAWN_LIVING_ENTITY) {
             final WrapperPlayServerSpawnLivingEntity w = new WrapperPlayServerSpawnLivingEntity(event);
@@ -259,7 +277,7 @@ final class BukkitPacketCaptureListener implements PacketListener {
             final Map<String, Object> m = new HashMap<>();
             m.put("entity_id", w.getEntityId());
             m.put("entity_uuid", w.getEntityUUID() == null ? null : w.getEntityUUID().toString());
-            m.put("entity_type", w.getEntityType() == null ? null : w.getEntityType().getName());
+            m.put("entity_type", w.getEntityType() == null ? null : w.getEntityType().getName().toString());
             m.put("x", pos.getX());
             m.put("y", pos.getY());
             m.put("z", pos.getZ());
@@ -288,6 +306,35 @@ final class BukkitPacketCaptureListener implements PacketListener {
             return m;
         }
 
+        // Entity status (status 2 and a few others play the hurt animation before 1.19.4)
+        if (packetType == PacketType.Play.Server.ENTITY_STATUS) {
+            final WrapperPlayServerEntityStatus w = new WrapperPlayServerEntityStatus(event);
+            final Map<String, Object> m = new HashMap<>();
+            m.put("entity_id", w.getEntityId());
+            m.put("status", w.getStatus());
+            return m;
+        }
+
+        // Hurt animation (1.19.4+)
+        if (packetType == PacketType.Play.Server.HURT_ANIMATION) {
+            final WrapperPlayServerHurtAnimation w = new WrapperPlayServerHurtAnimation(event);
+            final Map<String, Object> m = new HashMap<>();
+            m.put("entity_id", w.getEntityId());
+            m.put("yaw", w.getYaw());
+            return m;
+        }
+
+        // Damage taken (1.19.4+)
+        if (packetType == PacketType.Play.Server.DAMAGE_EVENT) {
+            final WrapperPlayServerDamageEvent w = new WrapperPlayServerDamageEvent(event);
+            final Map<String, Object> m = new HashMap<>();
+            m.put("entity_id", w.getEntityId());
+            m.put("source_type_id", w.getSourceTypeId());
+            m.put("source_cause_id", w.getSourceCauseId());
+            m.put("source_direct_id", w.getSourceDirectId());
+            return m;
+        }
+
         return Collections.emptyMap();
     }
 
@@ -0,0 +1,155 @@
+package md.thomas.asyncanticheat.bukkit;
+
+import com.google.gson.JsonArray;
+import com.google.gson.JsonElement;
+import com.google.gson.JsonObject;
+import com.google.gson.JsonParser;
+import md.thomas.asyncanticheat.core.AsyncAnticheatConfig;
+import org.bukkit.command.CommandSender;
+import org.bukkit.plugin.Plugin;
+import org.jetbrains.annotations.NotNull;
+
+import java.net.URI;
+import java.net.URLEncoder;
+import java.net.http.HttpClient;
+import java.net.http.HttpRequest;
+import java.net.http.HttpResponse;
+import java.nio.charset.StandardCharsets;
+import java.time.Duration;
+import java.util.ArrayList;
+import java.util.List;
+import java.util.concurrent.ExecutorService;
+import java.util.concurrent.Executors;
+
+/**
+ * Backs /aac lookup: fetches a player's recent findings, risk score and watchlist status
+ * from GET /plugin/findings off the main thread and replies to the sender.
+ */
+final class PlayerLookup {
+
+    /** The API answers within 750 ms or gives up; leave room for the network. */
+    private static final Duration REQUEST_TIMEOUT = Duration.ofSeconds(2);
+
+    private final Plugin plugin;
+    private final AsyncAnticheatConfig config;
+    private final String serverId;
+    private final HttpClient httpClient;
+    private final ExecutorService executor = Executors.newSingleThreadExecutor(r -> {
+        Thread t = new Thread(r, "AsyncAnticheat-Lookup");
+        t.setDaemon(true);
+        return t;
+    });
+
+    PlayerLookup(@NotNull Plugin plugin, @NotNull AsyncAnticheatConfig config, @NotNull String serverId) {
+        this.plugin = plugin;
+        this.config = config;
+        this.serverId = serverId;
+        this.httpClient = HttpClient.newBuilder()
+                .connectTimeout(REQUEST_TIMEOUT)
+                .build();
+    }
+
+    void lookup(@NotNull CommandSender sender, @NotNull String player) {
+        executor.execute(() -> {
+            final List<String> lines = fetch(player);
+            SchedulerUtil.runTask(plugin, () -> lines.forEach(sender::sendMessage));
+        });
+    }
+
+    void shutdown() {
+        executor.shutdownNow();
+    }
+
+    @NotNull
+    private List<String> fetch(@NotNull String player) {
+        final List<String> lines = new ArrayList<>();
+        final String token = config.getApiToken();
+        if (token == null || token.isBlank()) {
+            lines.add("§c[AsyncAnticheat] api.token is not configured.");
+            return lines;
+        }
+
+        try {
+            final String url = normalizeBaseUrl(config.getApiUrl()) + "/plugin/findings?player="
+                    + URLEncoder.encode(player, StandardCharsets.UTF_8);
+            final HttpRequest req = HttpRequest.newBuilder()
+                    .uri(URI.create(url))
+                    .timeout(REQUEST_TIMEOUT)
+                    .header("Authorization", "Bearer " + token)
+                    .header("X-Server-Id", serverId)
+                    .GET()
+                    .build();
+
+            final HttpResponse<String> resp = httpClient.send(req, HttpResponse.BodyHandlers.ofString());
+            if (resp.statusCode() == 404) {
+                lines.add("§c[AsyncAnticheat] No data for " + player + " on this server.");
+                return lines;
+            }
+            if (resp.statusCode() < 200 || resp.statusCode() >= 300) {
+                lines.add("§c[AsyncAnticheat] Lookup failed (HTTP " + resp.statusCode() + ").");
+                return lines;
+            }
+
+            final JsonObject body = JsonParser.parseString(resp.body()).getAsJsonObject();
+            final String name = body.has("player_name") ? body.get("player_name").getAsString() : player;
+            final int risk = body.get("risk").getAsInt();
+            lines.add("§a[AsyncAnticheat] §f" + name + " §7- risk " + riskColor(risk) + risk + "§7/100");
+            if (body.get("watchlisted").getAsBoolean()) {
+                final String reason = body.has("watchlist_reason")
+                        ? " §7(" + body.get("watchlist_reason").getAsString() + ")" : "";
+                lines.add("  §eOn watchlist" + reason);
+            }
+
+            final JsonArray findings = body.getAsJsonArray("findings");
+            if (findings.isEmpty()) {
+                lines.add("  §7No recent findings.");
+            }
+            for (JsonElement element : findings) {
+                final JsonObject f = element.getAsJsonObject();
+                lines.add(String.format("  %s%s §f%s §7x%d, %s ago",
+                        severityColor(f.get("severity").getAsString()),
+                        f.get("severity").getAsString(),
+                        f.get("title").getAsString(),
+                        f.get("count").getAsInt(),
+                        formatAgo(f.get("seconds_ago").getAsLong())
+                ));
+            }
+        } catch (Exception e) {
+            lines.add("§c[AsyncAnticheat] Lookup failed: " + e.getMessage());
+        }
+        return lines;
+    }
+
+    @NotNull
+    private static String riskColor(int risk) {
+        if (risk >= 75) return "§c";
+        if (risk >= 40) return "§6";
+        return "§a";
+    }
+
+    @NotNull
+    private static String severityColor(@NotNull String severity) {
+        return switch (severity) {
+            case "critical" -> "§4";
+            case "high" -> "§c";
+            case "medium" -> "§e";
+            case "low" -> "§9";
+            default -> "§7";
+        };
+    }
+
+    @NotNull
+    private static String formatAgo(long seconds) {
+        if (seconds < 60) return seconds + "s";
+        if (seconds < 3600) return (seconds / 60) + "m";
+        if (seconds < 86400) return (seconds / 3600) + "h";
+        return (seconds / 86400) + "d";
+    }
+
+    @NotNull
+    private static String normalizeBaseUrl(@NotNull String baseUrl) {
+        String b = baseUrl.trim();
+        while (b.endsWith("/")) b = b.substring(0, b.length() - 1);
+        return b;
+    }
+}
@@ -111,6 +111,9 @@ public final class PlayerStateTask implements Runnable {
         // Game mode
         fields.put("gamemode", player.getGameMode().name());
 
+        // World (lets the API group findings and give modules per-world context)
+        fields.put("world", player.getWorld().getName());
+
         // Relevant potion effects
         fields.put("slow_falling", player.hasPotionEffect(PotionEffectType.SLOW_FALLING));
         fields.put("levitation", player.hasPotionEffect(PotionEffectType.LEVITATION));
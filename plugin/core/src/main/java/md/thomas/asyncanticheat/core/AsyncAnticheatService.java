@@ -90,6 +90,7 @@ public final class AsyncAnticheatService {
         }
     }
     private final DiskSpool spool;
+    private final Watchlist watchlist = new Watchlist();
     private final HttpUploader uploader;
 
     public AsyncAnticheatService(@NotNull File dataFolder, @NotNull AcLogger logger) {
@@ -98,7 +99,8 @@ public final class AsyncAnticheatService {
         this.config = AsyncAnticheatConfig.load(dataFolder, logger);
         this.serverIdentity = ServerIdentity.loadOrCreate(dataFolder, logger);
         this.spool = new DiskSpool(new File(dataFolder, config.getSpoolDirName()), config, logger, gson);
-        this.uploader = new HttpUploader(config, logger, serverIdentity.getServerId(), sessionId);
+        this.uploader = new HttpUploader(config, logger, serverIdentity.getServerId(), sessionId, spool,
+                watchlist);
     }
 
     public void start() {
@@ -141,12 +143,16 @@ public final class AsyncAnticheatService {
         if (isDevMarker(record)) {
             return offerWithDropPolicy(record);
         }
+        // Watchlisted players get the capture settings sent with the watchlist.
+        final boolean watched = watchlist.isWatched(record.getPlayerUuid());
         // Sample rate: keep packets with probability = sampleRate
         // If sampleRate is 0.5, we want to keep ~50% of packets
-        if (Math.random() >= config.getSampleRate()) {
+        final double sampleRate = watched ? watchlist.getSampleRate() : config.getSampleRate();
+        if (Math.random() >= sampleRate) {
             return true; // Drop this packet (sampled out)
         }
-        if (!PacketFilters.shouldCapture(config, record.getPacketName())) {
+        final boolean unfiltered = watched && watchlist.isVerbose();
+        if (!unfiltered && !PacketFilters.shouldCapture(config, record.getPacketName())) {
             return true;
         }
         return offerWithDropPolicy(record);
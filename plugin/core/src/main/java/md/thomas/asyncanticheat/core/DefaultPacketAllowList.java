@@ -50,7 +50,11 @@ final class DefaultPacketAllowList {
                 "INTERACT_ENTITY",
                 "ENTITY_ACTION",
                 "ARM_ANIMATION",
-                "SWING"
+                "SWING",
+                // Target hurt timing (invulnerability window) for attack speed checks.
+                "ENTITY_STATUS",
+                "HURT_ANIMATION",
+                "DAMAGE_EVENT"
         )) {
             return true;
         }
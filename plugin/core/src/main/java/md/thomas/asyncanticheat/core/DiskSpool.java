@@ -16,6 +16,7 @@ import java.util.Comparator;
 import java.util.List;
 import java.util.Map;
 import java.util.UUID;
+import java.util.concurrent.ConcurrentHashMap;
 import java.util.zip.GZIPOutputStream;
 
 final class DiskSpool {
@@ -25,6 +26,11 @@ final class DiskSpool {
     private final AcLogger logger;
     private final Gson gson;
 
+    // Per-session batch numbers (sent as X-Batch-Seq so the API can spot lost or replayed batches).
+    // Files left over from a previous run have no entry and are uploaded without a number.
+    private long nextBatchSeq = 1L;
+    private final Map<String, Long> batchSeqs = new ConcurrentHashMap<>();
+
     DiskSpool(@NotNull File spoolDir, @NotNull AsyncAnticheatConfig config, @NotNull AcLogger logger, @NotNull Gson gson) {
         this.spoolDir = spoolDir;
         this.config = config;
@@ -56,6 +62,8 @@ final class DiskSpool {
         meta.put("session_id", sessionId);
         meta.put("created_at_ms", System.currentTimeMillis());
         meta.put("event_count", records.size());
+        final long batchSeq = nextBatchSeq;
+        meta.put("batch_seq", batchSeq);
 
         boolean success = false;
         try (FileOutputStream fos = new FileOutputStream(tempFile);
@@ -96,14 +104,15 @@ final class DiskSpool {
 
         // Atomically rename temp file to final file
         try {
-            if (tempFile.renameTo(finalFile)) {
-                return finalFile;
-            } else {
+            if (!tempFile.renameTo(finalFile)) {
                 // Rename failed - try copy + delete as fallback
                 Files.copy(tempFile.toPath(), finalFile.toPath());
                 Files.deleteIfExists(tempFile.toPath());
-                return finalFile;
             }
+            // Only spooled batches consume a number, so failed writes don't look like gaps.
+            nextBatchSeq++;
+            batchSeqs.put(finalFile.getName(), batchSeq);
+            return finalFile;
         } catch (Exception e) {
             logger.error("[AsyncAnticheat] Failed to finalize batch file: " + e.getMessage(), e);
             // Clean up both files on failure
@@ -115,6 +124,20 @@ final class DiskSpool {
         }
     }
 
+    /**
+     * @return the batch number assigned when this session spooled the file, or null for files
+     *         from a previous run.
+     */
+    @Nullable
+    Long getBatchSeq(@NotNull File file) {
+        return batchSeqs.get(file.getName());
+    }
+
+    /** Called once a file has been uploaded (or dropped). */
+    void forget(@NotNull File file) {
+        batchSeqs.remove(file.getName());
+    }
+
     private void enforceMaxSize() {
         final long maxBytes = (long) config.getSpoolMaxMb() * 1024L * 1024L;
         if (maxBytes <= 0) return;
@@ -135,6 +158,7 @@ final class DiskSpool {
                 if (total <= maxBytes) break;
                 long len = f.length();
                 if (Files.deleteIfExists(f.toPath())) {
+                    forget(f);
                     total -= len;
                     logger.warn("[AsyncAnticheat] Spool over limit, deleted: " + f.getName());
                 }
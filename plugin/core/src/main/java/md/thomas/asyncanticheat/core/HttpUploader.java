@@ -1,5 +1,8 @@
 package md.thomas.asyncanticheat.core;
 
+import com.google.gson.JsonElement;
+import com.google.gson.JsonObject;
+import com.google.gson.JsonParser;
 import org.jetbrains.annotations.NotNull;
 
 import java.io.File;
@@ -11,8 +14,12 @@ import java.net.http.HttpResponse;
 import java.nio.charset.StandardCharsets;
 import java.nio.file.Files;
 import java.time.Duration;
+import java.util.ArrayList;
 import java.util.Arrays;
 import java.util.Comparator;
+import java.util.List;
+import java.util.Set;
+import java.util.concurrent.ConcurrentHashMap;
 import java.util.concurrent.atomic.AtomicInteger;
 
 final class HttpUploader {
@@ -21,8 +28,14 @@ final class HttpUploader {
     private final AcLogger logger;
     private final String serverId;
     private final String sessionId;
+    private final DiskSpool spool;
+    private final Watchlist watchlist;
     private final HttpClient client;
 
+    // Ids of applied pending actions, acknowledged on the next upload (the API repeats an
+    // action in every ingest response until then).
+    private final Set<String> actionAcks = ConcurrentHashMap.newKeySet();
+
     static final int REG_UNKNOWN = 0;
     static final int REG_WAITING = 1;
     static final int REG_REGISTERED = 2;
@@ -40,13 +53,16 @@ final class HttpUploader {
     private final long missingTokenWarnIntervalMs = 5 * 60_000L; // 5 minutes
     private long lastRegistrationWarnAtMs = 0L;
     private final long registrationWarnIntervalMs = 5 * 60_000L; // 5 minutes
+    private long lastDeletedWarnAtMs = 0L;
 
     HttpUploader(@NotNull AsyncAnticheatConfig config, @NotNull AcLogger logger, @NotNull String serverId,
-            @NotNull String sessionId) {
+            @NotNull String sessionId, @NotNull DiskSpool spool, @NotNull Watchlist watchlist) {
         this.config = config;
         this.logger = logger;
         this.serverId = serverId;
         this.sessionId = sessionId;
+        this.spool = spool;
+        this.watchlist = watchlist;
         this.client = HttpClient.newBuilder()
                 .connectTimeout(Duration.ofSeconds(config.getTimeoutSeconds()))
                 .build();
@@ -102,7 +118,9 @@ final class HttpUploader {
 
         try {
             final HttpResponse<String> resp = client.send(req, HttpResponse.BodyHandlers.ofString());
-            handleRegistrationStatus(resp.statusCode(), resp.body());
+            if (!handleRegistrationStatus(resp.statusCode(), resp.body())) {
+                handleDeletedStatus(resp.statusCode(), resp.body());
+            }
         } catch (Exception e) {
             // Ignore; uploader will retry later via normal uploads.
         }
@@ -165,24 +183,45 @@ final class HttpUploader {
                 .header("X-Server-Id", serverId)
                 .header("X-Session-Id", sessionId);
 
+        final Long batchSeq = spool.getBatchSeq(file);
+        if (batchSeq != null) {
+            reqBuilder.header("X-Batch-Seq", Long.toString(batchSeq));
+        }
+
         // Send server address if configured (for dashboard ping feature)
         final String serverAddr = config.getServerAddress();
         if (serverAddr != null && !serverAddr.isBlank()) {
             reqBuilder.header("X-Server-Address", serverAddr.trim());
         }
 
+        // Our clock at send time; the API compares it with its own to rebase timestamps.
+        reqBuilder.header("X-Client-Time", Long.toString(System.currentTimeMillis()));
+
+        final List<String> acks = new ArrayList<>(actionAcks);
+        if (!acks.isEmpty()) {
+            reqBuilder.header("X-Ack-Actions", String.join(",", acks));
+        }
+
         final HttpRequest req = reqBuilder.POST(HttpRequest.BodyPublishers.ofByteArray(body)).build();
 
         try {
             final HttpResponse<String> resp = client.send(req, HttpResponse.BodyHandlers.ofString());
             if (resp.statusCode() >= 200 && resp.statusCode() < 300) {
                 Files.deleteIfExists(file.toPath());
+                spool.forget(file);
                 registrationState.set(REG_REGISTERED);
                 onSuccess();
+                acks.forEach(actionAcks::remove);
+                applyHints(resp.body());
+                applyActions(resp.body());
             } else if (handleRegistrationStatus(resp.statusCode(), resp.body())) {
                 // Server is not registered yet: keep the file, but don't spam retries.
                 // This is not a "network failure" and shouldn't trigger exponential backoff.
                 nextAttemptAtMs = System.currentTimeMillis() + 60_000L; // check again in 60s
+            } else if (handleDeletedStatus(resp.statusCode(), resp.body())) {
+                // Server was deleted from the dashboard: keep the file in case it is restored,
+                // but only check back occasionally.
+                nextAttemptAtMs = System.currentTimeMillis() + 30 * 60_000L; // 30 minutes
             } else {
                 onFailure("Upload failed (" + resp.statusCode() + ") for " + file.getName());
             }
@@ -223,6 +262,74 @@ final class HttpUploader {
         return true;
     }
 
+    /**
+     * @return true if the response indicates "server_deleted" and we handled it.
+     */
+    private boolean handleDeletedStatus(int statusCode, String body) {
+        // The API returns 410 with a JSON body containing server_deleted.
+        if (statusCode != 410)
+            return false;
+        final String b = body == null ? "" : body;
+        if (!b.contains("server_deleted"))
+            return false;
+
+        final long now = System.currentTimeMillis();
+        if (now - lastDeletedWarnAtMs >= registrationWarnIntervalMs) {
+            lastDeletedWarnAtMs = now;
+            logger.warn(
+                    "[AsyncAnticheat] This server was deleted from the dashboard; uploads are refused. Restore it there or remove the plugin.");
+        }
+        return true;
+    }
+
+    /**
+     * Honour the API's back-off hint: while it is loaded, wait the suggested interval
+     * before the next upload. Batches keep spooling to disk meanwhile.
+     */
+    private void applyHints(String body) {
+        try {
+            final JsonObject hints = JsonParser.parseString(body == null ? "" : body)
+                    .getAsJsonObject()
+                    .getAsJsonObject("hints");
+            if (hints == null)
+                return;
+            final JsonElement interval = hints.get("next_batch_interval_ms");
+            if (interval == null || interval.isJsonNull())
+                return;
+            final long waitMs = Math.min(interval.getAsLong(), maxBackoffMs);
+            if (waitMs > 0) {
+                nextAttemptAtMs = System.currentTimeMillis() + waitMs;
+            }
+        } catch (Exception ignored) {
+            // Older APIs send no hints.
+        }
+    }
+
+    /**
+     * Apply the pending actions the API sent and remember their ids for acknowledgement.
+     * Unknown actions are left unacknowledged for a plugin that understands them.
+     */
+    private void applyActions(String body) {
+        try {
+            final JsonElement actions = JsonParser.parseString(body == null ? "" : body)
+                    .getAsJsonObject()
+                    .get("pending_actions");
+            if (actions == null || !actions.isJsonArray())
+                return;
+            for (JsonElement e : actions.getAsJsonArray()) {
+                final JsonObject action = e.getAsJsonObject();
+                final String kind = action.get("action").getAsString();
+                if (Watchlist.SYNC_ACTION.equals(kind) && action.get("payload").isJsonObject()) {
+                    watchlist.apply(action.getAsJsonObject("payload"));
+                    actionAcks.add(action.get("id").getAsString());
+                    logger.info("[AsyncAnticheat] Watchlist updated: " + watchlist.size() + " player(s).");
+                }
+            }
+        } catch (Exception e) {
+            logger.warn("[AsyncAnticheat] Ignoring malformed pending actions: " + e.getMessage());
+        }
+    }
+
     private void onSuccess() {
         consecutiveFailures = 0;
         backoffMs = 1_000L;
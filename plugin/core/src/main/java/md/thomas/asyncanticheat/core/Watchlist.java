@@ -0,0 +1,83 @@
+package md.thomas.asyncanticheat.core;
+
+import com.google.gson.JsonElement;
+import com.google.gson.JsonObject;
+import org.jetbrains.annotations.NotNull;
+import org.jetbrains.annotations.Nullable;
+
+import java.time.Instant;
+import java.util.HashMap;
+import java.util.Locale;
+import java.util.Map;
+
+/**
+ * Players on the server's dashboard watchlist, pushed by the API as a {@code watchlist_sync}
+ * pending action. Their packets get the capture settings sent with the list (by default every
+ * packet, unsampled and unfiltered).
+ */
+final class Watchlist {
+
+    static final String SYNC_ACTION = "watchlist_sync";
+
+    /** Player UUID (lowercase) -> expiry in epoch ms ({@link Long#MAX_VALUE} = until removed). */
+    private volatile Map<String, Long> players = Map.of();
+    private volatile double sampleRate = 1.0;
+    private volatile boolean verbose = true;
+
+    /** Replace the list with a {@code watchlist_sync} payload (always the full set). */
+    void apply(@NotNull JsonObject payload) {
+        final Map<String, Long> next = new HashMap<>();
+        final JsonElement list = payload.get("players");
+        if (list != null && list.isJsonArray()) {
+            for (JsonElement e : list.getAsJsonArray()) {
+                if (!e.isJsonObject()) continue;
+                final JsonObject player = e.getAsJsonObject();
+                final JsonElement uuid = player.get("uuid");
+                if (uuid == null || !uuid.isJsonPrimitive()) continue;
+                next.put(uuid.getAsString().toLowerCase(Locale.ROOT), expiresAtMs(player.get("expires_at")));
+            }
+        }
+
+        final JsonElement capture = payload.get("capture");
+        if (capture != null && capture.isJsonObject()) {
+            final JsonObject c = capture.getAsJsonObject();
+            if (c.has("sample_rate") && c.get("sample_rate").isJsonPrimitive()) {
+                sampleRate = Math.max(0.0, Math.min(1.0, c.get("sample_rate").getAsDouble()));
+            }
+            if (c.has("verbose") && c.get("verbose").isJsonPrimitive()) {
+                verbose = c.get("verbose").getAsBoolean();
+            }
+        }
+        players = Map.copyOf(next);
+    }
+
+    boolean isWatched(@Nullable String playerUuid) {
+        if (playerUuid == null) return false;
+        final Long expiresAt = players.get(playerUuid.toLowerCase(Locale.ROOT));
+        return expiresAt != null && System.currentTimeMillis() < expiresAt;
+    }
+
+    int size() {
+        return players.size();
+    }
+
+    /** Sample rate for watched players' packets. */
+    double getSampleRate() {
+        return sampleRate;
+    }
+
+    /** Whether watched players' packets skip the packet allow/deny lists. */
+    boolean isVerbose() {
+        return verbose;
+    }
+
+    private static long expiresAtMs(@Nullable JsonElement expiresAt) {
+        if (expiresAt == null || expiresAt.isJsonNull()) return Long.MAX_VALUE;
+        try {
+            return Instant.parse(expiresAt.getAsString()).toEpochMilli();
+        } catch (Exception e) {
+            // Unreadable expiry: keep watching until the next sync.
+            return Long.MAX_VALUE;
+        }
+    }
+}